@@ -0,0 +1,238 @@
+//! Per-directory profile activation: `bridle activate` and `bridle hook`.
+
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use clap::ValueEnum;
+use dialoguer_multiselect::Confirm;
+
+use crate::cli::profile::manager_builder;
+use crate::config::{
+    BridleConfig, PROJECT_FILE, ProfileManager, ProfileName, ProjectFile, RecoveryAction,
+    TrustedProjects,
+};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+
+/// Shells `bridle hook` can emit code for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+const ZSH_HOOK: &str = r#"_bridle_hook() {
+  command bridle activate --hook
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _bridle_hook
+_bridle_hook
+"#;
+
+const BASH_HOOK: &str = r#"_bridle_hook() {
+  if [[ "$PWD" != "${_BRIDLE_LAST_PWD-}" ]]; then
+    _BRIDLE_LAST_PWD="$PWD"
+    command bridle activate --hook
+  fi
+}
+if [[ ";${PROMPT_COMMAND[*]-};" != *";_bridle_hook;"* ]]; then
+  PROMPT_COMMAND="_bridle_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
+"#;
+
+const FISH_HOOK: &str = r#"function __bridle_hook --on-variable PWD
+    command bridle activate --hook
+end
+__bridle_hook
+"#;
+
+/// Prints the hook for `shell`, meant for `eval "$(bridle hook zsh)"`.
+pub fn print_hook(shell: Shell) {
+    let hook = match shell {
+        Shell::Zsh => ZSH_HOOK,
+        Shell::Bash => BASH_HOOK,
+        Shell::Fish => FISH_HOOK,
+    };
+    print!("{hook}");
+}
+
+/// Serializes template variable prompts from harnesses switching at once.
+static PROMPT: Mutex<()> = Mutex::new(());
+
+/// How switching one harness went.
+enum Outcome {
+    Switched,
+    /// The switch failed; `rolled_back` says whether an interrupted switch
+    /// was restored to the config live before it.
+    Failed {
+        error: Error,
+        rolled_back: bool,
+    },
+}
+
+/// Switches harnesses to the profiles named by the nearest [`PROJECT_FILE`].
+///
+/// Harnesses switch concurrently, since each only touches its own config
+/// directory. Every name is checked before any switch starts; a harness
+/// whose switch fails is rolled back on its own, the others keep their new
+/// profile, and the report lists each harness in the file's order.
+///
+/// In `hook` mode, finding no file is silent and messages go to stderr so
+/// the shell's output stays clean.
+pub fn run(dir: Option<PathBuf>, hook: bool) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    let Some(file) = ProjectFile::find(&dir) else {
+        if !hook {
+            println!(
+                "No {PROJECT_FILE} found in {} or its parents",
+                dir.display()
+            );
+        }
+        return Ok(());
+    };
+
+    let config = BridleConfig::load()?;
+    if config.settings().confirm_projects && !confirm_trust(&config, &file)? {
+        return Ok(());
+    }
+
+    let project = ProjectFile::load(&file)?;
+    let registry = HarnessRegistry::load()?;
+    let manager = manager_builder()?
+        .var_prompt(|name| {
+            let _guard = PROMPT.lock().unwrap_or_else(|e| e.into_inner());
+            crate::cli::profile::prompt_var(name)
+        })
+        .build()?;
+
+    let mut switches: Vec<(String, ProfileName)> = Vec::new();
+    for (harness_name, profile) in &project.profiles {
+        let harness = registry.resolve(harness_name)?.harness();
+        let name = ProfileName::new(profile)
+            .map_err(|reason| Error::invalid_profile_name(profile, reason))?;
+        if switches.iter().any(|(id, _)| id == harness.id()) {
+            return Err(Error::Config(format!(
+                "{} names {} more than once",
+                file.display(),
+                harness.id()
+            )));
+        }
+        if config.active_profile_for(harness.id()) == Some(name.as_str()) {
+            continue;
+        }
+        if !manager.profile_exists(harness, &name) {
+            return Err(Error::profile_not_found(harness.id(), profile));
+        }
+        switches.push((harness.id().to_string(), name));
+    }
+
+    let outcomes: Vec<Outcome> = std::thread::scope(|scope| {
+        let workers: Vec<_> = switches
+            .iter()
+            .map(|(harness_id, name)| scope.spawn(|| switch(&manager, harness_id, name)))
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker.join().unwrap_or_else(|_| Outcome::Failed {
+                    error: Error::Command("the switch panicked".to_string()),
+                    rolled_back: false,
+                })
+            })
+            .collect()
+    });
+
+    let mut failed = 0;
+    for ((harness_id, name), outcome) in switches.iter().zip(outcomes) {
+        match outcome {
+            Outcome::Switched => eprintln!("bridle: {harness_id} -> {}", name.as_str()),
+            Outcome::Failed { error, rolled_back } => {
+                failed += 1;
+                let kept = if rolled_back {
+                    "rolled back"
+                } else {
+                    "previous config kept"
+                };
+                eprintln!(
+                    "bridle: {harness_id} -> {} failed ({kept}): {error}",
+                    name.as_str()
+                );
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(Error::Command(format!(
+            "{failed} of {} harnesses could not switch",
+            switches.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Switches `harness_id` to `name`, rolling back a switch left half done.
+fn switch(manager: &ProfileManager, harness_id: &str, name: &ProfileName) -> Outcome {
+    let harness = match HarnessRegistry::load().and_then(|registry| registry.take(harness_id)) {
+        Ok(harness) => harness,
+        Err(error) => {
+            return Outcome::Failed {
+                error,
+                rolled_back: false,
+            };
+        }
+    };
+    if let Err(e) = manager.backup_current(harness.as_ref()) {
+        eprintln!("Warning: Could not backup current {harness_id} config: {e}");
+    }
+    let Err(error) =
+        manager.switch_profile_with_resources(harness.as_ref(), harness.as_builtin(), name)
+    else {
+        return Outcome::Switched;
+    };
+    let interrupted = ProfileManager::pending_switches()
+        .ok()
+        .and_then(|pending| pending.into_iter().find(|j| j.harness == harness_id));
+    let rolled_back = interrupted.is_some_and(|journal| {
+        manager
+            .recover(harness.as_ref(), journal, RecoveryAction::RollBack)
+            .map_err(|e| eprintln!("Warning: Could not roll back {harness_id}: {e}"))
+            .is_ok()
+    });
+    Outcome::Failed { error, rolled_back }
+}
+
+/// Asks before applying a project file that is new or changed since it was trusted.
+fn confirm_trust(config: &BridleConfig, file: &Path) -> Result<bool> {
+    let paths = config.state_paths()?;
+    let mut trusted = TrustedProjects::load(&paths)?;
+    if trusted.is_trusted(file) {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        eprintln!(
+            "bridle: not applying untrusted {}; run `bridle activate` in a terminal to review it",
+            file.display()
+        );
+        return Ok(false);
+    }
+
+    let project = ProjectFile::load(file)?;
+    eprintln!("{} wants to switch:", file.display());
+    for (harness, profile) in &project.profiles {
+        eprintln!("  {harness} -> {profile}");
+    }
+    let allow = Confirm::new()
+        .with_prompt("Allow this project file?")
+        .default(false)
+        .interact()
+        .map_err(|e| Error::Command(e.to_string()))?;
+    if allow {
+        trusted.trust(file)?;
+        trusted.save(&paths)?;
+    }
+    Ok(allow)
+}
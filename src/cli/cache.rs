@@ -0,0 +1,67 @@
+//! `bridle cache`: how big the download cache is and what to drop from it.
+
+use crate::cli::output::{ResolvedFormat, output};
+use crate::config::{StatePaths, format_size, parse_size};
+use crate::error::{Error, Result};
+use crate::install::cache::{DownloadCache, parse_age};
+
+fn open() -> Result<DownloadCache> {
+    Ok(DownloadCache::new(StatePaths::resolve()?.downloads_dir()))
+}
+
+/// Prints the cache's location, entry count, size, and hit rate.
+pub fn info(format: ResolvedFormat) -> Result<()> {
+    let info = open()?.info();
+    output(&info, format, |info| {
+        let lookups = info.stats.hits + info.stats.misses;
+        println!("Location:  {}", info.path.display());
+        println!("Entries:   {}", info.entries);
+        println!("Size:      {}", format_size(info.size));
+        if lookups == 0 {
+            println!("Hit rate:  no lookups yet");
+        } else {
+            println!(
+                "Hit rate:  {:.0}% ({} of {lookups} lookups)",
+                info.stats.hits as f64 * 100.0 / lookups as f64,
+                info.stats.hits
+            );
+        }
+    });
+    Ok(())
+}
+
+/// Removes entries unused for `older_than`, then the least recently used
+/// until the cache fits in `max_size`; everything when neither is given.
+pub fn clean(
+    max_size: Option<&str>,
+    older_than: Option<&str>,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let invalid = |key: &str, value: &str| Error::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    };
+    let max_size = match max_size {
+        Some(value) => Some(parse_size(value).ok_or_else(|| invalid("--max-size", value))?),
+        None => None,
+    };
+    let older_than = match older_than {
+        Some(value) => Some(parse_age(value).ok_or_else(|| invalid("--older-than", value))?),
+        None => None,
+    };
+    let cache = open()?;
+    let cleaned = if max_size.is_none() && older_than.is_none() {
+        cache.clean(Some(0), None)
+    } else {
+        cache.clean(max_size, older_than)
+    };
+    output(&cleaned, format, |cleaned| {
+        println!(
+            "Removed {} entr{} ({})",
+            cleaned.removed,
+            if cleaned.removed == 1 { "y" } else { "ies" },
+            format_size(cleaned.freed)
+        );
+    });
+    Ok(())
+}
@@ -5,7 +5,25 @@ use clap::Subcommand;
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Show status of all harnesses.
-    Status,
+    Status {
+        /// Print only problems with the active profiles (missing profiles,
+        /// drift, lockfile mismatches) and exit with status 1 if there are any.
+        #[arg(long)]
+        check: bool,
+        /// List the issues behind each active profile's health score.
+        #[arg(long, conflicts_with = "check")]
+        explain: bool,
+    },
+
+    /// Show a harness's active profile, or its live config with `--live`.
+    Show {
+        /// Harness name.
+        harness: String,
+        /// Read the live config directory instead, whether or not bridle
+        /// manages it; nothing is created or changed.
+        #[arg(long)]
+        live: bool,
+    },
 
     /// Initialize bridle configuration.
     Init,
@@ -21,13 +39,122 @@ pub enum Commands {
     #[command(subcommand)]
     Config(ConfigCommands),
 
-    /// Install skills from a GitHub repository.
+    /// Read and edit keys in a harness's config file.
+    #[command(subcommand)]
+    ConfigFile(ConfigFileCommands),
+
+    /// Manage contexts (separate sets of profiles).
+    #[command(subcommand)]
+    Context(ContextCommands),
+
+    /// Manage secrets referenced as `bridle-secret://<name>` in profiles.
+    #[command(subcommand)]
+    Secret(SecretCommands),
+
+    /// Find skills and commands duplicated across profiles.
+    #[command(subcommand)]
+    Dedupe(DedupeCommands),
+
+    /// Inspect and trim the cache of downloaded sources.
+    #[command(subcommand)]
+    Cache(CacheCommands),
+
+    /// Set up the harnesses themselves.
+    #[command(subcommand)]
+    Harness(HarnessCommands),
+
+    /// Save and restore the harness config kept inside a repository.
+    #[command(subcommand)]
+    Project(ProjectCommands),
+
+    /// List MCP servers across profiles, or check a profile's MCP references.
+    #[command(subcommand)]
+    Mcp(McpCommands),
+
+    /// Inspect rules files assembled from a profile's `rules.d/` snippets.
+    #[command(subcommand)]
+    Rules(RulesCommands),
+
+    /// Install skills from a GitHub repository or an OCI registry.
     Install {
-        /// GitHub repository URL or owner/repo shorthand.
-        source: String,
+        /// GitHub repository URL, owner/repo shorthand, or oci://registry/repo:tag.
+        #[arg(required_unless_present = "resume")]
+        source: Option<String>,
         /// Force overwrite existing skills.
         #[arg(long, short)]
         force: bool,
+        /// Value for an environment variable a skill declares; repeatable.
+        #[arg(long = "set", value_name = "KEY=VAL")]
+        set: Vec<String>,
+        /// Run skills' setup hooks without asking.
+        #[arg(long)]
+        allow_hooks: bool,
+        /// Install to these target groups or <harness>/<profile> entries
+        /// instead of choosing; comma-separated or repeatable.
+        #[arg(long, value_delimiter = ',', value_name = "GROUP")]
+        targets: Vec<String>,
+        /// Only offer components whose name or path matches this glob; repeatable.
+        #[arg(long, value_name = "GLOB")]
+        filter: Vec<String>,
+        /// Leave out components whose name or path matches this glob; repeatable.
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+        /// Install the one selected skill under this name.
+        #[arg(long = "as", value_name = "NAME")]
+        rename: Option<String>,
+        /// Install skills into this directory below the profile's skills directory.
+        #[arg(long, value_name = "DIR")]
+        into: Option<String>,
+        /// Skip skills that do not list a target's harness under `harnesses`.
+        #[arg(long)]
+        strict_compat: bool,
+        /// Also report progress as events on stdout, for tools that wrap bridle.
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        progress: Option<super::install::ProgressFormat>,
+        /// Retry the components that failed in earlier installs, without downloading again.
+        #[arg(long, conflicts_with_all = ["source", "force", "set", "allow_hooks", "targets", "filter", "exclude", "rename", "into", "strict_compat"])]
+        resume: bool,
+    },
+
+    /// Create, fill, and activate the profiles a manifest declares, without
+    /// asking; safe to run on every container start.
+    Provision {
+        /// Manifest declaring `[[profiles]]`.
+        #[arg(long, value_name = "FILE", default_value = super::provision::MANIFEST_FILE)]
+        manifest: std::path::PathBuf,
+        /// Run the setup hooks of installed skills; they are skipped otherwise.
+        #[arg(long)]
+        allow_hooks: bool,
+    },
+
+    /// Install new and changed skills from the `[[subscriptions]]` in config.toml.
+    Update {
+        /// Only report what would change.
+        #[arg(long)]
+        dry_run: bool,
+        /// Run the setup hooks of installed skills; they are skipped otherwise.
+        #[arg(long)]
+        allow_hooks: bool,
+    },
+
+    /// Push a skills bundle or a profile to an OCI registry.
+    Push {
+        /// Target reference, e.g. ghcr.io/org/skills:v1.
+        reference: String,
+        /// Bundle directory laid out like a skills repository.
+        #[arg(
+            long,
+            value_name = "DIR",
+            required_unless_present = "harness",
+            conflicts_with = "harness"
+        )]
+        from: Option<std::path::PathBuf>,
+        /// Harness of the profile to push.
+        #[arg(long, requires = "profile")]
+        harness: Option<String>,
+        /// Profile to push, exported with secrets replaced by templates.
+        #[arg(long, requires = "harness")]
+        profile: Option<String>,
     },
 
     /// Uninstall components from a profile.
@@ -36,6 +163,194 @@ pub enum Commands {
         harness: String,
         /// Profile name.
         profile: String,
+        /// Skills, agents, or commands to remove. Asks which on a terminal
+        /// when none are named.
+        components: Vec<String>,
+    },
+
+    /// Run a command with a profile applied, then restore the previous config.
+    Exec {
+        /// Harness name.
+        harness: String,
+        /// Profile to apply while the command runs.
+        #[arg(long)]
+        profile: String,
+        /// Command and arguments, after `--`.
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Flag risky MCP servers and skills and unpinned sources in a profile.
+    Audit {
+        /// Harness name.
+        harness: String,
+        /// Profile to audit (defaults to the active profile).
+        profile: Option<String>,
+        /// Exit with status 1 if a finding has at least this severity.
+        #[arg(long, value_enum, default_value_t = super::audit::Severity::High)]
+        fail_on: super::audit::Severity,
+    },
+
+    /// Re-hash a profile's installed skills, agents, and commands and compare
+    /// them with its `bridle.lock`.
+    Verify {
+        /// Harness name.
+        harness: String,
+        /// Profile to verify (defaults to the active profile).
+        profile: Option<String>,
+    },
+
+    /// Save live changes into the active profiles, once or on a schedule.
+    #[command(args_conflicts_with_subcommands = true)]
+    Sync {
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
+        /// Also export the profiles to `settings.sync_remote`.
+        #[arg(long)]
+        push: bool,
+        /// Keep running and sync every `--interval`.
+        #[arg(long, conflicts_with = "cron")]
+        daemon: bool,
+        /// Time between syncs with `--daemon`, e.g. 30m or 1h.
+        #[arg(long, default_value = "1h", value_parser = super::sync::parse_interval)]
+        interval: std::time::Duration,
+        /// Wait a random time up to this long before each sync (default with
+        /// `--daemon`: a tenth of the interval).
+        #[arg(long, value_parser = super::sync::parse_interval)]
+        jitter: Option<std::time::Duration>,
+        /// Print a crontab line that runs the sync hourly instead of syncing.
+        #[arg(long)]
+        cron: bool,
+    },
+
+    /// Show a harness's newest log, or its logs about one MCP server.
+    Logs {
+        /// Harness name.
+        harness: String,
+        /// Only show logs about this MCP server.
+        #[arg(long, value_name = "SERVER")]
+        mcp: Option<String>,
+        /// Number of lines to show.
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+        /// Keep printing lines as they are written.
+        #[arg(short = 'f', long)]
+        tail: bool,
+    },
+
+    /// Show which installed skills and commands appear in harness session logs.
+    Insights {
+        /// Only analyze this harness.
+        harness: Option<String>,
+        /// Only read sessions from the last N days.
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+
+    /// Write profiles to a dotfiles directory, with secrets replaced by templates.
+    Export {
+        /// Directory to write `<harness>/<profile>/` trees into.
+        #[arg(long, value_name = "DIR")]
+        dotfiles: std::path::PathBuf,
+        /// Only export this harness's profiles.
+        #[arg(long)]
+        harness: Option<String>,
+    },
+
+    /// Move bridle's config, profiles, and state to another machine.
+    #[command(subcommand)]
+    Migrate(MigrateCommands),
+
+    /// Create profiles from a directory written by `bridle export --dotfiles`.
+    Import {
+        /// Directory containing `<harness>/<profile>/` trees.
+        #[arg(long, value_name = "DIR")]
+        dotfiles: std::path::PathBuf,
+        /// Only import this harness's profiles.
+        #[arg(long)]
+        harness: Option<String>,
+        /// Overwrite profiles that already exist.
+        #[arg(long, short)]
+        force: bool,
+    },
+
+    /// Capture a harness's existing config as its first profile, proposing
+    /// excludes for caches and logs.
+    ImportConfig {
+        /// Harness name.
+        harness: String,
+        /// Name of the profile to create.
+        #[arg(long, default_value = "default")]
+        name: String,
+        /// Accept all proposed excludes without asking.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Switch to the profiles named in the nearest .bridle.toml.
+    Activate {
+        /// Directory to start searching from (defaults to the current directory).
+        dir: Option<std::path::PathBuf>,
+        /// Run as a shell hook: stay quiet when no .bridle.toml is found.
+        #[arg(long)]
+        hook: bool,
+    },
+
+    /// Print shell code that runs `bridle activate` on every directory change.
+    Hook {
+        /// Shell to emit code for.
+        #[arg(value_enum)]
+        shell: super::activate::Shell,
+    },
+
+    /// Print a shell completion script: `source <(bridle completions bash)`.
+    Completions {
+        /// Shell to complete in.
+        #[arg(value_enum)]
+        shell: super::completions::Shell,
+    },
+
+    /// Print completion candidates for the words after `bridle`; used by
+    /// the completion scripts.
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(last = true)]
+        words: Vec<String>,
+    },
+
+    /// Print the active profile, for shell prompts.
+    Prompt {
+        /// Harness to show. Without it, prints `harness:profile` for every active profile.
+        #[arg(long)]
+        harness: Option<String>,
+    },
+
+    /// Serve profile and install operations as JSON over a local Unix socket.
+    Daemon {
+        /// Socket path (defaults to daemon.sock in the state directory).
+        #[arg(long)]
+        socket: Option<std::path::PathBuf>,
+    },
+
+    /// Complete or roll back a profile switch that was interrupted.
+    Recover {
+        /// Finish applying the profile that was being switched to.
+        #[arg(long, conflicts_with = "rollback")]
+        complete: bool,
+        /// Restore the config that was live before the switch.
+        #[arg(long)]
+        rollback: bool,
+    },
+
+    /// Revert the most recent install in every profile and live config it
+    /// wrote to, putting back the files it overwrote.
+    Undo {
+        /// Undo the most recent `bridle install` or `install --resume`.
+        #[arg(long, required = true)]
+        last_install: bool,
+        /// Undo even if files were changed after the install.
+        #[arg(long)]
+        force: bool,
     },
 }
 
@@ -43,9 +358,13 @@ pub enum Commands {
 pub enum ConfigCommands {
     /// Set a configuration value.
     Set {
-        /// Setting name (e.g., profile_marker).
+        /// Setting name (e.g., profile_marker, editor, copy_strategy,
+        /// switch_strategy, symlinks, exclude, auto_backup, check_after_switch, max_file_size, large_files, max_profile_size,
+        /// extraction_max_depth, extraction_max_entries,
+        /// confirm, confirm_projects, insights, check_subscriptions, mirror, ca_bundle, insecure,
+        /// policy, http_timeout_secs, http_retries, http_backoff_ms, color).
         key: String,
-        /// Value to set (true/false for booleans).
+        /// Value to set (true/false for booleans, comma-separated for exclude).
         value: String,
     },
 
@@ -54,14 +373,289 @@ pub enum ConfigCommands {
         /// Setting name.
         key: String,
     },
+
+    /// Print the JSON Schema for config.toml.
+    Schema,
 }
 
 #[derive(Subcommand, Debug)]
-pub enum ProfileCommands {
-    /// List profiles for a harness.
+pub enum ProjectCommands {
+    /// Copy a repository's `.claude/`, `.mcp.json`, `.opencode/`, and `.goose/`
+    /// to a named snapshot.
+    Snapshot {
+        /// Snapshot name.
+        name: String,
+        /// Repository root.
+        #[arg(long, default_value = ".")]
+        path: std::path::PathBuf,
+        /// Replace an existing snapshot of the same name.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Put a snapshot's entries into a repository, replacing those there.
+    Apply {
+        /// Snapshot name.
+        name: String,
+        /// Repository root.
+        #[arg(long, default_value = ".")]
+        path: std::path::PathBuf,
+    },
+
+    /// List project snapshots.
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum McpCommands {
+    /// List the MCP servers of every profile of every harness.
     List {
-        /// Harness name (claude-code, opencode, goose).
+        /// Only show servers configured with different commands or URLs in
+        /// several places.
+        #[arg(long)]
+        duplicates: bool,
+    },
+
+    /// Rewrite an MCP server to another transport, e.g. wrapping a remote
+    /// server in a stdio gateway.
+    Convert {
+        /// Harness name.
+        harness: String,
+        /// MCP server name.
+        name: String,
+        /// Transport to convert to.
+        #[arg(long, value_enum)]
+        to: crate::install::mcp_transport::Transport,
+        /// Profile to change (defaults to the active profile).
+        #[arg(long)]
+        profile: Option<String>,
+    },
+
+    /// Check that every `${NAME}`, `{{ name }}`, and `bridle-secret://`
+    /// reference in the MCP config resolves, without switching. Reports each
+    /// reference's source and exits with status 1 if any is missing.
+    Check {
+        /// Harness name.
         harness: String,
+        /// Profile to check (defaults to the active profile).
+        profile: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RulesCommands {
+    /// Show which snippet contributed each section of the rules file.
+    Explain {
+        /// Harness name.
+        harness: String,
+        /// Profile to explain (defaults to the active profile).
+        profile: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ContextCommands {
+    /// List contexts.
+    List,
+
+    /// Print the current context.
+    Current,
+
+    /// Switch to a context.
+    Use {
+        /// Context name.
+        name: String,
+    },
+
+    /// Create a new context.
+    Create {
+        /// Context name.
+        name: String,
+        /// Profiles directory (defaults to <config>/contexts/<name>/profiles).
+        #[arg(long)]
+        profiles_dir: Option<String>,
+    },
+
+    /// Delete a context. Its profiles are left on disk.
+    Delete {
+        /// Context name.
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigFileCommands {
+    /// Print the value at a dot-separated key path.
+    Get {
+        /// Harness name.
+        harness: String,
+        /// Key path, e.g. `mcp.github.enabled`.
+        key: String,
+        /// Read from this profile instead of the live config.
+        #[arg(long)]
+        profile: Option<String>,
+        /// File relative to the harness directory, instead of its main config file.
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
+    },
+
+    /// Set the value at a key path, keeping comments and formatting where possible.
+    Set {
+        /// Harness name.
+        harness: String,
+        /// Key path, e.g. `mcp.github.enabled`.
+        key: String,
+        /// Value, parsed as JSON (`true`, `3`, `["a"]`) or else taken as a string.
+        value: String,
+        /// Edit this profile instead of the live config.
+        #[arg(long)]
+        profile: Option<String>,
+        /// File relative to the harness directory, instead of its main config file.
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
+    },
+
+    /// Remove the value at a key path.
+    Unset {
+        /// Harness name.
+        harness: String,
+        /// Key path, e.g. `mcp.github.enabled`.
+        key: String,
+        /// Edit this profile instead of the live config.
+        #[arg(long)]
+        profile: Option<String>,
+        /// File relative to the harness directory, instead of its main config file.
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SecretCommands {
+    /// Store a secret in the system keyring (prompts, or reads stdin when piped).
+    Set {
+        /// Secret name, as used in `bridle-secret://<name>`.
+        name: String,
+    },
+
+    /// Print a secret's value.
+    Get {
+        /// Secret name.
+        name: String,
+    },
+
+    /// List stored secret names.
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MigrateCommands {
+    /// Write config.toml, every profile, and bridle's state to one archive.
+    Export {
+        /// Zip archive to write.
+        archive: std::path::PathBuf,
+    },
+
+    /// Restore an archive written by `bridle migrate export`.
+    Import {
+        /// Zip archive to read.
+        archive: std::path::PathBuf,
+        /// Overwrite an existing setup, or import an archive from a newer bridle.
+        #[arg(long, short)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SyncCommands {
+    /// Merge profiles from `settings.sync_remote` into the local ones.
+    Pull {
+        /// Only pull profiles of this harness.
+        harness: Option<String>,
+        /// Settle every file changed both here and in the remote this way
+        /// instead of asking.
+        #[arg(long, value_enum)]
+        resolve: Option<super::sync::Resolution>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    /// Show the cache's location, size, entry count, and hit rate.
+    Info,
+
+    /// Remove cached downloads; all of them unless limited by the options.
+    Clean {
+        /// Remove the least recently used entries until the cache fits, e.g. `500MB`.
+        #[arg(long, value_name = "SIZE")]
+        max_size: Option<String>,
+        /// Remove entries not used for this long, e.g. `30d`, `12h`, or `2w`.
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HarnessCommands {
+    /// Install a harness with npm, Homebrew, or its install script, then
+    /// create its default profile.
+    Install {
+        /// Harness name (claude-code, opencode, goose, amp-code).
+        harness: String,
+        /// Installer to use instead of the first one found on PATH.
+        #[arg(long, value_enum)]
+        via: Option<super::harness::Installer>,
+        /// Run the installer without asking.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Forget a harness: clear its active profile, profile marker, and
+    /// recorded state. Its live config directory is left in place.
+    Remove {
+        /// Harness name.
+        harness: String,
+        /// Also delete all of the harness's profiles.
+        #[arg(long)]
+        purge_profiles: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DedupeCommands {
+    /// List identical and near-identical skills and commands.
+    Report {
+        /// Only compare this harness's profiles.
+        #[arg(long)]
+        harness: Option<String>,
+    },
+
+    /// Replace identical copies with hard links or a shared fragment.
+    Apply {
+        /// Only compare this harness's profiles.
+        #[arg(long)]
+        harness: Option<String>,
+        /// How to replace the copies.
+        #[arg(long, value_enum, default_value_t = super::dedupe::DedupeMode::Hardlink)]
+        mode: super::dedupe::DedupeMode,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommands {
+    /// List profiles with their tags, last switch, size, and skill count.
+    List {
+        /// Harness name (claude-code, opencode, goose); every harness if omitted.
+        harness: Option<String>,
+        /// Order by name, most recently switched to, size, or skill count.
+        #[arg(long, value_enum, default_value_t = super::profile::ProfileSort::Name)]
+        sort: super::profile::ProfileSort,
+        /// Only profiles with this tag from their `profile.toml` (repeatable).
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Only profiles whose name matches this glob (e.g. 'work-*').
+        #[arg(long = "match", value_name = "GLOB")]
+        pattern: Option<String>,
     },
 
     /// Show details of a specific profile.
@@ -70,6 +664,40 @@ pub enum ProfileCommands {
         harness: String,
         /// Profile name.
         name: String,
+        /// Print a shareable report instead of the usual output.
+        #[arg(long, value_enum)]
+        format: Option<super::profile::ReportFormat>,
+        /// List the issues behind the profile's health score.
+        #[arg(long)]
+        explain: bool,
+    },
+
+    /// Write a Claude Code profile as a plugin marketplace repository.
+    Package {
+        /// Harness name (claude-code).
+        harness: String,
+        /// Profile name.
+        name: String,
+        /// Directory to write the plugin to.
+        #[arg(long, value_name = "DIR")]
+        dir: std::path::PathBuf,
+        /// Marketplace owner name.
+        #[arg(long, default_value = "bridle")]
+        owner: String,
+        /// Write into a directory that is not empty.
+        #[arg(long, short)]
+        force: bool,
+    },
+
+    /// Print an inventory of a profile's MCP servers, skills, and other resources as CycloneDX JSON.
+    Sbom {
+        /// Harness name.
+        harness: String,
+        /// Profile name.
+        name: String,
+        /// Write to this file instead of stdout.
+        #[arg(long, value_name = "FILE")]
+        file: Option<std::path::PathBuf>,
     },
 
     /// Create a new profile.
@@ -87,8 +715,24 @@ pub enum ProfileCommands {
     Delete {
         /// Harness name.
         harness: String,
-        /// Profile name.
+        /// Profile name, or a glob such as `client-*`.
         name: String,
+        /// Delete without asking, and allow a glob to delete several profiles
+        /// when not asked on a terminal.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// List profiles not switched to recently, optionally deleting them.
+    Prune {
+        /// Harness name.
+        harness: String,
+        /// Profiles unused for at least this many days are listed.
+        #[arg(long, default_value_t = 90)]
+        days: u32,
+        /// Delete the listed profiles.
+        #[arg(long)]
+        delete: bool,
     },
 
     /// Switch to a profile (set as active).
@@ -97,6 +741,18 @@ pub enum ProfileCommands {
         harness: String,
         /// Profile name.
         name: String,
+        /// What to do with live config changes not yet saved to the active
+        /// profile. Asks per file on a terminal; saves them otherwise.
+        #[arg(long, value_enum)]
+        on_drift: Option<super::profile::OnDrift>,
+        /// Replace the live config, merge the profile into it, or sync only
+        /// known paths. Defaults to the harness's `switch_strategy`.
+        #[arg(long, value_enum)]
+        strategy: Option<super::profile::Strategy>,
+        /// Have the harness load the new config afterwards, and exit 1 if it
+        /// cannot. Always on with `check_after_switch`.
+        #[arg(long)]
+        check: bool,
     },
 
     /// Edit a profile with $EDITOR.
@@ -116,4 +772,34 @@ pub enum ProfileCommands {
         /// Second profile name (optional, defaults to current config).
         other: Option<String>,
     },
+
+    /// List the snapshots of a profile taken on switch, and what changed between them.
+    Log {
+        /// Harness name.
+        harness: String,
+        /// Profile name.
+        name: String,
+        /// Show what changed in each snapshot since the one before it.
+        #[arg(short = 'p', long)]
+        patch: bool,
+        /// Show only the changes from this snapshot id.
+        #[arg(long)]
+        from: Option<String>,
+        /// Snapshot id to compare `--from` with (default: the profile as it is now).
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+    },
+
+    /// Copy one file out of a snapshot into a profile, and the live config if it is active.
+    RestoreFile {
+        /// Harness name.
+        harness: String,
+        /// Profile name.
+        name: String,
+        /// Snapshot id, as listed by `bridle profile log`.
+        #[arg(long)]
+        from_snapshot: String,
+        /// File to restore, relative to the config directory.
+        path: std::path::PathBuf,
+    },
 }
@@ -0,0 +1,366 @@
+//! Shell completion: `bridle completions <shell>` prints a script that asks
+//! `bridle __complete` for candidates on every Tab.
+//!
+//! Subcommands, flags, and enum values come from the clap definitions.
+//! Values that depend on the setup are looked up as the line is completed:
+//! harness and profile names, MCP server names for `mcp convert`, installed
+//! skills, agents, and commands for `uninstall`, and snapshot ids for
+//! `profile log` and `profile restore-file`. Lookups that fail complete
+//! nothing rather than print errors into the shell.
+
+use std::collections::HashMap;
+
+use clap::{Arg, Command, ValueEnum};
+
+use super::profile::manager_builder;
+use crate::config::{BridleConfig, ProfileName};
+use crate::harness::HarnessRegistry;
+
+/// Shells `bridle completions` writes a script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+const BASH: &str = r#"_bridle() {
+    local IFS=$'\n'
+    COMPREPLY=($(bridle __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null))
+}
+complete -o default -F _bridle bridle
+"#;
+
+const ZSH: &str = r#"#compdef bridle
+_bridle() {
+    local -a candidates
+    candidates=(${(f)"$(bridle __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)"})
+    (( ${#candidates} )) && compadd -a candidates || _files
+}
+compdef _bridle bridle
+"#;
+
+const FISH: &str = r#"complete -c bridle -f -a '(bridle __complete -- (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null)'
+"#;
+
+/// Prints the completion script for `shell`.
+pub fn script(shell: Shell) {
+    print!(
+        "{}",
+        match shell {
+            Shell::Bash => BASH,
+            Shell::Zsh => ZSH,
+            Shell::Fish => FISH,
+        }
+    );
+}
+
+/// Prints the candidates for the last of `words`, the arguments after
+/// `bridle` up to the cursor, one per line.
+pub fn run(command: Command, words: &[String]) {
+    for candidate in complete(command, words) {
+        println!("{candidate}");
+    }
+}
+
+/// Where the words before the cursor leave the command line.
+struct Position<'a> {
+    command: &'a Command,
+    /// Subcommand names below `bridle`.
+    path: Vec<String>,
+    /// Values given so far, by argument id.
+    values: HashMap<String, String>,
+    /// The argument the word under the cursor is a value of.
+    expecting: Option<&'a Arg>,
+    positional: usize,
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_action().takes_values()
+}
+
+fn walk<'a>(command: &'a Command, words: &[String]) -> Position<'a> {
+    let mut position = Position {
+        command,
+        path: Vec::new(),
+        values: HashMap::new(),
+        expecting: None,
+        positional: 0,
+    };
+    let mut only_positionals = false;
+    for word in words {
+        if let Some(arg) = position.expecting.take() {
+            position
+                .values
+                .insert(arg.get_id().to_string(), word.clone());
+            continue;
+        }
+        if !only_positionals && word == "--" {
+            only_positionals = true;
+            continue;
+        }
+        if !only_positionals && let Some(flag) = word.strip_prefix("--") {
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (flag, None),
+            };
+            if let Some(arg) = position
+                .command
+                .get_arguments()
+                .find(|a| a.get_long() == Some(name))
+                && takes_value(arg)
+            {
+                match value {
+                    Some(value) => {
+                        position
+                            .values
+                            .insert(arg.get_id().to_string(), value.to_string());
+                    }
+                    None => position.expecting = Some(arg),
+                }
+            }
+            continue;
+        }
+        if !only_positionals && word.len() > 1 && word.starts_with('-') {
+            let short = word.chars().nth(1);
+            if let Some(arg) = position
+                .command
+                .get_arguments()
+                .find(|a| a.get_short() == short)
+                && takes_value(arg)
+            {
+                match word.get(2..).filter(|v| !v.is_empty()) {
+                    Some(value) => {
+                        position
+                            .values
+                            .insert(arg.get_id().to_string(), value.to_string());
+                    }
+                    None => position.expecting = Some(arg),
+                }
+            }
+            continue;
+        }
+        if position.positional == 0
+            && let Some(sub) = position.command.find_subcommand(word)
+        {
+            position.path.push(sub.get_name().to_string());
+            position.command = sub;
+            continue;
+        }
+        if let Some(arg) = position.command.get_positionals().nth(position.positional) {
+            position
+                .values
+                .insert(arg.get_id().to_string(), word.clone());
+            if arg.get_num_args().is_none_or(|n| n.max_values() <= 1) {
+                position.positional += 1;
+            }
+        }
+    }
+    position
+}
+
+/// The candidates for the last of `words` that start with it.
+pub fn complete(mut command: Command, words: &[String]) -> Vec<String> {
+    command.build();
+    let (current, before) = match words.split_last() {
+        Some((current, before)) => (current.as_str(), before),
+        None => ("", words),
+    };
+    let position = walk(&command, before);
+    let mut candidates = if let Some(arg) = position.expecting {
+        values(&position, arg)
+    } else if current.starts_with('-') {
+        position
+            .command
+            .get_arguments()
+            .filter(|a| !a.is_hide_set())
+            .filter_map(|a| a.get_long().map(|long| format!("--{long}")))
+            .chain(["--help".to_string()])
+            .collect()
+    } else if position.command.has_subcommands() && position.positional == 0 {
+        position
+            .command
+            .get_subcommands()
+            .filter(|s| !s.is_hide_set())
+            .map(|s| s.get_name().to_string())
+            .collect()
+    } else if let Some(arg) = position.command.get_positionals().nth(position.positional) {
+        values(&position, arg)
+    } else {
+        Vec::new()
+    };
+    candidates.retain(|c| c.starts_with(current));
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+/// Values `arg` can take where the command line stands.
+fn values(position: &Position, arg: &Arg) -> Vec<String> {
+    let possible = arg.get_possible_values();
+    if !possible.is_empty() {
+        return possible
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| v.get_name().to_string())
+            .collect();
+    }
+    let path: Vec<&str> = position.path.iter().map(String::as_str).collect();
+    let harness = position.values.get("harness").map(String::as_str);
+    let profile = |id: &str| position.values.get(id).map(String::as_str);
+    match (path.as_slice(), arg.get_id().as_str(), harness) {
+        (_, "harness", _) => harness_ids(),
+        (["mcp", "convert"], "name", Some(harness)) => mcp_servers(harness, profile("profile")),
+        (["uninstall"], "components", Some(harness)) => {
+            components(harness, profile("profile").unwrap_or_default())
+        }
+        (["profile", "log"], "from" | "to", Some(harness)) => snapshots(harness, profile("name")),
+        (["profile", "restore-file"], "from_snapshot", Some(harness)) => snapshots(harness, None),
+        (_, "name" | "profile" | "other", Some(harness)) => profiles(harness),
+        _ => Vec::new(),
+    }
+}
+
+fn harness_ids() -> Vec<String> {
+    HarnessRegistry::load()
+        .map(|registry| registry.ids().into_iter().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn profiles(harness: &str) -> Vec<String> {
+    let Ok(harness) = HarnessRegistry::load().and_then(|r| r.take(harness)) else {
+        return Vec::new();
+    };
+    manager_builder()
+        .and_then(|b| b.build())
+        .and_then(|m| m.list_profiles(harness.as_ref()))
+        .map(|names| names.iter().map(|n| n.as_str().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// MCP servers of `profile`, or of the active profile.
+fn mcp_servers(harness: &str, profile: Option<&str>) -> Vec<String> {
+    let Ok(harness) = HarnessRegistry::load().and_then(|r| r.take(harness)) else {
+        return Vec::new();
+    };
+    let profile = match profile {
+        Some(profile) => profile.to_string(),
+        None => {
+            let Some(active) = BridleConfig::load()
+                .ok()
+                .and_then(|c| c.active_profile_for(harness.id()).map(String::from))
+            else {
+                return Vec::new();
+            };
+            active
+        }
+    };
+    let Ok(name) = ProfileName::new(&profile) else {
+        return Vec::new();
+    };
+    manager_builder()
+        .and_then(|b| b.build())
+        .and_then(|m| m.show_profile(harness.as_ref(), &name))
+        .map(|info| info.mcp_servers.into_iter().map(|s| s.name).collect())
+        .unwrap_or_default()
+}
+
+fn components(harness: &str, profile: &str) -> Vec<String> {
+    let Ok(profiles_dir) = BridleConfig::profiles_dir() else {
+        return Vec::new();
+    };
+    super::uninstall::installed_components(&profiles_dir.join(harness).join(profile))
+        .map(|found| found.into_iter().map(|(name, _)| name).collect())
+        .unwrap_or_default()
+}
+
+/// Snapshot ids of `harness`, only those of `profile` when given.
+fn snapshots(harness: &str, profile: Option<&str>) -> Vec<String> {
+    let Ok(harness) = HarnessRegistry::load().and_then(|r| r.take(harness)) else {
+        return Vec::new();
+    };
+    manager_builder()
+        .and_then(|b| b.build())
+        .and_then(|m| m.snapshots(harness.as_ref()))
+        .map(|snapshots| {
+            snapshots
+                .into_iter()
+                .filter(|s| profile.is_none() || s.profile.as_deref() == profile)
+                .map(|s| s.id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn cli() -> Command {
+        Command::new("bridle")
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .global(true)
+                    .value_parser(["text", "json"]),
+            )
+            .subcommand(
+                Command::new("profile")
+                    .subcommand(
+                        Command::new("switch")
+                            .arg(Arg::new("harness").required(true))
+                            .arg(Arg::new("name").required(true))
+                            .arg(
+                                Arg::new("on_drift")
+                                    .long("on-drift")
+                                    .value_parser(["save", "discard"]),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("list")
+                            .arg(Arg::new("all").long("all").action(ArgAction::SetTrue)),
+                    ),
+            )
+            .subcommand(Command::new("status"))
+            .subcommand(Command::new("__complete").hide(true))
+    }
+
+    fn complete(words: &[&str]) -> Vec<String> {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        super::complete(cli(), &words)
+    }
+
+    #[test]
+    fn completes_subcommands_flags_and_enum_values() {
+        assert_eq!(complete(&[""]), ["help", "profile", "status"]);
+        assert_eq!(complete(&["pro"]), ["profile"]);
+        assert_eq!(complete(&["profile", ""]), ["help", "list", "switch"]);
+        assert_eq!(
+            complete(&["profile", "switch", "--"]),
+            ["--help", "--on-drift", "--output"]
+        );
+        assert_eq!(
+            complete(&["profile", "switch", "--on-drift", "d"]),
+            ["discard"]
+        );
+        assert_eq!(complete(&["profile", "list", "-o", ""]), ["json", "text"]);
+    }
+
+    #[test]
+    fn tracks_values_given_before_the_cursor() {
+        let mut command = cli();
+        command.build();
+        let words: Vec<String> = ["profile", "switch", "--on-drift=save", "opencode", "work"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let position = walk(&command, &words);
+        assert_eq!(position.path, ["profile", "switch"]);
+        assert_eq!(position.values["harness"], "opencode");
+        assert_eq!(position.values["name"], "work");
+        assert_eq!(position.values["on_drift"], "save");
+        assert_eq!(position.positional, 2);
+    }
+}
@@ -1,14 +1,28 @@
-use harness_locate::{Harness, HarnessKind};
-
-use crate::config::BridleConfig;
+use crate::config::{BridleConfig, Settings};
 use crate::error::{Error, Result};
-use crate::harness::HarnessConfig;
+use crate::harness::HarnessRegistry;
+
+/// Strips an optional `settings.` prefix so both `editor` and `settings.editor` work.
+fn settings_key(key: &str) -> &str {
+    key.strip_prefix("settings.").unwrap_or(key)
+}
 
 pub fn set_config(key: &str, value: &str) -> Result<()> {
-    match key {
-        "profile_marker" => set_profile_marker(value),
-        _ => Err(Error::UnknownSetting(key.to_string())),
+    if key == "profile_marker" {
+        return set_profile_marker(value);
     }
+
+    let key = settings_key(key);
+    if !Settings::KEYS.contains(&key) {
+        return Err(Error::UnknownSetting(key.to_string()));
+    }
+
+    let mut config = BridleConfig::load()?;
+    config.settings_mut().set(key, value)?;
+    config.save()?;
+
+    println!("{} = {}", key, config.settings().get(key)?);
+    Ok(())
 }
 
 pub fn get_config(key: &str) -> Result<()> {
@@ -16,19 +30,21 @@ pub fn get_config(key: &str) -> Result<()> {
 
     match key {
         "profile_marker" => println!("{}", config.profile_marker),
-        _ => return Err(Error::UnknownSetting(key.to_string())),
+        _ => println!("{}", config.settings().get(settings_key(key))?),
     }
     Ok(())
 }
 
+pub fn print_schema() -> Result<()> {
+    let schema = serde_json::to_string_pretty(&BridleConfig::json_schema())?;
+    println!("{schema}");
+    Ok(())
+}
+
 fn set_profile_marker(value: &str) -> Result<()> {
-    let enabled = match value.to_lowercase().as_str() {
-        "true" | "1" | "yes" | "on" => true,
-        "false" | "0" | "no" | "off" => false,
-        _ => return Err(Error::InvalidValue(value.to_string())),
-    };
+    let enabled = BridleConfig::parse_bool("profile_marker", value)?;
 
-    let mut config = BridleConfig::load().unwrap_or_default();
+    let mut config = BridleConfig::load()?;
     config.set_profile_marker(enabled);
     config.save()?;
 
@@ -41,20 +57,20 @@ fn set_profile_marker(value: &str) -> Result<()> {
 }
 
 fn cleanup_all_marker_files() {
-    for kind in HarnessKind::ALL {
-        let harness = Harness::new(*kind);
-        let Ok(config_dir) = harness.config_dir() else {
+    let registry = HarnessRegistry::load().unwrap_or_else(|_| HarnessRegistry::builtin());
+    for entry in registry.iter() {
+        let Ok(config_dir) = entry.harness().config_dir() else {
             continue;
         };
         let Ok(entries) = std::fs::read_dir(&config_dir) else {
             continue;
         };
         for entry in entries.flatten() {
-            let file_name = entry.file_name();
-            let Some(name) = file_name.to_str() else {
-                continue;
-            };
-            if name.starts_with("BRIDLE_PROFILE_") {
+            if entry
+                .file_name()
+                .as_encoded_bytes()
+                .starts_with(b"BRIDLE_PROFILE_")
+            {
                 let _ = std::fs::remove_file(entry.path());
             }
         }
@@ -0,0 +1,133 @@
+//! `bridle config-file`: read and edit keys in a harness's config file, in the
+//! live config or in a profile.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::{BridleConfig, ProfileManager, ProfileName, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::edit;
+use crate::harness::schema::{
+    AmpSettings, ClaudeSettings, ConfigFormat, GooseConfig, OpenCodeConfig,
+};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+
+/// Which file a `config-file` command edits.
+pub struct Target<'a> {
+    pub harness: &'a str,
+    /// Profile to edit instead of the live config.
+    pub profile: Option<&'a str>,
+    /// File relative to the harness (or profile) directory, instead of the
+    /// harness's main config file.
+    pub file: Option<&'a Path>,
+}
+
+impl Target<'_> {
+    fn resolve(&self) -> Result<(PathBuf, ConfigFormat)> {
+        let harness = HarnessRegistry::load()?.take(self.harness)?;
+        let dir = match self.profile {
+            Some(profile) => {
+                let name = ProfileName::new(profile)
+                    .map_err(|reason| Error::invalid_profile_name(profile, reason))?;
+                let config = BridleConfig::load()?;
+                let manager = ProfileManager::builder()
+                    .settings(config.settings())
+                    .build()?;
+                let path = manager.profile_path(harness.as_ref(), &name);
+                if !path.is_dir() {
+                    return Err(Error::profile_not_found(harness.id(), profile));
+                }
+                path
+            }
+            None => harness.config_dir()?,
+        };
+        let path = match self.file {
+            Some(file) => dir.join(file),
+            None => dir.join(default_file(harness.as_ref(), &dir)?),
+        };
+        let format = ConfigFormat::from_path(&path).ok_or_else(|| Error::InvalidValue {
+            key: "config file".to_string(),
+            value: path.display().to_string(),
+        })?;
+        Ok((path, format))
+    }
+}
+
+/// The harness's main config file name within `dir`.
+fn default_file(harness: &dyn HarnessConfig, dir: &Path) -> Result<&'static str> {
+    Ok(match harness.id() {
+        // OpenCode reads either name; edit whichever already exists.
+        "opencode"
+            if !dir.join(OpenCodeConfig::FILENAME).exists()
+                && dir.join("opencode.json").exists() =>
+        {
+            "opencode.json"
+        }
+        "opencode" => OpenCodeConfig::FILENAME,
+        "claude-code" => ClaudeSettings::FILENAME,
+        "goose" => GooseConfig::FILENAME,
+        "amp-code" => AmpSettings::FILENAME,
+        id => {
+            return Err(Error::NoDefaultConfigFile {
+                harness: id.to_string(),
+            });
+        }
+    })
+}
+
+fn parse_error(path: &Path) -> impl FnOnce(String) -> Error + '_ {
+    move |message| Error::ConfigParse {
+        path: path.to_path_buf(),
+        message,
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Prints the value at `key`: strings as-is, anything else as JSON.
+pub fn get(target: &Target, key: &str) -> Result<()> {
+    let (path, format) = target.resolve()?;
+    let key_path = edit::parse_key_path(key)?;
+    let value = edit::get(&read(&path)?, format, &key_path)
+        .map_err(parse_error(&path))?
+        .ok_or_else(|| Error::ConfigKeyNotFound {
+            key: key.to_string(),
+            path: path.clone(),
+        })?;
+    match value {
+        serde_json::Value::String(s) => println!("{s}"),
+        other => println!("{}", serde_json::to_string_pretty(&other)?),
+    }
+    Ok(())
+}
+
+/// Sets `key` to `value`, read as JSON when it parses and as a string otherwise.
+pub fn set(target: &Target, key: &str, value: &str) -> Result<()> {
+    let (path, format) = target.resolve()?;
+    let key_path = edit::parse_key_path(key)?;
+    let content = edit::set(&read(&path)?, format, &key_path, &edit::parse_value(value))
+        .map_err(parse_error(&path))?;
+    write_atomic(&path, &content)?;
+    println!("Set {key} in {}", path.display());
+    Ok(())
+}
+
+/// Removes `key`.
+pub fn unset(target: &Target, key: &str) -> Result<()> {
+    let (path, format) = target.resolve()?;
+    let key_path = edit::parse_key_path(key)?;
+    let content = edit::unset(&read(&path)?, format, &key_path)
+        .map_err(parse_error(&path))?
+        .ok_or_else(|| Error::ConfigKeyNotFound {
+            key: key.to_string(),
+            path: path.clone(),
+        })?;
+    write_atomic(&path, &content)?;
+    println!("Unset {key} in {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Context command implementation.
+
+use serde::Serialize;
+
+use crate::cli::output::{ResolvedFormat, output_list};
+use crate::cli::profile::manager_builder;
+use crate::config::{BridleConfig, ContextConfig, DEFAULT_CONTEXT, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+
+#[derive(Serialize)]
+struct ContextEntry {
+    name: String,
+    profiles_dir: String,
+    is_current: bool,
+}
+
+fn validate_context_name(name: &str) -> Result<()> {
+    ProfileName::new(name)
+        .map(|_| ())
+        .map_err(|_| Error::InvalidValue {
+            key: "context".to_string(),
+            value: name.to_string(),
+        })
+}
+
+pub fn list_contexts(format: ResolvedFormat) -> Result<()> {
+    let config = BridleConfig::load()?;
+    let entries: Vec<ContextEntry> = config
+        .context_names()
+        .into_iter()
+        .map(|name| {
+            Ok(ContextEntry {
+                name: name.to_string(),
+                profiles_dir: config.context_profiles_dir(name)?.display().to_string(),
+                is_current: name == config.current_context(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    output_list(&entries, format, |entries| {
+        for entry in entries {
+            let marker = if entry.is_current { "*" } else { " " };
+            println!("{} {}  {}", marker, entry.name, entry.profiles_dir);
+        }
+    });
+    Ok(())
+}
+
+pub fn current_context() -> Result<()> {
+    let config = BridleConfig::load()?;
+    println!("{}", config.current_context());
+    Ok(())
+}
+
+/// Active profiles in `config`, by harness id.
+fn active_profiles(config: &BridleConfig) -> Result<Vec<(String, ProfileName)>> {
+    let mut active: Vec<(String, ProfileName)> = config
+        .active
+        .iter()
+        .map(|(harness, profile)| {
+            ProfileName::new(profile)
+                .map(|p| (harness.clone(), p))
+                .map_err(|reason| Error::invalid_profile_name(profile, reason))
+        })
+        .collect::<Result<_>>()?;
+    active.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(active)
+}
+
+/// Saves the live configs into the current context's active profiles, then
+/// applies the active profiles of context `name`.
+///
+/// Refuses before touching anything when an active profile of `name` no
+/// longer exists. Harnesses with no active profile in `name` keep their live
+/// config.
+pub fn use_context(name: &str) -> Result<()> {
+    let mut config = BridleConfig::load()?;
+    if config.current_context() == name {
+        println!("Already in context: {name}");
+        return Ok(());
+    }
+    let mut incoming = config.clone();
+    incoming.use_context(name)?;
+
+    let registry = HarnessRegistry::load()?;
+    let outgoing_manager = manager_builder()?.build()?;
+    let incoming_manager = manager_builder()?
+        .profiles_dir(incoming.context_profiles_dir(name)?)
+        .build()?;
+
+    let mut to_apply = Vec::new();
+    for (harness_id, profile) in active_profiles(&incoming)? {
+        let harness = registry.resolve(&harness_id)?.harness();
+        if !incoming_manager.profile_exists(harness, &profile) {
+            return Err(Error::Config(format!(
+                "context '{name}' has {harness_id} on profile '{profile}', which no longer exists in {}; \
+                 switch {harness_id} to another profile there or recreate it",
+                incoming_manager.profile_path(harness, &profile).display()
+            )));
+        }
+        to_apply.push((harness, profile));
+    }
+
+    for (harness_id, profile) in active_profiles(&config)? {
+        let harness = registry.resolve(&harness_id)?.harness();
+        outgoing_manager.save_to_profile(harness, None, &profile)?;
+    }
+
+    config.use_context(name)?;
+    config.save()?;
+    for (harness, profile) in to_apply {
+        incoming_manager.restore_profile(harness, &profile)?;
+        println!("{}: {}", harness.id(), profile);
+    }
+    println!("Switched to context: {name}");
+    Ok(())
+}
+
+pub fn create_context(name: &str, profiles_dir: Option<&str>) -> Result<()> {
+    validate_context_name(name)?;
+    let mut config = BridleConfig::load()?;
+    if name == DEFAULT_CONTEXT || config.contexts.contains_key(name) {
+        return Err(Error::ContextExists(name.to_string()));
+    }
+
+    config.contexts.insert(
+        name.to_string(),
+        ContextConfig {
+            profiles_dir: profiles_dir.map(Into::into),
+        },
+    );
+    let dir = config.context_profiles_dir(name)?;
+    std::fs::create_dir_all(&dir)?;
+    config.save()?;
+
+    println!("Created context: {name}");
+    println!("Profiles: {}", dir.display());
+    Ok(())
+}
+
+pub fn delete_context(name: &str) -> Result<()> {
+    let mut config = BridleConfig::load()?;
+    if name == DEFAULT_CONTEXT {
+        return Err(Error::InvalidValue {
+            key: "context".to_string(),
+            value: name.to_string(),
+        });
+    }
+    if config.contexts.remove(name).is_none() {
+        return Err(Error::ContextNotFound(name.to_string()));
+    }
+    if config.current_context() == name {
+        config.use_context(DEFAULT_CONTEXT)?;
+    }
+    config.save()?;
+
+    // Profiles are left on disk; only the registration is removed.
+    println!("Deleted context: {name}");
+    Ok(())
+}
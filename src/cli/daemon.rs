@@ -0,0 +1,414 @@
+//! `bridle daemon`: profile and install operations over a local socket.
+//!
+//! Clients connect to a Unix socket and exchange newline-delimited JSON. Each
+//! request is `{"id": <any>, "method": "<name>", "params": {...}}`; each
+//! response echoes the `id` with either `"result"` or
+//! `"error": {"message", "kind"}`. Requests are handled one at a time so
+//! concurrent switches cannot interleave.
+//!
+//! Methods: `ping`, `status`, `profiles.list`, `profiles.show`,
+//! `profiles.create`, `profiles.switch`, `install.discover`, `install`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result, eyre};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::cli::install::{
+    SelectedComponents, discover, install_to_target, skills_with_hooks, store_in_keyring,
+    store_secrets,
+};
+use crate::cli::profile::manager_builder;
+use crate::config::{BridleConfig, Notifier, ProfileManager, ProfileName, StatePaths};
+use crate::error::{Error, ErrorCategory};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+use crate::install::{InstallOptions, InstallTarget};
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorBody>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    message: String,
+    /// [`ErrorCategory`] of the failure, or `"other"`.
+    kind: Value,
+}
+
+#[derive(Deserialize)]
+struct HarnessParams {
+    harness: String,
+}
+
+#[derive(Deserialize)]
+struct ProfileParams {
+    harness: String,
+    name: String,
+    #[serde(default)]
+    from_current: bool,
+}
+
+#[derive(Deserialize)]
+struct SourceParams {
+    source: String,
+}
+
+#[derive(Deserialize)]
+struct InstallParams {
+    source: String,
+    harness: String,
+    profile: String,
+    #[serde(default)]
+    force: bool,
+    /// Values for environment variables the skills declare.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    /// Runs the skills' setup hooks, which are skipped otherwise.
+    #[serde(default)]
+    allow_hooks: bool,
+}
+
+#[derive(Serialize)]
+struct ProfileEntry {
+    name: String,
+    is_active: bool,
+}
+
+/// Serves requests on `socket` (or the default socket) until the process is killed.
+#[cfg(unix)]
+pub fn run(socket: Option<PathBuf>) -> Result<()> {
+    use std::os::unix::net::UnixStream;
+    use std::sync::{Arc, Mutex};
+
+    let path = match socket {
+        Some(path) => path,
+        None => StatePaths::resolve()?.socket_file(),
+    };
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            return Err(eyre!("a daemon is already listening on {}", path.display()));
+        }
+        // Left behind by a daemon that did not shut down cleanly.
+        std::fs::remove_file(&path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let listener = bind_private(&path)?;
+    eprintln!("Listening on {}", path.display());
+
+    let serial = Arc::new(Mutex::new(()));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Warning: Failed to accept connection: {e}");
+                continue;
+            }
+        };
+        let serial = Arc::clone(&serial);
+        std::thread::spawn(move || {
+            let reader = std::io::BufReader::new(&stream);
+            let _ = serve(reader, &stream, &serial);
+        });
+    }
+    Ok(())
+}
+
+/// Binds a socket at `path` that only the current user can connect to.
+///
+/// The socket is created inside a fresh `0700` directory next to `path`,
+/// restricted to `0600`, and only then renamed into place, so no other user
+/// can connect in between.
+#[cfg(unix)]
+fn bind_private(path: &std::path::Path) -> Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    let staging = parent.join(format!(".bridle-daemon-{}", std::process::id()));
+    std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    let staged = staging.join("socket");
+    let bound = std::os::unix::net::UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    Ok(bound?)
+}
+
+#[cfg(not(unix))]
+pub fn run(_socket: Option<PathBuf>) -> Result<()> {
+    Err(eyre!("`bridle daemon` requires Unix domain sockets"))
+}
+
+/// Answers each request line read from `reader` on `writer`.
+fn serve(
+    reader: impl std::io::BufRead,
+    mut writer: impl std::io::Write,
+    serial: &std::sync::Mutex<()>,
+) -> std::io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = {
+            let _guard = serial.lock().unwrap_or_else(|e| e.into_inner());
+            handle_line(&line)
+        };
+        writeln!(writer, "{response}")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Handles one request line and returns the serialized response.
+fn handle_line(line: &str) -> String {
+    let response = match serde_json::from_str::<Request>(line) {
+        Ok(request) => {
+            let result = dispatch(&request.method, request.params);
+            respond(request.id, result)
+        }
+        Err(e) => respond(Value::Null, Err(eyre!("invalid request: {e}"))),
+    };
+    serde_json::to_string(&response).expect("serialization should not fail")
+}
+
+fn respond(id: Value, result: Result<Value>) -> Response {
+    match result {
+        Ok(value) => Response {
+            id,
+            result: Some(value),
+            error: None,
+        },
+        Err(report) => {
+            let kind = report
+                .downcast_ref::<Error>()
+                .map(Error::category)
+                .map(|c: ErrorCategory| serde_json::to_value(c).unwrap_or(Value::Null))
+                .unwrap_or_else(|| json!("other"));
+            Response {
+                id,
+                result: None,
+                error: Some(ErrorBody {
+                    message: report.to_string(),
+                    kind,
+                }),
+            }
+        }
+    }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T> {
+    serde_json::from_value(params).map_err(|e| eyre!("invalid params: {e}"))
+}
+
+fn dispatch(method: &str, raw: Value) -> Result<Value> {
+    Ok(match method {
+        "ping" => json!({ "version": env!("CARGO_PKG_VERSION") }),
+        "status" => serde_json::to_value(super::status::collect_status())?,
+        "profiles.list" => {
+            let p: HarnessParams = params(raw)?;
+            let harness = resolve_harness(&p.harness)?;
+            let active = BridleConfig::load()?
+                .active_profile_for(harness.id())
+                .map(String::from);
+            let entries: Vec<ProfileEntry> = manager()?
+                .list_profiles(harness.as_ref())?
+                .into_iter()
+                .map(|name| ProfileEntry {
+                    is_active: active.as_deref() == Some(name.as_str()),
+                    name: name.to_string(),
+                })
+                .collect();
+            serde_json::to_value(entries)?
+        }
+        "profiles.show" => {
+            let p: ProfileParams = params(raw)?;
+            let harness = resolve_harness(&p.harness)?;
+            let info = manager()?.show_profile(harness.as_ref(), &profile_name(&p.name)?)?;
+            serde_json::to_value(info)?
+        }
+        "profiles.create" => {
+            let p: ProfileParams = params(raw)?;
+            let harness = resolve_harness(&p.harness)?;
+            let name = profile_name(&p.name)?;
+            let manager = manager()?;
+            let path = if p.from_current {
+                manager.create_from_current_with_resources(
+                    harness.as_ref(),
+                    harness.as_builtin(),
+                    &name,
+                )?
+            } else {
+                manager.create_profile(harness.as_ref(), &name)?
+            };
+            json!({ "path": path })
+        }
+        "profiles.switch" => {
+            let p: ProfileParams = params(raw)?;
+            let harness = resolve_harness(&p.harness)?;
+            let name = profile_name(&p.name)?;
+            let manager = manager()?;
+            if !manager.profile_exists(harness.as_ref(), &name) {
+                return Err(Error::profile_not_found(harness.id(), &p.name).into());
+            }
+            let backup = manager.backup_current(harness.as_ref()).ok().flatten();
+            manager.switch_profile_with_resources(harness.as_ref(), harness.as_builtin(), &name)?;
+            json!({ "harness": harness.id(), "profile": name.as_str(), "backup": backup })
+        }
+        "install.discover" => {
+            let p: SourceParams = params(raw)?;
+            let discovery = discover(&p.source)?;
+            let mut mcp_servers: Vec<&String> = discovery.mcp_servers.keys().collect();
+            mcp_servers.sort();
+            json!({
+                "source": discovery.source,
+                "skills": discovery.skills.iter().map(|s| &s.name).collect::<Vec<_>>(),
+                "mcp_servers": mcp_servers,
+                "agents": discovery.agents.iter().map(|a| &a.name).collect::<Vec<_>>(),
+                "commands": discovery.commands.iter().map(|c| &c.name).collect::<Vec<_>>(),
+            })
+        }
+        "install" => {
+            let p: InstallParams = params(raw)?;
+            let harness = resolve_harness(&p.harness)?;
+            let profile = profile_name(&p.profile)?;
+            if !manager()?.profile_exists(harness.as_ref(), &profile) {
+                return Err(Error::profile_not_found(harness.id(), &p.profile).into());
+            }
+            let discovery = discover(&p.source)?;
+            let target = InstallTarget {
+                harness: harness.id().to_string(),
+                profile,
+            };
+            let mut env = p.env;
+            store_secrets(&discovery.skills, &mut env, store_in_keyring)?;
+            let options = InstallOptions {
+                force: p.force,
+                env,
+                run_setup: if p.allow_hooks {
+                    skills_with_hooks(&discovery.skills)
+                } else {
+                    Default::default()
+                },
+                ..Default::default()
+            };
+            let selected = SelectedComponents::all(&discovery);
+            let mirrored = selected.mirror()?;
+            let report = install_to_target(&selected, &target, &options);
+            let source = format!("{}/{}", discovery.source.owner, discovery.source.repo);
+            Notifier::new(&BridleConfig::load()?).install(
+                &target.harness,
+                target.profile.as_str(),
+                &source,
+                &report,
+            );
+            json!({ "report": report, "mirror": mirrored })
+        }
+        _ => return Err(eyre!("unknown method: {method}")),
+    })
+}
+
+fn resolve_harness(name: &str) -> Result<Box<dyn HarnessConfig>> {
+    Ok(HarnessRegistry::load()?.take(name)?)
+}
+
+fn profile_name(name: &str) -> Result<ProfileName> {
+    Ok(ProfileName::new(name).map_err(|reason| Error::invalid_profile_name(name, reason))?)
+}
+
+fn manager() -> Result<ProfileManager> {
+    // No one is at a terminal to answer; missing template variables are errors.
+    Ok(manager_builder()?.var_prompt(|_| None).build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(line: &str) -> Value {
+        serde_json::from_str(&handle_line(line)).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_is_private_from_the_start() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("bridle.sock");
+        let _listener = bind_private(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let entries: Vec<_> = std::fs::read_dir(temp.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1, "staging directory left behind");
+        std::os::unix::net::UnixStream::connect(&path).unwrap();
+    }
+
+    #[test]
+    fn ping_echoes_id() {
+        let response = call(r#"{"id": 7, "method": "ping"}"#);
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn errors_carry_message_and_kind() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _env = crate::test_utils::ConfigEnvGuard::new(temp.path());
+        let response = call(r#"{"id": "a", "method": "nope"}"#);
+        assert_eq!(response["error"]["kind"], "other");
+        assert!(response.get("result").is_none());
+
+        let response =
+            call(r#"{"id": 1, "method": "profiles.list", "params": {"harness": "nope"}}"#);
+        assert_eq!(response["error"]["kind"], "invalid_input");
+
+        let response = call("not json");
+        assert!(
+            response["error"]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("invalid request")
+        );
+    }
+
+    #[test]
+    fn serve_answers_each_line() {
+        let input = b"{\"id\":1,\"method\":\"ping\"}\n\n{\"id\":2,\"method\":\"ping\"}\n";
+        let mut output = Vec::new();
+        serve(&input[..], &mut output, &std::sync::Mutex::new(())).unwrap();
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["id"], 2);
+    }
+}
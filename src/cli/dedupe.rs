@@ -0,0 +1,497 @@
+//! `bridle dedupe`: find skills and commands copied into several profiles.
+//!
+//! Every skill (a directory or a single Markdown file) and command in every
+//! profile is hashed. Copies with the same bytes are identical; copies that
+//! only differ in line endings, trailing whitespace, or blank lines are
+//! near-identical. `bridle dedupe apply` replaces identical copies either with
+//! hard links to one of them, or with a shared fragment that each profile
+//! includes through its `profile.toml`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::cli::output::{ResolvedFormat, output_list};
+use crate::config::{
+    BridleConfig, ProfileManager, ProfileSettings, SHARED_DIR, format_size, write_atomic,
+};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry, ResourceKind};
+
+/// Resource kinds compared across profiles.
+const KINDS: [ResourceKind; 2] = [ResourceKind::Skills, ResourceKind::Commands];
+
+/// How `bridle dedupe apply` replaces identical copies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DedupeMode {
+    /// Hard-link every copy to the first one. Editing one copy in place
+    /// changes all of them.
+    Hardlink,
+    /// Move the content to `shared/<name>/` and include it from each profile.
+    Fragment,
+}
+
+/// One skill or command in one profile.
+#[derive(Debug, Clone, Serialize)]
+struct Copy {
+    harness: String,
+    profile: String,
+    /// Path relative to the profile directory, e.g. `skills/review`.
+    path: PathBuf,
+    #[serde(skip)]
+    profile_dir: PathBuf,
+}
+
+impl Copy {
+    fn full_path(&self) -> PathBuf {
+        self.profile_dir.join(&self.path)
+    }
+}
+
+/// Copies of the same skill or command found in more than one place.
+#[derive(Debug, Serialize)]
+struct DuplicateGroup {
+    kind: &'static str,
+    name: String,
+    /// Whether all copies have the same bytes.
+    identical: bool,
+    /// Size of one copy in bytes.
+    size: u64,
+    copies: Vec<Copy>,
+}
+
+/// Prints groups of duplicate skills and commands.
+pub fn report(harness: Option<&str>, format: ResolvedFormat) -> Result<()> {
+    let groups = find_duplicates(&scan(harness)?)?;
+    output_list(&groups, format, |groups| {
+        if groups.is_empty() {
+            println!("No duplicate skills or commands found");
+        }
+        for group in groups {
+            println!(
+                "{} {} `{}` ({} copies, {} each):",
+                if group.identical {
+                    "Identical"
+                } else {
+                    "Near-identical"
+                },
+                group.kind,
+                group.name,
+                group.copies.len(),
+                format_size(group.size)
+            );
+            for copy in &group.copies {
+                println!(
+                    "  {}/{}: {}",
+                    copy.harness,
+                    copy.profile,
+                    copy.path.display()
+                );
+            }
+        }
+        let identical = groups.iter().filter(|g| g.identical).count();
+        if identical > 0 {
+            println!("\nRun `bridle dedupe apply` to replace the {identical} identical group(s).");
+        }
+    });
+    Ok(())
+}
+
+/// Replaces identical copies according to `mode`. Near-identical copies are
+/// left alone, since merging them would lose their differences.
+pub fn apply(harness: Option<&str>, mode: DedupeMode) -> Result<()> {
+    let groups = find_duplicates(&scan(harness)?)?;
+    let mut replaced = 0;
+    for group in groups.iter().filter(|g| g.identical) {
+        let done = match mode {
+            DedupeMode::Hardlink => hardlink_group(group)?,
+            DedupeMode::Fragment => fragment_group(group, &BridleConfig::config_dir()?)?,
+        };
+        if done {
+            replaced += 1;
+        }
+    }
+    println!("Deduplicated {replaced} group(s)");
+    Ok(())
+}
+
+/// Every skill and command in the profiles of `harness`, or of all harnesses.
+fn scan(harness: Option<&str>) -> Result<Vec<(&'static str, Copy)>> {
+    let config = BridleConfig::load()?;
+    let registry = HarnessRegistry::load()?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    let harnesses: Vec<&dyn HarnessConfig> = match harness {
+        Some(name) => vec![registry.resolve(name)?.harness()],
+        None => registry.iter().map(|e| e.harness()).collect(),
+    };
+
+    let mut found = Vec::new();
+    for harness in harnesses {
+        let layout = harness.resource_layout();
+        for name in manager.list_profiles(harness)? {
+            let profile_dir = manager.profile_path(harness, &name);
+            for kind in KINDS {
+                // Profiles can hold both the harness's own directory and the
+                // canonical copy; comparing one of them is enough.
+                let dir = [
+                    layout.get(kind).map(|d| d.dir_name()),
+                    Some(kind.canonical_dir()),
+                ]
+                .into_iter()
+                .flatten()
+                .find(|dir| profile_dir.join(dir).is_dir());
+                let Some(dir) = dir else { continue };
+                for entry in std::fs::read_dir(profile_dir.join(dir))? {
+                    let entry = entry?;
+                    if entry.file_name().to_string_lossy().starts_with('.') {
+                        continue;
+                    }
+                    found.push((
+                        kind_label(kind),
+                        Copy {
+                            harness: harness.id().to_string(),
+                            profile: name.as_str().to_string(),
+                            path: Path::new(dir).join(entry.file_name()),
+                            profile_dir: profile_dir.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn kind_label(kind: ResourceKind) -> &'static str {
+    match kind {
+        ResourceKind::Skills => "skill",
+        ResourceKind::Commands => "command",
+        ResourceKind::Agents => "agent",
+        ResourceKind::Plugins => "plugin",
+    }
+}
+
+/// Groups `copies` by exact content, then the rest by normalized content.
+fn find_duplicates(copies: &[(&'static str, Copy)]) -> Result<Vec<DuplicateGroup>> {
+    let mut exact: BTreeMap<(&str, String), Vec<&Copy>> = BTreeMap::new();
+    let mut normalized: BTreeMap<(&str, String), Vec<(&Copy, String)>> = BTreeMap::new();
+    let mut sizes: BTreeMap<String, u64> = BTreeMap::new();
+    for (kind, copy) in copies {
+        let files = files_of(&copy.full_path())?;
+        let exact_hash = content_hash(&files, false)?;
+        sizes.insert(exact_hash.clone(), total_size(&files));
+        normalized
+            .entry((kind, content_hash(&files, true)?))
+            .or_default()
+            .push((copy, exact_hash.clone()));
+        exact.entry((kind, exact_hash)).or_default().push(copy);
+    }
+
+    let mut groups = Vec::new();
+    for ((kind, hash), copies) in &exact {
+        if copies.len() > 1 {
+            groups.push(group(kind, true, sizes[hash], copies.iter().copied()));
+        }
+    }
+    for ((kind, _), copies) in &normalized {
+        let first = &copies[0].1;
+        if copies.iter().any(|(_, hash)| hash != first) {
+            groups.push(group(
+                kind,
+                false,
+                sizes[first],
+                copies.iter().map(|(c, _)| *c),
+            ));
+        }
+    }
+    Ok(groups)
+}
+
+fn group<'a>(
+    kind: &'static str,
+    identical: bool,
+    size: u64,
+    copies: impl Iterator<Item = &'a Copy>,
+) -> DuplicateGroup {
+    let copies: Vec<Copy> = copies.cloned().collect();
+    let name = copies[0]
+        .path
+        .file_stem()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    DuplicateGroup {
+        kind,
+        name,
+        identical,
+        size,
+        copies,
+    }
+}
+
+/// Files making up `path` with their paths relative to it, sorted; a plain
+/// file is its own single entry with an empty relative path.
+fn files_of(path: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    if !path.is_dir() {
+        return Ok(vec![(PathBuf::new(), path.to_path_buf())]);
+    }
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for entry in std::fs::read_dir(path.join(&rel))? {
+            let entry = entry?;
+            let rel = rel.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                pending.push(rel);
+            } else {
+                files.push((rel, entry.path()));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `base` joined with a relative path from [`files_of`].
+fn join_rel(base: &Path, rel: &Path) -> PathBuf {
+    if rel.as_os_str().is_empty() {
+        base.to_path_buf()
+    } else {
+        base.join(rel)
+    }
+}
+
+fn total_size(files: &[(PathBuf, PathBuf)]) -> u64 {
+    files
+        .iter()
+        .filter_map(|(_, path)| std::fs::metadata(path).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// SHA-256 over the relative paths and contents of `files`. With `normalize`,
+/// line endings, trailing whitespace, and blank lines are ignored.
+fn content_hash(files: &[(PathBuf, PathBuf)], normalize: bool) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for (rel, path) in files {
+        hasher.update(rel.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        let bytes = std::fs::read(path)?;
+        if normalize {
+            for line in String::from_utf8_lossy(&bytes).lines() {
+                let line = line.trim_end();
+                if !line.is_empty() {
+                    hasher.update(line.as_bytes());
+                    hasher.update(b"\n");
+                }
+            }
+        } else {
+            hasher.update(&bytes);
+        }
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Replaces every file of the later copies with a hard link to the first
+/// copy's file. Returns whether anything was linked.
+fn hardlink_group(group: &DuplicateGroup) -> Result<bool> {
+    let source = files_of(&group.copies[0].full_path())?;
+    let mut linked = false;
+    for copy in &group.copies[1..] {
+        let target = copy.full_path();
+        for (rel, from) in &source {
+            let to = join_rel(&target, rel);
+            if same_file(from, &to) {
+                continue;
+            }
+            // Link next to the target first so a failure leaves the copy intact.
+            let tmp = to.with_file_name(format!(
+                ".{}.bridle-link",
+                to.file_name().unwrap_or_default().to_string_lossy()
+            ));
+            let _ = std::fs::remove_file(&tmp);
+            std::fs::hard_link(from, &tmp)?;
+            std::fs::rename(&tmp, &to)?;
+            linked = true;
+        }
+    }
+    if linked {
+        println!(
+            "Linked {} {} `{}`",
+            group.copies.len(),
+            group.kind,
+            group.name
+        );
+    }
+    Ok(linked)
+}
+
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// Moves the group's content to `<config_dir>/shared/<kind>-<name>/` and
+/// makes each profile include it instead. Skipped, returning false, when the
+/// copies live at different paths in their profiles, since a fragment is laid
+/// over every profile at one path.
+fn fragment_group(group: &DuplicateGroup, config_dir: &Path) -> Result<bool> {
+    let path = &group.copies[0].path;
+    if group.copies.iter().any(|c| &c.path != path) {
+        eprintln!(
+            "Warning: Skipping {} `{}`: copies are at different paths; use --mode hardlink",
+            group.kind, group.name
+        );
+        return Ok(false);
+    }
+
+    let fragment = format!("{}-{}", group.kind, group.name);
+    let fragment_dir = config_dir.join(SHARED_DIR).join(&fragment);
+    if fragment_dir.exists() {
+        return Err(Error::Command(format!(
+            "shared fragment {} already exists",
+            fragment_dir.display()
+        )));
+    }
+    let source = group.copies[0].full_path();
+    for (rel, from) in files_of(&source)? {
+        let to = join_rel(&fragment_dir.join(path), &rel);
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_atomic(&to, std::fs::read(&from)?)?;
+    }
+
+    for copy in &group.copies {
+        let full = copy.full_path();
+        if full.is_dir() {
+            std::fs::remove_dir_all(&full)?;
+        } else {
+            std::fs::remove_file(&full)?;
+        }
+        let mut settings = ProfileSettings::load(&copy.profile_dir)?;
+        if !settings.include.contains(&fragment) {
+            settings.include.push(fragment.clone());
+            settings.save(&copy.profile_dir)?;
+        }
+    }
+    println!(
+        "Moved {} `{}` to shared fragment `{fragment}`, included by {} profile(s)",
+        group.kind,
+        group.name,
+        group.copies.len()
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn copy_at(root: &Path, profile: &str, path: &str) -> (&'static str, Copy) {
+        (
+            "skill",
+            Copy {
+                harness: "claude-code".to_string(),
+                profile: profile.to_string(),
+                path: PathBuf::from(path),
+                profile_dir: root.join(profile),
+            },
+        )
+    }
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn groups_identical_and_near_identical_copies() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(root, "a/skills/review/SKILL.md", "Review\n");
+        write(root, "b/skills/review/SKILL.md", "Review\n");
+        write(root, "c/skills/review/SKILL.md", "Review  \r\n\r\n");
+        write(root, "c/skills/other/SKILL.md", "Other\n");
+        let copies = vec![
+            copy_at(root, "a", "skills/review"),
+            copy_at(root, "b", "skills/review"),
+            copy_at(root, "c", "skills/review"),
+            copy_at(root, "c", "skills/other"),
+        ];
+
+        let groups = find_duplicates(&copies).unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].identical);
+        assert_eq!(groups[0].name, "review");
+        assert_eq!(groups[0].copies.len(), 2);
+        assert!(!groups[1].identical);
+        assert_eq!(groups[1].copies.len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hardlink_mode_links_later_copies_to_the_first() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(root, "a/commands/deploy.md", "Deploy\n");
+        write(root, "b/commands/deploy.md", "Deploy\n");
+        let copies = vec![
+            copy_at(root, "a", "commands/deploy.md"),
+            copy_at(root, "b", "commands/deploy.md"),
+        ];
+        let groups = find_duplicates(&copies).unwrap();
+
+        assert!(hardlink_group(&groups[0]).unwrap());
+        assert!(same_file(
+            &root.join("a/commands/deploy.md"),
+            &root.join("b/commands/deploy.md")
+        ));
+        assert!(!hardlink_group(&groups[0]).unwrap());
+    }
+
+    #[test]
+    fn fragment_mode_moves_content_to_shared_and_includes_it() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(root, "a/skills/review/SKILL.md", "Review\n");
+        write(root, "b/skills/review/SKILL.md", "Review\n");
+        let copies = vec![
+            copy_at(root, "a", "skills/review"),
+            copy_at(root, "b", "skills/review"),
+        ];
+        let groups = find_duplicates(&copies).unwrap();
+        let config_dir = root.join("config");
+
+        assert!(fragment_group(&groups[0], &config_dir).unwrap());
+
+        assert_eq!(
+            fs::read_to_string(config_dir.join("shared/skill-review/skills/review/SKILL.md"))
+                .unwrap(),
+            "Review\n"
+        );
+        for profile in ["a", "b"] {
+            assert!(!root.join(profile).join("skills/review").exists());
+            let settings = ProfileSettings::load(&root.join(profile)).unwrap();
+            assert_eq!(settings.include, vec!["skill-review".to_string()]);
+        }
+    }
+}
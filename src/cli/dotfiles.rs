@@ -0,0 +1,425 @@
+//! `bridle export --dotfiles` and `bridle import --dotfiles`.
+//!
+//! Profiles are laid out as `<dir>/<harness>/<profile>/...`, ready to commit
+//! to a dotfiles repository. Values of secret-looking keys (`token`,
+//! `api_key`, `password`, ...) in JSON, TOML, and YAML files are replaced
+//! with `{{ env "BRIDLE_SECRET_..." }}` and the file gets a `.tmpl` suffix.
+//! chezmoi renders those templates natively; `bridle import` renders them
+//! from the same environment variables.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::config::{BridleConfig, ProfileManager, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+
+const TEMPLATE_SUFFIX: &str = ".tmpl";
+const SECRET_PREFIX: &str = "BRIDLE_SECRET_";
+
+/// Normalized key fragments that mark a value as secret.
+const SECRET_KEYS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "apikey",
+    "authorization",
+    "credential",
+    "privatekey",
+    "accesskey",
+];
+
+/// Writes every profile (or those of `harness`) to `dir`.
+pub fn export(dir: &Path, harness: Option<&str>) -> Result<()> {
+    let registry = HarnessRegistry::load()?;
+    let manager = manager()?;
+    let harnesses: Vec<&dyn HarnessConfig> = match harness {
+        Some(name) => vec![registry.resolve(name)?.harness()],
+        None => registry.iter().map(|e| e.harness()).collect(),
+    };
+
+    let mut exported = 0;
+    let mut secrets = Vec::new();
+    for harness in harnesses {
+        for name in manager.list_profiles(harness)? {
+            let src = manager.profile_path(harness, &name);
+            let dst = dir.join(harness.id()).join(name.as_str());
+            if dst.exists() {
+                std::fs::remove_dir_all(&dst)?;
+            }
+            let prefix = format!("{}_{}", harness.id(), name.as_str());
+            secrets.extend(export_profile(&src, &dst, &prefix)?);
+            exported += 1;
+        }
+    }
+
+    println!("Exported {exported} profile(s) to {}", dir.display());
+    if !secrets.is_empty() {
+        println!("Secrets were replaced with placeholders; set these before importing:");
+        for name in secrets {
+            println!("  {name}");
+        }
+    }
+    Ok(())
+}
+
+/// Creates profiles from a directory written by [`export`].
+pub fn import(dir: &Path, harness: Option<&str>, force: bool) -> Result<()> {
+    let registry = HarnessRegistry::load()?;
+    let manager = manager()?;
+    let only = harness.map(|h| registry.resolve(h)).transpose()?;
+
+    let mut imported = 0;
+    for harness_dir in subdirs(dir)? {
+        let harness_name = file_name(&harness_dir);
+        let Some(entry) = registry.get(&harness_name) else {
+            eprintln!(
+                "Warning: Skipping {}: unknown harness",
+                harness_dir.display()
+            );
+            continue;
+        };
+        if only.is_some_and(|only| only.id() != entry.id()) {
+            continue;
+        }
+        let harness = entry.harness();
+
+        for profile_dir in subdirs(&harness_dir)? {
+            let profile = file_name(&profile_dir);
+            let name = ProfileName::new(&profile)
+                .map_err(|reason| Error::invalid_profile_name(&profile, reason))?;
+            if manager.profile_exists(harness, &name) && !force {
+                println!(
+                    "Skipped {}/{} (already exists, use --force to overwrite)",
+                    harness.id(),
+                    name.as_str()
+                );
+                continue;
+            }
+
+            // Render everything before touching the profile so a missing
+            // secret leaves it unchanged.
+            let files = read_profile(&profile_dir)?;
+            let dst = manager.profile_path(harness, &name);
+            if dst.exists() {
+                std::fs::remove_dir_all(&dst)?;
+            }
+            for (rel, content) in files {
+                let path = dst.join(rel);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, content)?;
+            }
+            println!("Imported {}/{}", harness.id(), name.as_str());
+            imported += 1;
+        }
+    }
+
+    println!("Imported {imported} profile(s) from {}", dir.display());
+    Ok(())
+}
+
+fn manager() -> Result<ProfileManager> {
+    let config = BridleConfig::load()?;
+    ProfileManager::builder()
+        .settings(config.settings())
+        .build()
+}
+
+/// Copies one profile to `dst`, templating secrets. Returns the variable
+/// names introduced.
+pub(crate) fn export_profile(src: &Path, dst: &Path, prefix: &str) -> Result<Vec<String>> {
+    let mut names = BTreeMap::new();
+    for rel in files_under(src)? {
+        let from = src.join(&rel);
+        let to = dst.join(&rel);
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let templated = std::fs::read_to_string(&from)
+            .ok()
+            .filter(|_| is_structured(&rel))
+            .and_then(|text| template_secrets(&text, is_yaml(&rel), prefix, &mut names));
+        match templated {
+            Some(text) => {
+                let mut name = to.into_os_string();
+                name.push(TEMPLATE_SUFFIX);
+                std::fs::write(name, text)?;
+            }
+            None => {
+                std::fs::copy(&from, &to)?;
+            }
+        }
+    }
+    Ok(names.into_keys().collect())
+}
+
+/// Reads a profile directory, rendering `.tmpl` files.
+pub(crate) fn read_profile(dir: &Path) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut files = Vec::new();
+    for rel in files_under(dir)? {
+        let path = dir.join(&rel);
+        let content = match rel.to_str().and_then(|s| s.strip_suffix(TEMPLATE_SUFFIX)) {
+            Some(stripped) => {
+                let text = std::fs::read_to_string(&path)?;
+                let rendered = render(&text, |var| std::env::var(var).ok())
+                    .map_err(|e| Error::Command(format!("{}: {e}", path.display())))?;
+                files.push((PathBuf::from(stripped), rendered.into_bytes()));
+                continue;
+            }
+            None => std::fs::read(&path)?,
+        };
+        files.push((rel, content));
+    }
+    Ok(files)
+}
+
+/// Relative paths of all files below `root`, skipping profile markers.
+fn files_under(root: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, rel: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+        let mut entries: Vec<_> = std::fs::read_dir(root.join(rel))?.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with("BRIDLE_PROFILE_") {
+                continue;
+            }
+            let rel = rel.join(&name);
+            if entry.path().is_dir() {
+                walk(root, &rel, out)?;
+            } else {
+                out.push(rel);
+            }
+        }
+        Ok(())
+    }
+    let mut out = Vec::new();
+    walk(root, Path::new(""), &mut out)?;
+    Ok(out)
+}
+
+fn subdirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Err(Error::Command(format!(
+            "not a directory: {}",
+            dir.display()
+        )));
+    }
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && !file_name(p).starts_with('.'))
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn is_structured(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("json" | "jsonc" | "toml" | "yaml" | "yml")
+    )
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+/// Replaces secret values in `text` with env placeholders, or returns `None`
+/// if there were none. `names` maps variable names to the values they hold.
+fn template_secrets(
+    text: &str,
+    yaml: bool,
+    prefix: &str,
+    names: &mut BTreeMap<String, String>,
+) -> Option<String> {
+    let mut changed = false;
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let Some((key, range)) = secret_value(line, yaml) else {
+            out.push_str(line);
+            continue;
+        };
+        let value = &line[range.clone()];
+        let base = variable_name(prefix, key);
+        let mut name = base.clone();
+        let mut n = 1;
+        while names.get(&name).is_some_and(|v| v != value) {
+            n += 1;
+            name = format!("{base}_{n}");
+        }
+        names.insert(name.clone(), value.to_string());
+        out.push_str(&line[..range.start]);
+        out.push_str(&format!("{{{{ env \"{name}\" }}}}"));
+        out.push_str(&line[range.end..]);
+        changed = true;
+    }
+    changed.then_some(out)
+}
+
+fn variable_name(prefix: &str, key: &str) -> String {
+    let raw = format!("{SECRET_PREFIX}{prefix}_{key}");
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Finds a `key: "value"` / `key = "value"` assignment on `line` whose key
+/// looks secret, returning the key and the byte range of the value.
+fn secret_value(line: &str, yaml: bool) -> Option<(&str, Range<usize>)> {
+    let start = line.len() - line.trim_start().len();
+    let rest = &line[start..];
+    let (key, key_end) = if let Some(quoted) = rest.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        (&quoted[..end], start + end + 2)
+    } else {
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || "_-.".contains(c)))
+            .unwrap_or(rest.len());
+        (&rest[..end], start + end)
+    };
+    let normalized: String = key
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    if key.is_empty() || !SECRET_KEYS.iter().any(|s| normalized.contains(s)) {
+        return None;
+    }
+
+    let after_key = &line[key_end..];
+    let sep = key_end + after_key.len() - after_key.trim_start().len();
+    if !line[sep..].starts_with([':', '=']) {
+        return None;
+    }
+    let after_sep = &line[sep + 1..];
+    let value_start = sep + 1 + after_sep.len() - after_sep.trim_start().len();
+    let value = &line[value_start..];
+
+    let range = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let mut escaped = false;
+            let end = value[1..].char_indices().find_map(|(i, c)| {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == quote {
+                    return Some(i);
+                }
+                None
+            })?;
+            value_start + 1..value_start + 1 + end
+        }
+        Some(_) if yaml => {
+            let end = value.find(" #").unwrap_or(value.len());
+            value_start..value_start + value[..end].trim_end().len()
+        }
+        _ => return None,
+    };
+
+    let secret = &line[range.clone()];
+    let is_reference = ["{{", "${", "$", "{env:", "{file:"]
+        .iter()
+        .any(|p| secret.starts_with(p));
+    // Bare YAML scalars like `max_tokens: 4096` are not secrets.
+    let is_scalar = secret.parse::<f64>().is_ok() || matches!(secret, "true" | "false" | "null");
+    (!secret.is_empty() && !is_reference && !is_scalar).then_some((key, range))
+}
+
+/// Renders `{{ env "NAME" }}` placeholders using `lookup`.
+fn render(
+    text: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut missing = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let close = after
+            .find("}}")
+            .ok_or_else(|| "unterminated `{{`".to_string())?;
+        let expr = after[..close].trim();
+        // Anything but `env "..."` is a profile template variable, rendered at switch time.
+        let Some(var) = expr
+            .strip_prefix("env")
+            .map(str::trim)
+            .and_then(|v| v.strip_prefix('"'))
+            .and_then(|v| v.strip_suffix('"'))
+        else {
+            out.push_str(&rest[open..open + 2 + close + 2]);
+            rest = &after[close + 2..];
+            continue;
+        };
+        match lookup(var) {
+            Some(value) => out.push_str(&value),
+            None => missing.push(var.to_string()),
+        }
+        rest = &after[close + 2..];
+    }
+    out.push_str(rest);
+    if missing.is_empty() {
+        Ok(out)
+    } else {
+        Err(format!(
+            "missing environment variables: {}",
+            missing.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_become_env_placeholders() {
+        let json = "{\n  \"env\": {\n    \"GITHUB_TOKEN\": \"ghp_abc\",\n    \"REF\": \"{env:X}\"\n  },\n  \"model\": \"opus\"\n}\n";
+        let mut names = BTreeMap::new();
+        let out = template_secrets(json, false, "opencode_work", &mut names).unwrap();
+        assert!(out.contains(
+            "\"GITHUB_TOKEN\": \"{{ env \"BRIDLE_SECRET_OPENCODE_WORK_GITHUB_TOKEN\" }}\""
+        ));
+        assert!(out.contains("{env:X}"));
+        assert!(out.contains("\"model\": \"opus\""));
+        assert_eq!(names.len(), 1);
+
+        assert_eq!(render(&out, |_| Some("ghp_abc".to_string())).unwrap(), json);
+        assert!(render(&out, |_| None).unwrap_err().contains("GITHUB_TOKEN"));
+        assert!(template_secrets("model = \"opus\"\n", false, "p", &mut names).is_none());
+    }
+
+    #[test]
+    fn yaml_and_toml_assignments_are_detected() {
+        assert_eq!(
+            secret_value("  api_key: sk-123 # prod\n", true).map(|(k, _)| k),
+            Some("api_key")
+        );
+        assert!(secret_value("api_key: sk-123\n", false).is_none());
+        let line = "password = 'hunter2'\n";
+        let (_, range) = secret_value(line, false).unwrap();
+        assert_eq!(&line[range], "hunter2");
+        assert!(secret_value("auth_token: ${TOKEN}\n", true).is_none());
+        assert!(secret_value("max_tokens: 4096\n", true).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+//! `bridle exec`: run one command under a profile without switching to it.
+
+use std::process::{Command, ExitStatus};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::cli::profile::manager_builder;
+use crate::config::ProfileName;
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+
+/// Applies `profile` to `harness`, runs `command`, and restores the previous
+/// config. Returns the command's exit code.
+///
+/// Interrupts do not kill bridle while the command runs: Ctrl-C reaches the
+/// command through the terminal, and a termination signal sent to bridle is
+/// passed on by killing the command. Either way the restore still happens.
+pub fn run(harness_name: &str, profile: &str, command: &[String]) -> Result<i32> {
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    let name =
+        ProfileName::new(profile).map_err(|reason| Error::invalid_profile_name(profile, reason))?;
+    let manager = manager_builder()?.build()?;
+    if !manager.profile_exists(harness.as_ref(), &name) {
+        return Err(Error::profile_not_found(harness.id(), profile));
+    }
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| Error::Command("no command given".to_string()))?;
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let terminated = Arc::new(AtomicBool::new(false));
+    register_signals(&interrupted, &terminated)?;
+
+    let status =
+        manager.with_profile_applied(harness.as_ref(), harness.as_builtin(), &name, || {
+            // Interrupted while switching: skip the command, just restore.
+            if interrupted.load(Ordering::SeqCst) || terminated.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+            let mut child = Command::new(program)
+                .args(args)
+                .env("BRIDLE_EXEC_PROFILE", name.as_str())
+                .spawn()
+                .map_err(|e| Error::Command(format!("failed to run {program}: {e}")))?;
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    return Ok(Some(status));
+                }
+                if terminated.load(Ordering::SeqCst) {
+                    let _ = child.kill();
+                    return child.wait().map(Some).map_err(Error::from);
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        })??;
+
+    Ok(match status {
+        Some(status) => exit_code(status),
+        None => 130,
+    })
+}
+
+fn register_signals(interrupted: &Arc<AtomicBool>, terminated: &Arc<AtomicBool>) -> Result<()> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+
+    signal_hook::flag::register(SIGINT, Arc::clone(interrupted))?;
+    signal_hook::flag::register(SIGTERM, Arc::clone(terminated))?;
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(terminated))?;
+    Ok(())
+}
+
+/// Exit code to pass on, using the shell convention `128 + signal` for
+/// commands killed by a signal.
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
@@ -0,0 +1,321 @@
+//! `bridle harness`: setting up and decommissioning harnesses.
+//!
+//! `install` runs the harness's own installer (npm, Homebrew, or its install
+//! script) after showing the command and asking, then gives the harness a
+//! `default` profile so it is managed from the start. `remove` undoes
+//! bridle's side of that, leaving the harness's live config for the user.
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::Command;
+
+use clap::ValueEnum;
+use dialoguer_multiselect::Confirm;
+use harness_locate::{HarnessKind, InstallationStatus};
+use serde::Serialize;
+
+use super::output::{ResolvedFormat, output};
+use super::profile::manager_builder;
+use crate::config::{BridleConfig, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+
+/// How a harness binary gets installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Installer {
+    /// A global npm package.
+    Npm,
+    /// A Homebrew formula or cask.
+    Brew,
+    /// The vendor's install script, piped to a shell.
+    Script,
+}
+
+impl Installer {
+    /// Program that has to be on PATH for this installer to work.
+    fn tool(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Brew => "brew",
+            Self::Script => "curl",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Brew => "brew",
+            Self::Script => "script",
+        }
+    }
+}
+
+/// Ways to install each built-in harness, most preferred first.
+fn recipes(kind: HarnessKind) -> &'static [(Installer, &'static str)] {
+    match kind {
+        HarnessKind::ClaudeCode => &[
+            (Installer::Npm, "npm install -g @anthropic-ai/claude-code"),
+            (Installer::Brew, "brew install --cask claude-code"),
+            (
+                Installer::Script,
+                "curl -fsSL https://claude.ai/install.sh | bash",
+            ),
+        ],
+        HarnessKind::OpenCode => &[
+            (Installer::Npm, "npm install -g opencode-ai"),
+            (Installer::Brew, "brew install sst/tap/opencode"),
+            (
+                Installer::Script,
+                "curl -fsSL https://opencode.ai/install | bash",
+            ),
+        ],
+        HarnessKind::Goose => &[
+            (Installer::Brew, "brew install block-goose-cli"),
+            (
+                Installer::Script,
+                "curl -fsSL https://github.com/block/goose/releases/download/stable/download_cli.sh | bash",
+            ),
+        ],
+        HarnessKind::AmpCode => &[
+            (Installer::Npm, "npm install -g @sourcegraph/amp"),
+            (
+                Installer::Script,
+                "curl -fsSL https://ampcode.com/install.sh | bash",
+            ),
+        ],
+        _ => &[],
+    }
+}
+
+/// The recipe to run: the one for `via`, or the first whose tool is on PATH.
+fn choose(
+    recipes: &'static [(Installer, &'static str)],
+    via: Option<Installer>,
+    on_path: impl Fn(&str) -> bool,
+) -> Option<(Installer, &'static str)> {
+    recipes.iter().copied().find(|(installer, _)| match via {
+        Some(via) => *installer == via,
+        None => on_path(installer.tool()),
+    })
+}
+
+/// Installs `harness_name` unless it is already installed, then creates its
+/// `default` profile from whatever config it has.
+///
+/// The installer runs only after confirmation on a terminal, or with `yes`.
+pub fn install(harness_name: &str, via: Option<Installer>, yes: bool) -> Result<()> {
+    let registry = HarnessRegistry::load()?;
+    let entry = registry.resolve(harness_name)?;
+    let harness = entry.harness();
+
+    match harness.installation_status()? {
+        InstallationStatus::FullyInstalled { .. } | InstallationStatus::BinaryOnly { .. } => {
+            println!("{} is already installed", entry.name());
+        }
+        _ => {
+            let recipes = entry.kind().map(recipes).unwrap_or_default();
+            if recipes.is_empty() {
+                return Err(Error::Command(format!(
+                    "bridle does not know how to install {}; install it yourself",
+                    harness.id()
+                )));
+            }
+            let available: Vec<&str> = recipes.iter().map(|(i, _)| i.name()).collect();
+            let (installer, command) = choose(recipes, via, |tool| {
+                harness_locate::find_binary(tool).is_ok_and(|path| path.is_some())
+            })
+            .ok_or_else(|| {
+                Error::Command(match via {
+                    Some(via) => format!(
+                        "{} has no {} installer; use one of: {}",
+                        harness.id(),
+                        via.name(),
+                        available.join(", ")
+                    ),
+                    None => format!(
+                        "none of the tools to install {} were found ({}); install one first",
+                        harness.id(),
+                        recipes
+                            .iter()
+                            .map(|(i, _)| i.tool())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                })
+            })?;
+
+            println!("Installing {} with {}:", entry.name(), installer.name());
+            println!("  {command}");
+            if !yes {
+                if !std::io::stdin().is_terminal() {
+                    return Err(Error::Command(
+                        "not a terminal; pass --yes to run the installer".to_string(),
+                    ));
+                }
+                let proceed = Confirm::new()
+                    .with_prompt("Run it?")
+                    .default(false)
+                    .interact()
+                    .map_err(|e| Error::Command(e.to_string()))?;
+                if !proceed {
+                    println!("Install cancelled");
+                    return Ok(());
+                }
+            }
+            run_installer(installer, command)?;
+            println!("Installed {}", entry.name());
+        }
+    }
+
+    let manager = manager_builder()?.build()?;
+    let name = ProfileName::new("default").expect("'default' is a valid profile name");
+    if manager.profile_exists(harness, &name) {
+        println!("Profile 'default' already exists");
+    } else if harness.config_dir()?.is_dir() {
+        manager.create_from_current_with_resources(harness, harness.as_builtin(), &name)?;
+        println!("Created profile 'default' from the current config");
+    } else {
+        manager.create_profile(harness, &name)?;
+        println!("Created empty profile 'default'");
+    }
+    Ok(())
+}
+
+fn run_installer(installer: Installer, command: &str) -> Result<()> {
+    let mut process = match installer {
+        // Install scripts are piped from curl, which takes a shell.
+        Installer::Script => {
+            let mut process = Command::new("sh");
+            process.args(["-c", command]);
+            process
+        }
+        Installer::Npm | Installer::Brew => {
+            let mut words = command.split_whitespace();
+            let mut process = Command::new(words.next().unwrap_or_default());
+            process.args(words);
+            process
+        }
+    };
+    let status = process
+        .status()
+        .map_err(|e| Error::Command(format!("failed to run `{command}`: {e}")))?;
+    if !status.success() {
+        return Err(Error::Command(format!("`{command}` failed ({status})")));
+    }
+    Ok(())
+}
+
+/// What `bridle harness remove` cleared and what it left.
+#[derive(Debug, Serialize)]
+struct RemoveReport {
+    harness: String,
+    /// Profile that was active before the removal.
+    active: Option<String>,
+    deleted_profiles: Vec<String>,
+    /// Profiles left in place, without `--purge-profiles`.
+    kept_profiles: usize,
+    profiles_dir: PathBuf,
+    /// The harness's own config directory, which bridle never deletes.
+    live_config: Option<PathBuf>,
+}
+
+/// Clears bridle's state for `harness_name`, deleting its profiles too with
+/// `purge_profiles`, and reports where its live config remains.
+pub fn remove(harness_name: &str, purge_profiles: bool, format: ResolvedFormat) -> Result<()> {
+    let registry = HarnessRegistry::load()?;
+    let harness = registry.resolve(harness_name)?.harness();
+    let config = BridleConfig::load()?;
+    let manager = manager_builder()?.build()?;
+    let profiles = manager.list_profiles(harness)?;
+
+    if purge_profiles
+        && !profiles.is_empty()
+        && config.settings().confirm
+        && std::io::stdin().is_terminal()
+    {
+        let proceed = Confirm::new()
+            .with_prompt(format!(
+                "Delete all {} profile(s) of {}?",
+                profiles.len(),
+                harness.id()
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| Error::Command(e.to_string()))?;
+        if !proceed {
+            println!("Remove cancelled");
+            return Ok(());
+        }
+    }
+
+    let deleted = manager.forget_harness(harness, purge_profiles)?;
+    let report = RemoveReport {
+        harness: harness.id().to_string(),
+        active: config.active_profile_for(harness.id()).map(str::to_string),
+        kept_profiles: profiles.len() - deleted.len(),
+        deleted_profiles: deleted.iter().map(|n| n.as_str().to_string()).collect(),
+        profiles_dir: manager.profiles_dir().join(harness.id()),
+        live_config: harness.config_dir().ok().filter(|dir| dir.exists()),
+    };
+    output(&report, format, |report| {
+        match &report.active {
+            Some(active) => println!(
+                "Removed bridle state for {} (was using profile '{active}')",
+                report.harness
+            ),
+            None => println!("Removed bridle state for {}", report.harness),
+        }
+        if !report.deleted_profiles.is_empty() {
+            println!(
+                "Deleted {} profile(s): {}",
+                report.deleted_profiles.len(),
+                report.deleted_profiles.join(", ")
+            );
+        }
+        if report.kept_profiles > 0 {
+            println!(
+                "Kept {} profile(s) in {}; pass --purge-profiles to delete them",
+                report.kept_profiles,
+                report.profiles_dir.display()
+            );
+        }
+        match &report.live_config {
+            Some(dir) => println!(
+                "Live config remains at {}; delete it yourself if you no longer need it",
+                dir.display()
+            ),
+            None => println!("No live config left behind"),
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chooses_requested_or_first_available_installer() {
+        let opencode = recipes(HarnessKind::OpenCode);
+        assert_eq!(
+            choose(opencode, None, |tool| tool == "curl").map(|(i, _)| i),
+            Some(Installer::Script)
+        );
+        assert_eq!(
+            choose(opencode, Some(Installer::Brew), |_| false).map(|(i, _)| i),
+            Some(Installer::Brew)
+        );
+        assert_eq!(choose(opencode, None, |_| false), None);
+        assert_eq!(
+            choose(recipes(HarnessKind::Goose), Some(Installer::Npm), |_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn every_builtin_harness_has_an_installer() {
+        for kind in HarnessKind::ALL {
+            assert!(!recipes(*kind).is_empty(), "{kind:?}");
+        }
+    }
+}
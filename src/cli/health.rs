@@ -0,0 +1,311 @@
+//! Profile health: one score for what `status` and `profile show` report.
+//!
+//! A profile starts at 100 and loses points for each issue found: extraction
+//! errors and files out of sync with `bridle.lock`, drift in the live config
+//! while it is active, enabled MCP servers that cannot start (a command not
+//! on `PATH`, a URL that is not HTTP), paths its MCP servers or rules file
+//! reference that do not exist, and a rules file of
+//! [`LARGE_RULES_TOKENS`] or more.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::{
+    LARGE_RULES_TOKENS, McpServerInfo, ProfileInfo, ProfileManager, format_tokens,
+};
+use crate::harness::{HarnessConfig, expand_home, find_all_binaries};
+use crate::install::lock::{LOCK_FILENAME, Lockfile, lock_path, verify};
+
+/// What a health issue is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The profile could not be read fully, or differs from its lockfile.
+    Validation,
+    /// The live config changed since the profile was switched in or saved.
+    Drift,
+    /// An enabled MCP server that cannot start.
+    McpServer,
+    /// A path the profile references does not exist.
+    MissingFile,
+    /// The rules file takes up a large part of the context.
+    OversizedRules,
+}
+
+impl IssueKind {
+    /// Points the issue takes off the score.
+    fn penalty(self) -> u8 {
+        match self {
+            Self::Validation => 20,
+            Self::McpServer => 15,
+            Self::Drift | Self::MissingFile | Self::OversizedRules => 10,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Validation => "validation",
+            Self::Drift => "drift",
+            Self::McpServer => "mcp",
+            Self::MissingFile => "missing file",
+            Self::OversizedRules => "rules size",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthIssue {
+    pub kind: IssueKind,
+    pub detail: String,
+}
+
+impl std::fmt::Display for HealthIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind.label(), self.detail)
+    }
+}
+
+/// A profile's health score, from 0 to 100, and the issues behind it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileHealth {
+    pub score: u8,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl ProfileHealth {
+    fn new(issues: Vec<HealthIssue>) -> Self {
+        let lost: u32 = issues.iter().map(|i| u32::from(i.kind.penalty())).sum();
+        Self {
+            score: 100u32.saturating_sub(lost) as u8,
+            issues,
+        }
+    }
+
+    /// `healthy`, `degraded`, or `unhealthy`.
+    pub fn grade(&self) -> &'static str {
+        match self.score {
+            90.. => "healthy",
+            60.. => "degraded",
+            _ => "unhealthy",
+        }
+    }
+
+    /// `85/100 (degraded)`.
+    pub fn summary(&self) -> String {
+        format!("{}/100 ({})", self.score, self.grade())
+    }
+}
+
+/// Assesses `info`, a profile of `harness` read by `manager`.
+pub fn assess(
+    manager: &ProfileManager,
+    harness: &dyn HarnessConfig,
+    info: &ProfileInfo,
+) -> ProfileHealth {
+    let mut issues = Vec::new();
+    let mut issue = |kind, detail: String| issues.push(HealthIssue { kind, detail });
+
+    for error in &info.extraction_errors {
+        issue(IssueKind::Validation, error.to_string());
+    }
+    if let Some(detail) = lock_mismatch(&info.path) {
+        issue(IssueKind::Validation, detail);
+    }
+    if info.is_active
+        && let Some(drift) = manager.drift(harness).ok().flatten()
+        && !drift.is_clean()
+    {
+        issue(
+            IssueKind::Drift,
+            format!("{} file(s) changed since switch", drift.len()),
+        );
+    }
+    for server in info.mcp_servers.iter().filter(|s| s.enabled) {
+        if let Some(problem) = unstartable(server) {
+            issue(IssueKind::McpServer, format!("{}: {problem}", server.name));
+        }
+        for path in missing_arg_paths(server) {
+            issue(
+                IssueKind::MissingFile,
+                format!(
+                    "{} is referenced by MCP server {}",
+                    path.display(),
+                    server.name
+                ),
+            );
+        }
+    }
+    if let Some(rules) = &info.rules_file {
+        for path in missing_imports(rules) {
+            issue(
+                IssueKind::MissingFile,
+                format!("{} is imported by the rules file", path.display()),
+            );
+        }
+    }
+    if let Some(tokens) = info.rules_tokens.filter(|t| *t >= LARGE_RULES_TOKENS) {
+        issue(
+            IssueKind::OversizedRules,
+            format!(
+                "rules file is ~{} tokens, over {}",
+                format_tokens(tokens),
+                format_tokens(LARGE_RULES_TOKENS)
+            ),
+        );
+    }
+    ProfileHealth::new(issues)
+}
+
+/// How the files installed into the profile at `profile_path` differ from
+/// its `bridle.lock`, if they do.
+pub fn lock_mismatch(profile_path: &Path) -> Option<String> {
+    let path = lock_path(profile_path);
+    if !path.exists() {
+        return None;
+    }
+    match Lockfile::load(&path) {
+        Ok(lock) => {
+            let differences = verify(profile_path, &lock).len();
+            (differences > 0).then(|| format!("{differences} file(s) differ from {LOCK_FILENAME}"))
+        }
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Whether `value` is filled in on switch, so it cannot be checked as is.
+fn is_templated(value: &str) -> bool {
+    value.contains("${") || value.contains("{{")
+}
+
+/// Why the enabled MCP `server` cannot start, if it cannot.
+fn unstartable(server: &McpServerInfo) -> Option<String> {
+    if let Some(command) = server.command.as_deref().filter(|c| !is_templated(c)) {
+        let found = if command.contains('/') || command.starts_with('~') {
+            expand_home(Path::new(command)).is_ok_and(|path| path.is_file())
+        } else {
+            !find_all_binaries(command).is_empty()
+        };
+        return (!found).then(|| format!("command {command} not found"));
+    }
+    let url = server.url.as_deref().filter(|u| !is_templated(u))?;
+    (!url.starts_with("http://") && !url.starts_with("https://"))
+        .then(|| format!("{url} is not an HTTP URL"))
+}
+
+/// Absolute or home-relative paths among `server`'s arguments that do not exist.
+fn missing_arg_paths(server: &McpServerInfo) -> Vec<PathBuf> {
+    server
+        .args
+        .iter()
+        .flatten()
+        .filter(|arg| (arg.starts_with('/') || arg.starts_with("~/")) && !is_templated(arg))
+        .filter_map(|arg| expand_home(Path::new(arg)).ok())
+        .filter(|path| !path.exists())
+        .collect()
+}
+
+/// Files imported with `@path` in the rules file at `rules` that do not exist.
+fn missing_imports(rules: &Path) -> Vec<PathBuf> {
+    let Ok(text) = std::fs::read_to_string(rules) else {
+        return Vec::new();
+    };
+    let base = rules.parent().unwrap_or(Path::new("."));
+    let mut in_code = false;
+    let mut missing = Vec::new();
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if in_code {
+            continue;
+        }
+        for word in line.split_whitespace() {
+            let word = word.trim_end_matches([',', '.', ';', ':', ')']);
+            let Some(import) = word.strip_prefix('@') else {
+                continue;
+            };
+            if !["/", "~/", "./", "../"]
+                .iter()
+                .any(|p| import.starts_with(p))
+            {
+                continue;
+            }
+            let Ok(path) = expand_home(Path::new(import)) else {
+                continue;
+            };
+            let path = base.join(path);
+            if !path.exists() && !missing.contains(&path) {
+                missing.push(path);
+            }
+        }
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: Option<&str>, args: &[&str], url: Option<&str>) -> McpServerInfo {
+        McpServerInfo {
+            name: "s".to_string(),
+            enabled: true,
+            command: command.map(str::to_string),
+            args: Some(args.iter().map(|a| a.to_string()).collect()),
+            url: url.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn score_drops_with_each_issue() {
+        let issue = |kind| HealthIssue {
+            kind,
+            detail: String::new(),
+        };
+        assert_eq!(
+            ProfileHealth::new(Vec::new()).summary(),
+            "100/100 (healthy)"
+        );
+        let health = ProfileHealth::new(vec![issue(IssueKind::McpServer), issue(IssueKind::Drift)]);
+        assert_eq!(health.summary(), "75/100 (degraded)");
+        let health = ProfileHealth::new(vec![issue(IssueKind::Validation); 6]);
+        assert_eq!(health.summary(), "0/100 (unhealthy)");
+    }
+
+    #[test]
+    fn finds_servers_that_cannot_start_and_missing_paths() {
+        assert_eq!(
+            unstartable(&server(Some("bridle-test-no-such-mcp"), &[], None)).as_deref(),
+            Some("command bridle-test-no-such-mcp not found")
+        );
+        assert_eq!(unstartable(&server(Some("${MCP_BIN}"), &[], None)), None);
+        assert_eq!(
+            unstartable(&server(None, &[], Some("https://mcp.example.com"))),
+            None
+        );
+        assert!(unstartable(&server(None, &[], Some("mcp.example.com"))).is_some());
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let present = temp.path().display().to_string();
+        let absent = temp.path().join("gone").display().to_string();
+        assert_eq!(
+            missing_arg_paths(&server(Some("npx"), &["-y", &present, &absent], None)),
+            [PathBuf::from(&absent)]
+        );
+    }
+
+    #[test]
+    fn finds_missing_rules_imports_outside_code() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("style.md"), "").unwrap();
+        let rules = temp.path().join("CLAUDE.md");
+        std::fs::write(
+            &rules,
+            "See @./style.md and @./testing.md, mail me @alice.\n```\n@./ignored.md\n```\n",
+        )
+        .unwrap();
+        assert_eq!(missing_imports(&rules), [temp.path().join("./testing.md")]);
+    }
+}
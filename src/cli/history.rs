@@ -0,0 +1,259 @@
+//! A profile's snapshot history: `bridle profile log` and `restore-file`.
+//!
+//! Snapshots are the copies of the live config taken before each switch, so
+//! the ones of a profile show it as it was each time it was switched away
+//! from. Patches compare the snapshots with each other and with the profile
+//! as it is now, using `diff -ruN`.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::output::{ResolvedFormat, output, output_list};
+use super::profile::manager_builder;
+use crate::config::{
+    PROFILE_FILE, ProfileManager, ProfileName, SNAPSHOT_FILE, Snapshot, rules::RULES_DIR,
+    template::VARS_FILE,
+};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+use crate::install::lock::LOCK_FILENAME;
+
+/// What `diff` leaves out: files bridle keeps in snapshots and profiles for
+/// itself, and machine variants, none of which reach the live config as they are.
+const NOT_CONFIG: [&str; 7] = [
+    SNAPSHOT_FILE,
+    "BRIDLE_PROFILE_*",
+    "*.machine-*",
+    PROFILE_FILE,
+    VARS_FILE,
+    RULES_DIR,
+    LOCK_FILENAME,
+];
+
+/// Stands for the profile as it is now wherever a snapshot id is taken.
+pub const CURRENT: &str = "current";
+
+#[derive(Debug, Serialize)]
+struct LogEntry {
+    /// A snapshot id, or [`CURRENT`].
+    id: String,
+    taken_at: Option<String>,
+    /// Changes since the entry before it, when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    patch: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SnapshotDiff {
+    from: String,
+    to: String,
+    patch: String,
+}
+
+/// The snapshots of profile `name` of `harness`, oldest first.
+fn profile_snapshots(
+    manager: &ProfileManager,
+    harness: &dyn HarnessConfig,
+    name: &ProfileName,
+) -> Result<Vec<Snapshot>> {
+    let mut snapshots = manager.snapshots(harness)?;
+    snapshots.retain(|s| s.profile.as_deref() == Some(name.as_str()));
+    Ok(snapshots)
+}
+
+/// The unified diff from `old` to `new`; empty when they are the same.
+fn patch(root: &Path, old: &Path, new: &Path) -> Result<String> {
+    let relative = |path: &Path| path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    let mut command = std::process::Command::new("diff");
+    command.arg("-ruN");
+    for pattern in NOT_CONFIG {
+        command.arg("-x").arg(pattern);
+    }
+    let result = command
+        .arg(relative(old))
+        .arg(relative(new))
+        .current_dir(root)
+        .output()?;
+    match result.status.code() {
+        // Without the `diff -ruN -x ...` line diff puts before each file.
+        Some(0 | 1) => Ok(String::from_utf8_lossy(&result.stdout)
+            .lines()
+            .filter(|line| !line.starts_with("diff -ruN "))
+            .map(|line| format!("{line}\n"))
+            .collect()),
+        _ => Err(Error::Command(format!(
+            "diff exited with status {}: {}",
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        ))),
+    }
+}
+
+/// Lists the snapshots of profile `name`, newest first. With `show_patch`,
+/// each comes with what changed since the one before it, and the profile as
+/// it is now with what changed since the newest. With `from`, prints only the
+/// patch between snapshot `from` and snapshot `to`, or the profile as it is
+/// now.
+pub fn log(
+    harness_name: &str,
+    profile_name: &str,
+    show_patch: bool,
+    from: Option<&str>,
+    to: Option<&str>,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    let manager = manager_builder()?.build()?;
+    let name = ProfileName::new(profile_name)
+        .map_err(|reason| Error::invalid_profile_name(profile_name, reason))?;
+    let profile_path = manager.profile_path(harness.as_ref(), &name);
+    if !profile_path.exists() {
+        return Err(Error::profile_not_found(harness.id(), profile_name));
+    }
+    let snapshots = profile_snapshots(&manager, harness.as_ref(), &name)?;
+    let root = manager.backups_dir();
+    let root = root.parent().unwrap_or(&root);
+
+    let locate = |id: &str| -> Result<std::path::PathBuf> {
+        if id == CURRENT {
+            return Ok(profile_path.clone());
+        }
+        snapshots
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.path.clone())
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no snapshot {id} of profile {profile_name}; see `bridle profile log {harness_name} {profile_name}`"
+                ))
+            })
+    };
+
+    if let Some(from) = from {
+        let to = to.unwrap_or(CURRENT);
+        let diff = SnapshotDiff {
+            patch: patch(root, &locate(from)?, &locate(to)?)?,
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        output(&diff, format, |diff| {
+            if diff.patch.is_empty() {
+                println!("No differences between {} and {}", diff.from, diff.to);
+            } else {
+                print!("{}", diff.patch);
+            }
+        });
+        return Ok(());
+    }
+
+    let mut entries = Vec::with_capacity(snapshots.len() + 1);
+    let mut newer = profile_path.as_path();
+    let mut newer_entry = LogEntry {
+        id: CURRENT.to_string(),
+        taken_at: None,
+        patch: None,
+    };
+    for snapshot in snapshots.iter().rev() {
+        if show_patch {
+            newer_entry.patch = Some(patch(root, &snapshot.path, newer)?);
+        }
+        entries.push(newer_entry);
+        newer = &snapshot.path;
+        newer_entry = LogEntry {
+            id: snapshot.id.clone(),
+            taken_at: snapshot.taken_at.map(|t| t.to_string()),
+            patch: None,
+        };
+    }
+    entries.push(newer_entry);
+
+    output_list(&entries, format, |entries| {
+        if entries.len() == 1 {
+            println!(
+                "No snapshots of {profile_name} yet; one is taken each time it is switched away from."
+            );
+            return;
+        }
+        for entry in entries {
+            match &entry.taken_at {
+                Some(taken_at) => println!("snapshot {}  ({taken_at})", entry.id),
+                None => println!("{} (profile as it is now)", entry.id),
+            }
+            match entry.patch.as_deref() {
+                Some("") => println!("    no changes\n"),
+                Some(patch) => println!("\n{patch}"),
+                None => {}
+            }
+        }
+    });
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct RestoreReport {
+    snapshot: String,
+    path: PathBuf,
+    profile_file: PathBuf,
+    live_file: Option<PathBuf>,
+}
+
+/// Copies the file at `path` out of snapshot `snapshot_id` into profile `name`,
+/// and into the live config when the profile is active.
+pub fn restore_file(
+    harness_name: &str,
+    profile_name: &str,
+    snapshot_id: &str,
+    path: &Path,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    let manager = manager_builder()?.build()?;
+    let name = ProfileName::new(profile_name)
+        .map_err(|reason| Error::invalid_profile_name(profile_name, reason))?;
+    let snapshot = manager.snapshot(harness.as_ref(), snapshot_id)?;
+    let restored = manager.restore_file(harness.as_ref(), &name, &snapshot, path)?;
+    let report = RestoreReport {
+        snapshot: snapshot.id,
+        path: path.to_path_buf(),
+        profile_file: restored.profile_file,
+        live_file: restored.live_file,
+    };
+    output(&report, format, |report| {
+        println!(
+            "Restored {} from snapshot {}",
+            report.path.display(),
+            report.snapshot
+        );
+        println!("  Profile: {}", report.profile_file.display());
+        if let Some(live_file) = &report.live_file {
+            println!("  Live:    {}", live_file.display());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_leaves_out_bridles_own_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let old = temp.path().join("backups/opencode/20260310_142501");
+        let new = temp.path().join("profiles/opencode/work");
+        std::fs::create_dir_all(&old).unwrap();
+        std::fs::create_dir_all(&new).unwrap();
+        std::fs::write(old.join(SNAPSHOT_FILE), "work\n").unwrap();
+        std::fs::write(old.join("opencode.json"), "{\"ask\": true}\n").unwrap();
+        std::fs::write(new.join("opencode.json"), "{\"ask\": false}\n").unwrap();
+        std::fs::write(new.join(LOCK_FILENAME), "").unwrap();
+
+        let diff = patch(temp.path(), &old, &new).unwrap();
+        assert!(diff.contains("--- backups/opencode/20260310_142501/opencode.json"));
+        assert!(diff.contains("-{\"ask\": true}\n+{\"ask\": false}"));
+        assert!(!diff.contains(SNAPSHOT_FILE));
+        assert!(!diff.contains(LOCK_FILENAME));
+        assert!(patch(temp.path(), &new, &new).unwrap().is_empty());
+    }
+}
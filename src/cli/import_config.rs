@@ -0,0 +1,157 @@
+//! `bridle import-config`: first capture of a harness bridle does not manage yet.
+//!
+//! Unlike the blind capture `bridle init` does, the import lists what the live
+//! config holds and how big it is, proposes exclude patterns for caches and
+//! logs, and saves the accepted ones to `[harness.<id>]` before the initial
+//! profile is created.
+
+use std::io::IsTerminal;
+
+use dialoguer_multiselect::MultiSelect;
+use dialoguer_multiselect::theme::ColorfulTheme;
+use serde::Serialize;
+
+use super::output::{ResolvedFormat, output};
+use super::profile::manager_builder;
+use crate::config::{BridleConfig, LiveEntry, ProfileName, format_size};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+
+/// What the import found and did.
+#[derive(Debug, Serialize)]
+struct ImportReport {
+    harness: String,
+    profile: String,
+    entries: Vec<LiveEntry>,
+    /// Exclude patterns added to `[harness.<id>]`.
+    added_excludes: Vec<String>,
+    /// Bytes copied into the profile.
+    profile_size: u64,
+}
+
+/// Imports the live config of `harness_name` as its first profile, `profile_name`.
+///
+/// Proposed excludes are chosen interactively on a terminal; `yes` accepts
+/// them all, and without a terminal none are added.
+pub fn run(
+    harness_name: &str,
+    profile_name: &str,
+    yes: bool,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let name = ProfileName::new(profile_name)
+        .map_err(|reason| Error::invalid_profile_name(profile_name, reason))?;
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    let config_dir = harness.config_dir()?;
+    if !config_dir.is_dir() {
+        return Err(Error::HarnessNotInstalled {
+            harness: harness.id().to_string(),
+        });
+    }
+    let manager = manager_builder()?.build()?;
+    let existing = manager.list_profiles(harness.as_ref())?;
+    if !existing.is_empty() {
+        return Err(Error::Config(format!(
+            "{} already has {} profile(s); use `bridle profile create {} <name> --from-current` instead",
+            harness.id(),
+            existing.len(),
+            harness.id()
+        )));
+    }
+
+    let entries = manager.inspect_live(harness.as_ref())?;
+    let mut proposed: Vec<String> = Vec::new();
+    for pattern in entries.iter().filter_map(|e| e.suggested_exclude.clone()) {
+        if !proposed.contains(&pattern) {
+            proposed.push(pattern);
+        }
+    }
+    if format == ResolvedFormat::Text {
+        print_entries(harness.as_ref(), &entries);
+    }
+    let added_excludes = choose_excludes(&proposed, yes)?;
+
+    if !added_excludes.is_empty() {
+        let mut config = BridleConfig::load()?;
+        let overrides = config.harness.entry(harness.id().to_string()).or_default();
+        for pattern in &added_excludes {
+            if !overrides.exclude.contains(pattern) {
+                overrides.exclude.push(pattern.clone());
+            }
+        }
+        config.save()?;
+    }
+
+    // Reload so the harness picks up the excludes just saved.
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    let path = manager.create_from_current_with_resources(
+        harness.as_ref(),
+        harness.as_builtin(),
+        &name,
+    )?;
+    let profile_size = manager.profile_size(harness.as_ref(), &name);
+
+    let report = ImportReport {
+        harness: harness.id().to_string(),
+        profile: name.as_str().to_string(),
+        entries,
+        added_excludes,
+        profile_size,
+    };
+    output(&report, format, |report| {
+        if !report.added_excludes.is_empty() {
+            println!(
+                "Added to [harness.{}] exclude: {}",
+                report.harness,
+                report.added_excludes.join(", ")
+            );
+        }
+        println!(
+            "Imported {} as profile '{}' ({})",
+            report.harness,
+            report.profile,
+            format_size(report.profile_size)
+        );
+        println!("Path: {}", path.display());
+    });
+    Ok(())
+}
+
+fn print_entries(harness: &dyn HarnessConfig, entries: &[LiveEntry]) {
+    println!("{} config:", harness.id());
+    for entry in entries {
+        let name = if entry.is_dir {
+            format!("{}/", entry.name)
+        } else {
+            entry.name.clone()
+        };
+        let note = if entry.excluded {
+            "  (excluded)".to_string()
+        } else if let Some(pattern) = &entry.suggested_exclude {
+            format!("  (looks regenerated, suggest excluding `{pattern}`)")
+        } else {
+            String::new()
+        };
+        println!("  {:>8}  {name}{note}", format_size(entry.size));
+    }
+}
+
+/// The proposed patterns to exclude: all of them with `yes`, a selection
+/// on a terminal, and none otherwise.
+fn choose_excludes(proposed: &[String], yes: bool) -> Result<Vec<String>> {
+    if proposed.is_empty() || yes {
+        return Ok(proposed.to_vec());
+    }
+    if !std::io::stdin().is_terminal() {
+        eprintln!("Not adding suggested excludes without a terminal; pass --yes to accept them");
+        return Ok(Vec::new());
+    }
+    let defaults = vec![true; proposed.len()];
+    let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Exclude from profiles (space to toggle)")
+        .items(proposed)
+        .defaults(&defaults)
+        .interact()
+        .map_err(|e| Error::Command(e.to_string()))?;
+    Ok(selected.into_iter().map(|i| proposed[i].clone()).collect())
+}
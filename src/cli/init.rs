@@ -1,9 +1,8 @@
 //! Init command implementation.
 
-use harness_locate::{Harness, HarnessKind};
-
 use crate::config::{BridleConfig, ProfileManager};
 use crate::error::Result;
+use crate::harness::HarnessRegistry;
 
 pub fn run_init() -> Result<()> {
     let config_dir = BridleConfig::config_dir()?;
@@ -23,9 +22,8 @@ pub fn run_init() -> Result<()> {
     config.save()?;
 
     let manager = ProfileManager::new(profiles_dir);
-    for kind in HarnessKind::ALL {
-        let harness = Harness::new(*kind);
-        let _ = manager.create_from_current_if_missing(&harness);
+    for entry in HarnessRegistry::load()?.iter() {
+        let _ = manager.create_from_current_if_missing(entry.harness());
     }
 
     println!("Initialized bridle at {}", config_dir.display());
@@ -0,0 +1,306 @@
+//! `bridle insights`: which installed skills and commands were actually used.
+//!
+//! Reads the session transcripts harnesses keep locally (Claude Code's
+//! `projects/` directory, OpenCode's `storage/`) and counts skill tool calls
+//! and slash-command invocations. Nothing leaves the machine, but because the
+//! transcripts are private the command only runs once `settings.insights` is
+//! enabled.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use harness_locate::HarnessKind;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cli::output::{ResolvedFormat, output_list};
+use crate::config::{BridleConfig, ProfileManager, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+
+#[derive(Debug, Serialize)]
+struct HarnessInsights {
+    harness: String,
+    profile: String,
+    logs_dir: PathBuf,
+    sessions: usize,
+    skills: Vec<Usage>,
+    commands: Vec<Usage>,
+}
+
+#[derive(Debug, Serialize)]
+struct Usage {
+    name: String,
+    invocations: u64,
+}
+
+/// Skill and command invocations found in session logs, by name.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Invocations {
+    skills: BTreeMap<String, u64>,
+    commands: BTreeMap<String, u64>,
+}
+
+pub fn run(harness: Option<&str>, days: u32, format: ResolvedFormat) -> Result<()> {
+    let config = BridleConfig::load()?;
+    if !config.settings().insights {
+        return Err(Error::Command(
+            "`bridle insights` reads your harness session logs; enable it with \
+             `bridle config set insights true`"
+                .to_string(),
+        ));
+    }
+    let registry = HarnessRegistry::load()?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    let since = SystemTime::now() - Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+
+    let entries: Vec<_> = match harness {
+        Some(name) => vec![registry.resolve(name)?],
+        None => registry
+            .iter()
+            .filter(|e| e.kind().is_some_and(keeps_logs))
+            .collect(),
+    };
+
+    let mut reports = Vec::new();
+    for entry in entries {
+        let harness = entry.harness();
+        let Some(logs_dir) = entry.kind().and_then(|kind| logs_dir(harness, kind)) else {
+            return Err(Error::Command(format!(
+                "{} does not keep session logs bridle can read",
+                harness.id()
+            )));
+        };
+        let Some(active) = config.active_profile_for(harness.id()) else {
+            continue;
+        };
+        let name = ProfileName::new(active)
+            .map_err(|reason| Error::invalid_profile_name(active, reason))?;
+        let info = manager.show_profile(harness, &name)?;
+
+        let mut found = Invocations::default();
+        let mut sessions = 0;
+        for file in log_files(&logs_dir, since) {
+            if let Ok(content) = std::fs::read_to_string(&file) {
+                sessions += 1;
+                found.scan(&content);
+            }
+        }
+        reports.push(HarnessInsights {
+            harness: harness.id().to_string(),
+            profile: active.to_string(),
+            logs_dir,
+            sessions,
+            skills: usage(&info.skills.items, &found.skills),
+            commands: usage(&info.commands.items, &found.commands),
+        });
+    }
+
+    output_list(&reports, format, |reports| {
+        if reports.is_empty() {
+            println!("No active profiles with readable session logs");
+        }
+        for report in reports {
+            print_report(report, days);
+        }
+    });
+    Ok(())
+}
+
+fn print_report(report: &HarnessInsights, days: u32) {
+    println!(
+        "{} (profile {}, {} session file(s) from the last {days} days)",
+        report.harness, report.profile, report.sessions
+    );
+    for (label, items) in [("Skills", &report.skills), ("Commands", &report.commands)] {
+        if items.is_empty() {
+            continue;
+        }
+        println!("  {label}:");
+        for item in items {
+            let note = if item.invocations == 0 {
+                "  (unused)"
+            } else {
+                ""
+            };
+            println!("    {:<30} {:>5}{note}", item.name, item.invocations);
+        }
+    }
+    let unused = report
+        .skills
+        .iter()
+        .chain(&report.commands)
+        .filter(|u| u.invocations == 0)
+        .count();
+    if unused > 0 {
+        println!(
+            "  {unused} unused; remove with `bridle uninstall {} {}`",
+            report.harness, report.profile
+        );
+    }
+    println!();
+}
+
+/// Installed `items` with their invocation counts, most used first. An
+/// invocation counts for an item of its full name, or of its name without a
+/// `plugin:` namespace.
+fn usage(items: &[String], found: &BTreeMap<String, u64>) -> Vec<Usage> {
+    let mut usage: Vec<Usage> = items
+        .iter()
+        .map(|name| {
+            let bare = name.trim_end_matches(".md");
+            Usage {
+                name: name.clone(),
+                invocations: found
+                    .iter()
+                    .filter(|(invoked, _)| invoked.as_str() == bare || bare_name(invoked) == bare)
+                    .map(|(_, count)| count)
+                    .sum(),
+            }
+        })
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.invocations));
+    usage
+}
+
+fn keeps_logs(kind: HarnessKind) -> bool {
+    matches!(kind, HarnessKind::ClaudeCode | HarnessKind::OpenCode)
+}
+
+/// Where `harness` keeps its session transcripts, if bridle knows how to read them.
+fn logs_dir(harness: &dyn HarnessConfig, kind: HarnessKind) -> Option<PathBuf> {
+    match kind {
+        HarnessKind::ClaudeCode => harness.config_dir().ok().map(|dir| dir.join("projects")),
+        HarnessKind::OpenCode => harness_locate::platform::data_dir()
+            .ok()
+            .map(|data| data.join("opencode").join("storage")),
+        _ => None,
+    }
+}
+
+/// `.json` and `.jsonl` files under `dir` modified since `since`.
+fn log_files(dir: &Path, since: SystemTime) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(path);
+            } else if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("json" | "jsonl")
+            ) && meta.modified().is_ok_and(|m| m >= since)
+            {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+impl Invocations {
+    /// Counts invocations in a JSON or JSON Lines document.
+    fn scan(&mut self, content: &str) {
+        match serde_json::from_str::<Value>(content) {
+            Ok(value) => self.visit(&value),
+            Err(_) => {
+                for line in content.lines() {
+                    if let Ok(value) = serde_json::from_str::<Value>(line) {
+                        self.visit(&value);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                let tool = map
+                    .get("name")
+                    .or_else(|| map.get("tool"))
+                    .and_then(Value::as_str);
+                if tool.is_some_and(|t| t.eq_ignore_ascii_case("skill")) {
+                    let input = map
+                        .get("input")
+                        .or_else(|| map.get("state").and_then(|s| s.get("input")));
+                    if let Some(skill) = input.and_then(|input| {
+                        ["skill", "name", "command"]
+                            .iter()
+                            .find_map(|k| input.get(k).and_then(Value::as_str))
+                    }) {
+                        *self.skills.entry(bare_name(skill)).or_default() += 1;
+                    }
+                }
+                map.values().for_each(|v| self.visit(v));
+            }
+            Value::Array(items) => items.iter().for_each(|v| self.visit(v)),
+            Value::String(text) => {
+                // Claude Code records slash commands as `<command-name>/foo</command-name>`.
+                let mut rest = text.as_str();
+                while let Some(start) = rest.find("<command-name>") {
+                    rest = &rest[start + "<command-name>".len()..];
+                    let Some(end) = rest.find("</command-name>") else {
+                        break;
+                    };
+                    // Kept whole: `git:commit` may be a command in `commands/git/`.
+                    let name = rest[..end].trim().trim_start_matches('/');
+                    if !name.is_empty() {
+                        *self.commands.entry(name.to_string()).or_default() += 1;
+                    }
+                    rest = &rest[end..];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Drops a `plugin:` namespace so invocations match installed names.
+fn bare_name(name: &str) -> String {
+    name.rsplit(':').next().unwrap_or(name).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_skill_calls_and_slash_commands() {
+        let transcript = [
+            r#"{"type":"user","message":{"content":"<command-name>/review</command-name>"}}"#,
+            r#"{"type":"user","message":{"content":"<command-name>/git:commit</command-name>"}}"#,
+            r#"{"type":"user","message":{"content":"<command-name>/tools:deploy</command-name>"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Skill","input":{"skill":"tools:pdf"}}]}}"#,
+            r#"{"type":"tool","tool":"skill","state":{"input":{"name":"pdf"}}}"#,
+            "not json",
+        ]
+        .join("\n");
+        let mut found = Invocations::default();
+        found.scan(&transcript);
+        assert_eq!(found.skills.get("pdf"), Some(&2));
+        assert_eq!(found.commands.get("review"), Some(&1));
+
+        let usage = usage(
+            &[
+                "review.md".to_string(),
+                "git:commit".to_string(),
+                "deploy.md".to_string(),
+                "lint.md".to_string(),
+            ],
+            &found.commands,
+        );
+        let counts: Vec<_> = usage.iter().map(|u| u.invocations).collect();
+        assert_eq!(counts, [1, 1, 1, 0]);
+    }
+}
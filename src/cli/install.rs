@@ -2,23 +2,42 @@
 
 use std::io::IsTerminal;
 
+use clap::ValueEnum;
 use color_eyre::eyre::{Result, eyre};
 use colored::Colorize;
 use dialoguer_multiselect::theme::ColorfulTheme;
-use dialoguer_multiselect::{GroupMultiSelect, ItemState};
+use dialoguer_multiselect::{Confirm, GroupMultiSelect, Input, ItemState, Password};
 
 use harness_locate::{Harness, HarnessKind, Scope, Severity, validate_agent_for_harness};
 
-use crate::config::{BridleConfig, ProfileManager};
-use crate::harness::HarnessConfig;
-use crate::install::discovery::{DiscoveryError, discover_skills};
-use crate::install::installer::{install_agent, install_command, install_skills};
+use crate::config::{
+    BridleConfig, Keyring, Notifier, ProfileManager, ProfileName, SECRET_SCHEME, SecretIndex,
+    StatePaths, write_atomic,
+};
+use crate::harness::registry::parse_kind;
+use crate::harness::{HarnessConfig, HarnessRegistry};
+use crate::install::convert::rename_skill;
+use crate::install::discovery::{DiscoveryError, discover_files, discover_skills};
+use crate::install::env::{EnvVar, required_env};
+use crate::install::filter::ComponentFilter;
+use crate::install::hooks::setup_script;
+use crate::install::installer::{
+    install_agent_with_source, install_command_with_source, install_skills, validate_subdir,
+};
 use crate::install::mcp_installer::{McpInstallOutcome, install_mcp};
+use crate::install::mirror;
+use crate::install::oci::{self, Artifact, ArtifactKind, OCI_SCHEME, OciReference};
+use crate::install::progress::{InstallEvent, InstallObserver, JsonLines};
+use crate::install::undo::InstallRecorder;
 use crate::install::{
-    AgentInfo, CommandInfo, DiscoveryResult, InstallOptions, InstallTarget, SkillInfo,
+    AgentInfo, CommandInfo, DiscoveryResult, InstallOptions, InstallTarget, SkillInfo, SkipReason,
+    SourceInfo,
 };
+use crate::tui::browser::{self, BrowseItem};
 use harness_locate::McpServer;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
 
 type TargetGroup = (
     String,
@@ -28,29 +47,33 @@ type TargetGroup = (
     Option<String>, // Harness-level warning (e.g., "HTTP not supported")
 );
 
+/// Components above which `bridle install` offers the tree browser instead
+/// of a flat list.
+const BROWSER_THRESHOLD: usize = 20;
+
 fn harness_supports_skills(harness_id: &str) -> bool {
-    parse_harness_kind(harness_id)
+    parse_kind(harness_id)
         .and_then(|kind| Harness::locate(kind).ok())
         .and_then(|h| h.skills(&Scope::Global).ok().flatten())
         .is_some()
 }
 
 fn harness_supports_agents(harness_id: &str) -> bool {
-    parse_harness_kind(harness_id)
+    parse_kind(harness_id)
         .and_then(|kind| Harness::locate(kind).ok())
         .and_then(|h| h.agents(&Scope::Global).ok().flatten())
         .is_some()
 }
 
 fn harness_supports_commands(harness_id: &str) -> bool {
-    parse_harness_kind(harness_id)
+    parse_kind(harness_id)
         .and_then(|kind| Harness::locate(kind).ok())
         .and_then(|h| h.commands(&Scope::Global).ok().flatten())
         .is_some()
 }
 
 fn harness_supports_mcp(harness_id: &str) -> bool {
-    parse_harness_kind(harness_id)
+    parse_kind(harness_id)
         .and_then(|kind| Harness::locate(kind).ok())
         .and_then(|h| h.mcp_config_path())
         .is_some()
@@ -66,6 +89,10 @@ fn count_incompatible_agents(agents: &[AgentInfo], kind: HarnessKind) -> usize {
         .count()
 }
 
+fn count_undeclared_skills(skills: &[SkillInfo], harness_id: &str) -> usize {
+    skills.iter().filter(|s| !s.supports(harness_id)).count()
+}
+
 fn count_incompatible_mcps(mcps: &HashMap<String, McpServer>, kind: HarnessKind) -> usize {
     mcps.values()
         .filter(|server| server.validate_capabilities(kind).is_err())
@@ -86,49 +113,167 @@ fn is_mcp_compatible(server: &McpServer, kind: HarnessKind) -> bool {
     server.validate_capabilities(kind).is_ok()
 }
 
-fn parse_harness_kind(id: &str) -> Option<HarnessKind> {
-    match id {
-        "claude-code" | "claude" | "cc" => Some(HarnessKind::ClaudeCode),
-        "opencode" | "oc" => Some(HarnessKind::OpenCode),
-        "goose" => Some(HarnessKind::Goose),
-        "amp-code" | "amp" | "ampcode" => Some(HarnessKind::AmpCode),
-        _ => None,
-    }
-}
-
 /// Selected components from the discovery result
-struct SelectedComponents {
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct SelectedComponents {
     skills: Vec<SkillInfo>,
     mcp_servers: HashMap<String, McpServer>,
     agents: Vec<AgentInfo>,
     commands: Vec<CommandInfo>,
+    /// Where the components came from, recorded in each profile's install manifest.
+    source: Option<SourceInfo>,
 }
 
 impl SelectedComponents {
+    /// Every component in `discovery`, as if all were selected.
+    pub(crate) fn all(discovery: &DiscoveryResult) -> Self {
+        Self {
+            skills: discovery.skills.clone(),
+            mcp_servers: discovery.mcp_servers.clone(),
+            agents: discovery.agents.clone(),
+            commands: discovery.commands.clone(),
+            source: Some(discovery.source.clone()),
+        }
+    }
+
+    /// Only `skills`, from `source`.
+    pub(crate) fn skills(skills: Vec<SkillInfo>, source: SourceInfo) -> Self {
+        Self {
+            skills,
+            mcp_servers: HashMap::new(),
+            agents: Vec::new(),
+            commands: Vec::new(),
+            source: Some(source),
+        }
+    }
+
+    /// Only the MCP servers in `servers`, declared rather than discovered.
+    pub(crate) fn mcp_servers(servers: HashMap<String, McpServer>) -> Self {
+        Self {
+            mcp_servers: servers,
+            ..Default::default()
+        }
+    }
+
+    /// Names of the selected skills that have a setup hook.
+    pub(crate) fn skills_with_hooks(&self) -> BTreeSet<String> {
+        skills_with_hooks(&self.skills)
+    }
+
+    /// Copies the files of the selected skills, agents, and commands to
+    /// `settings.mirror`, returning where they went, or `None` without a mirror.
+    pub(crate) fn mirror(&self) -> Result<Option<String>> {
+        let config = BridleConfig::load()?;
+        let (Some(location), Some(source)) = (&config.settings().mirror, &self.source) else {
+            return Ok(None);
+        };
+        let files = mirror::collect(&self.skills, &self.agents, &self.commands);
+        if files.is_empty() {
+            return Ok(None);
+        }
+        mirror::store(location, source, files)
+            .map(Some)
+            .map_err(|e| eyre!("Failed to mirror {}/{}: {e}", source.owner, source.repo))
+    }
+
     fn is_empty(&self) -> bool {
-        self.skills.is_empty()
-            && self.mcp_servers.is_empty()
-            && self.agents.is_empty()
-            && self.commands.is_empty()
+        self.len() == 0
+    }
+
+    fn len(&self) -> usize {
+        self.skills.len() + self.mcp_servers.len() + self.agents.len() + self.commands.len()
     }
 }
 
-pub fn run(source: &str, force: bool) -> Result<()> {
-    if !std::io::stdin().is_terminal() {
+/// How `bridle install` reports progress besides its messages on stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// One JSON [`InstallEvent`] per line on stdout.
+    JsonLines,
+}
+
+impl ProgressFormat {
+    fn observer(self) -> Arc<dyn InstallObserver> {
+        match self {
+            Self::JsonLines => Arc::new(JsonLines),
+        }
+    }
+}
+
+/// Installs components from `source`, with `set` giving `KEY=VAL` values
+/// for environment variables the selected skills declare. Setup hooks run
+/// after confirmation, or without asking when `allow_hooks` is set.
+/// `targets` names target groups or `<harness>/<profile>` entries to install
+/// to; without it the user picks, starting from the last targets used for
+/// the source. `filter` narrows the discovered components; without a
+/// terminal, everything it leaves is installed into `targets`. `rename`
+/// installs the one selected skill under another name, and `into` puts
+/// skills in a directory below the skills directory. With `strict_compat`,
+/// skills are left out of targets whose harness they do not list.
+/// `progress` adds a machine-readable event stream.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    source: &str,
+    force: bool,
+    set: &[String],
+    allow_hooks: bool,
+    targets: &[String],
+    filter: &ComponentFilter,
+    rename: Option<&str>,
+    into: Option<&str>,
+    strict_compat: bool,
+    progress: Option<ProgressFormat>,
+) -> Result<()> {
+    let observer = progress.map(ProgressFormat::observer);
+    let emit = |event| {
+        if let Some(observer) = &observer {
+            observer.on_event(&event);
+        }
+    };
+    let mut env = parse_env_values(set)?;
+    if let Some(subdir) = into {
+        validate_subdir(subdir).map_err(|e| eyre!("invalid --into '{subdir}': {e}"))?;
+    }
+    emit(InstallEvent::DiscoveryStarted {
+        source: source.to_string(),
+    });
+    let pulled = if source.starts_with(OCI_SCHEME) {
+        eprintln!("Pulling {}...", source);
+        Some(pull(source)?)
+    } else {
+        None
+    };
+    if let Some((_, artifact)) = pulled
+        .as_ref()
+        .filter(|(_, a)| a.kind == ArtifactKind::Profile)
+    {
+        return import_profile(artifact, force);
+    }
+
+    let interactive = std::io::stdin().is_terminal();
+    if !interactive && targets.is_empty() {
         return Err(eyre!(
-            "Interactive mode requires a terminal. Use --help for non-interactive options."
+            "Installing without a terminal needs --targets; choose components with --filter and --exclude"
         ));
     }
 
     let url = normalize_source(source);
 
-    eprintln!("Discovering components from {}...", url);
+    let mut discovery = match &pulled {
+        Some((reference, artifact)) => discover_artifact(reference, artifact)?,
+        None => {
+            eprintln!("Discovering components from {}...", url);
+            discover(source)?
+        }
+    };
 
-    let discovery = discover_skills(&url).map_err(|e| match e {
-        DiscoveryError::InvalidUrl(msg) => eyre!("Invalid URL: {}", msg),
-        DiscoveryError::FetchError(e) => eyre!("Failed to fetch repository: {}", e),
-        DiscoveryError::NoSkillsFound => eyre!("No installable components found in repository"),
-    })?;
+    for skill in &discovery.skills {
+        emit(InstallEvent::SkillFetched {
+            name: skill.name.clone(),
+            path: skill.path.clone(),
+        });
+    }
+    filter.apply(&mut discovery);
 
     // Build summary of what was found
     let mut found_parts = Vec::new();
@@ -157,127 +302,568 @@ pub fn run(source: &str, force: bool) -> Result<()> {
         discovery.source.repo
     );
 
-    let selected = select_components(&discovery)?;
+    let mut selected = if interactive {
+        select_components(&discovery)?
+    } else {
+        SelectedComponents::all(&discovery)
+    };
 
     if selected.is_empty() {
         eprintln!("No components selected");
         return Ok(());
     }
 
-    let targets = select_targets(&selected)?;
+    let config = BridleConfig::load()?;
+    let source = format!("{}/{}", discovery.source.owner, discovery.source.repo);
+    let targets = if targets.is_empty() {
+        select_targets(&selected, &config.last_install_targets(&source))?
+    } else {
+        resolve_targets(&config, targets)?
+    };
 
     if targets.is_empty() {
         eprintln!("No targets selected");
         return Ok(());
     }
+    config.remember_install_targets(&source, targets.iter().map(target_label).collect())?;
 
-    let options = InstallOptions { force };
+    if let Some(location) = selected.mirror()? {
+        eprintln!("Mirrored to {location}");
+    }
+    if let Some(name) = rename {
+        let [skill] = selected.skills.as_mut_slice() else {
+            return Err(eyre!(
+                "--as needs exactly one selected skill, not {}",
+                selected.skills.len()
+            ));
+        };
+        *skill = rename_skill(skill, name);
+    }
+    if interactive {
+        collect_env(&selected.skills, &mut env)?;
+    }
+    store_secrets(&selected.skills, &mut env, store_in_keyring)?;
+    let run_setup = if allow_hooks {
+        skills_with_hooks(&selected.skills)
+    } else if interactive {
+        confirm_hooks(&selected.skills)?
+    } else {
+        BTreeSet::new()
+    };
+    let paths = config.state_paths()?;
+    let recorder = Arc::new(InstallRecorder::begin(
+        &paths.installs_dir(),
+        &source,
+        targets.iter().map(target_label).collect(),
+    )?);
+    let options = InstallOptions {
+        force,
+        env,
+        run_setup,
+        subdir: into.map(str::to_string),
+        strict_compat,
+        observer,
+        recorder: Some(Arc::clone(&recorder)),
+        ..Default::default()
+    };
+    let notifier = Notifier::new(&config);
 
+    let mut pending = Vec::new();
     for target in &targets {
         eprintln!("\nInstalling to {}/{}...", target.harness, target.profile);
 
-        // Install skills
-        if !selected.skills.is_empty() {
-            let report = install_skills(&selected.skills, target, &options);
+        let (report, failed) = install_tracking_failures(&selected, target, &options);
+        for line in &report {
+            eprintln!("  {line}");
+        }
+        notifier.install(&target.harness, target.profile.as_str(), &source, &report);
+        if !failed.is_empty() {
+            pending.push(PendingInstall {
+                source: source.clone(),
+                target: target_label(target),
+                env: pending_env(&failed.skills, &options.env),
+                components: failed,
+                run_setup: options.run_setup.clone(),
+                subdir: options.subdir.clone(),
+            });
+        }
+    }
+
+    drop(options);
+    let recorded = finish_record(recorder)?;
+
+    let mut all_pending = load_pending(&paths)?;
+    all_pending.retain(|p| p.source != source);
+    let incomplete = pending.len();
+    all_pending.extend(pending);
+    save_pending(&paths, &all_pending)?;
+
+    if incomplete > 0 {
+        eprintln!(
+            "\n{incomplete} target(s) incomplete; run `bridle install --resume` to retry the failed components"
+        );
+    } else {
+        eprintln!("\nDone!");
+    }
+    if recorded {
+        eprintln!("Undo with `bridle undo --last-install`");
+    }
+    Ok(())
+}
+
+/// Settles the undo record of an install once every target is done, and
+/// tells whether one was kept.
+fn finish_record(recorder: Arc<InstallRecorder>) -> Result<bool> {
+    let Ok(recorder) = Arc::try_unwrap(recorder) else {
+        return Ok(false);
+    };
+    Ok(recorder.finish()?.is_some())
+}
+
+/// Components that failed to install into one target, with what they need
+/// to be installed again. Kept in [`StatePaths::pending_installs_file`] for
+/// `bridle install --resume`, so a retry needs no download.
+#[derive(Serialize, Deserialize)]
+struct PendingInstall {
+    /// `owner/repo` the components came from.
+    source: String,
+    /// `<harness>/<profile>`.
+    target: String,
+    components: SelectedComponents,
+    /// Only what [`pending_env`] keeps: secrets appear as keyring references.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    run_setup: BTreeSet<String>,
+    #[serde(default)]
+    subdir: Option<String>,
+}
+
+/// The part of `env` worth keeping for a retry of `skills`: the values of
+/// the plain variables they declare, and keyring references. Anything else
+/// is left out, so no secret value reaches the state directory.
+fn pending_env(skills: &[SkillInfo], env: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    declared_env(skills)
+        .into_iter()
+        .filter_map(|var| {
+            let value = env.get(&var.name)?;
+            (!var.secret || value.starts_with(SECRET_SCHEME)).then(|| (var.name, value.clone()))
+        })
+        .collect()
+}
+
+fn load_pending(paths: &StatePaths) -> Result<Vec<PendingInstall>> {
+    let path = paths.pending_installs_file();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content).map_err(|e| eyre!("{}: {e}", path.display()))
+}
+
+fn save_pending(paths: &StatePaths, pending: &[PendingInstall]) -> Result<()> {
+    let path = paths.pending_installs_file();
+    if pending.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    write_atomic(&path, serde_json::to_string_pretty(pending)?)?;
+    Ok(())
+}
+
+/// Retries the components that failed in earlier installs, from the copies
+/// kept when they failed. Retried components overwrite what a failed attempt
+/// left behind. `progress` is as for [`run`].
+pub fn resume(progress: Option<ProgressFormat>) -> Result<()> {
+    let config = BridleConfig::load()?;
+    let paths = config.state_paths()?;
+    let pending = load_pending(&paths)?;
+    if pending.is_empty() {
+        return Err(eyre!("No incomplete install to resume"));
+    }
+    let notifier = Notifier::new(&config);
+    let mut sources: Vec<&str> = pending.iter().map(|p| p.source.as_str()).collect();
+    sources.dedup();
+    let recorder = Arc::new(InstallRecorder::begin(
+        &paths.installs_dir(),
+        &sources.join(", "),
+        pending.iter().map(|p| p.target.clone()).collect(),
+    )?);
+
+    let mut still_pending = Vec::new();
+    for entry in pending {
+        let target = parse_target(&entry.target)?;
+        eprintln!("\nRetrying {} from {}...", entry.target, entry.source);
+        let mut env = entry.env.clone();
+        store_secrets(&entry.components.skills, &mut env, store_in_keyring)?;
+        let options = InstallOptions {
+            force: true,
+            env,
+            run_setup: entry.run_setup.clone(),
+            subdir: entry.subdir.clone(),
+            observer: progress.map(ProgressFormat::observer),
+            recorder: Some(Arc::clone(&recorder)),
+            ..Default::default()
+        };
+        let (report, failed) = install_tracking_failures(&entry.components, &target, &options);
+        for line in &report {
+            eprintln!("  {line}");
+        }
+        notifier.install(
+            &target.harness,
+            target.profile.as_str(),
+            &entry.source,
+            &report,
+        );
+        if !failed.is_empty() {
+            still_pending.push(PendingInstall {
+                env: pending_env(&failed.skills, &options.env),
+                components: failed,
+                ..entry
+            });
+        }
+    }
+    save_pending(&paths, &still_pending)?;
+    let recorded = finish_record(recorder)?;
+
+    if still_pending.is_empty() {
+        eprintln!("\nDone!");
+    } else {
+        eprintln!(
+            "\n{} target(s) still incomplete; run `bridle install --resume` again",
+            still_pending.len()
+        );
+    }
+    if recorded {
+        eprintln!("Undo with `bridle undo --last-install`");
+    }
+    Ok(())
+}
+
+/// Parses `--set KEY=VAL` arguments.
+fn parse_env_values(set: &[String]) -> Result<BTreeMap<String, String>> {
+    set.iter()
+        .map(|arg| {
+            arg.split_once('=')
+                .filter(|(key, _)| !key.is_empty())
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| eyre!("invalid --set '{arg}': expected KEY=VAL"))
+        })
+        .collect()
+}
+
+/// The environment variables `skills` declare, each once.
+fn declared_env(skills: &[SkillInfo]) -> Vec<EnvVar> {
+    let mut declared: Vec<EnvVar> = Vec::new();
+    for var in skills.iter().flat_map(|s| required_env(&s.content)) {
+        if !declared.iter().any(|d| d.name == var.name) {
+            declared.push(var);
+        }
+    }
+    declared
+}
+
+/// Prompts for the environment variables `skills` declare that `env` does
+/// not already hold.
+fn collect_env(skills: &[SkillInfo], env: &mut BTreeMap<String, String>) -> Result<()> {
+    for var in declared_env(skills) {
+        if env.contains_key(&var.name) {
+            continue;
+        }
+        let prompt = match &var.description {
+            Some(description) => format!("{} ({description}, empty to skip)", var.name),
+            None => format!("{} (empty to skip)", var.name),
+        };
+        let value = if var.secret {
+            Password::new()
+                .with_prompt(prompt)
+                .allow_empty_password(true)
+                .interact()?
+        } else {
+            Input::<String>::new()
+                .with_prompt(prompt)
+                .allow_empty(true)
+                .interact_text()?
+        };
+        if !value.is_empty() {
+            env.insert(var.name, value);
+        }
+    }
+    Ok(())
+}
+
+/// Hands the value of every secret variable `skills` declare over to
+/// `store`, leaving a `bridle-secret://` reference in `env`, so no profile
+/// file or state file holds it. Prompted and `--set` values alike.
+pub(crate) fn store_secrets(
+    skills: &[SkillInfo],
+    env: &mut BTreeMap<String, String>,
+    store: impl Fn(&str, &str) -> Result<()>,
+) -> Result<()> {
+    for var in declared_env(skills).into_iter().filter(|var| var.secret) {
+        if let Some(value) = env.get_mut(&var.name)
+            && !value.starts_with(SECRET_SCHEME)
+        {
+            store(&var.name, value)?;
+            *value = format!("{SECRET_SCHEME}{}", var.name);
+        }
+    }
+    Ok(())
+}
+
+/// Stores secret `name` in the keyring and records it in the secret index.
+pub(crate) fn store_in_keyring(name: &str, value: &str) -> Result<()> {
+    Keyring.set(name, value)?;
+    let paths = StatePaths::resolve()?;
+    let mut index = SecretIndex::load(&paths)?;
+    index.insert(name);
+    index.save(&paths)?;
+    Ok(())
+}
+
+/// Names of the `skills` that have a setup hook.
+pub(crate) fn skills_with_hooks(skills: &[SkillInfo]) -> BTreeSet<String> {
+    skills
+        .iter()
+        .filter(|skill| setup_script(skill).is_some())
+        .map(|skill| skill.name.clone())
+        .collect()
+}
+
+/// Names of the `skills` whose setup hook the user approves after seeing the script.
+fn confirm_hooks(skills: &[SkillInfo]) -> Result<BTreeSet<String>> {
+    let mut approved = BTreeSet::new();
+    for skill in skills {
+        let Some(script) = setup_script(skill) else {
+            continue;
+        };
+        eprintln!("\nSkill '{}' has a setup hook:", skill.name);
+        for line in script.lines() {
+            eprintln!("  | {line}");
+        }
+        let run = Confirm::new()
+            .with_prompt("Run it after installing?")
+            .default(false)
+            .interact()?;
+        if run {
+            approved.insert(skill.name.clone());
+        }
+    }
+    Ok(approved)
+}
+
+/// Fetches `source` (a URL, `owner/repo`, or `oci://` reference) and lists
+/// its installable components.
+pub(crate) fn discover(source: &str) -> Result<DiscoveryResult> {
+    if source.starts_with(OCI_SCHEME) {
+        let (reference, artifact) = pull(source)?;
+        if artifact.kind == ArtifactKind::Profile {
+            return Err(eyre!("{reference} holds a profile, not skills"));
+        }
+        return discover_artifact(&reference, &artifact);
+    }
+    discover_skills(&normalize_source(source)).map_err(discovery_error)
+}
+
+fn discovery_error(e: DiscoveryError) -> color_eyre::Report {
+    match e {
+        DiscoveryError::InvalidUrl(msg) => eyre!("Invalid URL: {}", msg),
+        DiscoveryError::FetchError(e) => eyre!("Failed to fetch repository: {}", e),
+        DiscoveryError::Download(e) => eyre!("Failed to download repository: {}", e),
+        DiscoveryError::NoSkillsFound => eyre!("No installable components found in repository"),
+    }
+}
+
+/// Pulls the bundle at an `oci://` source.
+fn pull(source: &str) -> Result<(OciReference, Artifact)> {
+    let reference = OciReference::parse(source)?;
+    let artifact = oci::pull(&reference)?;
+    Ok((reference, artifact))
+}
+
+fn discover_artifact(reference: &OciReference, artifact: &Artifact) -> Result<DiscoveryResult> {
+    let source = SourceInfo {
+        owner: reference.registry.clone(),
+        repo: reference.repository.clone(),
+        git_ref: Some(reference.reference.clone()),
+    };
+    discover_files(&artifact.files, source).map_err(discovery_error)
+}
+
+/// Creates the profiles in a pulled profile artifact.
+fn import_profile(artifact: &Artifact, force: bool) -> Result<()> {
+    let staging = oci::scratch_dir("install")?;
+    artifact.write_to(staging.path())?;
+    Ok(crate::cli::dotfiles::import(staging.path(), None, force)?)
+}
+
+/// Installs `selected` into `target`, returning one report line per component.
+pub(crate) fn install_to_target(
+    selected: &SelectedComponents,
+    target: &InstallTarget,
+    options: &InstallOptions,
+) -> Vec<String> {
+    install_tracking_failures(selected, target, options).0
+}
+
+/// Installs `selected` into `target`, returning the report lines and the
+/// components that failed.
+fn install_tracking_failures(
+    selected: &SelectedComponents,
+    target: &InstallTarget,
+    options: &InstallOptions,
+) -> (Vec<String>, SelectedComponents) {
+    let mut lines = Vec::new();
+    let mut failed = SelectedComponents {
+        source: selected.source.clone(),
+        ..Default::default()
+    };
 
-            for success in &report.installed {
-                eprintln!("  + Installed skill: {}", success.skill);
+    // Install skills
+    if !selected.skills.is_empty() {
+        let report = install_skills(&selected.skills, target, options, selected.source.as_ref());
+
+        for success in &report.installed {
+            lines.push(format!("+ Installed skill: {}", success.skill));
+            if !success.env.is_empty() {
+                lines.push(format!("  = Set {}", success.env.join(", ")));
             }
-            for skip in &report.skipped {
-                eprintln!("  = Skipped skill: {} (already exists)", skip.skill);
+            if let Some(setup) = &success.setup {
+                lines.push(match (setup.success, setup.status) {
+                    (true, _) => "  = Setup hook ran".to_string(),
+                    (false, Some(code)) => format!("  ! Setup hook failed (exit {code})"),
+                    (false, None) => "  ! Setup hook failed".to_string(),
+                });
+                lines.extend(setup.output.lines().map(|line| format!("    | {line}")));
             }
-            for error in &report.errors {
-                eprintln!(
-                    "  ! Error installing skill {}: {}",
-                    error.skill, error.error
-                );
+            for note in &success.notes {
+                lines.push(format!("  ~ {note}"));
             }
         }
-
-        // Install agents
-        if !selected.agents.is_empty() && !harness_supports_agents(&target.harness) {
-            eprintln!(
-                "  ~ Skipping {} agent(s) - not supported by {}",
-                selected.agents.len(),
-                target.harness
+        for skip in &report.skipped {
+            lines.push(match skip.reason {
+                SkipReason::AlreadyExists => {
+                    format!("= Skipped skill: {} (already exists)", skip.skill)
+                }
+                SkipReason::Incompatible => format!(
+                    "~ Skipping skill: {} (not declared for {})",
+                    skip.skill, target.harness
+                ),
+            });
+        }
+        for error in &report.errors {
+            lines.push(format!(
+                "! Error installing skill {}: {}",
+                error.skill, error.error
+            ));
+            failed.skills.extend(
+                selected
+                    .skills
+                    .iter()
+                    .filter(|s| s.name == error.skill)
+                    .cloned(),
             );
-        } else {
-            for agent in &selected.agents {
-                match install_agent(agent, target, &options) {
-                    Ok(crate::install::installer::InstallOutcome::Installed(success)) => {
-                        eprintln!("  + Installed agent: {}", success.skill);
-                    }
-                    Ok(crate::install::installer::InstallOutcome::Skipped(skip)) => {
-                        eprintln!("  = Skipped agent: {} (already exists)", skip.skill);
-                    }
-                    Err(e) => {
-                        eprintln!("  ! Error installing agent {}: {}", agent.name, e);
-                    }
+        }
+    }
+
+    // Install agents
+    if !selected.agents.is_empty() && !harness_supports_agents(&target.harness) {
+        lines.push(format!(
+            "~ Skipping {} agent(s) - not supported by {}",
+            selected.agents.len(),
+            target.harness
+        ));
+    } else {
+        for agent in &selected.agents {
+            match install_agent_with_source(agent, target, options, selected.source.as_ref()) {
+                Ok(crate::install::installer::InstallOutcome::Installed(success)) => {
+                    lines.push(format!("+ Installed agent: {}", success.skill));
+                }
+                Ok(crate::install::installer::InstallOutcome::Skipped(skip)) => {
+                    lines.push(format!("= Skipped agent: {} (already exists)", skip.skill));
+                }
+                Err(e) => {
+                    lines.push(format!("! Error installing agent {}: {}", agent.name, e));
+                    failed.agents.push(agent.clone());
                 }
             }
         }
+    }
 
-        // Install commands
-        if !selected.commands.is_empty() && !harness_supports_commands(&target.harness) {
-            eprintln!(
-                "  ~ Skipping {} command(s) - not supported by {}",
-                selected.commands.len(),
-                target.harness
-            );
-        } else {
-            for cmd in &selected.commands {
-                match install_command(cmd, target, &options) {
-                    Ok(crate::install::installer::InstallOutcome::Installed(success)) => {
-                        eprintln!("  + Installed command: {}", success.skill);
-                    }
-                    Ok(crate::install::installer::InstallOutcome::Skipped(skip)) => {
-                        eprintln!("  = Skipped command: {} (already exists)", skip.skill);
-                    }
-                    Err(e) => {
-                        eprintln!("  ! Error installing command {}: {}", cmd.name, e);
-                    }
+    // Install commands
+    if !selected.commands.is_empty() && !harness_supports_commands(&target.harness) {
+        lines.push(format!(
+            "~ Skipping {} command(s) - not supported by {}",
+            selected.commands.len(),
+            target.harness
+        ));
+    } else {
+        for cmd in &selected.commands {
+            match install_command_with_source(cmd, target, options, selected.source.as_ref()) {
+                Ok(crate::install::installer::InstallOutcome::Installed(success)) => {
+                    lines.push(format!("+ Installed command: {}", success.skill));
+                }
+                Ok(crate::install::installer::InstallOutcome::Skipped(skip)) => {
+                    lines.push(format!(
+                        "= Skipped command: {} (already exists)",
+                        skip.skill
+                    ));
+                }
+                Err(e) => {
+                    lines.push(format!("! Error installing command {}: {}", cmd.name, e));
+                    failed.commands.push(cmd.clone());
                 }
             }
         }
+    }
 
-        // Install MCP servers
-        if !selected.mcp_servers.is_empty() && harness_supports_mcp(&target.harness) {
-            let harness_kind = parse_harness_kind(&target.harness);
-            for (name, server) in &selected.mcp_servers {
-                // Check transport compatibility before attempting installation
-                if let Some(kind) = harness_kind
-                    && !is_mcp_compatible(server, kind)
-                {
-                    let transport = match server {
-                        McpServer::Stdio(_) => "stdio",
-                        McpServer::Sse(_) => "SSE",
-                        McpServer::Http(_) => "HTTP",
-                    };
-                    eprintln!(
-                        "  ~ Skipping MCP server: {} ({} transport not supported by {})",
-                        name, transport, target.harness
-                    );
-                    continue;
+    // Install MCP servers
+    if !selected.mcp_servers.is_empty() && harness_supports_mcp(&target.harness) {
+        let harness_kind = parse_kind(&target.harness);
+        for (name, server) in &selected.mcp_servers {
+            // Check transport compatibility before attempting installation
+            if let Some(kind) = harness_kind
+                && !is_mcp_compatible(server, kind)
+            {
+                let transport = match server {
+                    McpServer::Stdio(_) => "stdio",
+                    McpServer::Sse(_) => "SSE",
+                    McpServer::Http(_) => "HTTP",
+                };
+                lines.push(format!(
+                    "~ Skipping MCP server: {} ({} transport not supported by {})",
+                    name, transport, target.harness
+                ));
+                continue;
+            }
+            match install_mcp(name, server, target, options) {
+                Ok(McpInstallOutcome::Installed(success)) => {
+                    lines.push(format!("+ Installed MCP server: {}", success.name));
                 }
-                match install_mcp(name, server, target, &options) {
-                    Ok(McpInstallOutcome::Installed(success)) => {
-                        eprintln!("  + Installed MCP server: {}", success.name);
-                    }
-                    Ok(McpInstallOutcome::Skipped(skip)) => {
-                        eprintln!("  = Skipped MCP server: {} ({:?})", skip.name, skip.reason);
-                    }
-                    Err(e) => {
-                        eprintln!("  ! Error installing MCP server {}: {}", name, e);
-                    }
+                Ok(McpInstallOutcome::Skipped(skip)) => {
+                    lines.push(format!(
+                        "= Skipped MCP server: {} ({:?})",
+                        skip.name, skip.reason
+                    ));
+                }
+                Err(e) => {
+                    lines.push(format!("! Error installing MCP server {}: {}", name, e));
+                    failed.mcp_servers.insert(name.clone(), server.clone());
                 }
             }
-        } else if !selected.mcp_servers.is_empty() {
-            eprintln!("  ~ Skipping MCP servers (harness does not support MCP)");
         }
+    } else if !selected.mcp_servers.is_empty() {
+        lines.push("~ Skipping MCP servers (harness does not support MCP)".to_string());
     }
-
-    eprintln!("\nDone!");
-    Ok(())
+    options.emit(InstallEvent::TargetDone {
+        harness: target.harness.clone(),
+        profile: target.profile.as_str().to_string(),
+        installed: lines.iter().filter(|l| l.starts_with("+ ")).count(),
+        failed: failed.len(),
+    });
+    (lines, failed)
 }
 
 /// Select components to install using grouped multi-select UI
@@ -286,7 +872,7 @@ fn select_components(discovery: &DiscoveryResult) -> Result<SelectedComponents>
     let mut groups: Vec<(&str, Vec<String>, Vec<usize>)> = Vec::new();
 
     if !discovery.skills.is_empty() {
-        let names: Vec<String> = discovery.skills.iter().map(|s| s.name.clone()).collect();
+        let names: Vec<String> = discovery.skills.iter().map(skill_label).collect();
         let indices: Vec<usize> = (0..discovery.skills.len()).collect();
         groups.push(("Skills", names, indices));
     }
@@ -315,9 +901,15 @@ fn select_components(discovery: &DiscoveryResult) -> Result<SelectedComponents>
             mcp_servers: HashMap::new(),
             agents: Vec::new(),
             commands: Vec::new(),
+            source: None,
         });
     }
 
+    let total: usize = groups.iter().map(|(_, names, _)| names.len()).sum();
+    if total > BROWSER_THRESHOLD {
+        return browse_components(discovery);
+    }
+
     // All items selected by default
     let defaults: Vec<Vec<bool>> = groups
         .iter()
@@ -341,6 +933,7 @@ fn select_components(discovery: &DiscoveryResult) -> Result<SelectedComponents>
             mcp_servers: HashMap::new(),
             agents: Vec::new(),
             commands: Vec::new(),
+            source: None,
         });
     };
 
@@ -350,6 +943,7 @@ fn select_components(discovery: &DiscoveryResult) -> Result<SelectedComponents>
         mcp_servers: HashMap::new(),
         agents: Vec::new(),
         commands: Vec::new(),
+        source: Some(discovery.source.clone()),
     };
 
     for (group_idx, selected_indices) in selections.iter().enumerate() {
@@ -384,6 +978,88 @@ fn select_components(discovery: &DiscoveryResult) -> Result<SelectedComponents>
     Ok(selected)
 }
 
+/// `skill`'s name, followed by the harnesses it declares support for.
+fn skill_label(skill: &SkillInfo) -> String {
+    if skill.harnesses.is_empty() {
+        skill.name.clone()
+    } else {
+        format!("{} ({})", skill.name, skill.harnesses.join(", "))
+    }
+}
+
+/// Browses the components of `discovery` as a tree grouped by directory,
+/// for repositories too large for a flat list.
+fn browse_components(discovery: &DiscoveryResult) -> Result<SelectedComponents> {
+    let mcp_entries: Vec<_> = discovery.mcp_servers.iter().collect();
+    let mut items = Vec::new();
+    for skill in &discovery.skills {
+        items.push(BrowseItem {
+            kind: "Skills".to_string(),
+            name: skill_label(skill),
+            path: skill.path.clone(),
+            description: skill.description.clone(),
+            preview: skill.content.clone(),
+        });
+    }
+    for (name, server) in &mcp_entries {
+        items.push(BrowseItem {
+            kind: "MCP Servers".to_string(),
+            name: (*name).clone(),
+            path: String::new(),
+            description: None,
+            preview: serde_json::to_string_pretty(server).unwrap_or_default(),
+        });
+    }
+    for (kind, name, path, description, content) in discovery
+        .agents
+        .iter()
+        .map(|a| ("Agents", &a.name, &a.path, &a.description, &a.content))
+        .chain(
+            discovery
+                .commands
+                .iter()
+                .map(|c| ("Commands", &c.name, &c.path, &c.description, &c.content)),
+        )
+    {
+        items.push(BrowseItem {
+            kind: kind.to_string(),
+            name: name.clone(),
+            path: path.clone(),
+            description: description.clone(),
+            preview: content.clone(),
+        });
+    }
+
+    let prompt = format!(
+        " Select components from {}/{} ",
+        discovery.source.owner, discovery.source.repo
+    );
+    let mut selected = SelectedComponents::default();
+    let Some(chosen) = browser::browse(&prompt, items)? else {
+        return Ok(selected);
+    };
+    selected.source = Some(discovery.source.clone());
+
+    let skills = discovery.skills.len();
+    let mcps = skills + mcp_entries.len();
+    let agents = mcps + discovery.agents.len();
+    for idx in chosen {
+        if idx < skills {
+            selected.skills.push(discovery.skills[idx].clone());
+        } else if idx < mcps {
+            let (name, server) = mcp_entries[idx - skills];
+            selected.mcp_servers.insert(name.clone(), server.clone());
+        } else if idx < agents {
+            selected.agents.push(discovery.agents[idx - mcps].clone());
+        } else {
+            selected
+                .commands
+                .push(discovery.commands[idx - agents].clone());
+        }
+    }
+    Ok(selected)
+}
+
 fn normalize_source(source: &str) -> String {
     if source.starts_with("http://") || source.starts_with("https://") {
         source.to_string()
@@ -394,22 +1070,89 @@ fn normalize_source(source: &str) -> String {
     }
 }
 
-fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
+/// Parses a `<harness>/<profile>` target.
+pub(crate) fn parse_target(entry: &str) -> Result<InstallTarget> {
+    let (harness, profile) = entry
+        .split_once('/')
+        .ok_or_else(|| eyre!("invalid profile '{entry}': expected <harness>/<profile>"))?;
+    let profile =
+        ProfileName::new(profile).map_err(|reason| eyre!("invalid profile '{entry}': {reason}"))?;
+    Ok(InstallTarget {
+        harness: harness.to_string(),
+        profile,
+    })
+}
+
+fn target_label(target: &InstallTarget) -> String {
+    format!("{}/{}", target.harness, target.profile)
+}
+
+/// The targets `names` stand for: each is a `[target-groups]` entry or a
+/// `<harness>/<profile>`. Every profile must exist.
+fn resolve_targets(config: &BridleConfig, names: &[String]) -> Result<Vec<InstallTarget>> {
+    let mut entries: Vec<&str> = Vec::new();
+    for name in names {
+        match config.target_groups.get(name) {
+            Some(group) => entries.extend(group.iter().map(String::as_str)),
+            None if name.contains('/') => entries.push(name),
+            None => {
+                let known: Vec<&str> = config.target_groups.keys().map(String::as_str).collect();
+                return Err(eyre!(
+                    "unknown target group '{name}' (defined: {})",
+                    if known.is_empty() {
+                        "none".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                ));
+            }
+        }
+    }
+
+    let registry = HarnessRegistry::load()?;
+    let manager = ProfileManager::new(BridleConfig::profiles_dir()?);
+    let mut targets: Vec<InstallTarget> = Vec::new();
+    for entry in entries {
+        let mut target = parse_target(entry)?;
+        let harness = registry.resolve(&target.harness)?;
+        // Component installers are format-specific, so only built-in harnesses are targets.
+        if harness.kind().is_none() {
+            return Err(eyre!(
+                "cannot install into custom harness '{}'",
+                harness.id()
+            ));
+        }
+        if !manager.profile_exists(harness.harness(), &target.profile) {
+            return Err(eyre!("profile '{entry}' does not exist"));
+        }
+        target.harness = harness.id().to_string();
+        if !targets
+            .iter()
+            .any(|t| target_label(t) == target_label(&target))
+        {
+            targets.push(target);
+        }
+    }
+    Ok(targets)
+}
+
+/// Asks which profiles to install `selected` into, preselecting `remembered`
+/// (`<harness>/<profile>` entries), or the active profiles if it is empty.
+fn select_targets(
+    selected: &SelectedComponents,
+    remembered: &[String],
+) -> Result<Vec<InstallTarget>> {
     let config = BridleConfig::load()?;
     let profiles_dir = BridleConfig::profiles_dir()?;
     let manager = ProfileManager::new(profiles_dir);
 
-    let harness_kinds = [
-        HarnessKind::OpenCode,
-        HarnessKind::ClaudeCode,
-        HarnessKind::Goose,
-        HarnessKind::AmpCode,
-    ];
+    let registry = HarnessRegistry::load()?;
 
     let mut groups: Vec<TargetGroup> = Vec::new();
 
-    for kind in &harness_kinds {
-        let Ok(harness) = Harness::locate(*kind) else {
+    // Component installers are format-specific, so only built-in harnesses are targets.
+    for kind in registry.iter().filter_map(|entry| entry.kind()) {
+        let Ok(harness) = Harness::locate(kind) else {
             continue;
         };
         let harness_id = harness.id();
@@ -429,13 +1172,12 @@ fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
         let can_install_skills = supports_skills && !selected.skills.is_empty();
         let can_install_agents = supports_agents && !selected.agents.is_empty();
         let can_install_commands = supports_commands && !selected.commands.is_empty();
-        let incompatible_mcp_count = count_incompatible_mcps(&selected.mcp_servers, *kind);
+        let incompatible_mcp_count = count_incompatible_mcps(&selected.mcp_servers, kind);
         let compatible_mcp_count = selected.mcp_servers.len() - incompatible_mcp_count;
         let can_install_mcp = compatible_mcp_count > 0;
 
         // Claude Code MCP support is in development (no global MCP config support)
-        let claude_mcp_in_dev =
-            *kind == HarnessKind::ClaudeCode && !selected.mcp_servers.is_empty();
+        let claude_mcp_in_dev = kind == HarnessKind::ClaudeCode && !selected.mcp_servers.is_empty();
 
         let can_install_anything = can_install_skills
             || can_install_agents
@@ -451,7 +1193,12 @@ fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
         }
 
         let incompatible_agent_count = if supports_agents && !selected.agents.is_empty() {
-            count_incompatible_agents(&selected.agents, *kind)
+            count_incompatible_agents(&selected.agents, kind)
+        } else {
+            0
+        };
+        let undeclared_skill_count = if supports_skills {
+            count_undeclared_skills(&selected.skills, harness_id)
         } else {
             0
         };
@@ -478,12 +1225,19 @@ fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
                 }
             } else if !skipped.is_empty()
                 || incompatible_agent_count > 0
+                || undeclared_skill_count > 0
                 || incompatible_mcp_count > 0
             {
                 let mut warnings: Vec<String> = Vec::new();
                 if !skipped.is_empty() {
                     warnings.push(format!("{} not supported", skipped.join(", ")));
                 }
+                if undeclared_skill_count > 0 {
+                    warnings.push(format!(
+                        "{} skill(s) not declared for {}",
+                        undeclared_skill_count, harness_id
+                    ));
+                }
                 if incompatible_agent_count > 0 {
                     warnings.push(format!(
                         "{} agent(s) incompatible",
@@ -491,7 +1245,7 @@ fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
                     ));
                 }
                 if incompatible_mcp_count > 0 {
-                    let names = get_incompatible_mcp_names(&selected.mcp_servers, *kind);
+                    let names = get_incompatible_mcp_names(&selected.mcp_servers, kind);
                     warnings.push(format!("{} incompatible", names.join(", ")));
                 }
                 ItemState::Warning {
@@ -501,7 +1255,12 @@ fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
                 ItemState::Normal
             };
 
-            let default_selected = is_active && !matches!(state, ItemState::Disabled { .. });
+            let preferred = if remembered.is_empty() {
+                is_active
+            } else {
+                remembered.contains(&format!("{harness_id}/{profile}"))
+            };
+            let default_selected = preferred && !matches!(state, ItemState::Disabled { .. });
 
             items_with_states.push((label, state));
             targets.push(InstallTarget {
@@ -512,7 +1271,7 @@ fn select_targets(selected: &SelectedComponents) -> Result<Vec<InstallTarget>> {
         }
 
         let harness_warning = if incompatible_mcp_count > 0 {
-            let names = get_incompatible_mcp_names(&selected.mcp_servers, *kind);
+            let names = get_incompatible_mcp_names(&selected.mcp_servers, kind);
             Some(format!("{} incompatible", names.join(", ")))
         } else {
             None
@@ -587,4 +1346,83 @@ mod tests {
         let url = "http://example.com/repo";
         assert_eq!(normalize_source(url), url);
     }
+
+    #[test]
+    fn set_secrets_become_keyring_references_without_a_prompt() {
+        let skill = SkillInfo {
+            name: "jira".to_string(),
+            description: None,
+            path: String::new(),
+            content:
+                "---\nname: jira\nenv:\n  - JIRA_URL\n  - name: JIRA_TOKEN\n    secret: true\n---\n"
+                    .to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let mut env = BTreeMap::from([
+            (
+                "JIRA_URL".to_string(),
+                "https://jira.example.com".to_string(),
+            ),
+            ("JIRA_TOKEN".to_string(), "hunter2".to_string()),
+        ]);
+        let stored = std::cell::RefCell::new(Vec::new());
+
+        store_secrets(std::slice::from_ref(&skill), &mut env, |name, value| {
+            stored
+                .borrow_mut()
+                .push((name.to_string(), value.to_string()));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(env["JIRA_TOKEN"], format!("{SECRET_SCHEME}JIRA_TOKEN"));
+        assert_eq!(env["JIRA_URL"], "https://jira.example.com");
+        assert_eq!(
+            stored.into_inner(),
+            [("JIRA_TOKEN".to_string(), "hunter2".to_string())]
+        );
+    }
+
+    #[test]
+    fn pending_env_keeps_no_secret_values() {
+        let skill = SkillInfo {
+            name: "jira".to_string(),
+            description: None,
+            path: String::new(),
+            content:
+                "---\nname: jira\nenv:\n  - JIRA_URL\n  - name: JIRA_TOKEN\n    secret: true\n  - name: JIRA_KEY\n    secret: true\n---\n"
+                    .to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let env = BTreeMap::from([
+            (
+                "JIRA_URL".to_string(),
+                "https://jira.example.com".to_string(),
+            ),
+            (
+                "JIRA_TOKEN".to_string(),
+                format!("{SECRET_SCHEME}JIRA_TOKEN"),
+            ),
+            ("JIRA_KEY".to_string(), "hunter2".to_string()),
+            ("UNRELATED".to_string(), "hunter3".to_string()),
+        ]);
+
+        let kept = pending_env(std::slice::from_ref(&skill), &env);
+
+        assert_eq!(
+            kept,
+            BTreeMap::from([
+                (
+                    "JIRA_TOKEN".to_string(),
+                    format!("{SECRET_SCHEME}JIRA_TOKEN"),
+                ),
+                (
+                    "JIRA_URL".to_string(),
+                    "https://jira.example.com".to_string(),
+                ),
+            ])
+        );
+    }
 }
@@ -0,0 +1,230 @@
+//! `bridle logs`: the logs a harness writes, without knowing where they are.
+//!
+//! Each built-in harness logs somewhere else: Claude Code under `debug/` in
+//! its config directory and, per MCP server, under
+//! `claude-cli-nodejs/*/mcp-logs-<server>/` in the cache directory; OpenCode
+//! under `opencode/log/` in the data directory; Goose under
+//! `~/.local/state/goose/logs/`; Amp under `~/.cache/amp/logs/`.
+//! The newest log file is shown, or with `--mcp` the newest one about that
+//! server: a file whose path names it, or else the lines that mention it.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::cli::output::{ResolvedFormat, output};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+
+/// How often `--tail` checks the log file for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Serialize)]
+struct LogExcerpt {
+    harness: String,
+    file: PathBuf,
+    /// Server the lines were filtered by, when they were.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<String>,
+    lines: Vec<String>,
+}
+
+/// `$<var>` when it is an absolute path, otherwise `~/<default>`.
+fn xdg_dir(var: &str, default: &str) -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(var).map(PathBuf::from)
+        && dir.is_absolute()
+    {
+        return Ok(dir);
+    }
+    Ok(harness_locate::platform::home_dir()?.join(default))
+}
+
+#[cfg(target_os = "macos")]
+fn cache_dir() -> Result<PathBuf> {
+    Ok(harness_locate::platform::home_dir()?.join("Library/Caches"))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn cache_dir() -> Result<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+/// Directories a built-in harness writes its logs to. Empty for others.
+fn log_dirs(harness: &dyn HarnessConfig) -> Result<Vec<PathBuf>> {
+    Ok(match harness.id() {
+        "claude-code" => vec![
+            harness.config_dir()?.join("debug"),
+            cache_dir()?.join("claude-cli-nodejs"),
+        ],
+        "opencode" => vec![harness_locate::platform::data_dir()?.join("opencode/log")],
+        "goose" => vec![xdg_dir("XDG_STATE_HOME", ".local/state")?.join("goose/logs")],
+        "amp-code" => vec![cache_dir()?.join("amp/logs")],
+        _ => Vec::new(),
+    })
+}
+
+/// Files under `dirs`, newest first.
+fn log_files(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = dirs.to_vec();
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(path);
+            } else if meta.is_file() {
+                files.push((meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), path));
+            }
+        }
+    }
+    files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// The files to read for `server` and the text lines must contain:
+/// files whose path names the server, in full, or else every file, filtered.
+fn select(files: Vec<PathBuf>, server: Option<&str>) -> (Vec<PathBuf>, Option<String>) {
+    let Some(server) = server else {
+        return (files, None);
+    };
+    let (named, rest): (Vec<_>, Vec<_>) = files
+        .into_iter()
+        .partition(|path| path.to_string_lossy().contains(server));
+    if named.is_empty() {
+        (rest, Some(server.to_string()))
+    } else {
+        (named, None)
+    }
+}
+
+fn matching<'a>(text: &'a str, filter: Option<&'a str>) -> impl Iterator<Item = &'a str> {
+    text.lines()
+        .filter(move |line| filter.is_none_or(|filter| line.contains(filter)))
+}
+
+/// Shows the last `lines` lines of the newest log of `harness`, or of its
+/// MCP server `server`, then with `tail` keeps printing lines as they are
+/// written.
+pub fn show(
+    harness: &str,
+    server: Option<&str>,
+    lines: usize,
+    tail: bool,
+    format: ResolvedFormat,
+) -> Result<()> {
+    if tail && format != ResolvedFormat::Text {
+        return Err(Error::Config(
+            "--tail prints text as it is written; drop --output".to_string(),
+        ));
+    }
+    let harness = HarnessRegistry::load()?.take(harness)?;
+    let dirs = log_dirs(harness.as_ref())?;
+    if dirs.is_empty() {
+        return Err(Error::Config(format!(
+            "{} has no known log location",
+            harness.id()
+        )));
+    }
+    let (files, filter) = select(log_files(&dirs), server);
+    let found = files.into_iter().find_map(|file| {
+        let text = String::from_utf8_lossy(&std::fs::read(&file).ok()?).into_owned();
+        let matched: Vec<String> = matching(&text, filter.as_deref())
+            .map(str::to_string)
+            .collect();
+        (!matched.is_empty()).then_some((file, matched))
+    });
+    let Some((file, mut matched)) = found else {
+        let searched = dirs
+            .iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(Error::Config(match server {
+            Some(server) => format!("no logs of MCP server '{server}' in {searched}"),
+            None => format!("no logs in {searched}"),
+        }));
+    };
+    matched.drain(..matched.len().saturating_sub(lines));
+
+    let excerpt = LogExcerpt {
+        harness: harness.id().to_string(),
+        file,
+        filter,
+        lines: matched,
+    };
+    output(&excerpt, format, |excerpt| {
+        println!("==> {} <==", excerpt.file.display());
+        for line in &excerpt.lines {
+            println!("{line}");
+        }
+    });
+    if tail {
+        follow(&excerpt.file, excerpt.filter.as_deref())?;
+    }
+    Ok(())
+}
+
+/// Prints lines appended to `file` until interrupted, starting over when
+/// the file is truncated or rotated in place.
+fn follow(file: &Path, filter: Option<&str>) -> Result<()> {
+    let mut offset = std::fs::metadata(file)?.len();
+    let mut partial = String::new();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Ok(mut handle) = std::fs::File::open(file) else {
+            continue;
+        };
+        let len = handle.metadata()?.len();
+        if len < offset {
+            offset = 0;
+            partial.clear();
+        }
+        if len == offset {
+            continue;
+        }
+        handle.seek(SeekFrom::Start(offset))?;
+        let mut bytes = Vec::new();
+        handle.read_to_end(&mut bytes)?;
+        offset += bytes.len() as u64;
+        partial.push_str(&String::from_utf8_lossy(&bytes));
+        // Hold back a line still being written.
+        let complete = partial.rfind('\n').map_or(0, |end| end + 1);
+        for line in matching(&partial[..complete], filter) {
+            println!("{line}");
+        }
+        partial.drain(..complete);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mcp_server_selects_named_files_or_filters_lines() {
+        let files = vec![
+            PathBuf::from("/cache/claude-cli-nodejs/-repo/mcp-logs-github/1.txt"),
+            PathBuf::from("/home/.claude/debug/session.txt"),
+        ];
+        let (named, filter) = select(files.clone(), Some("github"));
+        assert_eq!(named, files[..1]);
+        assert_eq!(filter, None);
+
+        let (all, filter) = select(files.clone(), Some("fs"));
+        assert_eq!(all, files);
+        assert_eq!(filter.as_deref(), Some("fs"));
+        let text = "starting fs server\nok\nfs exited with 1\n";
+        assert_eq!(
+            matching(text, filter.as_deref()).collect::<Vec<_>>(),
+            ["starting fs server", "fs exited with 1"]
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! `bridle mcp`: MCP servers across harnesses and profiles.
+//!
+//! `list` shows every server each profile configures, and with
+//! `--duplicates` only servers configured differently in several places.
+//!
+//! `convert` rewrites one server of a profile to another transport, in the
+//! harness's own format; see [`mcp_transport`](crate::install::mcp_transport).
+//!
+//! `check` verifies a profile's MCP config references. The config
+//! can reference `${NAME}` and `{{ name }}` variables and `bridle-secret://`
+//! secrets, all resolved when the profile is applied. This lists each
+//! reference with where its value would come from, without prompting, and
+//! exits with status 1 if any has none, so a missing token shows up before
+//! the switch rather than halfway through it.
+
+use std::collections::BTreeMap;
+
+use std::path::PathBuf;
+
+use harness_locate::McpServer;
+use serde::Serialize;
+
+use super::profile::manager_builder;
+use crate::cli::output::{ResolvedFormat, output, output_list};
+use crate::config::template::{Reference, VARS_FILE, ValueSource, env_name};
+use crate::config::{BridleConfig, McpServerInfo, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+use crate::install::mcp_config::read_mcp_config;
+use crate::install::mcp_installer::{McpInstallOutcome, get_profile_config_path, install_mcp};
+use crate::install::mcp_transport::{self, Transport};
+use crate::install::{InstallOptions, InstallTarget};
+
+/// One MCP server in one profile.
+#[derive(Debug, Clone, Serialize)]
+struct McpEntry {
+    harness: String,
+    profile: String,
+    name: String,
+    transport: Option<String>,
+    /// Command line of a local server, or URL of a remote one.
+    command: Option<String>,
+    enabled: bool,
+}
+
+impl McpEntry {
+    fn new(harness: &str, profile: &str, server: McpServerInfo) -> Self {
+        let command = match (server.command, server.url) {
+            (Some(command), _) => Some(
+                std::iter::once(command)
+                    .chain(server.args.unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            (None, url) => url,
+        };
+        Self {
+            harness: harness.to_string(),
+            profile: profile.to_string(),
+            name: server.name,
+            transport: server.server_type,
+            command,
+            enabled: server.enabled,
+        }
+    }
+}
+
+/// A server name configured with different commands or URLs.
+#[derive(Debug, Serialize)]
+struct InconsistentServer {
+    name: String,
+    entries: Vec<McpEntry>,
+}
+
+/// Lists the MCP servers of every profile of every harness; with
+/// `duplicates`, only servers whose name appears in several places with
+/// different commands or URLs.
+pub fn list(duplicates: bool, format: ResolvedFormat) -> Result<()> {
+    let entries = scan()?;
+    if duplicates {
+        let inconsistent = find_inconsistent(entries);
+        output_list(&inconsistent, format, |groups| {
+            if groups.is_empty() {
+                println!("No MCP server is configured inconsistently");
+            }
+            for group in groups {
+                println!("{} ({} places):", group.name, group.entries.len());
+                for entry in &group.entries {
+                    println!(
+                        "  {}/{}: {}",
+                        entry.harness,
+                        entry.profile,
+                        entry.command.as_deref().unwrap_or("-")
+                    );
+                }
+            }
+        });
+        return Ok(());
+    }
+    output_list(&entries, format, |entries| {
+        if entries.is_empty() {
+            println!("No MCP servers configured in any profile");
+        }
+        for entry in entries {
+            println!(
+                "{}/{}  {}  {}  {}{}",
+                entry.harness,
+                entry.profile,
+                entry.name,
+                entry.transport.as_deref().unwrap_or("-"),
+                entry.command.as_deref().unwrap_or("-"),
+                if entry.enabled { "" } else { "  (disabled)" }
+            );
+        }
+    });
+    Ok(())
+}
+
+/// Every MCP server of every profile, by harness, profile, and name.
+fn scan() -> Result<Vec<McpEntry>> {
+    let registry = HarnessRegistry::load()?;
+    let manager = manager_builder()?.build()?;
+    let mut entries = Vec::new();
+    for harness in registry.iter().map(|e| e.harness()) {
+        for name in manager.list_profiles(harness)? {
+            let info = match manager.show_profile(harness, &name) {
+                Ok(info) => info,
+                Err(e) => {
+                    eprintln!("Warning: {}/{name}: {e}", harness.id());
+                    continue;
+                }
+            };
+            let mut servers = info.mcp_servers;
+            servers.sort_by(|a, b| a.name.cmp(&b.name));
+            entries.extend(
+                servers
+                    .into_iter()
+                    .map(|server| McpEntry::new(harness.id(), name.as_str(), server)),
+            );
+        }
+    }
+    Ok(entries)
+}
+
+/// Servers whose name appears with more than one command or URL. Entries
+/// whose definition could not be read do not count as a difference.
+fn find_inconsistent(entries: Vec<McpEntry>) -> Vec<InconsistentServer> {
+    let mut by_name: BTreeMap<String, Vec<McpEntry>> = BTreeMap::new();
+    for entry in entries {
+        by_name.entry(entry.name.clone()).or_default().push(entry);
+    }
+    by_name
+        .into_iter()
+        .filter(|(_, entries)| {
+            let mut commands: Vec<&str> = entries
+                .iter()
+                .filter_map(|e| e.command.as_deref())
+                .collect();
+            commands.sort_unstable();
+            commands.dedup();
+            commands.len() > 1
+        })
+        .map(|(name, entries)| InconsistentServer { name, entries })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+struct ReferenceCheck {
+    harness: String,
+    profile: String,
+    references: Vec<Reference>,
+}
+
+#[derive(Debug, Serialize)]
+struct Conversion {
+    harness: String,
+    profile: String,
+    name: String,
+    from: Transport,
+    to: Transport,
+    server: McpServer,
+    /// Files rewritten: the profile's, and the live config's when active.
+    written: Vec<PathBuf>,
+}
+
+/// `profile`, or the active profile of `harness` when `None`.
+fn profile_or_active(harness: &str, profile: Option<&str>, action: &str) -> Result<ProfileName> {
+    let name = match profile {
+        Some(name) => name.to_string(),
+        None => BridleConfig::load()?
+            .active_profile_for(harness)
+            .map(str::to_string)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no active profile for {harness}; name the profile to {action}"
+                ))
+            })?,
+    };
+    ProfileName::new(&name).map_err(|reason| Error::invalid_profile_name(&name, reason))
+}
+
+/// Rewrites MCP server `server_name` of `profile` of `harness`, or of its
+/// active profile, to use transport `to`, in the profile and, when the
+/// profile is active, in the live config.
+pub fn convert(
+    harness: &str,
+    server_name: &str,
+    to: Transport,
+    profile: Option<&str>,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let registry = HarnessRegistry::load()?;
+    let harness = registry.resolve(harness)?.harness();
+    let builtin = harness.as_builtin().ok_or_else(|| {
+        Error::Config(format!(
+            "{} is not a built-in harness; its MCP format is unknown",
+            harness.id()
+        ))
+    })?;
+    let kind = builtin.kind();
+    let name = profile_or_active(harness.id(), profile, "convert in")?;
+    let profile_dir = BridleConfig::profiles_dir()?
+        .join(harness.id())
+        .join(name.as_str());
+    if !profile_dir.is_dir() {
+        return Err(Error::profile_not_found(harness.id(), name.as_str()));
+    }
+    let active = BridleConfig::load()?.active_profile_for(harness.id()) == Some(name.as_str());
+    // An active profile's servers are read where the harness reads them.
+    let path = harness
+        .mcp_config_path()
+        .filter(|path| active && path.exists())
+        .unwrap_or_else(|| get_profile_config_path(&profile_dir, kind));
+
+    let servers = read_mcp_config(kind, &path).map_err(|e| Error::ConfigParse {
+        path: path.clone(),
+        message: e.to_string(),
+    })?;
+    let value = servers.get(server_name).ok_or_else(|| {
+        Error::Config(format!(
+            "no MCP server '{server_name}' in {}",
+            path.display()
+        ))
+    })?;
+    let server = builtin
+        .parse_mcp_server_config(server_name, value)
+        .map_err(|e| Error::Config(e.to_string()))?;
+    let converted = mcp_transport::convert(&server, to).map_err(|reason| {
+        Error::Config(format!("cannot convert '{server_name}' to {to}: {reason}"))
+    })?;
+    if !builtin.supports_mcp_server(&converted) {
+        return Err(Error::Config(format!(
+            "{} does not support '{server_name}' as a {to} server",
+            harness.id()
+        )));
+    }
+
+    let target = InstallTarget {
+        harness: harness.id().to_string(),
+        profile: name.clone(),
+    };
+    let options = InstallOptions {
+        force: true,
+        ..Default::default()
+    };
+    let written = match install_mcp(server_name, &converted, &target, &options)
+        .map_err(|e| Error::Config(e.to_string()))?
+    {
+        McpInstallOutcome::Installed(success) => std::iter::once(success.profile_path)
+            .chain(success.harness_path)
+            .collect(),
+        McpInstallOutcome::Skipped(_) => Vec::new(),
+    };
+
+    let conversion = Conversion {
+        harness: harness.id().to_string(),
+        profile: name.as_str().to_string(),
+        name: server_name.to_string(),
+        from: Transport::of(&server),
+        to,
+        server: converted,
+        written,
+    };
+    output(&conversion, format, |conversion| {
+        println!(
+            "Converted {} in {}/{} from {} to {}",
+            conversion.name, conversion.harness, conversion.profile, conversion.from, conversion.to
+        );
+        if let McpServer::Stdio(stdio) = &conversion.server {
+            println!("  command: {} {}", stdio.command, stdio.args.join(" "));
+        }
+        for path in &conversion.written {
+            println!("  wrote {}", path.display());
+        }
+    });
+    Ok(())
+}
+
+/// Checks that every reference in the MCP config of `profile` of `harness`,
+/// or its active profile, resolves, and returns the exit code: 1 if any is
+/// missing, else 0.
+pub fn check(harness: &str, profile: Option<&str>, format: ResolvedFormat) -> Result<i32> {
+    let harness = HarnessRegistry::load()?.take(harness)?;
+    let name = profile_or_active(harness.id(), profile, "check")?;
+    let references = manager_builder()?
+        .build()?
+        .mcp_references(harness.as_ref(), &name)?;
+
+    let failed = references.iter().any(|r| r.source == ValueSource::Missing);
+    let report = ReferenceCheck {
+        harness: harness.id().to_string(),
+        profile: name.as_str().to_string(),
+        references,
+    };
+    output(&report, format, |report| {
+        let target = format!("{}/{}", report.harness, report.profile);
+        if report.references.is_empty() {
+            println!("{target}: the MCP config references no variables or secrets");
+            return;
+        }
+        let missing = report
+            .references
+            .iter()
+            .filter(|r| r.source == ValueSource::Missing)
+            .count();
+        if missing == 0 {
+            println!(
+                "{target}: all {} reference(s) resolve",
+                report.references.len()
+            );
+        } else {
+            println!("{target}: {missing} reference(s) do not resolve");
+        }
+        for reference in &report.references {
+            let source = match reference.source {
+                ValueSource::Environment => env_name(&reference.name),
+                ValueSource::VarsFile => VARS_FILE.to_string(),
+                ValueSource::SecretStore => "secret store".to_string(),
+                ValueSource::Missing => "missing".to_string(),
+            };
+            println!(
+                "  {:<32}  {source}  ({})",
+                reference.reference,
+                reference.file.display()
+            );
+        }
+    });
+    Ok(i32::from(failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(profile: &str, name: &str, command: Option<&str>) -> McpEntry {
+        McpEntry::new(
+            "opencode",
+            profile,
+            McpServerInfo {
+                name: name.to_string(),
+                enabled: true,
+                command: command.map(str::to_string),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn finds_servers_with_differing_commands() {
+        let inconsistent = find_inconsistent(vec![
+            entry("work", "github", Some("npx server-github")),
+            entry("home", "github", Some("npx server-github@2")),
+            entry("work", "fs", Some("mcp-fs")),
+            entry("home", "fs", Some("mcp-fs")),
+            entry("ci", "fs", None),
+        ]);
+        let [group] = inconsistent.as_slice() else {
+            panic!("expected one group, got {inconsistent:?}");
+        };
+        assert_eq!(group.name, "github");
+        assert_eq!(group.entries.len(), 2);
+    }
+
+    #[test]
+    fn joins_command_and_args_or_uses_the_url() {
+        let local = McpEntry::new(
+            "claude-code",
+            "work",
+            McpServerInfo {
+                name: "github".to_string(),
+                command: Some("npx".to_string()),
+                args: Some(vec!["-y".to_string(), "server-github".to_string()]),
+                ..Default::default()
+            },
+        );
+        assert_eq!(local.command.as_deref(), Some("npx -y server-github"));
+        let remote = McpEntry::new(
+            "claude-code",
+            "work",
+            McpServerInfo {
+                name: "docs".to_string(),
+                url: Some("https://mcp.example.com".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(remote.command.as_deref(), Some("https://mcp.example.com"));
+    }
+}
@@ -0,0 +1,585 @@
+//! `bridle migrate`: move everything bridle knows to another machine.
+//!
+//! `export` writes one zip archive holding bridle's config directory
+//! (`config.toml`, profiles and their lockfiles, shared fragments, custom
+//! harnesses) under `config/` and its state directory (active profiles,
+//! switch history and statistics, sync records, secret names) under
+//! `state/`, next to a [`MANIFEST`] naming the bridle and config versions
+//! that wrote it. Snapshots in `backups/`, caches, journals, install undo
+//! records, and locks stay
+//! behind, as they only describe this machine; secret values stay in the
+//! keyring.
+//!
+//! `import` unpacks an archive into this machine's directories. Archives
+//! whose config is newer than this bridle reads are refused, as are those
+//! written by a newer bridle unless forced, and those whose symlinks point
+//! outside the directory they unpack into; older configs are upgraded on
+//! first load. Imported profiles are not left active, since the live configs
+//! here are not theirs: switching to them applies them.
+
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::cli::output::{ResolvedFormat, output};
+use crate::config::migration::CURRENT_VERSION;
+use crate::config::{BridleConfig, PersistedState, SecretIndex, StatePaths};
+use crate::error::{Error, Result};
+
+/// Archive entry describing the archive.
+const MANIFEST: &str = "bridle-migrate.json";
+
+/// Layout version of the archive, bumped when it changes incompatibly.
+const FORMAT: u32 = 1;
+
+/// Archive directory holding the config directory.
+const CONFIG_PREFIX: &str = "config";
+
+/// Archive directory holding the state directory.
+const STATE_PREFIX: &str = "state";
+
+/// Config directory entries that describe this machine only.
+const CONFIG_SKIPPED: &[&str] = &["backups"];
+
+/// State directory entries, at any depth, that describe this machine only.
+const STATE_SKIPPED: &[&str] = &["cache", "journal", "installs", "locks"];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleManifest {
+    format: u32,
+    bridle_version: String,
+    /// `version` of the archived `config.toml`.
+    config_version: u32,
+    created: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportReport {
+    archive: PathBuf,
+    files: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportReport {
+    archive: PathBuf,
+    bridle_version: String,
+    files: usize,
+    /// `<harness>/<profile>` of profiles active on the exporting machine.
+    previously_active: Vec<String>,
+    /// Secrets referenced by name whose values have to be set again.
+    secrets: Vec<String>,
+}
+
+/// `0.2.6` as `[0, 2, 6]`, ignoring pre-release and build suffixes.
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Why an archive from `manifest` cannot be imported here, if it cannot.
+fn incompatibility(manifest: &BundleManifest, force: bool) -> Option<String> {
+    let ours = env!("CARGO_PKG_VERSION");
+    if manifest.format > FORMAT {
+        return Some(format!(
+            "the archive was written by bridle {} in a newer format; upgrade bridle to import it",
+            manifest.bridle_version
+        ));
+    }
+    if manifest.config_version > CURRENT_VERSION {
+        return Some(format!(
+            "the archive's config version {} is newer than this bridle supports ({CURRENT_VERSION}); \
+             upgrade bridle to import it",
+            manifest.config_version
+        ));
+    }
+    if !force && version_parts(&manifest.bridle_version) > version_parts(ours) {
+        return Some(format!(
+            "the archive was written by bridle {}, newer than this one ({ours}); \
+             upgrade bridle or pass --force to import it anyway",
+            manifest.bridle_version
+        ));
+    }
+    None
+}
+
+/// Adds the files under `dir` to `zip` below `prefix`, leaving out the
+/// paths, relative to `dir`, that `skipped` accepts and everything below them.
+fn add_dir(
+    zip: &mut ZipWriter<std::fs::File>,
+    dir: &Path,
+    prefix: &str,
+    skipped: impl Fn(&Path) -> bool,
+) -> Result<usize> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    let mut added = 0;
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(dir.join(&rel)) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let rel = rel.join(entry.file_name());
+            if skipped(&rel) {
+                continue;
+            }
+            let name = format!("{prefix}/{}", rel.to_string_lossy().replace('\\', "/"));
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(rel);
+            } else if file_type.is_symlink() {
+                let target = std::fs::read_link(entry.path())?;
+                zip.add_symlink(name, target.to_string_lossy(), options)
+                    .map_err(zip_error)?;
+                added += 1;
+            } else if file_type.is_file() {
+                zip.start_file(name, options.unix_permissions(file_mode(&entry.path())))
+                    .map_err(zip_error)?;
+                zip.write_all(&std::fs::read(entry.path())?)?;
+                added += 1;
+            }
+        }
+    }
+    Ok(added)
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).map_or(0o644, |m| m.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> u32 {
+    0o644
+}
+
+fn zip_error(e: zip::result::ZipError) -> Error {
+    Error::Command(format!("migration archive: {e}"))
+}
+
+/// Writes bridle's config and state to the zip archive `archive`.
+pub fn export(archive: &Path, format: ResolvedFormat) -> Result<()> {
+    let config_dir = BridleConfig::config_dir()?;
+    if !config_dir.is_dir() {
+        return Err(Error::Config(format!(
+            "nothing to export: {} does not exist",
+            config_dir.display()
+        )));
+    }
+    let config = BridleConfig::load()?;
+    let state_dir = StatePaths::resolve()?.root().to_path_buf();
+    let archive_path = std::path::absolute(archive)?;
+
+    let mut zip = ZipWriter::new(std::fs::File::create(&archive_path)?);
+    let manifest = BundleManifest {
+        format: FORMAT,
+        bridle_version: env!("CARGO_PKG_VERSION").to_string(),
+        config_version: config.version,
+        created: Utc::now(),
+    };
+    zip.start_file(MANIFEST, SimpleFileOptions::default())
+        .map_err(zip_error)?;
+    zip.write_all(
+        &serde_json::to_vec_pretty(&manifest).map_err(|e| Error::Config(e.to_string()))?,
+    )?;
+    // The state directory may sit inside the config directory; it is archived on its own.
+    let mut files = add_dir(&mut zip, &config_dir, CONFIG_PREFIX, |rel| {
+        let path = config_dir.join(rel);
+        CONFIG_SKIPPED.iter().any(|s| Path::new(s) == rel)
+            || path == state_dir
+            || path == archive_path
+    })?;
+    files += add_dir(&mut zip, &state_dir, STATE_PREFIX, |rel| {
+        rel.file_name()
+            .is_some_and(|name| STATE_SKIPPED.iter().any(|s| name == *s))
+    })?;
+    zip.finish().map_err(zip_error)?;
+
+    let report = ExportReport {
+        archive: archive_path,
+        files,
+    };
+    output(&report, format, |r| {
+        println!("Exported {} file(s) to {}", r.files, r.archive.display());
+        println!("Secret values are not included; set them again after importing");
+    });
+    Ok(())
+}
+
+/// Where the archive entry `name` goes: the config or state directory, and
+/// the path below it. `None` for the manifest and unknown entries.
+fn destination<'a>(
+    name: &'a Path,
+    config_dir: &'a Path,
+    state_dir: &'a Path,
+) -> Option<(PathBuf, &'a Path)> {
+    let mut components = name.components();
+    let root = match components.next()?.as_os_str().to_str()? {
+        CONFIG_PREFIX => config_dir,
+        STATE_PREFIX => state_dir,
+        _ => return None,
+    };
+    let rel = components.as_path();
+    (!rel.as_os_str().is_empty()).then(|| (root.join(rel), root))
+}
+
+/// Restores bridle's config and state from the zip archive `archive`.
+pub fn import(archive: &Path, force: bool, format: ResolvedFormat) -> Result<()> {
+    let file = std::fs::File::open(archive)?;
+    let not_an_archive = |reason: String| {
+        Error::Config(format!(
+            "{} is not a bridle migration archive: {reason}",
+            archive.display()
+        ))
+    };
+    let mut zip = ZipArchive::new(file).map_err(|e| not_an_archive(e.to_string()))?;
+    let manifest: BundleManifest = {
+        let mut entry = zip
+            .by_name(MANIFEST)
+            .map_err(|_| not_an_archive(format!("{MANIFEST} is missing")))?;
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+        serde_json::from_str(&text).map_err(|e| not_an_archive(e.to_string()))?
+    };
+    if let Some(reason) = incompatibility(&manifest, force) {
+        return Err(Error::Config(reason));
+    }
+
+    let config_dir = BridleConfig::config_dir()?;
+    let state_dir = StatePaths::resolve()?.root().to_path_buf();
+    if !force && BridleConfig::config_path()?.exists() {
+        return Err(Error::Config(format!(
+            "bridle is already set up in {}; pass --force to overwrite its files",
+            config_dir.display()
+        )));
+    }
+
+    let (files, states) = unpack(&mut zip, &config_dir, &state_dir, &not_an_archive)?;
+
+    // The live configs here are not the imported profiles', so none is active yet.
+    let mut previously_active = Vec::new();
+    for root in states {
+        let paths = StatePaths::new(root);
+        let mut state = PersistedState::load(&paths)?;
+        previously_active.extend(state.active.iter().map(|(h, p)| format!("{h}/{p}")));
+        state.active.clear();
+        state.save(&paths)?;
+    }
+    previously_active.sort();
+    previously_active.dedup();
+    let secrets = SecretIndex::load(&StatePaths::resolve()?)?
+        .names()
+        .map(str::to_string)
+        .collect();
+
+    let report = ImportReport {
+        archive: archive.to_path_buf(),
+        bridle_version: manifest.bridle_version,
+        files,
+        previously_active,
+        secrets,
+    };
+    output(&report, format, |r| {
+        println!(
+            "Imported {} file(s) from {} (bridle {})",
+            r.files,
+            r.archive.display(),
+            r.bridle_version
+        );
+        if !r.previously_active.is_empty() {
+            println!("Active on the old machine; switch to apply them here:");
+            for label in &r.previously_active {
+                let (harness, profile) = label.split_once('/').unwrap_or((label, ""));
+                println!("  bridle profile switch {harness} {profile}");
+            }
+        }
+        if !r.secrets.is_empty() {
+            println!(
+                "Secret values stay in the old machine's keyring; set them with `bridle secret set`:"
+            );
+            for name in &r.secrets {
+                println!("  {name}");
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Writes the entries of `zip` into `config_dir` and `state_dir`, returning
+/// how many files were written and the state directories among them.
+///
+/// Symlinks must point inside the directory they are unpacked into, and
+/// nothing is written through a directory that is a symlink, so an archive
+/// cannot place files anywhere else.
+fn unpack<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    config_dir: &Path,
+    state_dir: &Path,
+    not_an_archive: &dyn Fn(String) -> Error,
+) -> Result<(usize, Vec<PathBuf>)> {
+    let mut files = 0;
+    let mut states = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(zip_error)?;
+        let Some(name) = entry.enclosed_name() else {
+            return Err(not_an_archive(format!("unsafe path {}", entry.name())));
+        };
+        let Some((path, root)) = destination(&name, config_dir, state_dir) else {
+            continue;
+        };
+        if let Some(dir) = symlinked_parent(root, &path) {
+            return Err(Error::Config(format!(
+                "refusing to write {}: {} is a symlink",
+                path.display(),
+                dir.display()
+            )));
+        }
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        let target = entry
+            .is_symlink()
+            .then(|| String::from_utf8_lossy(&content).into_owned());
+        if let Some(target) = &target
+            && !link_stays_inside(root, &path, Path::new(target))
+        {
+            return Err(not_an_archive(format!(
+                "symlink {} points outside the archive or through another symlink, to {target}",
+                name.display()
+            )));
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if path.is_symlink() || path.is_file() {
+            std::fs::remove_file(&path)?;
+        }
+        match target {
+            Some(target) => link(&target, &path)?,
+            None => {
+                std::fs::write(&path, content)?;
+                set_mode(&path, entry.unix_mode())?;
+            }
+        }
+        if root == state_dir && path.file_name().is_some_and(|n| n == "state.toml") {
+            states.push(path.parent().unwrap_or(state_dir).to_path_buf());
+        }
+        files += 1;
+    }
+    Ok((files, states))
+}
+
+/// Whether a symlink at `path`, below the directory `root` it is unpacked
+/// into, pointing at `target` stays inside that directory. A target that
+/// passes through a symlink already there does not count, since it could
+/// lead anywhere.
+fn link_stays_inside(root: &Path, path: &Path, target: &Path) -> bool {
+    let Ok(rel) = path.strip_prefix(root) else {
+        return false;
+    };
+    let mut depth = rel.components().count().saturating_sub(1);
+    let mut dir = path.parent().unwrap_or(root).to_path_buf();
+    for component in target.components() {
+        match component {
+            Component::Normal(name) => {
+                dir.push(name);
+                depth += 1;
+                if dir.is_symlink() {
+                    return false;
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => {
+                dir.pop();
+                depth -= 1;
+            }
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+/// The first directory below `root` on the way to `path` that is a symlink.
+fn symlinked_parent(root: &Path, path: &Path) -> Option<PathBuf> {
+    let mut components = path.strip_prefix(root).ok()?.components();
+    components.next_back();
+    let mut dir = root.to_path_buf();
+    for component in components {
+        dir.push(component);
+        if dir.is_symlink() {
+            return Some(dir);
+        }
+    }
+    None
+}
+
+#[cfg(unix)]
+fn link(target: &str, path: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, path)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn link(target: &str, path: &Path) -> Result<()> {
+    eprintln!(
+        "Warning: Skipped symlink {} -> {target}: not supported here",
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: Option<u32>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    if let Some(mode) = mode {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: Option<u32>) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(bridle_version: &str, config_version: u32) -> BundleManifest {
+        BundleManifest {
+            format: FORMAT,
+            bridle_version: bridle_version.to_string(),
+            config_version,
+            created: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn refuses_archives_from_newer_bridles() {
+        assert_eq!(version_parts("0.10.2-rc.1"), [0, 10, 2]);
+        assert!(version_parts("0.10.0") > version_parts("0.9.9"));
+
+        assert_eq!(incompatibility(&manifest("0.0.1", 1), false), None);
+        let newer = manifest("999.0.0", CURRENT_VERSION);
+        assert!(incompatibility(&newer, false).unwrap().contains("--force"));
+        assert_eq!(incompatibility(&newer, true), None);
+        let unreadable = manifest("0.0.1", CURRENT_VERSION + 1);
+        assert!(incompatibility(&unreadable, true).is_some());
+    }
+
+    /// An archive of `files`, where a `Some` target makes the entry a symlink.
+    fn archive(files: &[(&str, Option<&str>)]) -> ZipArchive<std::io::Cursor<Vec<u8>>> {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, target) in files {
+            match target {
+                Some(target) => zip
+                    .add_symlink(*name, *target, SimpleFileOptions::default())
+                    .unwrap(),
+                None => {
+                    zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+                    zip.write_all(b"payload").unwrap();
+                }
+            }
+        }
+        ZipArchive::new(zip.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn link_targets_stay_inside_their_directory() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        let path = root.join("profiles/opencode/work/AGENTS.md");
+        let stays = |path: &Path, target: &str| link_stays_inside(root, path, Path::new(target));
+        assert!(stays(&path, "../../../shared/AGENTS.md"));
+        assert!(stays(&path, "./skills/../AGENTS.md"));
+        assert!(!stays(&path, "../../../../outside"));
+        assert!(!stays(&path, "/etc/passwd"));
+        assert!(!stays(&root.join("top"), ".."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn malicious_archives_write_nothing_outside() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (config, state) = (temp.path().join("config"), temp.path().join("state"));
+        let outside = temp.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        let not_an_archive = |reason: String| Error::Config(reason);
+        let unpack_all = |files: &[(&str, Option<&str>)]| {
+            unpack(&mut archive(files), &config, &state, &not_an_archive)
+        };
+
+        let absolute = outside.join("absolute").display().to_string();
+        for files in [
+            &[("config/evil", Some(absolute.as_str()))][..],
+            &[("config/profiles/evil", Some("../../outside"))][..],
+            &[
+                ("config/profiles", Some(".")),
+                ("config/profiles/evil", None),
+            ][..],
+            &[
+                ("config/a/b/up", Some("../..")),
+                ("config/m", Some("a/b/up/..")),
+            ][..],
+        ] {
+            let error = unpack_all(files).unwrap_err().to_string();
+            assert!(
+                error.contains("outside the archive") || error.contains("is a symlink"),
+                "{error}"
+            );
+        }
+
+        std::fs::remove_dir_all(&config).unwrap();
+        std::fs::create_dir_all(&config).unwrap();
+        std::os::unix::fs::symlink(&outside, config.join("profiles")).unwrap();
+        let error = unpack_all(&[("config/profiles/opencode/work/AGENTS.md", None)])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("is a symlink"), "{error}");
+        assert_eq!(std::fs::read_dir(&outside).unwrap().count(), 0);
+
+        let (files, _) = unpack_all(&[
+            ("config/shared/AGENTS.md", None),
+            ("config/AGENTS.md", Some("shared/AGENTS.md")),
+        ])
+        .unwrap();
+        assert_eq!(files, 2);
+        assert_eq!(
+            std::fs::read_to_string(config.join("AGENTS.md")).unwrap(),
+            "payload"
+        );
+    }
+
+    #[test]
+    fn entries_go_to_their_directory() {
+        let (config, state) = (Path::new("/c"), Path::new("/s"));
+        let dest = |name: &str| destination(Path::new(name), config, state).map(|(p, _)| p);
+        assert_eq!(
+            dest("config/profiles/opencode/work/AGENTS.md"),
+            Some(PathBuf::from("/c/profiles/opencode/work/AGENTS.md"))
+        );
+        assert_eq!(
+            dest("state/state.toml"),
+            Some(PathBuf::from("/s/state.toml"))
+        );
+        assert_eq!(dest(MANIFEST), None);
+        assert_eq!(dest("config"), None);
+    }
+}
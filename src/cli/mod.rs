@@ -1,13 +1,49 @@
 //! CLI module for bridle.
 
+pub mod activate;
+pub mod audit;
+pub mod cache;
 mod commands;
+pub mod completions;
 pub mod config_cmd;
+pub mod config_file;
+pub mod context;
+pub mod daemon;
+pub mod dedupe;
+pub mod dotfiles;
+pub mod exec;
+pub mod harness;
+pub mod health;
+pub mod history;
+pub mod import_config;
 pub mod init;
+pub mod insights;
 pub mod install;
+pub mod logs;
+pub mod mcp;
+pub mod migrate;
 pub mod output;
+pub mod package;
 pub mod profile;
+pub mod project;
+pub mod prompt;
+pub mod provision;
+pub mod push;
+pub mod recover;
+pub mod remote;
+pub mod rules;
+pub mod sbom;
+pub mod secret;
 pub mod status;
+pub mod sync;
 pub mod tui;
+pub mod undo;
 pub mod uninstall;
+pub mod update;
+pub mod verify;
 
-pub use commands::{Commands, ConfigCommands, ProfileCommands};
+pub use commands::{
+    CacheCommands, Commands, ConfigCommands, ConfigFileCommands, ContextCommands, DedupeCommands,
+    HarnessCommands, McpCommands, MigrateCommands, ProfileCommands, ProjectCommands, RulesCommands,
+    SecretCommands, SyncCommands,
+};
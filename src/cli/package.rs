@@ -0,0 +1,270 @@
+//! `bridle profile package`: a Claude Code profile as a plugin marketplace.
+//!
+//! The output directory is both a Claude Code plugin and a single-plugin
+//! marketplace, so a team can publish it as a repository and install it
+//! with `/plugin marketplace add <repo>`:
+//!
+//! ```text
+//! <dir>/
+//!   .claude-plugin/plugin.json
+//!   .claude-plugin/marketplace.json
+//!   .mcp.json
+//!   commands/ agents/ skills/
+//! ```
+//!
+//! MCP server `env` values and `headers` are replaced with `${NAME}`
+//! references, which Claude Code expands from the user's environment, so
+//! the package never carries the profile's credentials. Disabled servers
+//! are left out.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::cli::output::{ResolvedFormat, output};
+use crate::config::{BridleConfig, ProfileManager, ProfileName, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::schema::ClaudeMcpConfig;
+use crate::harness::{HarnessRegistry, ResourceKind};
+use crate::install::installer::sanitize_name_for_opencode;
+
+/// The only harness whose profiles can be packaged.
+const CLAUDE_CODE: &str = "claude-code";
+
+/// Directory holding the plugin and marketplace manifests.
+const MANIFEST_DIR: &str = ".claude-plugin";
+
+/// Resource directories copied into the plugin.
+const RESOURCES: [ResourceKind; 3] = [
+    ResourceKind::Commands,
+    ResourceKind::Agents,
+    ResourceKind::Skills,
+];
+
+/// What was written to the package.
+#[derive(Debug, Default, Serialize)]
+struct PackageSummary {
+    plugin: String,
+    path: PathBuf,
+    /// Number of files copied per resource directory.
+    resources: BTreeMap<&'static str, usize>,
+    mcp_servers: Vec<String>,
+    /// Environment variables users must set for the MCP servers.
+    env: Vec<String>,
+}
+
+/// Writes profile `name` of `harness_name` as a plugin marketplace to `dir`.
+///
+/// # Errors
+/// Fails if the harness is not Claude Code, the profile does not exist, or
+/// `dir` is not empty and `force` is not set.
+pub fn package(
+    harness_name: &str,
+    name: &str,
+    dir: &Path,
+    owner: &str,
+    force: bool,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    if harness.id() != CLAUDE_CODE {
+        return Err(Error::Config(format!(
+            "only {CLAUDE_CODE} profiles can be packaged as plugins, not {}",
+            harness.id()
+        )));
+    }
+    let profile =
+        ProfileName::new(name).map_err(|reason| Error::invalid_profile_name(name, reason))?;
+    let config = BridleConfig::load()?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    if !manager.profile_exists(harness.as_ref(), &profile) {
+        return Err(Error::profile_not_found(harness.id(), name));
+    }
+    let is_empty = std::fs::read_dir(dir).map_or(true, |mut d| d.next().is_none());
+    if !is_empty && !force {
+        return Err(Error::Config(format!(
+            "{} is not empty; pass --force to write into it",
+            dir.display()
+        )));
+    }
+
+    let summary = write_package(
+        &manager.profile_path(harness.as_ref(), &profile),
+        dir,
+        &sanitize_name_for_opencode(name),
+        owner,
+    )?;
+    output(&summary, format, |summary| {
+        println!(
+            "Packaged plugin '{}' at {}",
+            summary.plugin,
+            summary.path.display()
+        );
+        for (dir, count) in &summary.resources {
+            println!("  {dir}: {count} file(s)");
+        }
+        if !summary.mcp_servers.is_empty() {
+            println!("  MCP servers: {}", summary.mcp_servers.join(", "));
+        }
+        if !summary.env.is_empty() {
+            println!("Users must set: {}", summary.env.join(", "));
+        }
+        println!(
+            "Publish the directory as a repository and add it with `/plugin marketplace add <repo>`."
+        );
+    });
+    Ok(())
+}
+
+fn write_package(profile: &Path, dir: &Path, plugin: &str, owner: &str) -> Result<PackageSummary> {
+    let mut summary = PackageSummary {
+        plugin: plugin.to_string(),
+        path: dir.to_path_buf(),
+        ..Default::default()
+    };
+    std::fs::create_dir_all(dir.join(MANIFEST_DIR))?;
+
+    for kind in RESOURCES {
+        let name = kind.canonical_dir();
+        let src = profile.join(name);
+        if src.is_dir() {
+            let dst = dir.join(name);
+            if dst.exists() {
+                std::fs::remove_dir_all(&dst)?;
+            }
+            summary.resources.insert(name, copy_tree(&src, &dst)?);
+        }
+    }
+
+    if let Some(mut mcp) = ClaudeMcpConfig::load(profile)? {
+        mcp.mcp_servers.retain(|_, server| !server.disabled);
+        mcp.extra.clear();
+        for server in mcp.mcp_servers.values_mut() {
+            for (key, value) in server.env.iter_mut() {
+                if !value.starts_with("${") {
+                    *value = env_reference(key, &mut summary.env);
+                }
+            }
+            if let Some(serde_json::Value::Object(headers)) = server.extra.get_mut("headers") {
+                for (key, value) in headers.iter_mut() {
+                    if !value.as_str().is_some_and(|v| v.starts_with("${")) {
+                        *value = env_reference(key, &mut summary.env).into();
+                    }
+                }
+            }
+        }
+        summary.mcp_servers = mcp.mcp_servers.keys().cloned().collect();
+        if !mcp.mcp_servers.is_empty() {
+            write_json(&dir.join(ClaudeMcpConfig::FILENAME), &mcp)?;
+        }
+    }
+    summary.env.sort();
+    summary.env.dedup();
+
+    let description = format!("Claude Code setup from the bridle profile `{plugin}`");
+    write_json(
+        &dir.join(MANIFEST_DIR).join("plugin.json"),
+        &json!({ "name": plugin, "description": description }),
+    )?;
+    write_json(
+        &dir.join(MANIFEST_DIR).join("marketplace.json"),
+        &json!({
+            "name": plugin,
+            "owner": { "name": owner },
+            "plugins": [{ "name": plugin, "source": "./", "description": description }],
+        }),
+    )?;
+    Ok(summary)
+}
+
+/// A `${NAME}` reference for the value of `key`, recording `NAME` in `env`.
+fn env_reference(key: &str, env: &mut Vec<String>) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    env.push(name.clone());
+    format!("${{{name}}}")
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let mut content =
+        serde_json::to_string_pretty(value).map_err(|e| Error::Config(e.to_string()))?;
+    content.push('\n');
+    write_atomic(path, content)
+}
+
+/// Copies the files under `src` to `dst`, returning how many were copied.
+pub(crate) fn copy_tree(src: &Path, dst: &Path) -> Result<usize> {
+    std::fs::create_dir_all(dst)?;
+    let mut count = 0;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.path().is_dir() {
+            count += copy_tree(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn packages_resources_and_templates_mcp_secrets() {
+        let temp = TempDir::new().unwrap();
+        let profile = temp.path().join("profile");
+        std::fs::create_dir_all(profile.join("skills/review")).unwrap();
+        std::fs::create_dir_all(profile.join("commands")).unwrap();
+        std::fs::write(profile.join("skills/review/SKILL.md"), "review").unwrap();
+        std::fs::write(profile.join("commands/ship.md"), "ship").unwrap();
+        std::fs::write(
+            profile.join(".mcp.json"),
+            r#"{"mcpServers": {
+                "github": {"command": "gh-mcp", "env": {"GITHUB_TOKEN": "ghp_secret"}},
+                "api": {"type": "http", "url": "https://api", "headers": {"X-Api-Key": "k"}},
+                "old": {"command": "old", "disabled": true}
+            }}"#,
+        )
+        .unwrap();
+
+        let out = temp.path().join("out");
+        let summary = write_package(&profile, &out, "team", "acme").unwrap();
+
+        assert_eq!(summary.resources.get("skills"), Some(&1));
+        assert_eq!(summary.resources.get("commands"), Some(&1));
+        assert_eq!(summary.mcp_servers, vec!["api", "github"]);
+        assert_eq!(summary.env, vec!["GITHUB_TOKEN", "X_API_KEY"]);
+        assert!(out.join("skills/review/SKILL.md").exists());
+
+        let mcp = std::fs::read_to_string(out.join(".mcp.json")).unwrap();
+        assert!(mcp.contains(r#""GITHUB_TOKEN": "${GITHUB_TOKEN}""#));
+        assert!(mcp.contains(r#""X-Api-Key": "${X_API_KEY}""#));
+        assert!(!mcp.contains("ghp_secret"));
+        assert!(!mcp.contains("old"));
+
+        let marketplace: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(out.join(".claude-plugin/marketplace.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(marketplace["owner"]["name"], "acme");
+        assert_eq!(marketplace["plugins"][0]["source"], "./");
+        assert!(out.join(".claude-plugin/plugin.json").exists());
+    }
+}
@@ -1,83 +1,413 @@
-use harness_locate::{Harness, HarnessKind};
+use std::io::IsTerminal;
+
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use dialoguer_multiselect::{Confirm, Password, Select};
 use serde::Serialize;
 
+use crate::cli::health::{ProfileHealth, assess};
 use crate::cli::output::{ResolvedFormat, output, output_list};
-use crate::config::{BridleConfig, ProfileManager, ProfileName};
-use crate::display::{ProfileNode, SectionKind, nodes_to_text, profile_to_nodes};
+use crate::config::{
+    BridleConfig, DriftAction, DriftChange, Notifier, ProfileManager, ProfileManagerBuilder,
+    ProfileName, ProfileSettings, SecretResolver, SwitchStrategy, format_size,
+};
+use crate::display::{
+    ProfileNode, SectionKind, nodes_to_text, profile_to_html, profile_to_markdown,
+    profile_to_nodes, render_markdown,
+};
 use crate::error::{Error, Result};
-use crate::harness::HarnessConfig;
+use crate::harness::check::{self as config_check, CheckOutcome};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+use crate::install::filter::{glob_match, is_glob};
 
 #[derive(Serialize)]
 struct ProfileListEntry {
     name: String,
     harness_id: String,
     is_active: bool,
+    tags: Vec<String>,
+    switches: u64,
+    last_used: Option<DateTime<Utc>>,
+    /// Bytes a switch would copy.
+    size: u64,
+    skills: usize,
 }
 
-fn resolve_harness(name: &str) -> Result<Harness> {
-    let kind = match name {
-        "claude-code" | "claude" | "cc" => HarnessKind::ClaudeCode,
-        "opencode" | "oc" => HarnessKind::OpenCode,
-        "goose" => HarnessKind::Goose,
-        "amp-code" | "amp" | "ampcode" => HarnessKind::AmpCode,
-        _ => return Err(Error::UnknownHarness(name.to_string())),
-    };
-    Ok(Harness::new(kind))
+/// Order of `bridle profile list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProfileSort {
+    /// Alphabetical.
+    Name,
+    /// Most recently switched to first; never-used profiles last.
+    Recent,
+    /// Largest first.
+    Size,
+    /// Most skills first.
+    Skills,
 }
 
-fn get_manager() -> Result<ProfileManager> {
-    let profiles_dir = BridleConfig::profiles_dir()?;
-    Ok(ProfileManager::new(profiles_dir))
+/// Document format for `bridle profile show --format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
 }
 
-pub fn list_profiles(harness_name: &str, format: ResolvedFormat) -> Result<()> {
-    let harness = resolve_harness(harness_name)?;
-    let manager = get_manager()?;
+/// What `bridle profile switch --on-drift` does with files changed in the live config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnDrift {
+    /// Save the changes into the active profile.
+    Save,
+    /// Drop the changes.
+    Discard,
+    /// Copy the changed files to a stash directory, then drop them.
+    Stash,
+    /// Cancel the switch.
+    Abort,
+}
+
+impl From<OnDrift> for DriftAction {
+    fn from(on_drift: OnDrift) -> Self {
+        match on_drift {
+            OnDrift::Save => Self::Save,
+            OnDrift::Discard => Self::Discard,
+            OnDrift::Stash => Self::Stash,
+            OnDrift::Abort => Self::Abort,
+        }
+    }
+}
+
+/// `bridle profile switch --strategy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Strategy {
+    /// Replace the live config with the profile.
+    Replace,
+    /// Lay the profile over the live config, keeping files it does not have.
+    Merge,
+    /// Sync only the paths the harness declares and leave the rest alone.
+    Known,
+}
+
+impl From<Strategy> for SwitchStrategy {
+    fn from(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::Replace => Self::Replace,
+            Strategy::Merge => Self::Merge,
+            Strategy::Known => Self::Known,
+        }
+    }
+}
 
-    let active_profile: Option<String> = BridleConfig::load()
+fn resolve_harness(name: &str) -> Result<Box<dyn HarnessConfig>> {
+    HarnessRegistry::load()?.take(name)
+}
+
+fn parse_profile_name(name: &str) -> Result<ProfileName> {
+    ProfileName::new(name).map_err(|reason| Error::invalid_profile_name(name, reason))
+}
+
+/// The one profile `pattern` names, which may be a glob matching exactly one.
+fn resolve_profile(
+    manager: &ProfileManager,
+    harness: &dyn HarnessConfig,
+    pattern: &str,
+) -> Result<ProfileName> {
+    let mut names = manager.resolve_profiles(harness, pattern)?;
+    if names.len() > 1 {
+        let names: Vec<&str> = names.iter().map(ProfileName::as_str).collect();
+        return Err(Error::Config(format!(
+            "'{pattern}' matches {} profiles ({}); name one",
+            names.len(),
+            names.join(", ")
+        )));
+    }
+    Ok(names.remove(0))
+}
+
+pub(crate) fn manager_builder() -> Result<ProfileManagerBuilder> {
+    let config = BridleConfig::load()?;
+    Ok(ProfileManager::builder()
+        .settings(config.settings())
+        .observer(Notifier::new(&config))
+        .secret_store(SecretResolver::new(&config))
+        .var_prompt(prompt_var))
+}
+
+fn get_manager() -> Result<ProfileManager> {
+    manager_builder()?.build()
+}
+
+/// Asks on the terminal for a profile template variable. Never prompts when
+/// stdin is not a terminal, so scripts get a missing-variable error instead.
+pub(crate) fn prompt_var(name: &str) -> Option<String> {
+    if !std::io::stdin().is_terminal() {
+        return None;
+    }
+    Password::new()
+        .with_prompt(format!("Value for {name}"))
+        .allow_empty_password(true)
+        .interact()
         .ok()
-        .and_then(|c| c.active_profile_for(harness.id()).map(|s| s.to_string()));
+}
 
-    let profiles = manager.list_profiles(&harness)?;
-    let entries: Vec<ProfileListEntry> = profiles
-        .iter()
-        .map(|p| ProfileListEntry {
-            name: p.to_string(),
-            harness_id: harness.id().to_string(),
-            is_active: active_profile
-                .as_ref()
-                .map(|a| a == &p.to_string())
-                .unwrap_or(false),
-        })
-        .collect();
+/// Asks on the terminal what to do with a file changed since the last switch.
+/// Never prompts when stdin is not a terminal, so scripts keep saving changes.
+fn prompt_drift(path: &str, change: DriftChange) -> Option<DriftAction> {
+    if !std::io::stdin().is_terminal() {
+        return None;
+    }
+    let change = match change {
+        DriftChange::Modified => "modified",
+        DriftChange::Added => "added",
+        DriftChange::Removed => "removed",
+    };
+    let actions = [
+        DriftAction::Save,
+        DriftAction::Discard,
+        DriftAction::Stash,
+        DriftAction::Abort,
+    ];
+    let choice = Select::new()
+        .with_prompt(format!("{path} was {change} since the last switch"))
+        .items([
+            "Save into the active profile",
+            "Discard",
+            "Stash a copy, then discard",
+            "Abort the switch",
+        ])
+        .default(0)
+        .interact_opt()
+        .ok()?;
+    // Esc cancels the switch rather than silently saving.
+    Some(choice.map_or(DriftAction::Abort, |i| actions[i]))
+}
+
+/// Lists the profiles of `harness_name`, or of every harness, optionally only
+/// those carrying all of `tags` and named to match the glob `pattern`.
+pub fn list_profiles(
+    harness_name: Option<&str>,
+    sort: ProfileSort,
+    tags: &[String],
+    pattern: Option<&str>,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let pattern = pattern.map(str::to_lowercase);
+    let registry = HarnessRegistry::load()?;
+    let harnesses: Vec<&dyn HarnessConfig> = match harness_name {
+        Some(name) => vec![registry.resolve(name)?.harness()],
+        None => registry.iter().map(|entry| entry.harness()).collect(),
+    };
+    let manager = get_manager()?;
+    let config = BridleConfig::load().ok();
+
+    let mut entries = Vec::new();
+    for harness in &harnesses {
+        let active_profile = config
+            .as_ref()
+            .and_then(|c| c.active_profile_for(harness.id()));
+        let mut usage = config
+            .as_ref()
+            .map(|c| c.profile_usage(harness.id()))
+            .unwrap_or_default();
+        for name in manager.list_profiles(*harness)? {
+            if pattern
+                .as_deref()
+                .is_some_and(|pattern| !glob_match(pattern, name.as_str()))
+            {
+                continue;
+            }
+            let path = manager.profile_path(*harness, &name);
+            let profile_tags = ProfileSettings::load(&path)
+                .map(|settings| settings.tags)
+                .unwrap_or_default();
+            if !tags.iter().all(|tag| profile_tags.contains(tag)) {
+                continue;
+            }
+            let usage = usage.remove(name.as_str()).unwrap_or_default();
+            entries.push(ProfileListEntry {
+                name: name.to_string(),
+                harness_id: harness.id().to_string(),
+                is_active: active_profile == Some(name.as_str()),
+                tags: profile_tags,
+                switches: usage.switches,
+                last_used: usage.last_used,
+                size: manager.profile_size(*harness, &name),
+                skills: manager
+                    .show_profile(*harness, &name)
+                    .map(|info| info.skills.items.len())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    // Stable sorts keep name order among equal keys.
+    match sort {
+        ProfileSort::Name => {}
+        ProfileSort::Recent => entries.sort_by_key(|e| std::cmp::Reverse(e.last_used)),
+        ProfileSort::Size => entries.sort_by_key(|e| std::cmp::Reverse(e.size)),
+        ProfileSort::Skills => entries.sort_by_key(|e| std::cmp::Reverse(e.skills)),
+    }
 
     output_list(&entries, format, |entries| {
         if entries.is_empty() {
-            println!("No profiles found for {}", harness.id());
-        } else {
-            println!("Profiles for {}:", harness.id());
-            for entry in entries {
-                let active = if entry.is_active { " (active)" } else { "" };
-                println!("  {}{}", entry.name, active);
+            match harness_name {
+                Some(_) if tags.is_empty() && pattern.is_none() => {
+                    println!("No profiles found for {}", harnesses[0].id())
+                }
+                _ => println!("No profiles found"),
             }
+        } else {
+            print_profile_table(entries);
         }
     });
     Ok(())
 }
 
-pub fn show_profile(harness_name: &str, profile_name: &str, format: ResolvedFormat) -> Result<()> {
+fn print_profile_table(entries: &[ProfileListEntry]) {
+    let header = [
+        "NAME",
+        "HARNESS",
+        "ACTIVE",
+        "TAGS",
+        "LAST SWITCHED",
+        "SIZE",
+        "SKILLS",
+    ];
+    let rows: Vec<[String; 7]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.name.clone(),
+                entry.harness_id.clone(),
+                if entry.is_active { "*" } else { "" }.to_string(),
+                entry.tags.join(","),
+                entry.last_used.map_or_else(
+                    || "never".to_string(),
+                    |at| at.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+                ),
+                format_size(entry.size),
+                entry.skills.to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[&str]| {
+        let mut line = String::new();
+        for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+            // Size and skill counts are numbers, so right-align them.
+            let cell = if i >= 5 {
+                format!("{cell:>width$}")
+            } else {
+                format!("{cell:<width$}")
+            };
+            line.push_str(&cell);
+            line.push_str("  ");
+        }
+        println!("{}", line.trim_end());
+    };
+    line(&header);
+    for row in &rows {
+        line(&row.each_ref().map(String::as_str));
+    }
+}
+
+#[derive(Serialize)]
+struct ShownProfile {
+    #[serde(flatten)]
+    info: crate::config::ProfileInfo,
+    health: ProfileHealth,
+}
+
+/// Shows `profile_name` of `harness_name` with its health score; `explain`
+/// lists the issues behind the score.
+pub fn show_profile(
+    harness_name: &str,
+    profile_name: &str,
+    report: Option<ReportFormat>,
+    explain: bool,
+    format: ResolvedFormat,
+) -> Result<()> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
     let manager = get_manager()?;
+    let name = resolve_profile(&manager, harness.as_ref(), profile_name)?;
+
+    let info = manager.show_profile(harness.as_ref(), &name)?;
+    match report {
+        Some(ReportFormat::Markdown) => print!("{}", profile_to_markdown(&info)),
+        Some(ReportFormat::Html) => print!("{}", profile_to_html(&info)),
+        None => {
+            let health = assess(&manager, harness.as_ref(), &info);
+            let shown = ShownProfile { info, health };
+            output(&shown, format, |shown| {
+                print_profile_text(&shown.info, &shown.health, explain, harness.as_ref())
+            })
+        }
+    }
+    Ok(())
+}
+
+/// Shows the active profile of `harness_name` as `profile show` does.
+pub fn show_active(harness_name: &str, format: ResolvedFormat) -> Result<()> {
+    let harness = resolve_harness(harness_name)?;
+    let config = BridleConfig::load()?;
+    let Some(active) = config.active_profile_for(harness.id()) else {
+        return Err(Error::Config(format!(
+            "no active profile for {}; use --live to read its live config",
+            harness.id()
+        )));
+    };
+    show_profile(harness.id(), active, None, false, format)
+}
 
-    let info = manager.show_profile(&harness, &name)?;
-    output(&info, format, |info| print_profile_text(info, &harness));
+/// Shows what is configured in the live config of `harness_name`, read the
+/// same way as a profile, without requiring or creating one.
+pub fn show_live(harness_name: &str, format: ResolvedFormat) -> Result<()> {
+    let harness = resolve_harness(harness_name)?;
+    let info = get_manager()?.show_live(harness.as_ref())?;
+    output(&info, format, |info| {
+        let mut nodes = profile_to_nodes(info);
+        if let Some(header) = nodes.first_mut() {
+            let managed = if info.is_active {
+                format!("profile '{}'", info.name)
+            } else {
+                "(no active profile)".to_string()
+            };
+            *header = ProfileNode::new(SectionKind::Header, "Live config")
+                .with_text(info.path.display().to_string())
+                .with_children(vec![
+                    ProfileNode::new(SectionKind::Field, "Harness").with_text(&info.harness_id),
+                    ProfileNode::new(SectionKind::Field, "Managed by").with_text(managed),
+                ]);
+        }
+        print!("{}", nodes_to_text(&nodes));
+    });
     Ok(())
 }
 
-fn print_profile_text(info: &crate::config::ProfileInfo, harness: &harness_locate::Harness) {
+fn print_profile_text(
+    info: &crate::config::ProfileInfo,
+    health: &ProfileHealth,
+    explain: bool,
+    harness: &dyn HarnessConfig,
+) {
     let mut nodes = profile_to_nodes(info);
+    if let Some(header) = nodes.first_mut() {
+        header
+            .children
+            .push(ProfileNode::new(SectionKind::Field, "Health").with_text(health.summary()));
+    }
+    if explain && !health.issues.is_empty() {
+        let children = health
+            .issues
+            .iter()
+            .map(|issue| ProfileNode::new(SectionKind::Error, "").with_text(issue.to_string()))
+            .collect();
+        nodes.push(ProfileNode::new(SectionKind::Error, "Health issues").with_children(children));
+    }
 
     if info.is_active {
         let marker_exists = harness
@@ -93,16 +423,18 @@ fn print_profile_text(info: &crate::config::ProfileInfo, harness: &harness_locat
         }
     }
 
+    if let Some(readme) = &info.readme {
+        println!("{}", render_markdown(readme));
+    }
     print!("{}", nodes_to_text(&nodes));
 }
 
 pub fn create_profile(harness_name: &str, profile_name: &str) -> Result<()> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
+    let name = parse_profile_name(profile_name)?;
     let manager = get_manager()?;
 
-    let path = manager.create_profile(&harness, &name)?;
+    let path = manager.create_profile(harness.as_ref(), &name)?;
     println!("Created profile: {}", name.as_str());
     println!("Path: {}", path.display());
     Ok(())
@@ -110,36 +442,162 @@ pub fn create_profile(harness_name: &str, profile_name: &str) -> Result<()> {
 
 pub fn create_profile_from_current(harness_name: &str, profile_name: &str) -> Result<()> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
+    let name = parse_profile_name(profile_name)?;
     let manager = get_manager()?;
 
-    let path = manager.create_from_current_with_resources(&harness, Some(&harness), &name)?;
+    let path = manager.create_from_current_with_resources(
+        harness.as_ref(),
+        harness.as_builtin(),
+        &name,
+    )?;
     println!("Created profile from current config: {}", name.as_str());
     println!("Path: {}", path.display());
     Ok(())
 }
 
-pub fn delete_profile(harness_name: &str, profile_name: &str) -> Result<()> {
+/// Deletes the profile named by `profile_name`, or every profile matching it
+/// as a glob, after one confirmation.
+/// Deletes the profiles `profile_name` names. A glob leaves the active
+/// profile alone, and deleting several profiles without a prompt on a
+/// terminal takes `yes`.
+pub fn delete_profile(harness_name: &str, profile_name: &str, yes: bool) -> Result<()> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
     let manager = get_manager()?;
+    let mut names = manager.resolve_profiles(harness.as_ref(), profile_name)?;
 
-    manager.delete_profile(&harness, &name)?;
-    println!("Deleted profile: {}", name.as_str());
+    if let Some(missing) = names
+        .iter()
+        .find(|name| !manager.profile_exists(harness.as_ref(), name))
+    {
+        return Err(Error::profile_not_found(harness.id(), missing.as_str()));
+    }
+
+    let config = BridleConfig::load()?;
+    if is_glob(profile_name)
+        && let Some(active) = config.active_profile_for(harness.id())
+        && let Some(index) = names.iter().position(|name| name.as_str() == active)
+    {
+        names.remove(index);
+        println!("Skipped active profile: {active}");
+        if names.is_empty() {
+            return Ok(());
+        }
+    }
+
+    let prompt = !yes && config.settings().confirm && std::io::stdin().is_terminal();
+    if names.len() > 1 && !prompt && !yes {
+        let listed: Vec<&str> = names.iter().map(ProfileName::as_str).collect();
+        return Err(Error::Config(format!(
+            "'{profile_name}' matches {} profiles ({}); pass --yes to delete them all",
+            listed.len(),
+            listed.join(", ")
+        )));
+    }
+    if prompt {
+        let listed: Vec<&str> = names.iter().map(ProfileName::as_str).collect();
+        let prompt = match listed.as_slice() {
+            [name] => format!("Delete profile '{name}'?"),
+            _ => format!("Delete {} profiles ({})?", listed.len(), listed.join(", ")),
+        };
+        let proceed = Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()
+            .map_err(|e| Error::Command(e.to_string()))?;
+        if !proceed {
+            println!("Delete cancelled");
+            return Ok(());
+        }
+    }
+
+    for name in &names {
+        manager.delete_profile(harness.as_ref(), name)?;
+        println!("Deleted profile: {}", name.as_str());
+    }
+    Ok(())
+}
+
+pub fn prune_profiles(harness_name: &str, days: u32, delete: bool) -> Result<()> {
+    let harness = resolve_harness(harness_name)?;
+    let manager = get_manager()?;
+    let config = BridleConfig::load()?;
+    let active = config.active_profile_for(harness.id());
+    let usage = config.profile_usage(harness.id());
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+
+    let mut stale = Vec::new();
+    for name in manager.list_profiles(harness.as_ref())? {
+        if active == Some(name.as_str()) {
+            continue;
+        }
+        let last_used = usage.get(name.as_str()).and_then(|u| u.last_used);
+        // Profiles never switched to are judged by when their files last changed.
+        let seen = last_used.or_else(|| {
+            std::fs::metadata(manager.profile_path(harness.as_ref(), &name))
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from)
+        });
+        if seen.is_none_or(|seen| seen < cutoff) {
+            let note = match (last_used, seen) {
+                (Some(at), _) => {
+                    format!("last used {}", at.with_timezone(&Local).format("%Y-%m-%d"))
+                }
+                (None, Some(at)) => format!(
+                    "never switched to, modified {}",
+                    at.with_timezone(&Local).format("%Y-%m-%d")
+                ),
+                (None, None) => "never switched to".to_string(),
+            };
+            stale.push((name, note));
+        }
+    }
+
+    if stale.is_empty() {
+        println!(
+            "All {} profiles were used in the last {days} days",
+            harness.id()
+        );
+        return Ok(());
+    }
+    println!("Not used in {days} days:");
+    for (name, note) in &stale {
+        println!("  {} ({note})", name.as_str());
+    }
+    if !delete {
+        println!(
+            "Run `bridle profile prune {} --days {days} --delete` to remove them",
+            harness.id()
+        );
+        return Ok(());
+    }
+
+    if config.settings().confirm && std::io::stdin().is_terminal() {
+        let proceed = Confirm::new()
+            .with_prompt(format!("Delete {} profile(s)?", stale.len()))
+            .default(false)
+            .interact()
+            .map_err(|e| Error::Command(e.to_string()))?;
+        if !proceed {
+            println!("Prune cancelled");
+            return Ok(());
+        }
+    }
+    for (name, _) in &stale {
+        manager.delete_profile(harness.as_ref(), name)?;
+        println!("Deleted profile: {}", name.as_str());
+    }
     Ok(())
 }
 
 pub fn edit_profile(harness_name: &str, profile_name: &str) -> Result<()> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
     let manager = get_manager()?;
+    let name = resolve_profile(&manager, harness.as_ref(), profile_name)?;
 
-    let profile_path = manager.profile_path(&harness, &name);
+    let profile_path = manager.profile_path(harness.as_ref(), &name);
     if !profile_path.exists() {
-        return Err(Error::ProfileNotFound(profile_name.to_string()));
+        return Err(Error::profile_not_found(harness.id(), profile_name));
     }
 
     let config = crate::config::BridleConfig::load().unwrap_or_default();
@@ -177,25 +635,23 @@ pub fn diff_profiles(
     other_name: Option<&str>,
 ) -> Result<()> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
     let manager = get_manager()?;
+    let name = resolve_profile(&manager, harness.as_ref(), profile_name)?;
 
-    let profile_path = manager.profile_path(&harness, &name);
+    let profile_path = manager.profile_path(harness.as_ref(), &name);
     if !profile_path.exists() {
-        return Err(Error::ProfileNotFound(profile_name.to_string()));
+        return Err(Error::profile_not_found(harness.id(), profile_name));
     }
 
     let other_path = if let Some(other) = other_name {
-        let other_name =
-            ProfileName::new(other).map_err(|_| Error::InvalidProfileName(other.to_string()))?;
-        let path = manager.profile_path(&harness, &other_name);
+        let other_name = resolve_profile(&manager, harness.as_ref(), other)?;
+        let path = manager.profile_path(harness.as_ref(), &other_name);
         if !path.exists() {
-            return Err(Error::ProfileNotFound(other.to_string()));
+            return Err(Error::profile_not_found(harness.id(), other));
         }
         path
     } else {
-        harness.config(&harness_locate::Scope::Global)?
+        harness.config_dir()?
     };
 
     let status = std::process::Command::new("diff")
@@ -212,29 +668,85 @@ pub fn diff_profiles(
     Ok(())
 }
 
-pub fn switch_profile(harness_name: &str, profile_name: &str) -> Result<()> {
+pub fn switch_profile(
+    harness_name: &str,
+    profile_name: &str,
+    on_drift: Option<OnDrift>,
+    strategy: Option<Strategy>,
+    check: bool,
+) -> Result<i32> {
     let harness = resolve_harness(harness_name)?;
-    let name = ProfileName::new(profile_name)
-        .map_err(|_| Error::InvalidProfileName(profile_name.to_string()))?;
-    let manager = get_manager()?;
+    let mut builder = match on_drift {
+        Some(action) => manager_builder()?.on_drift(action.into()),
+        None => manager_builder()?.drift_prompt(prompt_drift),
+    };
+    if let Some(strategy) = strategy {
+        builder = builder.force_switch_strategy(strategy.into());
+    }
+    let manager = builder.build()?;
+    let name = resolve_profile(&manager, harness.as_ref(), profile_name)?;
 
-    if !manager.profile_exists(&harness, &name) {
-        return Err(Error::ProfileNotFound(profile_name.to_string()));
+    if !manager.profile_exists(harness.as_ref(), &name) {
+        return Err(Error::profile_not_found(harness.id(), profile_name));
     }
 
     let harness_id = harness.id();
 
-    match manager.backup_current(&harness) {
-        Ok(backup_path) => {
+    match manager.backup_current(harness.as_ref()) {
+        Ok(Some(backup_path)) => {
             println!("Backed up current config to: {}", backup_path.display());
         }
+        Ok(None) => {}
         Err(e) => {
             println!("Warning: Could not backup current config: {e}");
         }
     }
 
-    manager.switch_profile_with_resources(&harness, Some(&harness), &name)?;
+    manager.switch_profile_with_resources(harness.as_ref(), harness.as_builtin(), &name)?;
     println!("Switched to profile: {}", name.as_str());
     println!("Harness: {harness_id}");
-    Ok(())
+
+    let check = check
+        || BridleConfig::load()
+            .map(|c| c.settings().check_after_switch)
+            .unwrap_or(false);
+    if !check {
+        return Ok(0);
+    }
+    let Some(run) = config_check::run(harness.as_ref()) else {
+        eprintln!(
+            "Warning: {harness_id} has no config check; set `check` under [harness.{harness_id}]"
+        );
+        return Ok(0);
+    };
+    match &run.outcome {
+        CheckOutcome::Passed => println!("Check passed: {}", run.command),
+        CheckOutcome::Unavailable { reason } => {
+            eprintln!("Warning: Could not check the config: {reason}");
+        }
+        CheckOutcome::Failed { status, output } => {
+            let status = status.map_or("a signal".to_string(), |s| format!("status {s}"));
+            eprintln!("Check failed: `{}` exited with {status}", run.command);
+            if !output.is_empty() {
+                eprintln!("{output}");
+            }
+        }
+        CheckOutcome::TimedOut { output } => {
+            eprintln!(
+                "Check failed: `{}` did not finish within {}s",
+                run.command,
+                config_check::TIMEOUT.as_secs()
+            );
+            if !output.is_empty() {
+                eprintln!("{output}");
+            }
+        }
+    }
+    if run.failed() {
+        eprintln!(
+            "The profile is switched; `bridle profile switch {harness_id} <previous>` goes back."
+        );
+        return Ok(1);
+    }
+    Ok(0)
 }
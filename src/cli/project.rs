@@ -0,0 +1,333 @@
+//! `bridle project`: snapshots of the harness config kept in a repository.
+//!
+//! Harnesses also read config from the project they run in: `.claude/` and
+//! `.mcp.json` for Claude Code, `.opencode/` for OpenCode, `.goose/` for
+//! Goose. `snapshot` copies whichever of these a repository has to
+//! `<config>/projects/<name>/`, and `apply` puts them back, in the same
+//! repository or another one. Nothing outside those entries is touched.
+
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use dialoguer_multiselect::Confirm;
+use harness_locate::Scope;
+use serde::{Deserialize, Serialize};
+
+use super::output::{ResolvedFormat, output, output_list};
+use super::package::copy_tree;
+use crate::config::{BridleConfig, Policy, ProfileName, Violation, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+
+/// Written next to the copied entries.
+const SNAPSHOT_FILE: &str = "snapshot.toml";
+
+/// What a snapshot holds and where it was taken.
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(skip_deserializing)]
+    name: String,
+    /// Repository the snapshot was taken in.
+    source: PathBuf,
+    taken_at: DateTime<Utc>,
+    /// Copied paths, relative to the repository root.
+    entries: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ApplyReport {
+    name: String,
+    path: PathBuf,
+    /// Entries that existed in the repository and were replaced.
+    replaced: Vec<String>,
+    /// Entries that did not exist before.
+    added: Vec<String>,
+}
+
+/// Where harnesses keep project-scoped config, relative to the project root.
+fn project_entries(registry: &HarnessRegistry, root: &Path) -> Vec<String> {
+    let scope = Scope::Project(root.to_path_buf());
+    let mut entries = Vec::new();
+    for harness in registry.iter().filter_map(|e| e.harness().as_builtin()) {
+        // Harnesses without a project scope (Amp) report an error here.
+        let Ok(dir) = harness.config(&scope) else {
+            continue;
+        };
+        let mcp = harness.mcp(&scope).ok().flatten().map(|mcp| mcp.file);
+        let paths = std::iter::once(dir.clone()).chain(mcp.filter(|file| !file.starts_with(&dir)));
+        for path in paths {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let relative = relative.to_string_lossy().into_owned();
+                if !entries.contains(&relative) {
+                    entries.push(relative);
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn snapshots_dir() -> Result<PathBuf> {
+    Ok(BridleConfig::config_dir()?.join("projects"))
+}
+
+fn repository_root(path: &Path) -> Result<PathBuf> {
+    path.canonicalize()
+        .ok()
+        .filter(|root| root.is_dir())
+        .ok_or_else(|| Error::Config(format!("{} is not a directory", path.display())))
+}
+
+fn parse_name(name: &str) -> Result<ProfileName> {
+    ProfileName::new(name).map_err(|reason| Error::invalid_profile_name(name, reason))
+}
+
+fn load(name: &ProfileName) -> Result<(PathBuf, Snapshot)> {
+    let dir = snapshots_dir()?.join(name.as_str());
+    let file = dir.join(SNAPSHOT_FILE);
+    if !file.is_file() {
+        return Err(Error::Config(format!(
+            "no project snapshot named '{name}'; run `bridle project list` to see them"
+        )));
+    }
+    let mut snapshot: Snapshot =
+        toml::from_str(&std::fs::read_to_string(&file)?).map_err(|e| Error::ConfigParse {
+            path: file,
+            message: e.to_string(),
+        })?;
+    snapshot.name = name.as_str().to_string();
+    Ok((dir, snapshot))
+}
+
+/// Removes `path`, file or directory, if it exists.
+fn remove_entry(path: &Path) -> Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path)?,
+        Ok(_) => std::fs::remove_file(path)?,
+        Err(_) => {}
+    }
+    Ok(())
+}
+
+/// Copies `src`, file or directory, to `dst`.
+fn copy_entry(src: &Path, dst: &Path) -> Result<()> {
+    if src.is_dir() {
+        copy_tree(src, dst)?;
+    } else {
+        if let Some(parent) = dst.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(src, dst)?;
+    }
+    Ok(())
+}
+
+/// Refuses the snapshot in `dir` if an MCP server or skill in any harness's
+/// project config breaks the team policy, naming every violation.
+fn check_policy(config: &BridleConfig, registry: &HarnessRegistry, dir: &Path) -> Result<()> {
+    let Some(policy) = Policy::from_settings(config.settings())? else {
+        return Ok(());
+    };
+    let scope = Scope::Project(dir.to_path_buf());
+    let mut found = Vec::new();
+    for entry in registry.iter() {
+        let harness = entry.harness();
+        let Some(builtin) = harness.as_builtin() else {
+            continue;
+        };
+        if let Ok(Some(mcp)) = builtin.mcp(&scope)
+            && let Ok(content) = std::fs::read_to_string(&mcp.file)
+        {
+            let file_name = mcp.file.file_name().unwrap_or_default().to_string_lossy();
+            for (name, _) in harness.parse_mcp_servers(&content, &file_name)? {
+                found.push(policy.check_mcp_server(&name));
+            }
+        }
+        if let Ok(Some(skills)) = builtin.skills(&scope)
+            && let Ok(entries) = std::fs::read_dir(&skills.path)
+        {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = if path.is_dir() {
+                    entry.file_name().to_string_lossy().into_owned()
+                } else if path.extension().is_some_and(|ext| ext == "md") {
+                    path.file_stem()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned()
+                } else {
+                    continue;
+                };
+                found.push(policy.check_skill(&name));
+            }
+        }
+    }
+    let mut violations: Vec<Violation> = Vec::new();
+    for violation in found.into_iter().flatten() {
+        if !violations.contains(&violation) {
+            violations.push(violation);
+        }
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(policy.violated(violations))
+    }
+}
+
+/// Copies the project-scoped harness config in `path` to the snapshot
+/// `name`, replacing an existing snapshot only with `force`.
+pub fn snapshot(name: &str, path: &Path, force: bool, format: ResolvedFormat) -> Result<()> {
+    let name = parse_name(name)?;
+    let root = repository_root(path)?;
+    let registry = HarnessRegistry::load()?;
+    let entries: Vec<String> = project_entries(&registry, &root)
+        .into_iter()
+        .filter(|entry| root.join(entry).exists())
+        .collect();
+    if entries.is_empty() {
+        return Err(Error::Config(format!(
+            "no project harness config in {}",
+            root.display()
+        )));
+    }
+
+    let dir = snapshots_dir()?.join(name.as_str());
+    if dir.exists() {
+        if !force {
+            return Err(Error::Config(format!(
+                "project snapshot '{name}' already exists; pass --force to replace it"
+            )));
+        }
+        std::fs::remove_dir_all(&dir)?;
+    }
+    for entry in &entries {
+        copy_entry(&root.join(entry), &dir.join(entry))?;
+    }
+    let snapshot = Snapshot {
+        name: name.as_str().to_string(),
+        source: root,
+        taken_at: Utc::now(),
+        entries,
+    };
+    let content = toml::to_string(&snapshot).map_err(|e| Error::Config(e.to_string()))?;
+    write_atomic(&dir.join(SNAPSHOT_FILE), content)?;
+
+    output(&snapshot, format, |snapshot| {
+        println!(
+            "Saved {} from {} as project snapshot '{}'",
+            snapshot.entries.join(", "),
+            snapshot.source.display(),
+            snapshot.name
+        );
+    });
+    Ok(())
+}
+
+/// Puts the entries of snapshot `name` into the repository at `path`,
+/// replacing what is there after confirmation on a terminal.
+pub fn apply(name: &str, path: &Path, format: ResolvedFormat) -> Result<()> {
+    let name = parse_name(name)?;
+    let root = repository_root(path)?;
+    let (dir, snapshot) = load(&name)?;
+    let (replaced, added): (Vec<String>, Vec<String>) = snapshot
+        .entries
+        .iter()
+        .cloned()
+        .partition(|entry| root.join(entry).symlink_metadata().is_ok());
+
+    let config = BridleConfig::load()?;
+    check_policy(&config, &HarnessRegistry::load()?, &dir)?;
+    if !replaced.is_empty() && config.settings().confirm && std::io::stdin().is_terminal() {
+        let proceed = Confirm::new()
+            .with_prompt(format!(
+                "Replace {} in {}?",
+                replaced.join(", "),
+                root.display()
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| Error::Command(e.to_string()))?;
+        if !proceed {
+            println!("Apply cancelled");
+            return Ok(());
+        }
+    }
+    for entry in &snapshot.entries {
+        let target = root.join(entry);
+        remove_entry(&target)?;
+        copy_entry(&dir.join(entry), &target)?;
+    }
+
+    let report = ApplyReport {
+        name: snapshot.name,
+        path: root,
+        replaced,
+        added,
+    };
+    output(&report, format, |report| {
+        println!(
+            "Applied project snapshot '{}' to {}",
+            report.name,
+            report.path.display()
+        );
+        if !report.replaced.is_empty() {
+            println!("Replaced: {}", report.replaced.join(", "));
+        }
+        if !report.added.is_empty() {
+            println!("Added: {}", report.added.join(", "));
+        }
+    });
+    Ok(())
+}
+
+/// Lists saved project snapshots.
+pub fn list(format: ResolvedFormat) -> Result<()> {
+    let dir = snapshots_dir()?;
+    let mut snapshots = Vec::new();
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(&dir)? {
+            let file_name = entry?.file_name();
+            let Ok(name) = ProfileName::new(&file_name.to_string_lossy()) else {
+                continue;
+            };
+            if let Ok((_, snapshot)) = load(&name) {
+                snapshots.push(snapshot);
+            }
+        }
+    }
+    snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+    output_list(&snapshots, format, |snapshots| {
+        if snapshots.is_empty() {
+            println!("No project snapshots; create one with `bridle project snapshot <name>`");
+        }
+        for snapshot in snapshots {
+            println!(
+                "{}  {}  ({}, {})",
+                snapshot.name,
+                snapshot.entries.join(", "),
+                snapshot.source.display(),
+                snapshot.taken_at.format("%Y-%m-%d %H:%M")
+            );
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_project_dirs_and_mcp_files_of_builtin_harnesses() {
+        let registry = HarnessRegistry::builtin();
+        let entries = project_entries(&registry, Path::new("/repo"));
+        for expected in [".claude", ".mcp.json", ".opencode", ".goose"] {
+            assert!(
+                entries.iter().any(|e| e == expected),
+                "{expected} missing from {entries:?}"
+            );
+        }
+    }
+}
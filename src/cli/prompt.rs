@@ -0,0 +1,86 @@
+//! `bridle prompt`: the active profile, for shell prompts.
+//!
+//! Reads `config.toml` and the state file directly rather than through
+//! [`BridleConfig::load`](crate::config::BridleConfig::load), which also runs
+//! migrations and writes back; a prompt calls this on every render.
+
+use serde::Deserialize;
+
+use crate::config::{BridleConfig, DEFAULT_CONTEXT, PersistedState, StatePaths, layers};
+use crate::error::Result;
+use crate::harness::registry::{builtin_id, parse_kind};
+
+/// The one field of `config.toml` the prompt needs.
+#[derive(Deserialize, Default)]
+struct ContextOnly {
+    current_context: Option<String>,
+}
+
+/// Context from `--context`/`BRIDLE_CONTEXT`, falling back to `config.toml`.
+fn current_context() -> String {
+    if let Some(context) = layers::all_overrides()
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .find(|o| o.key == "context")
+        .map(|o| o.value)
+        .filter(|v| !v.trim().is_empty())
+    {
+        return context;
+    }
+    BridleConfig::config_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| toml::from_str::<ContextOnly>(&content).ok())
+        .and_then(|c| c.current_context)
+        .unwrap_or_else(|| DEFAULT_CONTEXT.to_string())
+}
+
+/// Text to print for `harness` (or every harness), empty when nothing is active.
+fn render(state: &PersistedState, harness: Option<&str>) -> String {
+    match harness {
+        Some(name) => {
+            let id = parse_kind(name).map(builtin_id).unwrap_or(name);
+            state.active.get(id).cloned().unwrap_or_default()
+        }
+        None => {
+            let mut active: Vec<_> = state.active.iter().collect();
+            active.sort();
+            active
+                .into_iter()
+                .map(|(harness, profile)| format!("{harness}:{profile}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+    }
+}
+
+pub fn run(harness: Option<&str>) -> Result<()> {
+    let paths = StatePaths::resolve()?.for_context(&current_context());
+    let text = render(&PersistedState::load(&paths)?, harness);
+    if !text.is_empty() {
+        println!("{text}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_resolves_aliases_and_lists_all() {
+        let mut state = PersistedState::default();
+        state
+            .active
+            .insert("claude-code".to_string(), "work".to_string());
+        state
+            .active
+            .insert("opencode".to_string(), "home".to_string());
+
+        assert_eq!(render(&state, Some("claude-code")), "work");
+        assert_eq!(render(&state, Some("cc")), "work");
+        assert_eq!(render(&state, Some("goose")), "");
+        assert_eq!(render(&state, None), "claude-code:work opencode:home");
+    }
+}
@@ -0,0 +1,263 @@
+//! `bridle provision`: set up profiles from a manifest, for container startup.
+//!
+//! The manifest declares the profiles a machine should have, what to install
+//! into each, and which one each harness uses:
+//!
+//! ```toml
+//! [[profiles]]
+//! harness = "opencode"
+//! name = "dev"
+//! active = true
+//! install = ["owner/skills", "oci://ghcr.io/org/skills:v1"]
+//!
+//! [profiles.mcp.github]
+//! transport = "stdio"
+//! command = "npx"
+//! args = ["-y", "@modelcontextprotocol/server-github"]
+//! ```
+//!
+//! Nothing is asked. Running it again changes nothing that is already in
+//! place: existing profiles are kept, installed skills and MCP servers are
+//! skipped, and an active profile is not switched to again.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, eyre};
+use harness_locate::McpServer;
+use serde::{Deserialize, Serialize};
+
+use super::install::{SelectedComponents, discover, install_to_target};
+use super::output::{ResolvedFormat, output};
+use super::profile::manager_builder;
+use crate::config::{BridleConfig, Notifier, ProfileName};
+use crate::harness::HarnessRegistry;
+use crate::install::{InstallOptions, InstallTarget};
+
+/// Default manifest, looked up in the current directory.
+pub const MANIFEST_FILE: &str = "bridle.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    #[serde(default)]
+    profiles: Vec<ProfileSpec>,
+}
+
+/// One `[[profiles]]` entry.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileSpec {
+    harness: String,
+    name: String,
+    /// Capture the live config when creating the profile, instead of starting empty.
+    #[serde(default)]
+    from_current: bool,
+    /// Switch the harness to this profile.
+    #[serde(default)]
+    active: bool,
+    /// Sources to install every component of, as for `bridle install`.
+    #[serde(default)]
+    install: Vec<String>,
+    /// MCP servers to add, by name.
+    #[serde(default)]
+    mcp: HashMap<String, McpServer>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Action {
+    Created,
+    Installed,
+    Switched,
+}
+
+/// Something provisioning changed.
+#[derive(Debug, Serialize)]
+struct Step {
+    harness: String,
+    profile: String,
+    action: Action,
+    detail: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ProvisionReport {
+    steps: Vec<Step>,
+    errors: Vec<String>,
+}
+
+/// Brings the profiles declared in `manifest` into place, installing
+/// components into them and switching to the active ones. Setup hooks of
+/// installed skills run only with `allow_hooks`.
+///
+/// # Errors
+/// Fails on an unreadable manifest or unknown harness before changing
+/// anything, and after finishing if any step failed.
+pub fn run(manifest_path: &Path, allow_hooks: bool, format: ResolvedFormat) -> Result<()> {
+    let manifest = load(manifest_path)?;
+    let registry = HarnessRegistry::load()?;
+    let mut specs = Vec::new();
+    for spec in &manifest.profiles {
+        let harness = registry.resolve(&spec.harness)?.harness();
+        let name = ProfileName::new(&spec.name)
+            .map_err(|reason| eyre!("invalid profile '{}': {reason}", spec.name))?;
+        specs.push((harness, name, spec));
+    }
+    for harness in specs.iter().map(|(h, ..)| h.id()) {
+        let active = specs
+            .iter()
+            .filter(|(h, _, spec)| h.id() == harness && spec.active)
+            .count();
+        if active > 1 {
+            return Err(eyre!("{active} profiles of {harness} are marked active"));
+        }
+    }
+
+    let config = BridleConfig::load()?;
+    let manager = manager_builder()?.build()?;
+    let notifier = Notifier::new(&config);
+    let mut report = ProvisionReport::default();
+    let step = |harness: &str, profile: &ProfileName, action, detail: String| Step {
+        harness: harness.to_string(),
+        profile: profile.as_str().to_string(),
+        action,
+        detail,
+    };
+
+    for (harness, name, spec) in &specs {
+        if !manager.profile_exists(*harness, name) {
+            let detail = if spec.from_current {
+                manager.create_from_current_with_resources(*harness, harness.as_builtin(), name)?;
+                "from the current config"
+            } else {
+                manager.create_profile(*harness, name)?;
+                "empty"
+            };
+            report.steps.push(step(
+                harness.id(),
+                name,
+                Action::Created,
+                detail.to_string(),
+            ));
+        }
+
+        let target = InstallTarget {
+            harness: harness.id().to_string(),
+            profile: name.clone(),
+        };
+        let label = format!("{}/{}", target.harness, target.profile);
+        let mut batches = Vec::new();
+        for source in &spec.install {
+            match discover(source) {
+                Ok(discovery) => {
+                    batches.push((source.clone(), SelectedComponents::all(&discovery)))
+                }
+                Err(e) => report.errors.push(format!("{label}: {source}: {e}")),
+            }
+        }
+        if !spec.mcp.is_empty() {
+            batches.push((
+                manifest_path.display().to_string(),
+                SelectedComponents::mcp_servers(spec.mcp.clone()),
+            ));
+        }
+        for (source, selected) in batches {
+            let options = InstallOptions {
+                run_setup: if allow_hooks {
+                    selected.skills_with_hooks()
+                } else {
+                    Default::default()
+                },
+                ..Default::default()
+            };
+            let lines = install_to_target(&selected, &target, &options);
+            notifier.install(&target.harness, target.profile.as_str(), &source, &lines);
+            for line in &lines {
+                if let Some(installed) = line.strip_prefix("+ ") {
+                    report.steps.push(step(
+                        harness.id(),
+                        name,
+                        Action::Installed,
+                        installed.to_string(),
+                    ));
+                } else if line.starts_with('!') {
+                    report.errors.push(format!("{label}: {line}"));
+                }
+            }
+        }
+
+        // Reloaded, as creating a profile from the current config activates it.
+        let active = BridleConfig::load()?
+            .active_profile_for(harness.id())
+            .map(str::to_string);
+        if spec.active && active.as_deref() != Some(name.as_str()) {
+            manager.switch_profile_with_resources(*harness, harness.as_builtin(), name)?;
+            report
+                .steps
+                .push(step(harness.id(), name, Action::Switched, String::new()));
+        }
+    }
+
+    let failed = report.errors.len();
+    output(&report, format, |report| {
+        if report.steps.is_empty() && report.errors.is_empty() {
+            println!("Everything is already provisioned");
+        }
+        for step in &report.steps {
+            let target = format!("{}/{}", step.harness, step.profile);
+            match step.action {
+                Action::Created => println!("Created {target} ({})", step.detail),
+                Action::Installed => println!("{target}: {}", step.detail),
+                Action::Switched => println!("Switched {} to {}", step.harness, step.profile),
+            }
+        }
+        for error in &report.errors {
+            eprintln!("Error: {error}");
+        }
+    });
+    if failed > 0 {
+        return Err(eyre!("{failed} provisioning step(s) failed"));
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Manifest> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| eyre!("cannot read manifest {}: {e}", path.display()))?;
+    toml::from_str(&content).map_err(|e| eyre!("invalid manifest {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profiles_with_sources_and_mcp_servers() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [[profiles]]
+            harness = "opencode"
+            name = "dev"
+            active = true
+            install = ["owner/skills"]
+
+            [profiles.mcp.github]
+            transport = "stdio"
+            command = "npx"
+            args = ["-y", "server-github"]
+            "#,
+        )
+        .unwrap();
+        let [spec] = manifest.profiles.as_slice() else {
+            panic!("expected one profile");
+        };
+        assert!(spec.active && !spec.from_current);
+        assert_eq!(spec.install, ["owner/skills"]);
+        assert!(matches!(spec.mcp["github"], McpServer::Stdio(_)));
+
+        assert!(
+            toml::from_str::<Manifest>("[[profiles]]\nharness = \"oc\"\nnmae = \"x\"\n").is_err()
+        );
+    }
+}
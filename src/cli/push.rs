@@ -0,0 +1,119 @@
+//! `bridle push`: publishing a skills bundle or a profile to an OCI registry.
+//!
+//! A bundle is a directory laid out like a skills repository and is pushed
+//! as is. A profile is exported the way `bridle export --dotfiles` writes it,
+//! as `<harness>/<profile>/` with secrets replaced by templates, so the
+//! artifact never carries credentials and `bridle install oci://...`
+//! imports it like a dotfiles directory.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::dotfiles::export_profile;
+use super::output::{ResolvedFormat, output};
+use super::profile::manager_builder;
+use crate::config::{ProfileManager, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry};
+use crate::install::oci::{self, Artifact, ArtifactKind, OciError, OciReference};
+
+/// What was pushed.
+#[derive(Debug, Serialize)]
+struct PushReport {
+    reference: String,
+    digest: String,
+    kind: &'static str,
+    files: usize,
+    /// Variables that must be set when the profile is installed.
+    secrets: Vec<String>,
+}
+
+/// Pushes the bundle directory `from`, or else the `(harness, profile)`
+/// profile, to the registry `reference`.
+pub fn run(
+    reference: &str,
+    from: Option<&Path>,
+    profile: Option<(&str, &str)>,
+    format: ResolvedFormat,
+) -> Result<()> {
+    let reference = OciReference::parse(reference).map_err(oci_error)?;
+    let mut secrets = Vec::new();
+    let artifact = match (from, profile) {
+        (Some(dir), _) => {
+            if !dir.is_dir() {
+                return Err(Error::Config(format!(
+                    "{} is not a directory",
+                    dir.display()
+                )));
+            }
+            Artifact::from_dir(ArtifactKind::Skills, dir).map_err(oci_error)?
+        }
+        (None, Some((harness, profile))) => {
+            let harness = HarnessRegistry::load()?.take(harness)?;
+            let name = ProfileName::new(profile)
+                .map_err(|reason| Error::invalid_profile_name(profile, reason))?;
+            let manager = manager_builder()?.build()?;
+            if !manager.profile_exists(harness.as_ref(), &name) {
+                return Err(Error::profile_not_found(harness.id(), profile));
+            }
+            let (artifact, names) = profile_artifact(&manager, harness.as_ref(), &name)?;
+            secrets = names;
+            artifact
+        }
+        (None, None) => {
+            return Err(Error::Config(
+                "pass --from <dir> or --harness and --profile".to_string(),
+            ));
+        }
+    };
+    if artifact.files.is_empty() {
+        return Err(Error::Config("nothing to push: no files found".to_string()));
+    }
+
+    let digest = oci::push(&reference, &artifact).map_err(oci_error)?;
+    let report = PushReport {
+        reference: reference.to_string(),
+        digest,
+        kind: artifact.kind.as_str(),
+        files: artifact.files.len(),
+        secrets,
+    };
+    output(&report, format, |report| {
+        println!(
+            "Pushed {} ({} file(s)) to {}",
+            report.kind, report.files, report.reference
+        );
+        println!("Digest: {}", report.digest);
+        if !report.secrets.is_empty() {
+            println!("Secrets were replaced with placeholders; set these before installing:");
+            for name in &report.secrets {
+                println!("  {name}");
+            }
+        }
+        println!("Install with `bridle install oci://{}`", report.reference);
+    });
+    Ok(())
+}
+
+/// Profile `name` of `harness` as an artifact, exported the way
+/// `bridle export --dotfiles` writes it, with the secret variables it needs.
+pub(crate) fn profile_artifact(
+    manager: &ProfileManager,
+    harness: &dyn HarnessConfig,
+    name: &ProfileName,
+) -> Result<(Artifact, Vec<String>)> {
+    let staging = oci::scratch_dir("push")?;
+    let prefix = format!("{}_{}", harness.id(), name.as_str());
+    let secrets = export_profile(
+        &manager.profile_path(harness, name),
+        &staging.path().join(harness.id()).join(name.as_str()),
+        &prefix,
+    )?;
+    let artifact = Artifact::from_dir(ArtifactKind::Profile, staging.path()).map_err(oci_error)?;
+    Ok((artifact, secrets))
+}
+
+pub(crate) fn oci_error(e: OciError) -> Error {
+    Error::Command(e.to_string())
+}
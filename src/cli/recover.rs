@@ -0,0 +1,83 @@
+//! Recovery from interrupted profile switches.
+
+use std::io::IsTerminal;
+
+use dialoguer_multiselect::Confirm;
+
+use crate::cli::profile::manager_builder;
+use crate::config::{ProfileManager, RecoveryAction, SwitchJournal};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+
+fn describe(journal: &SwitchJournal) -> String {
+    format!(
+        "{}: {} -> {} (started {})",
+        journal.harness,
+        journal.from.as_deref().unwrap_or("(none)"),
+        journal.to,
+        journal.started_at
+    )
+}
+
+/// Warns on stderr when a previous switch was interrupted. Never fails.
+pub fn warn_if_interrupted() {
+    let Ok(pending) = ProfileManager::pending_switches() else {
+        return;
+    };
+    if pending.is_empty() {
+        return;
+    }
+    eprintln!("Warning: a profile switch was interrupted:");
+    for journal in &pending {
+        eprintln!("  {}", describe(journal));
+    }
+    eprintln!("Run `bridle recover` to complete or roll it back.");
+}
+
+pub fn run(action: Option<RecoveryAction>) -> Result<()> {
+    let pending = ProfileManager::pending_switches()?;
+    if pending.is_empty() {
+        println!("No interrupted profile switch found");
+        return Ok(());
+    }
+
+    let manager = manager_builder()?.build()?;
+    let registry = HarnessRegistry::load()?;
+
+    for journal in pending {
+        let summary = describe(&journal);
+        let action = match action {
+            Some(action) => action,
+            None => prompt(&journal)?,
+        };
+        let harness = registry.resolve(&journal.harness)?.harness();
+        manager.recover(harness, journal, action)?;
+        match action {
+            RecoveryAction::Complete => println!("Completed {summary}"),
+            RecoveryAction::RollBack => println!("Rolled back {summary}"),
+        }
+    }
+    Ok(())
+}
+
+fn prompt(journal: &SwitchJournal) -> Result<RecoveryAction> {
+    if !std::io::stdin().is_terminal() {
+        return Err(Error::Command(
+            "interrupted switch found; pass --complete or --rollback".to_string(),
+        ));
+    }
+    println!("Interrupted switch: {}", describe(journal));
+    let complete = Confirm::new()
+        .with_prompt(format!(
+            "Complete the switch to '{}'? (no rolls back)",
+            journal.to
+        ))
+        .default(true)
+        .interact()
+        .map_err(|e| Error::Command(e.to_string()))?;
+    Ok(if complete {
+        RecoveryAction::Complete
+    } else {
+        RecoveryAction::RollBack
+    })
+}
@@ -0,0 +1,29 @@
+//! `bridle --host`: run a command with another machine's bridle over SSH.
+
+use std::io::IsTerminal;
+use std::process::Command;
+
+use crate::config::{BridleConfig, Remote, strip_host_flag};
+use crate::error::{Error, Result};
+
+/// Runs this invocation, minus `--host`, on `target` and returns the remote
+/// bridle's exit code.
+pub fn run(target: &str) -> Result<i32> {
+    let config = BridleConfig::load()?;
+    let remote = Remote::resolve(&config, target);
+    let args = strip_host_flag(std::env::args_os().skip(1));
+    let tty = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let (program, ssh_args) = remote.command(&args, tty)?;
+    let status = Command::new(&program)
+        .args(&ssh_args)
+        .status()
+        .map_err(|e| Error::Command(format!("failed to run {program}: {e}")))?;
+    // The remote shell's "command not found".
+    if status.code() == Some(127) {
+        eprintln!(
+            "Hint: bridle must be installed on {}; set `bridle` under [remotes.<name>] if it is not on the remote PATH",
+            remote.host
+        );
+    }
+    Ok(status.code().unwrap_or(1))
+}
@@ -0,0 +1,91 @@
+//! `bridle rules explain`: where each part of an assembled rules file comes from.
+//!
+//! A profile with a `rules.d/` directory gets its `CLAUDE.md` or `AGENTS.md`
+//! assembled from the snippets there on switch. This lists every section of
+//! that file with its line and the snippet that contributed it.
+
+use serde::Serialize;
+
+use crate::cli::output::{ResolvedFormat, output};
+use crate::config::rules::{RULES_DIR, RulesSection, RulesSnippets};
+use crate::config::{BridleConfig, ProfileManager, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+use crate::harness::schema::rules_file;
+
+#[derive(Debug, Serialize)]
+struct RulesExplanation {
+    harness: String,
+    profile: String,
+    rules_file: String,
+    snippets: Vec<String>,
+    sections: Vec<RulesSection>,
+}
+
+/// Explains how `profile` of `harness`, or its active profile, assembles
+/// its rules file from `rules.d/`.
+pub fn explain(harness: &str, profile: Option<&str>, format: ResolvedFormat) -> Result<()> {
+    let harness = HarnessRegistry::load()?.take(harness)?;
+    let config = BridleConfig::load()?;
+    let name = match profile {
+        Some(name) => name.to_string(),
+        None => config
+            .active_profile_for(harness.id())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no active profile for {}; name the profile to explain",
+                    harness.id()
+                ))
+            })?,
+    };
+    let name =
+        ProfileName::new(&name).map_err(|reason| Error::invalid_profile_name(&name, reason))?;
+    let file_name = rules_file(harness.id())
+        .ok_or_else(|| Error::Config(format!("{} has no known rules file", harness.id())))?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    let info = manager.show_profile(harness.as_ref(), &name)?;
+    let snippets = RulesSnippets::load(&info.path)?;
+    if snippets.is_empty() {
+        return Err(Error::Config(format!(
+            "{}/{} has no snippets in {RULES_DIR}/; its {file_name} is used as is",
+            harness.id(),
+            name
+        )));
+    }
+
+    let explanation = RulesExplanation {
+        harness: harness.id().to_string(),
+        profile: name.as_str().to_string(),
+        rules_file: file_name.to_string(),
+        snippets: snippets.names().map(str::to_string).collect(),
+        sections: snippets.sections(),
+    };
+    output(&explanation, format, |explanation| {
+        println!(
+            "{} for {}/{}, from {} snippet(s) in {RULES_DIR}/:",
+            explanation.rules_file,
+            explanation.harness,
+            explanation.profile,
+            explanation.snippets.len()
+        );
+        let width = explanation
+            .sections
+            .iter()
+            .map(|s| s.heading.as_deref().map_or(0, |h| h.chars().count()))
+            .max()
+            .unwrap_or(0)
+            .max("(text)".len());
+        for section in &explanation.sections {
+            println!(
+                "  {:>5}  {:<width$}  {}",
+                format!("L{}", section.line),
+                section.heading.as_deref().unwrap_or("(text)"),
+                section.snippet
+            );
+        }
+    });
+    Ok(())
+}
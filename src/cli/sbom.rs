@@ -0,0 +1,260 @@
+//! `bridle profile sbom`: an inventory of a profile in CycloneDX JSON.
+//!
+//! Lists what the profile lets an agent run: MCP servers with their command
+//! or URL, skills with a SHA-256 of their definition file, and commands,
+//! agents, and plugins. Components installed with `bridle install` carry the
+//! repository and ref they came from, as recorded in the install manifest.
+//! The document follows CycloneDX 1.5, with bridle specifics under
+//! `properties` named `bridle:*`.
+
+use std::path::Path;
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::config::{BridleConfig, ProfileInfo, ProfileManager, ProfileName, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+use crate::install::ComponentType;
+use crate::install::manifest::{InstallManifest, manifest_path};
+
+/// Writes the inventory of profile `name` of `harness_name` to `file`, or
+/// to stdout.
+pub fn sbom(harness_name: &str, name: &str, file: Option<&Path>) -> Result<()> {
+    let harness = HarnessRegistry::load()?.take(harness_name)?;
+    let profile =
+        ProfileName::new(name).map_err(|reason| Error::invalid_profile_name(name, reason))?;
+    let config = BridleConfig::load()?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    let info = manager.show_profile(harness.as_ref(), &profile)?;
+    let skill_files: Vec<(String, String)> = manager
+        .skill_files(harness.as_ref(), &info)
+        .into_iter()
+        .collect();
+    let manifest = InstallManifest::load(&manifest_path(&info.path)).unwrap_or_default();
+
+    let document = build(
+        &info,
+        &skill_files,
+        &manifest,
+        &chrono::Utc::now().to_rfc3339(),
+    );
+    let mut content =
+        serde_json::to_string_pretty(&document).map_err(|e| Error::Config(e.to_string()))?;
+    content.push('\n');
+    match file {
+        Some(path) => {
+            write_atomic(path, content)?;
+            let count = document["components"].as_array().map_or(0, Vec::len);
+            eprintln!("Wrote {count} component(s) to {}", path.display());
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+/// The CycloneDX document for `info`, with `skill_files` holding each
+/// skill's definition file.
+fn build(
+    info: &ProfileInfo,
+    skill_files: &[(String, String)],
+    manifest: &InstallManifest,
+    timestamp: &str,
+) -> Value {
+    let mut components = Vec::new();
+
+    for server in &info.mcp_servers {
+        let mut properties = vec![property("bridle:kind", "mcp-server")];
+        properties.push(property("bridle:enabled", &server.enabled.to_string()));
+        if let Some(kind) = &server.server_type {
+            properties.push(property("bridle:transport", kind));
+        }
+        if let Some(command) = &server.command {
+            let args = server.args.clone().unwrap_or_default();
+            let line = std::iter::once(command.clone())
+                .chain(args)
+                .collect::<Vec<_>>();
+            properties.push(property("bridle:command", &line.join(" ")));
+        }
+        let mut component = json!({
+            "type": "application",
+            "bom-ref": format!("mcp:{}", server.name),
+            "name": server.name,
+            "properties": properties,
+        });
+        if let Some(url) = &server.url {
+            component["externalReferences"] = json!([{ "type": "website", "url": url }]);
+        }
+        components.push(component);
+    }
+
+    for (name, content) in skill_files {
+        let mut component = file_component(ComponentType::Skill, name, manifest);
+        component["hashes"] = json!([{
+            "alg": "SHA-256",
+            "content": hex(&Sha256::digest(content.as_bytes())),
+        }]);
+        components.push(component);
+    }
+    for name in info
+        .skills
+        .items
+        .iter()
+        .filter(|name| !skill_files.iter().any(|(n, _)| n == *name))
+    {
+        components.push(file_component(ComponentType::Skill, name, manifest));
+    }
+    for name in &info.commands.items {
+        components.push(file_component(ComponentType::Command, name, manifest));
+    }
+    for name in info.agents.iter().flat_map(|agents| &agents.items) {
+        components.push(file_component(ComponentType::Agent, name, manifest));
+    }
+    for name in info.plugins.iter().flat_map(|plugins| &plugins.items) {
+        components.push(json!({
+            "type": "library",
+            "bom-ref": format!("plugin:{name}"),
+            "name": name,
+            "properties": [property("bridle:kind", "plugin")],
+        }));
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp,
+            "tools": { "components": [{
+                "type": "application",
+                "name": "bridle",
+                "version": env!("CARGO_PKG_VERSION"),
+            }]},
+            "component": {
+                "type": "application",
+                "bom-ref": format!("profile:{}/{}", info.harness_id, info.name),
+                "name": format!("{}/{}", info.harness_id, info.name),
+                "properties": [property("bridle:active", &info.is_active.to_string())],
+            },
+        },
+        "components": components,
+    })
+}
+
+/// A skill, command, or agent, with its source when the install manifest has one.
+fn file_component(kind: ComponentType, name: &str, manifest: &InstallManifest) -> Value {
+    let label = match kind {
+        ComponentType::Skill => "skill",
+        ComponentType::Agent => "agent",
+        ComponentType::Command => "command",
+    };
+    let mut component = json!({
+        "type": "file",
+        "bom-ref": format!("{label}:{name}"),
+        "name": name,
+        "properties": [property("bridle:kind", label)],
+    });
+    if let Some(entry) = manifest.find_component(kind, name) {
+        let source = &entry.source;
+        let origin = format!("{}/{}", source.owner, source.repo);
+        if let Some(git_ref) = &source.git_ref {
+            component["version"] = json!(git_ref);
+        }
+        component["supplier"] = json!({ "name": source.owner });
+        component["externalReferences"] = json!([{
+            "type": "vcs",
+            "url": if source.owner.contains(['.', ':']) {
+                format!("oci://{origin}")
+            } else {
+                format!("https://github.com/{origin}")
+            },
+        }]);
+        if let Some(properties) = component["properties"].as_array_mut() {
+            properties.push(property("bridle:source", &origin));
+            properties.push(property("bridle:installed-at", &entry.installed_at));
+        }
+    }
+    component
+}
+
+fn property(name: &str, value: &str) -> Value {
+    json!({ "name": name, "value": value })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{McpServerInfo, ResourceSummary};
+    use crate::install::SourceInfo;
+    use crate::install::manifest::ManifestEntry;
+
+    fn summary(items: &[&str]) -> ResourceSummary {
+        ResourceSummary {
+            items: items.iter().map(|i| i.to_string()).collect(),
+            directory_exists: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn inventories_servers_skills_and_sources() {
+        let info = ProfileInfo {
+            name: "work".to_string(),
+            harness_id: "claude-code".to_string(),
+            is_active: false,
+            path: "/profiles/claude-code/work".into(),
+            mcp_servers: vec![McpServerInfo {
+                name: "github".to_string(),
+                enabled: true,
+                server_type: Some("stdio".to_string()),
+                command: Some("npx".to_string()),
+                args: Some(vec!["-y".to_string(), "gh-mcp@1.0.0".to_string()]),
+                url: None,
+            }],
+            skills: summary(&["review"]),
+            commands: summary(&["ship"]),
+            ..Default::default()
+        };
+        let mut manifest = InstallManifest::default();
+        manifest.add_entry(ManifestEntry {
+            component_type: ComponentType::Skill,
+            name: "review".to_string(),
+            source: SourceInfo {
+                owner: "org".to_string(),
+                repo: "skills".to_string(),
+                git_ref: Some("3f9c2ab".to_string()),
+            },
+            installed_at: "2026-01-01T00:00:00Z".to_string(),
+        });
+        let skills = [("review".to_string(), "hello".to_string())];
+
+        let doc = build(&info, &skills, &manifest, "2026-10-15T00:00:00Z");
+
+        assert_eq!(doc["bomFormat"], "CycloneDX");
+        assert_eq!(doc["metadata"]["component"]["name"], "claude-code/work");
+        let components = doc["components"].as_array().unwrap();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0]["bom-ref"], "mcp:github");
+        assert_eq!(
+            components[0]["properties"][3]["value"],
+            "npx -y gh-mcp@1.0.0"
+        );
+        assert_eq!(components[1]["version"], "3f9c2ab");
+        assert_eq!(
+            components[1]["hashes"][0]["content"],
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            components[1]["externalReferences"][0]["url"],
+            "https://github.com/org/skills"
+        );
+        assert_eq!(components[2]["bom-ref"], "command:ship");
+        assert!(components[2].get("version").is_none());
+    }
+}
@@ -0,0 +1,84 @@
+//! `bridle secret`: values for `bridle-secret://` references, kept in the
+//! system keyring or fetched from a configured secret provider.
+
+use std::io::{IsTerminal, Read};
+
+use dialoguer_multiselect::Password;
+use serde::Serialize;
+
+use crate::cli::output::{ResolvedFormat, output_list};
+use crate::config::{
+    BridleConfig, Keyring, SECRET_SCHEME, SecretIndex, SecretResolver, SecretStore, StatePaths,
+    validate_secret_name,
+};
+use crate::error::{Error, Result};
+
+#[derive(Serialize)]
+struct SecretEntry {
+    name: String,
+    reference: String,
+    /// `keyring`, or `provider` for a `[secret_providers]` entry.
+    source: &'static str,
+}
+
+/// Stores a secret, read from a hidden prompt or, when piped, from stdin.
+pub fn set(name: &str) -> Result<()> {
+    validate_secret_name(name)?;
+    let value = if std::io::stdin().is_terminal() {
+        Password::new()
+            .with_prompt(format!("Value for {name}"))
+            .interact()
+            .map_err(|e| Error::Command(e.to_string()))?
+    } else {
+        let mut value = String::new();
+        std::io::stdin().read_to_string(&mut value)?;
+        value.trim_end_matches(['\r', '\n']).to_string()
+    };
+
+    Keyring.set(name, &value)?;
+    let paths = StatePaths::resolve()?;
+    let mut index = SecretIndex::load(&paths)?;
+    index.insert(name);
+    index.save(&paths)?;
+    println!("Stored secret {name}; reference it as {SECRET_SCHEME}{name}");
+    Ok(())
+}
+
+/// Prints a secret's value, from the keyring or, for `<provider>/<key>`, the provider.
+pub fn get(name: &str) -> Result<()> {
+    let value = SecretResolver::new(&BridleConfig::load()?)
+        .get(name)?
+        .ok_or_else(|| Error::MissingSecret {
+            name: name.to_string(),
+        })?;
+    println!("{value}");
+    Ok(())
+}
+
+/// Lists the names of secrets stored with [`set`] and the configured providers.
+pub fn list(format: ResolvedFormat) -> Result<()> {
+    let index = SecretIndex::load(&StatePaths::resolve()?)?;
+    let resolver = SecretResolver::new(&BridleConfig::load()?);
+    let entries: Vec<SecretEntry> = index
+        .names()
+        .map(|name| SecretEntry {
+            name: name.to_string(),
+            reference: format!("{SECRET_SCHEME}{name}"),
+            source: "keyring",
+        })
+        .chain(resolver.providers().map(|name| SecretEntry {
+            name: name.to_string(),
+            reference: format!("{SECRET_SCHEME}{name}/<key>"),
+            source: "provider",
+        }))
+        .collect();
+    output_list(&entries, format, |entries| {
+        if entries.is_empty() {
+            println!("No secrets stored; add one with `bridle secret set <name>`");
+        }
+        for entry in entries {
+            println!("{:<30} {:<9} {}", entry.name, entry.source, entry.reference);
+        }
+    });
+    Ok(())
+}
@@ -1,13 +1,25 @@
-use harness_locate::{Harness, HarnessKind, InstallationStatus, Scope};
+use harness_locate::InstallationStatus;
 use serde::Serialize;
 
+use crate::cli::health::{ProfileHealth, assess, lock_mismatch};
 use crate::cli::output::{ResolvedFormat, output};
-use crate::config::BridleConfig;
+use crate::cli::sync::SyncRecord;
+use crate::config::{
+    BridleConfig, Drift, ProfileManager, ProfileName, format_tokens, machine_name,
+};
+use crate::error::Result;
+use crate::harness::HarnessRegistry;
 
 #[derive(Debug, Serialize)]
 pub struct StatusOutput {
     pub harnesses: Vec<HarnessStatus>,
     pub active_profiles: Vec<ActiveProfile>,
+    /// The last `bridle sync`, if there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<SyncRecord>,
+    /// The name picking `<file>.machine-<name>` variants in profiles.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -16,60 +28,109 @@ pub struct HarnessStatus {
     pub name: String,
     pub status: String,
     pub config_path: Option<String>,
+    /// Every copy of the harness's executable on `PATH`, when there is more
+    /// than one and none is pinned with `[harness.<id>] binary`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub installations: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ActiveProfile {
     pub harness: String,
     pub profile: String,
+    /// Changes to the live config since the profile was switched in or saved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drift: Option<Drift>,
+    /// Estimated tokens in the profile's rules file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules_tokens: Option<usize>,
+    /// Health score and the issues that lowered it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<ProfileHealth>,
 }
 
-pub fn display_status(format: ResolvedFormat) {
-    let harnesses: Vec<HarnessStatus> = HarnessKind::ALL
+/// Installation state of every harness plus active profiles and their drift.
+pub fn collect_status() -> StatusOutput {
+    let registry = HarnessRegistry::load().unwrap_or_else(|_| HarnessRegistry::builtin());
+    let harnesses: Vec<HarnessStatus> = registry
         .iter()
-        .map(|kind| {
-            let harness = Harness::new(*kind);
+        .map(|entry| {
+            let harness = entry.harness();
             let status = match harness.installation_status() {
                 Ok(InstallationStatus::FullyInstalled { .. }) => "installed",
                 Ok(InstallationStatus::ConfigOnly { .. }) => "config only",
                 Ok(InstallationStatus::BinaryOnly { .. }) => "binary only",
                 _ => "not installed",
             };
-            let config_path = if harness.is_installed() {
-                harness
-                    .config(&Scope::Global)
-                    .ok()
-                    .map(|p| p.display().to_string())
+            let config_path = if status != "not installed" {
+                harness.config_dir().ok().map(|p| p.display().to_string())
             } else {
                 None
             };
+            let candidates = harness.binary_candidates();
+            let installations = if candidates.len() > 1 {
+                candidates.iter().map(|p| p.display().to_string()).collect()
+            } else {
+                Vec::new()
+            };
             HarnessStatus {
-                id: kind.to_string(),
-                name: kind.to_string(),
+                id: entry.id().to_string(),
+                name: entry.name().to_string(),
                 status: status.to_string(),
                 config_path,
+                installations,
             }
         })
         .collect();
 
     let active_profiles: Vec<ActiveProfile> = BridleConfig::load()
         .map(|config| {
+            let manager = ProfileManager::builder()
+                .settings(config.settings())
+                .build()
+                .ok();
             config
                 .active
                 .iter()
-                .map(|(harness, profile)| ActiveProfile {
-                    harness: harness.clone(),
-                    profile: profile.clone(),
+                .map(|(harness_id, profile)| {
+                    let harness = registry.get(harness_id).map(|entry| entry.harness());
+                    let (drift, info) = match (manager.as_ref(), harness) {
+                        (Some(m), Some(harness)) => (
+                            m.drift(harness).ok().flatten(),
+                            ProfileName::new(profile)
+                                .ok()
+                                .and_then(|name| m.show_profile(harness, &name).ok()),
+                        ),
+                        _ => (None, None),
+                    };
+                    let health = match (manager.as_ref(), harness, info.as_ref()) {
+                        (Some(m), Some(harness), Some(info)) => Some(assess(m, harness, info)),
+                        _ => None,
+                    };
+                    ActiveProfile {
+                        harness: harness_id.clone(),
+                        profile: profile.clone(),
+                        drift,
+                        rules_tokens: info.and_then(|info| info.rules_tokens),
+                        health,
+                    }
                 })
                 .collect()
         })
         .unwrap_or_default();
 
-    let status = StatusOutput {
+    StatusOutput {
         harnesses,
         active_profiles,
-    };
+        last_sync: SyncRecord::load(),
+        machine: machine_name(),
+    }
+}
 
+/// Prints harness installations and active profiles; `explain` lists the
+/// issues behind each profile's health score.
+pub fn display_status(explain: bool, format: ResolvedFormat) {
+    let status = collect_status();
     output(&status, format, |s| {
         println!("Harnesses:");
         for h in &s.harnesses {
@@ -77,13 +138,140 @@ pub fn display_status(format: ResolvedFormat) {
             if let Some(path) = &h.config_path {
                 println!("    Config: {}", path);
             }
+            if !h.installations.is_empty() {
+                println!(
+                    "    Warning: {} installations on PATH; pin one with `[harness.{}] binary` (and `config_dir` if they differ):",
+                    h.installations.len(),
+                    h.id
+                );
+                for path in &h.installations {
+                    println!("      {path}");
+                }
+            }
         }
 
         if !s.active_profiles.is_empty() {
             println!("\nActive Profiles:");
             for ap in &s.active_profiles {
-                println!("  {}: {}", ap.harness, ap.profile);
+                let mut notes = Vec::new();
+                if let Some(health) = &ap.health {
+                    notes.push(format!("health {}/100, {}", health.score, health.grade()));
+                }
+                if let Some(drift) = ap.drift.as_ref().filter(|d| !d.is_clean()) {
+                    notes.push(format!("{} changed since switch", drift.len()));
+                }
+                if let Some(tokens) = ap.rules_tokens {
+                    notes.push(format!("rules ~{} tokens", format_tokens(tokens)));
+                }
+                if notes.is_empty() {
+                    println!("  {}: {}", ap.harness, ap.profile);
+                } else {
+                    println!("  {}: {} ({})", ap.harness, ap.profile, notes.join(", "));
+                }
+                if explain && let Some(health) = &ap.health {
+                    for issue in &health.issues {
+                        println!("    - {issue}");
+                    }
+                }
             }
         }
+
+        if let Some(sync) = &s.last_sync {
+            println!("\nLast sync: {}", sync.summary());
+        }
+        if let Some(machine) = &s.machine {
+            println!("Machine: {machine}");
+        }
+    });
+}
+
+/// Why `bridle status --check` fails for an active profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemKind {
+    /// The active profile no longer exists.
+    MissingProfile,
+    /// The live config changed since the profile was switched in or saved.
+    Drift,
+    /// Installed files no longer match the profile's `bridle.lock`.
+    LockMismatch,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    pub harness: String,
+    pub profile: String,
+    pub kind: ProblemKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckReport {
+    ok: bool,
+    problems: Vec<Problem>,
+}
+
+/// Problems with the active profiles: missing profiles, drift in the live
+/// config, and lockfiles out of sync with installed files.
+pub fn find_problems() -> Result<Vec<Problem>> {
+    let registry = HarnessRegistry::load()?;
+    let config = BridleConfig::load()?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    let mut problems = Vec::new();
+    let mut active: Vec<_> = config.active.iter().collect();
+    active.sort();
+    for (harness_id, profile) in active {
+        let Some(harness) = registry.get(harness_id).map(|entry| entry.harness()) else {
+            continue;
+        };
+        let mut problem = |kind, detail: String| {
+            problems.push(Problem {
+                harness: harness_id.clone(),
+                profile: profile.clone(),
+                kind,
+                detail,
+            });
+        };
+        let info = ProfileName::new(profile)
+            .ok()
+            .and_then(|name| manager.show_profile(harness, &name).ok());
+        let Some(info) = info else {
+            problem(
+                ProblemKind::MissingProfile,
+                "active profile does not exist".to_string(),
+            );
+            continue;
+        };
+        if let Some(drift) = manager.drift(harness)?.filter(|d| !d.is_clean()) {
+            problem(
+                ProblemKind::Drift,
+                format!("{} file(s) changed since switch", drift.len()),
+            );
+        }
+        if let Some(detail) = lock_mismatch(&info.path) {
+            problem(ProblemKind::LockMismatch, detail);
+        }
+    }
+    Ok(problems)
+}
+
+/// Prints problems with the active profiles, nothing when there are none,
+/// and returns the exit code: 1 if there are problems, else 0.
+pub fn check(format: ResolvedFormat) -> Result<i32> {
+    let problems = find_problems()?;
+    let report = CheckReport {
+        ok: problems.is_empty(),
+        problems,
+    };
+    output(&report, format, |report| {
+        for problem in &report.problems {
+            println!(
+                "{}/{}: {}",
+                problem.harness, problem.profile, problem.detail
+            );
+        }
     });
+    Ok(i32::from(!report.ok))
 }
@@ -0,0 +1,684 @@
+//! `bridle sync`: save live changes into the active profiles on a schedule.
+//!
+//! Each run saves the live config of every harness into its active profile,
+//! skipping harnesses whose live config is unchanged since the last switch or
+//! save. With `--push` the saved profiles are then exported to
+//! `settings.sync_remote`: a directory, laid out like `bridle export
+//! --dotfiles`, or `oci://<registry>/<namespace>`, where each profile becomes
+//! `<namespace>/<harness>/<profile>:latest`.
+//!
+//! A single run suits cron; `--cron` prints a crontab line for it. `--daemon`
+//! keeps running and syncs every `--interval`. Either way `--jitter` waits a
+//! random extra time before each run, so machines sharing a schedule do not
+//! all hit the remote at once. The outcome of the last run is recorded in the
+//! state directory and shown by `bridle status`.
+//!
+//! `bridle sync pull` merges the remote profiles back into the local ones.
+//! Every push and pull stores the profile as synced in the state directory,
+//! and the next pull merges three ways against it with
+//! [`crate::config::merge`]: files changed on one side are taken, files
+//! changed on both are conflicts, settled per file by asking, by
+//! `--resolve`, or left local until a later pull.
+
+use std::collections::BTreeSet;
+use std::hash::{BuildHasher, RandomState};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use dialoguer_multiselect::Select;
+use serde::{Deserialize, Serialize};
+
+use super::dotfiles::{export_profile, read_profile};
+use super::output::{ResolvedFormat, output, output_list};
+use super::profile::manager_builder;
+use super::push::{oci_error, profile_artifact};
+use crate::config::merge::{self, Conflict, FileTree};
+use crate::config::{BridleConfig, ProfileManager, ProfileName, StatePaths, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::{HarnessConfig, HarnessRegistry, expand_home};
+use crate::install::oci::{self, OCI_SCHEME, OciReference};
+
+/// Tag profiles are pushed under in a registry remote.
+const REMOTE_TAG: &str = "latest";
+
+/// What one sync run did, kept as the last sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRecord {
+    pub at: DateTime<Utc>,
+    /// `<harness>/<profile>` of each profile saved from the live config.
+    pub saved: Vec<String>,
+    /// `<harness>/<profile>` of each profile exported to the remote.
+    #[serde(default)]
+    pub pushed: Vec<String>,
+    /// Failures, one per harness that could not be synced.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+impl SyncRecord {
+    /// The last recorded sync, if there was one.
+    pub fn load() -> Option<Self> {
+        let path = StatePaths::resolve().ok()?.sync_file();
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+    }
+
+    fn store(&self) -> Result<()> {
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        write_atomic(&StatePaths::resolve()?.sync_file(), content)
+    }
+
+    /// `2026-10-15 14:02 UTC, 2 saved, 2 pushed`.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![
+            self.at.format("%Y-%m-%d %H:%M UTC").to_string(),
+            format!("{} saved", self.saved.len()),
+        ];
+        if !self.pushed.is_empty() {
+            parts.push(format!("{} pushed", self.pushed.len()));
+        }
+        if !self.errors.is_empty() {
+            parts.push(format!("{} failed", self.errors.len()));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Parses `30s`, `15m`, `1h`, or `1d`; a bare number counts minutes.
+pub fn parse_interval(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{value}' is not a duration like 30m or 1h"))?;
+    let seconds = match unit {
+        "s" => number,
+        "" | "m" => number * 60,
+        "h" => number * 60 * 60,
+        "d" => number * 60 * 60 * 24,
+        _ => {
+            return Err(format!(
+                "unknown unit '{unit}' in '{value}'; use s, m, h, or d"
+            ));
+        }
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// A random duration up to `max`.
+fn jitter(max: Duration) -> Duration {
+    let millis = u64::try_from(max.as_millis()).unwrap_or(u64::MAX);
+    if millis == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(RandomState::new().hash_one(Utc::now()) % millis)
+}
+
+/// Saves the live config into the active profile of `harness`, unless it
+/// has not changed. Returns whether it saved.
+fn save(manager: &ProfileManager, harness: &dyn HarnessConfig, name: &ProfileName) -> Result<bool> {
+    // Without a recorded baseline there is no telling, so save.
+    if manager
+        .drift(harness)?
+        .is_some_and(|drift| drift.is_clean())
+    {
+        return Ok(false);
+    }
+    manager.save_to_profile(harness, harness.as_builtin(), name)?;
+    Ok(true)
+}
+
+/// Where profile `name` of `harness` lives in the registry remote under
+/// `namespace`.
+fn remote_reference(namespace: &str, harness: &str, name: &str) -> Result<OciReference> {
+    OciReference::parse(&format!(
+        "{}/{harness}/{}:{REMOTE_TAG}",
+        namespace.trim_end_matches('/'),
+        name.to_lowercase()
+    ))
+    .map_err(oci_error)
+}
+
+/// Exports profile `name` of `harness` to `remote` and records it as the
+/// base of the next pull.
+fn push(
+    manager: &ProfileManager,
+    harness: &dyn HarnessConfig,
+    name: &ProfileName,
+    remote: &str,
+) -> Result<()> {
+    let profile_path = manager.profile_path(harness, name);
+    if let Some(namespace) = remote.strip_prefix(OCI_SCHEME) {
+        let reference = remote_reference(namespace, harness.id(), name.as_str())?;
+        let (artifact, _) = profile_artifact(manager, harness, name)?;
+        oci::push(&reference, &artifact).map_err(oci_error)?;
+    } else {
+        let dst = expand_home(Path::new(remote))?
+            .join(harness.id())
+            .join(name.as_str());
+        if dst.exists() {
+            std::fs::remove_dir_all(&dst)?;
+        }
+        let prefix = format!("{}_{}", harness.id(), name.as_str());
+        export_profile(&profile_path, &dst, &prefix)?;
+    }
+    let base = StatePaths::resolve()?.sync_base_dir(harness.id(), name.as_str());
+    write_tree(&base, &read_tree(&profile_path)?)
+}
+
+/// The files of the profile directory `dir`, or none if it does not exist.
+fn read_tree(dir: &Path) -> Result<FileTree> {
+    if !dir.is_dir() {
+        return Ok(FileTree::new());
+    }
+    Ok(read_profile(dir)?.into_iter().collect())
+}
+
+/// Replaces the contents of `dir` with `tree`.
+fn write_tree(dir: &Path, tree: &FileTree) -> Result<()> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    std::fs::create_dir_all(dir)?;
+    for (rel, content) in tree {
+        write_file(&dir.join(rel), content)?;
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Syncs every active profile once and records the outcome.
+fn sync_once(push_remote: Option<&str>) -> Result<SyncRecord> {
+    let registry = HarnessRegistry::load()?;
+    let config = BridleConfig::load()?;
+    let manager = manager_builder()?.build()?;
+    let mut record = SyncRecord {
+        at: Utc::now(),
+        saved: Vec::new(),
+        pushed: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut active: Vec<_> = config.active.iter().collect();
+    active.sort();
+    for (harness_id, profile) in active {
+        let Some(harness) = registry.get(harness_id).map(|entry| entry.harness()) else {
+            continue;
+        };
+        let label = format!("{harness_id}/{profile}");
+        let synced = ProfileName::new(profile)
+            .map_err(|reason| Error::invalid_profile_name(profile, reason))
+            .and_then(|name| {
+                let saved = save(&manager, harness, &name)?;
+                if saved {
+                    record.saved.push(label.clone());
+                }
+                if let Some(remote) = push_remote {
+                    push(&manager, harness, &name, remote)?;
+                    record.pushed.push(label.clone());
+                }
+                Ok(())
+            });
+        if let Err(e) = synced {
+            record.errors.push(format!("{label}: {e}"));
+        }
+    }
+    record.store()?;
+    Ok(record)
+}
+
+fn print_record(record: &SyncRecord) {
+    for label in &record.saved {
+        println!("Saved {label}");
+    }
+    for label in &record.pushed {
+        println!("Pushed {label}");
+    }
+    for error in &record.errors {
+        eprintln!("Error: {error}");
+    }
+    if record.saved.is_empty() && record.pushed.is_empty() && record.errors.is_empty() {
+        println!("Nothing changed since the last switch or save");
+    }
+}
+
+/// `settings.sync_remote`, which `what` needs.
+fn sync_remote(what: &str) -> Result<String> {
+    BridleConfig::load()?
+        .settings()
+        .sync_remote
+        .clone()
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "{what} needs a remote; run `bridle config set sync_remote <dir|oci://...>`"
+            ))
+        })
+}
+
+/// The remote to push to with `--push`.
+fn push_remote(push: bool) -> Result<Option<String>> {
+    push.then(|| sync_remote("--push")).transpose()
+}
+
+/// Waits up to `jitter_max`, then syncs once. Returns 1 if a harness failed
+/// to sync, else 0.
+pub fn run(push: bool, jitter_max: Duration, format: ResolvedFormat) -> Result<i32> {
+    let remote = push_remote(push)?;
+    std::thread::sleep(jitter(jitter_max));
+    let record = sync_once(remote.as_deref())?;
+    output(&record, format, print_record);
+    Ok(i32::from(!record.errors.is_empty()))
+}
+
+/// Syncs every `interval` plus up to `jitter_max` until interrupted.
+pub fn daemon(push: bool, interval: Duration, jitter_max: Duration) -> Result<()> {
+    let remote = push_remote(push)?;
+    if interval.is_zero() {
+        return Err(Error::Config(
+            "--interval must be longer than 0".to_string(),
+        ));
+    }
+    println!(
+        "Syncing active profiles every {}s; press Ctrl-C to stop",
+        interval.as_secs()
+    );
+    loop {
+        std::thread::sleep(jitter(jitter_max));
+        match sync_once(remote.as_deref()) {
+            Ok(record) => {
+                if !record.saved.is_empty() || !record.errors.is_empty() {
+                    println!("[{}]", record.at.format("%Y-%m-%d %H:%M:%S UTC"));
+                    print_record(&record);
+                }
+            }
+            Err(e) => eprintln!("Error: sync failed: {e}"),
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Prints a crontab line that runs `bridle sync` hourly at a random minute.
+pub fn print_cron(push: bool, jitter_max: Duration) -> Result<()> {
+    if push {
+        push_remote(true)?;
+    }
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("bridle"));
+    let minute = jitter(Duration::from_secs(60 * 60)).as_secs() / 60;
+    let mut command = format!("{} sync", exe.display());
+    if push {
+        command.push_str(" --push");
+    }
+    if !jitter_max.is_zero() {
+        command.push_str(&format!(" --jitter {}s", jitter_max.as_secs()));
+    }
+    println!("{minute} * * * * {command} >/dev/null 2>&1");
+    Ok(())
+}
+
+/// How `bridle sync pull` settles a file changed both locally and remotely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// Keep the local version.
+    Local,
+    /// Take the remote version.
+    Remote,
+    /// Keep both versions between conflict markers, to merge by hand.
+    Markers,
+}
+
+impl Resolution {
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Local => "kept local",
+            Self::Remote => "took remote",
+            Self::Markers => "wrote conflict markers",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ConflictOutcome {
+    path: PathBuf,
+    /// How it was settled; unsettled conflicts keep the local version.
+    resolution: Option<Resolution>,
+}
+
+/// What pulling one profile did.
+#[derive(Debug, Default, Serialize)]
+struct PullReport {
+    harness: String,
+    profile: String,
+    /// The profile did not exist here and was created from the remote.
+    created: bool,
+    updated: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    conflicts: Vec<ConflictOutcome>,
+    /// The profile is active, so the live config needs a switch to catch up.
+    active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl PullReport {
+    fn is_settled(&self) -> bool {
+        self.error.is_none() && self.conflicts.iter().all(|c| c.resolution.is_some())
+    }
+
+    fn changed(&self) -> bool {
+        self.created
+            || !self.updated.is_empty()
+            || !self.removed.is_empty()
+            || self
+                .conflicts
+                .iter()
+                .any(|c| matches!(c.resolution, Some(Resolution::Remote | Resolution::Markers)))
+    }
+}
+
+/// A profile as read from the remote.
+struct RemoteProfile {
+    harness: String,
+    profile: String,
+    files: Result<FileTree>,
+}
+
+/// The profiles in the remote. A registry cannot be listed, so from one only
+/// the active profiles and those synced before are read.
+fn remote_profiles(remote: &str, harness: Option<&str>) -> Result<Vec<RemoteProfile>> {
+    let wanted = |id: &str| harness.is_none_or(|h| h == id);
+    let Some(namespace) = remote.strip_prefix(OCI_SCHEME) else {
+        let root = expand_home(Path::new(remote))?;
+        let mut profiles = Vec::new();
+        for harness_dir in subdirs(&root)? {
+            let id = harness_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            if !wanted(&id) {
+                continue;
+            }
+            for profile_dir in subdirs(&harness_dir)? {
+                let name = profile_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                profiles.push(RemoteProfile {
+                    harness: id.to_string(),
+                    profile: name.to_string(),
+                    files: read_tree(&profile_dir),
+                });
+            }
+        }
+        return Ok(profiles);
+    };
+
+    let mut candidates: BTreeSet<(String, String)> = BridleConfig::load()?
+        .active
+        .into_iter()
+        .filter(|(id, _)| wanted(id))
+        .collect();
+    let bases = StatePaths::resolve()?.sync_base_root();
+    for harness_dir in subdirs(&bases)? {
+        let id = harness_dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        if wanted(&id) {
+            for profile_dir in subdirs(&harness_dir)? {
+                let name = profile_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                candidates.insert((id.to_string(), name.to_string()));
+            }
+        }
+    }
+    Ok(candidates
+        .into_iter()
+        .map(|(harness, profile)| RemoteProfile {
+            files: fetch(namespace, &harness, &profile),
+            harness,
+            profile,
+        })
+        .collect())
+}
+
+/// Pulls profile `name` of `harness` from the registry remote under
+/// `namespace`.
+fn fetch(namespace: &str, harness: &str, name: &str) -> Result<FileTree> {
+    let reference = remote_reference(namespace, harness, name)?;
+    let artifact = oci::pull(&reference).map_err(oci_error)?;
+    let scratch = oci::scratch_dir("sync")?;
+    artifact
+        .write_to(scratch.path())
+        .map_err(oci_error)
+        .and_then(|()| read_tree(&scratch.path().join(harness).join(name)))
+}
+
+/// Directories directly in `dir`, sorted; none if it does not exist.
+fn subdirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Asks on the terminal how to settle `conflict`. Never prompts when stdin
+/// is not a terminal, so scripts leave conflicts for a later pull.
+fn prompt_conflict(label: &str, conflict: &Conflict) -> Option<Resolution> {
+    if !std::io::stdin().is_terminal() {
+        return None;
+    }
+    let state = |side: &Option<Vec<u8>>| if side.is_some() { "changed" } else { "deleted" };
+    let mut choices = vec![
+        (Some(Resolution::Local), "Keep local"),
+        (Some(Resolution::Remote), "Take remote"),
+    ];
+    if conflict.with_markers().is_some() {
+        choices.push((
+            Some(Resolution::Markers),
+            "Keep both between conflict markers",
+        ));
+    }
+    choices.push((None, "Decide later"));
+    let choice = Select::new()
+        .with_prompt(format!(
+            "{label}: {} was {} locally and {} remotely",
+            conflict.path.display(),
+            state(&conflict.local),
+            state(&conflict.remote)
+        ))
+        .items(choices.iter().map(|(_, item)| *item))
+        .default(0)
+        .interact_opt()
+        .ok()??;
+    choices[choice].0
+}
+
+/// Writes `content` to `rel` in `dir`, or deletes it on `None`.
+fn apply(dir: &Path, rel: &Path, content: Option<&[u8]>) -> Result<()> {
+    let path = dir.join(rel);
+    match content {
+        Some(content) => write_file(&path, content),
+        None if path.exists() => Ok(std::fs::remove_file(path)?),
+        None => Ok(()),
+    }
+}
+
+/// Merges `remote` into profile `name` of `harness`, settling conflicts
+/// with `resolve` or by asking.
+fn pull_profile(
+    manager: &ProfileManager,
+    harness: &dyn HarnessConfig,
+    name: &ProfileName,
+    remote: &FileTree,
+    resolve: Option<Resolution>,
+    report: &mut PullReport,
+) -> Result<()> {
+    let dir = manager.profile_path(harness, name);
+    let base_dir = StatePaths::resolve()?.sync_base_dir(harness.id(), name.as_str());
+    if dir.is_dir() {
+        let base = if base_dir.is_dir() {
+            Some(read_tree(&base_dir)?)
+        } else {
+            None
+        };
+        let plan = merge::plan(base.as_ref(), &read_tree(&dir)?, remote);
+        for (rel, content) in &plan.changes {
+            apply(&dir, rel, content.as_deref())?;
+            if content.is_some() {
+                report.updated.push(rel.clone());
+            } else {
+                report.removed.push(rel.clone());
+            }
+        }
+        let label = format!("{}/{}", harness.id(), name.as_str());
+        for conflict in &plan.conflicts {
+            let markers = conflict.with_markers();
+            let resolution = resolve
+                .filter(|r| *r != Resolution::Markers || markers.is_some())
+                .or_else(|| prompt_conflict(&label, conflict));
+            match resolution {
+                Some(Resolution::Remote) => {
+                    apply(&dir, &conflict.path, conflict.remote.as_deref())?
+                }
+                Some(Resolution::Markers) => apply(&dir, &conflict.path, markers.as_deref())?,
+                Some(Resolution::Local) | None => {}
+            }
+            report.conflicts.push(ConflictOutcome {
+                path: conflict.path.clone(),
+                resolution,
+            });
+        }
+    } else {
+        write_tree(&dir, remote)?;
+        report.created = true;
+    }
+    // An open conflict keeps the old base, so the next pull asks again.
+    if report.is_settled() {
+        write_tree(&base_dir, remote)?;
+    }
+    Ok(())
+}
+
+fn print_pull(reports: &[PullReport]) {
+    for report in reports {
+        let label = format!("{}/{}", report.harness, report.profile);
+        if let Some(error) = &report.error {
+            eprintln!("Error: {label}: {error}");
+            continue;
+        }
+        if report.created {
+            println!("Created {label} from the remote");
+        } else if report.updated.is_empty()
+            && report.removed.is_empty()
+            && report.conflicts.is_empty()
+        {
+            println!("{label} is up to date");
+        } else {
+            println!(
+                "Pulled {label}: {} updated, {} removed, {} conflict(s)",
+                report.updated.len(),
+                report.removed.len(),
+                report.conflicts.len()
+            );
+        }
+        for conflict in &report.conflicts {
+            let outcome = conflict
+                .resolution
+                .map_or("unresolved, kept local", Resolution::describe);
+            println!("  {}: {outcome}", conflict.path.display());
+        }
+        if report.active && report.changed() {
+            println!(
+                "  {label} is active; run `bridle profile switch {} {}` to apply",
+                report.harness, report.profile
+            );
+        }
+    }
+    if reports.is_empty() {
+        println!("No profiles in the remote");
+    }
+}
+
+/// Pulls every profile in `settings.sync_remote`, or those of `harness`,
+/// merging each with the local profile against the version both last
+/// synced from. Returns 1 if a conflict was left open or a profile failed,
+/// else 0.
+pub fn pull(
+    harness: Option<&str>,
+    resolve: Option<Resolution>,
+    format: ResolvedFormat,
+) -> Result<i32> {
+    let remote = sync_remote("sync pull")?;
+    let registry = HarnessRegistry::load()?;
+    let config = BridleConfig::load()?;
+    let manager = manager_builder()?.build()?;
+    let harness = harness
+        .map(|name| registry.resolve(name).map(|e| e.harness().id().to_string()))
+        .transpose()?;
+    let mut reports = Vec::new();
+    for remote in remote_profiles(&remote, harness.as_deref())? {
+        let Some(harness) = registry.get(&remote.harness).map(|entry| entry.harness()) else {
+            continue;
+        };
+        let mut report = PullReport {
+            active: config.active.get(&remote.harness) == Some(&remote.profile),
+            harness: remote.harness,
+            profile: remote.profile,
+            ..Default::default()
+        };
+        let pulled = remote.files.and_then(|files| {
+            let name = ProfileName::new(&report.profile)
+                .map_err(|reason| Error::invalid_profile_name(&report.profile, reason))?;
+            pull_profile(&manager, harness, &name, &files, resolve, &mut report)
+        });
+        if let Err(e) = pulled {
+            report.error = Some(e.to_string());
+        }
+        reports.push(report);
+    }
+    output_list(&reports, format, print_pull);
+    Ok(i32::from(!reports.iter().all(PullReport::is_settled)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_intervals() {
+        assert_eq!(parse_interval("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_interval("15"), Ok(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_interval("2h"), Ok(Duration::from_secs(2 * 3600)));
+        assert!(parse_interval("1w").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[test]
+    fn jitter_stays_below_its_bound() {
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+        for _ in 0..20 {
+            assert!(jitter(Duration::from_secs(5)) < Duration::from_secs(5));
+        }
+    }
+}
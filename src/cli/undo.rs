@@ -0,0 +1,55 @@
+//! `bridle undo --last-install`: reverting the most recent install.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::output::{ResolvedFormat, output};
+use crate::config::BridleConfig;
+use crate::error::Result;
+use crate::install::undo::InstallRecord;
+
+#[derive(Debug, Serialize)]
+struct UndoneInstall {
+    id: String,
+    source: String,
+    targets: Vec<String>,
+    restored: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+}
+
+/// Puts back every file the most recent install wrote, in profiles and live
+/// configs alike. Refuses when one was changed since, unless `force` is set.
+pub fn last_install(force: bool, format: ResolvedFormat) -> Result<()> {
+    let paths = BridleConfig::load()?.state_paths()?;
+    let Some(record) = InstallRecord::latest(&paths.installs_dir())? else {
+        println!("No install to undo");
+        return Ok(());
+    };
+    let id = record.id.clone();
+    let source = record.source.clone();
+    let targets = record.targets.clone();
+    let report = record.undo(force)?;
+    let undone = UndoneInstall {
+        id,
+        source,
+        targets,
+        restored: report.restored,
+        removed: report.removed,
+    };
+    output(&undone, format, |undone| {
+        println!(
+            "Undid install {} of {} into {}",
+            undone.id,
+            undone.source,
+            undone.targets.join(", ")
+        );
+        for path in &undone.restored {
+            println!("  restored {}", path.display());
+        }
+        for path in &undone.removed {
+            println!("  removed  {}", path.display());
+        }
+    });
+    Ok(())
+}
@@ -11,9 +11,11 @@ use crate::config::BridleConfig;
 use crate::install::uninstaller::uninstall_components;
 use crate::install::{ComponentType, InstallTarget};
 
-pub fn run(harness: &str, profile: &str) -> Result<()> {
-    if !std::io::stdin().is_terminal() {
-        return Err(eyre!("Interactive mode requires a terminal."));
+pub fn run(harness: &str, profile: &str, names: &[String]) -> Result<()> {
+    if names.is_empty() && !std::io::stdin().is_terminal() {
+        return Err(eyre!(
+            "Interactive mode requires a terminal. Name the components to uninstall instead."
+        ));
     }
 
     let profiles_dir = BridleConfig::profiles_dir()?;
@@ -24,36 +26,53 @@ pub fn run(harness: &str, profile: &str) -> Result<()> {
         return Err(eyre!("Profile not found: {}/{}", harness, profile));
     }
 
-    let components = list_installed_components(&profile_path)?;
+    let components = installed_components(&profile_path)?;
 
     if components.is_empty() {
         eprintln!("No components installed in {}/{}", harness, profile);
         return Ok(());
     }
 
-    let component_labels: Vec<String> = components
-        .iter()
-        .map(|(name, comp_type)| format!("{:?}: {}", comp_type, name))
-        .collect();
-
-    let Some(selected_indices) = MultiSelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select components to uninstall (Esc to cancel)")
-        .items(&component_labels)
-        .interact_opt()?
-    else {
-        eprintln!("Cancelled");
-        return Ok(());
-    };
-
-    if selected_indices.is_empty() {
-        eprintln!("No components selected");
-        return Ok(());
-    }
+    let selected_components: Vec<_> = if names.is_empty() {
+        let component_labels: Vec<String> = components
+            .iter()
+            .map(|(name, comp_type)| format!("{:?}: {}", comp_type, name))
+            .collect();
+
+        let Some(selected_indices) = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select components to uninstall (Esc to cancel)")
+            .items(&component_labels)
+            .interact_opt()?
+        else {
+            eprintln!("Cancelled");
+            return Ok(());
+        };
+
+        if selected_indices.is_empty() {
+            eprintln!("No components selected");
+            return Ok(());
+        }
 
-    let selected_components: Vec<_> = selected_indices
-        .iter()
-        .map(|&i| components[i].clone())
-        .collect();
+        selected_indices
+            .iter()
+            .map(|&i| components[i].clone())
+            .collect()
+    } else {
+        let mut selected = Vec::new();
+        for name in names {
+            let matching: Vec<_> = components.iter().filter(|(n, _)| n == name).collect();
+            if matching.is_empty() {
+                return Err(eyre!(
+                    "{} is not installed in {}/{}",
+                    name,
+                    harness,
+                    profile
+                ));
+            }
+            selected.extend(matching.into_iter().cloned());
+        }
+        selected
+    };
 
     let target = InstallTarget {
         harness: harness.to_string(),
@@ -82,7 +101,8 @@ pub fn run(harness: &str, profile: &str) -> Result<()> {
     Ok(())
 }
 
-fn list_installed_components(profile_path: &Path) -> Result<Vec<(String, ComponentType)>> {
+/// The skills, agents, and commands installed in the profile at `profile_path`.
+pub(crate) fn installed_components(profile_path: &Path) -> Result<Vec<(String, ComponentType)>> {
     let mut components = Vec::new();
 
     let component_types = [
@@ -0,0 +1,389 @@
+//! `bridle update`: new and changed skills from subscribed sources.
+//!
+//! Each `[[subscriptions]]` source is fetched and compared with the profiles
+//! it feeds: those listed in its `profiles` and those whose install manifest
+//! records skills from it. A skill is new to a profile that does not have it,
+//! and updated when the profile's copy was installed from the source but no
+//! longer matches what the source would install now. Skills of the same name
+//! installed from elsewhere, or written by hand, are left alone.
+
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::eyre::{Result, eyre};
+use harness_locate::Harness;
+use serde::Serialize;
+
+use super::install::{
+    SelectedComponents, discover, install_to_target, parse_target, skills_with_hooks,
+};
+use super::output::{ResolvedFormat, output};
+use crate::config::{BridleConfig, Notifier, ProfileManager, StatePaths, Subscription};
+use crate::harness::registry::parse_kind;
+use crate::harness::{HarnessConfig, HarnessRegistry};
+use crate::install::convert::convert_skill;
+use crate::install::manifest::{InstallManifest, manifest_path};
+use crate::install::{
+    ComponentType, DiscoveryResult, InstallOptions, InstallTarget, SkillInfo, SourceInfo,
+};
+
+/// Minimum time between the background checks `settings.check_subscriptions` enables.
+const CHECK_INTERVAL: Duration = Duration::hours(24);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Change {
+    New,
+    Updated,
+}
+
+/// One skill that differs between a source and a profile it feeds.
+#[derive(Debug, Clone, Serialize)]
+struct SkillChange {
+    source: String,
+    harness: String,
+    profile: String,
+    skill: String,
+    change: Change,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct UpdateReport {
+    changes: Vec<SkillChange>,
+    /// Whether the changes were installed, as opposed to only reported.
+    applied: bool,
+    errors: Vec<String>,
+}
+
+/// Changes one subscription would make, with the skills to install for them.
+struct Pending {
+    source: SourceInfo,
+    changes: Vec<(InstallTarget, Change, SkillInfo)>,
+}
+
+/// Reports the changes of every subscription and, unless `dry_run`, installs
+/// them, running their setup hooks only with `allow_hooks`.
+pub fn run(dry_run: bool, allow_hooks: bool, format: ResolvedFormat) -> Result<()> {
+    let config = BridleConfig::load()?;
+    if config.subscriptions.is_empty() {
+        return Err(eyre!(
+            "No subscriptions; add `[[subscriptions]] source = \"owner/repo\"` to config.toml"
+        ));
+    }
+    let notifier = Notifier::new(&config);
+    let mut options = InstallOptions {
+        force: true,
+        ..Default::default()
+    };
+
+    let mut report = UpdateReport {
+        applied: !dry_run,
+        ..Default::default()
+    };
+    for subscription in &config.subscriptions {
+        let pending = match check(subscription) {
+            Ok(pending) => pending,
+            Err(e) => {
+                report.errors.push(format!("{}: {e}", subscription.source));
+                continue;
+            }
+        };
+        let source = format!("{}/{}", pending.source.owner, pending.source.repo);
+        report.changes.extend(
+            pending
+                .changes
+                .iter()
+                .map(|(target, change, skill)| SkillChange {
+                    source: source.clone(),
+                    harness: target.harness.clone(),
+                    profile: target.profile.as_str().to_string(),
+                    skill: skill.name.clone(),
+                    change: *change,
+                }),
+        );
+        if dry_run {
+            continue;
+        }
+
+        let mut targets: Vec<&InstallTarget> = Vec::new();
+        for (target, _, _) in &pending.changes {
+            if !targets.iter().any(|t| same_target(t, target)) {
+                targets.push(target);
+            }
+        }
+        for target in targets {
+            let skills: Vec<SkillInfo> = pending
+                .changes
+                .iter()
+                .filter(|(t, _, _)| same_target(t, target))
+                .map(|(_, _, skill)| skill.clone())
+                .collect();
+            if allow_hooks {
+                options.run_setup = skills_with_hooks(&skills);
+            }
+            let selected = SelectedComponents::skills(skills, pending.source.clone());
+            if let Err(e) = selected.mirror() {
+                report
+                    .errors
+                    .push(format!("{}/{}: {e}", target.harness, target.profile));
+                continue;
+            }
+            let lines = install_to_target(&selected, target, &options);
+            report.errors.extend(
+                lines
+                    .iter()
+                    .filter(|line| line.starts_with('!'))
+                    .map(|line| format!("{}/{}: {line}", target.harness, target.profile)),
+            );
+            notifier.install(&target.harness, target.profile.as_str(), &source, &lines);
+        }
+    }
+
+    output(&report, format, |report| {
+        if report.changes.is_empty() {
+            println!("Subscribed skills are up to date");
+        }
+        for change in &report.changes {
+            let (marker, label) = match change.change {
+                Change::New => ('+', "new"),
+                Change::Updated => ('~', "updated"),
+            };
+            println!(
+                "{marker} {}/{}: {} ({label}, from {})",
+                change.harness, change.profile, change.skill, change.source
+            );
+        }
+        for error in &report.errors {
+            eprintln!("Error: {error}");
+        }
+        if !report.changes.is_empty() {
+            if report.applied {
+                println!("Installed {} skill change(s)", report.changes.len());
+            } else {
+                println!("Run `bridle update` without --dry-run to install them");
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Prints a hint when subscribed sources have new or changed skills.
+///
+/// Runs only with `settings.check_subscriptions`, at most once per
+/// [`CHECK_INTERVAL`], and never fails the command it follows.
+pub fn check_if_due() {
+    let Ok(config) = BridleConfig::load() else {
+        return;
+    };
+    if !config.settings().check_subscriptions || config.subscriptions.is_empty() {
+        return;
+    }
+    let Ok(paths) = StatePaths::resolve() else {
+        return;
+    };
+    let stamp = paths.subscriptions_checked_file();
+    let due = std::fs::read_to_string(&stamp)
+        .ok()
+        .and_then(|text| DateTime::parse_from_rfc3339(text.trim()).ok())
+        .is_none_or(|last| Utc::now() - last.with_timezone(&Utc) >= CHECK_INTERVAL);
+    if !due {
+        return;
+    }
+    // Record the attempt first, so an unreachable source is not retried on every command.
+    let _ = stamp
+        .parent()
+        .map(std::fs::create_dir_all)
+        .transpose()
+        .and_then(|_| std::fs::write(&stamp, Utc::now().to_rfc3339()));
+
+    let count: usize = config
+        .subscriptions
+        .iter()
+        .filter_map(|subscription| check(subscription).ok())
+        .map(|pending| pending.changes.len())
+        .sum();
+    if count > 0 {
+        eprintln!("{count} subscribed skill update(s) available; run `bridle update`");
+    }
+}
+
+/// Fetches `subscription`'s source and compares it with the profiles it feeds.
+fn check(subscription: &Subscription) -> Result<Pending> {
+    let discovery = discover(&subscription.source)?;
+    let profiles_dir = BridleConfig::profiles_dir()?;
+    let targets = targets(subscription, &discovery.source, &profiles_dir)?;
+    Ok(Pending {
+        changes: plan(&discovery, &targets, &profiles_dir),
+        source: discovery.source,
+    })
+}
+
+/// Profiles listed in `subscription` plus those with skills installed from `source`.
+fn targets(
+    subscription: &Subscription,
+    source: &SourceInfo,
+    profiles_dir: &Path,
+) -> Result<Vec<InstallTarget>> {
+    let mut targets = Vec::new();
+    for entry in &subscription.profiles {
+        targets.push(parse_target(entry)?);
+    }
+
+    let manager = ProfileManager::new(profiles_dir.to_path_buf());
+    let registry = HarnessRegistry::load()?;
+    for kind in registry.iter().filter_map(|entry| entry.kind()) {
+        let Ok(harness) = Harness::locate(kind) else {
+            continue;
+        };
+        for profile in manager.list_profiles(&harness).unwrap_or_default() {
+            let target = InstallTarget {
+                harness: harness.id().to_string(),
+                profile,
+            };
+            let manifest =
+                InstallManifest::load(&manifest_path(&profile_dir(profiles_dir, &target)))
+                    .unwrap_or_default();
+            let fed = manifest.entries.iter().any(|e| {
+                matches!(e.component_type, ComponentType::Skill) && same_source(&e.source, source)
+            });
+            if fed && !targets.iter().any(|t| same_target(t, &target)) {
+                targets.push(target);
+            }
+        }
+    }
+    Ok(targets)
+}
+
+/// The skills of `discovery` that are new to, or updated for, each of `targets`.
+fn plan(
+    discovery: &DiscoveryResult,
+    targets: &[InstallTarget],
+    profiles_dir: &Path,
+) -> Vec<(InstallTarget, Change, SkillInfo)> {
+    let mut changes = Vec::new();
+    for target in targets {
+        let Some(kind) = parse_kind(&target.harness) else {
+            continue;
+        };
+        let dir = profile_dir(profiles_dir, target);
+        if !dir.is_dir() {
+            continue;
+        }
+        let manifest = InstallManifest::load(&manifest_path(&dir)).unwrap_or_default();
+        for skill in &discovery.skills {
+            let Some(converted) = convert_skill(skill, kind) else {
+                continue;
+            };
+            let installed = dir.join("skills").join(&converted.name).join("SKILL.md");
+            let change = match std::fs::read_to_string(&installed) {
+                Err(_) => Change::New,
+                Ok(content) => {
+                    let from_source = manifest
+                        .find_component(ComponentType::Skill, &converted.name)
+                        .is_some_and(|e| same_source(&e.source, &discovery.source));
+                    if !from_source || content == converted.content {
+                        continue;
+                    }
+                    Change::Updated
+                }
+            };
+            changes.push((target.clone(), change, skill.clone()));
+        }
+    }
+    changes
+}
+
+fn profile_dir(profiles_dir: &Path, target: &InstallTarget) -> std::path::PathBuf {
+    profiles_dir
+        .join(&target.harness)
+        .join(target.profile.as_str())
+}
+
+fn same_source(a: &SourceInfo, b: &SourceInfo) -> bool {
+    a.owner.eq_ignore_ascii_case(&b.owner) && a.repo.eq_ignore_ascii_case(&b.repo)
+}
+
+fn same_target(a: &InstallTarget, b: &InstallTarget) -> bool {
+    a.harness == b.harness && a.profile == b.profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProfileName;
+    use crate::install::manifest::ManifestEntry;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn skill(name: &str, body: &str) -> SkillInfo {
+        SkillInfo {
+            name: name.to_string(),
+            description: Some(name.to_string()),
+            path: format!("skills/{name}/SKILL.md"),
+            content: format!("---\nname: {name}\ndescription: {name}\n---\n{body}\n"),
+            setup_script: None,
+            harnesses: Vec::new(),
+        }
+    }
+
+    fn source(owner: &str) -> SourceInfo {
+        SourceInfo {
+            owner: owner.to_string(),
+            repo: "agent-skills".to_string(),
+            git_ref: Some("main".to_string()),
+        }
+    }
+
+    #[test]
+    fn plans_new_and_updated_skills_only_for_the_source() {
+        let temp = TempDir::new().unwrap();
+        let target = InstallTarget {
+            harness: "claude-code".to_string(),
+            profile: ProfileName::new("work").unwrap(),
+        };
+        let dir = profile_dir(temp.path(), &target);
+        for (name, body) in [("review", "old"), ("local", "mine"), ("same", "same")] {
+            std::fs::create_dir_all(dir.join("skills").join(name)).unwrap();
+            std::fs::write(
+                dir.join("skills").join(name).join("SKILL.md"),
+                skill(name, body).content,
+            )
+            .unwrap();
+        }
+        let mut manifest = InstallManifest::default();
+        for (name, owner) in [("review", "org"), ("same", "org"), ("local", "someone")] {
+            manifest.add_entry(ManifestEntry {
+                component_type: ComponentType::Skill,
+                name: name.to_string(),
+                source: source(owner),
+                installed_at: "2026-01-01T00:00:00Z".to_string(),
+            });
+        }
+        manifest.save(&manifest_path(&dir)).unwrap();
+
+        let discovery = DiscoveryResult {
+            skills: vec![
+                skill("review", "new"),
+                skill("local", "theirs"),
+                skill("same", "same"),
+                skill("lint", "lint"),
+            ],
+            mcp_servers: HashMap::new(),
+            agents: Vec::new(),
+            commands: Vec::new(),
+            source: source("Org"),
+        };
+        let changes: Vec<_> = plan(&discovery, &[target], temp.path())
+            .into_iter()
+            .map(|(_, change, skill)| (skill.name, change))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                ("review".to_string(), Change::Updated),
+                ("lint".to_string(), Change::New),
+            ]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! `bridle verify`: installed files checked against `bridle.lock`.
+//!
+//! Hashes the skill, agent, and command files in a profile again and lists
+//! those edited since install, deleted, or present without being in the
+//! lockfile. The command exits with status 1 when anything differs, so it
+//! can gate a CI job the way `bridle audit` does.
+
+use serde::Serialize;
+
+use crate::cli::output::{ResolvedFormat, output};
+use crate::config::{BridleConfig, ProfileManager, ProfileName};
+use crate::error::{Error, Result};
+use crate::harness::HarnessRegistry;
+use crate::install::lock::{Drift, LOCK_FILENAME, Lockfile, lock_path, verify};
+
+#[derive(Debug, Serialize)]
+struct Difference {
+    path: String,
+    status: Drift,
+}
+
+#[derive(Debug, Serialize)]
+struct VerifyReport {
+    harness: String,
+    profile: String,
+    verified: usize,
+    differences: Vec<Difference>,
+}
+
+/// Verifies `profile` of `harness`, or its active profile, against its
+/// lockfile and returns the exit code: 1 if anything differs, else 0.
+pub fn run(harness: &str, profile: Option<&str>, format: ResolvedFormat) -> Result<i32> {
+    let harness = HarnessRegistry::load()?.take(harness)?;
+    let config = BridleConfig::load()?;
+    let name = match profile {
+        Some(name) => name.to_string(),
+        None => config
+            .active_profile_for(harness.id())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no active profile for {}; name the profile to verify",
+                    harness.id()
+                ))
+            })?,
+    };
+    let name =
+        ProfileName::new(&name).map_err(|reason| Error::invalid_profile_name(&name, reason))?;
+    let manager = ProfileManager::builder()
+        .settings(config.settings())
+        .build()?;
+    let info = manager.show_profile(harness.as_ref(), &name)?;
+
+    let path = lock_path(&info.path);
+    if !path.exists() {
+        return Err(Error::Config(format!(
+            "{}/{} has no {LOCK_FILENAME}; it is written by `bridle install`",
+            harness.id(),
+            name
+        )));
+    }
+    let lock = Lockfile::load(&path).map_err(|e| Error::Config(e.to_string()))?;
+    let differences: Vec<Difference> = verify(&info.path, &lock)
+        .into_iter()
+        .map(|(path, status)| Difference { path, status })
+        .collect();
+
+    let failed = !differences.is_empty();
+    let report = VerifyReport {
+        harness: harness.id().to_string(),
+        profile: name.as_str().to_string(),
+        verified: lock.artifacts.len(),
+        differences,
+    };
+    output(&report, format, |report| {
+        if report.differences.is_empty() {
+            println!(
+                "{}/{}: {} file(s) match {LOCK_FILENAME}",
+                report.harness, report.profile, report.verified
+            );
+            return;
+        }
+        println!(
+            "{}/{}: {} difference(s) from {LOCK_FILENAME}",
+            report.harness,
+            report.profile,
+            report.differences.len()
+        );
+        for difference in &report.differences {
+            let label = match difference.status {
+                Drift::Modified => "modified",
+                Drift::Missing => "missing",
+                Drift::Extraneous => "extraneous",
+            };
+            println!("  {label:<10}  {}", difference.path);
+        }
+    });
+    Ok(i32::from(failed))
+}
@@ -0,0 +1,100 @@
+//! Crash-safe file replacement that leaves hard links to the old file alone.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+
+/// Replaces `path` with `contents` so readers see either the old or the new file.
+///
+/// Writes a temporary file next to `path`, flushes it to disk, then renames it
+/// into place. Missing parent directories are created.
+///
+/// Since `path` gets a new file rather than new content, other hard links to
+/// the old file keep the old content. Profile and live copies linked by the
+/// `hardlink` copy strategy or `bridle dedupe` rely on this, so every write
+/// that may land on such a copy goes through here.
+///
+/// # Errors
+/// Returns [`Error::Io`](crate::error::Error::Io) if any step fails; the
+/// original file is left untouched and the temporary file is removed.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+
+    let tmp = temp_path(path);
+    let result = write_and_rename(&tmp, path, contents.as_ref());
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result?;
+
+    sync_dir(parent);
+    Ok(())
+}
+
+fn write_and_rename(tmp: &Path, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(tmp, path)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.{}.tmp", std::process::id()))
+}
+
+/// Persists the rename itself. Best effort: not every platform or filesystem
+/// allows opening a directory.
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn creates_parents_and_replaces_contents() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("nested/dir/config.toml");
+
+        write_atomic(&path, "a = 1\n").unwrap();
+        write_atomic(&path, "a = 2\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a = 2\n");
+        let leftovers: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name() != "config.toml")
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn failure_keeps_original() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(&path, "original").unwrap();
+        // A directory where the temp file should go makes the write fail.
+        fs::create_dir(temp_path(&path)).unwrap();
+
+        assert!(write_atomic(&path, "new").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+    }
+}
@@ -1,11 +1,24 @@
 //! Bridle's own configuration file handling.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
+use super::atomic::write_atomic;
+use super::layers::{self, Override, Shadowed};
+use super::migration;
+use super::size::{self, format_size, parse_size};
+use super::state::{PersistedState, ProfileUsage, StatePaths};
+use super::{
+    CopyStrategy, ExtractionLimits, LargeFilePolicy, NotifyTarget, Remote, SecretProvider,
+    SwitchStrategy, SymlinkPolicy,
+};
+use crate::error::{Error, Result};
+use crate::harness::{CustomResource, HarnessRegistry};
+
 /// User preference for TUI view mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ViewPreference {
     /// Classic table-based view.
@@ -19,18 +32,414 @@ pub enum ViewPreference {
 }
 
 /// TUI-specific configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TuiConfig {
     /// Preferred view mode.
     #[serde(default)]
     pub view: ViewPreference,
 }
 
+/// When to colorize terminal output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Colorize when writing to a terminal.
+    #[default]
+    Auto,
+    /// Always colorize.
+    Always,
+    /// Never colorize.
+    Never,
+}
+
+/// Global behavior settings, stored in the `[settings]` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Settings {
+    /// Editor command for editing profiles. Takes precedence over the top-level `editor`.
+    pub editor: Option<String>,
+    /// How files are copied between profiles and live config.
+    pub copy_strategy: CopyStrategy,
+    /// Whether switching replaces the live config or lays the profile over it.
+    pub switch_strategy: SwitchStrategy,
+    /// What to do with symbolic links in config directories.
+    pub symlinks: SymlinkPolicy,
+    /// File-name patterns (e.g. `*.log`) never copied between profiles and live config.
+    pub exclude: Vec<String>,
+    /// Snapshot the live config before switching profiles.
+    pub auto_backup: bool,
+    /// Have the harness load its config after `bridle profile switch`, as
+    /// its `--check` flag does.
+    pub check_after_switch: bool,
+    /// Files larger than this (e.g. `"100MB"`) trigger `large_files`. `0` disables the check.
+    #[serde(with = "size::serde_size")]
+    #[schemars(with = "String")]
+    pub max_file_size: u64,
+    /// What to do with files over `max_file_size`.
+    pub large_files: LargeFilePolicy,
+    /// Warn when a profile's files add up to more than this. `0` disables the check.
+    #[serde(with = "size::serde_size")]
+    #[schemars(with = "String")]
+    pub max_profile_size: u64,
+    /// Directory levels below a profile that `profile show`, `list`, and
+    /// `status` read; deeper files are never looked at.
+    pub extraction_max_depth: usize,
+    /// Entries read from any one profile directory; longer resource listings
+    /// are cut short and marked truncated.
+    pub extraction_max_entries: usize,
+    /// Ask before destructive operations such as deleting a profile.
+    pub confirm: bool,
+    /// Ask before `bridle activate` applies a `.bridle.toml` not seen before.
+    pub confirm_projects: bool,
+    /// Allow `bridle insights` to read harness session logs.
+    pub insights: bool,
+    /// Check `[[subscriptions]]` for new and changed skills once a day after
+    /// other commands.
+    pub check_subscriptions: bool,
+    /// Directory, or `oci://<registry>/<namespace>`, that every installed
+    /// source is copied to first. A leading `~` expands to the home directory.
+    pub mirror: Option<String>,
+    /// Directory, or `oci://<registry>/<namespace>`, that `bridle sync --push`
+    /// exports saved profiles to. A leading `~` expands to the home directory.
+    pub sync_remote: Option<String>,
+    /// This machine's name, picking the `<file>.machine-<name>` variants in
+    /// profiles. Unset uses the host name up to its first dot.
+    pub machine: Option<String>,
+    /// PEM file of root certificates trusted for HTTPS instead of the built-in
+    /// ones, e.g. a TLS-intercepting proxy's. A leading `~` expands to the
+    /// home directory.
+    pub ca_bundle: Option<String>,
+    /// Skip TLS certificate verification. A last resort; prefer `ca_bundle`.
+    pub insecure: bool,
+    /// Team policy file, or an `https://` URL serving one, that `install`,
+    /// `profile switch`, and `audit` check against. A leading `~` expands to
+    /// the home directory.
+    pub policy: Option<String>,
+    /// Seconds a download or registry request may take. `0` waits forever.
+    pub http_timeout_secs: u64,
+    /// How many times a download is retried after a timeout, connection
+    /// failure, or server error.
+    pub http_retries: u32,
+    /// Milliseconds before the first retry; each later retry waits twice as long.
+    pub http_backoff_ms: u64,
+    /// When to colorize output.
+    pub color: ColorChoice,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            editor: None,
+            copy_strategy: CopyStrategy::default(),
+            switch_strategy: SwitchStrategy::default(),
+            symlinks: SymlinkPolicy::default(),
+            exclude: Vec::new(),
+            auto_backup: true,
+            check_after_switch: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            large_files: LargeFilePolicy::default(),
+            max_profile_size: DEFAULT_MAX_PROFILE_SIZE,
+            extraction_max_depth: ExtractionLimits::default().max_depth,
+            extraction_max_entries: ExtractionLimits::default().max_entries,
+            confirm: true,
+            confirm_projects: false,
+            insights: false,
+            check_subscriptions: false,
+            mirror: None,
+            sync_remote: None,
+            machine: None,
+            ca_bundle: None,
+            insecure: false,
+            policy: None,
+            http_timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS,
+            http_retries: DEFAULT_HTTP_RETRIES,
+            http_backoff_ms: DEFAULT_HTTP_BACKOFF_MS,
+            color: ColorChoice::default(),
+        }
+    }
+}
+
+const DEFAULT_MAX_FILE_SIZE: u64 = 100 << 20;
+const DEFAULT_MAX_PROFILE_SIZE: u64 = 1 << 30;
+const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_HTTP_RETRIES: u32 = 2;
+const DEFAULT_HTTP_BACKOFF_MS: u64 = 100;
+
+fn parse_size_value(key: &str, value: &str) -> Result<u64> {
+    parse_size(value).ok_or_else(|| Error::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| Error::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(Error::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+fn parse_enum<T: serde::de::DeserializeOwned>(key: &str, value: &str) -> Result<T> {
+    T::deserialize(
+        serde::de::value::StrDeserializer::<serde::de::value::Error>::new(&value.to_lowercase()),
+    )
+    .map_err(|_| Error::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+impl Settings {
+    /// Keys accepted by [`Settings::get`] and [`Settings::set`].
+    pub const KEYS: &'static [&'static str] = &[
+        "editor",
+        "copy_strategy",
+        "switch_strategy",
+        "symlinks",
+        "exclude",
+        "auto_backup",
+        "check_after_switch",
+        "max_file_size",
+        "large_files",
+        "max_profile_size",
+        "extraction_max_depth",
+        "extraction_max_entries",
+        "confirm",
+        "confirm_projects",
+        "insights",
+        "check_subscriptions",
+        "mirror",
+        "sync_remote",
+        "machine",
+        "ca_bundle",
+        "insecure",
+        "policy",
+        "http_timeout_secs",
+        "http_retries",
+        "http_backoff_ms",
+        "color",
+    ];
+
+    /// Returns the value of `key` formatted for display.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownSetting`] if `key` is not one of [`Settings::KEYS`].
+    pub fn get(&self, key: &str) -> Result<String> {
+        Ok(match key {
+            "editor" => self.editor.clone().unwrap_or_default(),
+            "copy_strategy" => match self.copy_strategy {
+                CopyStrategy::Copy => "copy",
+                CopyStrategy::Hardlink => "hardlink",
+            }
+            .to_string(),
+            "switch_strategy" => match self.switch_strategy {
+                SwitchStrategy::Replace => "replace",
+                SwitchStrategy::Merge => "merge",
+                SwitchStrategy::Known => "known",
+            }
+            .to_string(),
+            "symlinks" => match self.symlinks {
+                SymlinkPolicy::Preserve => "preserve",
+                SymlinkPolicy::Follow => "follow",
+                SymlinkPolicy::Skip => "skip",
+            }
+            .to_string(),
+            "exclude" => self.exclude.join(","),
+            "auto_backup" => self.auto_backup.to_string(),
+            "check_after_switch" => self.check_after_switch.to_string(),
+            "max_file_size" => format_size(self.max_file_size),
+            "large_files" => match self.large_files {
+                LargeFilePolicy::Warn => "warn",
+                LargeFilePolicy::Skip => "skip",
+            }
+            .to_string(),
+            "max_profile_size" => format_size(self.max_profile_size),
+            "extraction_max_depth" => self.extraction_max_depth.to_string(),
+            "extraction_max_entries" => self.extraction_max_entries.to_string(),
+            "confirm" => self.confirm.to_string(),
+            "confirm_projects" => self.confirm_projects.to_string(),
+            "insights" => self.insights.to_string(),
+            "check_subscriptions" => self.check_subscriptions.to_string(),
+            "mirror" => self.mirror.clone().unwrap_or_default(),
+            "sync_remote" => self.sync_remote.clone().unwrap_or_default(),
+            "machine" => self.machine.clone().unwrap_or_default(),
+            "ca_bundle" => self.ca_bundle.clone().unwrap_or_default(),
+            "insecure" => self.insecure.to_string(),
+            "policy" => self.policy.clone().unwrap_or_default(),
+            "http_timeout_secs" => self.http_timeout_secs.to_string(),
+            "http_retries" => self.http_retries.to_string(),
+            "http_backoff_ms" => self.http_backoff_ms.to_string(),
+            "color" => match self.color {
+                ColorChoice::Auto => "auto",
+                ColorChoice::Always => "always",
+                ColorChoice::Never => "never",
+            }
+            .to_string(),
+            _ => return Err(Error::UnknownSetting(key.to_string())),
+        })
+    }
+
+    /// Parses `value` and stores it under `key`.
+    ///
+    /// An empty value clears `editor`, `mirror`, `sync_remote`, `machine`, `ca_bundle`, `policy`, and `exclude`; `exclude` takes a
+    /// comma-separated list.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownSetting`] for unknown keys or
+    /// [`Error::InvalidValue`] if `value` does not parse.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "editor" => {
+                self.editor = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "copy_strategy" => self.copy_strategy = parse_enum(key, value)?,
+            "switch_strategy" => self.switch_strategy = parse_enum(key, value)?,
+            "symlinks" => self.symlinks = parse_enum(key, value)?,
+            "exclude" => {
+                self.exclude = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            "auto_backup" => self.auto_backup = parse_bool(key, value)?,
+            "check_after_switch" => self.check_after_switch = parse_bool(key, value)?,
+            "max_file_size" => self.max_file_size = parse_size_value(key, value)?,
+            "large_files" => self.large_files = parse_enum(key, value)?,
+            "max_profile_size" => self.max_profile_size = parse_size_value(key, value)?,
+            "extraction_max_depth" => self.extraction_max_depth = parse_number(key, value)?,
+            "extraction_max_entries" => self.extraction_max_entries = parse_number(key, value)?,
+            "confirm" => self.confirm = parse_bool(key, value)?,
+            "confirm_projects" => self.confirm_projects = parse_bool(key, value)?,
+            "insights" => self.insights = parse_bool(key, value)?,
+            "check_subscriptions" => self.check_subscriptions = parse_bool(key, value)?,
+            "mirror" => {
+                self.mirror = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "sync_remote" => {
+                self.sync_remote = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "machine" => {
+                self.machine = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "ca_bundle" => {
+                self.ca_bundle = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "insecure" => self.insecure = parse_bool(key, value)?,
+            "policy" => {
+                self.policy = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "http_timeout_secs" => self.http_timeout_secs = parse_number(key, value)?,
+            "http_retries" => self.http_retries = parse_number(key, value)?,
+            "http_backoff_ms" => self.http_backoff_ms = parse_number(key, value)?,
+            "color" => self.color = parse_enum(key, value)?,
+            _ => return Err(Error::UnknownSetting(key.to_string())),
+        }
+        Ok(())
+    }
+}
+
+/// Per-harness overrides, stored in `[harness.<id>]` tables.
+///
+/// Useful when a harness is installed somewhere `harness_locate` does not look.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct HarnessOverride {
+    /// Live configuration directory. A leading `~` expands to the home directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_dir: Option<PathBuf>,
+    /// Executable to treat as the harness when several are on `PATH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<PathBuf>,
+    /// Pinned location of the MCP config file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_config: Option<PathBuf>,
+    /// File-name patterns never copied for this harness, on top of `settings.exclude`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Switch strategy for this harness, overriding `settings.switch_strategy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub switch_strategy: Option<SwitchStrategy>,
+    /// Paths relative to the config directory synced by the `known` switch
+    /// strategy, on top of the ones the harness declares.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<PathBuf>,
+    /// Command, program first, that checks the harness's config after a
+    /// switch. Replaces the harness's own check.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub check: Vec<String>,
+    /// Config file sections, as `<file>:<key.path>`, whose live values a
+    /// switch keeps, on top of the ones the harness declares. `*` matches
+    /// any key.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protect: Vec<String>,
+    /// Resource categories beyond skills, commands, agents, and plugins,
+    /// keyed by name (`[harness.<id>.resources.<name>]`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, CustomResource>,
+}
+
+/// One `[[subscriptions]]` entry: a skill source kept up to date by
+/// `bridle update`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Subscription {
+    /// `owner/repo`, a GitHub URL, or an `oci://` reference.
+    pub source: String,
+    /// Profiles (`<harness>/<profile>`) that receive the source's new skills,
+    /// on top of those that already have skills installed from it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
+}
+
+/// Name of the implicit context that uses `<config_dir>/profiles`.
+pub const DEFAULT_CONTEXT: &str = "default";
+
+/// A named, fully separate set of profiles and active-profile state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct ContextConfig {
+    /// Profiles directory. Defaults to `<config_dir>/contexts/<name>/profiles`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profiles_dir: Option<PathBuf>,
+}
+
 /// Bridle's configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BridleConfig {
-    /// Active profile per harness (harness_id -> profile_name).
+    /// Layout version, see [`migration::CURRENT_VERSION`].
     #[serde(default)]
+    pub version: u32,
+
+    /// Active profile per harness (harness_id -> profile_name).
+    ///
+    /// Persisted in the state directory (see [`StatePaths`]); only read from
+    /// `config.toml` when migrating older files.
+    #[serde(default, skip_serializing)]
+    #[schemars(skip)]
     pub active: HashMap<String, String>,
 
     /// Whether to create `BRIDLE_PROFILE_<name>` marker files in harness config directories.
@@ -38,15 +447,6 @@ pub struct BridleConfig {
     #[serde(default)]
     pub profile_marker: bool,
 
-    /// Legacy field for migration (ignored on save).
-    #[serde(skip_serializing, default)]
-    active_profile: Option<String>,
-
-    /// Preferred editor for editing profiles.
-    /// Falls back to $EDITOR env var, then "vi".
-    #[serde(default)]
-    pub editor: Option<String>,
-
     /// TUI-specific settings.
     #[serde(default)]
     pub tui: TuiConfig,
@@ -54,11 +454,83 @@ pub struct BridleConfig {
     /// Default harness to show when TUI opens.
     #[serde(default)]
     pub default_harness: Option<String>,
+
+    /// Global behavior settings.
+    #[serde(default)]
+    pub settings: Settings,
+
+    /// Per-harness overrides (harness_id -> overrides).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub harness: HashMap<String, HarnessOverride>,
+
+    /// Context selected with `bridle context use`; `None` means [`DEFAULT_CONTEXT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_context: Option<String>,
+
+    /// Named contexts besides the default one.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub contexts: HashMap<String, ContextConfig>,
+
+    /// Targets notified of profile switches and installs (`[[notify]]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<NotifyTarget>,
+
+    /// Commands that resolve `bridle-secret://<provider>/<key>` references
+    /// (`[secret_providers.<provider>]`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub secret_providers: BTreeMap<String, SecretProvider>,
+
+    /// Skill sources checked by `bridle update` (`[[subscriptions]]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscriptions: Vec<Subscription>,
+
+    /// Named sets of install targets (`<harness>/<profile>`) for
+    /// `bridle install --targets` (`[target-groups]`).
+    #[serde(
+        rename = "target-groups",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub target_groups: BTreeMap<String, Vec<String>>,
+
+    /// Machines `--host` can name (`[remotes.<name>]`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, Remote>,
+
+    /// Keys replaced by environment or command-line overrides (see [`layers`]).
+    #[serde(skip)]
+    #[schemars(skip)]
+    shadowed: Vec<Shadowed>,
+}
+
+impl Default for BridleConfig {
+    fn default() -> Self {
+        Self {
+            version: migration::CURRENT_VERSION,
+            active: HashMap::new(),
+            profile_marker: false,
+            tui: TuiConfig::default(),
+            default_harness: None,
+            settings: Settings::default(),
+            harness: HashMap::new(),
+            current_context: None,
+            contexts: HashMap::new(),
+            notify: Vec::new(),
+            secret_providers: BTreeMap::new(),
+            subscriptions: Vec::new(),
+            target_groups: BTreeMap::new(),
+            remotes: BTreeMap::new(),
+            shadowed: Vec::new(),
+        }
+    }
 }
 
 impl BridleConfig {
+    /// Preferred editor for editing profiles.
+    /// Falls back to $EDITOR env var, then "vi".
     pub fn editor(&self) -> String {
-        self.editor
+        self.settings
+            .editor
             .clone()
             .or_else(|| std::env::var("EDITOR").ok())
             .unwrap_or_else(|| "vi".to_string())
@@ -75,49 +547,176 @@ impl BridleConfig {
         let args: Vec<String> = parts.map(String::from).collect();
         (program, args)
     }
+
+    /// JSON Schema describing `config.toml`, for editor completion and validation.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(BridleConfig))
+            .expect("schema serializes to JSON")
+    }
 }
 
 impl BridleConfig {
     /// Load configuration from the default location.
-    pub fn load() -> crate::error::Result<Self> {
+    ///
+    /// Files written by an older bridle are migrated to the current layout and
+    /// rewritten; the original is kept next to it (see [`migration::backup_path`]).
+    pub fn load() -> Result<Self> {
         let path = Self::config_path()?;
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
-            let config: Self = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Self::default())
+        if !path.exists() {
+            let mut config = Self::default();
+            config.apply_overrides(layers::all_overrides()?)?;
+            config.active = config.load_state()?.active;
+            return Ok(config);
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let mut table: toml::Table = toml::from_str(&content)?;
+        let migrated_from = migration::migrate(&mut table, &Self::marker_dirs())?;
+        let mut config: Self = toml::Value::Table(table).try_into()?;
+
+        if let Some(from) = migrated_from {
+            write_atomic(&migration::backup_path(&path, from), &content)?;
+            config.save()?;
         }
+
+        config.apply_overrides(layers::all_overrides()?)?;
+        // Entries still in config.toml predate the state dir; recorded state wins.
+        let state = config.load_state()?;
+        config.active.extend(state.active);
+        Ok(config)
+    }
+
+    /// Applies `overrides` in order, remembering the values they replace so
+    /// [`BridleConfig::save`] does not persist them.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidValue`] naming the override's source if a value
+    /// does not parse.
+    pub fn apply_overrides(&mut self, overrides: Vec<Override>) -> Result<()> {
+        for o in overrides {
+            let file_value = match self.shadowed.iter().position(|s| s.key == o.key) {
+                Some(i) => self.shadowed.remove(i).file_value,
+                None => self.get_value(o.key)?,
+            };
+            self.set_value(o.key, &o.value)
+                .map_err(|e| layers::attribute(e, &o.source))?;
+            self.shadowed.push(Shadowed {
+                key: o.key,
+                file_value,
+                effective: self.get_value(o.key)?,
+            });
+        }
+        Ok(())
+    }
+
+    /// Copy of `self` with overridden keys restored to their file values,
+    /// unless they were changed after loading.
+    fn file_layer(&self) -> Result<Self> {
+        let mut file = self.clone();
+        for s in &self.shadowed {
+            if file.get_value(s.key)? == s.effective {
+                file.set_value(s.key, &s.file_value)?;
+            }
+        }
+        Ok(file)
+    }
+
+    pub fn state_paths(&self) -> Result<StatePaths> {
+        Ok(StatePaths::resolve()?.for_context(self.current_context()))
+    }
+
+    fn load_state(&self) -> Result<PersistedState> {
+        PersistedState::load(&self.state_paths()?)
+    }
+
+    /// Live config directories of the built-in harnesses, for marker-file migration.
+    fn marker_dirs() -> Vec<(String, PathBuf)> {
+        HarnessRegistry::builtin()
+            .iter()
+            .filter_map(|e| Some((e.id().to_string(), e.harness().config_dir().ok()?)))
+            .collect()
     }
 
     /// Get the default configuration file path.
-    pub fn config_path() -> crate::error::Result<PathBuf> {
+    pub fn config_path() -> Result<PathBuf> {
         Self::config_dir().map(|d| d.join("config.toml"))
     }
 
     /// Get the configuration directory path.
     ///
     /// Respects the `BRIDLE_CONFIG_DIR` environment variable for testing.
-    pub fn config_dir() -> crate::error::Result<PathBuf> {
+    pub fn config_dir() -> Result<PathBuf> {
         if let Ok(dir) = std::env::var("BRIDLE_CONFIG_DIR") {
             return Ok(PathBuf::from(dir));
         }
         harness_locate::platform::config_dir()
             .map(|d| d.join("bridle"))
-            .map_err(|e| crate::error::Error::NoConfigFound(e.to_string()))
+            .map_err(|e| Error::ConfigDirUnavailable(e.to_string()))
     }
 
     /// Get the profiles directory path.
-    pub fn profiles_dir() -> crate::error::Result<PathBuf> {
-        Self::config_dir().map(|d| d.join("profiles"))
+    ///
+    /// Follows the current context (see [`BridleConfig::context_profiles_dir`]).
+    pub fn profiles_dir() -> Result<PathBuf> {
+        let config = Self::load()?;
+        config.context_profiles_dir(config.current_context())
     }
 
     /// Save configuration to the default location.
-    pub fn save(&self) -> crate::error::Result<()> {
+    ///
+    /// Active profiles are written to the state directory instead.
+    pub fn save(&self) -> Result<()> {
         let path = Self::config_path()?;
-        let content =
-            toml::to_string_pretty(self).map_err(|e| crate::error::Error::Config(e.to_string()))?;
-        std::fs::write(&path, content)?;
+        let content = toml::to_string_pretty(&self.file_layer()?)
+            .map_err(|e| Error::Config(e.to_string()))?;
+        write_atomic(&path, content)?;
+
+        let paths = self.state_paths()?;
+        // Keep what bridle recorded besides active profiles, such as usage.
+        let mut state = PersistedState::load(&paths).unwrap_or_default();
+        state.active = self.active.clone();
+        state.save(&paths)
+    }
+
+    /// Name of the context in use.
+    pub fn current_context(&self) -> &str {
+        self.current_context.as_deref().unwrap_or(DEFAULT_CONTEXT)
+    }
+
+    /// Names of all contexts, starting with [`DEFAULT_CONTEXT`].
+    pub fn context_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.contexts.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names.insert(0, DEFAULT_CONTEXT);
+        names
+    }
+
+    /// Profiles directory of the context `name`.
+    ///
+    /// # Errors
+    /// Returns [`Error::ContextNotFound`] if no such context exists.
+    pub fn context_profiles_dir(&self, name: &str) -> Result<PathBuf> {
+        let config_dir = Self::config_dir()?;
+        if name == DEFAULT_CONTEXT {
+            return Ok(config_dir.join("profiles"));
+        }
+        let context = self
+            .contexts
+            .get(name)
+            .ok_or_else(|| Error::ContextNotFound(name.to_string()))?;
+        match &context.profiles_dir {
+            Some(dir) => crate::harness::expand_home(dir),
+            None => Ok(config_dir.join("contexts").join(name).join("profiles")),
+        }
+    }
+
+    /// Switches to the context `name` and loads its active-profile state.
+    ///
+    /// # Errors
+    /// Returns [`Error::ContextNotFound`] if no such context exists.
+    pub fn use_context(&mut self, name: &str) -> Result<()> {
+        self.set_value("context", name)?;
+        self.active = self.load_state()?.active;
         Ok(())
     }
 
@@ -132,6 +731,27 @@ impl BridleConfig {
             .insert(harness_id.to_string(), profile.to_string());
     }
 
+    /// Switch counts and last-used times of `harness_id`'s profiles, by profile name.
+    pub fn profile_usage(&self, harness_id: &str) -> BTreeMap<String, ProfileUsage> {
+        self.load_state()
+            .ok()
+            .and_then(|mut state| state.usage.remove(harness_id))
+            .unwrap_or_default()
+    }
+
+    /// Targets (`<harness>/<profile>`) last chosen when installing from `source`.
+    pub fn last_install_targets(&self, source: &str) -> Vec<String> {
+        self.load_state()
+            .ok()
+            .and_then(|mut state| state.install_targets.remove(source))
+            .unwrap_or_default()
+    }
+
+    /// Remembers `targets` for the next install from `source`.
+    pub fn remember_install_targets(&self, source: &str, targets: Vec<String>) -> Result<()> {
+        PersistedState::record_install_targets(&self.state_paths()?, source, targets)
+    }
+
     /// Clear the active profile for a harness.
     pub fn clear_active_profile(&mut self, harness_id: &str) {
         self.active.remove(harness_id);
@@ -145,6 +765,27 @@ impl BridleConfig {
         self.profile_marker = enabled;
     }
 
+    /// Returns the `[harness.<id>]` overrides for a harness, if any.
+    pub fn harness_override(&self, harness_id: &str) -> Option<&HarnessOverride> {
+        self.harness.get(harness_id)
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Parses a boolean setting value such as `true`, `off`, or `1`.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidValue`] if `value` is not a recognized boolean.
+    pub fn parse_bool(key: &str, value: &str) -> Result<bool> {
+        parse_bool(key, value)
+    }
+
     pub fn default_harness(&self) -> Option<&str> {
         self.default_harness.as_deref()
     }
@@ -152,4 +793,297 @@ impl BridleConfig {
     pub fn set_default_harness(&mut self, harness_id: Option<&str>) {
         self.default_harness = harness_id.map(String::from);
     }
+
+    /// Keys accepted by [`BridleConfig::get_value`] and [`BridleConfig::set_value`]:
+    /// [`Settings::KEYS`] plus a few top-level options.
+    pub const VALUE_KEYS: &'static [&'static str] = &[
+        "editor",
+        "copy_strategy",
+        "switch_strategy",
+        "symlinks",
+        "exclude",
+        "auto_backup",
+        "check_after_switch",
+        "max_file_size",
+        "large_files",
+        "max_profile_size",
+        "extraction_max_depth",
+        "extraction_max_entries",
+        "confirm",
+        "confirm_projects",
+        "insights",
+        "check_subscriptions",
+        "mirror",
+        "sync_remote",
+        "machine",
+        "ca_bundle",
+        "insecure",
+        "policy",
+        "http_timeout_secs",
+        "http_retries",
+        "http_backoff_ms",
+        "color",
+        "profile_marker",
+        "default_harness",
+        "context",
+    ];
+
+    /// Returns the value of `key` formatted for display.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownSetting`] if `key` is not one of [`BridleConfig::VALUE_KEYS`].
+    pub fn get_value(&self, key: &str) -> Result<String> {
+        match key {
+            "profile_marker" => Ok(self.profile_marker.to_string()),
+            "default_harness" => Ok(self.default_harness.clone().unwrap_or_default()),
+            "context" => Ok(self.current_context().to_string()),
+            _ => self.settings.get(key),
+        }
+    }
+
+    /// Parses `value` and stores it under `key`. An empty value clears
+    /// `default_harness` and selects the default context.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownSetting`], [`Error::InvalidValue`], or
+    /// [`Error::ContextNotFound`] for an unknown context.
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "profile_marker" => self.profile_marker = parse_bool(key, value)?,
+            "default_harness" => {
+                self.default_harness = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from);
+            }
+            "context" => {
+                let name = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .unwrap_or(DEFAULT_CONTEXT);
+                if name != DEFAULT_CONTEXT && !self.contexts.contains_key(name) {
+                    return Err(Error::ContextNotFound(name.to_string()));
+                }
+                self.current_context = (name != DEFAULT_CONTEXT).then(|| name.to_string());
+            }
+            _ => self.settings.set(key, value)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_when_table_missing() {
+        let config: BridleConfig = toml::from_str("profile_marker = true\n").unwrap();
+        assert!(config.settings().auto_backup);
+        assert!(config.settings().confirm);
+        assert_eq!(config.settings().copy_strategy, CopyStrategy::Copy);
+        assert_eq!(config.settings().color, ColorChoice::Auto);
+    }
+
+    #[test]
+    fn settings_table_round_trips() {
+        let content = r#"
+[settings]
+editor = "code --wait"
+copy_strategy = "hardlink"
+exclude = ["*.log"]
+auto_backup = false
+max_file_size = "10MB"
+large_files = "skip"
+max_profile_size = 0
+color = "never"
+"#;
+        let config: BridleConfig = toml::from_str(content).unwrap();
+        assert_eq!(config.editor(), "code --wait");
+        assert_eq!(config.settings().copy_strategy, CopyStrategy::Hardlink);
+        assert_eq!(config.settings().max_file_size, 10 << 20);
+        assert_eq!(config.settings().large_files, LargeFilePolicy::Skip);
+        assert_eq!(config.settings().max_profile_size, 0);
+        assert!(!config.settings().auto_backup);
+        assert!(config.settings().confirm);
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: BridleConfig = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.settings(), config.settings());
+    }
+
+    #[test]
+    fn load_migrates_and_backs_up_legacy_config() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _env = crate::test_utils::ConfigEnvGuard::new(temp.path());
+        let path = BridleConfig::config_path().unwrap();
+        std::fs::write(&path, "editor = \"nano\"\n[active]\nopencode = \"work\"\n").unwrap();
+
+        let config = BridleConfig::load().unwrap();
+        assert_eq!(config.version, migration::CURRENT_VERSION);
+        assert_eq!(config.settings().editor.as_deref(), Some("nano"));
+        assert_eq!(config.active_profile_for("opencode"), Some("work"));
+
+        let backup = migration::backup_path(&path, 0);
+        assert!(std::fs::read_to_string(backup).unwrap().contains("nano"));
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        assert!(rewritten.contains(&format!("version = {}", migration::CURRENT_VERSION)));
+        assert!(!rewritten.contains("[active]"));
+    }
+
+    #[test]
+    fn settings_set_parses_values() {
+        let mut settings = Settings::default();
+        settings.set("copy_strategy", "Hardlink").unwrap();
+        settings.set("exclude", "*.log, cache").unwrap();
+        settings.set("confirm", "off").unwrap();
+        settings.set("color", "always").unwrap();
+        settings.set("mirror", " ~/skill-mirror ").unwrap();
+        settings.set("http_retries", "5").unwrap();
+
+        assert_eq!(settings.get("copy_strategy").unwrap(), "hardlink");
+        assert_eq!(settings.get("exclude").unwrap(), "*.log,cache");
+        assert_eq!(settings.get("confirm").unwrap(), "false");
+        assert_eq!(settings.get("color").unwrap(), "always");
+        assert_eq!(settings.get("mirror").unwrap(), "~/skill-mirror");
+        assert_eq!(settings.http_retries, 5);
+        settings.set("mirror", "").unwrap();
+        assert_eq!(settings.mirror, None);
+    }
+
+    #[test]
+    fn settings_set_rejects_bad_input() {
+        let mut settings = Settings::default();
+        assert!(matches!(
+            settings.set("color", "rainbow"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            settings.set("nope", "1"),
+            Err(Error::UnknownSetting(_))
+        ));
+    }
+
+    #[test]
+    fn contexts_keep_separate_profiles_and_state() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _env = crate::test_utils::ConfigEnvGuard::new(temp.path());
+
+        let mut config = BridleConfig::load().unwrap();
+        config.set_active_profile("opencode", "home");
+        config
+            .contexts
+            .insert("work".to_string(), ContextConfig::default());
+        config.save().unwrap();
+
+        assert_eq!(config.context_names(), vec![DEFAULT_CONTEXT, "work"]);
+        assert_eq!(
+            config.context_profiles_dir("work").unwrap(),
+            temp.path().join("contexts/work/profiles")
+        );
+
+        config.use_context("work").unwrap();
+        assert_eq!(config.active_profile_for("opencode"), None);
+        config.set_active_profile("opencode", "client");
+        config.save().unwrap();
+
+        let reloaded = BridleConfig::load().unwrap();
+        assert_eq!(reloaded.current_context(), "work");
+        assert_eq!(reloaded.active_profile_for("opencode"), Some("client"));
+        assert_eq!(
+            BridleConfig::profiles_dir().unwrap(),
+            temp.path().join("contexts/work/profiles")
+        );
+
+        let mut config = reloaded;
+        config.use_context(DEFAULT_CONTEXT).unwrap();
+        assert_eq!(config.active_profile_for("opencode"), Some("home"));
+        assert!(matches!(
+            config.use_context("missing"),
+            Err(Error::ContextNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn target_groups_and_remembered_targets() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _env = crate::test_utils::ConfigEnvGuard::new(temp.path());
+        std::fs::write(
+            BridleConfig::config_path().unwrap(),
+            "[target-groups]\ndaily = [\"claude-code/default\", \"opencode/default\"]\n",
+        )
+        .unwrap();
+
+        let config = BridleConfig::load().unwrap();
+        assert_eq!(
+            config.target_groups["daily"],
+            vec!["claude-code/default", "opencode/default"]
+        );
+        assert!(config.last_install_targets("org/skills").is_empty());
+
+        config
+            .remember_install_targets("org/skills", vec!["opencode/work".to_string()])
+            .unwrap();
+        config.save().unwrap();
+        let reloaded = BridleConfig::load().unwrap();
+        assert_eq!(
+            reloaded.last_install_targets("org/skills"),
+            vec!["opencode/work"]
+        );
+        assert!(reloaded.last_install_targets("org/other").is_empty());
+        assert!(
+            std::fs::read_to_string(BridleConfig::config_path().unwrap())
+                .unwrap()
+                .contains("[target-groups]")
+        );
+    }
+
+    #[test]
+    fn json_schema_describes_config_tables() {
+        let schema = BridleConfig::json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("settings"));
+        assert!(properties.contains_key("harness"));
+        assert!(!properties.contains_key("active"));
+
+        let defs = schema["$defs"].as_object().unwrap();
+        assert!(defs["Settings"]["properties"]["copy_strategy"].is_object());
+    }
+
+    #[test]
+    fn overrides_apply_but_are_not_saved() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _env = crate::test_utils::ConfigEnvGuard::new(temp.path());
+        let path = BridleConfig::config_path().unwrap();
+        std::fs::write(&path, "[settings]\nauto_backup = true\neditor = \"nano\"\n").unwrap();
+
+        unsafe { std::env::set_var("BRIDLE_NO_BACKUP", "1") };
+        let mut config = BridleConfig::load().unwrap();
+        config
+            .apply_overrides(vec![Override {
+                key: "editor",
+                value: "vim".to_string(),
+                source: "--editor".to_string(),
+            }])
+            .unwrap();
+        assert!(!config.settings().auto_backup);
+        assert_eq!(config.settings().editor.as_deref(), Some("vim"));
+
+        config.settings_mut().confirm = false;
+        config.save().unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("auto_backup = true"));
+        assert!(saved.contains("editor = \"nano\""));
+        assert!(saved.contains("confirm = false"));
+    }
+
+    #[test]
+    fn invalid_env_override_names_the_variable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _env = crate::test_utils::ConfigEnvGuard::new(temp.path());
+
+        unsafe { std::env::set_var("BRIDLE_COPY_STRATEGY", "rsync") };
+        match BridleConfig::load() {
+            Err(Error::InvalidValue { key, .. }) => assert_eq!(key, "BRIDLE_COPY_STRATEGY"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
 }
@@ -1,90 +1,82 @@
 //! JSONC parsing utilities for OpenCode config files.
+//!
+//! Comments and trailing commas are blanked out with spaces rather than
+//! removed, so the stripped text keeps every line and column of the
+//! original and a parse error points at the right place in the file.
 
 pub fn strip_jsonc_comments(input: &str) -> String {
     let mut result = String::with_capacity(input.len());
     let mut chars = input.chars().peekable();
     let mut in_string = false;
     let mut escape_next = false;
+    // Where in `result` the last comma went, until a value follows it.
+    let mut last_comma = None;
 
     while let Some(c) = chars.next() {
-        if escape_next {
-            result.push(c);
-            escape_next = false;
-            continue;
-        }
-
-        if c == '\\' && in_string {
-            result.push(c);
-            escape_next = true;
-            continue;
-        }
-
-        if c == '"' && !escape_next {
-            in_string = !in_string;
+        if in_string {
             result.push(c);
+            if escape_next {
+                escape_next = false;
+            } else if c == '\\' {
+                escape_next = true;
+            } else if c == '"' {
+                in_string = false;
+            }
             continue;
         }
 
-        if !in_string && c == '/' {
-            match chars.peek() {
-                Some('/') => {
-                    chars.next();
-                    while let Some(&ch) = chars.peek() {
-                        if ch == '\n' {
-                            break;
-                        }
-                        chars.next();
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                blank(&mut result, c);
+                while let Some(&ch) = chars.peek() {
+                    if ch == '\n' {
+                        break;
                     }
-                }
-                Some('*') => {
+                    blank(&mut result, ch);
                     chars.next();
-                    while let Some(ch) = chars.next() {
-                        if ch == '*' && chars.peek() == Some(&'/') {
-                            chars.next();
-                            break;
-                        }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                blank(&mut result, c);
+                while let Some(ch) = chars.next() {
+                    blank(&mut result, ch);
+                    if ch == '*' && chars.peek() == Some(&'/') {
+                        blank(&mut result, '/');
+                        chars.next();
+                        break;
                     }
                 }
-                _ => result.push(c),
             }
-        } else {
-            result.push(c);
-        }
-    }
-    strip_trailing_commas(&result)
-}
-
-fn strip_trailing_commas(input: &str) -> String {
-    let mut result = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
-    let mut in_string = false;
-
-    while let Some(c) = chars.next() {
-        if c == '"' && !result.ends_with('\\') {
-            in_string = !in_string;
-            result.push(c);
-            continue;
-        }
-
-        if !in_string && c == ',' {
-            let mut lookahead = chars.clone();
-            let has_trailing = loop {
-                match lookahead.next() {
-                    Some(ch) if ch.is_whitespace() => continue,
-                    Some(']') | Some('}') => break true,
-                    _ => break false,
+            ',' => {
+                last_comma = Some(result.len());
+                result.push(c);
+            }
+            ']' | '}' => {
+                if let Some(at) = last_comma.take() {
+                    result.replace_range(at..=at, " ");
                 }
-            };
-            if !has_trailing {
                 result.push(c);
             }
-        } else {
-            result.push(c);
+            c if c.is_whitespace() => result.push(c),
+            _ => {
+                last_comma = None;
+                in_string = c == '"';
+                result.push(c);
+            }
         }
     }
     result
 }
 
+/// Stands in for `c` with as many spaces as it takes bytes, keeping line breaks.
+fn blank(result: &mut String, c: char) {
+    if c == '\n' || c == '\r' {
+        result.push(c);
+    } else {
+        result.extend(std::iter::repeat_n(' ', c.len_utf8()));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,7 +94,7 @@ mod tests {
     fn strips_block_comments() {
         let input = r#"{"key": /* block */ "value"}"#;
         let result = strip_jsonc_comments(input);
-        assert_eq!(result, r#"{"key":  "value"}"#);
+        assert_eq!(result, r#"{"key":             "value"}"#);
     }
 
     #[test]
@@ -116,6 +108,17 @@ mod tests {
     fn strips_trailing_commas() {
         let input = r#"{"a": 1, "b": 2,}"#;
         let result = strip_jsonc_comments(input);
-        assert_eq!(result, r#"{"a": 1, "b": 2}"#);
+        assert_eq!(result, r#"{"a": 1, "b": 2 }"#);
+    }
+
+    #[test]
+    fn keeps_lines_and_columns() {
+        let input = "{\n  /* two\n     lines, é */ \"a\": [1, 2,], // c\n  \"b\": \"x\\\\\", // s\n  \"c\": nope\n}";
+        let result = strip_jsonc_comments(input);
+        assert_eq!(result.len(), input.len());
+        assert_eq!(result.lines().count(), input.lines().count());
+        assert!(result.contains("\"b\": \"x\\\\\",     \n"));
+        let error = serde_json::from_str::<serde_json::Value>(&result).unwrap_err();
+        assert_eq!((error.line(), error.column()), (5, 9));
     }
 }
@@ -0,0 +1,153 @@
+//! Layered overrides on top of `config.toml`.
+//!
+//! Precedence, lowest first: built-in defaults, `config.toml`, `BRIDLE_*`
+//! environment variables, then global command-line flags. Overrides only
+//! affect the loaded [`BridleConfig`](super::BridleConfig); saving writes the
+//! file's own values back for any key still holding an overridden value.
+
+use std::sync::OnceLock;
+
+use crate::error::{Error, Result};
+
+/// Environment variables mapped onto config keys.
+///
+/// Each entry is `(variable, key, negated)`; negated variables store the
+/// inverse of a boolean (e.g. `BRIDLE_NO_BACKUP=1` sets `auto_backup = false`).
+pub const ENV_VARS: &[(&str, &str, bool)] = &[
+    ("BRIDLE_EDITOR", "editor", false),
+    ("BRIDLE_COPY_STRATEGY", "copy_strategy", false),
+    ("BRIDLE_SWITCH_STRATEGY", "switch_strategy", false),
+    ("BRIDLE_SYMLINKS", "symlinks", false),
+    ("BRIDLE_EXCLUDE", "exclude", false),
+    ("BRIDLE_AUTO_BACKUP", "auto_backup", false),
+    ("BRIDLE_NO_BACKUP", "auto_backup", true),
+    ("BRIDLE_MAX_FILE_SIZE", "max_file_size", false),
+    ("BRIDLE_LARGE_FILES", "large_files", false),
+    ("BRIDLE_MAX_PROFILE_SIZE", "max_profile_size", false),
+    ("BRIDLE_EXTRACTION_MAX_DEPTH", "extraction_max_depth", false),
+    (
+        "BRIDLE_EXTRACTION_MAX_ENTRIES",
+        "extraction_max_entries",
+        false,
+    ),
+    ("BRIDLE_CONFIRM", "confirm", false),
+    ("BRIDLE_NO_CONFIRM", "confirm", true),
+    ("BRIDLE_CONFIRM_PROJECTS", "confirm_projects", false),
+    ("BRIDLE_INSIGHTS", "insights", false),
+    ("BRIDLE_CHECK_SUBSCRIPTIONS", "check_subscriptions", false),
+    ("BRIDLE_CHECK_AFTER_SWITCH", "check_after_switch", false),
+    ("BRIDLE_MIRROR", "mirror", false),
+    ("BRIDLE_SYNC_REMOTE", "sync_remote", false),
+    ("BRIDLE_MACHINE", "machine", false),
+    ("BRIDLE_CA_BUNDLE", "ca_bundle", false),
+    ("BRIDLE_INSECURE", "insecure", false),
+    ("BRIDLE_POLICY", "policy", false),
+    ("BRIDLE_HTTP_TIMEOUT_SECS", "http_timeout_secs", false),
+    ("BRIDLE_HTTP_RETRIES", "http_retries", false),
+    ("BRIDLE_HTTP_BACKOFF_MS", "http_backoff_ms", false),
+    ("BRIDLE_COLOR", "color", false),
+    ("BRIDLE_PROFILE_MARKER", "profile_marker", false),
+    ("BRIDLE_DEFAULT_HARNESS", "default_harness", false),
+    ("BRIDLE_CONTEXT", "context", false),
+];
+
+/// A single `key = value` override and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Override {
+    /// Config key, as accepted by `BridleConfig::set_value`.
+    pub key: &'static str,
+    /// Value to apply.
+    pub value: String,
+    /// Environment variable or flag that supplied the value, for error messages.
+    pub source: String,
+}
+
+static CLI_OVERRIDES: OnceLock<Vec<Override>> = OnceLock::new();
+
+/// Records overrides from global command-line flags. Only the first call has effect.
+pub fn set_cli_overrides(overrides: Vec<Override>) {
+    let _ = CLI_OVERRIDES.set(overrides);
+}
+
+/// Overrides from the environment, in [`ENV_VARS`] order.
+///
+/// # Errors
+/// Returns [`Error::InvalidValue`] if a negated variable is not a boolean.
+pub fn env_overrides() -> Result<Vec<Override>> {
+    let mut overrides = Vec::new();
+    for &(var, key, negated) in ENV_VARS {
+        let Ok(value) = std::env::var(var) else {
+            continue;
+        };
+        let value = if negated {
+            let set = super::BridleConfig::parse_bool(var, &value)?;
+            (!set).to_string()
+        } else {
+            value
+        };
+        overrides.push(Override {
+            key,
+            value,
+            source: var.to_string(),
+        });
+    }
+    Ok(overrides)
+}
+
+/// Environment overrides followed by command-line overrides.
+pub fn all_overrides() -> Result<Vec<Override>> {
+    let mut overrides = env_overrides()?;
+    overrides.extend(CLI_OVERRIDES.get().into_iter().flatten().cloned());
+    Ok(overrides)
+}
+
+/// Rewrites an [`Error::InvalidValue`] to name the override's source.
+pub(crate) fn attribute(err: Error, source: &str) -> Error {
+    match err {
+        Error::InvalidValue { value, .. } => Error::InvalidValue {
+            key: source.to_string(),
+            value,
+        },
+        other => other,
+    }
+}
+
+/// A key whose file value was replaced by an override.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Shadowed {
+    pub key: &'static str,
+    /// Value from `config.toml` (or the default).
+    pub file_value: String,
+    /// Value after all overrides were applied.
+    pub effective: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_names_the_source() {
+        let err = Error::InvalidValue {
+            key: "copy_strategy".to_string(),
+            value: "rsync".to_string(),
+        };
+        match attribute(err, "BRIDLE_COPY_STRATEGY") {
+            Error::InvalidValue { key, value } => {
+                assert_eq!(key, "BRIDLE_COPY_STRATEGY");
+                assert_eq!(value, "rsync");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn env_var_keys_are_known() {
+        for &(_, key, _) in ENV_VARS {
+            assert!(
+                super::super::BridleConfig::VALUE_KEYS.contains(&key),
+                "{key}"
+            );
+        }
+    }
+}
@@ -1,63 +1,89 @@
+mod settings_layers;
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs::DirEntry;
 use std::path::{Path, PathBuf};
 
 pub use harness_locate::DirectoryStructure;
 use harness_locate::{Harness, Scope};
-
-use crate::config::jsonc::strip_jsonc_comments;
-use crate::config::types::{McpServerInfo, ResourceSummary};
+pub use settings_layers::{
+    MANAGED_SETTINGS_FILE, MergedSettings, managed_settings_dir, merge_settings, settings_files,
+};
+
+use super::platform;
+use crate::config::types::{
+    ExtractionCategory, ExtractionIssue, ExtractionIssueKind, McpServerInfo, ResourceMeta,
+    ResourceSummary,
+};
 use crate::error::{Error, Result};
-use crate::harness::HarnessConfig;
-
-pub fn extract_mcp_from_opencode_config(profile_path: &Path) -> Result<Vec<McpServerInfo>> {
-    let config_path = profile_path.join("opencode.jsonc");
-    if !config_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| Error::Config(format!("Failed to read opencode.jsonc: {}", e)))?;
-    let content = strip_jsonc_comments(&content);
-
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| Error::Config(format!("Failed to parse opencode.jsonc: {}", e)))?;
+use crate::harness::schema::{
+    AmpSettings, ClaudeMcpConfig, ClaudeSettings, GooseConfig, HarnessSettings, JsonMcpServer,
+    McpServers, OpenCodeConfig,
+};
+use crate::harness::{HarnessConfig, ResourceDir, ResourceKind, ResourceLayout, ResourceStructure};
+use crate::install::convert::split_frontmatter;
+use crate::install::discovery::parse_yaml_frontmatter;
+
+/// MCP servers found in a profile, and an issue for each entry that does
+/// not parse.
+type McpExtraction = (Vec<McpServerInfo>, Vec<ExtractionIssue>);
+
+/// An issue naming each server in `servers` that did not parse.
+fn invalid_servers<T>(servers: &McpServers<T>, path: &Path) -> Vec<ExtractionIssue> {
+    servers
+        .invalid
+        .iter()
+        .map(|(name, invalid)| {
+            ExtractionIssue::new(
+                ExtractionCategory::Mcp,
+                ExtractionIssueKind::Parse,
+                format!("server '{name}': {}", invalid.error),
+            )
+            .at(path)
+        })
+        .collect()
+}
 
-    let mcp_obj = match config.get("mcp").and_then(|v| v.as_object()) {
-        Some(obj) => obj,
-        None => return Ok(Vec::new()),
+pub fn extract_mcp_from_opencode_config(profile_path: &Path) -> Result<McpExtraction> {
+    let Some(config) = OpenCodeConfig::load(profile_path)? else {
+        return Ok(Default::default());
     };
 
-    let servers = mcp_obj
-        .iter()
-        .map(|(name, value)| {
-            let server_type = value.get("type").and_then(|v| v.as_str()).map(String::from);
-            let command = value
-                .get("command")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            let args = value.get("args").and_then(|v| v.as_array()).map(|arr| {
-                arr.iter()
-                    .filter_map(|a| a.as_str().map(String::from))
-                    .collect()
-            });
-            let url = value.get("url").and_then(|v| v.as_str()).map(String::from);
+    let issues = invalid_servers(&config.mcp, &profile_path.join(OpenCodeConfig::FILENAME));
+    let servers = config
+        .mcp
+        .into_iter()
+        .map(|(name, server)| {
+            let embedded_args = server
+                .command
+                .as_ref()
+                .map(|c| c.embedded_args().to_vec())
+                .filter(|a| !a.is_empty());
             McpServerInfo {
-                name: name.clone(),
-                enabled: true,
-                server_type,
-                command,
-                args,
-                url,
+                name,
+                enabled: server.enabled.unwrap_or(true),
+                server_type: server.server_type,
+                command: server
+                    .command
+                    .as_ref()
+                    .and_then(|c| c.program())
+                    .map(String::from),
+                args: server.args.or(embedded_args),
+                url: server.url,
             }
         })
         .collect();
 
-    Ok(servers)
+    Ok((servers, issues))
 }
 
+/// The MCP servers in a profile, with an issue for each one that does not
+/// parse.
 pub fn extract_mcp_servers(
     harness: &dyn HarnessConfig,
     profile_path: &Path,
-) -> Result<Vec<McpServerInfo>> {
+) -> Result<McpExtraction> {
     match harness.id() {
         "opencode" => extract_mcp_from_opencode_config(profile_path),
         "amp-code" => extract_mcp_from_ampcode_config(profile_path),
@@ -67,23 +93,28 @@ pub fn extract_mcp_servers(
     }
 }
 
-fn extract_mcp_generic(
-    harness: &dyn HarnessConfig,
-    profile_path: &Path,
-) -> Result<Vec<McpServerInfo>> {
+fn extract_mcp_generic(harness: &dyn HarnessConfig, profile_path: &Path) -> Result<McpExtraction> {
     let mcp_filename = match harness.mcp_filename() {
         Some(f) => f,
-        None => return Ok(Vec::new()),
+        None => return Ok(Default::default()),
     };
 
     let profile_mcp_path = profile_path.join(&mcp_filename);
     if !profile_mcp_path.exists() {
-        return Ok(Vec::new());
+        return Ok(Default::default());
     }
 
     let content = std::fs::read_to_string(&profile_mcp_path)?;
-    let servers = harness.parse_mcp_servers(&content, &mcp_filename)?;
-    Ok(servers
+    let servers = harness
+        .parse_mcp_servers(&content, &mcp_filename)
+        .map_err(|e| match e {
+            Error::ConfigParse { message, .. } => Error::ConfigParse {
+                path: profile_mcp_path.clone(),
+                message,
+            },
+            e => e,
+        })?;
+    let servers = servers
         .into_iter()
         .map(|(name, enabled)| McpServerInfo {
             name,
@@ -93,269 +124,134 @@ fn extract_mcp_generic(
             args: None,
             url: None,
         })
-        .collect())
+        .collect();
+    Ok((servers, Vec::new()))
 }
 
-fn extract_mcp_from_claudecode_config(profile_path: &Path) -> Result<Vec<McpServerInfo>> {
-    let config_path = profile_path.join(".mcp.json");
-    if !config_path.exists() {
-        return Ok(Vec::new());
+fn json_mcp_server_info(name: String, server: JsonMcpServer) -> McpServerInfo {
+    McpServerInfo {
+        name,
+        enabled: !server.disabled,
+        server_type: Some("stdio".to_string()),
+        command: server.command,
+        args: server.args,
+        url: server.url,
     }
+}
 
-    let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| Error::Config(format!("Failed to read .mcp.json: {}", e)))?;
-
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| Error::Config(format!("Failed to parse .mcp.json: {}", e)))?;
-
-    let mcp_obj = match config.get("mcpServers").and_then(|v| v.as_object()) {
-        Some(obj) => obj,
-        None => return Ok(Vec::new()),
+fn extract_mcp_from_claudecode_config(profile_path: &Path) -> Result<McpExtraction> {
+    let Some(config) = ClaudeMcpConfig::load(profile_path)? else {
+        return Ok(Default::default());
     };
 
-    let servers = mcp_obj
-        .iter()
-        .map(|(name, value)| {
-            let disabled = value
-                .get("disabled")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-            let command = value
-                .get("command")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            let args = value.get("args").and_then(|v| v.as_array()).map(|arr| {
-                arr.iter()
-                    .filter_map(|a| a.as_str().map(String::from))
-                    .collect()
-            });
-            let url = value.get("url").and_then(|v| v.as_str()).map(String::from);
-            McpServerInfo {
-                name: name.clone(),
-                enabled: !disabled,
-                server_type: Some("stdio".to_string()),
-                command,
-                args,
-                url,
-            }
-        })
+    let issues = invalid_servers(
+        &config.mcp_servers,
+        &profile_path.join(ClaudeMcpConfig::FILENAME),
+    );
+    let servers = config
+        .mcp_servers
+        .into_iter()
+        .map(|(name, server)| json_mcp_server_info(name, server))
         .collect();
-
-    Ok(servers)
+    Ok((servers, issues))
 }
 
-fn extract_mcp_from_goose_config(profile_path: &Path) -> Result<Vec<McpServerInfo>> {
-    let config_path = profile_path.join("config.yaml");
-    if !config_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| Error::Config(format!("Failed to read config.yaml: {}", e)))?;
-
-    let config: serde_yaml::Value = serde_yaml::from_str(&content)
-        .map_err(|e| Error::Config(format!("Failed to parse config.yaml: {}", e)))?;
-
-    let extensions = match config.get("extensions").and_then(|v| v.as_mapping()) {
-        Some(obj) => obj,
-        None => return Ok(Vec::new()),
+fn extract_mcp_from_goose_config(profile_path: &Path) -> Result<McpExtraction> {
+    let Some(config) = GooseConfig::load(profile_path)? else {
+        return Ok(Default::default());
     };
 
-    let mcp_types = ["stdio", "sse", "http", "streamable_http"];
-    let servers = extensions
-        .iter()
-        .filter_map(|(name, value)| {
-            let name_str = name.as_str()?;
-            let ext_type = value.get("type").and_then(|v| v.as_str())?;
-            if !mcp_types.contains(&ext_type) {
-                return None;
-            }
-            let enabled = value
-                .get("enabled")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true);
-            let command = value.get("cmd").and_then(|v| v.as_str()).map(String::from);
-            let args = value.get("args").and_then(|v| v.as_sequence()).map(|arr| {
-                arr.iter()
-                    .filter_map(|a| a.as_str().map(String::from))
-                    .collect()
-            });
-            let url = value.get("url").and_then(|v| v.as_str()).map(String::from);
-            Some(McpServerInfo {
-                name: name_str.to_string(),
-                enabled,
-                server_type: Some(ext_type.to_string()),
-                command,
-                args,
-                url,
-            })
+    let issues = invalid_servers(
+        &config.extensions,
+        &profile_path.join(GooseConfig::FILENAME),
+    );
+    let servers = config
+        .extensions
+        .into_iter()
+        .filter(|(_, ext)| ext.is_mcp())
+        .map(|(name, ext)| McpServerInfo {
+            name,
+            enabled: ext.enabled.unwrap_or(true),
+            server_type: ext.ext_type,
+            command: ext.cmd,
+            args: ext.args,
+            url: ext.url,
         })
         .collect();
 
-    Ok(servers)
+    Ok((servers, issues))
 }
 
-fn extract_mcp_from_ampcode_config(profile_path: &Path) -> Result<Vec<McpServerInfo>> {
-    let config_path = profile_path.join("settings.json");
-    if !config_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| Error::Config(format!("Failed to read settings.json: {}", e)))?;
-
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| Error::Config(format!("Failed to parse settings.json: {}", e)))?;
-
-    let mcp_obj = match config.get("amp.mcpServers").and_then(|v| v.as_object()) {
-        Some(obj) => obj,
-        None => return Ok(Vec::new()),
+fn extract_mcp_from_ampcode_config(profile_path: &Path) -> Result<McpExtraction> {
+    let Some(config) = AmpSettings::load(profile_path)? else {
+        return Ok(Default::default());
     };
 
-    let servers = mcp_obj
-        .iter()
-        .map(|(name, value)| {
-            let command = value
-                .get("command")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            let args = value.get("args").and_then(|v| v.as_array()).map(|arr| {
-                arr.iter()
-                    .filter_map(|a| a.as_str().map(String::from))
-                    .collect()
-            });
-            let url = value.get("url").and_then(|v| v.as_str()).map(String::from);
-            McpServerInfo {
-                name: name.clone(),
-                enabled: true,
-                server_type: Some("stdio".to_string()),
-                command,
-                args,
-                url,
-            }
-        })
+    let issues = invalid_servers(
+        &config.mcp_servers,
+        &profile_path.join(AmpSettings::FILENAME),
+    );
+    let servers = config
+        .mcp_servers
+        .into_iter()
+        .map(|(name, server)| json_mcp_server_info(name, server))
         .collect();
-
-    Ok(servers)
+    Ok((servers, issues))
 }
 
-pub fn extract_theme(harness: &dyn HarnessConfig, profile_path: &Path) -> Option<String> {
-    match harness.id() {
-        "opencode" => {
-            let config_path = profile_path.join("opencode.jsonc");
-            if !config_path.exists() {
-                return None;
-            }
-            let content = std::fs::read_to_string(&config_path).ok()?;
-            let clean_json = strip_jsonc_comments(&content);
-            let parsed: serde_json::Value = serde_json::from_str(&clean_json).ok()?;
-            parsed
-                .get("theme")
-                .and_then(|v| v.as_str())
-                .map(String::from)
-        }
-        "goose" => {
-            let config_path = profile_path.join("config.yaml");
-            let content = std::fs::read_to_string(&config_path).ok()?;
-            let parsed: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
-            parsed
-                .get("GOOSE_CLI_THEME")
-                .and_then(|v| v.as_str())
-                .map(String::from)
-        }
-        "amp-code" => {
-            let config_path = profile_path.join("settings.json");
-            let content = std::fs::read_to_string(&config_path).ok()?;
-            let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
-            parsed
-                .get("amp.theme")
-                .and_then(|v| v.as_str())
-                .map(String::from)
-        }
-        "claude-code" => {
-            let config_path = profile_path.join("settings.json");
-            let content = std::fs::read_to_string(&config_path).ok()?;
-            let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
-            parsed
-                .get("theme")
-                .and_then(|v| v.as_str())
-                .map(String::from)
-        }
-        _ => None,
+fn load_settings(
+    harness: &dyn HarnessConfig,
+    profile_path: &Path,
+) -> Option<Box<dyn HarnessSettings>> {
+    fn boxed<T: HarnessSettings + 'static>(
+        config: Result<Option<T>>,
+    ) -> Option<Box<dyn HarnessSettings>> {
+        config
+            .ok()
+            .flatten()
+            .map(|c| Box::new(c) as Box<dyn HarnessSettings>)
     }
-}
 
-pub fn extract_model(harness: &dyn HarnessConfig, profile_path: &Path) -> Option<String> {
     match harness.id() {
-        "opencode" => extract_model_opencode(profile_path),
-        "claude-code" => extract_model_claude_code(profile_path),
-        "goose" => extract_model_goose(profile_path),
-        "amp-code" => extract_model_ampcode(profile_path),
+        "opencode" => boxed(OpenCodeConfig::load(profile_path)),
+        "claude-code" => boxed(ClaudeSettings::load(profile_path)),
+        "goose" => boxed(GooseConfig::load(profile_path)),
+        "amp-code" => boxed(AmpSettings::load(profile_path)),
         _ => None,
     }
 }
 
-fn extract_model_opencode(profile_path: &Path) -> Option<String> {
-    let config_path = profile_path.join("opencode.jsonc");
-    let content = std::fs::read_to_string(&config_path).ok()?;
-    let clean_json = strip_jsonc_comments(&content);
-    let parsed: serde_json::Value = serde_json::from_str(&clean_json).ok()?;
-
-    parsed
-        .get("model")
-        .and_then(|v| v.as_str())
-        .or_else(|| {
-            parsed
-                .get("agent")
-                .and_then(|a| a.get("general"))
-                .and_then(|g| g.get("model"))
-                .and_then(|v| v.as_str())
-        })
+pub fn extract_theme(harness: &dyn HarnessConfig, profile_path: &Path) -> Option<String> {
+    load_settings(harness, profile_path)?
+        .theme()
         .map(String::from)
 }
 
-fn extract_model_claude_code(profile_path: &Path) -> Option<String> {
-    let config_path = profile_path.join("settings.json");
-    let content = std::fs::read_to_string(&config_path).ok()?;
-    let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
-    parsed
-        .get("model")
-        .and_then(|v| v.as_str())
+pub fn extract_model(harness: &dyn HarnessConfig, profile_path: &Path) -> Option<String> {
+    load_settings(harness, profile_path)?
+        .model()
         .map(String::from)
 }
 
-fn extract_model_goose(profile_path: &Path) -> Option<String> {
-    let config_path = profile_path.join("config.yaml");
-    let content = std::fs::read_to_string(&config_path).ok()?;
-    let parsed: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
-    parsed
-        .get("GOOSE_MODEL")
-        .and_then(|v| v.as_str())
-        .map(String::from)
+/// How much of a profile extraction reads, so that huge profiles stay quick
+/// to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionLimits {
+    /// Directory levels below the profile whose changes invalidate a cached
+    /// extraction; files deeper down are never read.
+    pub max_depth: usize,
+    /// Entries read from any one directory; resource listings that stop here
+    /// are marked truncated.
+    pub max_entries: usize,
 }
 
-fn extract_model_ampcode(profile_path: &Path) -> Option<String> {
-    let config_path = profile_path.join("settings.json");
-    let content = std::fs::read_to_string(&config_path).ok()?;
-    let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
-
-    // AMP Code uses dotted keys like "amp.model.default" directly containing the model name
-    if let Some(model) = parsed.get("amp.model.default").and_then(|v| v.as_str()) {
-        return Some(model.to_string());
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            max_entries: 1000,
+        }
     }
-
-    // Fallback: nested amp.model object
-    parsed
-        .get("amp")
-        .and_then(|amp| amp.get("model"))
-        .and_then(|m| m.as_str())
-        .map(String::from)
-}
-
-fn dir_name_from_path(path: &Path) -> &str {
-    path.file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("skills")
 }
 
 fn fallback_dir_name(primary: &str) -> Option<&'static str> {
@@ -367,31 +263,58 @@ fn fallback_dir_name(primary: &str) -> Option<&'static str> {
     }
 }
 
-pub fn extract_skills(harness: &Harness, profile_path: &Path) -> (ResourceSummary, Option<String>) {
+/// The directory `layout` declares for `kind`, or why it could not be located.
+fn layout_entry(
+    layout: &ResourceLayout,
+    kind: ResourceKind,
+    category: ExtractionCategory,
+) -> std::result::Result<Option<&ResourceDir>, ExtractionIssue> {
+    match layout.error(kind) {
+        Some(e) => Err(ExtractionIssue::new(
+            category,
+            ExtractionIssueKind::Layout,
+            e,
+        )),
+        None => Ok(layout.get(kind)),
+    }
+}
+
+/// The issue for a resource directory at `path` that could not be listed.
+fn unreadable(category: ExtractionCategory, path: &Path, e: &std::io::Error) -> ExtractionIssue {
+    ExtractionIssue::new(category, ExtractionIssueKind::Io, e.to_string()).at(path)
+}
+
+pub fn extract_skills(
+    harness: &dyn HarnessConfig,
+    layout: &ResourceLayout,
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
     if harness.id() == "amp-code" {
-        return extract_ampcode_skills(profile_path);
+        return extract_ampcode_skills(profile_path, limits);
     }
 
-    match harness.skills(&Scope::Global) {
+    match layout_entry(layout, ResourceKind::Skills, ExtractionCategory::Skills) {
         Ok(Some(dir)) => {
-            let subdir = dir_name_from_path(&dir.path);
-            let summary = extract_resource_summary(profile_path, subdir, &dir.structure);
+            let subdir = dir.dir_name();
+            let summary = extract_resource_summary(profile_path, subdir, &dir.structure, limits);
             if !summary.items.is_empty() {
                 return (summary, None);
             }
             let md_summary = extract_resource_summary(
                 profile_path,
                 subdir,
-                &DirectoryStructure::Flat {
+                &ResourceStructure::Flat {
                     file_pattern: "*.md".to_string(),
                 },
+                limits,
             );
             if !md_summary.items.is_empty() || md_summary.directory_exists {
                 return (md_summary, None);
             }
             if let Some(fallback) = fallback_dir_name(subdir) {
                 let fallback_summary =
-                    extract_resource_summary(profile_path, fallback, &dir.structure);
+                    extract_resource_summary(profile_path, fallback, &dir.structure, limits);
                 if !fallback_summary.items.is_empty() {
                     return (fallback_summary, None);
                 }
@@ -399,78 +322,132 @@ pub fn extract_skills(harness: &Harness, profile_path: &Path) -> (ResourceSummar
             (summary, None)
         }
         Ok(None) => (ResourceSummary::default(), None),
-        Err(e) => (ResourceSummary::default(), Some(format!("skills: {}", e))),
+        Err(e) => (ResourceSummary::default(), Some(e)),
     }
 }
 
-fn extract_ampcode_skills(profile_path: &Path) -> (ResourceSummary, Option<String>) {
+/// Reads each skill's definition file (`<name>/SKILL.md` or `<name>.md`),
+/// keyed by skill name. Skills whose file cannot be read are left out.
+pub fn read_skill_files(
+    layout: &ResourceLayout,
+    profile_path: &Path,
+    skills: &[String],
+) -> BTreeMap<String, String> {
+    let subdir = layout
+        .get(ResourceKind::Skills)
+        .map_or(ResourceKind::Skills.canonical_dir(), ResourceDir::dir_name);
+    let dirs = [Some(subdir), fallback_dir_name(subdir)];
+    skills
+        .iter()
+        .filter_map(|name| {
+            let content = dirs
+                .iter()
+                .flatten()
+                .map(|dir| profile_path.join(dir))
+                .flat_map(|dir| {
+                    [
+                        dir.join(name).join("SKILL.md"),
+                        dir.join(format!("{name}.md")),
+                    ]
+                })
+                .find_map(|path| std::fs::read_to_string(path).ok())?;
+            Some((name.clone(), content))
+        })
+        .collect()
+}
+
+/// Reads the front matter `description` of each skill in `skill_files`.
+/// Skills without one are left out.
+pub fn extract_skill_descriptions(
+    skill_files: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    skill_files
+        .iter()
+        .filter_map(|(name, content)| {
+            let (_, description) = parse_yaml_frontmatter(content, Some(name))?;
+            Some((name.clone(), description?))
+        })
+        .collect()
+}
+
+fn extract_ampcode_skills(
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
     let skills_dir = profile_path.join("skills");
     if !skills_dir.exists() {
         return (ResourceSummary::default(), None);
     }
 
-    let entries = match std::fs::read_dir(&skills_dir) {
+    let (entries, truncated) = match read_entries(&skills_dir, limits.max_entries) {
         Ok(e) => e,
         Err(e) => {
             return (
                 ResourceSummary {
                     items: Vec::new(),
                     directory_exists: true,
+                    ..Default::default()
                 },
-                Some(format!("skills: {}", e)),
+                Some(unreadable(ExtractionCategory::Skills, &skills_dir, &e)),
             );
         }
     };
 
     let items: Vec<String> = entries
-        .filter_map(|e| e.ok())
+        .into_iter()
         .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
         .filter(|e| e.path().join("SKILL.md").exists())
-        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .map(|e| e.file_name().to_string_lossy().into_owned())
         .collect();
 
     (
         ResourceSummary {
             items,
             directory_exists: true,
+            truncated,
+            ..Default::default()
         },
         None,
     )
 }
 
 pub fn extract_commands(
-    harness: &Harness,
+    harness: &dyn HarnessConfig,
+    layout: &ResourceLayout,
     profile_path: &Path,
-) -> (ResourceSummary, Option<String>) {
+    limits: &ExtractionLimits,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
     if harness.id() == "goose" {
-        return extract_goose_recipes(profile_path);
+        return extract_goose_recipes(profile_path, limits);
     }
 
     if harness.id() == "amp-code" {
-        return extract_ampcode_commands(profile_path);
+        return extract_ampcode_commands(profile_path, limits);
     }
 
-    let dir_result = match harness.commands(&Scope::Global) {
-        Ok(Some(dir)) => {
-            let subdir = dir_name_from_path(&dir.path);
-            let summary = extract_resource_summary(profile_path, subdir, &dir.structure);
-            if !summary.items.is_empty() {
-                (summary, None)
-            } else if let Some(fallback) = fallback_dir_name(subdir) {
-                let fallback_summary =
-                    extract_resource_summary(profile_path, fallback, &dir.structure);
-                if !fallback_summary.items.is_empty() {
-                    (fallback_summary, None)
+    let dir_result =
+        match layout_entry(layout, ResourceKind::Commands, ExtractionCategory::Commands) {
+            Ok(Some(dir)) => {
+                let subdir = dir.dir_name();
+                let summary =
+                    extract_resource_summary(profile_path, subdir, &dir.structure, limits);
+                if !summary.items.is_empty() {
+                    (summary, None)
+                } else if let Some(fallback) = fallback_dir_name(subdir) {
+                    let fallback_summary =
+                        extract_resource_summary(profile_path, fallback, &dir.structure, limits);
+                    if !fallback_summary.items.is_empty() {
+                        (fallback_summary, None)
+                    } else {
+                        (summary, None)
+                    }
                 } else {
                     (summary, None)
                 }
-            } else {
-                (summary, None)
             }
-        }
-        Ok(None) => (ResourceSummary::default(), None),
-        Err(e) => (ResourceSummary::default(), Some(format!("commands: {}", e))),
-    };
+            Ok(None) => (ResourceSummary::default(), None),
+            Err(e) => (ResourceSummary::default(), Some(e)),
+        };
 
     if harness.id() == "opencode" {
         let (config_summary, config_err) = extract_commands_from_opencode_config(profile_path);
@@ -482,6 +459,8 @@ pub fn extract_commands(
             ResourceSummary {
                 items: merged_items,
                 directory_exists: dir_result.0.directory_exists || config_summary.directory_exists,
+                truncated: dir_result.0.truncated,
+                meta: dir_result.0.meta,
             },
             dir_result.1.or(config_err),
         );
@@ -490,39 +469,37 @@ pub fn extract_commands(
     dir_result
 }
 
-fn extract_commands_from_opencode_config(profile_path: &Path) -> (ResourceSummary, Option<String>) {
-    let config_path = profile_path.join("opencode.jsonc");
-    if !config_path.exists() {
-        return (ResourceSummary::default(), None);
-    }
-
-    let content = match std::fs::read_to_string(&config_path) {
-        Ok(c) => c,
-        Err(e) => return (ResourceSummary::default(), Some(format!("commands: {}", e))),
-    };
-
-    let clean_json = strip_jsonc_comments(&content);
-    let parsed: serde_json::Value = match serde_json::from_str(&clean_json) {
-        Ok(v) => v,
-        Err(e) => return (ResourceSummary::default(), Some(format!("commands: {}", e))),
+fn extract_commands_from_opencode_config(
+    profile_path: &Path,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
+    let config = match OpenCodeConfig::load(profile_path) {
+        Ok(Some(config)) => config,
+        Ok(None) => return (ResourceSummary::default(), None),
+        Err(e) => {
+            return (
+                ResourceSummary::default(),
+                Some(ExtractionIssue::from_error(
+                    ExtractionCategory::Commands,
+                    &e,
+                )),
+            );
+        }
     };
 
-    let commands = parsed
-        .get("command")
-        .and_then(|v| v.as_object())
-        .map(|obj| obj.keys().cloned().collect::<Vec<_>>())
-        .unwrap_or_default();
-
     (
         ResourceSummary {
-            items: commands,
+            items: config.command.into_keys().collect(),
             directory_exists: false,
+            ..Default::default()
         },
         None,
     )
 }
 
-fn extract_goose_recipes(profile_path: &Path) -> (ResourceSummary, Option<String>) {
+fn extract_goose_recipes(
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
     let commands_dir = profile_path.join("commands");
     let recipes_dir = profile_path.join("recipes");
     let target_dir = if commands_dir.exists() {
@@ -533,21 +510,22 @@ fn extract_goose_recipes(profile_path: &Path) -> (ResourceSummary, Option<String
         return (ResourceSummary::default(), None);
     };
 
-    let entries = match std::fs::read_dir(&target_dir) {
+    let (entries, truncated) = match read_entries(&target_dir, limits.max_entries) {
         Ok(e) => e,
         Err(e) => {
             return (
                 ResourceSummary {
                     items: Vec::new(),
                     directory_exists: true,
+                    ..Default::default()
                 },
-                Some(format!("recipes: {}", e)),
+                Some(unreadable(ExtractionCategory::Commands, &target_dir, &e)),
             );
         }
     };
 
     let items: Vec<String> = entries
-        .filter_map(|e| e.ok())
+        .into_iter()
         .filter(|e| {
             let path = e.path();
             path.is_file()
@@ -556,117 +534,103 @@ fn extract_goose_recipes(profile_path: &Path) -> (ResourceSummary, Option<String
                     Some("yaml") | Some("yml") | Some("json") | Some("md")
                 )
         })
-        .filter_map(|e| {
-            e.path()
-                .file_stem()
-                .and_then(|n| n.to_str())
-                .map(String::from)
-        })
+        .filter_map(|e| Some(e.path().file_stem()?.to_string_lossy().into_owned()))
         .collect();
 
     (
         ResourceSummary {
             items,
             directory_exists: true,
+            truncated,
+            ..Default::default()
         },
         None,
     )
 }
 
-fn extract_ampcode_commands(profile_path: &Path) -> (ResourceSummary, Option<String>) {
+fn extract_ampcode_commands(
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
     let commands_dir = profile_path.join("commands");
     if !commands_dir.exists() {
         return (ResourceSummary::default(), None);
     }
 
-    let entries = match std::fs::read_dir(&commands_dir) {
+    let (entries, truncated) = match read_entries(&commands_dir, limits.max_entries) {
         Ok(e) => e,
         Err(e) => {
             return (
                 ResourceSummary {
                     items: Vec::new(),
                     directory_exists: true,
+                    ..Default::default()
                 },
-                Some(format!("commands: {}", e)),
+                Some(unreadable(ExtractionCategory::Commands, &commands_dir, &e)),
             );
         }
     };
 
     let items: Vec<String> = entries
-        .filter_map(|e| e.ok())
+        .into_iter()
         .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
-        .filter_map(|e| {
-            e.path()
-                .file_stem()
-                .and_then(|n| n.to_str())
-                .map(String::from)
-        })
+        .filter_map(|e| Some(e.path().file_stem()?.to_string_lossy().into_owned()))
         .collect();
 
     (
         ResourceSummary {
             items,
             directory_exists: true,
+            truncated,
+            ..Default::default()
         },
         None,
     )
 }
 
 pub fn extract_plugins(
-    harness: &Harness,
+    harness: &dyn HarnessConfig,
+    layout: &ResourceLayout,
     profile_path: &Path,
-) -> (Option<ResourceSummary>, Option<String>) {
+    limits: &ExtractionLimits,
+) -> (Option<ResourceSummary>, Option<ExtractionIssue>) {
     if harness.id() == "opencode" {
         return extract_plugins_from_opencode_config(profile_path);
     }
 
     if harness.id() == "claude-code" {
-        return extract_claude_code_plugins(profile_path);
+        return extract_claude_code_plugins(profile_path, limits);
     }
 
-    match harness.plugins(&Scope::Global) {
+    match layout_entry(layout, ResourceKind::Plugins, ExtractionCategory::Plugins) {
         Ok(Some(dir)) => (
             Some(extract_resource_summary(
                 profile_path,
-                "plugins",
+                ResourceKind::Plugins.canonical_dir(),
                 &dir.structure,
+                limits,
             )),
             None,
         ),
         Ok(None) => (None, None),
-        Err(e) => (None, Some(format!("plugins: {}", e))),
+        Err(e) => (None, Some(e)),
     }
 }
 
 fn extract_plugins_from_opencode_config(
     profile_path: &Path,
-) -> (Option<ResourceSummary>, Option<String>) {
-    let config_path = profile_path.join("opencode.jsonc");
-    if !config_path.exists() {
-        return (None, None);
-    }
-
-    let content = match std::fs::read_to_string(&config_path) {
-        Ok(c) => c,
-        Err(e) => return (None, Some(format!("plugins: {}", e))),
-    };
-
-    let clean_json = strip_jsonc_comments(&content);
-    let parsed: serde_json::Value = match serde_json::from_str(&clean_json) {
-        Ok(v) => v,
-        Err(e) => return (None, Some(format!("plugins: {}", e))),
+) -> (Option<ResourceSummary>, Option<ExtractionIssue>) {
+    let plugins = match OpenCodeConfig::load(profile_path) {
+        Ok(Some(config)) => config.plugin,
+        Ok(None) => return (None, None),
+        Err(e) => {
+            return (
+                None,
+                Some(ExtractionIssue::from_error(ExtractionCategory::Plugins, &e)),
+            );
+        }
     };
 
-    let plugins = parsed
-        .get("plugin")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default();
-
     if plugins.is_empty() {
         (None, None)
     } else {
@@ -674,13 +638,17 @@ fn extract_plugins_from_opencode_config(
             Some(ResourceSummary {
                 items: plugins,
                 directory_exists: true,
+                ..Default::default()
             }),
             None,
         )
     }
 }
 
-fn extract_claude_code_plugins(profile_path: &Path) -> (Option<ResourceSummary>, Option<String>) {
+fn extract_claude_code_plugins(
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> (Option<ResourceSummary>, Option<ExtractionIssue>) {
     let marketplace_path = profile_path.join(".claude-plugin").join("marketplace.json");
     if marketplace_path.exists()
         && let Some(result) = parse_marketplace_json(&marketplace_path)
@@ -693,24 +661,25 @@ fn extract_claude_code_plugins(profile_path: &Path) -> (Option<ResourceSummary>,
         return (None, None);
     }
 
-    let entries = match std::fs::read_dir(&plugins_dir) {
+    let (entries, truncated) = match read_entries(&plugins_dir, limits.max_entries) {
         Ok(e) => e,
         Err(e) => {
             return (
                 Some(ResourceSummary {
                     items: Vec::new(),
                     directory_exists: true,
+                    ..Default::default()
                 }),
-                Some(format!("plugins: {}", e)),
+                Some(unreadable(ExtractionCategory::Plugins, &plugins_dir, &e)),
             );
         }
     };
 
     let items: Vec<String> = entries
-        .filter_map(|e| e.ok())
+        .into_iter()
         .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
         .filter(|e| e.path().join(".claude-plugin").join("plugin.json").exists())
-        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .map(|e| e.file_name().to_string_lossy().into_owned())
         .collect();
 
     if items.is_empty() {
@@ -720,21 +689,37 @@ fn extract_claude_code_plugins(profile_path: &Path) -> (Option<ResourceSummary>,
             Some(ResourceSummary {
                 items,
                 directory_exists: true,
+                truncated,
+                ..Default::default()
             }),
             None,
         )
     }
 }
 
-fn parse_marketplace_json(path: &Path) -> Option<(Option<ResourceSummary>, Option<String>)> {
+fn parse_marketplace_json(
+    path: &Path,
+) -> Option<(Option<ResourceSummary>, Option<ExtractionIssue>)> {
     let content = match std::fs::read_to_string(path) {
         Ok(c) => c,
-        Err(e) => return Some((None, Some(format!("plugins: {}", e)))),
+        Err(e) => {
+            return Some((
+                None,
+                Some(unreadable(ExtractionCategory::Plugins, path, &e)),
+            ));
+        }
     };
 
     let parsed: serde_json::Value = match serde_json::from_str(&content) {
         Ok(v) => v,
-        Err(e) => return Some((None, Some(format!("plugins: {}", e)))),
+        Err(e) => {
+            let issue = ExtractionIssue::new(
+                ExtractionCategory::Plugins,
+                ExtractionIssueKind::Parse,
+                e.to_string(),
+            );
+            return Some((None, Some(issue.at(path))));
+        }
     };
 
     let plugins = parsed
@@ -753,6 +738,7 @@ fn parse_marketplace_json(path: &Path) -> Option<(Option<ResourceSummary>, Optio
             Some(ResourceSummary {
                 items: plugins,
                 directory_exists: true,
+                ..Default::default()
             }),
             None,
         ))
@@ -760,28 +746,31 @@ fn parse_marketplace_json(path: &Path) -> Option<(Option<ResourceSummary>, Optio
 }
 
 pub fn extract_agents(
-    harness: &Harness,
+    harness: &dyn HarnessConfig,
+    layout: &ResourceLayout,
     profile_path: &Path,
-) -> (Option<ResourceSummary>, Option<String>) {
-    let dir_result = match harness.agents(&Scope::Global) {
+    limits: &ExtractionLimits,
+) -> (Option<ResourceSummary>, Option<ExtractionIssue>) {
+    let dir_result = match layout_entry(layout, ResourceKind::Agents, ExtractionCategory::Agents) {
         Ok(Some(dir)) => {
-            let subdir = dir_name_from_path(&dir.path);
-            let summary = extract_resource_summary(profile_path, subdir, &dir.structure);
+            let subdir = dir.dir_name();
+            let summary = extract_resource_summary(profile_path, subdir, &dir.structure, limits);
             if !summary.items.is_empty() {
                 (Some(summary), None)
             } else {
                 let md_summary = extract_resource_summary(
                     profile_path,
                     subdir,
-                    &DirectoryStructure::Flat {
+                    &ResourceStructure::Flat {
                         file_pattern: "*.md".to_string(),
                     },
+                    limits,
                 );
                 if !md_summary.items.is_empty() || md_summary.directory_exists {
                     (Some(md_summary), None)
                 } else if let Some(fallback) = fallback_dir_name(subdir) {
                     let fallback_summary =
-                        extract_resource_summary(profile_path, fallback, &dir.structure);
+                        extract_resource_summary(profile_path, fallback, &dir.structure, limits);
                     if !fallback_summary.items.is_empty() {
                         (Some(fallback_summary), None)
                     } else {
@@ -792,8 +781,8 @@ pub fn extract_agents(
                 }
             }
         }
-        Ok(None) => extract_agents_fallback(profile_path),
-        Err(e) => (None, Some(format!("agents: {}", e))),
+        Ok(None) => extract_agents_fallback(profile_path, limits),
+        Err(e) => (None, Some(e)),
     };
 
     if harness.id() == "opencode" {
@@ -815,6 +804,12 @@ pub fn extract_agents(
                         .as_ref()
                         .map(|s| s.directory_exists)
                         .unwrap_or(false),
+                    truncated: dir_result.0.as_ref().is_some_and(|s| s.truncated),
+                    meta: dir_result
+                        .0
+                        .as_ref()
+                        .map(|s| s.meta.clone())
+                        .unwrap_or_default(),
                 }),
                 dir_result.1.or(config_err),
             );
@@ -824,48 +819,44 @@ pub fn extract_agents(
     dir_result
 }
 
-fn extract_agents_from_opencode_config(profile_path: &Path) -> (ResourceSummary, Option<String>) {
-    let config_path = profile_path.join("opencode.jsonc");
-    if !config_path.exists() {
-        return (ResourceSummary::default(), None);
-    }
-
-    let content = match std::fs::read_to_string(&config_path) {
-        Ok(c) => c,
-        Err(e) => return (ResourceSummary::default(), Some(format!("agents: {}", e))),
-    };
-
-    let clean_json = strip_jsonc_comments(&content);
-    let parsed: serde_json::Value = match serde_json::from_str(&clean_json) {
-        Ok(v) => v,
-        Err(e) => return (ResourceSummary::default(), Some(format!("agents: {}", e))),
+fn extract_agents_from_opencode_config(
+    profile_path: &Path,
+) -> (ResourceSummary, Option<ExtractionIssue>) {
+    let config = match OpenCodeConfig::load(profile_path) {
+        Ok(Some(config)) => config,
+        Ok(None) => return (ResourceSummary::default(), None),
+        Err(e) => {
+            return (
+                ResourceSummary::default(),
+                Some(ExtractionIssue::from_error(ExtractionCategory::Agents, &e)),
+            );
+        }
     };
 
-    let agents = parsed
-        .get("agent")
-        .and_then(|v| v.as_object())
-        .map(|obj| obj.keys().cloned().collect::<Vec<_>>())
-        .unwrap_or_default();
-
     (
         ResourceSummary {
-            items: agents,
+            items: config.agent.into_keys().collect(),
             directory_exists: false,
+            ..Default::default()
         },
         None,
     )
 }
 
-fn extract_agents_fallback(profile_path: &Path) -> (Option<ResourceSummary>, Option<String>) {
+fn extract_agents_fallback(
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> (Option<ResourceSummary>, Option<ExtractionIssue>) {
     for subdir in ["agent", "agents"] {
         let dir_path = profile_path.join(subdir);
         if dir_path.exists() && dir_path.is_dir() {
             let summary = extract_resource_summary(
                 profile_path,
                 subdir,
-                &DirectoryStructure::Flat {
+                &ResourceStructure::Flat {
                     file_pattern: "*.md".to_string(),
                 },
+                limits,
             );
             if !summary.items.is_empty() || summary.directory_exists {
                 return (Some(summary), None);
@@ -878,7 +869,7 @@ fn extract_agents_fallback(profile_path: &Path) -> (Option<ResourceSummary>, Opt
 pub fn extract_rules_file(
     harness: &Harness,
     profile_path: &Path,
-) -> (Option<PathBuf>, Option<String>) {
+) -> (Option<PathBuf>, Option<ExtractionIssue>) {
     match harness.rules(&Scope::Global) {
         Ok(Some(dir)) => {
             let rules_path = match &dir.structure {
@@ -898,98 +889,280 @@ pub fn extract_rules_file(
             (rules_path, None)
         }
         Ok(None) => (None, None),
-        Err(e) => (None, Some(format!("rules: {}", e))),
+        Err(e) => (
+            None,
+            Some(ExtractionIssue::new(
+                ExtractionCategory::Rules,
+                ExtractionIssueKind::Layout,
+                e.to_string(),
+            )),
+        ),
     }
 }
 
 fn find_first_matching_file(dir: &Path, pattern: &str) -> Option<PathBuf> {
+    let ignore_case = platform::case_insensitive(dir);
     let mut matches: Vec<PathBuf> = std::fs::read_dir(dir)
         .ok()?
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
         .map(|e| e.path())
-        .filter(|p| matches_pattern(p.file_name().and_then(|n| n.to_str()), pattern))
+        .filter(|p| matches_pattern(p.file_name(), pattern, ignore_case))
         .collect();
     matches.sort();
     matches.into_iter().next()
 }
 
-pub fn matches_pattern(filename: Option<&str>, pattern: &str) -> bool {
+/// Matches a file name against `*`, `*.ext`, `*suffix`, `prefix*`, or an exact name.
+///
+/// Compares raw bytes, so names that are not valid UTF-8 still match (or not)
+/// exactly, ignoring ASCII case if `ignore_case` is set.
+pub fn matches_pattern(filename: Option<&OsStr>, pattern: &str, ignore_case: bool) -> bool {
     let Some(name) = filename else { return false };
+    let name = name.as_encoded_bytes();
     if pattern == "*" {
         return true;
     }
-    if let Some(suffix) = pattern.strip_prefix("*.") {
-        return name.ends_with(&format!(".{}", suffix));
-    }
     if let Some(suffix) = pattern.strip_prefix('*') {
-        return name.ends_with(suffix);
+        return platform::name_ends_with(name, suffix.as_bytes(), ignore_case);
     }
     if let Some(prefix) = pattern.strip_suffix('*') {
-        return name.starts_with(prefix);
+        return platform::name_starts_with(name, prefix.as_bytes(), ignore_case);
     }
-    name == pattern
+    platform::names_equal(name, pattern.as_bytes(), ignore_case)
 }
 
 pub fn extract_resource_summary(
     base_path: &Path,
-    subdir: &str,
-    structure: &DirectoryStructure,
+    subdir: impl AsRef<Path>,
+    structure: &ResourceStructure,
+    limits: &ExtractionLimits,
 ) -> ResourceSummary {
+    let subdir = subdir.as_ref();
     let dir_path = base_path.join(subdir);
 
     if !dir_path.exists() {
-        return ResourceSummary {
-            items: vec![],
-            directory_exists: false,
-        };
+        return ResourceSummary::default();
     }
 
-    let items = match structure {
-        DirectoryStructure::Flat { file_pattern } => list_files_matching(&dir_path, file_pattern),
-        DirectoryStructure::Nested {
+    // Each item with the file that defines it, relative to `dir_path`.
+    let named = |files: Vec<PathBuf>| -> Vec<(String, PathBuf)> {
+        files
+            .into_iter()
+            .filter_map(|file| Some((structure.resource_name(&file)?, file)))
+            .collect()
+    };
+    let (mut found, truncated) = match structure {
+        ResourceStructure::Flat { file_pattern } => {
+            let (files, truncated) = files_matching(&dir_path, file_pattern, limits.max_entries);
+            (named(files), truncated)
+        }
+        ResourceStructure::Nested {
             subdir_pattern,
             file_name,
-        } => list_subdirs_with_file(&dir_path, subdir_pattern, file_name),
+        } => {
+            let (names, truncated) =
+                list_subdirs_with_file(&dir_path, subdir_pattern, file_name, limits.max_entries);
+            let found = names
+                .into_iter()
+                .map(|name| {
+                    let file = Path::new(&name).join(file_name);
+                    (name, file)
+                })
+                .collect();
+            (found, truncated)
+        }
+        ResourceStructure::Recursive { file_pattern, .. } => {
+            // The resource directory's own level counts towards the depth limit.
+            let levels = limits.max_depth.saturating_sub(subdir.components().count());
+            let mut files = Vec::new();
+            let truncated = list_files_recursive(
+                &dir_path,
+                Path::new(""),
+                file_pattern,
+                levels,
+                limits.max_entries,
+                &mut files,
+            );
+            (named(files), truncated)
+        }
     };
+    found.sort();
 
+    let meta = found
+        .iter()
+        .filter_map(|(name, file)| Some((name.clone(), read_meta(&dir_path.join(file))?)))
+        .collect();
     ResourceSummary {
-        items,
+        items: found.into_iter().map(|(name, _)| name).collect(),
         directory_exists: true,
+        truncated,
+        meta,
     }
 }
 
-pub fn list_files_matching(dir: &Path, pattern: &str) -> Vec<String> {
-    std::fs::read_dir(dir)
-        .ok()
-        .map(|entries| {
-            let mut items: Vec<String> = entries
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
-                .filter(|e| matches_pattern(e.file_name().to_str(), pattern))
-                .filter_map(|e| e.path().file_stem()?.to_str().map(String::from))
-                .collect();
-            items.sort();
-            items
-        })
-        .unwrap_or_default()
+/// The front matter of the resource file at `path`, if it has any.
+fn read_meta(path: &Path) -> Option<ResourceMeta> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let (yaml, _) = split_frontmatter(&content)?;
+    let serde_yaml::Value::Mapping(map) = serde_yaml::from_str(yaml).ok()? else {
+        return None;
+    };
+    let mut meta = ResourceMeta::default();
+    for (key, value) in &map {
+        let (Some(key), Some(text)) = (key.as_str(), yaml_text(value)) else {
+            continue;
+        };
+        match key {
+            // Already the item's name, or one it is installed under.
+            "name" => {}
+            "description" => meta.description = Some(text),
+            _ => {
+                meta.metadata.insert(key.to_string(), text);
+            }
+        }
+    }
+    (meta != ResourceMeta::default()).then_some(meta)
 }
 
-pub fn list_subdirs_with_file(dir: &Path, subdir_pattern: &str, file_name: &str) -> Vec<String> {
-    std::fs::read_dir(dir)
-        .ok()
-        .map(|entries| {
-            let mut items: Vec<String> = entries
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
-                .filter(|e| matches_pattern(e.file_name().to_str(), subdir_pattern))
-                .filter(|e| e.path().join(file_name).exists())
-                .filter_map(|e| e.file_name().to_str().map(String::from))
-                .collect();
-            items.sort();
-            items
+/// `value` as text, if it is a scalar or a list of scalars.
+fn yaml_text(value: &serde_yaml::Value) -> Option<String> {
+    use serde_yaml::Value;
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Sequence(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::Sequence(_) => None,
+                item => yaml_text(item),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|items| items.join(", ")),
+        _ => None,
+    }
+}
+
+/// Summaries of the custom categories `layout` declares, keyed by name,
+/// each listed from its directory under `profile_path`.
+pub fn extract_custom_resources(
+    layout: &ResourceLayout,
+    profile_path: &Path,
+    limits: &ExtractionLimits,
+) -> BTreeMap<String, ResourceSummary> {
+    layout
+        .custom()
+        .map(|(name, resource)| {
+            let summary = extract_resource_summary(
+                profile_path,
+                &resource.dir,
+                &resource.structure(),
+                limits,
+            );
+            (name.to_string(), summary)
         })
-        .unwrap_or_default()
+        .collect()
+}
+
+/// The first `max_entries` entries of `dir`, and whether it has more.
+fn read_entries(dir: &Path, max_entries: usize) -> std::io::Result<(Vec<DirEntry>, bool)> {
+    let mut entries = std::fs::read_dir(dir)?.filter_map(|e| e.ok());
+    let read: Vec<DirEntry> = entries.by_ref().take(max_entries).collect();
+    Ok((read, entries.next().is_some()))
+}
+
+/// Stems of the files in `dir` matching `pattern`, sorted, and whether
+/// `dir` has more than `max_entries` entries that were left unread.
+pub fn list_files_matching(dir: &Path, pattern: &str, max_entries: usize) -> (Vec<String>, bool) {
+    let (files, truncated) = files_matching(dir, pattern, max_entries);
+    let mut items: Vec<String> = files
+        .iter()
+        .filter_map(|file| Some(file.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    items.sort();
+    (items, truncated)
+}
+
+/// Names of the files in `dir` matching `pattern`, and whether `dir` has
+/// more than `max_entries` entries that were left unread.
+fn files_matching(dir: &Path, pattern: &str, max_entries: usize) -> (Vec<PathBuf>, bool) {
+    let Ok((entries, truncated)) = read_entries(dir, max_entries) else {
+        return (Vec::new(), false);
+    };
+    let ignore_case = platform::case_insensitive(dir);
+    let files = entries
+        .into_iter()
+        .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter(|e| matches_pattern(Some(&e.file_name()), pattern, ignore_case))
+        .map(|e| PathBuf::from(e.file_name()))
+        .collect();
+    (files, truncated)
+}
+
+/// Paths, relative to the listing's root, of the files matching `pattern`
+/// in `dir` (at `relative` below the root) and its subdirectories down to
+/// `levels` levels. Returns whether any directory had more than
+/// `max_entries` entries or subdirectories below the last level.
+fn list_files_recursive(
+    dir: &Path,
+    relative: &Path,
+    pattern: &str,
+    levels: usize,
+    max_entries: usize,
+    files: &mut Vec<PathBuf>,
+) -> bool {
+    let Ok((entries, mut truncated)) = read_entries(dir, max_entries) else {
+        return false;
+    };
+    let ignore_case = platform::case_insensitive(dir);
+    for entry in entries {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name();
+        if file_type.is_dir() {
+            if levels <= 1 {
+                truncated = true;
+                continue;
+            }
+            truncated |= list_files_recursive(
+                &entry.path(),
+                &relative.join(&name),
+                pattern,
+                levels - 1,
+                max_entries,
+                files,
+            );
+        } else if file_type.is_file() && matches_pattern(Some(&name), pattern, ignore_case) {
+            files.push(relative.join(&name));
+        }
+    }
+    truncated
+}
+
+/// Names of the subdirectories of `dir` matching `subdir_pattern` that hold
+/// `file_name`, sorted, and whether `dir` has more than `max_entries`
+/// entries that were left unread.
+pub fn list_subdirs_with_file(
+    dir: &Path,
+    subdir_pattern: &str,
+    file_name: &str,
+    max_entries: usize,
+) -> (Vec<String>, bool) {
+    let Ok((entries, truncated)) = read_entries(dir, max_entries) else {
+        return (Vec::new(), false);
+    };
+    let ignore_case = platform::case_insensitive(dir);
+    let mut items: Vec<String> = entries
+        .into_iter()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter(|e| matches_pattern(Some(&e.file_name()), subdir_pattern, ignore_case))
+        .filter(|e| e.path().join(file_name).exists())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    items.sort();
+    (items, truncated)
 }
 
 #[cfg(test)]
@@ -997,43 +1170,74 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    fn dir_name(path: &str) -> String {
+        ResourceDir::new(
+            path,
+            DirectoryStructure::Flat {
+                file_pattern: "*.md".to_string(),
+            },
+        )
+        .dir_name()
+        .to_string()
+    }
+
+    #[test]
+    fn mistyped_mcp_server_is_an_issue_naming_it() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join(ClaudeMcpConfig::FILENAME),
+            r#"{"mcpServers": {"bad": {"env": {"PORT": 8080}}, "fs": {"command": "npx"}}}"#,
+        )
+        .unwrap();
+
+        let (servers, issues) = extract_mcp_from_claudecode_config(temp.path()).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "fs");
+        let [issue] = issues.as_slice() else {
+            panic!("expected one issue, got {issues:?}");
+        };
+        assert_eq!(issue.category, ExtractionCategory::Mcp);
+        assert!(issue.message.starts_with("server 'bad':"), "{issue}");
+    }
+
     #[test]
-    fn dir_name_from_path_extracts_final_component() {
-        assert_eq!(dir_name_from_path(Path::new("/foo/bar/skill")), "skill");
-        assert_eq!(dir_name_from_path(Path::new("/foo/bar/skills")), "skills");
-        assert_eq!(dir_name_from_path(Path::new("agent")), "agent");
-        assert_eq!(dir_name_from_path(Path::new("/command")), "command");
+    fn dir_name_extracts_final_component() {
+        assert_eq!(dir_name("/foo/bar/skill"), "skill");
+        assert_eq!(dir_name("/foo/bar/skills"), "skills");
+        assert_eq!(dir_name("agent"), "agent");
+        assert_eq!(dir_name("/command"), "command");
     }
 
     #[test]
-    fn dir_name_from_path_handles_trailing_slash() {
-        assert_eq!(dir_name_from_path(Path::new("/foo/bar/skill/")), "skill");
+    fn dir_name_handles_trailing_slash() {
+        assert_eq!(dir_name("/foo/bar/skill/"), "skill");
     }
 
     #[test]
     fn opencode_uses_singular_directory_names() {
-        let harness = Harness::new(harness_locate::HarnessKind::OpenCode);
+        let layout = Harness::new(harness_locate::HarnessKind::OpenCode).resource_layout();
 
-        if let Ok(Some(skills_dir)) = harness.skills(&Scope::Global) {
-            let name = dir_name_from_path(&skills_dir.path);
+        if let Some(skills_dir) = layout.get(ResourceKind::Skills) {
             assert_eq!(
-                name, "skill",
+                skills_dir.dir_name(),
+                "skill",
                 "OpenCode skills directory should be 'skill' (singular)"
             );
+            assert!(skills_dir.sanitize_names);
         }
 
-        if let Ok(Some(agents_dir)) = harness.agents(&Scope::Global) {
-            let name = dir_name_from_path(&agents_dir.path);
+        if let Some(agents_dir) = layout.get(ResourceKind::Agents) {
             assert_eq!(
-                name, "agent",
+                agents_dir.dir_name(),
+                "agent",
                 "OpenCode agents directory should be 'agent' (singular)"
             );
         }
 
-        if let Ok(Some(commands_dir)) = harness.commands(&Scope::Global) {
-            let name = dir_name_from_path(&commands_dir.path);
+        if let Some(commands_dir) = layout.get(ResourceKind::Commands) {
             assert_eq!(
-                name, "command",
+                commands_dir.dir_name(),
+                "command",
                 "OpenCode commands directory should be 'command' (singular)"
             );
         }
@@ -1041,17 +1245,82 @@ mod tests {
 
     #[test]
     fn claude_uses_plural_directory_names() {
-        let harness = Harness::new(harness_locate::HarnessKind::ClaudeCode);
+        let layout = Harness::new(harness_locate::HarnessKind::ClaudeCode).resource_layout();
 
-        if let Ok(Some(skills_dir)) = harness.skills(&Scope::Global) {
-            let name = dir_name_from_path(&skills_dir.path);
+        if let Some(skills_dir) = layout.get(ResourceKind::Skills) {
             assert_eq!(
-                name, "skills",
+                skills_dir.dir_name(),
+                "skills",
                 "Claude skills directory should be 'skills' (plural)"
             );
+            assert!(!skills_dir.sanitize_names);
         }
     }
 
+    #[test]
+    fn skill_descriptions_come_from_front_matter() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let skills = temp.path().join("skills");
+        std::fs::create_dir_all(skills.join("review")).unwrap();
+        std::fs::write(
+            skills.join("review/SKILL.md"),
+            "---\nname: review\ndescription: Reviews diffs\n---\nBody",
+        )
+        .unwrap();
+        std::fs::create_dir_all(skills.join("bare")).unwrap();
+        std::fs::write(skills.join("bare/SKILL.md"), "No front matter").unwrap();
+
+        let files = read_skill_files(
+            &ResourceLayout::default(),
+            temp.path(),
+            &[
+                "review".to_string(),
+                "bare".to_string(),
+                "missing".to_string(),
+            ],
+        );
+        assert_eq!(files.len(), 2);
+        let descriptions = extract_skill_descriptions(&files);
+
+        assert_eq!(descriptions.len(), 1);
+        assert_eq!(descriptions["review"], "Reviews diffs");
+    }
+
+    #[test]
+    fn summaries_carry_front_matter_of_each_item() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let agents = temp.path().join("agents");
+        std::fs::create_dir_all(&agents).unwrap();
+        std::fs::write(
+            agents.join("reviewer.md"),
+            "---\nname: reviewer\ndescription: >\n  Reviews pull\n  requests\nmodel: opus\n\
+             tools: [Read, Grep]\ntemperature: 0.2\nhooks:\n  pre: lint\n---\nBody",
+        )
+        .unwrap();
+        std::fs::write(agents.join("plain.md"), "No front matter").unwrap();
+        std::fs::write(agents.join("broken.md"), "---\ndescription: [\n---\n").unwrap();
+        let structure = ResourceStructure::Flat {
+            file_pattern: "*.md".to_string(),
+        };
+
+        let summary =
+            extract_resource_summary(temp.path(), "agents", &structure, &Default::default());
+
+        assert_eq!(summary.items, ["broken", "plain", "reviewer"]);
+        assert_eq!(summary.meta.len(), 1);
+        let meta = &summary.meta["reviewer"];
+        assert_eq!(meta.description.as_deref(), Some("Reviews pull requests"));
+        assert_eq!(meta.metadata["model"], "opus");
+        assert_eq!(meta.metadata["tools"], "Read, Grep");
+        assert_eq!(meta.metadata["temperature"], "0.2");
+        assert!(!meta.metadata.contains_key("hooks"));
+        assert!(!meta.metadata.contains_key("name"));
+        assert_eq!(
+            summary.description("reviewer"),
+            Some("Reviews pull requests")
+        );
+    }
+
     #[test]
     fn fallback_dir_name_maps_singular_to_plural() {
         assert_eq!(fallback_dir_name("skill"), Some("skills"));
@@ -1061,4 +1330,17 @@ mod tests {
         assert_eq!(fallback_dir_name("agents"), None);
         assert_eq!(fallback_dir_name("other"), None);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn matches_pattern_handles_non_utf8_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = OsStr::from_bytes(b"caf\xe9.md");
+        assert!(matches_pattern(Some(name), "*.md", false));
+        assert!(matches_pattern(Some(name), "caf*", false));
+        assert!(!matches_pattern(Some(name), "*.json", false));
+        assert!(matches_pattern(Some(name), "CAF*", true));
+        assert!(!matches_pattern(Some(name), "CAF*", false));
+    }
 }
@@ -0,0 +1,176 @@
+//! Claude Code's settings layers.
+//!
+//! Claude Code merges `settings.json` from several places, later ones
+//! winning: the user's own (what a profile holds), the project's
+//! `.claude/settings.json` and `.claude/settings.local.json`, and the
+//! managed settings an administrator installs system-wide, which nothing
+//! overrides. Layers replace each other per top-level key.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::config::types::{ExtractionCategory, ExtractionIssue, OverriddenSetting, SettingsLayer};
+use crate::harness::schema::{ClaudeSettings, ConfigFormat, read_config};
+
+/// File administrators install in [`managed_settings_dir`].
+pub const MANAGED_SETTINGS_FILE: &str = "managed-settings.json";
+
+/// Where Claude Code looks for managed settings on this platform.
+pub fn managed_settings_dir() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support/ClaudeCode")
+    } else if cfg!(windows) {
+        PathBuf::from(r"C:\ProgramData\ClaudeCode")
+    } else {
+        PathBuf::from("/etc/claude-code")
+    }
+}
+
+/// Effective settings, merged from every layer.
+#[derive(Debug, Default)]
+pub struct MergedSettings {
+    pub values: Map<String, Value>,
+    /// Layer each key's value came from.
+    pub layers: BTreeMap<String, SettingsLayer>,
+    /// Keys the profile sets that managed settings replace with another value.
+    pub overridden: Vec<OverriddenSetting>,
+    pub errors: Vec<ExtractionIssue>,
+}
+
+/// The settings files merged for the profile in `profile_path`, lowest
+/// precedence first: its own, those of the project in `project_dir`, and the
+/// managed settings in `managed_dir`.
+///
+/// A project directory holding the profile itself (e.g. the home directory
+/// when the profile is live) is not read twice.
+pub fn settings_files(
+    profile_path: &Path,
+    project_dir: Option<&Path>,
+    managed_dir: &Path,
+) -> Vec<(SettingsLayer, PathBuf)> {
+    let project = project_dir
+        .map(|dir| dir.join(".claude"))
+        .filter(|dir| !same_dir(dir, profile_path));
+    let mut files = vec![(
+        SettingsLayer::User,
+        profile_path.join(ClaudeSettings::FILENAME),
+    )];
+    if let Some(project) = project {
+        files.push((SettingsLayer::Project, project.join("settings.json")));
+        files.push((SettingsLayer::Local, project.join("settings.local.json")));
+    }
+    files.push((
+        SettingsLayer::Managed,
+        managed_dir.join(MANAGED_SETTINGS_FILE),
+    ));
+    files
+}
+
+/// Merges the profile's settings in `profile_path` with those of the project
+/// in `project_dir` and the managed settings in `managed_dir`.
+pub fn merge_settings(
+    profile_path: &Path,
+    project_dir: Option<&Path>,
+    managed_dir: &Path,
+) -> MergedSettings {
+    let mut merged = MergedSettings::default();
+    let mut profile_values = Map::new();
+    for (layer, path) in settings_files(profile_path, project_dir, managed_dir) {
+        let values = match read_config::<Map<String, Value>>(&path, ConfigFormat::Json) {
+            Ok(values) => values.unwrap_or_default(),
+            Err(e) => {
+                let issue = ExtractionIssue::from_error(ExtractionCategory::Settings, &e);
+                merged.errors.push(match issue.path {
+                    Some(_) => issue,
+                    None => issue.at(&path),
+                });
+                continue;
+            }
+        };
+        if layer == SettingsLayer::User {
+            profile_values = values.clone();
+        }
+        for (key, value) in values {
+            merged.layers.insert(key.clone(), layer);
+            merged.values.insert(key, value);
+        }
+    }
+    for (key, profile_value) in profile_values {
+        if merged.layers.get(&key) == Some(&SettingsLayer::Managed)
+            && merged.values.get(&key) != Some(&profile_value)
+        {
+            merged.overridden.push(OverriddenSetting {
+                layer: SettingsLayer::Managed,
+                effective_value: merged.values[&key].clone(),
+                key,
+                profile_value,
+            });
+        }
+    }
+    merged
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn later_layers_win_and_managed_overrides_are_reported() {
+        let temp = TempDir::new().unwrap();
+        let profile = temp.path().join("profile");
+        let project = temp.path().join("project");
+        let managed = temp.path().join("managed");
+        for dir in [&profile, &project.join(".claude"), &managed] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(
+            profile.join("settings.json"),
+            r#"{"model": "opus", "theme": "dark", "cleanupPeriodDays": 7}"#,
+        )
+        .unwrap();
+        fs::write(
+            project.join(".claude/settings.json"),
+            r#"{"theme": "light"}"#,
+        )
+        .unwrap();
+        fs::write(
+            managed.join(MANAGED_SETTINGS_FILE),
+            r#"{"model": "sonnet", "cleanupPeriodDays": 7}"#,
+        )
+        .unwrap();
+
+        let merged = merge_settings(&profile, Some(&project), &managed);
+        assert_eq!(merged.values["model"], "sonnet");
+        assert_eq!(merged.values["theme"], "light");
+        assert_eq!(merged.layers["model"], SettingsLayer::Managed);
+        assert_eq!(merged.layers["theme"], SettingsLayer::Project);
+        let [overridden] = merged.overridden.as_slice() else {
+            panic!("expected one override, got {:?}", merged.overridden);
+        };
+        assert_eq!(overridden.key, "model");
+        assert_eq!(overridden.profile_value, "opus");
+        assert!(merged.errors.is_empty());
+    }
+
+    #[test]
+    fn project_holding_the_profile_is_read_once() {
+        let temp = TempDir::new().unwrap();
+        let claude = temp.path().join(".claude");
+        fs::create_dir_all(&claude).unwrap();
+        fs::write(claude.join("settings.json"), r#"{"model": "opus"}"#).unwrap();
+
+        let merged = merge_settings(&claude, Some(temp.path()), &temp.path().join("none"));
+        assert_eq!(merged.layers["model"], SettingsLayer::User);
+    }
+}
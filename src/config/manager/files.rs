@@ -1,8 +1,11 @@
-use std::path::Path;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 
 use chrono::Local;
-use harness_locate::{Harness, HarnessKind, Scope};
 
+use super::extraction::matches_pattern;
+use super::options::{CopyStrategy, LargeFilePolicy, SwitchStrategy, SymlinkPolicy};
+use super::platform;
 use crate::error::Result;
 use crate::harness::HarnessConfig;
 use crate::install::installer::{sanitize_name_for_opencode, transform_skill_for_opencode};
@@ -25,20 +28,268 @@ const SESSION_DATA: &[&str] = &[
     "history.jsonl",
 ];
 
-fn is_excluded(name: &str) -> bool {
-    ALWAYS_EXCLUDED.contains(&name) || SESSION_DATA.contains(&name)
+fn is_excluded(name: &OsStr, ignore_case: bool) -> bool {
+    is_listed(ALWAYS_EXCLUDED, name, ignore_case) || is_session_data(name, ignore_case)
 }
 
-fn is_session_data(name: &str) -> bool {
-    SESSION_DATA.contains(&name)
+fn is_session_data(name: &OsStr, ignore_case: bool) -> bool {
+    is_listed(SESSION_DATA, name, ignore_case)
+}
+
+fn is_listed(list: &[&str], name: &OsStr, ignore_case: bool) -> bool {
+    list.iter()
+        .any(|n| platform::names_equal(name.as_encoded_bytes(), n.as_bytes(), ignore_case))
 }
 
 const MAX_EXTRA_BACKUPS: usize = 5;
 
+/// Per-manager copy behavior: how files are materialized and what to skip.
+#[derive(Debug, Clone, Default)]
+pub struct CopyOptions {
+    pub strategy: CopyStrategy,
+    pub symlinks: SymlinkPolicy,
+    /// User-supplied file-name patterns (`*.log`, `cache*`) skipped when copying.
+    pub exclude: Vec<String>,
+    /// Files larger than this many bytes trigger [`CopyOptions::large_files`]; 0 disables.
+    pub max_file_size: u64,
+    pub large_files: LargeFilePolicy,
+    /// Whether exclude patterns ignore ASCII case, as on the live config's
+    /// filesystem (see [`platform::case_insensitive`]).
+    pub ignore_case: bool,
+}
+
+impl CopyOptions {
+    /// Whether `name` matches one of the user-supplied exclude patterns.
+    fn is_user_excluded(&self, name: &OsStr) -> bool {
+        self.exclude
+            .iter()
+            .any(|pattern| matches_pattern(Some(name), pattern, self.ignore_case))
+    }
+
+    pub(super) fn is_excluded(&self, name: &OsStr) -> bool {
+        is_excluded(name, self.ignore_case) || self.is_user_excluded(name)
+    }
+
+    /// Size of the file at `path` if it is over [`CopyOptions::max_file_size`].
+    fn oversized(&self, path: &Path) -> Option<u64> {
+        if self.max_file_size == 0 {
+            return None;
+        }
+        let size = std::fs::metadata(path).ok()?.len();
+        (size > self.max_file_size).then_some(size)
+    }
+
+    /// Whether the file at `path` is left out under [`LargeFilePolicy::Skip`].
+    pub(super) fn skips_large(&self, path: &Path) -> bool {
+        self.large_files == LargeFilePolicy::Skip && self.oversized(path).is_some()
+    }
+
+    /// Decides how to copy the entry at `path` of type `file_type`.
+    fn classify(&self, path: &Path, file_type: std::fs::FileType) -> Entry {
+        if !file_type.is_symlink() {
+            return if file_type.is_dir() {
+                Entry::Dir(path.to_path_buf())
+            } else {
+                Entry::File(path.to_path_buf())
+            };
+        }
+        let policy = match self.symlinks {
+            // Without the privilege to create links, copying the target is the closest we get.
+            SymlinkPolicy::Preserve if !platform::symlinks_supported() => SymlinkPolicy::Follow,
+            policy => policy,
+        };
+        match policy {
+            SymlinkPolicy::Preserve => match std::fs::read_link(path) {
+                Ok(target) => Entry::Link(target),
+                Err(e) => {
+                    eprintln!("Warning: Failed to read symlink {}: {}", path.display(), e);
+                    Entry::Skip
+                }
+            },
+            SymlinkPolicy::Follow => follow_symlink(path),
+            SymlinkPolicy::Skip => {
+                eprintln!("Warning: Skipping symlink {}", path.display());
+                Entry::Skip
+            }
+        }
+    }
+
+    /// Copies one directory entry according to [`CopyOptions::classify`].
+    ///
+    /// Errors in nested directories are logged and skipped, matching [`copy_dir_filtered`].
+    fn copy_entry(
+        &self,
+        src: &Path,
+        file_type: std::fs::FileType,
+        dst: &Path,
+        ancestors: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        self.copy_classified(self.classify(src, file_type), src, dst, ancestors)
+    }
+
+    fn copy_classified(
+        &self,
+        entry: Entry,
+        src: &Path,
+        dst: &Path,
+        ancestors: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        match entry {
+            Entry::File(from) => self.copy_file(&from, dst)?,
+            Entry::Dir(from) => copy_dir_walk(&from, dst, self, ancestors)?,
+            Entry::Link(target) => match create_symlink(&target, src, dst) {
+                // Junctions and links can be read without privileges but not recreated.
+                Err(e) if platform::is_symlink_privilege_error(&e) => {
+                    self.copy_classified(follow_symlink(src), src, dst, ancestors)?
+                }
+                result => result?,
+            },
+            Entry::Skip => {}
+        }
+        Ok(())
+    }
+
+    fn copy_file(&self, src: &Path, dst: &Path) -> std::io::Result<()> {
+        if let Some(size) = self.oversized(src) {
+            let limit = crate::config::format_size(self.max_file_size);
+            let size = crate::config::format_size(size);
+            match self.large_files {
+                LargeFilePolicy::Warn => eprintln!(
+                    "Warning: {} is {size}, over the {limit} max_file_size",
+                    src.display()
+                ),
+                LargeFilePolicy::Skip => {
+                    eprintln!(
+                        "Warning: Skipping {} ({size}, over the {limit} max_file_size)",
+                        src.display()
+                    );
+                    return Ok(());
+                }
+            }
+        }
+        match self.strategy {
+            CopyStrategy::Copy => copy_file_preserving(src, dst),
+            CopyStrategy::Hardlink => {
+                let _ = std::fs::remove_file(dst);
+                std::fs::hard_link(src, dst).or_else(|_| copy_file_preserving(src, dst))
+            }
+        }
+    }
+}
+
+/// An entry to copy, after applying the [`SymlinkPolicy`].
+enum Entry {
+    /// Regular file (or followed link) read from this path.
+    File(PathBuf),
+    /// Directory (or followed link) read from this path.
+    Dir(PathBuf),
+    /// Symlink to recreate with this target.
+    Link(PathBuf),
+    Skip,
+}
+
+/// Resolves a symlink for copying its target. Dangling links are skipped with a warning.
+///
+/// Copies from the resolved path so hard-linking never links the symlink itself.
+fn follow_symlink(path: &Path) -> Entry {
+    match std::fs::canonicalize(path) {
+        Ok(target) if target.is_dir() => Entry::Dir(target),
+        Ok(target) => Entry::File(target),
+        Err(e) => {
+            eprintln!(
+                "Warning: Skipping dangling symlink {}: {}",
+                path.display(),
+                e
+            );
+            Entry::Skip
+        }
+    }
+}
+
+/// Recreates a symlink at `dst` pointing to `target`; `src` is the original link.
+fn create_symlink(target: &Path, src: &Path, dst: &Path) -> std::io::Result<()> {
+    if let Ok(meta) = std::fs::symlink_metadata(dst) {
+        if meta.is_dir() {
+            std::fs::remove_dir_all(dst)?;
+        } else {
+            std::fs::remove_file(dst)?;
+        }
+    }
+    #[cfg(unix)]
+    {
+        let _ = src;
+        std::os::unix::fs::symlink(target, dst)
+    }
+    #[cfg(windows)]
+    {
+        if src.is_dir() {
+            std::os::windows::fs::symlink_dir(target, dst)
+        } else {
+            std::os::windows::fs::symlink_file(target, dst)
+        }
+    }
+}
+
+/// Copies a file along with its permissions (including execute bits) and timestamps.
+///
+/// Hook scripts stored in profiles must stay executable, and tools that watch
+/// mtimes should not see every switch as an edit.
+pub fn copy_file_preserving(src: &Path, dst: &Path) -> std::io::Result<()> {
+    // `fs::copy` carries permission bits on Unix and attributes on Windows.
+    std::fs::copy(src, dst)?;
+    copy_times(src, dst)
+}
+
+/// Copies a directory's permissions and timestamps onto `dst`.
+///
+/// Call after filling `dst`, since adding entries bumps its mtime. The owner
+/// keeps write access so bridle can still replace the directory later.
+fn copy_dir_metadata(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let mut permissions = std::fs::metadata(src)?.permissions();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o700);
+    }
+    #[cfg(not(unix))]
+    permissions.set_readonly(false);
+    std::fs::set_permissions(dst, permissions)?;
+    copy_times(src, dst)
+}
+
+fn copy_times(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let meta = std::fs::metadata(src)?;
+    let mut times = std::fs::FileTimes::new().set_modified(meta.modified()?);
+    if let Ok(accessed) = meta.accessed() {
+        times = times.set_accessed(accessed);
+    }
+    open_for_times(dst)?.set_times(times)
+}
+
+#[cfg(not(windows))]
+fn open_for_times(path: &Path) -> std::io::Result<std::fs::File> {
+    // Setting explicit times only requires ownership, so a read-only handle
+    // works for files and directories alike.
+    std::fs::File::open(path)
+}
+
+#[cfg(windows)]
+fn open_for_times(path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::windows::fs::OpenOptionsExt;
+    const FILE_WRITE_ATTRIBUTES: u32 = 0x0100;
+    // Required to open a handle to a directory.
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+    std::fs::OpenOptions::new()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+}
+
 pub fn copy_config_files(
     harness: &dyn HarnessConfig,
     source_is_live: bool,
     profile_path: &Path,
+    opts: &CopyOptions,
 ) -> Result<()> {
     use std::collections::HashSet;
 
@@ -50,23 +301,19 @@ pub fn copy_config_files(
             for entry in std::fs::read_dir(&config_dir)? {
                 let entry = entry?;
                 let file_name = entry.file_name();
-                let name_str = file_name.to_string_lossy();
-
-                if is_excluded(&name_str) {
+                if opts.is_excluded(&file_name) {
                     continue;
                 }
 
                 let file_type = entry.file_type()?;
                 let dest = profile_path.join(&file_name);
 
-                if file_type.is_file() {
-                    std::fs::copy(entry.path(), &dest)?;
-                    if let Ok(canonical) = entry.path().canonicalize() {
-                        copied_files.insert(canonical);
-                    }
-                } else if file_type.is_dir() {
-                    copy_dir_filtered(&entry.path(), &dest)?;
+                if !file_type.is_dir()
+                    && let Ok(canonical) = entry.path().canonicalize()
+                {
+                    copied_files.insert(canonical);
                 }
+                opts.copy_entry(&entry.path(), file_type, &dest, &mut Vec::new())?;
             }
         }
 
@@ -82,7 +329,7 @@ pub fn copy_config_files(
                 && let Some(filename) = mcp_path.file_name()
             {
                 let dest = profile_path.join(filename);
-                std::fs::copy(&mcp_path, dest)?;
+                copy_file_preserving(&mcp_path, &dest)?;
             }
         }
     } else {
@@ -103,12 +350,12 @@ pub fn copy_config_files(
                     && &filename == mcp_name
                     && let Some(mcp_path) = harness.mcp_config_path()
                 {
-                    std::fs::copy(entry.path(), &mcp_path)?;
+                    copy_file_preserving(&entry.path(), &mcp_path)?;
                     continue;
                 }
 
                 let dest = config_dir.join(&filename);
-                std::fs::copy(entry.path(), dest)?;
+                opts.copy_file(&entry.path(), &dest)?;
             }
         }
     }
@@ -116,7 +363,20 @@ pub fn copy_config_files(
     Ok(())
 }
 
-pub fn copy_all_contents(src: &Path, dst: &Path) -> Result<()> {
+/// Copies the harness's MCP config file, if it has one, into `profile_path`.
+pub fn copy_mcp_config_to_profile(harness: &dyn HarnessConfig, profile_path: &Path) -> Result<()> {
+    if let Some(mcp_path) = harness.mcp_config_path()
+        && mcp_path.is_file()
+        && let Some(filename) = mcp_path.file_name()
+    {
+        copy_file_preserving(&mcp_path, &profile_path.join(filename))?;
+    }
+    Ok(())
+}
+
+pub fn copy_all_contents(src: &Path, dst: &Path, opts: &CopyOptions) -> Result<()> {
+    let (src, dst) = (platform::long_path(src), platform::long_path(dst));
+    let (src, dst) = (src.as_ref(), dst.as_ref());
     if !src.exists() {
         return Ok(());
     }
@@ -124,19 +384,17 @@ pub fn copy_all_contents(src: &Path, dst: &Path) -> Result<()> {
     for entry in std::fs::read_dir(src)? {
         let entry = entry?;
         let file_name = entry.file_name();
-        let name_str = file_name.to_string_lossy();
-
-        if is_excluded(&name_str) {
+        if opts.is_excluded(&file_name) {
             continue;
         }
 
-        let src_path = entry.path();
         let dst_path = dst.join(&file_name);
-        if entry.file_type()?.is_dir() {
-            copy_dir_filtered(&src_path, &dst_path)?;
-        } else {
-            std::fs::copy(&src_path, &dst_path)?;
-        }
+        opts.copy_entry(
+            &entry.path(),
+            entry.file_type()?,
+            &dst_path,
+            &mut Vec::new(),
+        )?;
     }
     Ok(())
 }
@@ -146,9 +404,10 @@ pub fn backup_session_data(config_dir: &Path, extra_dir: &Path) -> Result<()> {
         return Ok(());
     }
 
+    let ignore_case = platform::case_insensitive(config_dir);
     let has_session_data = std::fs::read_dir(config_dir)?
         .filter_map(|e| e.ok())
-        .any(|e| is_session_data(&e.file_name().to_string_lossy()));
+        .any(|e| is_session_data(&e.file_name(), ignore_case));
 
     if !has_session_data {
         return Ok(());
@@ -161,9 +420,7 @@ pub fn backup_session_data(config_dir: &Path, extra_dir: &Path) -> Result<()> {
     for entry in std::fs::read_dir(config_dir)? {
         let entry = entry?;
         let file_name = entry.file_name();
-        let name_str = file_name.to_string_lossy();
-
-        if !is_session_data(&name_str) {
+        if !is_session_data(&file_name, ignore_case) {
             continue;
         }
 
@@ -173,7 +430,7 @@ pub fn backup_session_data(config_dir: &Path, extra_dir: &Path) -> Result<()> {
         if entry.file_type()?.is_dir() {
             copy_dir_recursive(&src_path, &dst_path)?;
         } else {
-            std::fs::copy(&src_path, &dst_path)?;
+            copy_file_preserving(&src_path, &dst_path)?;
         }
     }
 
@@ -201,11 +458,42 @@ fn rotate_extra_backups(extra_dir: &Path, max_keep: usize) {
     }
 }
 
+/// Removes all but the newest `keep` timestamped snapshots in a harness backup dir.
+///
+/// Only directories whose names start with a digit are considered, so `extra/`
+/// and `no-profile/` are never rotated away.
+pub fn rotate_snapshots(harness_backup_dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(harness_backup_dir) else {
+        return;
+    };
+
+    let mut snapshots: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter(|e| {
+            e.file_name()
+                .as_encoded_bytes()
+                .first()
+                .is_some_and(u8::is_ascii_digit)
+        })
+        .map(|e| e.path())
+        .collect();
+
+    snapshots.sort();
+
+    if snapshots.len() > keep {
+        for old in snapshots.iter().take(snapshots.len() - keep) {
+            let _ = std::fs::remove_dir_all(old);
+        }
+    }
+}
+
 /// Safely switches harness config directory to match profile contents.
 ///
 /// Uses backup-wipe-copy pattern with automatic rollback on failure.
 /// This ensures complete profile isolation - the config_dir will contain
-/// EXACTLY what the profile contains, nothing more.
+/// EXACTLY what the profile contains, nothing more. Entries matching the
+/// user's exclude patterns are left in place, since bridle doesn't manage them.
 ///
 /// # Errors
 /// Returns error if profile_path doesn't exist or any filesystem operation fails.
@@ -214,57 +502,81 @@ pub fn switch_config_dir_safely(
     profile_path: &Path,
     config_dir: &Path,
     backup_dir: &Path,
+    opts: &CopyOptions,
 ) -> Result<()> {
+    let backup_path = new_backup_path(backup_dir);
+    let has_backup = switch_config_dir_with_backup(
+        profile_path,
+        config_dir,
+        &backup_path,
+        opts,
+        SwitchStrategy::Replace,
+        &[],
+        |_| Ok(()),
+    )?;
+    // Success: delete backup (best-effort)
+    if has_backup {
+        let _ = std::fs::remove_dir_all(&backup_path);
+    }
+    Ok(())
+}
+
+/// Uniquely-named backup location under `backup_dir` (millis + pid to prevent collision).
+pub fn new_backup_path(backup_dir: &Path) -> PathBuf {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+    backup_dir.join(format!("{}_{}", timestamp, std::process::id()))
+}
+
+/// [`switch_config_dir_safely`] with a caller-chosen `backup_path` that is kept
+/// on success, so the caller decides when the switch is final.
+///
+/// `before_wipe` runs once the backup is complete and before the live config
+/// is touched; it receives whether a backup was taken. Returns the same flag.
+/// With [`SwitchStrategy::Merge`], only entries the profile replaces are removed;
+/// with [`SwitchStrategy::Known`], only `known_paths` are replaced.
+#[allow(clippy::too_many_arguments)]
+pub fn switch_config_dir_with_backup(
+    profile_path: &Path,
+    config_dir: &Path,
+    backup_path: &Path,
+    opts: &CopyOptions,
+    strategy: SwitchStrategy,
+    known_paths: &[PathBuf],
+    before_wipe: impl FnOnce(bool) -> Result<()>,
+) -> Result<bool> {
     use crate::error::Error;
 
+    let config_dir = platform::long_path(config_dir);
+    let config_dir = config_dir.as_ref();
+
     // Precondition: profile must exist
     if !profile_path.exists() {
-        return Err(Error::ProfileNotFound(profile_path.display().to_string()));
+        return Err(Error::ProfileDirMissing(profile_path.to_path_buf()));
     }
 
-    // Create uniquely-named backup (millis + pid to prevent collision)
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
-    let backup_path = backup_dir.join(format!("{}_{}", timestamp, std::process::id()));
-
     let has_backup = if config_dir.exists() && std::fs::read_dir(config_dir)?.next().is_some() {
-        std::fs::create_dir_all(&backup_path)?;
-        copy_all_contents(config_dir, &backup_path)?;
+        std::fs::create_dir_all(backup_path)?;
+        copy_all_contents(config_dir, backup_path, &CopyOptions::default())?;
         true
     } else {
         false
     };
 
-    if config_dir.exists() {
-        for entry in std::fs::read_dir(config_dir)? {
-            let entry = entry?;
-            let file_name = entry.file_name();
-            let name_str = file_name.to_string_lossy();
-
-            if is_session_data(&name_str) {
-                continue;
-            }
-
-            let path = entry.path();
-            let file_type = entry.file_type()?;
-            if file_type.is_dir() {
-                std::fs::remove_dir_all(&path)?;
-            } else {
-                std::fs::remove_file(&path)?;
-            }
-        }
+    before_wipe(has_backup)?;
+    match strategy {
+        SwitchStrategy::Replace => wipe_managed_entries(config_dir, opts)?,
+        SwitchStrategy::Merge => clear_overlaid_entries(profile_path, config_dir, opts)?,
+        SwitchStrategy::Known => clear_known_entries(config_dir, known_paths, opts)?,
     }
 
     // Copy profile contents
-    let copy_result = copy_all_contents(profile_path, config_dir);
+    let copy_result = match strategy {
+        SwitchStrategy::Known => copy_known_entries(profile_path, config_dir, known_paths, opts),
+        _ => copy_all_contents(profile_path, config_dir, opts),
+    };
 
     match copy_result {
-        Ok(()) => {
-            // Success: delete backup (best-effort)
-            if has_backup {
-                let _ = std::fs::remove_dir_all(&backup_path);
-            }
-            Ok(())
-        }
+        Ok(()) => Ok(has_backup),
         Err(e) => {
             eprintln!("Profile switch failed, restoring from backup...");
 
@@ -286,7 +598,9 @@ pub fn switch_config_dir_safely(
 
             // Restore from backup if we have one
             if has_backup && backup_path.exists() {
-                if let Err(restore_err) = copy_all_contents(&backup_path, config_dir) {
+                if let Err(restore_err) =
+                    copy_all_contents(backup_path, config_dir, &CopyOptions::default())
+                {
                     // Restore failed - keep backup, return compound error
                     return Err(Error::Config(format!(
                         "Profile switch failed ({}), restore also failed ({}). Backup preserved at: {}",
@@ -295,7 +609,7 @@ pub fn switch_config_dir_safely(
                         backup_path.display()
                     )));
                 }
-                let _ = std::fs::remove_dir_all(&backup_path);
+                let _ = std::fs::remove_dir_all(backup_path);
             }
 
             Err(e)
@@ -303,7 +617,161 @@ pub fn switch_config_dir_safely(
     }
 }
 
+/// Total size in bytes of the files under `dir` that a copy with `opts` would carry.
+///
+/// Symlinks count as their own size; unreadable entries are ignored.
+pub fn copied_size(dir: &Path, opts: &CopyOptions) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| !opts.is_excluded(&entry.file_name()))
+        .map(|entry| {
+            let path = entry.path();
+            match std::fs::symlink_metadata(&path) {
+                Ok(meta) if meta.is_dir() => copied_size(&path, opts),
+                Ok(_) if opts.skips_large(&path) => 0,
+                Ok(meta) => meta.len(),
+                Err(_) => 0,
+            }
+        })
+        .sum()
+}
+
+/// Removes everything bridle manages from `config_dir`: session data,
+/// entries matching the user's exclude patterns, and files skipped under
+/// [`LargeFilePolicy::Skip`] stay.
+pub fn wipe_managed_entries(config_dir: &Path, opts: &CopyOptions) -> Result<()> {
+    if !config_dir.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(config_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if is_session_data(&file_name, opts.ignore_case) || opts.is_user_excluded(&file_name) {
+            continue;
+        }
+
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        // Skipped large files were never saved to a profile; removing them would lose them.
+        if file_type.is_file() && opts.skips_large(&path) {
+            continue;
+        }
+        if file_type.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes the entries in `config_dir` that copying `profile_path` over it
+/// would replace, so a merge never writes through a hard link or onto an
+/// entry of another type. Directories in both are descended into.
+pub fn clear_overlaid_entries(
+    profile_path: &Path,
+    config_dir: &Path,
+    opts: &CopyOptions,
+) -> Result<()> {
+    let Ok(entries) = std::fs::read_dir(profile_path) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if opts.is_excluded(&file_name) {
+            continue;
+        }
+        let live = config_dir.join(&file_name);
+        let Ok(meta) = std::fs::symlink_metadata(&live) else {
+            continue;
+        };
+        if meta.is_dir() && entry.file_type()?.is_dir() {
+            clear_overlaid_entries(&entry.path(), &live, opts)?;
+        } else if meta.is_dir() {
+            std::fs::remove_dir_all(&live)?;
+        } else {
+            std::fs::remove_file(&live)?;
+        }
+    }
+    Ok(())
+}
+
+/// Refreshes the files already in `profile_path` from `config_dir` without
+/// adding any; files `config_dir` no longer has are removed.
+pub fn refresh_existing_files(
+    config_dir: &Path,
+    profile_path: &Path,
+    opts: &CopyOptions,
+) -> Result<()> {
+    for entry in std::fs::read_dir(profile_path)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if opts.is_excluded(&file_name) {
+            continue;
+        }
+        let path = entry.path();
+        let live = config_dir.join(&file_name);
+        if entry.file_type()?.is_dir() {
+            refresh_existing_files(&live, &path, opts)?;
+            continue;
+        }
+        // Removed first, so the copy is a new file as with `write_atomic`.
+        std::fs::remove_file(&path)?;
+        if live.is_file() {
+            copy_file_preserving(&live, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes each of `paths` (relative to `dir`) from `dir`, skipping excluded names.
+pub fn clear_known_entries(dir: &Path, paths: &[PathBuf], opts: &CopyOptions) -> Result<()> {
+    for rel in paths {
+        if rel.file_name().is_some_and(|name| opts.is_excluded(name)) {
+            continue;
+        }
+        let path = dir.join(rel);
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(&path)?,
+            Ok(_) => std::fs::remove_file(&path)?,
+            Err(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Copies each of `paths` (relative to both directories) that `src` has into
+/// `dst`, which should not have them; see [`clear_known_entries`].
+pub fn copy_known_entries(
+    src: &Path,
+    dst: &Path,
+    paths: &[PathBuf],
+    opts: &CopyOptions,
+) -> Result<()> {
+    for rel in paths {
+        if rel.file_name().is_some_and(|name| opts.is_excluded(name)) {
+            continue;
+        }
+        let from = src.join(rel);
+        let Ok(meta) = std::fs::symlink_metadata(&from) else {
+            continue;
+        };
+        let to = dst.join(rel);
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        opts.copy_entry(&from, meta.file_type(), &to, &mut Vec::new())?;
+    }
+    Ok(())
+}
+
 pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
+    let (src, dst) = (platform::long_path(src), platform::long_path(dst));
+    let (src, dst) = (src.as_ref(), dst.as_ref());
     std::fs::create_dir_all(dst)?;
 
     for entry in std::fs::read_dir(src)? {
@@ -314,17 +782,38 @@ pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
         if entry.file_type()?.is_dir() {
             copy_dir_recursive(&src_path, &dst_path)?;
         } else {
-            std::fs::copy(&src_path, &dst_path)?;
+            copy_file_preserving(&src_path, &dst_path)?;
         }
     }
 
+    copy_dir_metadata(src, dst)?;
     Ok(())
 }
 
-/// Copy directory recursively, preserving symlinks and skipping excluded dirs.
+/// Copy directory recursively, skipping excluded entries and handling symlinks
+/// according to [`CopyOptions::symlinks`].
 /// Continues on errors (logs warning) rather than aborting.
-pub fn copy_dir_filtered(src: &Path, dst: &Path) -> Result<()> {
+pub fn copy_dir_filtered(src: &Path, dst: &Path, opts: &CopyOptions) -> Result<()> {
+    let (src, dst) = (platform::long_path(src), platform::long_path(dst));
+    copy_dir_walk(&src, &dst, opts, &mut Vec::new())
+}
+
+/// [`copy_dir_filtered`], tracking the canonical directories being copied so a
+/// followed symlink that leads back into one of them is not copied forever.
+fn copy_dir_walk(
+    src: &Path,
+    dst: &Path,
+    opts: &CopyOptions,
+    ancestors: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let canonical = src.canonicalize()?;
+    if ancestors.contains(&canonical) {
+        eprintln!("Warning: Skipping symlink cycle at {}", src.display());
+        return Ok(());
+    }
+
     std::fs::create_dir_all(dst)?;
+    ancestors.push(canonical);
 
     for entry in std::fs::read_dir(src)? {
         let entry = match entry {
@@ -336,55 +825,37 @@ pub fn copy_dir_filtered(src: &Path, dst: &Path) -> Result<()> {
         };
 
         let file_name = entry.file_name();
-        let name_str = file_name.to_string_lossy();
-
-        if is_excluded(&name_str) {
+        if opts.is_excluded(&file_name) {
             continue;
         }
 
         let src_path = entry.path();
         let dst_path = dst.join(&file_name);
-        let file_type = entry.file_type()?;
-
-        #[cfg(unix)]
-        if file_type.is_symlink() {
-            if let Ok(target) = std::fs::read_link(&src_path) {
-                let _ = std::fs::remove_file(&dst_path);
-                if let Err(e) = std::os::unix::fs::symlink(&target, &dst_path) {
-                    eprintln!(
-                        "Warning: Failed to create symlink {}: {}",
-                        dst_path.display(),
-                        e
-                    );
-                }
+        let file_type = match entry.file_type() {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("Warning: Failed to stat {}: {}", src_path.display(), e);
+                continue;
             }
-            continue;
-        }
+        };
 
-        if file_type.is_dir() {
-            if let Err(e) = copy_dir_filtered(&src_path, &dst_path) {
-                eprintln!(
-                    "Warning: Failed to copy directory {}: {}",
-                    src_path.display(),
-                    e
-                );
-            }
-        } else if let Err(e) = std::fs::copy(&src_path, &dst_path) {
-            eprintln!("Warning: Failed to copy file {}: {}", src_path.display(), e);
+        if let Err(e) = opts.copy_entry(&src_path, file_type, &dst_path, ancestors) {
+            eprintln!("Warning: Failed to copy {}: {}", src_path.display(), e);
         }
     }
 
+    ancestors.pop();
+    if let Err(e) = copy_dir_metadata(src, dst) {
+        eprintln!(
+            "Warning: Failed to copy metadata of {}: {}",
+            src.display(),
+            e
+        );
+    }
     Ok(())
 }
 
-/// Canonical directory names used inside profiles for resource storage.
-/// These are bridle's internal convention - harness-locate maps them to actual paths.
-pub const CANONICAL_COMMANDS_DIR: &str = "commands";
-pub const CANONICAL_AGENTS_DIR: &str = "agents";
-pub const CANONICAL_SKILLS_DIR: &str = "skills";
-pub const CANONICAL_PLUGINS_DIR: &str = "plugins";
-
-fn copy_skills_for_opencode(src: &Path, dst: &Path) -> Result<()> {
+fn copy_sanitized_skills(src: &Path, dst: &Path, opts: &CopyOptions) -> Result<()> {
     if !src.exists() {
         return Ok(());
     }
@@ -411,20 +882,17 @@ fn copy_skills_for_opencode(src: &Path, dst: &Path) -> Result<()> {
             let skill_dst = dst_skill_dir.join(skill_entry.file_name());
 
             if skill_src.is_file() {
-                let is_skill_md = skill_entry
-                    .file_name()
-                    .to_string_lossy()
-                    .eq_ignore_ascii_case("SKILL.md");
+                let is_skill_md = skill_entry.file_name().eq_ignore_ascii_case("SKILL.md");
 
                 if is_skill_md {
                     let content = std::fs::read_to_string(&skill_src)?;
                     let transformed = transform_skill_for_opencode(&content, &sanitized_name);
                     std::fs::write(&skill_dst, transformed)?;
                 } else {
-                    std::fs::copy(&skill_src, &skill_dst)?;
+                    opts.copy_file(&skill_src, &skill_dst)?;
                 }
             } else if skill_src.is_dir() {
-                copy_dir_filtered(&skill_src, &skill_dst)?;
+                copy_dir_filtered(&skill_src, &skill_dst, opts)?;
             }
         }
     }
@@ -432,60 +900,34 @@ fn copy_skills_for_opencode(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Copy resource directories between profile and harness using harness-aware paths.
+/// Copy resource directories between profile and harness using the harness's
+/// [`ResourceLayout`](crate::harness::ResourceLayout).
 ///
 /// When `to_profile` is true: harness paths → canonical profile dirs
 /// When `to_profile` is false: canonical profile dirs → harness paths
 ///
 /// Uses canonical names inside profiles for cross-harness portability.
 pub fn copy_resource_directories(
-    harness: &Harness,
+    harness: &dyn HarnessConfig,
     to_profile: bool,
     profile_path: &Path,
+    opts: &CopyOptions,
 ) -> Result<()> {
-    let scope = Scope::Global;
-
-    let resources: Vec<(&str, Option<std::path::PathBuf>)> = vec![
-        (
-            CANONICAL_COMMANDS_DIR,
-            harness.commands(&scope).ok().flatten().map(|r| r.path),
-        ),
-        (
-            CANONICAL_AGENTS_DIR,
-            harness.agents(&scope).ok().flatten().map(|r| r.path),
-        ),
-        (
-            CANONICAL_SKILLS_DIR,
-            harness.skills(&scope).ok().flatten().map(|r| r.path),
-        ),
-        (
-            CANONICAL_PLUGINS_DIR,
-            harness.plugins(&scope).ok().flatten().map(|r| r.path),
-        ),
-    ];
-
-    for (canonical_name, harness_path) in resources {
-        let Some(harness_path) = harness_path else {
-            continue;
-        };
-
-        let profile_resource = profile_path.join(canonical_name);
+    let layout = harness.resource_layout();
+    for (kind, dir) in layout.dirs() {
+        let profile_resource = profile_path.join(kind.canonical_dir());
 
         let (src, dst) = if to_profile {
-            (harness_path.as_path(), profile_resource.as_path())
+            (dir.path.as_path(), profile_resource.as_path())
         } else {
-            (profile_resource.as_path(), harness_path.as_path())
+            (profile_resource.as_path(), dir.path.as_path())
         };
 
         if src.exists() && src.is_dir() {
-            let is_skills_to_opencode = !to_profile
-                && canonical_name == CANONICAL_SKILLS_DIR
-                && matches!(harness.kind(), HarnessKind::OpenCode);
-
-            if is_skills_to_opencode {
-                copy_skills_for_opencode(src, dst)?;
+            if !to_profile && dir.sanitize_names {
+                copy_sanitized_skills(src, dst, opts)?;
             } else {
-                copy_dir_filtered(src, dst)?;
+                copy_dir_filtered(src, dst, opts)?;
             }
         }
     }
@@ -510,7 +952,7 @@ mod tests {
         fs::write(src.path().join("plugins/myplugin.json"), "{}").unwrap();
         fs::write(src.path().join("config.json"), "{}").unwrap();
 
-        copy_dir_filtered(src.path(), dst.path()).unwrap();
+        copy_dir_filtered(src.path(), dst.path(), &CopyOptions::default()).unwrap();
 
         assert!(!dst.path().join(".git").exists());
         assert!(dst.path().join("plugins").exists());
@@ -526,7 +968,7 @@ mod tests {
         fs::create_dir_all(src.path().join("hooks/pre-commit")).unwrap();
         fs::write(src.path().join("hooks/pre-commit/run.sh"), "#!/bin/bash").unwrap();
 
-        copy_dir_filtered(src.path(), dst.path()).unwrap();
+        copy_dir_filtered(src.path(), dst.path(), &CopyOptions::default()).unwrap();
 
         assert!(dst.path().join("hooks/pre-commit/run.sh").exists());
         let content = fs::read_to_string(dst.path().join("hooks/pre-commit/run.sh")).unwrap();
@@ -535,36 +977,7 @@ mod tests {
 
     #[test]
     fn copy_config_files_copies_directories_when_saving() {
-        use crate::harness::HarnessConfig;
-        use std::path::PathBuf;
-
-        struct TestHarness(PathBuf);
-        impl HarnessConfig for TestHarness {
-            fn id(&self) -> &str {
-                "test"
-            }
-            fn config_dir(&self) -> crate::error::Result<PathBuf> {
-                Ok(self.0.clone())
-            }
-            fn installation_status(
-                &self,
-            ) -> crate::error::Result<harness_locate::InstallationStatus> {
-                Ok(harness_locate::InstallationStatus::NotInstalled)
-            }
-            fn mcp_filename(&self) -> Option<String> {
-                None
-            }
-            fn mcp_config_path(&self) -> Option<PathBuf> {
-                None
-            }
-            fn parse_mcp_servers(
-                &self,
-                _: &str,
-                _: &str,
-            ) -> crate::error::Result<Vec<(String, bool)>> {
-                Ok(vec![])
-            }
-        }
+        use crate::test_utils::MockHarness;
 
         let temp = TempDir::new().unwrap();
         let config_dir = temp.path().join("config");
@@ -577,8 +990,8 @@ mod tests {
         fs::write(config_dir.join("custom-dir/data.txt"), "precious").unwrap();
         fs::write(config_dir.join("custom-dir/nested/deep.txt"), "deep data").unwrap();
 
-        let harness = TestHarness(config_dir);
-        copy_config_files(&harness, true, &profile_dir).unwrap();
+        let harness = MockHarness::new("test", config_dir);
+        copy_config_files(&harness, true, &profile_dir, &CopyOptions::default()).unwrap();
 
         assert!(profile_dir.join("settings.json").exists());
         assert!(profile_dir.join("custom-dir").exists());
@@ -591,6 +1004,38 @@ mod tests {
     }
 
     #[cfg(unix)]
+    #[test]
+    fn copy_resource_directories_follows_declared_layout() {
+        use crate::harness::{
+            ResourceDir, ResourceKind, ResourceLayout, layout::DirectoryStructure,
+        };
+        use crate::test_utils::MockHarness;
+
+        let live = TempDir::new().unwrap();
+        let profile = TempDir::new().unwrap();
+        let skill_dir = live.path().join("skill");
+        fs::create_dir_all(skill_dir.join("review")).unwrap();
+        fs::write(skill_dir.join("review/SKILL.md"), "review").unwrap();
+        let structure = DirectoryStructure::Nested {
+            subdir_pattern: "*".to_string(),
+            file_name: "SKILL.md".to_string(),
+        };
+        let harness = MockHarness::new("custom", live.path().to_path_buf()).with_resource_layout(
+            ResourceLayout::default().with_dir(
+                ResourceKind::Skills,
+                ResourceDir::new(&skill_dir, structure),
+            ),
+        );
+
+        copy_resource_directories(&harness, true, profile.path(), &CopyOptions::default()).unwrap();
+        assert!(profile.path().join("skills/review/SKILL.md").exists());
+
+        fs::remove_dir_all(&skill_dir).unwrap();
+        copy_resource_directories(&harness, false, profile.path(), &CopyOptions::default())
+            .unwrap();
+        assert!(skill_dir.join("review/SKILL.md").exists());
+    }
+
     #[test]
     fn copy_dir_filtered_preserves_symlinks() {
         use std::os::unix::fs::symlink;
@@ -601,7 +1046,7 @@ mod tests {
         fs::write(src.path().join("target.txt"), "target content").unwrap();
         symlink("target.txt", src.path().join("link.txt")).unwrap();
 
-        copy_dir_filtered(src.path(), dst.path()).unwrap();
+        copy_dir_filtered(src.path(), dst.path(), &CopyOptions::default()).unwrap();
 
         let link_path = dst.path().join("link.txt");
         assert!(
@@ -628,7 +1073,13 @@ mod tests {
         fs::create_dir_all(&profile_dir).unwrap();
         fs::write(profile_dir.join("new.txt"), "new content").unwrap();
 
-        switch_config_dir_safely(&profile_dir, &config_dir, &backup_dir).unwrap();
+        switch_config_dir_safely(
+            &profile_dir,
+            &config_dir,
+            &backup_dir,
+            &CopyOptions::default(),
+        )
+        .unwrap();
 
         assert!(config_dir.join("new.txt").exists());
         assert!(!config_dir.join("old.txt").exists());
@@ -651,7 +1102,13 @@ mod tests {
 
         fs::create_dir_all(&profile_dir).unwrap();
 
-        switch_config_dir_safely(&profile_dir, &config_dir, &backup_dir).unwrap();
+        switch_config_dir_safely(
+            &profile_dir,
+            &config_dir,
+            &backup_dir,
+            &CopyOptions::default(),
+        )
+        .unwrap();
 
         assert!(!config_dir.join("skills").exists());
     }
@@ -666,8 +1123,168 @@ mod tests {
         fs::create_dir_all(&profile_dir).unwrap();
         fs::write(profile_dir.join("config.json"), "{}").unwrap();
 
-        switch_config_dir_safely(&profile_dir, &config_dir, &backup_dir).unwrap();
+        switch_config_dir_safely(
+            &profile_dir,
+            &config_dir,
+            &backup_dir,
+            &CopyOptions::default(),
+        )
+        .unwrap();
 
         assert!(config_dir.join("config.json").exists());
     }
+
+    #[test]
+    fn large_files_are_skipped_and_kept_live() {
+        let temp = TempDir::new().unwrap();
+        let live = temp.path().join("live");
+        let profile = temp.path().join("profile");
+        fs::create_dir_all(live.join("cache")).unwrap();
+        fs::create_dir_all(&profile).unwrap();
+        fs::write(live.join("settings.json"), "{}").unwrap();
+        fs::write(live.join("dump.bin"), vec![0u8; 2048]).unwrap();
+        fs::write(live.join("cache/blob"), vec![0u8; 4096]).unwrap();
+        let opts = CopyOptions {
+            max_file_size: 1024,
+            large_files: LargeFilePolicy::Skip,
+            ..Default::default()
+        };
+
+        copy_all_contents(&live, &profile, &opts).unwrap();
+        assert!(profile.join("settings.json").exists());
+        assert!(!profile.join("dump.bin").exists());
+        assert!(!profile.join("cache/blob").exists());
+        assert_eq!(copied_size(&live, &opts), 2);
+
+        wipe_managed_entries(&live, &opts).unwrap();
+        assert!(live.join("dump.bin").exists());
+        assert!(!live.join("settings.json").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_all_contents_preserves_modes_and_mtimes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let src = TempDir::new().unwrap();
+        let dst = TempDir::new().unwrap();
+        let hook = src.path().join("hooks/pre-tool.sh");
+        fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        fs::write(&hook, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o750)).unwrap();
+        fs::set_permissions(src.path().join("hooks"), fs::Permissions::from_mode(0o710)).unwrap();
+
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        let times = fs::FileTimes::new().set_modified(old);
+        fs::File::open(&hook).unwrap().set_times(times).unwrap();
+        fs::File::open(src.path().join("hooks"))
+            .unwrap()
+            .set_times(times)
+            .unwrap();
+
+        copy_all_contents(src.path(), dst.path(), &CopyOptions::default()).unwrap();
+
+        let copied = fs::metadata(dst.path().join("hooks/pre-tool.sh")).unwrap();
+        assert_eq!(copied.permissions().mode() & 0o777, 0o750);
+        assert_eq!(copied.modified().unwrap(), old);
+        let dir = fs::metadata(dst.path().join("hooks")).unwrap();
+        assert_eq!(dir.permissions().mode() & 0o777, 0o710);
+        assert_eq!(dir.modified().unwrap(), old);
+    }
+
+    #[cfg(unix)]
+    fn tree_with_symlinks() -> TempDir {
+        let src = TempDir::new().unwrap();
+        fs::create_dir_all(src.path().join("dotfiles/nested")).unwrap();
+        fs::write(src.path().join("dotfiles/nested/rules.md"), "rules").unwrap();
+        std::os::unix::fs::symlink("dotfiles", src.path().join("linked")).unwrap();
+        // Points back at its own parent directory.
+        std::os::unix::fs::symlink("..", src.path().join("dotfiles/nested/loop")).unwrap();
+        std::os::unix::fs::symlink("missing.md", src.path().join("dangling.md")).unwrap();
+        src
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_preserved_by_default() {
+        let src = tree_with_symlinks();
+        let dst = TempDir::new().unwrap();
+
+        copy_all_contents(src.path(), dst.path(), &CopyOptions::default()).unwrap();
+
+        assert_eq!(
+            fs::read_link(dst.path().join("linked")).unwrap(),
+            PathBuf::from("dotfiles")
+        );
+        assert_eq!(
+            fs::read_link(dst.path().join("dangling.md")).unwrap(),
+            PathBuf::from("missing.md")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn followed_symlinks_copy_contents_and_stop_at_cycles() {
+        let src = tree_with_symlinks();
+        let dst = TempDir::new().unwrap();
+        let opts = CopyOptions {
+            symlinks: SymlinkPolicy::Follow,
+            ..Default::default()
+        };
+
+        copy_all_contents(src.path(), dst.path(), &opts).unwrap();
+
+        let linked = dst.path().join("linked");
+        assert!(!linked.is_symlink());
+        assert_eq!(
+            fs::read_to_string(linked.join("nested/rules.md")).unwrap(),
+            "rules"
+        );
+        // The cycle is cut where it re-enters `dotfiles`.
+        assert!(!linked.join("nested/loop/nested").exists());
+        assert!(!dst.path().join("dangling.md").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skipped_symlinks_are_left_out() {
+        let src = tree_with_symlinks();
+        let dst = TempDir::new().unwrap();
+        let opts = CopyOptions {
+            symlinks: SymlinkPolicy::Skip,
+            ..Default::default()
+        };
+
+        copy_all_contents(src.path(), dst.path(), &opts).unwrap();
+
+        assert!(dst.path().join("dotfiles/nested/rules.md").exists());
+        assert!(fs::symlink_metadata(dst.path().join("linked")).is_err());
+        assert!(fs::symlink_metadata(dst.path().join("dotfiles/nested/loop")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn switch_keeps_non_utf8_file_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let profile = TempDir::new().unwrap();
+        let live = TempDir::new().unwrap();
+        let backups = TempDir::new().unwrap();
+        let odd = OsStr::from_bytes(b"notes-\xff.md");
+        fs::write(profile.path().join(odd), "kept").unwrap();
+        fs::write(
+            profile.path().join(OsStr::from_bytes(b"trace-\xfe.log")),
+            "noise",
+        )
+        .unwrap();
+        let opts = CopyOptions {
+            exclude: vec!["*.log".to_string()],
+            ..Default::default()
+        };
+
+        switch_config_dir_safely(profile.path(), live.path(), backups.path(), &opts).unwrap();
+
+        assert_eq!(fs::read_to_string(live.path().join(odd)).unwrap(), "kept");
+        assert_eq!(fs::read_dir(live.path()).unwrap().count(), 1);
+    }
 }
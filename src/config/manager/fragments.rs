@@ -0,0 +1,385 @@
+//! Shared fragments merged into profiles on switch.
+//!
+//! A profile's [`PROFILE_FILE`] can list fragment directories to include:
+//!
+//! ```toml
+//! include = ["mcp-base", "~/work/bridle-fragments/reviewers"]
+//! ```
+//!
+//! Bare names refer to `<bridle config dir>/shared/<name>/`. When the profile
+//! is applied, each fragment's files are laid over the live config in order:
+//! JSON, JSONC, TOML, and YAML files are deep-merged with the profile's own
+//! values taking precedence, other files are copied only where the profile
+//! has none. Merged files are rewritten, so JSONC comments in them are lost.
+//! Saving the live config back strips values that still match a
+//! fragment, so the profile keeps only its own settings and picks up later
+//! changes to the fragment.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::jsonc::strip_jsonc_comments;
+use crate::config::{BridleConfig, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::edit;
+use crate::harness::schema::ConfigFormat;
+
+/// Per-profile settings file, kept in the profile and never copied to the live config.
+pub const PROFILE_FILE: &str = "profile.toml";
+
+/// Directory under bridle's config directory that holds named fragments.
+pub const SHARED_DIR: &str = "shared";
+
+/// Contents of a profile's [`PROFILE_FILE`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileSettings {
+    /// Fragment directories merged into the profile on switch, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Whether the shared skills library is mounted when the profile is applied.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub shared_skills: bool,
+    /// Labels for `bridle profile list --tag`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            shared_skills: true,
+            tags: Vec::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+impl ProfileSettings {
+    pub fn load(profile_path: &Path) -> Result<Self> {
+        let path = profile_path.join(PROFILE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    pub fn save(&self, profile_path: &Path) -> Result<()> {
+        let content = toml::to_string(self).map_err(|e| Error::Config(e.to_string()))?;
+        write_atomic(&profile_path.join(PROFILE_FILE), content)
+    }
+}
+
+/// The fragment directories a profile includes.
+#[derive(Debug, Default)]
+pub(super) struct Fragments {
+    dirs: Vec<PathBuf>,
+}
+
+impl Fragments {
+    /// Resolves the includes listed in `profile_path`'s [`PROFILE_FILE`].
+    ///
+    /// # Errors
+    /// Returns [`Error::FragmentNotFound`] if an included directory does not exist.
+    pub(super) fn load(profile_path: &Path) -> Result<Self> {
+        let settings = ProfileSettings::load(profile_path)?;
+        let mut dirs = Vec::new();
+        for include in &settings.include {
+            let dir = resolve_include(include)?;
+            if !dir.is_dir() {
+                return Err(Error::FragmentNotFound(dir));
+            }
+            dirs.push(dir);
+        }
+        Ok(Self { dirs })
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+
+    /// Lays every fragment over `live_dir`, and over `mcp_path` for a
+    /// fragment's top-level file of the same name.
+    pub(super) fn apply(&self, live_dir: &Path, mcp_path: Option<&Path>) -> Result<()> {
+        for dir in &self.dirs {
+            for rel in files_under(dir)? {
+                let target = match mcp_path {
+                    Some(mcp) if is_outside_mcp(&rel, mcp, live_dir) => mcp.to_path_buf(),
+                    _ => live_dir.join(&rel),
+                };
+                merge_file(&dir.join(&rel), &target)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes fragment content from a profile that was just saved from the
+    /// live config: values equal to a fragment's, and whole files identical
+    /// to a fragment file.
+    pub(super) fn strip(&self, profile_path: &Path) -> Result<()> {
+        for dir in self.dirs.iter().rev() {
+            for rel in files_under(dir)? {
+                let target = profile_path.join(&rel);
+                strip_file(&dir.join(&rel), &target)?;
+                // Drop directories the fragment's files leave empty; remove_dir fails on the rest.
+                for parent in target.ancestors().skip(1) {
+                    if parent == profile_path || std::fs::remove_dir(parent).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Directory for an `include` entry: a bare name under [`SHARED_DIR`], or a path.
+fn resolve_include(include: &str) -> Result<PathBuf> {
+    if let Some(rest) = include.strip_prefix("~/") {
+        return Ok(harness_locate::platform::home_dir()?.join(rest));
+    }
+    let path = Path::new(include);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    Ok(BridleConfig::config_dir()?.join(SHARED_DIR).join(path))
+}
+
+fn is_outside_mcp(rel: &Path, mcp_path: &Path, live_dir: &Path) -> bool {
+    mcp_path.file_name().is_some_and(|n| Path::new(n) == rel) && !mcp_path.starts_with(live_dir)
+}
+
+/// Relative paths of all files below `root`.
+fn files_under(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for entry in std::fs::read_dir(root.join(&rel))? {
+            let entry = entry?;
+            let rel = rel.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                pending.push(rel);
+            } else {
+                files.push(rel);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl From<Format> for ConfigFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Json => ConfigFormat::Jsonc,
+            Format::Toml => ConfigFormat::Toml,
+            Format::Yaml => ConfigFormat::Yaml,
+        }
+    }
+}
+
+fn format_of(path: &Path) -> Option<Format> {
+    match path.extension()?.to_str()? {
+        "json" | "jsonc" => Some(Format::Json),
+        "toml" => Some(Format::Toml),
+        "yaml" | "yml" => Some(Format::Yaml),
+        _ => None,
+    }
+}
+
+fn parse(text: &str, format: Format) -> Option<Value> {
+    match format {
+        Format::Json => serde_json::from_str(&strip_jsonc_comments(text)).ok(),
+        Format::Toml => toml::from_str::<toml::Value>(text)
+            .ok()
+            .and_then(|v| serde_json::to_value(v).ok()),
+        Format::Yaml => serde_yaml::from_str(text).ok(),
+    }
+}
+
+fn serialize(value: &Value, format: Format) -> Result<String> {
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(value)? + "\n",
+        Format::Toml => toml::to_string_pretty(value).map_err(|e| Error::Config(e.to_string()))?,
+        Format::Yaml => serde_yaml::to_string(value)?,
+    })
+}
+
+/// Merges fragment file `from` into `target`, whose own values win.
+fn merge_file(from: &Path, target: &Path) -> Result<()> {
+    if !target.exists() {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(from, target)?;
+        return Ok(());
+    }
+    let Some(format) = format_of(target) else {
+        return Ok(());
+    };
+    let own_text = std::fs::read_to_string(target)?;
+    let (Some(base), Some(own)) = (
+        parse(&std::fs::read_to_string(from)?, format),
+        parse(&own_text, format),
+    ) else {
+        return Ok(());
+    };
+    let merged = merge(base, own);
+    // Add the fragment's values to the file as written, keeping its comments.
+    let content = match edit::update(&own_text, format.into(), &merged) {
+        Ok(content) => content,
+        Err(_) => serialize(&merged, format)?,
+    };
+    write_atomic(target, content)
+}
+
+/// Removes from `target` what fragment file `from` would contribute.
+fn strip_file(from: &Path, target: &Path) -> Result<()> {
+    if !target.exists() {
+        return Ok(());
+    }
+    let fragment = std::fs::read(from)?;
+    let own_text = std::fs::read(target)?;
+    if fragment == own_text {
+        std::fs::remove_file(target)?;
+        return Ok(());
+    }
+    let Some(format) = format_of(target) else {
+        return Ok(());
+    };
+    let (Some(base), Some(mut own)) = (
+        parse(&String::from_utf8_lossy(&fragment), format),
+        parse(&String::from_utf8_lossy(&own_text), format),
+    ) else {
+        return Ok(());
+    };
+    if own == base {
+        std::fs::remove_file(target)?;
+        return Ok(());
+    }
+    subtract(&mut own, &base);
+    let content = match edit::update(&String::from_utf8_lossy(&own_text), format.into(), &own) {
+        Ok(content) => content,
+        Err(_) => serialize(&own, format)?,
+    };
+    std::fs::remove_file(target)?;
+    std::fs::write(target, content)?;
+    Ok(())
+}
+
+/// `own` laid over `base`: objects merge key by key, anything else in `own` replaces `base`.
+fn merge(base: Value, own: Value) -> Value {
+    match (base, own) {
+        (Value::Object(mut base), Value::Object(own)) => {
+            for (key, value) in own {
+                let merged = match base.remove(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (_, own) => own,
+    }
+}
+
+/// Drops keys from `own` whose values equal `base`'s, recursing into objects.
+fn subtract(own: &mut Value, base: &Value) {
+    let (Value::Object(own), Value::Object(base)) = (own, base) else {
+        return;
+    };
+    let mut emptied = Vec::new();
+    for (key, base_value) in base {
+        let Some(own_value) = own.get_mut(key) else {
+            continue;
+        };
+        if own_value == base_value {
+            emptied.push(key.clone());
+        } else if base_value.is_object() {
+            subtract(own_value, base_value);
+            if own_value.as_object().is_some_and(Map::is_empty) {
+                emptied.push(key.clone());
+            }
+        }
+    }
+    for key in emptied {
+        own.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_then_subtract_recovers_own_values() {
+        let base = json!({"mcp": {"github": {"url": "a"}, "docs": {"url": "b"}}, "theme": "dark"});
+        let own = json!({"mcp": {"docs": {"url": "mine"}, "local": {"cmd": "x"}}, "model": "m"});
+
+        let merged = merge(base.clone(), own.clone());
+        assert_eq!(
+            merged,
+            json!({
+                "mcp": {"github": {"url": "a"}, "docs": {"url": "mine"}, "local": {"cmd": "x"}},
+                "theme": "dark",
+                "model": "m"
+            })
+        );
+
+        let mut saved = merged;
+        subtract(&mut saved, &base);
+        assert_eq!(saved, own);
+    }
+
+    #[test]
+    fn apply_and_strip_round_trip_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let fragment = temp.path().join("fragment");
+        let live = temp.path().join("live");
+        std::fs::create_dir_all(fragment.join("skills")).unwrap();
+        std::fs::create_dir_all(&live).unwrap();
+        std::fs::write(fragment.join("settings.toml"), "a = 1\n[t]\nx = 1\n").unwrap();
+        std::fs::write(fragment.join("skills/shared.md"), "shared").unwrap();
+        std::fs::write(live.join("settings.toml"), "# mine\nb = 2\n[t]\ny = 2\n").unwrap();
+
+        let fragments = Fragments {
+            dirs: vec![fragment.clone()],
+        };
+        fragments.apply(&live, None).unwrap();
+        let merged = std::fs::read_to_string(live.join("settings.toml")).unwrap();
+        assert!(merged.starts_with("# mine\n"));
+        let merged: toml::Table = toml::from_str(&merged).unwrap();
+        assert_eq!(merged["a"].as_integer(), Some(1));
+        assert_eq!(merged["t"]["y"].as_integer(), Some(2));
+        assert!(live.join("skills/shared.md").exists());
+
+        fragments.strip(&live).unwrap();
+        let own = std::fs::read_to_string(live.join("settings.toml")).unwrap();
+        assert!(own.starts_with("# mine\n"));
+        let own: toml::Table = toml::from_str(&own).unwrap();
+        assert_eq!(own, toml::from_str("b = 2\n[t]\ny = 2\n").unwrap());
+        assert!(!live.join("skills").exists());
+    }
+}
@@ -0,0 +1,139 @@
+//! Inspecting a harness's live config before bridle first captures it.
+
+use serde::Serialize;
+
+use super::{ProfileManager, files};
+use crate::config::profile_name::ProfileName;
+use crate::error::Result;
+use crate::harness::HarnessConfig;
+
+/// Directory names harnesses use for data they regenerate on their own.
+const REGENERATED_DIRS: &[&str] = &[
+    "cache",
+    "caches",
+    ".cache",
+    "logs",
+    "log",
+    "tmp",
+    "temp",
+    "telemetry",
+    "crash-reports",
+    "crashpad",
+];
+
+/// File extensions of logs and scratch files, excluded with a `*.<ext>` pattern.
+const REGENERATED_EXTENSIONS: &[&str] = &["log", "tmp"];
+
+/// One top-level entry of a harness's live config directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LiveEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// Bytes a profile copy would carry; for directories, their files that
+    /// are not excluded.
+    pub size: u64,
+    /// Already left out of profiles by bridle's built-in or configured excludes.
+    pub excluded: bool,
+    /// Exclude pattern proposed for a cache or log the entry looks like.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_exclude: Option<String>,
+}
+
+/// Proposes an exclude pattern for an entry whose name marks it as a cache or log.
+pub fn suggest_exclude(name: &str, is_dir: bool) -> Option<String> {
+    let lower = name.to_ascii_lowercase();
+    if is_dir {
+        return (REGENERATED_DIRS.contains(&lower.as_str()) || lower.contains("cache"))
+            .then(|| name.to_string());
+    }
+    let ext = lower.rsplit_once('.').map(|(_, ext)| ext);
+    if let Some(ext) = ext.filter(|ext| REGENERATED_EXTENSIONS.contains(ext)) {
+        return Some(format!("*.{ext}"));
+    }
+    lower.contains("cache").then(|| name.to_string())
+}
+
+impl ProfileManager {
+    /// Lists the top-level entries of `harness`'s live config, largest first,
+    /// with exclude patterns proposed for caches and logs.
+    pub fn inspect_live(&self, harness: &dyn HarnessConfig) -> Result<Vec<LiveEntry>> {
+        let config_dir = harness.config_dir()?;
+        let opts = self.copy_options_for(harness);
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&config_dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let name = file_name.to_string_lossy().into_owned();
+            let meta = std::fs::symlink_metadata(entry.path())?;
+            let is_dir = meta.is_dir();
+            let size = if is_dir {
+                files::copied_size(&entry.path(), &opts)
+            } else {
+                meta.len()
+            };
+            let excluded = opts.is_excluded(&file_name);
+            entries.push(LiveEntry {
+                suggested_exclude: if excluded {
+                    None
+                } else {
+                    suggest_exclude(&name, is_dir)
+                },
+                name,
+                is_dir,
+                size,
+                excluded,
+            });
+        }
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    }
+
+    /// Bytes of files stored in profile `name`.
+    pub fn profile_size(&self, harness: &dyn HarnessConfig, name: &ProfileName) -> u64 {
+        files::copied_size(&self.profile_path(harness, name), &Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockHarness;
+    use tempfile::TempDir;
+
+    #[test]
+    fn suggests_excludes_for_caches_and_logs() {
+        assert_eq!(suggest_exclude("Cache", true), Some("Cache".to_string()));
+        assert_eq!(
+            suggest_exclude("image-cache", true),
+            Some("image-cache".to_string())
+        );
+        assert_eq!(
+            suggest_exclude("debug.log", false),
+            Some("*.log".to_string())
+        );
+        assert_eq!(suggest_exclude("logs", false), None);
+        assert_eq!(suggest_exclude("skills", true), None);
+        assert_eq!(suggest_exclude("settings.json", false), None);
+    }
+
+    #[test]
+    fn inspect_live_sizes_entries_and_skips_excluded_ones() {
+        let temp = TempDir::new().unwrap();
+        let live = temp.path().join("live");
+        std::fs::create_dir_all(live.join("cache")).unwrap();
+        std::fs::create_dir_all(live.join("todos")).unwrap();
+        std::fs::write(live.join("cache/blob"), vec![0u8; 64]).unwrap();
+        std::fs::write(live.join("settings.json"), "{}").unwrap();
+
+        let harness = MockHarness::new("test-inspect", live);
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        let entries = manager.inspect_live(&harness).unwrap();
+
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["cache", "settings.json", "todos"]);
+        assert_eq!(entries[0].size, 64);
+        assert_eq!(entries[0].suggested_exclude.as_deref(), Some("cache"));
+        assert!(entries[2].excluded);
+        assert_eq!(entries[2].suggested_exclude, None);
+    }
+}
@@ -0,0 +1,175 @@
+//! Cache of what extraction found in a profile.
+//!
+//! Extraction parses every config file and lists every resource directory,
+//! which adds up when `bridle profile list` or `bridle status` covers dozens
+//! of profiles. Each result is stored with a stamp of the size and mtime of
+//! every entry down to the extraction depth limit, plus the settings files
+//! kept outside the profile that extraction also reads. While the stamp
+//! matches, the stored result is used without parsing anything.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::extraction::{self, ExtractionLimits};
+use super::manifest::hex;
+use crate::config::types::ProfileInfo;
+use crate::config::{BridleConfig, write_atomic};
+use crate::error::Result;
+use crate::harness::HarnessConfig;
+
+/// An extraction result and the stamp of what it was read from.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedInfo {
+    stamp: String,
+    info: ProfileInfo,
+}
+
+/// Digest of everything extracting `path` for `harness` could read.
+///
+/// Covers the bridle version, the limits, the harness's custom resource
+/// categories, `config.toml` (where harness overrides are defined), each
+/// entry under `path` down to `limits.max_depth` levels and the first
+/// `limits.max_entries` of each directory, and for Claude Code the project
+/// and managed settings.
+pub(super) fn stamp(harness: &dyn HarnessConfig, path: &Path, limits: &ExtractionLimits) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update(format!(
+        "\0{}\0{}\0{}\0",
+        harness.id(),
+        limits.max_depth,
+        limits.max_entries
+    ));
+    for (name, resource) in harness.resource_layout().custom() {
+        hasher.update(format!("{name}\0{resource:?}\0"));
+    }
+
+    let mut outside: Vec<PathBuf> = BridleConfig::config_path().into_iter().collect();
+    if harness.id() == "claude-code" {
+        let project = std::env::current_dir().ok();
+        outside.extend(
+            extraction::settings_files(
+                path,
+                project.as_deref(),
+                &extraction::managed_settings_dir(),
+            )
+            .into_iter()
+            .map(|(_, file)| file),
+        );
+    }
+    for file in std::iter::once(path).chain(outside.iter().map(PathBuf::as_path)) {
+        hasher.update(file.as_os_str().as_encoded_bytes());
+        stamp_entry(&mut hasher, file);
+    }
+    stamp_dir(&mut hasher, path, 0, limits);
+    hex(&hasher.finalize())
+}
+
+/// Adds the size and mtime of `path`, following symlinks as extraction does.
+fn stamp_entry(hasher: &mut Sha256, path: &Path) {
+    match std::fs::metadata(path).or_else(|_| std::fs::symlink_metadata(path)) {
+        Ok(meta) => {
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or_default();
+            hasher.update(format!("\0{}\0{mtime}\n", meta.len()));
+        }
+        Err(_) => hasher.update("\0-\n"),
+    }
+}
+
+fn stamp_dir(hasher: &mut Sha256, dir: &Path, depth: usize, limits: &ExtractionLimits) {
+    if depth >= limits.max_depth {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .take(limits.max_entries)
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        hasher.update(entry.file_name().as_encoded_bytes());
+        stamp_entry(hasher, &path);
+        if path.is_dir() {
+            stamp_dir(hasher, &path, depth + 1, limits);
+        }
+    }
+}
+
+/// The result stored in `file`, if it was read from what `stamp` describes.
+/// A missing or unreadable file is a miss; it is only a cache.
+pub(super) fn load(file: &Path, stamp: &str) -> Option<ProfileInfo> {
+    let content = std::fs::read_to_string(file).ok()?;
+    let cached: CachedInfo = serde_json::from_str(&content).ok()?;
+    (cached.stamp == stamp).then_some(cached.info)
+}
+
+pub(super) fn save(file: &Path, stamp: &str, info: &ProfileInfo) -> Result<()> {
+    let cached = CachedInfo {
+        stamp: stamp.to_string(),
+        info: info.clone(),
+    };
+    write_atomic(file, serde_json::to_string(&cached)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockHarness;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn stamp_follows_changes_within_the_depth_limit() {
+        let temp = TempDir::new().unwrap();
+        let profile = temp.path().join("profile");
+        fs::create_dir_all(profile.join("skills/review/deep")).unwrap();
+        fs::write(profile.join("skills/review/SKILL.md"), "one").unwrap();
+        fs::write(profile.join("skills/review/deep/notes.md"), "one").unwrap();
+        let harness = MockHarness::new("test-cache", profile.clone());
+        let limits = ExtractionLimits {
+            max_depth: 3,
+            max_entries: 100,
+        };
+
+        let before = stamp(&harness, &profile, &limits);
+        assert_eq!(stamp(&harness, &profile, &limits), before);
+
+        fs::write(
+            profile.join("skills/review/deep/notes.md"),
+            "beyond the limit",
+        )
+        .unwrap();
+        assert_eq!(stamp(&harness, &profile, &limits), before);
+
+        fs::write(profile.join("skills/review/SKILL.md"), "longer").unwrap();
+        assert_ne!(stamp(&harness, &profile, &limits), before);
+    }
+
+    #[test]
+    fn load_misses_on_another_stamp() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("cache/profile.json");
+        let info = ProfileInfo {
+            harness_id: "opencode".to_string(),
+            model: Some("gpt-5".to_string()),
+            ..Default::default()
+        };
+        save(&file, "abc", &info).unwrap();
+
+        let loaded = load(&file, "abc").unwrap();
+        assert_eq!(loaded.model.as_deref(), Some("gpt-5"));
+        assert!(load(&file, "def").is_none());
+        assert!(load(&temp.path().join("missing.json"), "abc").is_none());
+    }
+}
@@ -0,0 +1,215 @@
+//! Write-ahead record of an in-progress profile switch.
+//!
+//! A switch writes its intent under the state directory before touching the
+//! live config and removes the record once the new profile is recorded as
+//! active. A record left behind means the process died mid-switch; see
+//! [`ProfileManager::recover`](super::ProfileManager::recover).
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use super::SwitchStrategy;
+use crate::config::{StatePaths, write_atomic};
+use crate::error::{Error, Result};
+
+/// How far a switch got before it was interrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwitchStep {
+    /// Intent recorded; the live config has not been modified.
+    Started,
+    /// The live config is backed up and is being replaced.
+    BackedUp,
+    /// The profile is in place; only recording it as active remains.
+    Applied,
+}
+
+/// How [`ProfileManager::recover`](super::ProfileManager::recover) settles an interrupted switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Finish applying the target profile.
+    Complete,
+    /// Restore the live config from the pre-switch backup.
+    RollBack,
+}
+
+/// Journal entry for one harness's profile switch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwitchJournal {
+    pub harness: String,
+    /// Profile active before the switch, if any.
+    pub from: Option<String>,
+    pub to: String,
+    pub step: SwitchStep,
+    pub started_at: String,
+    pub config_dir: PathBuf,
+    pub profile_path: PathBuf,
+    /// Copy of the live config taken before it was replaced.
+    pub backup_path: PathBuf,
+    /// MCP config file living outside `config_dir`, if the harness has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_path: Option<PathBuf>,
+    /// Exclude patterns in effect, so recovery leaves the same entries alone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Whether completing the switch replaces the live config or merges into it.
+    #[serde(default, skip_serializing_if = "is_replace")]
+    pub strategy: SwitchStrategy,
+    /// Paths replaced under [`SwitchStrategy::Known`], relative to `config_dir`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub known_paths: Vec<PathBuf>,
+    #[serde(skip)]
+    file: PathBuf,
+}
+
+impl SwitchJournal {
+    /// Records the intent to switch and returns the journal.
+    ///
+    /// Also copies an MCP config outside `config_dir` next to the backup,
+    /// since the directory backup does not cover it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn begin(
+        paths: &StatePaths,
+        harness: &str,
+        from: Option<String>,
+        to: &str,
+        config_dir: PathBuf,
+        profile_path: PathBuf,
+        backup_path: PathBuf,
+        mcp_path: Option<PathBuf>,
+        exclude: Vec<String>,
+        strategy: SwitchStrategy,
+        known_paths: Vec<PathBuf>,
+    ) -> Result<Self> {
+        let mcp_path = mcp_path.filter(|p| !p.starts_with(&config_dir));
+        let journal = Self {
+            harness: harness.to_string(),
+            from,
+            to: to.to_string(),
+            step: SwitchStep::Started,
+            started_at: Local::now().to_rfc3339(),
+            config_dir,
+            profile_path,
+            backup_path,
+            mcp_path,
+            exclude,
+            strategy,
+            known_paths,
+            file: journal_file(paths, harness),
+        };
+        if let Some(mcp) = journal.mcp_path.as_deref().filter(|p| p.is_file()) {
+            let backup = journal.mcp_backup_path();
+            if let Some(parent) = backup.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            super::files::copy_file_preserving(mcp, &backup)?;
+        }
+        journal.write()?;
+        Ok(journal)
+    }
+
+    /// Records that the switch reached `step`.
+    pub fn advance(&mut self, step: SwitchStep) -> Result<()> {
+        self.step = step;
+        self.write()
+    }
+
+    /// Marks the switch as settled: removes the journal and the backups it kept.
+    pub fn finish(self) -> Result<()> {
+        std::fs::remove_file(&self.file)?;
+        let _ = std::fs::remove_dir_all(&self.backup_path);
+        let _ = std::fs::remove_file(self.mcp_backup_path());
+        Ok(())
+    }
+
+    /// Where the pre-switch copy of [`SwitchJournal::mcp_path`] is kept.
+    pub fn mcp_backup_path(&self) -> PathBuf {
+        let mut name = self
+            .backup_path
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_default();
+        name.push(".mcp");
+        self.backup_path.with_file_name(name)
+    }
+
+    /// Journals left behind by interrupted switches, sorted by harness.
+    pub fn pending(paths: &StatePaths) -> Result<Vec<Self>> {
+        let dir = paths.journal_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut journals = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "toml") {
+                journals.push(Self::read(&path)?);
+            }
+        }
+        journals.sort_by(|a, b| a.harness.cmp(&b.harness));
+        Ok(journals)
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut journal: Self = toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        journal.file = path.to_path_buf();
+        Ok(journal)
+    }
+
+    fn write(&self) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        write_atomic(&self.file, content)
+    }
+}
+
+fn is_replace(strategy: &SwitchStrategy) -> bool {
+    *strategy == SwitchStrategy::Replace
+}
+
+fn journal_file(paths: &StatePaths, harness: &str) -> PathBuf {
+    paths.journal_dir().join(format!("{harness}.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn journal_round_trips_and_finishes() {
+        let temp = TempDir::new().unwrap();
+        let paths = StatePaths::new(temp.path().join("state"));
+        let backup = temp.path().join("backups/opencode/1");
+        std::fs::create_dir_all(&backup).unwrap();
+
+        let mut journal = SwitchJournal::begin(
+            &paths,
+            "opencode",
+            Some("home".to_string()),
+            "work",
+            temp.path().join("live"),
+            temp.path().join("profiles/opencode/work"),
+            backup.clone(),
+            None,
+            vec!["*.log".to_string()],
+            SwitchStrategy::Known,
+            vec![PathBuf::from("opencode.jsonc")],
+        )
+        .unwrap();
+        journal.advance(SwitchStep::BackedUp).unwrap();
+
+        let pending = SwitchJournal::pending(&paths).unwrap();
+        assert_eq!(pending, vec![journal.clone()]);
+        assert_eq!(pending[0].step, SwitchStep::BackedUp);
+
+        journal.finish().unwrap();
+        assert!(SwitchJournal::pending(&paths).unwrap().is_empty());
+        assert!(!backup.exists());
+    }
+}
@@ -1,14 +1,61 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use chrono::Local;
 use harness_locate::Harness;
 
-use super::ProfileManager;
 use super::files;
-use crate::config::BridleConfig;
+use super::fragments::{Fragments, PROFILE_FILE};
+use super::journal::{RecoveryAction, SwitchJournal, SwitchStep};
+use super::machine::MachineOverrides;
+use super::platform;
+use super::protected::ProtectedValues;
+use super::rules::{RULES_DIR, RulesSnippets};
+use super::shared_skills::SharedSkills;
+use super::template::{ProfileTemplates, VARS_FILE};
+use super::{DriftAction, ProfileEvent, ProfileManager, SnapshotPolicy, SwitchStrategy};
+use crate::config::Policy;
 use crate::config::profile_name::ProfileName;
+use crate::config::{BridleConfig, PersistedState, StatePaths, write_atomic};
 use crate::error::{Error, Result};
 use crate::harness::HarnessConfig;
+use crate::harness::schema::rules_file;
+use crate::install::manifest::{InstallManifest, manifest_path};
+
+/// Serializes updates to `config.toml` and the state file, which every
+/// harness's switch reads and rewrites, when several harnesses switch at once.
+static SHARED_STATE: Mutex<()> = Mutex::new(());
+
+/// Profile files whose live changes were discarded, captured before the live
+/// config is saved over the profile.
+struct KeptFiles {
+    profile_path: PathBuf,
+    /// Relative path and original content; `None` if the profile had no such file.
+    files: Vec<(String, Option<Vec<u8>>)>,
+}
+
+impl KeptFiles {
+    /// Puts the captured files back into the profile.
+    fn restore(self) -> Result<()> {
+        for (path, content) in self.files {
+            let target = self.profile_path.join(&path);
+            match content {
+                Some(content) => write_atomic(&target, content)?,
+                None => {
+                    if target.is_file() {
+                        std::fs::remove_file(&target)?;
+                    }
+                    for parent in target.ancestors().skip(1) {
+                        if parent == self.profile_path || std::fs::remove_dir(parent).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
 impl ProfileManager {
     pub fn backups_dir(&self) -> PathBuf {
@@ -19,7 +66,15 @@ impl ProfileManager {
             .join("backups")
     }
 
-    pub fn backup_current(&self, harness: &dyn HarnessConfig) -> Result<PathBuf> {
+    /// Snapshots the harness's live config into a timestamped backup directory.
+    ///
+    /// Returns `Ok(None)` when the snapshot policy is [`SnapshotPolicy::Never`]
+    /// or the manager is in dry-run mode.
+    pub fn backup_current(&self, harness: &dyn HarnessConfig) -> Result<Option<PathBuf>> {
+        if self.options.snapshot_policy == SnapshotPolicy::Never {
+            return Ok(None);
+        }
+
         let source_dir = harness.config_dir()?;
         let has_config_dir = source_dir.exists();
         let has_mcp = harness
@@ -28,22 +83,34 @@ impl ProfileManager {
             .unwrap_or(false);
 
         if !has_config_dir && !has_mcp {
-            return Err(Error::NoConfigFound(format!(
-                "No config found for {}",
-                harness.id()
-            )));
+            return Err(Error::NoConfigFound {
+                harness: harness.id().to_string(),
+            });
         }
 
         let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
         let backup_path = self.backups_dir().join(harness.id()).join(&timestamp);
 
+        if self.options.dry_run {
+            return Ok(None);
+        }
+
         std::fs::create_dir_all(&backup_path)?;
-        files::copy_config_files(harness, true, &backup_path)?;
+        files::copy_config_files(harness, true, &backup_path, &self.copy_options_for(harness))?;
+        self.label_snapshot(harness, &backup_path)?;
 
         let extra_dir = self.backups_dir().join(harness.id()).join("extra");
         let _ = files::backup_session_data(&source_dir, &extra_dir);
 
-        Ok(backup_path)
+        if let SnapshotPolicy::KeepLast(keep) = self.options.snapshot_policy {
+            files::rotate_snapshots(&self.backups_dir().join(harness.id()), keep);
+        }
+
+        self.emit(ProfileEvent::SnapshotCreated {
+            harness: harness.id().to_string(),
+            path: backup_path.clone(),
+        });
+        Ok(Some(backup_path))
     }
 
     pub fn save_to_profile(
@@ -67,29 +134,83 @@ impl ProfileManager {
             return Ok(());
         }
 
-        for entry in std::fs::read_dir(&profile_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                std::fs::remove_file(&path)?;
-            } else if path.is_dir() {
-                std::fs::remove_dir_all(&path)?;
-            }
-        }
-
-        files::copy_all_contents(&source_dir, &profile_path)?;
-        if let Some(mcp_path) = harness.mcp_config_path()
-            && mcp_path.exists()
-            && mcp_path.is_file()
-            && let Some(filename) = mcp_path.file_name()
-        {
-            let dest = profile_path.join(filename);
-            std::fs::copy(&mcp_path, dest)?;
+        self.warn_if_profile_too_large(harness, &source_dir);
+        if !self.options.dry_run {
+            self.replace_profile_with_live(harness, &source_dir, &profile_path)?;
+            let _ = self.record_manifest(harness, name.as_str());
         }
         let _ = harness_for_resources;
+        self.emit(ProfileEvent::Saved {
+            harness: harness.id().to_string(),
+            profile: name.as_str().to_string(),
+        });
         Ok(())
     }
 
+    fn replace_profile_with_live(
+        &self,
+        harness: &dyn HarnessConfig,
+        source_dir: &std::path::Path,
+        profile_path: &std::path::Path,
+    ) -> Result<()> {
+        let overrides = MachineOverrides::load(profile_path)?;
+        let mut templates =
+            ProfileTemplates::scan(profile_path, harness.mcp_filename().as_deref())?;
+        templates.skip(overrides.targets());
+        let fragments = Fragments::load(profile_path).unwrap_or_else(|e| {
+            eprintln!("Warning: Shared fragments were not stripped from the saved profile: {e}");
+            Fragments::default()
+        });
+        let rules = RulesSnippets::load(profile_path).unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: The assembled rules file was not stripped from the saved profile: {e}"
+            );
+            RulesSnippets::default()
+        });
+        let shared_skills = SharedSkills::for_profile(profile_path).unwrap_or_else(|e| {
+            eprintln!("Warning: Shared skills were not stripped from the saved profile: {e}");
+            None
+        });
+        let opts = self.copy_options_for(harness);
+        match self.switch_strategy_for(harness) {
+            SwitchStrategy::Replace => {
+                for entry in std::fs::read_dir(profile_path)? {
+                    let entry = entry?;
+                    let path = entry.path();
+                    let file_name = entry.file_name();
+                    if file_name == VARS_FILE || file_name == PROFILE_FILE || file_name == RULES_DIR
+                    {
+                        continue;
+                    }
+                    if path.is_file() {
+                        std::fs::remove_file(&path)?;
+                    } else if path.is_dir() {
+                        std::fs::remove_dir_all(&path)?;
+                    }
+                }
+                files::copy_all_contents(source_dir, profile_path, &opts)?;
+            }
+            SwitchStrategy::Merge => {
+                files::refresh_existing_files(source_dir, profile_path, &opts)?
+            }
+            SwitchStrategy::Known => {
+                let known_paths = harness.known_paths();
+                files::clear_known_entries(profile_path, &known_paths, &opts)?;
+                files::copy_known_entries(source_dir, profile_path, &known_paths, &opts)?;
+            }
+        }
+        files::copy_mcp_config_to_profile(harness, profile_path)?;
+        fragments.strip(profile_path)?;
+        if let Some(file_name) = rules_file(harness.id()).filter(|_| !rules.is_empty()) {
+            rules.strip(profile_path, file_name)?;
+        }
+        if let Some(shared_skills) = shared_skills {
+            shared_skills.strip(harness, profile_path)?;
+        }
+        templates.restore(profile_path, source_dir, self.options.secret_store())?;
+        overrides.restore(profile_path)
+    }
+
     pub fn switch_profile(
         &self,
         harness: &dyn HarnessConfig,
@@ -107,8 +228,9 @@ impl ProfileManager {
         let profile_path = self.profile_path(harness, name);
 
         if !profile_path.exists() {
-            return Err(Error::ProfileNotFound(name.as_str().to_string()));
+            return Err(Error::profile_not_found(harness.id(), name.as_str()));
         }
+        self.check_policy(harness, name)?;
 
         let harness_id = harness.id();
 
@@ -121,56 +243,466 @@ impl ProfileManager {
             return Ok(profile_path);
         }
 
-        let saved_to_profile = if let Ok(config) = BridleConfig::load()
-            && let Some(active_name) = config.active_profile_for(harness_id)
+        let previous = BridleConfig::load()
+            .ok()
+            .and_then(|c| c.active_profile_for(harness_id).map(String::from));
+
+        let target_dir = harness.config_dir()?;
+
+        if self.options.dry_run {
+            if let Some(active_name) = previous.as_deref()
+                && let Ok(active_profile) = ProfileName::new(active_name)
+            {
+                self.save_to_profile(harness, harness_for_resources, &active_profile)?;
+            }
+            self.emit(ProfileEvent::Switched {
+                harness: harness_id.to_string(),
+                from: previous,
+                to: name.as_str().to_string(),
+            });
+            return Ok(target_dir);
+        }
+
+        let saved_to_profile = if let Some(active_name) = previous.as_deref()
             && let Ok(active_profile) = ProfileName::new(active_name)
             && active_profile.as_str() != name.as_str()
         {
+            let kept = self.resolve_drift(harness, &active_profile)?;
             self.save_to_profile(harness, harness_for_resources, &active_profile)?;
+            kept.restore()?;
             true
         } else {
             false
         };
 
-        let target_dir = harness.config_dir()?;
-
         // If no active profile was saved, backup current state to "no-profile" folder
         // This preserves unknown files when switching for the first time
         if !saved_to_profile && target_dir.exists() {
             let no_profile_backup = self.backups_dir().join(harness.id()).join("no-profile");
             let _ = std::fs::remove_dir_all(&no_profile_backup);
             std::fs::create_dir_all(&no_profile_backup)?;
-            files::copy_all_contents(&target_dir, &no_profile_backup)?;
+            files::copy_all_contents(&target_dir, &no_profile_backup, &Default::default())?;
         }
 
+        self.apply_profile(harness, previous, name, &profile_path)?;
+        if let Ok(paths) = BridleConfig::load().and_then(|c| c.state_paths()) {
+            let _guard = SHARED_STATE.lock().unwrap_or_else(|e| e.into_inner());
+            let _ = PersistedState::record_switch(&paths, harness_id, name.as_str());
+        }
+        Ok(target_dir)
+    }
+
+    /// Replaces the live config with profile `name` and records it as active,
+    /// without saving the live config into any profile first. For callers
+    /// that saved it elsewhere, such as `bridle context use`.
+    ///
+    /// # Errors
+    /// Returns [`Error::ProfileNotFound`] if the profile does not exist, or
+    /// [`Error::PolicyViolation`] if it breaks the team policy.
+    pub fn restore_profile(&self, harness: &dyn HarnessConfig, name: &ProfileName) -> Result<()> {
+        if !self.profile_exists(harness, name) {
+            return Err(Error::profile_not_found(harness.id(), name.as_str()));
+        }
+        self.check_policy(harness, name)?;
+        if self.options.dry_run {
+            return Ok(());
+        }
+        self.apply_profile(harness, None, name, &self.profile_path(harness, name))
+    }
+
+    /// Refuses profile `name` if it breaks the team policy, naming every
+    /// violation.
+    fn check_policy(&self, harness: &dyn HarnessConfig, name: &ProfileName) -> Result<()> {
+        let Some(location) = self.options.policy.as_deref() else {
+            return Ok(());
+        };
+        let policy = Policy::load(location)?;
+        let info = self.show_profile(harness, name)?;
+        let manifest = InstallManifest::load(&manifest_path(&info.path)).unwrap_or_default();
+        let violations = policy.check_profile(&info, manifest.entries.iter().map(|e| &e.source));
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(policy.violated(violations))
+        }
+    }
+
+    /// Decides what happens to each file that drifted from the active profile
+    /// `name`, stashing live copies as asked. Returns the profile files to put
+    /// back once the live config has been saved over them.
+    ///
+    /// # Errors
+    /// Returns [`Error::DriftAborted`] if any file's action is
+    /// [`DriftAction::Abort`]; nothing has been written by then.
+    fn resolve_drift(&self, harness: &dyn HarnessConfig, name: &ProfileName) -> Result<KeptFiles> {
+        let mut kept = KeptFiles {
+            profile_path: self.profile_path(harness, name),
+            files: Vec::new(),
+        };
+        let options = &self.options;
+        if options.on_drift == Some(DriftAction::Save)
+            || (options.on_drift.is_none() && options.drift_prompt.is_none())
+        {
+            return Ok(kept);
+        }
+        let Some(drift) = self.drift(harness)? else {
+            return Ok(kept);
+        };
+
+        let mut decisions = Vec::new();
+        for (path, change) in drift.changes() {
+            let action = match (options.on_drift, &options.drift_prompt) {
+                (Some(action), _) => action,
+                (None, Some(prompt)) => prompt(path, change).unwrap_or_default(),
+                (None, None) => DriftAction::Save,
+            };
+            if action == DriftAction::Abort {
+                return Err(Error::DriftAborted {
+                    harness: harness.id().to_string(),
+                    profile: name.as_str().to_string(),
+                    files: drift.changes().map(|(p, _)| p.to_string()).collect(),
+                });
+            }
+            decisions.push((path, action));
+        }
+
+        let live_dir = harness.config_dir()?;
+        let stash_dir = self
+            .backups_dir()
+            .join(harness.id())
+            .join("stash")
+            .join(format!(
+                "{}-{}",
+                name.as_str(),
+                Local::now().format("%Y%m%d_%H%M%S")
+            ));
+        let mut stashed = 0;
+        for (path, action) in decisions {
+            if action == DriftAction::Save {
+                continue;
+            }
+            let live = live_dir.join(path);
+            if action == DriftAction::Stash && live.is_file() {
+                let dest = stash_dir.join(path);
+                if let Some(parent) = dest.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(&live, &dest)?;
+                stashed += 1;
+            }
+            let original = kept.profile_path.join(path);
+            let content = if original.is_file() {
+                Some(std::fs::read(&original)?)
+            } else {
+                None
+            };
+            kept.files.push((path.to_string(), content));
+        }
+        if stashed > 0 {
+            eprintln!(
+                "Stashed {stashed} changed file(s) from {} in {}",
+                name.as_str(),
+                stash_dir.display()
+            );
+        }
+        Ok(kept)
+    }
+
+    /// Replaces the live config with profile `name`, kept at `profile_path`,
+    /// without saving the live config anywhere first, journaled like a
+    /// regular switch.
+    fn apply_profile(
+        &self,
+        harness: &dyn HarnessConfig,
+        previous: Option<String>,
+        name: &ProfileName,
+        profile_path: &Path,
+    ) -> Result<()> {
+        let harness_id = harness.id();
+        let profile_path = profile_path.to_path_buf();
+        let target_dir = harness.config_dir()?;
         if !target_dir.exists() {
             std::fs::create_dir_all(&target_dir)?;
         }
 
-        let backup_dir = self.backups_dir().join(harness.id());
-        files::switch_config_dir_safely(&profile_path, &target_dir, &backup_dir)?;
+        let fragments = Fragments::load(&profile_path)?;
+        let rules = RulesSnippets::load(&profile_path)?;
+        let shared_skills = SharedSkills::for_profile(&profile_path)?;
+        let overrides = MachineOverrides::load(&profile_path)?;
+        let mut templates =
+            ProfileTemplates::scan(&profile_path, harness.mcp_filename().as_deref())?;
+        templates.skip(overrides.targets());
+        let vars = if templates.is_empty() {
+            Default::default()
+        } else {
+            templates.resolve(
+                &profile_path,
+                name.as_str(),
+                self.options.var_prompt.as_deref(),
+                self.options.secret_store(),
+            )?
+        };
+
+        let opts = self.copy_options_for(harness);
+        let strategy = self.switch_strategy_for(harness);
+        let known_paths = match strategy {
+            SwitchStrategy::Known => harness.known_paths(),
+            _ => Vec::new(),
+        };
+        let backup_path = files::new_backup_path(&self.backups_dir().join(harness.id()));
+        let mut journal = SwitchJournal::begin(
+            &StatePaths::resolve()?,
+            harness_id,
+            previous.clone(),
+            name.as_str(),
+            target_dir.clone(),
+            profile_path.clone(),
+            backup_path.clone(),
+            harness.mcp_config_path(),
+            opts.exclude.clone(),
+            strategy,
+            known_paths.clone(),
+        )?;
+        let protected = ProtectedValues::capture(harness)?;
+
+        let switched = files::switch_config_dir_with_backup(
+            &profile_path,
+            &target_dir,
+            &backup_path,
+            &opts,
+            strategy,
+            &known_paths,
+            |_| journal.advance(SwitchStep::BackedUp),
+        );
+        if let Err(e) = switched {
+            // The switch rolled itself back; nothing is left to recover.
+            let _ = journal.finish();
+            return Err(e);
+        }
+
+        Self::copy_mcp_from_profile(&profile_path, harness.mcp_config_path().as_deref())?;
+        if !overrides.is_empty() {
+            overrides.apply(&target_dir, harness.mcp_config_path().as_deref())?;
+        }
+        if !templates.is_empty() {
+            templates.render_into(&target_dir, harness.mcp_config_path().as_deref(), &vars)?;
+        }
+        if !rules.is_empty() {
+            match rules_file(harness_id) {
+                Some(file_name) => rules.apply(&target_dir, file_name)?,
+                None => eprintln!(
+                    "Warning: {harness_id} has no known rules file; {RULES_DIR}/ was not assembled"
+                ),
+            }
+        }
+        if !fragments.is_empty() {
+            fragments.apply(&target_dir, harness.mcp_config_path().as_deref())?;
+        }
+        if !protected.is_empty() {
+            protected.apply()?;
+        }
+        if let Some(shared_skills) = shared_skills {
+            shared_skills.mount(harness)?;
+        }
+        journal.advance(SwitchStep::Applied)?;
+
+        Self::record_active(harness_id, &target_dir, Some(name.as_str()))?;
+        journal.finish()?;
+        let _ = self.record_manifest(harness, name.as_str());
+
+        self.emit(ProfileEvent::Switched {
+            harness: harness_id.to_string(),
+            from: previous,
+            to: name.as_str().to_string(),
+        });
+        Ok(())
+    }
+
+    /// Applies profile `name`, calls `run`, then puts back the config that was
+    /// live before, leaving the active profile unchanged.
+    ///
+    /// Changes made to the live config while `run` executes are discarded
+    /// rather than saved into `name`. If nothing was active, the pre-switch
+    /// config is restored from the `no-profile` backup.
+    ///
+    /// # Errors
+    /// Returns an error if either the switch or the restore fails; `run`'s
+    /// output is lost in that case.
+    pub fn with_profile_applied<T>(
+        &self,
+        harness: &dyn HarnessConfig,
+        harness_for_resources: Option<&Harness>,
+        name: &ProfileName,
+        run: impl FnOnce() -> T,
+    ) -> Result<T> {
+        let harness_id = harness.id();
+        let previous = BridleConfig::load()?
+            .active_profile_for(harness_id)
+            .map(String::from);
+        if previous.as_deref() == Some(name.as_str()) {
+            return Ok(run());
+        }
+
+        self.switch_profile_with_resources(harness, harness_for_resources, name)?;
+        let output = run();
 
-        if let Some(mcp_path) = harness.mcp_config_path()
+        match previous.as_deref().map(ProfileName::new) {
+            Some(Ok(previous)) => {
+                let profile_path = self.profile_path(harness, &previous);
+                self.apply_profile(
+                    harness,
+                    Some(name.as_str().to_string()),
+                    &previous,
+                    &profile_path,
+                )?
+            }
+            _ => {
+                let target_dir = harness.config_dir()?;
+                let opts = self.copy_options_for(harness);
+                let no_profile = self.backups_dir().join(harness_id).join("no-profile");
+                if no_profile.exists() {
+                    files::switch_config_dir_safely(
+                        &no_profile,
+                        &target_dir,
+                        &self.backups_dir().join(harness_id),
+                        &opts,
+                    )?;
+                } else {
+                    // There was no live config before the switch.
+                    files::wipe_managed_entries(&target_dir, &opts)?;
+                }
+                Self::record_active(harness_id, &target_dir, None)?;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Copies the profile's MCP config to its live location outside the config dir.
+    fn copy_mcp_from_profile(
+        profile_path: &std::path::Path,
+        mcp_path: Option<&std::path::Path>,
+    ) -> Result<()> {
+        if let Some(mcp_path) = mcp_path
             && let Some(filename) = mcp_path.file_name()
         {
             let mcp_in_profile = profile_path.join(filename);
             if mcp_in_profile.exists() {
-                std::fs::copy(&mcp_in_profile, &mcp_path)?;
+                files::copy_file_preserving(&mcp_in_profile, mcp_path)?;
             }
         }
+        Ok(())
+    }
 
-        let _ = harness_for_resources;
-
-        let mut config = BridleConfig::load().unwrap_or_default();
-        config.set_active_profile(harness.id(), name.as_str());
+    /// Records `profile` as active for `harness_id` and refreshes the marker file.
+    fn record_active(
+        harness_id: &str,
+        config_dir: &std::path::Path,
+        profile: Option<&str>,
+    ) -> Result<()> {
+        let _guard = SHARED_STATE.lock().unwrap_or_else(|e| e.into_inner());
+        let mut config = BridleConfig::load()?;
+        match profile {
+            Some(profile) => config.set_active_profile(harness_id, profile),
+            None => config.clear_active_profile(harness_id),
+        }
         config.save()?;
 
-        Self::delete_marker_files(&target_dir)?;
-        if config.profile_marker_enabled() {
-            Self::create_marker_file(&target_dir, name.as_str())?;
+        Self::delete_marker_files(config_dir)?;
+        if let Some(profile) = profile
+            && config.profile_marker_enabled()
+        {
+            Self::create_marker_file(config_dir, profile)?;
         }
+        Ok(())
+    }
 
-        Ok(target_dir)
+    /// Switches interrupted before they finished, oldest harness id first.
+    ///
+    /// # Errors
+    /// Returns an error if the state directory cannot be read or a journal is corrupt.
+    pub fn pending_switches() -> Result<Vec<SwitchJournal>> {
+        SwitchJournal::pending(&StatePaths::resolve()?)
+    }
+
+    /// Settles an interrupted switch of `harness` by finishing it or
+    /// restoring the previous config.
+    ///
+    /// Completing puts the pre-switch config back and applies the profile
+    /// again the way a switch does, with machine overrides, templates, rules
+    /// snippets, fragments, protected values, and shared skills.
+    ///
+    /// # Errors
+    /// Returns [`Error::ProfileDirMissing`] when completing a switch whose
+    /// profile is gone, or an IO error if the live config cannot be rewritten.
+    pub fn recover(
+        &self,
+        harness: &dyn HarnessConfig,
+        journal: SwitchJournal,
+        action: RecoveryAction,
+    ) -> Result<()> {
+        match action {
+            RecoveryAction::Complete => {
+                if !journal.profile_path.exists() {
+                    return Err(Error::ProfileDirMissing(journal.profile_path.clone()));
+                }
+                let name = ProfileName::new(&journal.to)
+                    .map_err(|reason| Error::invalid_profile_name(&journal.to, reason))?;
+                if journal.step == SwitchStep::Applied {
+                    // Only the bookkeeping after applying was lost.
+                    Self::record_active(&journal.harness, &journal.config_dir, Some(&journal.to))?;
+                    let event = ProfileEvent::Switched {
+                        harness: journal.harness.clone(),
+                        from: journal.from.clone(),
+                        to: journal.to.clone(),
+                    };
+                    journal.finish()?;
+                    let _ = self.record_manifest(harness, name.as_str());
+                    self.emit(event);
+                    return Ok(());
+                }
+                self.restore_backup(&journal)?;
+                let from = journal.from.clone();
+                let profile_path = journal.profile_path.clone();
+                journal.finish()?;
+                self.apply_profile(harness, from, &name, &profile_path)
+            }
+            RecoveryAction::RollBack => {
+                self.restore_backup(&journal)?;
+                Self::record_active(
+                    &journal.harness,
+                    &journal.config_dir,
+                    journal.from.as_deref(),
+                )?;
+                journal.finish()
+            }
+        }
+    }
+
+    /// Puts back the live config an interrupted switch backed up. Before
+    /// [`SwitchStep::BackedUp`] the live config was never touched.
+    fn restore_backup(&self, journal: &SwitchJournal) -> Result<()> {
+        if journal.step < SwitchStep::BackedUp {
+            return Ok(());
+        }
+        let mut opts = self.options.copy.clone();
+        opts.exclude = journal.exclude.clone();
+        opts.ignore_case = platform::case_insensitive(&journal.config_dir);
+        std::fs::create_dir_all(&journal.config_dir)?;
+        files::wipe_managed_entries(&journal.config_dir, &opts)?;
+        if journal.backup_path.exists() {
+            files::copy_all_contents(
+                &journal.backup_path,
+                &journal.config_dir,
+                &Default::default(),
+            )?;
+        }
+        let mcp_backup = journal.mcp_backup_path();
+        if let Some(mcp_path) = &journal.mcp_path
+            && mcp_backup.is_file()
+        {
+            files::copy_file_preserving(&mcp_backup, mcp_path)?;
+        }
+        Ok(())
     }
 
     pub fn update_marker_file(
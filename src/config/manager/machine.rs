@@ -0,0 +1,289 @@
+//! Per-machine variants of profile files, applied on switch.
+//!
+//! A profile synced between machines can keep, next to any of its files, a
+//! variant for one machine named `<file>.machine-<name>`:
+//!
+//! ```text
+//! settings.json
+//! settings.json.machine-laptop
+//! settings.json.machine-desktop
+//! ```
+//!
+//! When the profile is applied on the machine named `laptop`, its variant
+//! replaces `settings.json` in the live config; variants never reach the
+//! live config themselves. A machine is named by the `machine` setting, or
+//! else by its host name up to the first dot, compared case-insensitively.
+//! Saving the live config back writes changes to an overridden file into
+//! this machine's variant, and leaves the shared file and the variants of
+//! other machines as they were. Variants are copied as they are; `{{ var }}`
+//! placeholders in them are not rendered.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::{BridleConfig, write_atomic};
+use crate::error::Result;
+
+/// Separates a file name from the machine its variant is for.
+pub const MACHINE_INFIX: &str = ".machine-";
+
+/// The host name as the system reports it: `gethostname(2)`, else the
+/// `hostname` command, else `COMPUTERNAME` on Windows.
+pub(crate) fn host_name() -> Option<String> {
+    system_host_name()
+        .or_else(|| {
+            std::process::Command::new("hostname")
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+        })
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(unix)]
+fn system_host_name() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: `buf` is valid for writes of `buf.len()` bytes, which is all
+    // gethostname may write.
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    // A name that fills the buffer may come without its terminating NUL.
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+#[cfg(not(unix))]
+fn system_host_name() -> Option<String> {
+    None
+}
+
+/// This machine's name for picking variants: the `machine` setting, or else
+/// the host name up to its first dot. Lowercase.
+pub fn machine_name() -> Option<String> {
+    let configured = BridleConfig::load()
+        .ok()
+        .and_then(|config| config.settings().machine.clone());
+    configured
+        .or_else(|| host_name().map(|host| host.split('.').next().unwrap_or_default().to_string()))
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+}
+
+/// One `<file>.machine-<name>` file in a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Variant {
+    /// Path of the variant, relative to the profile.
+    path: PathBuf,
+    /// Path of the file it stands in for.
+    target: PathBuf,
+    machine: String,
+    content: Vec<u8>,
+}
+
+/// The machine variants in a profile, and which of them apply here.
+#[derive(Debug, Default)]
+pub struct MachineOverrides {
+    machine: Option<String>,
+    variants: Vec<Variant>,
+    /// The profile's own copy of each file this machine's variants stand in
+    /// for; `None` where it has none.
+    shadowed: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl MachineOverrides {
+    /// Reads the variants in `profile_path` for this machine.
+    pub fn load(profile_path: &Path) -> Result<Self> {
+        Self::load_for(profile_path, machine_name().as_deref())
+    }
+
+    /// Reads the variants in `profile_path` for the machine named `machine`.
+    fn load_for(profile_path: &Path, machine: Option<&str>) -> Result<Self> {
+        let machine = machine.map(str::to_lowercase);
+        let mut variants = Vec::new();
+        let mut pending = vec![PathBuf::new()];
+        while let Some(rel) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(profile_path.join(&rel)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = rel.join(entry.file_name());
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let Some((file, for_machine)) = name.rsplit_once(MACHINE_INFIX) else {
+                    continue;
+                };
+                if !file_type.is_file() || file.is_empty() || for_machine.is_empty() {
+                    continue;
+                }
+                variants.push(Variant {
+                    target: rel.join(file),
+                    machine: for_machine.to_lowercase(),
+                    content: std::fs::read(entry.path())?,
+                    path,
+                });
+            }
+        }
+        variants.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut overrides = Self {
+            machine,
+            variants,
+            shadowed: Vec::new(),
+        };
+        overrides.shadowed = overrides
+            .ours()
+            .map(|v| {
+                (
+                    v.target.clone(),
+                    std::fs::read(profile_path.join(&v.target)).ok(),
+                )
+            })
+            .collect();
+        Ok(overrides)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// This machine's variants.
+    fn ours(&self) -> impl Iterator<Item = &Variant> {
+        self.variants
+            .iter()
+            .filter(|v| self.machine.as_deref() == Some(v.machine.as_str()))
+    }
+
+    /// Profile files replaced by this machine's variants.
+    pub fn targets(&self) -> impl Iterator<Item = &Path> {
+        self.ours().map(|v| v.target.as_path())
+    }
+
+    /// This machine's variant of `target`, relative to the profile, if it has one.
+    pub(super) fn variant_of(&self, target: &Path) -> Option<&Path> {
+        self.ours()
+            .find(|v| v.target == target)
+            .map(|v| v.path.as_path())
+    }
+
+    /// Drops the variants copied into `live_dir` and writes this machine's
+    /// over the files they stand in for, and over `mcp_path` when it is the
+    /// profile's top-level MCP file kept outside `live_dir`.
+    pub(super) fn apply(&self, live_dir: &Path, mcp_path: Option<&Path>) -> Result<()> {
+        for variant in &self.variants {
+            let copied = live_dir.join(&variant.path);
+            if copied.is_file() {
+                std::fs::remove_file(copied)?;
+            }
+        }
+        for variant in self.ours() {
+            write_atomic(&live_dir.join(&variant.target), &variant.content)?;
+            if let Some(mcp_path) = mcp_path
+                && mcp_path
+                    .file_name()
+                    .is_some_and(|n| Path::new(n) == variant.target)
+                && !mcp_path.starts_with(live_dir)
+            {
+                write_atomic(mcp_path, &variant.content)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// After the live config was saved over `profile_path`, moves the files
+    /// this machine overrides into its variants, and puts back the shared
+    /// files and every other variant.
+    pub(super) fn restore(&self, profile_path: &Path) -> Result<()> {
+        for variant in &self.variants {
+            let saved = (self.machine.as_deref() == Some(variant.machine.as_str()))
+                .then(|| std::fs::read(profile_path.join(&variant.target)).ok())
+                .flatten();
+            write_atomic(
+                &profile_path.join(&variant.path),
+                saved.as_deref().unwrap_or(&variant.content),
+            )?;
+        }
+        for (target, original) in &self.shadowed {
+            let path = profile_path.join(target);
+            match original {
+                Some(content) => write_atomic(&path, content)?,
+                None if path.is_file() => std::fs::remove_file(path)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn host_name_comes_from_the_kernel() {
+        let kernel = fs::read_to_string("/proc/sys/kernel/hostname").unwrap();
+        assert_eq!(system_host_name().as_deref(), Some(kernel.trim()));
+        assert_eq!(host_name().as_deref(), Some(kernel.trim()));
+    }
+
+    #[test]
+    fn applies_this_machines_variants_and_saves_back_into_them() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let profile = temp.path().join("profile");
+        let live = temp.path().join("live");
+        fs::create_dir_all(profile.join("themes")).unwrap();
+        fs::write(profile.join("settings.json"), "shared").unwrap();
+        fs::write(profile.join("settings.json.machine-Laptop"), "laptop").unwrap();
+        fs::write(profile.join("settings.json.machine-desktop"), "desktop").unwrap();
+        fs::write(profile.join("themes/dark.json.machine-laptop"), "dark").unwrap();
+        fs::create_dir_all(live.join("themes")).unwrap();
+        fs::write(live.join("settings.json"), "shared").unwrap();
+        fs::write(live.join("settings.json.machine-desktop"), "desktop").unwrap();
+
+        let overrides = MachineOverrides::load_for(&profile, Some("laptop")).unwrap();
+        assert_eq!(
+            overrides.targets().collect::<Vec<_>>(),
+            [Path::new("settings.json"), Path::new("themes/dark.json")]
+        );
+        overrides.apply(&live, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(live.join("settings.json")).unwrap(),
+            "laptop"
+        );
+        assert_eq!(
+            fs::read_to_string(live.join("themes/dark.json")).unwrap(),
+            "dark"
+        );
+        assert!(!live.join("settings.json.machine-desktop").exists());
+
+        // A save replaces the profile with the live config, edits included.
+        fs::remove_dir_all(&profile).unwrap();
+        fs::create_dir_all(profile.join("themes")).unwrap();
+        fs::write(profile.join("settings.json"), "laptop, edited").unwrap();
+        fs::write(profile.join("themes/dark.json"), "dark").unwrap();
+        overrides.restore(&profile).unwrap();
+        let read = |rel: &str| fs::read_to_string(profile.join(rel)).unwrap();
+        assert_eq!(read("settings.json"), "shared");
+        assert_eq!(read("settings.json.machine-Laptop"), "laptop, edited");
+        assert_eq!(read("settings.json.machine-desktop"), "desktop");
+        assert!(!profile.join("themes/dark.json").exists());
+    }
+
+    #[test]
+    fn other_machines_keep_the_shared_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("AGENTS.md.machine-laptop"), "laptop").unwrap();
+        let overrides = MachineOverrides::load_for(temp.path(), Some("desktop")).unwrap();
+        assert!(!overrides.is_empty());
+        assert_eq!(overrides.targets().count(), 0);
+        let overrides = MachineOverrides::load_for(temp.path(), None).unwrap();
+        assert_eq!(overrides.targets().count(), 0);
+    }
+}
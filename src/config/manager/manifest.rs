@@ -0,0 +1,365 @@
+//! Hash manifests for detecting changes to the live config.
+//!
+//! Whenever a profile is switched in or saved, the live config matches the
+//! profile and its files are recorded with size, mtime, and a SHA-256 hash.
+//! Drift checks rehash only files whose size or mtime differ from that
+//! record, so `bridle status` stays fast on profiles with many files.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::files::CopyOptions;
+use super::platform;
+use super::{MARKER_PREFIX, ProfileManager};
+use crate::config::profile_name::ProfileName;
+use crate::config::{BridleConfig, write_atomic};
+use crate::error::Result;
+use crate::harness::HarnessConfig;
+
+/// Size, mtime, and content hash of one file in the live config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileRecord {
+    size: u64,
+    /// Nanoseconds since the Unix epoch.
+    mtime: u128,
+    hash: String,
+}
+
+/// Files under a config directory, keyed by `/`-separated relative path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    files: BTreeMap<String, FileRecord>,
+}
+
+/// Changes to the live config since its profile was last switched in or saved.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Drift {
+    pub modified: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// How one path in the live config changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftChange {
+    Modified,
+    Added,
+    Removed,
+}
+
+impl Drift {
+    /// Every changed path with how it changed.
+    pub fn changes(&self) -> impl Iterator<Item = (&str, DriftChange)> {
+        fn tag(paths: &[String], change: DriftChange) -> impl Iterator<Item = (&str, DriftChange)> {
+            paths.iter().map(move |p| (p.as_str(), change))
+        }
+        tag(&self.modified, DriftChange::Modified)
+            .chain(tag(&self.added, DriftChange::Added))
+            .chain(tag(&self.removed, DriftChange::Removed))
+    }
+
+    /// Whether the live config still matches the profile.
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Number of changed paths.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.modified.len() + self.added.len() + self.removed.len()
+    }
+}
+
+impl Manifest {
+    /// Reads a manifest. Returns `None` if it is missing or unreadable; it is
+    /// only a cache.
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, serde_json::to_string(self)?)
+    }
+
+    /// Records the files under `dir` that a copy with `opts` would carry.
+    ///
+    /// Files whose size and mtime match `cache` reuse its hash instead of
+    /// being read.
+    pub fn scan(dir: &Path, opts: &CopyOptions, cache: &Manifest) -> Result<Self> {
+        let mut manifest = Self::default();
+        if dir.exists() {
+            manifest.scan_dir(dir, "", opts, cache)?;
+        }
+        Ok(manifest)
+    }
+
+    fn scan_dir(
+        &mut self,
+        dir: &Path,
+        prefix: &str,
+        opts: &CopyOptions,
+        cache: &Self,
+    ) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if opts.is_excluded(&name)
+                || (prefix.is_empty()
+                    && name
+                        .as_encoded_bytes()
+                        .starts_with(MARKER_PREFIX.as_bytes()))
+            {
+                continue;
+            }
+            let key = format!("{prefix}{}", name.to_string_lossy());
+            let path = entry.path();
+            let meta = std::fs::symlink_metadata(&path)?;
+            if meta.is_dir() {
+                self.scan_dir(&path, &format!("{key}/"), opts, cache)?;
+                continue;
+            }
+            if meta.is_file() && opts.skips_large(&path) {
+                continue;
+            }
+
+            let size = meta.len();
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or_default();
+            let hash = match cache.files.get(&key) {
+                Some(r) if r.size == size && r.mtime == mtime => r.hash.clone(),
+                _ if meta.is_symlink() => hash_link(&path)?,
+                _ => hash_file(&path)?,
+            };
+            self.files.insert(key, FileRecord { size, mtime, hash });
+        }
+        Ok(())
+    }
+
+    /// Compares `current` against this baseline by content. With
+    /// `ignore_case`, paths differing only in ASCII case are the same file,
+    /// as on a case-insensitive filesystem.
+    pub fn diff(&self, current: &Manifest, ignore_case: bool) -> Drift {
+        let fold = |key: &str| {
+            if ignore_case {
+                key.to_ascii_lowercase()
+            } else {
+                key.to_string()
+            }
+        };
+        let base: BTreeMap<String, &FileRecord> =
+            self.files.iter().map(|(k, r)| (fold(k), r)).collect();
+        let live: BTreeMap<String, &FileRecord> =
+            current.files.iter().map(|(k, r)| (fold(k), r)).collect();
+
+        let mut drift = Drift::default();
+        for (key, record) in &current.files {
+            match base.get(&fold(key)) {
+                Some(base) if base.hash == record.hash => {}
+                Some(_) => drift.modified.push(key.clone()),
+                None => drift.added.push(key.clone()),
+            }
+        }
+        drift.removed = self
+            .files
+            .keys()
+            .filter(|key| !live.contains_key(&fold(key)))
+            .cloned()
+            .collect();
+        drift
+    }
+
+    /// Takes the size and mtime of files in `current` whose content is
+    /// unchanged, so they are not rehashed next time. Returns whether
+    /// anything was updated.
+    fn refresh_stats(&mut self, current: &Manifest) -> bool {
+        let mut changed = false;
+        for (key, base) in &mut self.files {
+            if let Some(record) = current.files.get(key)
+                && record.hash == base.hash
+                && record != base
+            {
+                *base = record.clone();
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Symlinks are compared by target, matching how they are copied by default.
+fn hash_link(path: &Path) -> Result<String> {
+    let target = std::fs::read_link(path)?;
+    Ok(format!(
+        "link:{}",
+        hex(&Sha256::digest(target.as_os_str().as_encoded_bytes()))
+    ))
+}
+
+pub(super) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+impl ProfileManager {
+    /// Records the live config as the baseline for `name`'s drift checks.
+    ///
+    /// Reuses hashes from the previous manifest for files whose size and
+    /// mtime are unchanged, which covers files copied back from the profile.
+    pub(super) fn record_manifest(&self, harness: &dyn HarnessConfig, name: &str) -> Result<()> {
+        let path = BridleConfig::load()?
+            .state_paths()?
+            .manifest_file(harness.id(), name);
+        let cache = Manifest::load(&path).unwrap_or_default();
+        let manifest = Manifest::scan(
+            &harness.config_dir()?,
+            &self.copy_options_for(harness),
+            &cache,
+        )?;
+        manifest.save(&path)
+    }
+
+    /// Changes to the live config since the active profile was switched in or saved.
+    ///
+    /// Returns `Ok(None)` when no profile is active or no baseline has been
+    /// recorded for it yet.
+    ///
+    /// # Errors
+    /// Returns an error if the config or the live config directory cannot be read.
+    pub fn drift(&self, harness: &dyn HarnessConfig) -> Result<Option<Drift>> {
+        let config = BridleConfig::load()?;
+        let Some(active) = config.active_profile_for(harness.id()) else {
+            return Ok(None);
+        };
+        if ProfileName::new(active).is_err() {
+            return Ok(None);
+        }
+        let path = config.state_paths()?.manifest_file(harness.id(), active);
+        let Some(mut baseline) = Manifest::load(&path) else {
+            return Ok(None);
+        };
+
+        let current = Manifest::scan(
+            &harness.config_dir()?,
+            &self.copy_options_for(harness),
+            &baseline,
+        )?;
+        let drift = baseline.diff(&current, platform::case_insensitive(&harness.config_dir()?));
+        if baseline.refresh_stats(&current) {
+            let _ = baseline.save(&path);
+        }
+        Ok(Some(drift))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn diff_reports_modified_added_and_removed() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("keep.json"), "1").unwrap();
+        std::fs::write(dir.join("edit.json"), "1").unwrap();
+        std::fs::write(dir.join("gone.json"), "1").unwrap();
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested/a.md"), "a").unwrap();
+        let opts = CopyOptions::default();
+        let baseline = Manifest::scan(dir, &opts, &Manifest::default()).unwrap();
+
+        std::fs::write(dir.join("edit.json"), "22").unwrap();
+        std::fs::remove_file(dir.join("gone.json")).unwrap();
+        std::fs::write(dir.join("nested/b.md"), "b").unwrap();
+        let current = Manifest::scan(dir, &opts, &baseline).unwrap();
+
+        let drift = baseline.diff(&current, false);
+        assert_eq!(drift.modified, vec!["edit.json"]);
+        assert_eq!(drift.added, vec!["nested/b.md"]);
+        assert_eq!(drift.removed, vec!["gone.json"]);
+    }
+
+    #[test]
+    fn diff_folds_case_on_case_insensitive_filesystems() {
+        let record = |hash: &str| FileRecord {
+            size: 1,
+            mtime: 0,
+            hash: hash.to_string(),
+        };
+        let baseline = Manifest {
+            files: BTreeMap::from([
+                ("CLAUDE.md".to_string(), record("a")),
+                ("Skills/x.md".to_string(), record("b")),
+            ]),
+        };
+        let current = Manifest {
+            files: BTreeMap::from([
+                ("claude.md".to_string(), record("a")),
+                ("skills/x.md".to_string(), record("c")),
+            ]),
+        };
+
+        let folded = baseline.diff(&current, true);
+        assert_eq!(folded.modified, vec!["skills/x.md"]);
+        assert!(folded.added.is_empty() && folded.removed.is_empty());
+        assert_eq!(baseline.diff(&current, false).len(), 4);
+    }
+
+    #[test]
+    fn scan_reuses_hashes_of_unchanged_files() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("big.json"), "real").unwrap();
+        let opts = CopyOptions::default();
+        let mut cache = Manifest::scan(temp.path(), &opts, &Manifest::default()).unwrap();
+        cache.files.get_mut("big.json").unwrap().hash = "cached".to_string();
+
+        let rescanned = Manifest::scan(temp.path(), &opts, &cache).unwrap();
+        assert_eq!(rescanned.files["big.json"].hash, "cached");
+    }
+
+    #[test]
+    fn touched_files_are_not_drift() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("settings.json");
+        std::fs::write(&file, "{}").unwrap();
+        let opts = CopyOptions::default();
+        let mut baseline = Manifest::scan(temp.path(), &opts, &Manifest::default()).unwrap();
+
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let current = Manifest::scan(temp.path(), &opts, &baseline).unwrap();
+
+        assert!(baseline.diff(&current, false).is_clean());
+        assert!(baseline.refresh_stats(&current));
+        assert_eq!(baseline, current);
+    }
+}
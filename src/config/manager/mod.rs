@@ -5,17 +5,46 @@
 
 mod extraction;
 mod files;
+mod fragments;
+mod import;
+mod info_cache;
+mod journal;
 mod lifecycle;
-
-use std::path::PathBuf;
+mod machine;
+mod manifest;
+mod options;
+mod platform;
+mod protected;
+pub mod rules;
+mod shared_skills;
+mod snapshots;
+pub mod template;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use harness_locate::{Harness, InstallationStatus};
 
-use super::BridleConfig;
-use super::profile_name::ProfileName;
-use super::types::ProfileInfo;
+use super::profile_name::{InvalidProfileName, ProfileName};
+use super::types::{ExtractionCategory, ExtractionIssue, ProfileInfo, ResourceSummary};
+use super::{BridleConfig, PersistedState};
 use crate::error::{Error, Result};
 use crate::harness::HarnessConfig;
+use crate::install::filter::{glob_match, is_glob};
+use options::ManagerOptions;
+
+pub use extraction::ExtractionLimits;
+pub use fragments::{PROFILE_FILE, ProfileSettings, SHARED_DIR};
+pub use import::LiveEntry;
+pub use journal::{RecoveryAction, SwitchJournal, SwitchStep};
+pub(crate) use machine::host_name;
+pub use machine::{MACHINE_INFIX, MachineOverrides, machine_name};
+pub use manifest::{Drift, DriftChange};
+pub use options::{
+    CopyStrategy, DriftAction, DriftPrompt, LargeFilePolicy, ProfileEvent, ProfileManagerBuilder,
+    ProfileObserver, SnapshotPolicy, SwitchStrategy, SymlinkPolicy,
+};
+pub use snapshots::{RestoredFile, SNAPSHOT_FILE, Snapshot};
 
 /// Manages harness configuration profiles.
 ///
@@ -35,17 +64,82 @@ use crate::harness::HarnessConfig;
 /// └── goose/
 ///     └── default/
 /// ```
+///
+/// Behavior such as copy strategy, exclude patterns, snapshot retention, and
+/// dry-run mode is configured through [`ProfileManager::builder`].
 #[derive(Debug)]
 pub struct ProfileManager {
     profiles_dir: PathBuf,
+    options: ManagerOptions,
 }
 
 const MARKER_PREFIX: &str = "BRIDLE_PROFILE_";
+/// Documentation shipped with a profile, shown by `bridle profile show`.
+const README_FILENAME: &str = "README.md";
 
 impl ProfileManager {
     /// Creates a new profile manager with the given profiles directory.
     pub fn new(profiles_dir: PathBuf) -> Self {
-        Self { profiles_dir }
+        Self {
+            profiles_dir,
+            options: ManagerOptions::default(),
+        }
+    }
+
+    /// Returns a builder for configuring manager behavior.
+    pub fn builder() -> ProfileManagerBuilder {
+        ProfileManagerBuilder::default()
+    }
+
+    /// Whether mutating operations only report what they would do.
+    pub fn is_dry_run(&self) -> bool {
+        self.options.dry_run
+    }
+
+    /// The manager's copy options plus any excludes specific to `harness`.
+    fn copy_options_for(&self, harness: &dyn HarnessConfig) -> files::CopyOptions {
+        let mut opts = self.options.copy.clone();
+        opts.exclude.extend(harness.exclude_patterns());
+        opts.exclude.push(template::VARS_FILE.to_string());
+        opts.exclude.push(fragments::PROFILE_FILE.to_string());
+        opts.exclude.push(rules::RULES_DIR.to_string());
+        opts.ignore_case = harness
+            .config_dir()
+            .is_ok_and(|dir| platform::case_insensitive(&dir));
+        opts
+    }
+
+    /// The switch strategy for `harness`: a forced one, else the harness's
+    /// own, else the manager's default.
+    fn switch_strategy_for(&self, harness: &dyn HarnessConfig) -> SwitchStrategy {
+        self.options
+            .forced_strategy
+            .or_else(|| harness.switch_strategy())
+            .unwrap_or(self.options.switch_strategy)
+    }
+
+    /// Warns when the files a copy from `dir` would carry exceed `max_profile_size`.
+    fn warn_if_profile_too_large(&self, harness: &dyn HarnessConfig, dir: &std::path::Path) {
+        let limit = self.options.max_profile_size;
+        if limit == 0 {
+            return;
+        }
+        let size = files::copied_size(dir, &self.copy_options_for(harness));
+        if size > limit {
+            eprintln!(
+                "Warning: {} config is {}, over the {} max_profile_size. \
+                 Consider excluding caches with `bridle config set exclude`.",
+                harness.id(),
+                super::format_size(size),
+                super::format_size(limit)
+            );
+        }
+    }
+
+    fn emit(&self, event: ProfileEvent) {
+        if let Some(observer) = &self.options.observer {
+            observer.on_event(&event);
+        }
     }
 
     fn delete_marker_files(dir: &std::path::Path) -> Result<()> {
@@ -54,11 +148,11 @@ impl ProfileManager {
         }
         for entry in std::fs::read_dir(dir)? {
             let entry = entry?;
-            let dominated_name = entry.file_name();
-            let Some(name) = dominated_name.to_str() else {
-                continue;
-            };
-            if name.starts_with(MARKER_PREFIX) && entry.file_type()?.is_file() {
+            let is_marker = entry
+                .file_name()
+                .as_encoded_bytes()
+                .starts_with(MARKER_PREFIX.as_bytes());
+            if is_marker && entry.file_type()?.is_file() {
                 std::fs::remove_file(entry.path())?;
             }
         }
@@ -112,10 +206,41 @@ impl ProfileManager {
         Ok(profiles)
     }
 
+    /// Profiles of `harness` named by `pattern`: the profile of that name, or
+    /// every existing profile matching it as a glob (`*`, `?`), sorted.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidProfileName`] if `pattern` is neither a glob
+    /// nor a valid name, and [`Error::ProfileNotFound`] if a glob matches
+    /// nothing.
+    pub fn resolve_profiles(
+        &self,
+        harness: &dyn HarnessConfig,
+        pattern: &str,
+    ) -> Result<Vec<ProfileName>> {
+        if !is_glob(pattern) {
+            let name = ProfileName::new(pattern)
+                .map_err(|reason| Error::invalid_profile_name(pattern, reason))?;
+            return Ok(vec![name]);
+        }
+        let pattern_lower = pattern.to_lowercase();
+        let matched: Vec<ProfileName> = self
+            .list_profiles(harness)?
+            .into_iter()
+            .filter(|name| glob_match(&pattern_lower, name.as_str()))
+            .collect();
+        if matched.is_empty() {
+            return Err(Error::profile_not_found(harness.id(), pattern));
+        }
+        Ok(matched)
+    }
+
     /// Creates an empty profile directory.
     ///
     /// # Errors
-    /// Returns [`Error::ProfileExists`] if profile already exists, or IO error on failure.
+    /// Returns [`Error::ProfileExists`] if profile already exists,
+    /// [`Error::InvalidProfileName`] if another profile directory differs from
+    /// `name` only in case, or IO error on failure.
     pub fn create_profile(
         &self,
         harness: &dyn HarnessConfig,
@@ -123,11 +248,32 @@ impl ProfileManager {
     ) -> Result<PathBuf> {
         let path = self.profile_path(harness, name);
 
+        // Checked before `exists`, which a case-insensitive filesystem would
+        // answer for the other directory.
+        if let Ok(entries) = std::fs::read_dir(self.profiles_dir.join(harness.id()))
+            && let Some(existing) = entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .find(|existing| name.collides_with(existing))
+        {
+            return Err(Error::invalid_profile_name(
+                name.as_str(),
+                InvalidProfileName::CaseCollision(existing),
+            ));
+        }
+
         if path.exists() {
-            return Err(Error::ProfileExists(name.as_str().to_string()));
+            return Err(Error::profile_exists(harness.id(), name.as_str()));
         }
 
-        std::fs::create_dir_all(&path)?;
+        if !self.options.dry_run {
+            std::fs::create_dir_all(&path)?;
+        }
+        self.emit(ProfileEvent::Created {
+            harness: harness.id().to_string(),
+            profile: name.as_str().to_string(),
+            path: path.clone(),
+        });
         Ok(path)
     }
 
@@ -154,16 +300,28 @@ impl ProfileManager {
         name: &ProfileName,
     ) -> Result<PathBuf> {
         let profile_path = self.create_profile(harness, name)?;
-        files::copy_config_files(harness, true, &profile_path)?;
-        if let Some(h) = harness_for_resources {
-            files::copy_resource_directories(h, true, &profile_path)?;
+        if self.options.dry_run {
+            return Ok(profile_path);
         }
 
-        if let Ok(mut config) = BridleConfig::load() {
-            config.set_active_profile(harness.id(), name.as_str());
-            let _ = config.save();
+        let opts = self.copy_options_for(harness);
+        self.warn_if_profile_too_large(harness, &harness.config_dir()?);
+        if self.switch_strategy_for(harness) == SwitchStrategy::Known {
+            let known_paths = harness.known_paths();
+            files::copy_known_entries(&harness.config_dir()?, &profile_path, &known_paths, &opts)?;
+            files::copy_mcp_config_to_profile(harness, &profile_path)?;
+        } else {
+            files::copy_config_files(harness, true, &profile_path, &opts)?;
+        }
+        if let Some(h) = harness_for_resources {
+            files::copy_resource_directories(h, true, &profile_path, &opts)?;
         }
 
+        let mut config = BridleConfig::load()?;
+        config.set_active_profile(harness.id(), name.as_str());
+        config.save()?;
+        let _ = self.record_manifest(harness, name.as_str());
+
         Ok(profile_path)
     }
 
@@ -196,13 +354,71 @@ impl ProfileManager {
         let path = self.profile_path(harness, name);
 
         if !path.exists() {
-            return Err(Error::ProfileNotFound(name.as_str().to_string()));
+            return Err(Error::profile_not_found(harness.id(), name.as_str()));
         }
 
-        std::fs::remove_dir_all(&path)?;
+        if !self.options.dry_run {
+            std::fs::remove_dir_all(&path)?;
+            if let Ok(paths) = BridleConfig::load().and_then(|c| c.state_paths()) {
+                let _ = PersistedState::forget_profile(&paths, harness.id(), name.as_str());
+                let _ = std::fs::remove_file(paths.extraction_file(harness.id(), name.as_str()));
+            }
+        }
+        self.emit(ProfileEvent::Deleted {
+            harness: harness.id().to_string(),
+            profile: name.as_str().to_string(),
+        });
         Ok(())
     }
 
+    /// Removes what bridle keeps about `harness`: its active profile, the
+    /// profile marker in its live config, recorded usage and manifests, and
+    /// with `purge_profiles` all of its profiles. The live config stays.
+    ///
+    /// Returns the profiles deleted.
+    ///
+    /// # Errors
+    /// Returns an error if the state or a profile cannot be removed.
+    pub fn forget_harness(
+        &self,
+        harness: &dyn HarnessConfig,
+        purge_profiles: bool,
+    ) -> Result<Vec<ProfileName>> {
+        let profiles = if purge_profiles {
+            self.list_profiles(harness)?
+        } else {
+            Vec::new()
+        };
+        if self.options.dry_run {
+            return Ok(profiles);
+        }
+        for name in &profiles {
+            self.delete_profile(harness, name)?;
+        }
+        let harness_dir = self.profiles_dir.join(harness.id());
+        if purge_profiles && harness_dir.exists() {
+            std::fs::remove_dir_all(&harness_dir)?;
+        }
+        if let Ok(dir) = harness.config_dir() {
+            Self::delete_marker_files(&dir)?;
+        }
+
+        let mut config = BridleConfig::load()?;
+        config.clear_active_profile(harness.id());
+        config.save()?;
+        let paths = config.state_paths()?;
+        PersistedState::forget_harness(&paths, harness.id())?;
+        for cache in [
+            paths.manifests_dir(harness.id()),
+            paths.extraction_dir(harness.id()),
+        ] {
+            if cache.exists() {
+                std::fs::remove_dir_all(cache)?;
+            }
+        }
+        Ok(profiles)
+    }
+
     /// Extracts and returns detailed information about a profile.
     ///
     /// When a profile is active, reads from the live harness config directory
@@ -210,11 +426,15 @@ impl ProfileManager {
     ///
     /// # Errors
     /// Returns [`Error::ProfileNotFound`] if profile doesn't exist.
-    pub fn show_profile(&self, harness: &Harness, name: &ProfileName) -> Result<ProfileInfo> {
+    pub fn show_profile(
+        &self,
+        harness: &dyn HarnessConfig,
+        name: &ProfileName,
+    ) -> Result<ProfileInfo> {
         let profile_path = self.profile_path(harness, name);
 
         if !profile_path.exists() {
-            return Err(Error::ProfileNotFound(name.as_str().to_string()));
+            return Err(Error::profile_not_found(harness.id(), name.as_str()));
         }
 
         let harness_id = harness.id().to_string();
@@ -228,154 +448,245 @@ impl ProfileManager {
         } else {
             profile_path.clone()
         };
+        let mut info = self.cached_info(harness, &extraction_path, name.as_str());
+        info.name = name.as_str().to_string();
+        info.is_active = is_active;
+        info.readme = std::fs::read_to_string(profile_path.join(README_FILENAME)).ok();
+        info.path = profile_path;
+        Ok(info)
+    }
 
-        let theme = extraction::extract_theme(harness, &extraction_path);
-        let model = extraction::extract_model(harness, &extraction_path);
+    /// Each variable and secret reference in the MCP config of profile
+    /// `name`, with where its value comes from on switch. Nothing is
+    /// prompted for.
+    pub fn mcp_references(
+        &self,
+        harness: &dyn HarnessConfig,
+        name: &ProfileName,
+    ) -> Result<Vec<template::Reference>> {
+        let profile_path = self.profile_path(harness, name);
+        if !profile_path.exists() {
+            return Err(Error::profile_not_found(harness.id(), name.as_str()));
+        }
+        template::ProfileTemplates::scan(&profile_path, harness.mcp_filename().as_deref())?
+            .check_mcp(&profile_path, self.options.secret_store())
+    }
+
+    /// Extracts the harness's live config the way [`show_profile`](Self::show_profile)
+    /// extracts a profile, whether or not bridle manages it. Nothing is written.
+    ///
+    /// The result is named after the active profile, if there is one.
+    ///
+    /// # Errors
+    /// Returns [`Error::NoConfigFound`] if the live config directory does not exist.
+    pub fn show_live(&self, harness: &dyn HarnessConfig) -> Result<ProfileInfo> {
+        let config_dir = harness.config_dir()?;
+        if !config_dir.is_dir() {
+            return Err(Error::NoConfigFound {
+                harness: harness.id().to_string(),
+            });
+        }
+        let mut info = Self::extract_info(harness, &config_dir, &self.options.extraction);
+        if let Some(active) = BridleConfig::load()?.active_profile_for(harness.id()) {
+            info.name = active.to_string();
+            info.is_active = true;
+        }
+        info.path = config_dir;
+        Ok(info)
+    }
+
+    /// [`extract_info`](Self::extract_info) for profile `name`, reusing the
+    /// result cached under the state directory while nothing it was read
+    /// from has changed.
+    fn cached_info(
+        &self,
+        harness: &dyn HarnessConfig,
+        extraction_path: &Path,
+        name: &str,
+    ) -> ProfileInfo {
+        let limits = &self.options.extraction;
+        let Ok(paths) = BridleConfig::load().and_then(|c| c.state_paths()) else {
+            return Self::extract_info(harness, extraction_path, limits);
+        };
+        let file = paths.extraction_file(harness.id(), name);
+        let stamp = info_cache::stamp(harness, extraction_path, limits);
+        if let Some(info) = info_cache::load(&file, &stamp) {
+            return info;
+        }
+        let info = Self::extract_info(harness, extraction_path, limits);
+        if !self.options.dry_run {
+            let _ = info_cache::save(&file, &stamp, &info);
+        }
+        info
+    }
+
+    /// Everything extraction finds in `extraction_path`, with the profile's
+    /// name, status, path, and README left for the caller.
+    fn extract_info(
+        harness: &dyn HarnessConfig,
+        extraction_path: &Path,
+        limits: &ExtractionLimits,
+    ) -> ProfileInfo {
+        let mut theme = extraction::extract_theme(harness, extraction_path);
+        let mut model = extraction::extract_model(harness, extraction_path);
 
         let mut extraction_errors = Vec::new();
 
-        let mcp_servers = match extraction::extract_mcp_servers(harness, &extraction_path) {
-            Ok(servers) => servers,
+        // Claude Code applies project and managed settings on top of the
+        // profile's, so report what it will actually use.
+        let mut merged = extraction::MergedSettings::default();
+        if harness.id() == "claude-code" {
+            let project = std::env::current_dir().ok();
+            merged = extraction::merge_settings(
+                extraction_path,
+                project.as_deref(),
+                &extraction::managed_settings_dir(),
+            );
+            let effective = |key: &str| merged.values.get(key)?.as_str().map(String::from);
+            model = effective("model").or(model);
+            theme = effective("theme").or(theme);
+            extraction_errors.append(&mut merged.errors);
+        }
+
+        let mcp_servers = match extraction::extract_mcp_servers(harness, extraction_path) {
+            Ok((servers, issues)) => {
+                extraction_errors.extend(issues);
+                servers
+            }
             Err(e) => {
-                extraction_errors.push(format!("MCP config: {}", e));
+                extraction_errors.push(ExtractionIssue::from_error(ExtractionCategory::Mcp, &e));
                 Vec::new()
             }
         };
 
-        let (skills, err) = extraction::extract_skills(harness, &extraction_path);
-        if let Some(e) = err {
-            extraction_errors.push(e);
-        }
+        let mut rules_file = None;
 
-        let (commands, err) = extraction::extract_commands(harness, &extraction_path);
-        if let Some(e) = err {
-            extraction_errors.push(e);
-        }
+        let layout = harness.resource_layout();
+        let (skills, err) = extraction::extract_skills(harness, &layout, extraction_path, limits);
+        extraction_errors.extend(err);
+        let skill_files = extraction::read_skill_files(&layout, extraction_path, &skills.items);
+        let skill_descriptions = extraction::extract_skill_descriptions(&skill_files);
+        let skill_tokens = skill_files
+            .iter()
+            .map(|(name, content)| (name.clone(), super::estimate_tokens(content)))
+            .collect();
 
-        let (plugins, err) = extraction::extract_plugins(harness, &extraction_path);
-        if let Some(e) = err {
-            extraction_errors.push(e);
-        }
+        let (commands, err) =
+            extraction::extract_commands(harness, &layout, extraction_path, limits);
+        extraction_errors.extend(err);
 
-        let (agents, err) = extraction::extract_agents(harness, &extraction_path);
-        if let Some(e) = err {
-            extraction_errors.push(e);
-        }
+        let (plugins, err) = extraction::extract_plugins(harness, &layout, extraction_path, limits);
+        extraction_errors.extend(err);
+
+        let (agents, err) = extraction::extract_agents(harness, &layout, extraction_path, limits);
+        extraction_errors.extend(err);
+
+        let resources = extraction::extract_custom_resources(&layout, extraction_path, limits);
 
-        let (rules_file, err) = extraction::extract_rules_file(harness, &extraction_path);
-        if let Some(e) = err {
-            extraction_errors.push(e);
+        // Rules files are only located for built-in harnesses.
+        if let Some(builtin) = harness.as_builtin() {
+            let (found, err) = extraction::extract_rules_file(builtin, extraction_path);
+            rules_file = found;
+            extraction_errors.extend(err);
         }
+        let rules_tokens = rules_file
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| super::estimate_tokens(&content));
 
-        Ok(ProfileInfo {
-            name: name.as_str().to_string(),
-            harness_id,
-            is_active,
-            path: profile_path,
+        ProfileInfo {
+            harness_id: harness.id().to_string(),
             mcp_servers,
             skills,
+            skill_descriptions,
+            skill_tokens,
             commands,
             plugins,
             agents,
+            resources,
+            rules_tokens,
             rules_file,
             theme,
             model,
+            setting_layers: merged.layers,
+            overridden_settings: merged.overridden,
             extraction_errors,
-        })
+            ..Default::default()
+        }
+    }
+
+    /// Reads the definition file of each skill in `info`, from the same
+    /// place [`show_profile`](Self::show_profile) read it, keyed by skill name.
+    pub fn skill_files(
+        &self,
+        harness: &dyn HarnessConfig,
+        info: &ProfileInfo,
+    ) -> BTreeMap<String, String> {
+        let path = if info.is_active {
+            harness.config_dir().unwrap_or(info.path.clone())
+        } else {
+            info.path.clone()
+        };
+        extraction::read_skill_files(&harness.resource_layout(), &path, &info.skills.items)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::extraction::{
-        DirectoryStructure, extract_resource_summary, list_files_matching, list_subdirs_with_file,
+        extract_resource_summary, list_files_matching, list_subdirs_with_file,
     };
     use super::*;
-    use std::ffi::OsString;
+    use crate::harness::ResourceStructure;
+    use crate::test_utils::{ConfigEnvGuard, MockHarness};
     use std::fs;
-    use std::sync::{Mutex, OnceLock};
     use tempfile::TempDir;
 
-    static TEST_ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-
-    struct TestEnvGuard {
-        _lock: std::sync::MutexGuard<'static, ()>,
-        prev: Option<OsString>,
+    fn setup_test_env(temp: &TempDir) -> ConfigEnvGuard {
+        ConfigEnvGuard::new(&temp.path().join("bridle_config"))
     }
 
-    impl Drop for TestEnvGuard {
-        fn drop(&mut self) {
-            if let Some(prev) = &self.prev {
-                unsafe { std::env::set_var("BRIDLE_CONFIG_DIR", prev) };
-            } else {
-                unsafe { std::env::remove_var("BRIDLE_CONFIG_DIR") };
-            }
-        }
-    }
-
-    struct MockHarness {
-        id: String,
-        config_dir: PathBuf,
-        mcp_path: Option<PathBuf>,
-    }
-
-    impl MockHarness {
-        fn new(id: &str, config_dir: PathBuf) -> Self {
-            Self {
-                id: id.to_string(),
-                config_dir,
-                mcp_path: None,
-            }
-        }
-
-        fn with_mcp(mut self, mcp_path: PathBuf) -> Self {
-            self.mcp_path = Some(mcp_path);
-            self
-        }
-    }
-
-    impl HarnessConfig for MockHarness {
-        fn id(&self) -> &str {
-            &self.id
-        }
-
-        fn config_dir(&self) -> Result<PathBuf> {
-            Ok(self.config_dir.clone())
-        }
-
-        fn installation_status(&self) -> Result<InstallationStatus> {
-            Ok(InstallationStatus::FullyInstalled {
-                binary_path: PathBuf::from("/bin/mock"),
-                config_path: self.config_dir.clone(),
-            })
-        }
-
-        fn mcp_filename(&self) -> Option<String> {
-            None
-        }
-
-        fn mcp_config_path(&self) -> Option<PathBuf> {
-            self.mcp_path.clone()
+    #[test]
+    fn resolve_profiles_expands_globs() {
+        let temp = TempDir::new().unwrap();
+        let harness = MockHarness::new("test-resolve-globs", temp.path().join("live"));
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        for name in ["tmp-a", "tmp-b", "work"] {
+            let name = ProfileName::new(name).unwrap();
+            manager.create_profile(&harness, &name).unwrap();
         }
+        let resolve = |pattern| {
+            manager
+                .resolve_profiles(&harness, pattern)
+                .map(|names| names.iter().map(|n| n.to_string()).collect::<Vec<_>>())
+        };
 
-        fn parse_mcp_servers(
-            &self,
-            _content: &str,
-            _filename: &str,
-        ) -> Result<Vec<(String, bool)>> {
-            Ok(vec![])
-        }
+        assert_eq!(resolve("TMP-*").unwrap(), ["tmp-a", "tmp-b"]);
+        assert_eq!(resolve("missing").unwrap(), ["missing"]);
+        assert!(matches!(
+            resolve("old-*"),
+            Err(Error::ProfileNotFound { .. })
+        ));
     }
 
-    fn setup_test_env(temp: &TempDir) -> TestEnvGuard {
-        let lock = TEST_ENV_LOCK.get_or_init(|| Mutex::new(())).lock().unwrap();
-
-        let prev = std::env::var_os("BRIDLE_CONFIG_DIR");
-        let bridle_config_dir = temp.path().join("bridle_config");
-        fs::create_dir_all(&bridle_config_dir).unwrap();
-        unsafe { std::env::set_var("BRIDLE_CONFIG_DIR", &bridle_config_dir) };
-
-        TestEnvGuard { _lock: lock, prev }
+    #[test]
+    fn create_profile_rejects_case_collisions() {
+        let temp = TempDir::new().unwrap();
+        let harness = MockHarness::new("test-case-collision", temp.path().join("live"));
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        fs::create_dir_all(temp.path().join("profiles/test-case-collision/Work")).unwrap();
+
+        let work = ProfileName::new("work").unwrap();
+        let err = manager.create_profile(&harness, &work).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidProfileName {
+                reason: InvalidProfileName::CaseCollision(ref existing),
+                ..
+            } if existing == "Work"
+        ));
+        let home = ProfileName::new("home").unwrap();
+        assert!(manager.create_profile(&harness, &home).is_ok());
     }
 
     #[test]
@@ -424,9 +735,272 @@ mod tests {
         );
     }
 
+    #[test]
+    fn with_profile_applied_restores_previous_config() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("test-temporary", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+
+        let work = ProfileName::new("work").unwrap();
+        let ci = ProfileName::new("ci").unwrap();
+        fs::write(live_config.join("settings.json"), "ci").unwrap();
+        manager.create_from_current(&harness, &ci).unwrap();
+        fs::write(live_config.join("settings.json"), "work").unwrap();
+        manager.create_from_current(&harness, &work).unwrap();
+
+        let seen = manager
+            .with_profile_applied(&harness, None, &ci, || {
+                let seen = fs::read_to_string(live_config.join("settings.json")).unwrap();
+                fs::write(live_config.join("scratch.txt"), "temporary").unwrap();
+                seen
+            })
+            .unwrap();
+
+        assert_eq!(seen, "ci");
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "work"
+        );
+        assert!(!live_config.join("scratch.txt").exists());
+        assert!(
+            !manager
+                .profile_path(&harness, &ci)
+                .join("scratch.txt")
+                .exists()
+        );
+        assert_eq!(
+            BridleConfig::load()
+                .unwrap()
+                .active_profile_for("test-temporary"),
+            Some("work")
+        );
+    }
+
+    #[test]
+    fn profiles_breaking_the_policy_are_not_applied() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "home").unwrap();
+        let harness = MockHarness::new("test-policy", live_config.clone());
+        let policy = temp.path().join("policy.toml");
+        fs::write(&policy, "required_rules = [\"Never commit secrets.\"]\n").unwrap();
+        let manager = ProfileManager::builder()
+            .profiles_dir(temp.path().join("profiles"))
+            .policy(policy.to_string_lossy())
+            .build()
+            .unwrap();
+
+        let work = ProfileName::new("work").unwrap();
+        manager.create_profile(&harness, &work).unwrap();
+        fs::write(
+            manager.profile_path(&harness, &work).join("settings.json"),
+            "work",
+        )
+        .unwrap();
+
+        for refused in [
+            manager.switch_profile(&harness, &work).map(|_| ()),
+            manager.restore_profile(&harness, &work),
+        ] {
+            assert!(matches!(refused, Err(Error::PolicyViolation { .. })));
+        }
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "home"
+        );
+
+        let rules = manager.profile_path(&harness, &work).join(rules::RULES_DIR);
+        fs::create_dir_all(&rules).unwrap();
+        fs::write(rules.join("secrets.md"), "Never commit secrets.\n").unwrap();
+        manager.switch_profile(&harness, &work).unwrap();
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "work"
+        );
+    }
+
+    #[test]
+    fn switch_renders_templates_and_save_keeps_them() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("test-template", live_config.clone());
+        let manager = ProfileManager::builder()
+            .profiles_dir(temp.path().join("profiles"))
+            .var_prompt(|name| (name == "endpoint").then(|| "https://api.test".to_string()))
+            .build()
+            .unwrap();
+
+        let work = ProfileName::new("work").unwrap();
+        let other = ProfileName::new("other").unwrap();
+        manager.create_from_current(&harness, &other).unwrap();
+        let template = r#"{"key": "{{ api_key }}", "url": "{{ endpoint }}"}"#;
+        fs::write(live_config.join("settings.json"), template).unwrap();
+        manager.create_from_current(&harness, &work).unwrap();
+        let profile = manager.profile_path(&harness, &work);
+        fs::write(profile.join(template::VARS_FILE), "api_key = \"sk-work\"\n").unwrap();
+
+        manager.switch_profile(&harness, &other).unwrap();
+        manager.switch_profile(&harness, &work).unwrap();
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            r#"{"key": "sk-work", "url": "https://api.test"}"#
+        );
+        assert!(!live_config.join(template::VARS_FILE).exists());
+
+        manager.switch_profile(&harness, &other).unwrap();
+        assert_eq!(
+            fs::read_to_string(profile.join("settings.json")).unwrap(),
+            template
+        );
+        assert!(profile.join(template::VARS_FILE).exists());
+    }
+
+    #[test]
+    fn builder_dry_run_reports_without_touching_disk() {
+        use std::sync::{Arc, Mutex};
+
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let profiles_dir = temp.path().join("profiles");
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let manager = ProfileManager::builder()
+            .profiles_dir(&profiles_dir)
+            .dry_run(true)
+            .observer(move |e: &ProfileEvent| sink.lock().unwrap().push(e.clone()))
+            .build()
+            .unwrap();
+
+        let harness = MockHarness::new("test-dry-run", live_config);
+        let name = ProfileName::new("work").unwrap();
+        let path = manager.create_from_current(&harness, &name).unwrap();
+
+        assert!(!path.exists());
+        assert!(BridleConfig::load().unwrap().active.is_empty());
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            &[ProfileEvent::Created {
+                harness: "test-dry-run".to_string(),
+                profile: "work".to_string(),
+                path,
+            }]
+        );
+    }
+
+    #[test]
+    fn builder_exclude_patterns_skip_and_preserve_files() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+        fs::write(live_config.join("debug.log"), "noise").unwrap();
+
+        let manager = ProfileManager::builder()
+            .profiles_dir(temp.path().join("profiles"))
+            .exclude("*.log")
+            .build()
+            .unwrap();
+        let harness = MockHarness::new("test-exclude", live_config.clone());
+
+        let profile_a = ProfileName::new("profile-a").unwrap();
+        let profile_b = ProfileName::new("profile-b").unwrap();
+        let path_a = manager.create_from_current(&harness, &profile_a).unwrap();
+        manager.create_from_current(&harness, &profile_b).unwrap();
+        assert!(path_a.join("settings.json").exists());
+        assert!(!path_a.join("debug.log").exists());
+
+        manager.switch_profile(&harness, &profile_a).unwrap();
+        assert!(
+            live_config.join("debug.log").exists(),
+            "Excluded files should be left alone in the live config"
+        );
+    }
+
+    #[test]
+    fn snapshot_policy_keep_last_rotates_backups() {
+        let temp = TempDir::new().unwrap();
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+
+        let manager = ProfileManager::builder()
+            .profiles_dir(temp.path().join("profiles"))
+            .snapshot_policy(SnapshotPolicy::KeepLast(1))
+            .build()
+            .unwrap();
+        let harness = MockHarness::new("test-rotate", live_config);
+
+        let harness_backups = manager.backups_dir().join("test-rotate");
+        fs::create_dir_all(harness_backups.join("20000101_000000")).unwrap();
+        fs::create_dir_all(harness_backups.join("no-profile")).unwrap();
+
+        let latest = manager.backup_current(&harness).unwrap().unwrap();
+
+        assert!(latest.exists());
+        assert!(!harness_backups.join("20000101_000000").exists());
+        assert!(harness_backups.join("no-profile").exists());
+    }
+
+    #[test]
+    fn snapshot_policy_never_skips_backup() {
+        let temp = TempDir::new().unwrap();
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+
+        let manager = ProfileManager::builder()
+            .profiles_dir(temp.path().join("profiles"))
+            .snapshot_policy(SnapshotPolicy::Never)
+            .build()
+            .unwrap();
+        let harness = MockHarness::new("test-never", live_config);
+
+        assert!(manager.backup_current(&harness).unwrap().is_none());
+        assert!(!manager.backups_dir().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hardlink_strategy_links_profile_files() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+
+        let manager = ProfileManager::builder()
+            .profiles_dir(temp.path().join("profiles"))
+            .copy_strategy(CopyStrategy::Hardlink)
+            .build()
+            .unwrap();
+        let harness = MockHarness::new("test-hardlink", live_config.clone());
+        let name = ProfileName::new("linked").unwrap();
+        let path = manager.create_from_current(&harness, &name).unwrap();
+
+        let live_ino = fs::metadata(live_config.join("settings.json"))
+            .unwrap()
+            .ino();
+        let profile_ino = fs::metadata(path.join("settings.json")).unwrap().ino();
+        assert_eq!(live_ino, profile_ino);
+    }
+
     #[test]
     fn create_from_current_copies_mcp_config() {
         let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
         let profiles_dir = temp.path().join("profiles");
         let live_config = temp.path().join("live_config");
         let mcp_file = temp.path().join(".mcp.json");
@@ -541,9 +1115,153 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merge_switch_keeps_unknown_live_files() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let profiles_dir = temp.path().join("profiles");
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("runtime.db"), "state").unwrap();
+
+        let harness = MockHarness::new("test-merge", live_config.clone());
+        let manager = ProfileManager::builder()
+            .profiles_dir(&profiles_dir)
+            .switch_strategy(SwitchStrategy::Merge)
+            .build()
+            .unwrap();
+        let profile_a = ProfileName::new("profile-a").unwrap();
+        let profile_b = ProfileName::new("profile-b").unwrap();
+        for (name, content) in [(&profile_a, "a"), (&profile_b, "b")] {
+            let path = manager.create_profile(&harness, name).unwrap();
+            fs::write(path.join("settings.json"), content).unwrap();
+        }
+
+        manager.switch_profile(&harness, &profile_a).unwrap();
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "a"
+        );
+        fs::write(live_config.join("settings.json"), "a2").unwrap();
+        fs::write(live_config.join("new.md"), "notes").unwrap();
+
+        manager.switch_profile(&harness, &profile_b).unwrap();
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "b"
+        );
+        assert!(live_config.join("runtime.db").exists());
+        assert!(live_config.join("new.md").exists());
+
+        let saved = manager.profile_path(&harness, &profile_a);
+        assert_eq!(
+            fs::read_to_string(saved.join("settings.json")).unwrap(),
+            "a2"
+        );
+        assert!(!saved.join("runtime.db").exists());
+        assert!(!saved.join("new.md").exists());
+    }
+
+    #[test]
+    fn known_switch_syncs_only_declared_paths() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let profiles_dir = temp.path().join("profiles");
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(live_config.join("skills/review")).unwrap();
+        fs::create_dir_all(live_config.join("sessions")).unwrap();
+        fs::write(live_config.join("settings.json"), "a").unwrap();
+        fs::write(live_config.join("skills/review/SKILL.md"), "review").unwrap();
+        fs::write(live_config.join("sessions/1.jsonl"), "history").unwrap();
+
+        let harness = MockHarness::new("test-known", live_config.clone())
+            .with_known_paths(&["settings.json", "skills"]);
+        let manager = ProfileManager::builder()
+            .profiles_dir(&profiles_dir)
+            .switch_strategy(SwitchStrategy::Known)
+            .build()
+            .unwrap();
+        let profile_a = ProfileName::new("profile-a").unwrap();
+        let profile_b = ProfileName::new("profile-b").unwrap();
+        let saved = manager
+            .create_from_current_with_resources(&harness, None, &profile_a)
+            .unwrap();
+        assert!(saved.join("skills/review/SKILL.md").exists());
+        assert!(!saved.join("sessions").exists());
+
+        let path = manager.create_profile(&harness, &profile_b).unwrap();
+        fs::write(path.join("settings.json"), "b").unwrap();
+        fs::write(path.join("stray.txt"), "ignored").unwrap();
+
+        manager.switch_profile(&harness, &profile_b).unwrap();
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "b"
+        );
+        assert!(!live_config.join("skills").exists());
+        assert!(!live_config.join("stray.txt").exists());
+        assert!(live_config.join("sessions/1.jsonl").exists());
+        assert!(!saved.join("sessions").exists());
+    }
+
+    #[test]
+    fn shared_skills_are_mounted_on_switch_and_not_saved() {
+        use crate::harness::{ResourceDir, ResourceKind, ResourceLayout};
+        use harness_locate::DirectoryStructure;
+
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let library = BridleConfig::config_dir().unwrap().join("shared/skills");
+        fs::create_dir_all(library.join("commit")).unwrap();
+        fs::create_dir_all(library.join("review")).unwrap();
+        fs::write(library.join("commit/SKILL.md"), "shared commit").unwrap();
+        fs::write(library.join("review/SKILL.md"), "shared review").unwrap();
+
+        let profiles_dir = temp.path().join("profiles");
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let layout = ResourceLayout::default().with_dir(
+            ResourceKind::Skills,
+            ResourceDir::new(
+                live_config.join("skills"),
+                DirectoryStructure::Nested {
+                    subdir_pattern: "*".to_string(),
+                    file_name: "SKILL.md".to_string(),
+                },
+            ),
+        );
+        let harness = MockHarness::new("test-shared-skills", live_config.clone())
+            .with_resource_layout(layout);
+        let manager = ProfileManager::new(profiles_dir);
+
+        let own = ProfileName::new("own").unwrap();
+        let opted_out = ProfileName::new("opted-out").unwrap();
+        let own_path = manager.create_profile(&harness, &own).unwrap();
+        fs::create_dir_all(own_path.join("skills/review")).unwrap();
+        fs::write(own_path.join("skills/review/SKILL.md"), "own review").unwrap();
+        let opted_out_path = manager.create_profile(&harness, &opted_out).unwrap();
+        fs::write(opted_out_path.join(PROFILE_FILE), "shared_skills = false\n").unwrap();
+
+        manager.switch_profile(&harness, &own).unwrap();
+        assert_eq!(
+            fs::read_to_string(live_config.join("skills/commit/SKILL.md")).unwrap(),
+            "shared commit"
+        );
+        assert_eq!(
+            fs::read_to_string(live_config.join("skills/review/SKILL.md")).unwrap(),
+            "own review"
+        );
+
+        manager.switch_profile(&harness, &opted_out).unwrap();
+        assert!(!own_path.join("skills/commit").exists());
+        assert!(own_path.join("skills/review/SKILL.md").exists());
+        assert!(!live_config.join("skills/commit").exists());
+    }
+
     #[test]
     fn save_to_profile_captures_everything() {
         let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
         let profiles_dir = temp.path().join("profiles");
         let live_config = temp.path().join("live_config");
         fs::create_dir_all(&live_config).unwrap();
@@ -572,6 +1290,7 @@ mod tests {
     #[test]
     fn create_from_current_captures_arbitrary_directories() {
         let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
         let profiles_dir = temp.path().join("profiles");
         let live_config = temp.path().join("live_config");
         fs::create_dir_all(&live_config).unwrap();
@@ -761,9 +1480,69 @@ mod tests {
         fs::write(dir.join("readme.txt"), "content").unwrap();
         fs::create_dir(dir.join("subdir")).unwrap();
 
-        let result = list_files_matching(dir, "*.md");
+        let result = list_files_matching(dir, "*.md", 100);
+
+        assert_eq!(
+            result,
+            (vec!["skill1".to_string(), "skill2".to_string()], false)
+        );
+    }
+
+    #[test]
+    fn listings_stop_at_the_entry_limit() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        for i in 0..5 {
+            fs::write(dir.join(format!("skill{i}.md")), "content").unwrap();
+        }
+
+        let (items, truncated) = list_files_matching(dir, "*.md", 3);
+        assert_eq!(items.len(), 3);
+        assert!(truncated);
+
+        let limits = ExtractionLimits {
+            max_depth: 4,
+            max_entries: 5,
+        };
+        let structure = ResourceStructure::Flat {
+            file_pattern: "*.md".to_string(),
+        };
+        let summary = extract_resource_summary(dir, "", &structure, &limits);
+        assert_eq!(summary.items.len(), 5);
+        assert!(!summary.truncated);
+    }
+
+    #[test]
+    fn show_profile_lists_custom_resource_categories() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(live_config.join("prompts")).unwrap();
+        fs::write(live_config.join("prompts/triage.md"), "triage").unwrap();
+        fs::write(live_config.join("prompts/notes.txt"), "notes").unwrap();
+        fs::create_dir_all(live_config.join("flows/release")).unwrap();
+        fs::write(live_config.join("flows/release/WORKFLOW.md"), "steps").unwrap();
+        let layout = crate::harness::ResourceLayout::default()
+            .with_custom(
+                "prompts",
+                toml::from_str("dir = \"prompts\"\npattern = \"*.md\"").unwrap(),
+            )
+            .with_custom(
+                "workflows",
+                toml::from_str("dir = \"flows\"\nfile_name = \"WORKFLOW.md\"").unwrap(),
+            )
+            .with_custom("empty", toml::from_str("dir = \"missing\"").unwrap());
+        let harness =
+            MockHarness::new("test-custom-resources", live_config).with_resource_layout(layout);
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        let name = ProfileName::new("custom").unwrap();
+        manager.create_from_current(&harness, &name).unwrap();
 
-        assert_eq!(result, vec!["skill1", "skill2"]);
+        let info = manager.show_profile(&harness, &name).unwrap();
+
+        assert_eq!(info.resources["prompts"].items, ["triage"]);
+        assert_eq!(info.resources["workflows"].items, ["release"]);
+        assert!(!info.resources["empty"].directory_exists);
     }
 
     #[test]
@@ -781,7 +1560,7 @@ mod tests {
 
         fs::write(dir.join("file.md"), "content").unwrap();
 
-        let result = list_subdirs_with_file(dir, "*", "index.md");
+        let (result, _) = list_subdirs_with_file(dir, "*", "index.md", 100);
 
         assert_eq!(result, vec!["cmd1", "cmd2"]);
     }
@@ -789,16 +1568,46 @@ mod tests {
     #[test]
     fn extract_resource_summary_handles_nonexistent_dir() {
         let temp = TempDir::new().unwrap();
-        let structure = DirectoryStructure::Flat {
+        let structure = ResourceStructure::Flat {
             file_pattern: "*.md".to_string(),
         };
 
-        let result = extract_resource_summary(temp.path(), "nonexistent", &structure);
+        let result = extract_resource_summary(
+            temp.path(),
+            "nonexistent",
+            &structure,
+            &ExtractionLimits::default(),
+        );
 
         assert!(!result.directory_exists);
         assert!(result.items.is_empty());
     }
 
+    #[test]
+    fn recursive_listings_name_commands_by_their_path() {
+        let temp = TempDir::new().unwrap();
+        let commands = temp.path().join("commands");
+        fs::create_dir_all(commands.join("git/remote/deep")).unwrap();
+        fs::write(commands.join("review.md"), "review").unwrap();
+        fs::write(commands.join("git/commit.md"), "commit").unwrap();
+        fs::write(commands.join("git/notes.txt"), "notes").unwrap();
+        fs::write(commands.join("git/remote/push.md"), "push").unwrap();
+        fs::write(commands.join("git/remote/deep/force.md"), "force").unwrap();
+        let structure = ResourceStructure::Recursive {
+            file_pattern: "*.md".to_string(),
+            separator: ":".to_string(),
+        };
+        let limits = ExtractionLimits {
+            max_depth: 4,
+            max_entries: 100,
+        };
+
+        let summary = extract_resource_summary(temp.path(), "commands", &structure, &limits);
+
+        assert_eq!(summary.items, ["git:commit", "git:remote:push", "review"]);
+        assert!(summary.truncated);
+    }
+
     // ==========================================================================
     // Profile Isolation Tests (GitHub Issue #21)
     //
@@ -1560,4 +2369,335 @@ mod tests {
             "BUG: MCP servers leaked - server2 should not exist after switching to no-mcp profile"
         );
     }
+
+    /// Leaves `live_config` as a crash between wiping and copying would: a
+    /// journal at [`SwitchStep::BackedUp`] and a half-empty config dir.
+    fn interrupted_switch(
+        temp: &TempDir,
+        manager: &ProfileManager,
+        harness: &MockHarness,
+        live_config: &std::path::Path,
+    ) {
+        let home = ProfileName::new("home").unwrap();
+        let work = ProfileName::new("work").unwrap();
+        fs::write(live_config.join("settings.json"), "home").unwrap();
+        manager.create_from_current(harness, &home).unwrap();
+        fs::create_dir_all(manager.profile_path(harness, &work)).unwrap();
+        fs::write(
+            manager.profile_path(harness, &work).join("settings.json"),
+            "work",
+        )
+        .unwrap();
+
+        let backup = temp.path().join("backups/interrupted");
+        fs::create_dir_all(&backup).unwrap();
+        fs::copy(
+            live_config.join("settings.json"),
+            backup.join("settings.json"),
+        )
+        .unwrap();
+        let mut journal = SwitchJournal::begin(
+            &crate::config::StatePaths::resolve().unwrap(),
+            harness.id(),
+            Some("home".to_string()),
+            "work",
+            live_config.to_path_buf(),
+            manager.profile_path(harness, &work),
+            backup,
+            None,
+            Vec::new(),
+            SwitchStrategy::Replace,
+            Vec::new(),
+        )
+        .unwrap();
+        journal.advance(SwitchStep::BackedUp).unwrap();
+        fs::remove_file(live_config.join("settings.json")).unwrap();
+    }
+
+    #[test]
+    fn recover_rolls_back_interrupted_switch() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("test-recover-rollback", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        interrupted_switch(&temp, &manager, &harness, &live_config);
+
+        let pending = ProfileManager::pending_switches().unwrap();
+        assert_eq!(pending.len(), 1);
+        manager
+            .recover(
+                &harness,
+                pending.into_iter().next().unwrap(),
+                RecoveryAction::RollBack,
+            )
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "home"
+        );
+        let config = BridleConfig::load().unwrap();
+        assert_eq!(config.active_profile_for(harness.id()), Some("home"));
+        assert!(ProfileManager::pending_switches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn recover_completes_interrupted_switch() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("test-recover-complete", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        interrupted_switch(&temp, &manager, &harness, &live_config);
+
+        let journal = ProfileManager::pending_switches().unwrap().remove(0);
+        let backup = journal.backup_path.clone();
+        manager
+            .recover(&harness, journal, RecoveryAction::Complete)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "work"
+        );
+        let config = BridleConfig::load().unwrap();
+        assert_eq!(config.active_profile_for(harness.id()), Some("work"));
+        assert!(!backup.exists());
+        assert!(ProfileManager::pending_switches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn recover_applies_machine_overrides() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("test-recover-machine", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        let mut config = BridleConfig::load().unwrap();
+        config.settings_mut().machine = Some("laptop".to_string());
+        config.save().unwrap();
+        interrupted_switch(&temp, &manager, &harness, &live_config);
+        let work = manager.profile_path(&harness, &ProfileName::new("work").unwrap());
+        fs::write(work.join("settings.json.machine-laptop"), "work on laptop").unwrap();
+
+        let journal = ProfileManager::pending_switches().unwrap().remove(0);
+        manager
+            .recover(&harness, journal, RecoveryAction::Complete)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(live_config.join("settings.json")).unwrap(),
+            "work on laptop"
+        );
+        assert!(!live_config.join("settings.json.machine-laptop").exists());
+    }
+
+    #[test]
+    fn recover_assembles_rules_snippets() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("opencode", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        interrupted_switch(&temp, &manager, &harness, &live_config);
+        let rules = manager
+            .profile_path(&harness, &ProfileName::new("work").unwrap())
+            .join(rules::RULES_DIR);
+        fs::create_dir_all(&rules).unwrap();
+        fs::write(rules.join("10-style.md"), "# Style\n").unwrap();
+        fs::write(rules.join("20-testing.md"), "# Testing\n").unwrap();
+
+        let journal = ProfileManager::pending_switches().unwrap().remove(0);
+        manager
+            .recover(&harness, journal, RecoveryAction::Complete)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(live_config.join("AGENTS.md")).unwrap(),
+            "# Style\n\n# Testing\n"
+        );
+        assert!(!live_config.join(rules::RULES_DIR).exists());
+    }
+
+    #[test]
+    fn recover_mounts_shared_skills() {
+        use crate::harness::{ResourceDir, ResourceKind, ResourceLayout};
+        use harness_locate::DirectoryStructure;
+
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let library = BridleConfig::config_dir().unwrap().join("shared/skills");
+        fs::create_dir_all(library.join("commit")).unwrap();
+        fs::write(library.join("commit/SKILL.md"), "shared commit").unwrap();
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let layout = ResourceLayout::default().with_dir(
+            ResourceKind::Skills,
+            ResourceDir::new(
+                live_config.join("skills"),
+                DirectoryStructure::Nested {
+                    subdir_pattern: "*".to_string(),
+                    file_name: "SKILL.md".to_string(),
+                },
+            ),
+        );
+        let harness = MockHarness::new("test-recover-shared-skills", live_config.clone())
+            .with_resource_layout(layout);
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        interrupted_switch(&temp, &manager, &harness, &live_config);
+
+        let journal = ProfileManager::pending_switches().unwrap().remove(0);
+        manager
+            .recover(&harness, journal, RecoveryAction::Complete)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(live_config.join("skills/commit/SKILL.md")).unwrap(),
+            "shared commit"
+        );
+    }
+
+    #[test]
+    fn recover_keeps_live_protected_values() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        let harness = MockHarness::new("claude-code", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        interrupted_switch(&temp, &manager, &harness, &live_config);
+        let journal = ProfileManager::pending_switches().unwrap().remove(0);
+        fs::write(
+            journal.backup_path.join(".credentials.json"),
+            r#"{"claudeAiOauth": {"token": "fresh"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            manager
+                .profile_path(&harness, &ProfileName::new("work").unwrap())
+                .join(".credentials.json"),
+            r#"{"claudeAiOauth": {"token": "stale"}, "theme": "work"}"#,
+        )
+        .unwrap();
+
+        manager
+            .recover(&harness, journal, RecoveryAction::Complete)
+            .unwrap();
+
+        let credentials: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(live_config.join(".credentials.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(credentials["claudeAiOauth"]["token"], "fresh");
+        assert_eq!(credentials["theme"], "work");
+    }
+
+    #[test]
+    fn completed_switch_leaves_no_journal() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+        let harness = MockHarness::new("test-journal-cleared", live_config);
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        let name = ProfileName::new("work").unwrap();
+        manager.create_profile(&harness, &name).unwrap();
+
+        manager.switch_profile(&harness, &name).unwrap();
+
+        assert!(ProfileManager::pending_switches().unwrap().is_empty());
+        let backups = manager.backups_dir().join(harness.id());
+        let leftovers = fs::read_dir(&backups)
+            .map(|d| {
+                d.flatten()
+                    .filter(|e| e.file_name() != "no-profile")
+                    .count()
+            })
+            .unwrap_or(0);
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn drift_tracks_edits_since_switch() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+        let harness = MockHarness::new("test-drift", live_config.clone());
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        let name = ProfileName::new("work").unwrap();
+
+        assert_eq!(manager.drift(&harness).unwrap(), None);
+        manager.create_from_current(&harness, &name).unwrap();
+        assert!(manager.drift(&harness).unwrap().unwrap().is_clean());
+
+        fs::write(live_config.join("settings.json"), r#"{"model":"x"}"#).unwrap();
+        fs::write(live_config.join("extra.md"), "notes").unwrap();
+        let drift = manager.drift(&harness).unwrap().unwrap();
+        assert_eq!(drift.modified, vec!["settings.json"]);
+        assert_eq!(drift.added, vec!["extra.md"]);
+
+        manager.save_to_profile(&harness, None, &name).unwrap();
+        assert!(manager.drift(&harness).unwrap().unwrap().is_clean());
+    }
+
+    #[test]
+    fn switch_resolves_drift_per_file() {
+        let temp = TempDir::new().unwrap();
+        let _env = setup_test_env(&temp);
+        let live_config = temp.path().join("live_config");
+        fs::create_dir_all(&live_config).unwrap();
+        fs::write(live_config.join("settings.json"), "{}").unwrap();
+        let harness = MockHarness::new("test-drift-resolve", live_config.clone());
+        let profiles_dir = temp.path().join("profiles");
+        let work = ProfileName::new("work").unwrap();
+        let other = ProfileName::new("other").unwrap();
+        let plain = ProfileManager::new(profiles_dir.clone());
+        plain.create_from_current(&harness, &other).unwrap();
+        plain.create_from_current(&harness, &work).unwrap();
+
+        fs::write(live_config.join("settings.json"), r#"{"model":"x"}"#).unwrap();
+        fs::write(live_config.join("extra.md"), "notes").unwrap();
+
+        let aborting = ProfileManager::builder()
+            .profiles_dir(&profiles_dir)
+            .on_drift(DriftAction::Abort)
+            .build()
+            .unwrap();
+        let err = aborting.switch_profile(&harness, &other).unwrap_err();
+        assert!(matches!(err, Error::DriftAborted { ref files, .. } if files.len() == 2));
+        assert!(live_config.join("extra.md").exists());
+
+        let prompting = ProfileManager::builder()
+            .profiles_dir(&profiles_dir)
+            .drift_prompt(|path, change| match (path, change) {
+                ("settings.json", DriftChange::Modified) => Some(DriftAction::Stash),
+                _ => Some(DriftAction::Discard),
+            })
+            .build()
+            .unwrap();
+        prompting.switch_profile(&harness, &other).unwrap();
+
+        let work_path = prompting.profile_path(&harness, &work);
+        assert_eq!(
+            fs::read_to_string(work_path.join("settings.json")).unwrap(),
+            "{}"
+        );
+        assert!(!work_path.join("extra.md").exists());
+        let stash = prompting.backups_dir().join("test-drift-resolve/stash");
+        let stashed: Vec<_> = fs::read_dir(&stash).unwrap().collect();
+        assert_eq!(stashed.len(), 1);
+        let stashed = stashed[0].as_ref().unwrap().path();
+        assert_eq!(
+            fs::read_to_string(stashed.join("settings.json")).unwrap(),
+            r#"{"model":"x"}"#
+        );
+    }
 }
@@ -0,0 +1,368 @@
+//! Behavior options for [`ProfileManager`] and its builder.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ProfileManager;
+use super::extraction::ExtractionLimits;
+use super::files::CopyOptions;
+use super::manifest::DriftChange;
+use super::template::VarPrompt;
+use crate::config::{BridleConfig, Keyring, SecretStore, Settings};
+use crate::error::Result;
+
+/// How files are materialized when copying between profiles and live config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyStrategy {
+    /// Copy file contents.
+    #[default]
+    Copy,
+    /// Hard-link files where the filesystem allows it, falling back to a copy.
+    Hardlink,
+}
+
+/// How a switch treats live files the profile does not contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SwitchStrategy {
+    /// Replace the live config with the profile; files only the live config
+    /// has are saved into the outgoing profile and then removed.
+    #[default]
+    Replace,
+    /// Lay the profile over the live config and leave other files alone.
+    /// Saving back only updates files the profile already has.
+    Merge,
+    /// Sync only the paths the harness declares (config files, resource
+    /// directories, MCP config) and leave everything else alone, both ways.
+    Known,
+}
+
+/// What to do with symbolic links found while copying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkPolicy {
+    /// Recreate the link, pointing at the same target.
+    #[default]
+    Preserve,
+    /// Copy what the link points to. Dangling links and cycles are skipped with a warning.
+    Follow,
+    /// Leave links out, with a warning.
+    Skip,
+}
+
+/// What to do with files over the `max_file_size` limit when copying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LargeFilePolicy {
+    /// Copy the file anyway, with a warning.
+    #[default]
+    Warn,
+    /// Leave the file out, with a warning. It stays in place in the live config.
+    Skip,
+}
+
+/// Which timestamped snapshots [`ProfileManager::backup_current`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotPolicy {
+    /// Keep every snapshot.
+    #[default]
+    KeepAll,
+    /// Keep only the newest `n` snapshots per harness.
+    KeepLast(usize),
+    /// Never take snapshots.
+    Never,
+}
+
+/// What a switch does with a file in the live config that changed since the
+/// active profile was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DriftAction {
+    /// Save the live file into the active profile.
+    #[default]
+    Save,
+    /// Drop the change; the profile keeps its own version.
+    Discard,
+    /// Copy the live file to a stash directory, then drop the change.
+    Stash,
+    /// Stop the switch before anything is written.
+    Abort,
+}
+
+/// Asks what to do with one drifted file. Returning `None` saves it.
+pub type DriftPrompt = dyn Fn(&str, DriftChange) -> Option<DriftAction> + Send + Sync;
+
+/// Something that happened (or, in dry-run mode, would happen) to a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileEvent {
+    /// A profile directory was created.
+    Created {
+        harness: String,
+        profile: String,
+        path: PathBuf,
+    },
+    /// A profile directory was deleted.
+    Deleted { harness: String, profile: String },
+    /// Live config was saved back into a profile.
+    Saved { harness: String, profile: String },
+    /// A profile was applied to the live config.
+    Switched {
+        harness: String,
+        from: Option<String>,
+        to: String,
+    },
+    /// A snapshot of the live config was written.
+    SnapshotCreated { harness: String, path: PathBuf },
+}
+
+/// Receives [`ProfileEvent`]s emitted by a [`ProfileManager`].
+pub trait ProfileObserver: Send + Sync {
+    fn on_event(&self, event: &ProfileEvent);
+}
+
+impl<F> ProfileObserver for F
+where
+    F: Fn(&ProfileEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &ProfileEvent) {
+        self(event)
+    }
+}
+
+/// Resolved behavior knobs held by a [`ProfileManager`].
+#[derive(Clone, Default)]
+pub(crate) struct ManagerOptions {
+    pub(crate) copy: CopyOptions,
+    pub(crate) snapshot_policy: SnapshotPolicy,
+    /// Used for harnesses that do not set their own.
+    pub(crate) switch_strategy: SwitchStrategy,
+    /// Overrides every harness's strategy, e.g. for a single switch.
+    pub(crate) forced_strategy: Option<SwitchStrategy>,
+    /// Warn when a profile's files add up to more than this many bytes; 0 disables.
+    pub(crate) max_profile_size: u64,
+    /// How much of a profile `show_profile` reads.
+    pub(crate) extraction: ExtractionLimits,
+    pub(crate) dry_run: bool,
+    pub(crate) observer: Option<Arc<dyn ProfileObserver>>,
+    /// Asks for template variables no other source supplies.
+    pub(crate) var_prompt: Option<Arc<VarPrompt>>,
+    /// Resolves `bridle-secret://` references; the system keyring when unset.
+    pub(crate) secrets: Option<Arc<dyn SecretStore>>,
+    /// Applied to every drifted file at switch time; overrides `drift_prompt`.
+    pub(crate) on_drift: Option<DriftAction>,
+    /// Asks per drifted file when `on_drift` is unset. Without either, drift is saved.
+    pub(crate) drift_prompt: Option<Arc<DriftPrompt>>,
+    /// Team policy file or URL a profile has to satisfy before it is applied.
+    pub(crate) policy: Option<String>,
+}
+
+impl ManagerOptions {
+    pub(crate) fn secret_store(&self) -> &dyn SecretStore {
+        match &self.secrets {
+            Some(store) => store.as_ref(),
+            None => &Keyring,
+        }
+    }
+}
+
+impl fmt::Debug for ManagerOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManagerOptions")
+            .field("copy", &self.copy)
+            .field("snapshot_policy", &self.snapshot_policy)
+            .field("switch_strategy", &self.switch_strategy)
+            .field("forced_strategy", &self.forced_strategy)
+            .field("max_profile_size", &self.max_profile_size)
+            .field("extraction", &self.extraction)
+            .field("dry_run", &self.dry_run)
+            .field("observer", &self.observer.is_some())
+            .field("var_prompt", &self.var_prompt.is_some())
+            .field("secrets", &self.secrets.is_some())
+            .field("on_drift", &self.on_drift)
+            .field("drift_prompt", &self.drift_prompt.is_some())
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// Builder for [`ProfileManager`].
+///
+/// ```ignore
+/// let manager = ProfileManager::builder()
+///     .exclude("*.log")
+///     .snapshot_policy(SnapshotPolicy::KeepLast(5))
+///     .dry_run(true)
+///     .build()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct ProfileManagerBuilder {
+    profiles_dir: Option<PathBuf>,
+    options: ManagerOptions,
+}
+
+impl ProfileManagerBuilder {
+    /// Sets the profiles directory. Defaults to [`BridleConfig::profiles_dir`].
+    pub fn profiles_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.profiles_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets how files are copied between profiles and live config.
+    pub fn copy_strategy(mut self, strategy: CopyStrategy) -> Self {
+        self.options.copy.strategy = strategy;
+        self
+    }
+
+    /// Sets how symbolic links are handled when copying.
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.options.copy.symlinks = policy;
+        self
+    }
+
+    /// Adds a file-name pattern (e.g. `*.log`) to skip when copying.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.options.copy.exclude.push(pattern.into());
+        self
+    }
+
+    /// Sets the size in bytes above which a single file triggers the
+    /// [`LargeFilePolicy`]. 0 disables the check.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.options.copy.max_file_size = bytes;
+        self
+    }
+
+    /// Sets what happens to files over [`ProfileManagerBuilder::max_file_size`].
+    pub fn large_files(mut self, policy: LargeFilePolicy) -> Self {
+        self.options.copy.large_files = policy;
+        self
+    }
+
+    /// Sets the total size in bytes above which saving a profile warns. 0 disables the check.
+    pub fn max_profile_size(mut self, bytes: u64) -> Self {
+        self.options.max_profile_size = bytes;
+        self
+    }
+
+    /// Sets how many directory levels and entries per directory profile
+    /// extraction reads.
+    pub fn extraction_limits(mut self, limits: ExtractionLimits) -> Self {
+        self.options.extraction = limits;
+        self
+    }
+
+    /// Sets which snapshots of the live config are kept.
+    pub fn snapshot_policy(mut self, policy: SnapshotPolicy) -> Self {
+        self.options.snapshot_policy = policy;
+        self
+    }
+
+    /// Sets the switch strategy for harnesses without a `switch_strategy` of their own.
+    pub fn switch_strategy(mut self, strategy: SwitchStrategy) -> Self {
+        self.options.switch_strategy = strategy;
+        self
+    }
+
+    /// Uses `strategy` for every harness, ignoring per-harness settings.
+    pub fn force_switch_strategy(mut self, strategy: SwitchStrategy) -> Self {
+        self.options.forced_strategy = Some(strategy);
+        self
+    }
+
+    /// When enabled, mutating operations report events but leave the filesystem untouched.
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.options.dry_run = enabled;
+        self
+    }
+
+    /// Applies the copy and switch strategies, symlink policy, excludes, size
+    /// and extraction limits, auto-backup choice, and team policy from [`Settings`].
+    pub fn settings(mut self, settings: &Settings) -> Self {
+        self.options.copy.strategy = settings.copy_strategy;
+        self.options.switch_strategy = settings.switch_strategy;
+        self.options.copy.symlinks = settings.symlinks;
+        self.options.copy.max_file_size = settings.max_file_size;
+        self.options.copy.large_files = settings.large_files;
+        self.options.max_profile_size = settings.max_profile_size;
+        self.options.extraction = ExtractionLimits {
+            max_depth: settings.extraction_max_depth,
+            max_entries: settings.extraction_max_entries,
+        };
+        self.options
+            .copy
+            .exclude
+            .extend(settings.exclude.iter().cloned());
+        if !settings.auto_backup {
+            self.options.snapshot_policy = SnapshotPolicy::Never;
+        }
+        self.options.policy = settings.policy.clone();
+        self
+    }
+
+    /// Refuses to apply profiles that break the team policy at `location`,
+    /// a file path or an `https://` URL.
+    pub fn policy(mut self, location: impl Into<String>) -> Self {
+        self.options.policy = Some(location.into());
+        self
+    }
+
+    /// Registers an observer notified of every profile event.
+    pub fn observer(mut self, observer: impl ProfileObserver + 'static) -> Self {
+        self.options.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Sets how template variables missing from the environment and the
+    /// profile's vars file are asked for. Without one, they are an error.
+    pub fn var_prompt(
+        mut self,
+        prompt: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.options.var_prompt = Some(Arc::new(prompt));
+        self
+    }
+
+    /// Sets where `bridle-secret://` references are looked up. Defaults to
+    /// the system [`Keyring`].
+    pub fn secret_store(mut self, store: impl SecretStore + 'static) -> Self {
+        self.options.secrets = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets what a switch does with every file changed in the live config
+    /// since the active profile was applied.
+    pub fn on_drift(mut self, action: DriftAction) -> Self {
+        self.options.on_drift = Some(action);
+        self
+    }
+
+    /// Sets how to ask about each drifted file when no
+    /// [`ProfileManagerBuilder::on_drift`] action is set.
+    pub fn drift_prompt(
+        mut self,
+        prompt: impl Fn(&str, DriftChange) -> Option<DriftAction> + Send + Sync + 'static,
+    ) -> Self {
+        self.options.drift_prompt = Some(Arc::new(prompt));
+        self
+    }
+
+    /// Builds the manager.
+    ///
+    /// # Errors
+    /// Returns an error if no profiles directory was set and bridle's config
+    /// directory cannot be determined.
+    pub fn build(self) -> Result<ProfileManager> {
+        let profiles_dir = match self.profiles_dir {
+            Some(dir) => dir,
+            None => BridleConfig::profiles_dir()?,
+        };
+        Ok(ProfileManager {
+            profiles_dir,
+            options: self.options,
+        })
+    }
+}
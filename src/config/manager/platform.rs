@@ -0,0 +1,185 @@
+//! Platform differences the copy layer has to paper over.
+//!
+//! On Windows: paths longer than `MAX_PATH` need the `\\?\` prefix, and
+//! creating symlinks needs a privilege (or Developer Mode). Elsewhere these
+//! helpers are no-ops. File names compare case-insensitively on Windows and
+//! on case-insensitive volumes elsewhere, such as macOS's default APFS.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Whether file names under `dir` compare case-insensitively.
+///
+/// Always true on Windows. Elsewhere the deepest existing ancestor of `dir`
+/// whose name has ASCII letters is looked up with their case flipped: the
+/// filesystem ignores case if that finds the same file.
+#[cfg(unix)]
+pub fn case_insensitive(dir: &Path) -> bool {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    for ancestor in dir.ancestors() {
+        let Some(name) = ancestor.file_name() else {
+            continue;
+        };
+        let flipped: Vec<u8> = name
+            .as_bytes()
+            .iter()
+            .map(|b| {
+                if b.is_ascii_lowercase() {
+                    b.to_ascii_uppercase()
+                } else {
+                    b.to_ascii_lowercase()
+                }
+            })
+            .collect();
+        if flipped == name.as_bytes() {
+            continue;
+        }
+        let Ok(meta) = std::fs::metadata(ancestor) else {
+            continue;
+        };
+        return std::fs::metadata(ancestor.with_file_name(OsStr::from_bytes(&flipped)))
+            .is_ok_and(|other| other.dev() == meta.dev() && other.ino() == meta.ino());
+    }
+    false
+}
+
+#[cfg(not(unix))]
+pub fn case_insensitive(_dir: &Path) -> bool {
+    cfg!(windows)
+}
+
+/// Compares two file names, ignoring ASCII case if `ignore_case` is set.
+pub fn names_equal(a: &[u8], b: &[u8], ignore_case: bool) -> bool {
+    if ignore_case {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Whether `name` starts with `prefix`, using [`names_equal`] semantics.
+pub fn name_starts_with(name: &[u8], prefix: &[u8], ignore_case: bool) -> bool {
+    name.len() >= prefix.len() && names_equal(&name[..prefix.len()], prefix, ignore_case)
+}
+
+/// Whether `name` ends with `suffix`, using [`names_equal`] semantics.
+pub fn name_ends_with(name: &[u8], suffix: &[u8], ignore_case: bool) -> bool {
+    name.len() >= suffix.len()
+        && names_equal(&name[name.len() - suffix.len()..], suffix, ignore_case)
+}
+
+/// Returns `path` in a form that is not limited to `MAX_PATH` (260 characters).
+///
+/// On Windows, absolute paths get the verbatim `\\?\` (or `\\?\UNC\`) prefix so
+/// deep profile trees can be copied; everything joined onto the result inherits it.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    use std::ffi::OsString;
+    use std::path::{Component, PathBuf, Prefix};
+
+    // Verbatim paths skip normalization, so resolve `.`/`..` and `/` first.
+    let Ok(absolute) = std::path::absolute(path) else {
+        return Cow::Borrowed(path);
+    };
+    let out = match absolute.components().next() {
+        Some(Component::Prefix(p)) => match p.kind() {
+            Prefix::Disk(_) => {
+                let mut out = OsString::from(r"\\?\");
+                out.push(absolute.as_os_str());
+                out
+            }
+            Prefix::UNC(..) => {
+                let Some(rest) = absolute.to_str().and_then(|s| s.strip_prefix(r"\\")) else {
+                    return Cow::Borrowed(path);
+                };
+                OsString::from(format!(r"\\?\UNC\{rest}"))
+            }
+            // Already verbatim, or a device path we should not touch.
+            _ => return Cow::Borrowed(path),
+        },
+        _ => return Cow::Borrowed(path),
+    };
+    Cow::Owned(PathBuf::from(out))
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+/// Whether this process can create symbolic links.
+///
+/// Probed once on Windows by creating a link in the temp directory; always
+/// true elsewhere. When false, links are copied as if [`SymlinkPolicy::Follow`]
+/// were set.
+///
+/// [`SymlinkPolicy::Follow`]: super::SymlinkPolicy::Follow
+#[cfg(windows)]
+pub fn symlinks_supported() -> bool {
+    use std::sync::OnceLock;
+
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let dir = std::env::temp_dir();
+        let target = dir.join(format!("bridle-link-probe-{}.txt", std::process::id()));
+        let link = dir.join(format!("bridle-link-probe-{}.lnk", std::process::id()));
+        let _ = std::fs::write(&target, b"");
+        let supported = std::os::windows::fs::symlink_file(&target, &link).is_ok();
+        let _ = std::fs::remove_file(&link);
+        let _ = std::fs::remove_file(&target);
+        supported
+    })
+}
+
+#[cfg(not(windows))]
+pub fn symlinks_supported() -> bool {
+    true
+}
+
+/// Whether a failed symlink creation should fall back to copying the target.
+pub fn is_symlink_privilege_error(err: &std::io::Error) -> bool {
+    // ERROR_PRIVILEGE_NOT_HELD
+    cfg!(windows) && err.raw_os_error() == Some(1314)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_helpers_ignore_case_when_asked() {
+        assert!(name_ends_with(b"notes.md", b".md", false));
+        assert!(name_starts_with(
+            b"BRIDLE_PROFILE_work",
+            b"BRIDLE_PROFILE_",
+            false
+        ));
+        assert!(names_equal(b"README.MD", b"readme.md", true));
+        assert!(!names_equal(b"README.MD", b"readme.md", false));
+        assert!(name_ends_with(b"NOTES.MD", b".md", true));
+        assert!(!name_ends_with(b"md", b".md", true));
+    }
+
+    #[test]
+    fn case_insensitive_probes_the_filesystem() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("Probe");
+        std::fs::create_dir(&dir).unwrap();
+        let folds = std::fs::metadata(temp.path().join("pROBE")).is_ok();
+        assert_eq!(case_insensitive(&dir), folds || cfg!(windows));
+        assert_eq!(
+            case_insensitive(&dir.join("missing/child")),
+            folds || cfg!(windows)
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn long_path_is_identity_off_windows() {
+        let path = Path::new("/tmp/some/deep/path");
+        assert_eq!(long_path(path), path);
+    }
+}
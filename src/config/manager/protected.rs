@@ -0,0 +1,253 @@
+//! Protected sections: values in the live config that belong to this machine
+//! rather than to a profile, such as sign-ins and MCP OAuth tokens.
+//!
+//! A harness declares them as `<file>:<key.path>`, see
+//! [`HarnessConfig::protected_sections`]; a `*` segment matches every key at
+//! its level. The file is relative to the config directory, or names the MCP
+//! config kept outside it. A switch reads the live values before it replaces
+//! the config and splices them into the applied files afterwards, so the copy
+//! a profile saved some time ago never replaces them. Sections the live
+//! config lacks keep the profile's values.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::config::write_atomic;
+use crate::error::{Error, Result};
+use crate::harness::HarnessConfig;
+use crate::harness::edit;
+use crate::harness::schema::ConfigFormat;
+
+/// One live value to put back.
+#[derive(Debug, Clone, PartialEq)]
+struct Kept {
+    file: PathBuf,
+    key: Vec<String>,
+    value: Value,
+}
+
+/// The live values of a harness's protected sections.
+#[derive(Debug, Default)]
+pub(super) struct ProtectedValues {
+    kept: Vec<Kept>,
+}
+
+/// Splits `<file>:<key.path>`.
+fn parse_section(section: &str) -> Result<(&str, Vec<String>)> {
+    let invalid = || {
+        Error::Config(format!(
+            "invalid protected section {section:?}: expected <file>:<key.path>"
+        ))
+    };
+    let (file, key) = section.split_once(':').ok_or_else(invalid)?;
+    if file.is_empty() {
+        return Err(invalid());
+    }
+    let key = edit::parse_key_path(key).map_err(|_| invalid())?;
+    Ok((file, key))
+}
+
+/// Where `file` of a protected section lives.
+fn locate(harness: &dyn HarnessConfig, config_dir: &Path, file: &str) -> PathBuf {
+    match harness.mcp_config_path() {
+        Some(mcp_path)
+            if mcp_path
+                .file_name()
+                .is_some_and(|f| Path::new(f) == Path::new(file))
+                && !mcp_path.starts_with(config_dir) =>
+        {
+            mcp_path
+        }
+        _ => config_dir.join(file),
+    }
+}
+
+/// The values in `node` matched by `pattern`, with their concrete key paths.
+fn expand(node: &Value, pattern: &[String], key: &mut Vec<String>, found: &mut Vec<Kept>) {
+    let Some((segment, rest)) = pattern.split_first() else {
+        found.push(Kept {
+            file: PathBuf::new(),
+            key: key.clone(),
+            value: node.clone(),
+        });
+        return;
+    };
+    let Value::Object(map) = node else {
+        return;
+    };
+    for (name, child) in map {
+        if segment == "*" || segment == name {
+            key.push(name.clone());
+            expand(child, rest, key, found);
+            key.pop();
+        }
+    }
+}
+
+impl ProtectedValues {
+    /// Reads the live values of `harness`'s protected sections.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] for a section not written as
+    /// `<file>:<key.path>`. Files that are missing or do not parse are
+    /// skipped.
+    pub(super) fn capture(harness: &dyn HarnessConfig) -> Result<Self> {
+        let sections = harness.protected_sections();
+        if sections.is_empty() {
+            return Ok(Self::default());
+        }
+        let config_dir = harness.config_dir()?;
+        let mut kept = Vec::new();
+        for section in &sections {
+            let (file, pattern) = parse_section(section)?;
+            let path = locate(harness, &config_dir, file);
+            let (Some(format), Ok(content)) = (
+                ConfigFormat::from_path(&path),
+                std::fs::read_to_string(&path),
+            ) else {
+                continue;
+            };
+            let Ok(Some(root)) = edit::get(&content, format, &[]) else {
+                continue;
+            };
+            let mut found = Vec::new();
+            expand(&root, &pattern, &mut Vec::new(), &mut found);
+            kept.extend(found.into_iter().map(|k| Kept {
+                file: path.clone(),
+                ..k
+            }));
+        }
+        Ok(Self { kept })
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.kept.is_empty()
+    }
+
+    /// Writes the values back into the live config as it is now, creating
+    /// files a profile does not have.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] when an applied file no longer parses.
+    pub(super) fn apply(&self) -> Result<()> {
+        let mut files: Vec<&Path> = self.kept.iter().map(|k| k.file.as_path()).collect();
+        files.sort();
+        files.dedup();
+        for file in files {
+            let Some(format) = ConfigFormat::from_path(file) else {
+                continue;
+            };
+            let original = match std::fs::read_to_string(file) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e.into()),
+            };
+            let mut content = original.clone();
+            for kept in self.kept.iter().filter(|k| k.file == file) {
+                if edit::get(&content, format, &kept.key)
+                    .ok()
+                    .flatten()
+                    .as_ref()
+                    == Some(&kept.value)
+                {
+                    continue;
+                }
+                content = edit::set(&content, format, &kept.key, &kept.value)
+                    .map_err(|e| Error::Config(format!("{}: {e}", file.display())))?;
+            }
+            if content != original {
+                write_atomic(file, content)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockHarness;
+    use std::fs;
+
+    struct Protecting(MockHarness, Vec<String>);
+
+    impl HarnessConfig for Protecting {
+        fn id(&self) -> &str {
+            self.0.id()
+        }
+        fn config_dir(&self) -> Result<PathBuf> {
+            self.0.config_dir()
+        }
+        fn installation_status(&self) -> Result<harness_locate::InstallationStatus> {
+            self.0.installation_status()
+        }
+        fn mcp_filename(&self) -> Option<String> {
+            self.0.mcp_filename()
+        }
+        fn mcp_config_path(&self) -> Option<PathBuf> {
+            self.0.mcp_config_path()
+        }
+        fn parse_mcp_servers(&self, content: &str, filename: &str) -> Result<Vec<(String, bool)>> {
+            self.0.parse_mcp_servers(content, filename)
+        }
+        fn protected_sections(&self) -> Vec<String> {
+            self.1.clone()
+        }
+    }
+
+    #[test]
+    fn keeps_live_tokens_over_the_profiles_copy() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let live = temp.path().join("live");
+        let mcp = temp.path().join(".mcp-servers.json");
+        fs::create_dir_all(&live).unwrap();
+        fs::write(
+            &mcp,
+            r#"{"account": {"token": "fresh"}, "servers": {"github": {"oauth": {"token": "gh-fresh"}}, "jira": {}}}"#,
+        )
+        .unwrap();
+        fs::write(live.join("auth.yaml"), "session: live\n").unwrap();
+        let harness = Protecting(
+            MockHarness::new("test-protected", live.clone()).with_mcp(mcp.clone()),
+            vec![
+                ".mcp-servers.json:account".to_string(),
+                ".mcp-servers.json:servers.*.oauth".to_string(),
+                "auth.yaml:session".to_string(),
+            ],
+        );
+
+        let kept = ProtectedValues::capture(&harness).unwrap();
+        assert_eq!(kept.kept.len(), 3);
+
+        // The switch puts the profile's stale copies in place.
+        fs::write(
+            &mcp,
+            "{\n  // from the profile\n  \"account\": {\"token\": \"stale\"},\n  \"servers\": {\"github\": {\"url\": \"x\"}}\n}\n",
+        )
+        .unwrap();
+        fs::remove_file(live.join("auth.yaml")).unwrap();
+        kept.apply().unwrap();
+
+        let applied = fs::read_to_string(&mcp).unwrap();
+        assert!(applied.contains("// from the profile"));
+        let applied: Value =
+            serde_json::from_str(&crate::config::jsonc::strip_jsonc_comments(&applied)).unwrap();
+        assert_eq!(applied["account"]["token"], "fresh");
+        assert_eq!(applied["servers"]["github"]["oauth"]["token"], "gh-fresh");
+        assert_eq!(applied["servers"]["github"]["url"], "x");
+        assert!(applied["servers"].get("jira").is_none());
+        assert_eq!(
+            fs::read_to_string(live.join("auth.yaml")).unwrap(),
+            "session: live\n"
+        );
+    }
+
+    #[test]
+    fn rejects_sections_without_a_key() {
+        assert!(parse_section("auth.json:token").is_ok());
+        for invalid in ["auth.json", ":token", "auth.json:", "auth.json:a..b"] {
+            assert!(parse_section(invalid).is_err(), "{invalid}");
+        }
+    }
+}
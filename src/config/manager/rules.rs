@@ -0,0 +1,237 @@
+//! Rules files assembled from snippets on switch.
+//!
+//! Instead of one `CLAUDE.md` or `AGENTS.md`, a profile can keep its
+//! instructions as snippet files in [`RULES_DIR`]:
+//!
+//! ```text
+//! rules.d/
+//! ├── 10-style.md
+//! ├── 20-testing.md
+//! └── 90-local.md
+//! ```
+//!
+//! When the profile is applied, the `*.md` snippets are joined in file-name
+//! order, a blank line apart, into the harness's rules file, replacing any
+//! rules file the profile has. The directory itself never reaches the live
+//! config. Saving the live config back drops the assembled file again while
+//! it still matches the snippets; edits made to it live are kept in the
+//! profile with a warning, as the next switch assembles over them.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config::write_atomic;
+use crate::error::Result;
+
+/// Directory in a profile holding rules snippets.
+pub const RULES_DIR: &str = "rules.d";
+
+/// One snippet file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Snippet {
+    name: String,
+    content: String,
+}
+
+/// A part of the assembled rules file, as shown by `bridle rules explain`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RulesSection {
+    /// Line the section starts on in the assembled file, from 1.
+    pub line: usize,
+    /// The section's Markdown heading; `None` for text before a snippet's
+    /// first heading.
+    pub heading: Option<String>,
+    /// Snippet file the section came from.
+    pub snippet: String,
+}
+
+/// The snippets in a profile's [`RULES_DIR`], in assembly order.
+#[derive(Debug, Default)]
+pub struct RulesSnippets {
+    snippets: Vec<Snippet>,
+}
+
+impl RulesSnippets {
+    /// Reads the `*.md` files in `profile_path`'s [`RULES_DIR`], if it has one.
+    pub fn load(profile_path: &Path) -> Result<Self> {
+        let dir = profile_path.join(RULES_DIR);
+        if !dir.is_dir() {
+            return Ok(Self::default());
+        }
+        let mut snippets = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            snippets.push(Snippet {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                content: std::fs::read_to_string(&path)?,
+            });
+        }
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self { snippets })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+
+    /// Snippet file names, in assembly order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.snippets.iter().map(|s| s.name.as_str())
+    }
+
+    /// Non-blank snippets with trailing whitespace trimmed.
+    fn parts(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.snippets
+            .iter()
+            .map(|s| (s.name.as_str(), s.content.trim_end()))
+            .filter(|(_, content)| !content.is_empty())
+    }
+
+    /// The rules file the snippets make up.
+    pub fn assemble(&self) -> String {
+        let mut text = self
+            .parts()
+            .map(|(_, content)| content)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Each heading of the assembled file, and any text before a snippet's
+    /// first heading, with the snippet it came from.
+    pub fn sections(&self) -> Vec<RulesSection> {
+        let mut sections = Vec::new();
+        let mut start = 1;
+        for (name, content) in self.parts() {
+            let mut in_code = false;
+            let mut started = false;
+            for (offset, line) in content.lines().enumerate() {
+                if line.trim_start().starts_with("```") {
+                    in_code = !in_code;
+                }
+                let heading = if in_code { None } else { heading(line) };
+                if heading.is_some() || (!started && !line.trim().is_empty()) {
+                    started = true;
+                    sections.push(RulesSection {
+                        line: start + offset,
+                        heading: heading.map(str::to_string),
+                        snippet: name.to_string(),
+                    });
+                }
+            }
+            start += content.lines().count() + 1;
+        }
+        sections
+    }
+
+    /// Writes the assembled rules file to `live_dir/file_name`.
+    pub(super) fn apply(&self, live_dir: &Path, file_name: &str) -> Result<()> {
+        write_atomic(&live_dir.join(file_name), self.assemble())
+    }
+
+    /// Removes `file_name` from a profile just saved from the live config
+    /// when it is still the assembly of the snippets.
+    pub(super) fn strip(&self, profile_path: &Path, file_name: &str) -> Result<()> {
+        let path = profile_path.join(file_name);
+        match std::fs::read_to_string(&path) {
+            Ok(content) if content == self.assemble() => std::fs::remove_file(&path)?,
+            Ok(_) => eprintln!(
+                "Warning: {file_name} was edited outside {RULES_DIR}/; the next switch \
+                 replaces it with the snippets, so move the edits into a snippet"
+            ),
+            Err(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// The ATX heading on `line`, if it is one.
+fn heading(line: &str) -> Option<&str> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[hashes..];
+    ((1..=6).contains(&hashes) && (rest.is_empty() || rest.starts_with(' ')))
+        .then(|| line.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn snippets(files: &[(&str, &str)]) -> RulesSnippets {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join(RULES_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, content) in files {
+            fs::write(dir.join(name), content).unwrap();
+        }
+        RulesSnippets::load(temp.path()).unwrap()
+    }
+
+    #[test]
+    fn assembles_markdown_snippets_in_name_order() {
+        let rules = snippets(&[
+            ("20-testing.md", "# Testing\n\nRun the tests.\n\n\n"),
+            ("10-style.md", "# Style\n\nBe brief.\n"),
+            ("notes.txt", "ignored"),
+            ("15-empty.md", "\n"),
+        ]);
+        assert_eq!(
+            rules.names().collect::<Vec<_>>(),
+            ["10-style.md", "15-empty.md", "20-testing.md"]
+        );
+        assert_eq!(
+            rules.assemble(),
+            "# Style\n\nBe brief.\n\n# Testing\n\nRun the tests.\n"
+        );
+    }
+
+    #[test]
+    fn sections_point_at_their_snippets() {
+        let rules = snippets(&[
+            ("10-intro.md", "You work on bridle.\n"),
+            (
+                "20-style.md",
+                "# Style\n\n```sh\n# not a heading\n```\n\n## Naming\n",
+            ),
+        ]);
+        let sections: Vec<_> = rules
+            .sections()
+            .into_iter()
+            .map(|s| (s.line, s.heading, s.snippet))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                (1, None, "10-intro.md".to_string()),
+                (3, Some("# Style".to_string()), "20-style.md".to_string()),
+                (9, Some("## Naming".to_string()), "20-style.md".to_string()),
+            ]
+        );
+        let assembled = rules.assemble();
+        let lines: Vec<_> = assembled.lines().collect();
+        assert_eq!(lines[2], "# Style");
+        assert_eq!(lines[8], "## Naming");
+    }
+
+    #[test]
+    fn strip_keeps_edited_rules_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let rules = snippets(&[("10-style.md", "# Style\n")]);
+        rules.apply(temp.path(), "AGENTS.md").unwrap();
+        rules.strip(temp.path(), "AGENTS.md").unwrap();
+        assert!(!temp.path().join("AGENTS.md").exists());
+
+        fs::write(temp.path().join("AGENTS.md"), "# Style\n\nEdited.\n").unwrap();
+        rules.strip(temp.path(), "AGENTS.md").unwrap();
+        assert!(temp.path().join("AGENTS.md").exists());
+    }
+}
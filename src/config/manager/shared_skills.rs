@@ -0,0 +1,168 @@
+//! The shared skills library, mounted into every profile on switch.
+//!
+//! Skills in `<bridle config dir>/shared/skills/` are linked into the live
+//! skills directory of whichever harness a profile is applied to, so skills
+//! every profile wants only need installing once. A skill the profile has
+//! itself wins over the library's, and a profile can opt out with
+//! `shared_skills = false` in its [`PROFILE_FILE`](super::PROFILE_FILE).
+//!
+//! Entries are symlinked where the platform allows it and copied otherwise.
+//! Saving the live config back drops links into the library and copies that
+//! still match it, so profiles pick up later changes to the library.
+
+use std::path::{Path, PathBuf};
+
+use super::fragments::SHARED_DIR;
+use super::platform;
+use super::{ProfileSettings, files};
+use crate::config::BridleConfig;
+use crate::error::Result;
+use crate::harness::{HarnessConfig, ResourceKind};
+
+/// Directory under [`SHARED_DIR`] holding the shared skills library.
+const SHARED_SKILLS_DIR: &str = "skills";
+
+/// The shared skills library.
+pub(super) struct SharedSkills {
+    dir: PathBuf,
+}
+
+impl SharedSkills {
+    /// The library in bridle's config directory, if it exists and the
+    /// profile at `profile_path` has not opted out of it.
+    pub(super) fn for_profile(profile_path: &Path) -> Result<Option<Self>> {
+        if !ProfileSettings::load(profile_path)?.shared_skills {
+            return Ok(None);
+        }
+        let dir = BridleConfig::config_dir()?
+            .join(SHARED_DIR)
+            .join(SHARED_SKILLS_DIR);
+        Ok(dir.is_dir().then_some(Self { dir }))
+    }
+
+    /// Library entries, skipping hidden files.
+    fn entries(&self) -> Result<Vec<(std::ffi::OsString, PathBuf)>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with('.') {
+                continue;
+            }
+            entries.push((name, entry.path()));
+        }
+        Ok(entries)
+    }
+
+    /// Links every library skill the live config lacks into `harness`'s
+    /// skills directory. Harnesses without one are left alone.
+    pub(super) fn mount(&self, harness: &dyn HarnessConfig) -> Result<()> {
+        let layout = harness.resource_layout();
+        let Some(skills) = layout.get(ResourceKind::Skills) else {
+            return Ok(());
+        };
+        for (name, source) in self.entries()? {
+            let target = skills.path.join(&name);
+            if std::fs::symlink_metadata(&target).is_ok() {
+                continue;
+            }
+            std::fs::create_dir_all(&skills.path)?;
+            link_or_copy(&source, &target)?;
+        }
+        Ok(())
+    }
+
+    /// Removes mounted library skills from a profile that was just saved
+    /// from the live config, in both the harness's own skills directory and
+    /// the canonical one. Skills edited since they were mounted are kept.
+    pub(super) fn strip(&self, harness: &dyn HarnessConfig, profile_path: &Path) -> Result<()> {
+        let mut dirs = vec![profile_path.join(ResourceKind::Skills.canonical_dir())];
+        let layout = harness.resource_layout();
+        let native = harness.config_dir().ok().and_then(|config_dir| {
+            let skills = layout.get(ResourceKind::Skills)?;
+            Some(profile_path.join(skills.path.strip_prefix(&config_dir).ok()?))
+        });
+        dirs.extend(native);
+        dirs.dedup();
+
+        for dir in dirs {
+            let mut stripped = false;
+            for (name, source) in self.entries()? {
+                let saved = dir.join(&name);
+                let Ok(meta) = std::fs::symlink_metadata(&saved) else {
+                    continue;
+                };
+                let mounted = if meta.file_type().is_symlink() {
+                    std::fs::read_link(&saved).is_ok_and(|target| target == source)
+                } else {
+                    same_content(&source, &saved)?
+                };
+                if !mounted {
+                    continue;
+                }
+                if meta.is_dir() {
+                    std::fs::remove_dir_all(&saved)?;
+                } else {
+                    std::fs::remove_file(&saved)?;
+                }
+                stripped = true;
+            }
+            // Leave no empty skills directory behind when it only held library skills.
+            if stripped && std::fs::read_dir(&dir).is_ok_and(|mut d| d.next().is_none()) {
+                let _ = std::fs::remove_dir(&dir);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Symlinks `dst` to `source`, or copies `source` where links cannot be created.
+fn link_or_copy(source: &Path, dst: &Path) -> Result<()> {
+    if platform::symlinks_supported() {
+        #[cfg(unix)]
+        let linked = std::os::unix::fs::symlink(source, dst);
+        #[cfg(windows)]
+        let linked = if source.is_dir() {
+            std::os::windows::fs::symlink_dir(source, dst)
+        } else {
+            std::os::windows::fs::symlink_file(source, dst)
+        };
+        match linked {
+            Ok(()) => return Ok(()),
+            Err(e) if platform::is_symlink_privilege_error(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if source.is_dir() {
+        files::copy_dir_recursive(source, dst)
+    } else {
+        files::copy_file_preserving(source, dst).map_err(Into::into)
+    }
+}
+
+/// Whether `a` and `b` hold the same files with the same contents.
+fn same_content(a: &Path, b: &Path) -> Result<bool> {
+    if a.is_file() && b.is_file() {
+        return Ok(std::fs::read(a)? == std::fs::read(b)?);
+    }
+    if !(a.is_dir() && b.is_dir()) {
+        return Ok(false);
+    }
+    let mut names_a = std::fs::read_dir(a)?
+        .map(|e| e.map(|e| e.file_name()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let mut names_b = std::fs::read_dir(b)?
+        .map(|e| e.map(|e| e.file_name()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    names_a.sort();
+    names_b.sort();
+    if names_a != names_b {
+        return Ok(false);
+    }
+    for name in names_a {
+        if !same_content(&a.join(&name), &b.join(&name))? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,281 @@
+//! The timestamped snapshots [`ProfileManager::backup_current`] takes of a
+//! harness's live config before each switch.
+//!
+//! A snapshot lives in `backups/<harness>/<YYYYmmdd_HHMMSS>` and is named by
+//! that timestamp. The live config holds the active profile, so each
+//! snapshot records which profile it is a copy of in a [`SNAPSHOT_FILE`];
+//! snapshots taken before that fall back to the profile marker file, when
+//! there is one.
+
+use std::path::{Component, Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+use super::machine::MachineOverrides;
+use super::template::ProfileTemplates;
+use super::{MARKER_PREFIX, ProfileManager};
+use crate::config::profile_name::ProfileName;
+use crate::config::{BridleConfig, write_atomic};
+use crate::error::{Error, Result};
+use crate::harness::HarnessConfig;
+
+/// Names the profile a snapshot was taken of.
+pub const SNAPSHOT_FILE: &str = ".bridle-snapshot";
+
+const ID_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// One snapshot of a harness's live config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The timestamp it is stored under, e.g. `20260310_142501`.
+    pub id: String,
+    pub path: PathBuf,
+    /// The profile that was active, if one was.
+    pub profile: Option<String>,
+    pub taken_at: Option<NaiveDateTime>,
+}
+
+/// Where [`ProfileManager::restore_file`] wrote a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoredFile {
+    pub profile_file: PathBuf,
+    /// Set when the profile is active.
+    pub live_file: Option<PathBuf>,
+}
+
+impl Snapshot {
+    fn read(path: PathBuf) -> Option<Self> {
+        let id = path.file_name()?.to_str()?.to_string();
+        let taken_at = NaiveDateTime::parse_from_str(&id, ID_FORMAT).ok()?;
+        Some(Self {
+            profile: snapshot_profile(&path),
+            id,
+            path,
+            taken_at: Some(taken_at),
+        })
+    }
+
+    /// Whether `name` is a file bridle put in the snapshot rather than part
+    /// of the config.
+    pub fn is_bookkeeping(name: &str) -> bool {
+        name == SNAPSHOT_FILE || name.starts_with(MARKER_PREFIX)
+    }
+}
+
+fn snapshot_profile(path: &Path) -> Option<String> {
+    if let Ok(profile) = std::fs::read_to_string(path.join(SNAPSHOT_FILE)) {
+        return Some(profile.trim().to_string()).filter(|p| !p.is_empty());
+    }
+    std::fs::read_dir(path).ok()?.flatten().find_map(|entry| {
+        entry
+            .file_name()
+            .to_str()?
+            .strip_prefix(MARKER_PREFIX)
+            .map(String::from)
+    })
+}
+
+impl ProfileManager {
+    /// The snapshots of `harness`'s live config, oldest first.
+    pub fn snapshots(&self, harness: &dyn HarnessConfig) -> Result<Vec<Snapshot>> {
+        let dir = self.backups_dir().join(harness.id());
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut snapshots = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(snapshot) = Snapshot::read(entry.path())
+            {
+                snapshots.push(snapshot);
+            }
+        }
+        snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(snapshots)
+    }
+
+    /// The snapshot of `harness`'s live config named `id`.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] when there is no such snapshot.
+    pub fn snapshot(&self, harness: &dyn HarnessConfig, id: &str) -> Result<Snapshot> {
+        self.snapshots(harness)?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| Error::Config(format!("no snapshot {id} of {}", harness.id())))
+    }
+
+    /// Copies the file at `path`, relative to the config directory, out of
+    /// `snapshot` into profile `name`, and into the live config too when the
+    /// profile is active. A file this machine has a variant of is written
+    /// into the variant.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] when `path` leaves the config directory, is
+    /// not a file in the snapshot, or is a template in the profile, whose
+    /// placeholders and secret references the snapshot has filled in.
+    pub fn restore_file(
+        &self,
+        harness: &dyn HarnessConfig,
+        name: &ProfileName,
+        snapshot: &Snapshot,
+        path: &Path,
+    ) -> Result<RestoredFile> {
+        if path.as_os_str().is_empty()
+            || !path.components().all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(Error::Config(format!(
+                "{} is not a path inside the config directory",
+                path.display()
+            )));
+        }
+        if path.to_str().is_some_and(Snapshot::is_bookkeeping) {
+            return Err(Error::Config(format!(
+                "{} is not a config file",
+                path.display()
+            )));
+        }
+        let source = snapshot.path.join(path);
+        if !source.is_file() {
+            return Err(Error::Config(format!(
+                "{} is not a file in snapshot {}",
+                path.display(),
+                snapshot.id
+            )));
+        }
+        let profile_path = self.profile_path(harness, name);
+        if !profile_path.exists() {
+            return Err(Error::profile_not_found(harness.id(), name.as_str()));
+        }
+        let templates = ProfileTemplates::scan(&profile_path, harness.mcp_filename().as_deref())?;
+        if templates.contains(path) {
+            return Err(Error::Config(format!(
+                "{} is a template in profile {name}; edit it with `bridle profile edit`",
+                path.display()
+            )));
+        }
+
+        let overrides = MachineOverrides::load(&profile_path)?;
+        let profile_file = profile_path.join(overrides.variant_of(path).unwrap_or(path));
+        let active = BridleConfig::load()
+            .ok()
+            .and_then(|c| c.active_profile_for(harness.id()).map(String::from))
+            .is_some_and(|active| active == name.as_str());
+        let live_file = if active {
+            let config_dir = harness.config_dir()?;
+            Some(match harness.mcp_config_path() {
+                Some(mcp_path)
+                    if mcp_path.file_name().is_some_and(|f| Path::new(f) == path)
+                        && !mcp_path.starts_with(&config_dir) =>
+                {
+                    mcp_path
+                }
+                _ => config_dir.join(path),
+            })
+        } else {
+            None
+        };
+
+        if !self.options.dry_run {
+            let content = std::fs::read(&source)?;
+            write_atomic(&profile_file, &content)?;
+            if let Some(live_file) = &live_file {
+                write_atomic(live_file, &content)?;
+            }
+        }
+        Ok(RestoredFile {
+            profile_file,
+            live_file,
+        })
+    }
+
+    /// Writes which profile is active for `harness` into the snapshot at `path`.
+    pub(super) fn label_snapshot(&self, harness: &dyn HarnessConfig, path: &Path) -> Result<()> {
+        if let Some(profile) = BridleConfig::load()
+            .ok()
+            .and_then(|c| c.active_profile_for(harness.id()).map(String::from))
+        {
+            std::fs::write(path.join(SNAPSHOT_FILE), format!("{profile}\n"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn reads_the_profile_a_snapshot_was_taken_of() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let labelled = temp.path().join("20260310_142501");
+        let marked = temp.path().join("20260309_080000");
+        fs::create_dir_all(&labelled).unwrap();
+        fs::create_dir_all(&marked).unwrap();
+        fs::write(labelled.join(SNAPSHOT_FILE), "work\n").unwrap();
+        fs::write(marked.join("BRIDLE_PROFILE_home"), "").unwrap();
+
+        let snapshot = Snapshot::read(labelled).unwrap();
+        assert_eq!(snapshot.profile.as_deref(), Some("work"));
+        assert_eq!(
+            snapshot.taken_at.unwrap().to_string(),
+            "2026-03-10 14:25:01"
+        );
+        assert_eq!(
+            Snapshot::read(marked).unwrap().profile.as_deref(),
+            Some("home")
+        );
+        assert!(Snapshot::read(temp.path().join("extra")).is_none());
+    }
+
+    #[test]
+    fn restores_one_file_into_the_profile() {
+        use crate::test_utils::MockHarness;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let manager = ProfileManager::new(temp.path().join("profiles"));
+        let harness = MockHarness::new("test-restore-file", temp.path().join("live"));
+        let name = ProfileName::new("work").unwrap();
+        let profile = manager.profile_path(&harness, &name);
+        fs::create_dir_all(&profile).unwrap();
+        fs::write(profile.join("settings.json"), "new").unwrap();
+        fs::write(profile.join("keys.json"), "{{ api_key }}").unwrap();
+        let taken = manager
+            .backups_dir()
+            .join("test-restore-file/20260310_142501");
+        fs::create_dir_all(taken.join("agents")).unwrap();
+        fs::write(taken.join("settings.json"), "old").unwrap();
+        fs::write(taken.join("keys.json"), "sk-live").unwrap();
+        fs::write(taken.join("agents/review.md"), "review").unwrap();
+        let snapshot = manager.snapshot(&harness, "20260310_142501").unwrap();
+
+        let restored = manager
+            .restore_file(&harness, &name, &snapshot, Path::new("settings.json"))
+            .unwrap();
+        assert_eq!(restored.profile_file, profile.join("settings.json"));
+        assert_eq!(restored.live_file, None);
+        assert_eq!(
+            fs::read_to_string(profile.join("settings.json")).unwrap(),
+            "old"
+        );
+        manager
+            .restore_file(&harness, &name, &snapshot, Path::new("agents/review.md"))
+            .unwrap();
+        assert!(profile.join("agents/review.md").is_file());
+
+        for rejected in ["keys.json", "../settings.json", "missing.json", "agents"] {
+            assert!(
+                manager
+                    .restore_file(&harness, &name, &snapshot, Path::new(rejected))
+                    .is_err(),
+                "{rejected}"
+            );
+        }
+        assert_eq!(
+            fs::read_to_string(profile.join("keys.json")).unwrap(),
+            "{{ api_key }}"
+        );
+    }
+}
@@ -0,0 +1,595 @@
+//! `{{ var }}` placeholders in profile config files, rendered on switch.
+//!
+//! Config files in a profile (JSON, TOML, YAML, `.env`) may contain
+//! placeholders such as `{{ api_key }}`. When the profile is applied, each is
+//! replaced with a value from, in order: the `BRIDLE_VAR_<NAME>` environment
+//! variable, the profile's [`VARS_FILE`], or the manager's variable prompt.
+//! `bridle-secret://<name>` references, in the files or in variable values,
+//! are then replaced from the manager's [`SecretStore`]. The profile keeps the
+//! template; saving the live config back never writes rendered values into it.
+//!
+//! The profile's MCP config file may also use `${NAME}` references, as MCP
+//! server definitions elsewhere do; they take their values from the same
+//! sources as `{{ NAME }}`. References with a default or other shell syntax,
+//! such as `${NAME:-x}`, are left for the harness to expand.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::secrets::{self, SECRET_SCHEME};
+use crate::config::{SecretStore, write_atomic};
+use crate::error::{Error, Result};
+
+/// Per-profile variable values, kept in the profile and never copied to the live config.
+pub const VARS_FILE: &str = "bridle-vars.toml";
+
+/// Prefix of environment variables that supply template values.
+pub const VAR_ENV_PREFIX: &str = "BRIDLE_VAR_";
+
+const TEMPLATED_EXTENSIONS: &[&str] = &["json", "jsonc", "toml", "yaml", "yml", "env"];
+
+/// Asks for a variable's value when no other source has one.
+pub type VarPrompt = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+/// Values for every placeholder and secret reference in a profile.
+#[derive(Debug, Default)]
+pub(super) struct Values {
+    vars: BTreeMap<String, String>,
+    secrets: BTreeMap<String, String>,
+}
+
+impl Values {
+    fn render(&self, text: &str, mcp: bool) -> String {
+        let text = render_vars(text, &self.vars, mcp);
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        secrets::for_each_reference(&text, |range, name| {
+            if let Some(value) = self.secrets.get(name) {
+                out.push_str(&text[last..range.start]);
+                out.push_str(value);
+                last = range.end;
+            }
+        });
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+/// Where the value of a reference comes from on switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueSource {
+    /// A `BRIDLE_VAR_<NAME>` environment variable.
+    Environment,
+    /// The profile's [`VARS_FILE`].
+    VarsFile,
+    /// The keyring or a secret provider.
+    SecretStore,
+    /// Nothing; switching would prompt for a variable, or fail.
+    Missing,
+}
+
+/// A variable or secret a profile file references, and its value's source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    /// File the reference is in, relative to the profile root.
+    pub file: PathBuf,
+    /// As written: `${NAME}`, `{{ name }}`, or `bridle-secret://name`.
+    pub reference: String,
+    /// The variable or secret name.
+    pub name: String,
+    pub source: ValueSource,
+}
+
+/// The templated files of one profile.
+#[derive(Debug, Default)]
+pub(super) struct ProfileTemplates {
+    /// Path relative to the profile root, and the file's template text.
+    files: Vec<(PathBuf, String)>,
+    /// The profile's top-level MCP config file, where `${NAME}` is expanded too.
+    mcp_file: Option<PathBuf>,
+}
+
+impl ProfileTemplates {
+    /// Finds files under `profile_path` that contain placeholders or secret
+    /// references, counting `${NAME}` in the MCP config file `mcp_file`.
+    pub(super) fn scan(profile_path: &Path, mcp_file: Option<&str>) -> Result<Self> {
+        let mcp_file = mcp_file.map(PathBuf::from);
+        let mut files = Vec::new();
+        let mut pending = vec![PathBuf::new()];
+        while let Some(rel) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(profile_path.join(&rel)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let rel = rel.join(entry.file_name());
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    pending.push(rel);
+                } else if file_type.is_file()
+                    && rel != Path::new(VARS_FILE)
+                    && is_templated(&rel)
+                    && let Ok(text) = std::fs::read_to_string(entry.path())
+                    && (!placeholders(&text).is_empty()
+                        || text.contains(SECRET_SCHEME)
+                        || (mcp_file.as_ref() == Some(&rel) && !env_references(&text).is_empty()))
+                {
+                    files.push((rel, text));
+                }
+            }
+        }
+        files.sort();
+        Ok(Self { files, mcp_file })
+    }
+
+    /// Leaves out `paths`, which are written from somewhere else.
+    pub(super) fn skip<'a>(&mut self, paths: impl IntoIterator<Item = &'a Path>) {
+        for path in paths {
+            self.files.retain(|(rel, _)| rel != path);
+        }
+    }
+
+    fn is_mcp(&self, rel: &Path) -> bool {
+        self.mcp_file.as_deref() == Some(rel)
+    }
+
+    /// Variable names referenced in the file `rel` with template `text`.
+    fn variables_in<'a>(&self, rel: &Path, text: &'a str) -> Vec<&'a str> {
+        let mut names = placeholders(text);
+        if self.is_mcp(rel) {
+            names.extend(env_references(text));
+        }
+        names
+    }
+
+    /// Whether the file at `path`, relative to the profile, is a template.
+    pub(super) fn contains(&self, path: &Path) -> bool {
+        self.files.iter().any(|(rel, _)| rel == path)
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Every variable name the templates reference.
+    pub(super) fn variables(&self) -> BTreeSet<String> {
+        self.files
+            .iter()
+            .flat_map(|(rel, text)| self.variables_in(rel, text))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Resolves all variables and secrets for the profile at `profile_path`.
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingVariable`] for the first variable no source
+    /// provides, or [`Error::MissingSecret`] for a secret `store` lacks.
+    pub(super) fn resolve(
+        &self,
+        profile_path: &Path,
+        profile: &str,
+        prompt: Option<&VarPrompt>,
+        store: &dyn SecretStore,
+    ) -> Result<Values> {
+        let file_vars = load_vars_file(profile_path)?;
+        let mut vars = BTreeMap::new();
+        for name in self.variables() {
+            let value = std::env::var(env_name(&name))
+                .ok()
+                .or_else(|| file_vars.get(&name).cloned())
+                .or_else(|| prompt.and_then(|p| p(&name)))
+                .ok_or_else(|| Error::MissingVariable {
+                    name: name.clone(),
+                    profile: profile.to_string(),
+                })?;
+            vars.insert(name, value);
+        }
+
+        let mut secrets = BTreeMap::new();
+        for (rel, text) in &self.files {
+            let text = render_vars(text, &vars, self.is_mcp(rel));
+            for name in secrets::references(&text) {
+                if secrets.contains_key(name) {
+                    continue;
+                }
+                let value = store.get(name)?.ok_or_else(|| Error::MissingSecret {
+                    name: name.to_string(),
+                })?;
+                secrets.insert(name.to_string(), value);
+            }
+        }
+        Ok(Values { vars, secrets })
+    }
+
+    /// Renders the templates over their copies in `live_dir`, and over
+    /// `mcp_path` when it is the profile's top-level MCP file.
+    pub(super) fn render_into(
+        &self,
+        live_dir: &Path,
+        mcp_path: Option<&Path>,
+        values: &Values,
+    ) -> Result<()> {
+        for (rel, text) in &self.files {
+            let rendered = values.render(text, self.is_mcp(rel));
+            write_atomic(&live_dir.join(rel), &rendered)?;
+            if let Some(mcp_path) = mcp_path
+                && mcp_path.file_name().is_some_and(|n| Path::new(n) == rel)
+                && !mcp_path.starts_with(live_dir)
+            {
+                write_atomic(mcp_path, &rendered)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts the templates back into `profile_path` after the live config was
+    /// saved over it, warning about live edits to rendered files that are
+    /// being dropped.
+    pub(super) fn restore(
+        &self,
+        profile_path: &Path,
+        live_dir: &Path,
+        store: &dyn SecretStore,
+    ) -> Result<()> {
+        let values = self.resolve(profile_path, "", None, store).ok();
+        for (rel, text) in &self.files {
+            if let Some(values) = &values
+                && let Ok(live) = std::fs::read_to_string(live_dir.join(rel))
+                && live != values.render(text, self.is_mcp(rel))
+            {
+                eprintln!(
+                    "Warning: {} is rendered from a template; edit the template in the profile \
+                     instead. Live changes to it were not saved.",
+                    rel.display()
+                );
+            }
+            write_atomic(&profile_path.join(rel), text)?;
+        }
+        Ok(())
+    }
+
+    /// Every reference in the MCP config file and where its value comes from,
+    /// without prompting or failing on missing values.
+    ///
+    /// # Errors
+    /// Returns an error if the vars file does not parse or the secret store
+    /// cannot be read.
+    pub(super) fn check_mcp(
+        &self,
+        profile_path: &Path,
+        store: &dyn SecretStore,
+    ) -> Result<Vec<Reference>> {
+        let file_vars = load_vars_file(profile_path)?;
+        let mut references = Vec::new();
+        for (rel, text) in self.files.iter().filter(|(rel, _)| self.is_mcp(rel)) {
+            let mut vars = BTreeMap::new();
+            let mut push = |reference: String, name: &str, source| {
+                references.push(Reference {
+                    file: rel.clone(),
+                    reference,
+                    name: name.to_string(),
+                    source,
+                })
+            };
+            for name in placeholders(text) {
+                let (value, source) = var_source(name, &file_vars);
+                push(format!("{{{{ {name} }}}}"), name, source);
+                vars.extend(value.map(|v| (name.to_string(), v)));
+            }
+            for name in env_references(text) {
+                let (value, source) = var_source(name, &file_vars);
+                push(format!("${{{name}}}"), name, source);
+                vars.extend(value.map(|v| (name.to_string(), v)));
+            }
+            let rendered = render_vars(text, &vars, true);
+            for name in secrets::references(&rendered) {
+                let source = match store.get(name)? {
+                    Some(_) => ValueSource::SecretStore,
+                    None => ValueSource::Missing,
+                };
+                push(format!("{SECRET_SCHEME}{name}"), name, source);
+            }
+        }
+        Ok(references)
+    }
+}
+
+/// The value of variable `name` and where it comes from, before any prompt.
+fn var_source(name: &str, file_vars: &BTreeMap<String, String>) -> (Option<String>, ValueSource) {
+    if let Ok(value) = std::env::var(env_name(name)) {
+        (Some(value), ValueSource::Environment)
+    } else if let Some(value) = file_vars.get(name) {
+        (Some(value.clone()), ValueSource::VarsFile)
+    } else {
+        (None, ValueSource::Missing)
+    }
+}
+
+fn is_templated(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == ".env")
+        || path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| TEMPLATED_EXTENSIONS.contains(&e))
+}
+
+/// Environment variable that supplies `name`, e.g. `BRIDLE_VAR_API_KEY` for `api-key`.
+pub fn env_name(name: &str) -> String {
+    let suffix: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{VAR_ENV_PREFIX}{suffix}")
+}
+
+fn load_vars_file(profile_path: &Path) -> Result<BTreeMap<String, String>> {
+    let path = profile_path.join(VARS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    let table: toml::Table = toml::from_str(&content).map_err(|e| Error::ConfigParse {
+        path: path.clone(),
+        message: e.to_string(),
+    })?;
+    Ok(table
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (key, value)
+        })
+        .collect())
+}
+
+/// Splits `text` at each `{{ name }}` placeholder, calling `on_var` with the
+/// byte range and name of each. Braces around anything but a plain name are
+/// left alone.
+fn for_each_placeholder(text: &str, mut on_var: impl FnMut(std::ops::Range<usize>, &str)) {
+    let mut from = 0;
+    while let Some(open) = text[from..].find("{{").map(|i| from + i) {
+        let Some(close) = text[open + 2..].find("}}").map(|i| open + 2 + i) else {
+            break;
+        };
+        let name = text[open + 2..close].trim();
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if valid {
+            on_var(open..close + 2, name);
+            from = close + 2;
+        } else {
+            from = open + 2;
+        }
+    }
+}
+
+/// Variable names referenced in `text`.
+pub fn placeholders(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    for_each_placeholder(text, |range, _| {
+        let name = text[range].trim_matches(|c| c == '{' || c == '}').trim();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    });
+    names
+}
+
+/// Replaces placeholders with values from `vars`; unknown names are kept as-is.
+pub fn render(text: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for_each_placeholder(text, |range, name| {
+        if let Some(value) = vars.get(name) {
+            out.push_str(&text[last..range.start]);
+            out.push_str(value);
+            last = range.end;
+        }
+    });
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Splits `text` at each `${NAME}` reference, calling `on_var` with the byte
+/// range and name of each. Only plain shell variable names count, so
+/// `${NAME:-default}` and similar expansions are left alone.
+fn for_each_env_reference(text: &str, mut on_var: impl FnMut(std::ops::Range<usize>, &str)) {
+    let mut from = 0;
+    while let Some(open) = text[from..].find("${").map(|i| from + i) {
+        let Some(close) = text[open + 2..].find('}').map(|i| open + 2 + i) else {
+            break;
+        };
+        let name = &text[open + 2..close];
+        let valid = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if valid {
+            on_var(open..close + 1, name);
+            from = close + 1;
+        } else {
+            from = open + 2;
+        }
+    }
+}
+
+/// Variable names referenced as `${NAME}` in `text`.
+pub fn env_references(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    for_each_env_reference(text, |range, _| {
+        let name = &text[range.start + 2..range.end - 1];
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    });
+    names
+}
+
+/// Renders `{{ name }}` placeholders and, in an MCP config file, `${NAME}`
+/// references; unknown names are kept as-is.
+fn render_vars(text: &str, vars: &BTreeMap<String, String>, mcp: bool) -> String {
+    if !mcp {
+        return render(text, vars);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for_each_env_reference(text, |range, name| {
+        if let Some(value) = vars.get(name) {
+            out.push_str(&text[last..range.start]);
+            out.push_str(value);
+            last = range.end;
+        }
+    });
+    out.push_str(&text[last..]);
+    render(&out, vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_plain_names_only() {
+        let text = r#"{"key": "{{ api_key }}", "url": "{{endpoint}}", "x": "{{ not a var }}"}"#;
+        assert_eq!(placeholders(text), ["api_key", "endpoint"]);
+
+        let vars = BTreeMap::from([("api_key".to_string(), "sk-1".to_string())]);
+        assert_eq!(
+            render(text, &vars),
+            r#"{"key": "sk-1", "url": "{{endpoint}}", "x": "{{ not a var }}"}"#
+        );
+        assert_eq!(env_name("api-key"), "BRIDLE_VAR_API_KEY");
+    }
+
+    #[test]
+    fn resolves_from_vars_file_then_prompt() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("settings.json"),
+            r#"{"a": "{{ from_file }}", "b": "{{ from_prompt }}"}"#,
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("notes.md"), "{{ ignored }}").unwrap();
+        std::fs::write(temp.path().join(VARS_FILE), "from_file = \"f\"\n").unwrap();
+
+        let templates = ProfileTemplates::scan(temp.path(), None).unwrap();
+        assert_eq!(
+            templates.variables().into_iter().collect::<Vec<_>>(),
+            ["from_file", "from_prompt"]
+        );
+        let err = templates
+            .resolve(temp.path(), "work", None, &NoSecrets)
+            .unwrap_err();
+        assert!(matches!(err, Error::MissingVariable { ref name, .. } if name == "from_prompt"));
+
+        let prompt = |name: &str| Some(format!("asked-{name}"));
+        let values = templates
+            .resolve(temp.path(), "work", Some(&prompt), &NoSecrets)
+            .unwrap();
+        assert_eq!(values.vars["from_file"], "f");
+        assert_eq!(values.vars["from_prompt"], "asked-from_prompt");
+    }
+
+    #[test]
+    fn expands_env_references_only_in_the_mcp_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mcp =
+            r#"{"env": {"TOKEN": "${GITHUB_TOKEN}", "HOME": "${HOME:-/root}", "K": "${KEY}"}}"#;
+        std::fs::write(temp.path().join(".mcp.json"), mcp).unwrap();
+        std::fs::write(temp.path().join("settings.json"), r#"{"a": "${SHELL}"}"#).unwrap();
+        std::fs::write(
+            temp.path().join(VARS_FILE),
+            "GITHUB_TOKEN = \"bridle-secret://gh\"\n",
+        )
+        .unwrap();
+        assert_eq!(env_references(mcp), ["GITHUB_TOKEN", "KEY"]);
+
+        let templates = ProfileTemplates::scan(temp.path(), Some(".mcp.json")).unwrap();
+        assert_eq!(
+            templates.variables().into_iter().collect::<Vec<_>>(),
+            ["GITHUB_TOKEN", "KEY"]
+        );
+        let checked: Vec<_> = templates
+            .check_mcp(temp.path(), &NoSecrets)
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.reference, r.source))
+            .collect();
+        assert_eq!(
+            checked,
+            [
+                ("${GITHUB_TOKEN}".to_string(), ValueSource::VarsFile),
+                ("${KEY}".to_string(), ValueSource::Missing),
+                ("bridle-secret://gh".to_string(), ValueSource::Missing),
+            ]
+        );
+
+        let values = Values {
+            vars: BTreeMap::from([("KEY".to_string(), "k".to_string())]),
+            secrets: BTreeMap::new(),
+        };
+        assert_eq!(
+            values.render(mcp, true),
+            r#"{"env": {"TOKEN": "${GITHUB_TOKEN}", "HOME": "${HOME:-/root}", "K": "k"}}"#
+        );
+        assert_eq!(values.render(r#"${KEY}"#, false), "${KEY}");
+    }
+
+    struct NoSecrets;
+
+    impl SecretStore for NoSecrets {
+        fn get(&self, _name: &str) -> Result<Option<String>> {
+            Ok(None)
+        }
+    }
+
+    struct OneSecret;
+
+    impl SecretStore for OneSecret {
+        fn get(&self, name: &str) -> Result<Option<String>> {
+            Ok((name == "work-key").then(|| "sk-secret".to_string()))
+        }
+    }
+
+    #[test]
+    fn resolves_secret_references_in_files_and_vars() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("settings.json"),
+            r#"{"a": "bridle-secret://work-key", "b": "{{ key }}"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join(VARS_FILE),
+            "key = \"bridle-secret://work-key\"\n",
+        )
+        .unwrap();
+
+        let templates = ProfileTemplates::scan(temp.path(), None).unwrap();
+        let values = templates
+            .resolve(temp.path(), "work", None, &OneSecret)
+            .unwrap();
+        assert_eq!(
+            values.render(&templates.files[0].1, false),
+            r#"{"a": "sk-secret", "b": "sk-secret"}"#
+        );
+        let err = templates
+            .resolve(temp.path(), "work", None, &NoSecrets)
+            .unwrap_err();
+        assert!(matches!(err, Error::MissingSecret { ref name } if name == "work-key"));
+    }
+}
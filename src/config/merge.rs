@@ -0,0 +1,134 @@
+//! Three-way merges of profile directories, file by file.
+//!
+//! A profile synced between machines has three versions: the local one, the
+//! remote one, and the base, which is the profile as it was when the two
+//! last agreed. A file changed on one side only takes that side's version; a
+//! file changed on both sides to different contents is a conflict. Files are
+//! compared whole, so edits to different files always merge cleanly and any
+//! two edits to the same file conflict.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// A profile's files by path relative to the profile directory.
+pub type FileTree = BTreeMap<PathBuf, Vec<u8>>;
+
+/// A file changed differently on both sides. `None` means deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub path: PathBuf,
+    pub local: Option<Vec<u8>>,
+    pub remote: Option<Vec<u8>>,
+}
+
+impl Conflict {
+    /// Both versions in one file between conflict markers, when both exist
+    /// and are text.
+    pub fn with_markers(&self) -> Option<Vec<u8>> {
+        let local = std::str::from_utf8(self.local.as_deref()?).ok()?;
+        let remote = std::str::from_utf8(self.remote.as_deref()?).ok()?;
+        let line = |text: &str| {
+            if text.is_empty() || text.ends_with('\n') {
+                text.to_string()
+            } else {
+                format!("{text}\n")
+            }
+        };
+        Some(
+            format!(
+                "<<<<<<< local\n{}=======\n{}>>>>>>> remote\n",
+                line(local),
+                line(remote)
+            )
+            .into_bytes(),
+        )
+    }
+}
+
+/// What merging the remote version into the local one takes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MergePlan {
+    /// Files to take from the remote: new contents, or `None` to delete.
+    pub changes: BTreeMap<PathBuf, Option<Vec<u8>>>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergePlan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.conflicts.is_empty()
+    }
+}
+
+/// Plans merging `remote` into `local`, with `base` their last common
+/// version. Without a base every difference is a conflict.
+pub fn plan(base: Option<&FileTree>, local: &FileTree, remote: &FileTree) -> MergePlan {
+    let paths: BTreeSet<&PathBuf> = local
+        .keys()
+        .chain(remote.keys())
+        .chain(base.into_iter().flat_map(|b| b.keys()))
+        .collect();
+    let mut plan = MergePlan::default();
+    for path in paths {
+        let ours = local.get(path);
+        let theirs = remote.get(path);
+        if ours == theirs {
+            continue;
+        }
+        match base.map(|b| b.get(path)) {
+            Some(ancestor) if ancestor == ours => {
+                plan.changes.insert(path.clone(), theirs.cloned());
+            }
+            Some(ancestor) if ancestor == theirs => {}
+            _ => plan.conflicts.push(Conflict {
+                path: path.clone(),
+                local: ours.cloned(),
+                remote: theirs.cloned(),
+            }),
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(files: &[(&str, &str)]) -> FileTree {
+        files
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn takes_one_sided_changes_and_flags_overlaps() {
+        let base = tree(&[("a", "1"), ("b", "1"), ("c", "1"), ("gone", "1")]);
+        let local = tree(&[("a", "2"), ("b", "1"), ("c", "local"), ("gone", "1")]);
+        let remote = tree(&[("a", "1"), ("b", "2"), ("c", "remote"), ("new", "1")]);
+        let plan = plan(Some(&base), &local, &remote);
+        assert_eq!(
+            plan.changes,
+            BTreeMap::from([
+                (PathBuf::from("b"), Some(b"2".to_vec())),
+                (PathBuf::from("gone"), None),
+                (PathBuf::from("new"), Some(b"1".to_vec())),
+            ])
+        );
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].path, PathBuf::from("c"));
+        assert_eq!(
+            plan.conflicts[0].with_markers().unwrap(),
+            b"<<<<<<< local\nlocal\n=======\nremote\n>>>>>>> remote\n"
+        );
+    }
+
+    #[test]
+    fn without_a_base_every_difference_conflicts() {
+        let local = tree(&[("a", "1"), ("b", "1")]);
+        let remote = tree(&[("a", "1"), ("b", "2")]);
+        let plan = plan(None, &local, &remote);
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.conflicts.len(), 1);
+        assert!(plan.conflicts[0].local.is_some());
+    }
+}
@@ -0,0 +1,200 @@
+//! Versioning and upgrades for `config.toml`.
+//!
+//! Each entry in [`MIGRATIONS`] upgrades the raw TOML table by one version.
+//! [`BridleConfig::load`](super::BridleConfig::load) runs them before
+//! deserializing and rewrites the file, keeping a backup of the original.
+
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use crate::error::{Error, Result};
+
+/// Version written by this build of bridle.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Live config directories of known harnesses, used to read marker-file state.
+pub type MarkerDirs = [(String, PathBuf)];
+
+type Migration = fn(&mut Table, &MarkerDirs);
+
+/// `MIGRATIONS[n]` upgrades a version `n` table to version `n + 1`.
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+const MARKER_PREFIX: &str = "BRIDLE_PROFILE_";
+
+/// Returns the version recorded in `table`; files predating versioning are version 0.
+pub fn version_of(table: &Table) -> u32 {
+    table
+        .get("version")
+        .and_then(Value::as_integer)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Upgrades `table` to [`CURRENT_VERSION`].
+///
+/// Returns the version the table started at if anything changed.
+///
+/// # Errors
+/// Returns [`Error::Config`] if the table was written by a newer bridle.
+pub fn migrate(table: &mut Table, marker_dirs: &MarkerDirs) -> Result<Option<u32>> {
+    let from = version_of(table);
+    if from > CURRENT_VERSION {
+        return Err(Error::Config(format!(
+            "config version {from} is newer than this bridle supports ({CURRENT_VERSION})"
+        )));
+    }
+    if from == CURRENT_VERSION {
+        return Ok(None);
+    }
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(table, marker_dirs);
+    }
+    table.insert(
+        "version".to_string(),
+        Value::Integer(i64::from(CURRENT_VERSION)),
+    );
+    Ok(Some(from))
+}
+
+/// Path of the backup written before migrating a version `from` config.
+pub fn backup_path(config_path: &Path, from: u32) -> PathBuf {
+    let name = config_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "config.toml".to_string());
+    config_path.with_file_name(format!("{name}.v{from}.bak"))
+}
+
+/// Returns the profile named by a `BRIDLE_PROFILE_<name>` marker in `dir`.
+fn marker_profile(dir: &Path) -> Option<String> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .find_map(|name| name.strip_prefix(MARKER_PREFIX).map(String::from))
+}
+
+/// v0 → v1:
+/// - the single legacy `active_profile` becomes an entry in `[active]`,
+/// - marker files left in harness config dirs seed `[active]` and `profile_marker`,
+/// - the top-level `editor` moves to `settings.editor`.
+fn migrate_v0_to_v1(table: &mut Table, marker_dirs: &MarkerDirs) {
+    let mut active = match table.remove("active") {
+        Some(Value::Table(active)) => active,
+        _ => Table::new(),
+    };
+
+    let markers: Vec<(&str, String)> = marker_dirs
+        .iter()
+        .filter_map(|(id, dir)| marker_profile(dir).map(|p| (id.as_str(), p)))
+        .collect();
+
+    if let Some(Value::String(legacy)) = table.remove("active_profile") {
+        // Either `harness/profile`, or a bare name matched against marker files.
+        let harness = match legacy.split_once('/') {
+            Some((harness, profile)) => Some((harness.to_string(), profile.to_string())),
+            None => markers
+                .iter()
+                .find(|(_, profile)| *profile == legacy)
+                .map(|(id, profile)| (id.to_string(), profile.clone())),
+        };
+        if let Some((harness, profile)) = harness {
+            active
+                .entry(harness)
+                .or_insert_with(|| Value::String(profile));
+        }
+    }
+
+    if !markers.is_empty() {
+        for (id, profile) in &markers {
+            active
+                .entry(id.to_string())
+                .or_insert_with(|| Value::String(profile.clone()));
+        }
+        table
+            .entry("profile_marker")
+            .or_insert(Value::Boolean(true));
+    }
+
+    if !active.is_empty() {
+        table.insert("active".to_string(), Value::Table(active));
+    }
+
+    if let Some(editor) = table.remove("editor") {
+        let settings = table
+            .entry("settings")
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Value::Table(settings) = settings {
+            settings.entry("editor").or_insert(editor);
+        }
+    }
+}
+
+/// v1 → v2: `[active]` moves to the state directory.
+///
+/// The table is left as is: `BridleConfig::load` still reads `[active]` from
+/// it, and the rewrite after migration persists it to the state file instead.
+fn migrate_v1_to_v2(_table: &mut Table, _marker_dirs: &MarkerDirs) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn parse(content: &str) -> Table {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn current_version_is_left_alone() {
+        let mut table = parse(&format!("version = {CURRENT_VERSION}\neditor = \"vim\"\n"));
+        assert_eq!(migrate(&mut table, &[]).unwrap(), None);
+        assert!(table.contains_key("editor"));
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let mut table = parse(&format!("version = {}\n", CURRENT_VERSION + 1));
+        assert!(matches!(migrate(&mut table, &[]), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn v0_moves_legacy_fields() {
+        let mut table = parse(
+            "active_profile = \"opencode/work\"\neditor = \"code --wait\"\n\n[active]\ngoose = \"home\"\n",
+        );
+        assert_eq!(migrate(&mut table, &[]).unwrap(), Some(0));
+
+        assert_eq!(version_of(&table), CURRENT_VERSION);
+        assert!(!table.contains_key("active_profile"));
+        assert!(!table.contains_key("editor"));
+        let active = table["active"].as_table().unwrap();
+        assert_eq!(active["opencode"].as_str(), Some("work"));
+        assert_eq!(active["goose"].as_str(), Some("home"));
+        assert_eq!(table["settings"]["editor"].as_str(), Some("code --wait"));
+    }
+
+    #[test]
+    fn v0_reads_marker_files() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("BRIDLE_PROFILE_work"), "").unwrap();
+        let dirs = [("claude-code".to_string(), temp.path().to_path_buf())];
+
+        let mut table = parse("active_profile = \"work\"\n");
+        migrate(&mut table, &dirs).unwrap();
+
+        assert_eq!(table["active"]["claude-code"].as_str(), Some("work"));
+        assert_eq!(table["profile_marker"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn backup_path_keeps_original_name() {
+        let path = Path::new("/tmp/bridle/config.toml");
+        assert_eq!(
+            backup_path(path, 0),
+            PathBuf::from("/tmp/bridle/config.toml.v0.bak")
+        );
+    }
+}
@@ -3,13 +3,50 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+mod atomic;
 mod bridle;
 pub mod jsonc;
+pub mod layers;
 mod manager;
+pub mod merge;
+pub mod migration;
+mod notify;
+mod policy;
 mod profile_name;
+mod project;
+mod remote;
+mod secrets;
+mod size;
+mod state;
+mod tokens;
 mod types;
 
-pub use bridle::{BridleConfig, TuiConfig, ViewPreference};
-pub use manager::ProfileManager;
+pub use atomic::write_atomic;
+pub use bridle::{
+    BridleConfig, ColorChoice, ContextConfig, DEFAULT_CONTEXT, HarnessOverride, Settings,
+    Subscription, TuiConfig, ViewPreference,
+};
+pub use manager::{
+    CopyStrategy, Drift, DriftAction, DriftChange, ExtractionLimits, LargeFilePolicy, LiveEntry,
+    MACHINE_INFIX, PROFILE_FILE, ProfileEvent, ProfileManager, ProfileManagerBuilder,
+    ProfileObserver, ProfileSettings, RecoveryAction, RestoredFile, SHARED_DIR, SNAPSHOT_FILE,
+    Snapshot, SnapshotPolicy, SwitchJournal, SwitchStep, SwitchStrategy, SymlinkPolicy,
+    machine_name, rules, template,
+};
+pub use notify::{Notification, Notifier, NotifyChannel, NotifyEvent, NotifyTarget};
+pub use policy::{Policy, PolicyRule, Violation};
 pub use profile_name::{InvalidProfileName, ProfileName};
-pub use types::{McpServerInfo, ProfileInfo, ResourceSummary};
+pub use project::{PROJECT_FILE, ProjectFile, TrustedProjects};
+pub use remote::{Remote, strip_host_flag};
+pub use secrets::{
+    Keyring, SECRET_SCHEME, SecretIndex, SecretProvider, SecretResolver, SecretStore,
+    validate_secret_name,
+};
+pub use size::{format_size, parse_size};
+pub use state::PersistedState;
+pub use state::{ProfileUsage, StatePaths};
+pub use tokens::{LARGE_RULES_TOKENS, LARGE_SKILL_TOKENS, estimate_tokens, format_tokens};
+pub use types::{
+    ExtractionCategory, ExtractionIssue, ExtractionIssueKind, McpServerInfo, OverriddenSetting,
+    ProfileInfo, ResourceMeta, ResourceSummary, SettingsLayer,
+};
@@ -0,0 +1,321 @@
+//! Notification targets fired after profile switches and installs.
+//!
+//! Declared as `[[notify]]` tables in `config.toml`:
+//!
+//! ```toml
+//! [[notify]]
+//! type = "webhook"
+//! url = "https://audit.example.com/bridle"
+//! events = ["switch"]
+//!
+//! [[notify]]
+//! type = "command"
+//! command = "logger -t bridle"
+//!
+//! [[notify]]
+//! type = "desktop"
+//! ```
+//!
+//! Every target receives the same JSON [`Notification`]: webhooks as the POST
+//! body, commands on stdin. A failing target prints a warning and never fails
+//! the operation that triggered it.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::manager::host_name;
+use super::{BridleConfig, ProfileEvent, ProfileObserver};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events a [`NotifyTarget`] can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// A profile was switched in.
+    Switch,
+    /// `bridle install` finished installing into a profile.
+    Install,
+}
+
+/// How a notification is delivered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifyChannel {
+    /// Runs `command` through the shell with the payload on stdin.
+    Command { command: String },
+    /// Shows a desktop notification (`notify-send` on Linux, `osascript` on macOS).
+    Desktop,
+    /// POSTs the payload as JSON to `url`.
+    Webhook {
+        url: String,
+        /// Extra request headers, e.g. `Authorization`.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+/// One `[[notify]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NotifyTarget {
+    #[serde(flatten)]
+    pub channel: NotifyChannel,
+    /// Events to send; empty means all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<NotifyEvent>,
+}
+
+impl NotifyTarget {
+    fn wants(&self, event: NotifyEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// JSON payload sent to every target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub harness: String,
+    pub profile: String,
+    /// Previously active profile, for switches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Install source (`owner/repo`), for installs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Per-component install results, for installs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub report: Vec<String>,
+    pub context: String,
+    pub user: Option<String>,
+    pub host: Option<String>,
+    /// RFC 3339 time the event happened.
+    pub timestamp: String,
+}
+
+impl Notification {
+    fn new(event: NotifyEvent, harness: &str, profile: &str, context: &str) -> Self {
+        Self {
+            event,
+            harness: harness.to_string(),
+            profile: profile.to_string(),
+            from: None,
+            source: None,
+            report: Vec::new(),
+            context: context.to_string(),
+            user: std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .ok(),
+            host: host_name(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// One-line human summary, used for desktop notifications.
+    pub fn summary(&self) -> String {
+        match self.event {
+            NotifyEvent::Switch => match &self.from {
+                Some(from) => format!("{}: {from} -> {}", self.harness, self.profile),
+                None => format!("{}: switched to {}", self.harness, self.profile),
+            },
+            NotifyEvent::Install => format!(
+                "Installed {} into {}/{}",
+                self.source.as_deref().unwrap_or("components"),
+                self.harness,
+                self.profile
+            ),
+        }
+    }
+}
+
+/// Delivers [`Notification`]s to the targets declared in `config.toml`.
+///
+/// Also a [`ProfileObserver`], so attaching it to a [`ProfileManager`](super::ProfileManager)
+/// reports every switch the manager performs.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    targets: Vec<NotifyTarget>,
+    context: String,
+}
+
+impl Notifier {
+    pub fn new(config: &BridleConfig) -> Self {
+        Self {
+            targets: config.notify.clone(),
+            context: config.current_context().to_string(),
+        }
+    }
+
+    /// Reports a finished install into `harness`/`profile`.
+    pub fn install(&self, harness: &str, profile: &str, source: &str, report: &[String]) {
+        let mut notification =
+            Notification::new(NotifyEvent::Install, harness, profile, &self.context);
+        notification.source = Some(source.to_string());
+        notification.report = report.to_vec();
+        self.send(&notification);
+    }
+
+    /// Sends `notification` to every subscribed target, warning about failures.
+    pub fn send(&self, notification: &Notification) {
+        for target in self.targets.iter().filter(|t| t.wants(notification.event)) {
+            if let Err(e) = deliver(&target.channel, notification) {
+                eprintln!("Warning: Notification failed: {e}");
+            }
+        }
+    }
+}
+
+impl ProfileObserver for Notifier {
+    fn on_event(&self, event: &ProfileEvent) {
+        if let ProfileEvent::Switched { harness, from, to } = event {
+            let mut notification =
+                Notification::new(NotifyEvent::Switch, harness, to, &self.context);
+            notification.from = from.clone();
+            self.send(&notification);
+        }
+    }
+}
+
+fn deliver(channel: &NotifyChannel, notification: &Notification) -> Result<(), String> {
+    let payload = serde_json::to_string(notification).map_err(|e| e.to_string())?;
+    match channel {
+        NotifyChannel::Command { command } => run_command(command, notification, &payload),
+        NotifyChannel::Desktop => desktop(&notification.summary()),
+        NotifyChannel::Webhook { url, headers } => {
+            let agent: ureq::Agent = crate::install::http::agent_config()
+                .map_err(|e| e.to_string())?
+                .timeout_global(Some(WEBHOOK_TIMEOUT))
+                .build()
+                .into();
+            let mut request = agent.post(url).header("Content-Type", "application/json");
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            request
+                .send(payload)
+                .map(|_| ())
+                .map_err(|e| format!("webhook {url}: {e}"))
+        }
+    }
+}
+
+fn run_command(command: &str, notification: &Notification, payload: &str) -> Result<(), String> {
+    let mut shell = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/C");
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c");
+        c
+    };
+    let event = serde_json::to_value(notification.event).map_err(|e| e.to_string())?;
+    let mut child = shell
+        .arg(command)
+        .env("BRIDLE_EVENT", event.as_str().unwrap_or_default())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("`{command}`: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The command may not read its input; a closed pipe is not an error.
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+    let status = child.wait().map_err(|e| format!("`{command}`: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("`{command}` exited with {status}"))
+    }
+}
+
+fn desktop(message: &str) -> Result<(), String> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut c = Command::new("osascript");
+        c.arg("-e").arg(format!(
+            "display notification {:?} with title \"bridle\"",
+            message
+        ));
+        c
+    } else if cfg!(unix) {
+        let mut c = Command::new("notify-send");
+        c.arg("bridle").arg(message);
+        c
+    } else {
+        return Err("desktop notifications are not supported on this platform".to_string());
+    };
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("desktop notification: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("desktop notification exited with {status}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_parse_from_toml() {
+        let config: BridleConfig = toml::from_str(
+            r#"
+            [[notify]]
+            type = "webhook"
+            url = "http://localhost/hook"
+            events = ["install"]
+
+            [[notify]]
+            type = "desktop"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.notify.len(), 2);
+        assert!(matches!(
+            config.notify[0].channel,
+            NotifyChannel::Webhook { .. }
+        ));
+        assert!(!config.notify[0].wants(NotifyEvent::Switch));
+        assert!(config.notify[1].wants(NotifyEvent::Switch));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_target_receives_json_payload() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let out = temp.path().join("payload.json");
+        let notifier = Notifier {
+            targets: vec![NotifyTarget {
+                channel: NotifyChannel::Command {
+                    command: format!("{{ cat; echo; echo $BRIDLE_EVENT; }} > '{}'", out.display()),
+                },
+                events: vec![NotifyEvent::Switch],
+            }],
+            context: "default".to_string(),
+        };
+
+        notifier.install("opencode", "work", "owner/repo", &[]);
+        assert!(!out.exists());
+
+        notifier.on_event(&ProfileEvent::Switched {
+            harness: "opencode".to_string(),
+            from: Some("default".to_string()),
+            to: "work".to_string(),
+        });
+        let written = std::fs::read_to_string(&out).unwrap();
+        let (json, event) = written.split_once('\n').unwrap();
+        let payload: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(payload["event"], "switch");
+        assert_eq!(payload["from"], "default");
+        assert_eq!(payload["profile"], "work");
+        assert_eq!(event.trim(), "switch");
+    }
+}
@@ -0,0 +1,280 @@
+//! Team policy files: what an organization allows in its members' profiles.
+//!
+//! `settings.policy` (or `--policy`) names a TOML file, or an `https://` URL
+//! serving one, shared by everyone on a team:
+//!
+//! ```toml
+//! allowed_mcp_servers = ["github", "linear"]  # omit to allow any server
+//! banned_skills = ["*-shell", "yolo"]
+//! banned_sources = ["untrusted-org/*"]
+//! required_rules = ["Never commit secrets."]
+//! ```
+//!
+//! Names and sources are globs, as for `bridle install --filter`. The
+//! installer refuses components the policy bans, [`ProfileManager`] refuses
+//! to apply a profile that breaks it, and `audit` reports each violation as
+//! a finding.
+//!
+//! [`ProfileManager`]: super::ProfileManager
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::rules::RulesSnippets;
+use super::{ProfileInfo, Settings};
+use crate::error::{Error, Result};
+use crate::harness::expand_home;
+use crate::install::SourceInfo;
+use crate::install::filter::glob_match;
+
+/// The rules a policy file sets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    /// MCP servers a profile may configure, by name; `None` allows any.
+    pub allowed_mcp_servers: Option<Vec<String>>,
+    /// Skills no profile may contain, by name.
+    pub banned_skills: Vec<String>,
+    /// Sources nothing may be installed from, as `owner/repo`.
+    pub banned_sources: Vec<String>,
+    /// Text every profile's rules file has to contain.
+    pub required_rules: Vec<String>,
+    /// Path or URL the policy was read from.
+    #[serde(skip)]
+    pub location: String,
+}
+
+/// Which part of a policy was broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRule {
+    AllowedMcpServers,
+    BannedSkills,
+    BannedSources,
+    RequiredRules,
+}
+
+/// One way a profile or an install breaks a policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub rule: PolicyRule,
+    /// The server, skill, source, or required text concerned.
+    pub subject: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Policy {
+    /// The policy `settings.policy` names, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the policy cannot be read or does not parse;
+    /// a policy that is set but unreadable never counts as no policy.
+    pub fn from_settings(settings: &Settings) -> Result<Option<Self>> {
+        settings.policy.as_deref().map(Self::load).transpose()
+    }
+
+    /// Reads the policy at `location`, a file path or an `http(s)://` URL.
+    pub fn load(location: &str) -> Result<Self> {
+        let content = if location.starts_with("https://") || location.starts_with("http://") {
+            let bytes = crate::install::http::fetch_bytes(location)
+                .map_err(|e| Error::Config(format!("cannot fetch policy {location}: {e}")))?;
+            String::from_utf8(bytes)
+                .map_err(|_| Error::Config(format!("policy {location} is not UTF-8 text")))?
+        } else {
+            let path = expand_home(Path::new(location))?;
+            fs::read_to_string(&path)
+                .map_err(|e| Error::Config(format!("cannot read policy {location}: {e}")))?
+        };
+        let mut policy: Self = toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path: location.into(),
+            message: e.to_string(),
+        })?;
+        policy.location = location.to_string();
+        Ok(policy)
+    }
+
+    /// Violations by an MCP server called `name`.
+    pub fn check_mcp_server(&self, name: &str) -> Option<Violation> {
+        let allowed = self.allowed_mcp_servers.as_ref()?;
+        (!allowed.iter().any(|pattern| glob_match(pattern, name))).then(|| Violation {
+            rule: PolicyRule::AllowedMcpServers,
+            subject: name.to_string(),
+            message: format!("MCP server '{name}' is not in allowed_mcp_servers"),
+        })
+    }
+
+    /// Violations by a skill called `name`.
+    pub fn check_skill(&self, name: &str) -> Option<Violation> {
+        let pattern = self
+            .banned_skills
+            .iter()
+            .find(|pattern| glob_match(pattern, name))?;
+        Some(Violation {
+            rule: PolicyRule::BannedSkills,
+            subject: name.to_string(),
+            message: format!("skill '{name}' is banned (matches '{pattern}')"),
+        })
+    }
+
+    /// Violations by installing from `source`.
+    pub fn check_source(&self, source: &SourceInfo) -> Option<Violation> {
+        let origin = format!("{}/{}", source.owner, source.repo);
+        let pattern = self
+            .banned_sources
+            .iter()
+            .find(|pattern| glob_match(pattern, &origin))?;
+        Some(Violation {
+            rule: PolicyRule::BannedSources,
+            subject: origin.clone(),
+            message: format!("source {origin} is banned (matches '{pattern}')"),
+        })
+    }
+
+    /// Required snippets missing from `rules`, the text of a rules file.
+    pub fn check_rules(&self, rules: Option<&str>) -> Vec<Violation> {
+        let rules = rules.unwrap_or_default();
+        self.required_rules
+            .iter()
+            .filter(|snippet| !rules.contains(snippet.trim()))
+            .map(|snippet| Violation {
+                rule: PolicyRule::RequiredRules,
+                subject: snippet.clone(),
+                message: format!(
+                    "rules file is missing required text: \"{}\"",
+                    snippet.trim()
+                ),
+            })
+            .collect()
+    }
+
+    /// Every violation in the profile `info`, whose components were
+    /// installed from `sources`.
+    pub fn check_profile<'a>(
+        &self,
+        info: &ProfileInfo,
+        sources: impl IntoIterator<Item = &'a SourceInfo>,
+    ) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = info
+            .mcp_servers
+            .iter()
+            .filter_map(|server| self.check_mcp_server(&server.name))
+            .chain(info.skills.items.iter().filter_map(|s| self.check_skill(s)))
+            .collect();
+        for violation in sources.into_iter().filter_map(|s| self.check_source(s)) {
+            if !violations.contains(&violation) {
+                violations.push(violation);
+            }
+        }
+        // A profile assembling its rules from snippets is checked against the assembly.
+        let rules = match RulesSnippets::load(&info.path) {
+            Ok(snippets) if !snippets.is_empty() => Some(snippets.assemble()),
+            _ => info
+                .rules_file
+                .as_ref()
+                .and_then(|path| fs::read_to_string(path).ok()),
+        };
+        violations.extend(self.check_rules(rules.as_deref()));
+        violations
+    }
+
+    /// The error for `violations` of this policy.
+    pub fn violated(&self, violations: Vec<Violation>) -> Error {
+        Error::PolicyViolation {
+            policy: self.location.clone(),
+            violations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{McpServerInfo, ResourceSummary};
+
+    fn policy() -> Policy {
+        toml::from_str(
+            r#"
+            allowed_mcp_servers = ["github", "linear-*"]
+            banned_skills = ["*-shell"]
+            banned_sources = ["untrusted/*"]
+            required_rules = ["Never commit secrets."]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn checks_servers_skills_and_sources_against_globs() {
+        let policy = policy();
+        assert!(policy.check_mcp_server("github").is_none());
+        assert!(policy.check_mcp_server("linear-eu").is_none());
+        assert_eq!(
+            policy.check_mcp_server("shell").map(|v| v.rule),
+            Some(PolicyRule::AllowedMcpServers)
+        );
+        assert!(Policy::default().check_mcp_server("shell").is_none());
+
+        assert_eq!(
+            policy.check_skill("root-shell").unwrap().message,
+            "skill 'root-shell' is banned (matches '*-shell')"
+        );
+        assert!(policy.check_skill("pdf").is_none());
+
+        let source = |owner: &str| SourceInfo {
+            owner: owner.to_string(),
+            repo: "skills".to_string(),
+            git_ref: None,
+        };
+        assert!(policy.check_source(&source("untrusted")).is_some());
+        assert!(policy.check_source(&source("org")).is_none());
+    }
+
+    #[test]
+    fn checks_a_profile_and_its_rules_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let rules = temp.path().join("AGENTS.md");
+        fs::write(&rules, "# Rules\n\nNever commit secrets.\n").unwrap();
+        let mut info = ProfileInfo {
+            mcp_servers: vec![McpServerInfo {
+                name: "shell".to_string(),
+                ..Default::default()
+            }],
+            skills: ResourceSummary {
+                items: vec!["pdf".to_string(), "root-shell".to_string()],
+                directory_exists: true,
+                ..Default::default()
+            },
+            rules_file: Some(rules),
+            ..Default::default()
+        };
+        let rules_of = |violations: Vec<Violation>| -> Vec<PolicyRule> {
+            violations.into_iter().map(|v| v.rule).collect()
+        };
+        assert_eq!(
+            rules_of(policy().check_profile(&info, [])),
+            [PolicyRule::AllowedMcpServers, PolicyRule::BannedSkills]
+        );
+
+        info.rules_file = None;
+        info.mcp_servers.clear();
+        info.skills.items.clear();
+        assert_eq!(
+            rules_of(policy().check_profile(&info, [])),
+            [PolicyRule::RequiredRules]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(toml::from_str::<Policy>("banned_skill = [\"x\"]\n").is_err());
+    }
+}
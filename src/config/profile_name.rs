@@ -9,12 +9,19 @@ use std::fmt;
 /// - Lowercase alphanumeric with hyphens
 /// - No leading or trailing hyphens
 /// - No consecutive hyphens
+/// - Not a reserved Windows device name (`con`, `nul`, `com1`, ...)
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize)]
 pub struct ProfileName(String);
 
 impl ProfileName {
     pub const MAX_LENGTH: usize = 64;
 
+    /// Names Windows reserves for devices, whatever the extension or case.
+    const RESERVED: &[&str] = &[
+        "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+        "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+    ];
+
     /// Create a new profile name, validating the input.
     ///
     /// # Errors
@@ -29,6 +36,12 @@ impl ProfileName {
         &self.0
     }
 
+    /// Whether a directory called `existing` is a different name that a
+    /// case-insensitive filesystem (macOS, Windows) would treat as this one.
+    pub fn collides_with(&self, existing: &str) -> bool {
+        existing != self.0 && existing.eq_ignore_ascii_case(&self.0)
+    }
+
     fn validate(name: &str) -> Result<(), InvalidProfileName> {
         if name.is_empty() {
             return Err(InvalidProfileName::Empty);
@@ -52,6 +65,13 @@ impl ProfileName {
             }
         }
 
+        if let Some(reserved) = Self::RESERVED
+            .iter()
+            .find(|reserved| name.eq_ignore_ascii_case(reserved))
+        {
+            return Err(InvalidProfileName::Reserved(reserved.to_string()));
+        }
+
         Ok(())
     }
 }
@@ -97,6 +117,10 @@ pub enum InvalidProfileName {
     ConsecutiveHyphens,
     /// Profile name contains an invalid character.
     InvalidCharacter(char),
+    /// Profile name is a device name Windows reserves.
+    Reserved(String),
+    /// Profile name differs only in case from an existing profile directory.
+    CaseCollision(String),
 }
 
 impl fmt::Display for InvalidProfileName {
@@ -120,6 +144,13 @@ impl fmt::Display for InvalidProfileName {
                     "invalid character '{c}': only lowercase alphanumeric and hyphens allowed"
                 )
             }
+            Self::Reserved(name) => {
+                write!(f, "'{name}' is a reserved device name on Windows")
+            }
+            Self::CaseCollision(existing) => write!(
+                f,
+                "collides with existing profile directory '{existing}' on case-insensitive filesystems"
+            ),
         }
     }
 }
@@ -199,6 +230,28 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn rejects_reserved_device_names() {
+        assert_eq!(
+            ProfileName::new("CON"),
+            Err(InvalidProfileName::Reserved("con".to_string()))
+        );
+        assert!(matches!(
+            ProfileName::new("lpt9"),
+            Err(InvalidProfileName::Reserved(_))
+        ));
+        assert!(ProfileName::new("console").is_ok());
+        assert!(ProfileName::new("com10").is_ok());
+    }
+
+    #[test]
+    fn detects_case_collisions() {
+        let name = ProfileName::new("work").unwrap();
+        assert!(name.collides_with("Work"));
+        assert!(!name.collides_with("work"));
+        assert!(!name.collides_with("works"));
+    }
+
     #[test]
     fn try_from_str() {
         let name: Result<ProfileName, _> = "valid-name".try_into();
@@ -0,0 +1,139 @@
+//! Project-local profile selection via `.bridle.toml`.
+//!
+//! A `.bridle.toml` in a directory (or any parent) names the profile each
+//! harness should use while working there:
+//!
+//! ```toml
+//! [profiles]
+//! claude-code = "work"
+//! opencode = "work"
+//! ```
+//!
+//! `bridle activate` applies it; `bridle hook <shell>` runs that on every `cd`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::StatePaths;
+use super::atomic::write_atomic;
+use crate::error::{Error, Result};
+
+/// File name searched for by [`ProjectFile::find`].
+pub const PROJECT_FILE: &str = ".bridle.toml";
+
+/// Parsed contents of a [`PROJECT_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    /// Profile per harness (harness id or alias -> profile name).
+    #[serde(default)]
+    pub profiles: BTreeMap<String, String>,
+}
+
+impl ProjectFile {
+    /// Nearest [`PROJECT_FILE`] in `start` or one of its ancestors.
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_FILE))
+            .find(|path| path.is_file())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Project files the user agreed to apply, recorded with a hash of their
+/// contents so an edited file is asked about again.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedProjects {
+    #[serde(default)]
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl TrustedProjects {
+    pub fn load(paths: &StatePaths) -> Result<Self> {
+        let path = paths.trusted_projects_file();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    pub fn save(&self, paths: &StatePaths) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        write_atomic(&paths.trusted_projects_file(), content)
+    }
+
+    /// Whether `file` is trusted with its current contents.
+    pub fn is_trusted(&self, file: &Path) -> bool {
+        match (self.files.get(file), content_hash(file)) {
+            (Some(trusted), Ok(current)) => *trusted == current,
+            _ => false,
+        }
+    }
+
+    /// Trusts `file` with its current contents.
+    pub fn trust(&mut self, file: &Path) -> Result<()> {
+        self.files.insert(file.to_path_buf(), content_hash(file)?);
+        Ok(())
+    }
+}
+
+fn content_hash(file: &Path) -> std::io::Result<String> {
+    let content = std::fs::read(file)?;
+    Ok(Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn find_walks_up_to_the_nearest_file() {
+        let temp = TempDir::new().unwrap();
+        let nested = temp.path().join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(ProjectFile::find(&nested), None);
+
+        let file = temp.path().join("a").join(PROJECT_FILE);
+        std::fs::write(&file, "[profiles]\nclaude-code = \"work\"\n").unwrap();
+        assert_eq!(ProjectFile::find(&nested), Some(file.clone()));
+        assert_eq!(
+            ProjectFile::load(&file).unwrap().profiles["claude-code"],
+            "work"
+        );
+    }
+
+    #[test]
+    fn trust_is_revoked_by_edits() {
+        let temp = TempDir::new().unwrap();
+        let paths = StatePaths::new(temp.path().join("state"));
+        let file = temp.path().join(PROJECT_FILE);
+        std::fs::write(&file, "[profiles]\nopencode = \"a\"\n").unwrap();
+
+        let mut trusted = TrustedProjects::load(&paths).unwrap();
+        assert!(!trusted.is_trusted(&file));
+        trusted.trust(&file).unwrap();
+        trusted.save(&paths).unwrap();
+        assert!(TrustedProjects::load(&paths).unwrap().is_trusted(&file));
+
+        std::fs::write(&file, "[profiles]\nopencode = \"b\"\n").unwrap();
+        assert!(!trusted.is_trusted(&file));
+    }
+}
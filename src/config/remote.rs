@@ -0,0 +1,187 @@
+//! Machines bridle can run on over SSH, declared as `[remotes.<name>]`.
+//!
+//! `bridle --host <target> ...` runs the rest of the command line with the
+//! remote machine's own bridle, so profiles are created, listed, and switched
+//! in that machine's config directories. The target is a remote's name or
+//! any `user@server` that `ssh` accepts.
+
+use std::ffi::OsString;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::BridleConfig;
+use crate::error::{Error, Result};
+
+fn default_bridle() -> String {
+    "bridle".to_string()
+}
+
+fn default_ssh() -> String {
+    "ssh".to_string()
+}
+
+/// A remote machine, declared as `[remotes.<name>]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Remote {
+    /// SSH destination, e.g. `me@devbox` or a `Host` from `~/.ssh/config`.
+    pub host: String,
+    /// bridle executable on the remote machine.
+    #[serde(default = "default_bridle")]
+    pub bridle: String,
+    /// SSH command, with any options, e.g. `ssh -p 2222`.
+    #[serde(default = "default_ssh")]
+    pub ssh: String,
+}
+
+impl Remote {
+    /// The remote called `target` in `config`, or `target` itself as an SSH
+    /// destination with the default bridle and ssh commands.
+    pub fn resolve(config: &BridleConfig, target: &str) -> Self {
+        config.remotes.get(target).cloned().unwrap_or_else(|| Self {
+            host: target.to_string(),
+            bridle: default_bridle(),
+            ssh: default_ssh(),
+        })
+    }
+
+    /// The program and arguments that run bridle with `args` on the remote.
+    ///
+    /// `tty` asks ssh for a terminal so prompts and the TUI work remotely.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidValue`] for a host that is empty, starts with
+    /// `-` (which ssh would read as an option such as `-oProxyCommand=...`),
+    /// or holds whitespace or control characters.
+    pub fn command(&self, args: &[OsString], tty: bool) -> Result<(String, Vec<OsString>)> {
+        if self.host.is_empty()
+            || self.host.starts_with('-')
+            || self
+                .host
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(Error::InvalidValue {
+                key: "host".to_string(),
+                value: self.host.clone(),
+            });
+        }
+        let mut words = self.ssh.split_whitespace().map(OsString::from);
+        let program = words
+            .next()
+            .map(|w| w.to_string_lossy().into_owned())
+            .unwrap_or_else(default_ssh);
+        let mut ssh_args: Vec<OsString> = words.collect();
+        if tty {
+            ssh_args.push("-t".into());
+        }
+        ssh_args.push(self.host.clone().into());
+        // ssh joins everything after the host into one shell command line.
+        let remote_command: Vec<String> = std::iter::once(self.bridle.clone())
+            .chain(args.iter().map(|a| shell_quote(&a.to_string_lossy())))
+            .collect();
+        ssh_args.push("--".into());
+        ssh_args.push(remote_command.join(" ").into());
+        Ok((program, ssh_args))
+    }
+}
+
+/// Quotes `arg` for a POSIX shell, leaving plain words alone.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Command-line arguments with `--host <target>` (or `--host=<target>`)
+/// taken out, for passing on to the remote bridle.
+pub fn strip_host_flag(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let mut out = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            out.push(arg);
+            out.extend(args);
+            break;
+        }
+        if arg == "--host" {
+            args.next();
+        } else if !arg.to_string_lossy().starts_with("--host=") {
+            out.push(arg);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn builds_ssh_command_for_named_and_ad_hoc_remotes() {
+        let mut config = BridleConfig::default();
+        config.remotes.insert(
+            "dev".to_string(),
+            Remote {
+                host: "me@devbox".to_string(),
+                bridle: "~/.cargo/bin/bridle".to_string(),
+                ssh: "ssh -p 2222".to_string(),
+            },
+        );
+        let args = os(&["profile", "create", "opencode", "it's"]);
+
+        let (program, ssh_args) = Remote::resolve(&config, "dev")
+            .command(&args, true)
+            .unwrap();
+        assert_eq!(program, "ssh");
+        assert_eq!(
+            ssh_args,
+            os(&[
+                "-p",
+                "2222",
+                "-t",
+                "me@devbox",
+                "--",
+                r"~/.cargo/bin/bridle profile create opencode 'it'\''s'"
+            ])
+        );
+
+        let (_, ssh_args) = Remote::resolve(&config, "ops@build")
+            .command(&args[..1], false)
+            .unwrap();
+        assert_eq!(ssh_args, os(&["ops@build", "--", "bridle profile"]));
+    }
+
+    #[test]
+    fn rejects_hosts_ssh_would_read_as_options() {
+        let config = BridleConfig::default();
+        for host in ["-oProxyCommand=touch /tmp/x", "", "a b", "dev\n"] {
+            let err = Remote::resolve(&config, host)
+                .command(&os(&["status"]), false)
+                .unwrap_err();
+            assert!(matches!(err, Error::InvalidValue { ref key, .. } if key == "host"));
+        }
+    }
+
+    #[test]
+    fn strips_only_the_host_flag() {
+        assert_eq!(
+            strip_host_flag(os(&["--host", "dev", "profile", "list", "--host=x"])),
+            os(&["profile", "list"])
+        );
+        assert_eq!(
+            strip_host_flag(os(&["exec", "oc", "--", "cmd", "--host", "y"])),
+            os(&["exec", "oc", "--", "cmd", "--host", "y"])
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! `bridle-secret://<name>` references in profiles, resolved from the OS keyring.
+//!
+//! A profile config file can hold a reference instead of a raw API key:
+//!
+//! ```json
+//! { "apiKey": "bridle-secret://anthropic-key" }
+//! ```
+//!
+//! Switching to the profile writes the secret's value into the live config;
+//! the profile itself only ever stores the reference. Plain names are looked
+//! up in the system keyring (Keychain on macOS, Credential Manager on Windows,
+//! Secret Service on Linux), where `bridle secret set` stores them.
+//!
+//! A reference of the form `bridle-secret://<provider>/<key>` is instead
+//! resolved by running the provider's command from `config.toml`:
+//!
+//! ```toml
+//! [secret_providers.op]
+//! command = "op read op://Private/{key}/credential"
+//!
+//! [secret_providers.pass]
+//! command = "pass show {key}"
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::{Command, Stdio};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::atomic::write_atomic;
+use super::{BridleConfig, StatePaths};
+use crate::error::{Error, Result};
+
+/// Prefix that marks a secret reference.
+pub const SECRET_SCHEME: &str = "bridle-secret://";
+
+/// Keyring service name secrets are stored under.
+const KEYRING_SERVICE: &str = "bridle";
+
+/// Looks up secret values by name.
+pub trait SecretStore: Send + Sync {
+    /// The value of secret `name`, or `None` if it was never stored.
+    fn get(&self, name: &str) -> Result<Option<String>>;
+}
+
+/// The system keyring.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keyring;
+
+impl Keyring {
+    fn entry(name: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, name).map_err(|e| Error::Keyring(e.to_string()))
+    }
+
+    /// Stores `value` as secret `name`, replacing any previous value.
+    pub fn set(&self, name: &str, value: &str) -> Result<()> {
+        validate_secret_name(name)?;
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| Error::Keyring(e.to_string()))
+    }
+}
+
+impl SecretStore for Keyring {
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(Error::Keyring(e.to_string())),
+        }
+    }
+}
+
+/// An external command that prints secrets, declared as `[secret_providers.<name>]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SecretProvider {
+    /// Shell command that prints the secret on stdout. `{key}` is replaced
+    /// with the part of the reference after `<name>/`, which is also passed
+    /// as `BRIDLE_SECRET_KEY`.
+    pub command: String,
+}
+
+impl SecretProvider {
+    fn read(&self, provider: &str, key: &str) -> Result<Option<String>> {
+        let command = self.command.replace("{key}", key);
+        let mut shell = if cfg!(windows) {
+            let mut c = Command::new("cmd");
+            c.arg("/C");
+            c
+        } else {
+            let mut c = Command::new("sh");
+            c.arg("-c");
+            c
+        };
+        let output = shell
+            .arg(&command)
+            .env("BRIDLE_SECRET_KEY", key)
+            .stdin(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|e| Error::SecretProvider {
+                provider: provider.to_string(),
+                message: format!("`{command}`: {e}"),
+            })?;
+        if !output.status.success() {
+            return Err(Error::SecretProvider {
+                provider: provider.to_string(),
+                message: format!(
+                    "`{command}` exited with {}: {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+        let value = String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches(['\r', '\n'])
+            .to_string();
+        Ok(Some(value).filter(|v| !v.is_empty()))
+    }
+}
+
+/// Resolves references through the configured providers, and plain names
+/// through the [`Keyring`].
+#[derive(Debug, Clone, Default)]
+pub struct SecretResolver {
+    providers: BTreeMap<String, SecretProvider>,
+}
+
+impl SecretResolver {
+    pub fn new(config: &BridleConfig) -> Self {
+        Self {
+            providers: config.secret_providers.clone(),
+        }
+    }
+
+    /// Names of the configured providers.
+    pub fn providers(&self) -> impl Iterator<Item = &str> {
+        self.providers.keys().map(String::as_str)
+    }
+}
+
+impl SecretStore for SecretResolver {
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        let Some((provider, key)) = name.split_once('/') else {
+            return Keyring.get(name);
+        };
+        let command = self
+            .providers
+            .get(provider)
+            .ok_or_else(|| Error::SecretProvider {
+                provider: provider.to_string(),
+                message: "no such provider in config.toml".to_string(),
+            })?;
+        command.read(provider, key)
+    }
+}
+
+/// Names stored with `bridle secret set`, kept because keyrings cannot be
+/// listed. Holds names only, never values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretIndex {
+    #[serde(default)]
+    names: BTreeSet<String>,
+}
+
+impl SecretIndex {
+    pub fn load(paths: &StatePaths) -> Result<Self> {
+        let path = paths.secrets_file();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    pub fn save(&self, paths: &StatePaths) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        write_atomic(&paths.secrets_file(), content)
+    }
+
+    pub fn insert(&mut self, name: &str) {
+        self.names.insert(name.to_string());
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Characters allowed in a reference: a name, or `<provider>/<key>`.
+fn is_reference_char(c: char) -> bool {
+    is_name_char(c) || c == '/'
+}
+
+/// Rejects names that could not be stored in the keyring and written as a reference.
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    if name.is_empty() || !name.chars().all(is_name_char) {
+        return Err(Error::InvalidValue {
+            key: "secret name".to_string(),
+            value: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Calls `on_ref` with the byte range and name of each `bridle-secret://<name>` in `text`.
+pub(crate) fn for_each_reference(text: &str, mut on_ref: impl FnMut(std::ops::Range<usize>, &str)) {
+    let mut from = 0;
+    while let Some(start) = text[from..].find(SECRET_SCHEME).map(|i| from + i) {
+        let name_start = start + SECRET_SCHEME.len();
+        let len = text[name_start..]
+            .find(|c: char| !is_reference_char(c))
+            .unwrap_or(text.len() - name_start);
+        if len > 0 {
+            on_ref(start..name_start + len, &text[name_start..name_start + len]);
+        }
+        from = name_start + len;
+    }
+}
+
+/// Secret names referenced in `text`.
+pub fn references(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    for_each_reference(text, |range, _| {
+        let name = &text[range.start + SECRET_SCHEME.len()..range.end];
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    });
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_references_and_validates_names() {
+        let text = r#"{"a": "bridle-secret://anthropic-key", "b": "bridle-secret://", "c": "x bridle-secret://gh.token"}"#;
+        assert_eq!(references(text), ["anthropic-key", "gh.token"]);
+        assert_eq!(
+            references("key: bridle-secret://op/Work/api-key\n"),
+            ["op/Work/api-key"]
+        );
+
+        assert!(validate_secret_name("work_key-1").is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name("a/b").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn provider_references_run_the_provider_command() {
+        let config: BridleConfig = toml::from_str(
+            r#"
+            [secret_providers.echo]
+            command = "printf 'value-for-{key}:%s' \"$BRIDLE_SECRET_KEY\""
+
+            [secret_providers.broken]
+            command = "echo nope >&2; exit 3"
+            "#,
+        )
+        .unwrap();
+        let resolver = SecretResolver::new(&config);
+
+        assert_eq!(
+            resolver.get("echo/work/api").unwrap().as_deref(),
+            Some("value-for-work/api:work/api")
+        );
+        let err = resolver.get("broken/x").unwrap_err();
+        assert!(err.to_string().contains("nope"));
+        assert!(matches!(
+            resolver.get("missing/x").unwrap_err(),
+            Error::SecretProvider { ref provider, .. } if provider == "missing"
+        ));
+    }
+
+    #[test]
+    fn index_round_trips() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let paths = StatePaths::new(temp.path());
+        let mut index = SecretIndex::load(&paths).unwrap();
+        index.insert("b");
+        index.insert("a");
+        index.save(&paths).unwrap();
+        let names: Vec<_> = SecretIndex::load(&paths)
+            .unwrap()
+            .names()
+            .map(String::from)
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+}
@@ -0,0 +1,91 @@
+//! Human-readable byte sizes (`100MB`, `1.5G`) for size-limit settings.
+//!
+//! Units are binary: `K` is 1024 bytes, `M` is 1024 K, and so on. A trailing
+//! `B` or `iB` is accepted and ignored. `0` means "no limit".
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+const UNITS: &[(&str, u64)] = &[
+    ("T", 1 << 40),
+    ("G", 1 << 30),
+    ("M", 1 << 20),
+    ("K", 1 << 10),
+];
+
+/// Parses a size such as `512`, `100MB`, `1.5G`, or `64 KiB`.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let upper = value.trim().to_ascii_uppercase();
+    let number = upper
+        .strip_suffix("IB")
+        .or_else(|| upper.strip_suffix('B'))
+        .unwrap_or(&upper)
+        .trim_end();
+    let (number, multiplier) = UNITS
+        .iter()
+        .find_map(|&(unit, m)| number.strip_suffix(unit).map(|n| (n.trim_end(), m)))
+        .unwrap_or((number, 1));
+
+    if let Ok(n) = number.parse::<u64>() {
+        return n.checked_mul(multiplier);
+    }
+    let n: f64 = number.parse().ok()?;
+    (n.is_finite() && n >= 0.0).then(|| (n * multiplier as f64).round() as u64)
+}
+
+/// Formats `bytes` with the largest unit that keeps one decimal of precision.
+pub fn format_size(bytes: u64) -> String {
+    for &(unit, m) in UNITS {
+        if bytes >= m {
+            return if bytes.is_multiple_of(m) {
+                format!("{}{unit}B", bytes / m)
+            } else {
+                format!("{:.1}{unit}B", bytes as f64 / m as f64)
+            };
+        }
+    }
+    format!("{bytes}B")
+}
+
+/// Serde adapter storing sizes as strings like `"100MB"`; plain integers
+/// (bytes) are accepted when reading.
+pub mod serde_size {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_size(*bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bytes(u64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Bytes(n) => Ok(n),
+            Raw::Text(s) => {
+                parse_size(&s).ok_or_else(|| serde::de::Error::custom(format!("invalid size: {s}")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_and_formats_back() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("100MB"), Some(100 << 20));
+        assert_eq!(parse_size("1.5g"), Some(3 << 29));
+        assert_eq!(parse_size("64 KiB"), Some(64 << 10));
+        assert_eq!(parse_size("lots"), None);
+        assert_eq!(parse_size("-1M"), None);
+
+        assert_eq!(format_size(0), "0B");
+        assert_eq!(format_size(100 << 20), "100MB");
+        assert_eq!(format_size(3 << 29), "1.5GB");
+    }
+}
@@ -0,0 +1,316 @@
+//! Locations of bridle's runtime state, kept apart from user configuration.
+//!
+//! `config.toml` holds options the user chose; everything bridle records on its
+//! own (active profiles, history, locks, caches) lives under the state directory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Paths inside bridle's state directory.
+///
+/// Resolved in order from `BRIDLE_STATE_DIR`, `<BRIDLE_CONFIG_DIR>/state`, then
+/// the platform default (`$XDG_STATE_HOME/bridle` or `~/.local/state/bridle` on
+/// Linux, the platform data directory elsewhere).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatePaths {
+    root: PathBuf,
+}
+
+impl StatePaths {
+    /// Uses `root` as the state directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Resolves the state directory for this environment.
+    ///
+    /// # Errors
+    /// Returns [`Error::ConfigDirUnavailable`] if no home directory can be found.
+    pub fn resolve() -> Result<Self> {
+        if let Ok(dir) = std::env::var("BRIDLE_STATE_DIR") {
+            return Ok(Self::new(dir));
+        }
+        // An explicit config dir keeps state alongside it, so isolated setups stay isolated.
+        if let Ok(dir) = std::env::var("BRIDLE_CONFIG_DIR") {
+            return Ok(Self::new(PathBuf::from(dir).join("state")));
+        }
+        platform_state_dir()
+            .map(|d| Self::new(d.join("bridle")))
+            .map_err(|e| Error::ConfigDirUnavailable(e.to_string()))
+    }
+
+    /// State directory for the context `name`; the default context uses the root.
+    pub fn for_context(&self, name: &str) -> Self {
+        if name == super::DEFAULT_CONTEXT {
+            self.clone()
+        } else {
+            Self::new(self.root.join("contexts").join(name))
+        }
+    }
+
+    /// The state directory itself.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// File recording active profiles.
+    pub fn state_file(&self) -> PathBuf {
+        self.root.join("state.toml")
+    }
+
+    /// Append-only log of profile operations.
+    pub fn history_file(&self) -> PathBuf {
+        self.root.join("history.jsonl")
+    }
+
+    /// Directory holding journals of in-progress profile switches.
+    pub fn journal_dir(&self) -> PathBuf {
+        self.root.join("journal")
+    }
+
+    /// Directory for lock files.
+    pub fn locks_dir(&self) -> PathBuf {
+        self.root.join("locks")
+    }
+
+    /// Project files the user allowed `bridle activate` to apply.
+    pub fn trusted_projects_file(&self) -> PathBuf {
+        self.root.join("trusted_projects.toml")
+    }
+
+    /// Names of secrets stored in the keyring with `bridle secret set`.
+    pub fn secrets_file(&self) -> PathBuf {
+        self.root.join("secrets.toml")
+    }
+
+    /// Components that failed to install, kept for `bridle install --resume`.
+    pub fn pending_installs_file(&self) -> PathBuf {
+        self.root.join("pending-installs.json")
+    }
+
+    /// Records of recent installs, for `bridle undo --last-install`.
+    pub fn installs_dir(&self) -> PathBuf {
+        self.root.join("installs")
+    }
+
+    /// Outcome of the last `bridle sync`.
+    pub fn sync_file(&self) -> PathBuf {
+        self.root.join("last-sync.json")
+    }
+
+    /// Profiles as they were when last pushed or pulled by `bridle sync`,
+    /// as `<harness>/<profile>/`.
+    pub fn sync_base_root(&self) -> PathBuf {
+        self.root.join("sync-base")
+    }
+
+    /// `profile` of `harness` as it was when last pushed or pulled by
+    /// `bridle sync`, the base of the next pull's merge.
+    pub fn sync_base_dir(&self, harness: &str, profile: &str) -> PathBuf {
+        self.sync_base_root().join(harness).join(profile)
+    }
+
+    /// Unix socket `bridle daemon` listens on by default.
+    pub fn socket_file(&self) -> PathBuf {
+        self.root.join("daemon.sock")
+    }
+
+    /// Directory for disposable caches.
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root.join("cache")
+    }
+
+    /// Downloads that cannot change, reused by later installs.
+    pub fn downloads_dir(&self) -> PathBuf {
+        self.cache_dir().join("downloads")
+    }
+
+    /// When `[[subscriptions]]` were last checked for updates.
+    pub fn subscriptions_checked_file(&self) -> PathBuf {
+        self.cache_dir().join("subscriptions-checked")
+    }
+
+    /// Hash manifest of `profile`'s files as last applied to the live config.
+    pub fn manifest_file(&self, harness: &str, profile: &str) -> PathBuf {
+        self.manifests_dir(harness).join(format!("{profile}.json"))
+    }
+
+    /// Hash manifests of all of `harness`'s profiles.
+    pub fn manifests_dir(&self, harness: &str) -> PathBuf {
+        self.cache_dir().join("manifests").join(harness)
+    }
+
+    /// What extraction last found in `profile`, reused while it is unchanged.
+    pub fn extraction_file(&self, harness: &str, profile: &str) -> PathBuf {
+        self.extraction_dir(harness).join(format!("{profile}.json"))
+    }
+
+    /// Cached extractions of all of `harness`'s profiles.
+    pub fn extraction_dir(&self, harness: &str) -> PathBuf {
+        self.cache_dir().join("extraction").join(harness)
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_state_dir() -> harness_locate::Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_STATE_HOME").map(PathBuf::from)
+        && dir.is_absolute()
+    {
+        return Ok(dir);
+    }
+    Ok(harness_locate::platform::home_dir()?.join(".local/state"))
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn platform_state_dir() -> harness_locate::Result<PathBuf> {
+    harness_locate::platform::data_dir()
+}
+
+/// Contents of [`StatePaths::state_file`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Active profile per harness (harness_id -> profile_name).
+    #[serde(default)]
+    pub active: HashMap<String, String>,
+    /// Switch statistics per harness and profile (harness_id -> profile_name -> usage).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub usage: BTreeMap<String, BTreeMap<String, ProfileUsage>>,
+    /// Targets (`<harness>/<profile>`) last chosen by `bridle install`, per source.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub install_targets: BTreeMap<String, Vec<String>>,
+}
+
+/// How often and how recently a profile was switched to. Recorded locally only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileUsage {
+    /// Number of completed switches to the profile.
+    #[serde(default)]
+    pub switches: u64,
+    /// When the profile was last switched to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+impl PersistedState {
+    /// Reads the state file, treating a missing file as empty state.
+    pub fn load(paths: &StatePaths) -> Result<Self> {
+        let path = paths.state_file();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    pub fn save(&self, paths: &StatePaths) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        super::atomic::write_atomic(&paths.state_file(), content)
+    }
+
+    /// Counts a switch to `profile` of `harness` happening now.
+    pub fn record_switch(paths: &StatePaths, harness: &str, profile: &str) -> Result<()> {
+        let mut state = Self::load(paths)?;
+        let usage = state
+            .usage
+            .entry(harness.to_string())
+            .or_default()
+            .entry(profile.to_string())
+            .or_default();
+        usage.switches += 1;
+        usage.last_used = Some(Utc::now());
+        state.save(paths)
+    }
+
+    /// Records `targets` as the ones last chosen when installing from `source`.
+    pub fn record_install_targets(
+        paths: &StatePaths,
+        source: &str,
+        targets: Vec<String>,
+    ) -> Result<()> {
+        let mut state = Self::load(paths)?;
+        state.install_targets.insert(source.to_string(), targets);
+        state.save(paths)
+    }
+
+    /// Drops the usage recorded for a deleted profile.
+    pub fn forget_profile(paths: &StatePaths, harness: &str, profile: &str) -> Result<()> {
+        let mut state = Self::load(paths)?;
+        let Some(profiles) = state.usage.get_mut(harness) else {
+            return Ok(());
+        };
+        if profiles.remove(profile).is_none() {
+            return Ok(());
+        }
+        if profiles.is_empty() {
+            state.usage.remove(harness);
+        }
+        state.save(paths)
+    }
+
+    /// Drops everything recorded about `harness`: its active profile and usage.
+    pub fn forget_harness(paths: &StatePaths, harness: &str) -> Result<()> {
+        let mut state = Self::load(paths)?;
+        let active = state.active.remove(harness).is_some();
+        if state.usage.remove(harness).is_some() || active {
+            state.save(paths)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn paths_live_under_root() {
+        let paths = StatePaths::new("/tmp/bridle-state");
+        assert_eq!(
+            paths.state_file(),
+            PathBuf::from("/tmp/bridle-state/state.toml")
+        );
+        assert_eq!(paths.locks_dir(), PathBuf::from("/tmp/bridle-state/locks"));
+    }
+
+    #[test]
+    fn persisted_state_round_trips() {
+        let temp = TempDir::new().unwrap();
+        let paths = StatePaths::new(temp.path().join("state"));
+        assert_eq!(
+            PersistedState::load(&paths).unwrap(),
+            PersistedState::default()
+        );
+
+        let mut state = PersistedState::default();
+        state
+            .active
+            .insert("opencode".to_string(), "work".to_string());
+        state.save(&paths).unwrap();
+
+        assert_eq!(PersistedState::load(&paths).unwrap(), state);
+    }
+
+    #[test]
+    fn switches_are_counted_and_forgotten() {
+        let temp = TempDir::new().unwrap();
+        let paths = StatePaths::new(temp.path().join("state"));
+        PersistedState::record_switch(&paths, "opencode", "work").unwrap();
+        PersistedState::record_switch(&paths, "opencode", "work").unwrap();
+
+        let usage = &PersistedState::load(&paths).unwrap().usage["opencode"]["work"];
+        assert_eq!(usage.switches, 2);
+        assert!(usage.last_used.is_some());
+
+        PersistedState::forget_profile(&paths, "opencode", "work").unwrap();
+        assert!(PersistedState::load(&paths).unwrap().usage.is_empty());
+    }
+}
@@ -0,0 +1,47 @@
+//! Approximate token counts for instructions a harness loads into its context.
+//!
+//! Counts are estimates at roughly four bytes per token, which is close for
+//! English prose and code with common tokenizers. They are meant to spot a
+//! rules file or skill that has grown too big, not to budget exactly.
+
+/// Bytes per token assumed by [`estimate_tokens`].
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Skills estimated at this many tokens or more are listed as large.
+pub const LARGE_SKILL_TOKENS: usize = 2_000;
+
+/// Rules files estimated at this many tokens or more lower a profile's health.
+pub const LARGE_RULES_TOKENS: usize = 5_000;
+
+/// Estimates how many tokens `text` takes up.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(BYTES_PER_TOKEN)
+}
+
+/// Formats a token count as `850`, `1.2k`, or `12k`.
+pub fn format_tokens(tokens: usize) -> String {
+    match tokens {
+        0..1_000 => tokens.to_string(),
+        1_000..10_000 => format!("{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("{}k", (tokens + 500) / 1_000),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_four_bytes_per_token() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abc"), 1);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+    }
+
+    #[test]
+    fn formats_counts_compactly() {
+        assert_eq!(format_tokens(850), "850");
+        assert_eq!(format_tokens(1_234), "1.2k");
+        assert_eq!(format_tokens(12_400), "12k");
+    }
+}
@@ -1,11 +1,15 @@
 //! Shared types for profile management.
 
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
 
 /// MCP server info with enabled status and connection details.
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct McpServerInfo {
     pub name: String,
     pub enabled: bool,
@@ -15,17 +19,177 @@ pub struct McpServerInfo {
     pub url: Option<String>,
 }
 
+/// A settings file a harness merges into its effective settings, lowest
+/// precedence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsLayer {
+    /// The user's own settings, which is what a profile holds.
+    User,
+    /// The project's shared settings, e.g. `.claude/settings.json`.
+    Project,
+    /// The project's uncommitted settings, e.g. `.claude/settings.local.json`.
+    Local,
+    /// Settings an administrator installed system-wide, which nothing overrides.
+    Managed,
+}
+
+impl SettingsLayer {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Project => "project",
+            Self::Local => "local",
+            Self::Managed => "managed",
+        }
+    }
+}
+
+/// A setting the profile sets that a higher layer replaces.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverriddenSetting {
+    pub key: String,
+    pub layer: SettingsLayer,
+    pub profile_value: serde_json::Value,
+    pub effective_value: serde_json::Value,
+}
+
+/// The part of a profile an [`ExtractionIssue`] was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionCategory {
+    Settings,
+    Mcp,
+    Skills,
+    Commands,
+    Plugins,
+    Agents,
+    Rules,
+}
+
+impl ExtractionCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Settings => "settings",
+            Self::Mcp => "MCP config",
+            Self::Skills => "skills",
+            Self::Commands => "commands",
+            Self::Plugins => "plugins",
+            Self::Agents => "agents",
+            Self::Rules => "rules",
+        }
+    }
+}
+
+/// What went wrong reading part of a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionIssueKind {
+    /// A file exists but does not parse.
+    Parse,
+    /// A file or directory could not be read.
+    Io,
+    /// The harness could not say where the resource lives.
+    Layout,
+}
+
+/// A problem found while reading a profile, which leaves that part of it
+/// out of [`ProfileInfo`] rather than failing the whole read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionIssue {
+    pub category: ExtractionCategory,
+    /// The file at fault, when known.
+    pub path: Option<PathBuf>,
+    pub kind: ExtractionIssueKind,
+    pub message: String,
+}
+
+impl ExtractionIssue {
+    pub fn new(
+        category: ExtractionCategory,
+        kind: ExtractionIssueKind,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            category,
+            path: None,
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the file at fault.
+    pub fn at(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// The issue `error` raised while reading `category`, keeping the path
+    /// of a file that does not parse.
+    pub fn from_error(category: ExtractionCategory, error: &Error) -> Self {
+        match error {
+            Error::ConfigParse { path, message } => {
+                Self::new(category, ExtractionIssueKind::Parse, message.clone()).at(path)
+            }
+            Error::Json(_) | Error::Yaml(_) | Error::Toml(_) => {
+                Self::new(category, ExtractionIssueKind::Parse, error.to_string())
+            }
+            _ => Self::new(category, ExtractionIssueKind::Io, error.to_string()),
+        }
+    }
+
+    /// The issue without its category, e.g. `settings.json: expected value`.
+    pub fn detail(&self) -> String {
+        match &self.path {
+            Some(path) => format!("{}: {}", path.display(), self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ExtractionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.category.label(), self.detail())
+    }
+}
+
+/// What a resource file's YAML front matter says about it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceMeta {
+    /// The `description` key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Other keys with a scalar or a list of scalars, e.g. `model` or
+    /// `allowed-tools`, as text. Lists are joined with `, `.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
 /// Summary of directory-based resources (skills, commands, etc.).
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResourceSummary {
     /// List of resource names/items.
     pub items: Vec<String>,
     /// Whether the resource directory exists.
     pub directory_exists: bool,
+    /// Whether listing stopped at the extraction entry limit, leaving items out.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Front matter of the items whose file has any, keyed by item name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, ResourceMeta>,
+}
+
+impl ResourceSummary {
+    /// The front matter description of `item`, if it has one.
+    pub fn description(&self, item: &str) -> Option<&str> {
+        self.meta.get(item)?.description.as_deref()
+    }
 }
 
 /// Information about a profile for display purposes.
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProfileInfo {
     /// Profile name.
     pub name: String,
@@ -41,20 +205,42 @@ pub struct ProfileInfo {
 
     /// Skills directory summary.
     pub skills: ResourceSummary,
+    /// Skill descriptions from `SKILL.md` front matter, keyed by skill name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub skill_descriptions: BTreeMap<String, String>,
+    /// Estimated tokens in each skill's definition file, keyed by skill name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub skill_tokens: BTreeMap<String, usize>,
     /// Commands directory summary.
     pub commands: ResourceSummary,
     /// Plugins directory summary (OpenCode only).
     pub plugins: Option<ResourceSummary>,
     /// Agents directory summary (OpenCode only).
     pub agents: Option<ResourceSummary>,
+    /// Summaries of the harness's custom resource categories, keyed by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, ResourceSummary>,
     /// Path to rules file if it exists.
     pub rules_file: Option<PathBuf>,
+    /// Estimated tokens in the rules file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules_tokens: Option<usize>,
     /// Theme setting (OpenCode only).
     pub theme: Option<String>,
     /// Model setting.
     pub model: Option<String>,
-    /// Errors encountered during extraction.
-    pub extraction_errors: Vec<String>,
+    /// Layer each top-level setting's effective value comes from, for
+    /// harnesses that merge several settings files (Claude Code).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub setting_layers: BTreeMap<String, SettingsLayer>,
+    /// Profile settings replaced by managed (administrator) settings.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overridden_settings: Vec<OverriddenSetting>,
+    /// Problems encountered during extraction.
+    pub extraction_errors: Vec<ExtractionIssue>,
+    /// Contents of the profile's `README.md`, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub readme: Option<String>,
 }
 
 #[cfg(test)]
@@ -96,4 +282,32 @@ mod tests {
         assert!(json.contains("\"name\":\"test\""));
         assert!(json.contains("\"is_active\":true"));
     }
+
+    #[test]
+    fn extraction_issues_keep_the_file_that_failed_to_parse() {
+        let error = Error::ConfigParse {
+            path: PathBuf::from("/p/opencode.jsonc"),
+            message: "expected value at line 5 column 5".to_string(),
+        };
+        let issue = ExtractionIssue::from_error(ExtractionCategory::Mcp, &error);
+        assert_eq!(issue.kind, ExtractionIssueKind::Parse);
+        assert_eq!(
+            issue.to_string(),
+            "MCP config: /p/opencode.jsonc: expected value at line 5 column 5"
+        );
+        assert_eq!(
+            serde_json::to_value(&issue).unwrap(),
+            serde_json::json!({
+                "category": "mcp",
+                "path": "/p/opencode.jsonc",
+                "kind": "parse",
+                "message": "expected value at line 5 column 5",
+            })
+        );
+
+        let io = Error::Io(std::io::Error::other("denied"));
+        let issue = ExtractionIssue::from_error(ExtractionCategory::Skills, &io);
+        assert_eq!(issue.kind, ExtractionIssueKind::Io);
+        assert!(issue.path.is_none());
+    }
 }
@@ -0,0 +1,118 @@
+//! Minimal Markdown rendering for the terminal.
+//!
+//! Enough of CommonMark for profile READMEs: headings, lists, block quotes,
+//! fenced code, and inline emphasis, code, and links. Anything else passes
+//! through as written.
+
+use colored::Colorize;
+
+/// Renders `markdown` as styled terminal text. Styling follows
+/// `settings.color`, so piped output is plain.
+pub fn render_markdown(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        let rendered = if in_code {
+            format!("    {}", line.dimmed())
+        } else if let Some((level, title)) = heading(trimmed) {
+            let title = render_inline(title);
+            if level == 1 {
+                title.bold().underline().to_string()
+            } else {
+                title.bold().to_string()
+            }
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            let indent = &line[..line.len() - trimmed.len()];
+            format!("{indent}  \u{2022} {}", render_inline(item))
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            format!("  \u{2502} {}", render_inline(quote.trim_start()).italic())
+        } else {
+            render_inline(line)
+        };
+        out.push_str(&rendered);
+        out.push('\n');
+    }
+    out
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let title = line[level..].strip_prefix(' ')?;
+    (1..=6)
+        .contains(&level)
+        .then(|| (level, title.trim_end_matches('#').trim()))
+}
+
+/// Styles `**bold**`, `*italic*`, `` `code` ``, and `[text](url)` spans.
+fn render_inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['*', '`', '[']) {
+        out.push_str(&rest[..start]);
+        let span = &rest[start..];
+        let (styled, used) = if let Some(inner) = delimited(span, "**") {
+            (inner.bold().to_string(), inner.len() + 4)
+        } else if let Some(inner) = delimited(span, "`") {
+            (inner.cyan().to_string(), inner.len() + 2)
+        } else if let Some(inner) = delimited(span, "*") {
+            (inner.italic().to_string(), inner.len() + 2)
+        } else if let Some((label, url, used)) = link(span) {
+            (format!("{} ({})", label.underline(), url.dimmed()), used)
+        } else {
+            (span[..1].to_string(), 1)
+        };
+        out.push_str(&styled);
+        rest = &span[used..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The text between `marker` at the start of `span` and its next occurrence.
+fn delimited<'a>(span: &'a str, marker: &str) -> Option<&'a str> {
+    let body = span.strip_prefix(marker)?;
+    let end = body.find(marker)?;
+    Some(&body[..end]).filter(|inner| !inner.is_empty() && !inner.starts_with(' '))
+}
+
+/// `[label](url)` at the start of `span`, with the bytes it spans.
+fn link(span: &str) -> Option<(&str, &str, usize)> {
+    let label_end = span.find("](")?;
+    let url_end = span[label_end..].find(')')? + label_end;
+    Some((
+        &span[1..label_end],
+        &span[label_end + 2..url_end],
+        url_end + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_lists_code_and_links() {
+        let rendered = render_markdown(
+            "# Team profile\n\nUse with [the docs](https://example.com).\n\n- first\n  - nested\n\n```sh\nbridle install\n```\n",
+        );
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert!(lines[0].contains("Team profile"));
+        assert!(!lines[0].contains('#'));
+        assert!(lines[2].contains("the docs"));
+        assert!(lines[2].contains("https://example.com"));
+        assert!(!lines[2].contains("]("));
+        assert!(lines[4].starts_with("  \u{2022} first"));
+        assert!(lines[5].starts_with("    \u{2022} nested"));
+        assert!(lines[7].starts_with("    "));
+        assert!(lines[7].contains("bridle install"));
+        assert_eq!(lines.len(), 8);
+    }
+}
@@ -4,12 +4,23 @@
 //! Both CLI and TUI consume the same `ProfileNode` tree structure, then render it
 //! according to their output format (flat text vs styled lines with tree branches).
 
+mod markdown;
+mod report;
+
+pub use markdown::render_markdown;
+pub use report::{profile_to_html, profile_to_markdown};
+
+use std::borrow::Cow;
+
+use colored::Colorize;
 use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
 };
 
-use crate::config::{McpServerInfo, ProfileInfo, ResourceSummary};
+use crate::config::{
+    LARGE_SKILL_TOKENS, McpServerInfo, ProfileInfo, ResourceSummary, SettingsLayer, format_tokens,
+};
 
 /// Semantic section types for profile display.
 ///
@@ -43,7 +54,7 @@ pub struct ProfileNode {
     /// Semantic type of this node.
     pub kind: SectionKind,
     /// Display label (e.g., "Theme", "MCP Servers").
-    pub label: &'static str,
+    pub label: Cow<'static, str>,
     /// Optional text content.
     pub text: Option<String>,
     /// Child nodes for nested content.
@@ -52,10 +63,10 @@ pub struct ProfileNode {
 
 impl ProfileNode {
     /// Create a new node with the given kind and label.
-    pub fn new(kind: SectionKind, label: &'static str) -> Self {
+    pub fn new(kind: SectionKind, label: impl Into<Cow<'static, str>>) -> Self {
         Self {
             kind,
-            label,
+            label: label.into(),
             text: None,
             children: vec![],
         }
@@ -114,15 +125,20 @@ pub fn profile_to_nodes(info: &ProfileInfo) -> Vec<ProfileNode> {
             ]),
     );
 
+    // Values from project or managed settings say where they came from.
+    let layer_note = |key: &str| match info.setting_layers.get(key) {
+        Some(layer) if *layer != SettingsLayer::User => format!(" ({})", layer.label()),
+        _ => String::new(),
+    };
     let theme_text = match &info.theme {
-        Some(theme) => theme.clone(),
+        Some(theme) => format!("{theme}{}", layer_note("theme")),
         None if info.harness_id == "opencode" => "(not set)".to_string(),
         None => "(not supported)".to_string(),
     };
     nodes.push(ProfileNode::new(SectionKind::Field, "Theme").with_text(theme_text));
 
     let model_text = match &info.model {
-        Some(model) => model.clone(),
+        Some(model) => format!("{model}{}", layer_note("model")),
         None => "(not set)".to_string(),
     };
     nodes.push(ProfileNode::new(SectionKind::Field, "Model").with_text(model_text));
@@ -130,6 +146,9 @@ pub fn profile_to_nodes(info: &ProfileInfo) -> Vec<ProfileNode> {
     nodes.push(build_mcp_node(info));
 
     nodes.push(build_resource_node("Skills", &info.skills, true));
+    if let Some(large) = large_skills_text(info) {
+        nodes.push(ProfileNode::new(SectionKind::Field, "Large skills").with_text(large));
+    }
     nodes.push(build_resource_node("Commands", &info.commands, true));
 
     match &info.plugins {
@@ -148,6 +167,10 @@ pub fn profile_to_nodes(info: &ProfileInfo) -> Vec<ProfileNode> {
         ),
     }
 
+    for (name, summary) in &info.resources {
+        nodes.push(build_resource_node(capitalized(name), summary, true));
+    }
+
     // Rules file
     let (rules_exists, rules_text) = match &info.rules_file {
         Some(path) => {
@@ -155,7 +178,13 @@ pub fn profile_to_nodes(info: &ProfileInfo) -> Vec<ProfileNode> {
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("(unknown)");
-            (true, filename.to_string())
+            match info.rules_tokens {
+                Some(tokens) => (
+                    true,
+                    format!("{filename} (~{} tokens)", format_tokens(tokens)),
+                ),
+                None => (true, filename.to_string()),
+            }
         }
         None => (false, "(none)".to_string()),
     };
@@ -169,11 +198,31 @@ pub fn profile_to_nodes(info: &ProfileInfo) -> Vec<ProfileNode> {
         .with_text(rules_text),
     );
 
+    if !info.overridden_settings.is_empty() {
+        let children = info
+            .overridden_settings
+            .iter()
+            .map(|o| {
+                ProfileNode::new(SectionKind::Error, "").with_text(format!(
+                    "{}: profile sets {}, {} settings enforce {}",
+                    o.key,
+                    o.profile_value,
+                    o.layer.label(),
+                    o.effective_value
+                ))
+            })
+            .collect();
+        nodes.push(ProfileNode::new(SectionKind::Error, "Overridden").with_children(children));
+    }
+
     if !info.extraction_errors.is_empty() {
         let error_children: Vec<ProfileNode> = info
             .extraction_errors
             .iter()
-            .map(|err| ProfileNode::new(SectionKind::Error, "").with_text(err.clone()))
+            .map(|issue| {
+                ProfileNode::new(SectionKind::Error, issue.category.label())
+                    .with_text(issue.detail())
+            })
             .collect();
         nodes.push(ProfileNode::new(SectionKind::Error, "Errors").with_children(error_children));
     }
@@ -181,6 +230,26 @@ pub fn profile_to_nodes(info: &ProfileInfo) -> Vec<ProfileNode> {
     nodes
 }
 
+/// Skills at or above [`LARGE_SKILL_TOKENS`], largest first, e.g.
+/// `review (~3.1k tokens)`.
+fn large_skills_text(info: &ProfileInfo) -> Option<String> {
+    let mut large: Vec<(&String, usize)> = info
+        .skill_tokens
+        .iter()
+        .filter(|(_, tokens)| **tokens >= LARGE_SKILL_TOKENS)
+        .map(|(name, tokens)| (name, *tokens))
+        .collect();
+    if large.is_empty() {
+        return None;
+    }
+    large.sort_by_key(|(_, tokens)| std::cmp::Reverse(*tokens));
+    let parts: Vec<String> = large
+        .iter()
+        .map(|(name, tokens)| format!("{name} (~{} tokens)", format_tokens(*tokens)))
+        .collect();
+    Some(parts.join(", "))
+}
+
 fn build_mcp_node(info: &ProfileInfo) -> ProfileNode {
     if info.mcp_servers.is_empty() {
         return ProfileNode::new(SectionKind::McpGroup, "MCP Servers").with_text("(none)");
@@ -213,7 +282,7 @@ fn build_mcp_node(info: &ProfileInfo) -> ProfileNode {
 }
 
 fn build_resource_node(
-    label: &'static str,
+    label: impl Into<Cow<'static, str>>,
     summary: &ResourceSummary,
     _supported: bool,
 ) -> ProfileNode {
@@ -231,14 +300,46 @@ fn build_resource_node(
     let children: Vec<ProfileNode> = summary
         .items
         .iter()
-        .map(|item| ProfileNode::new(SectionKind::ResourceItem, "").with_text(item.clone()))
+        .map(|item| {
+            let node = ProfileNode::new(SectionKind::ResourceItem, "").with_text(item.clone());
+            match summary.description(item) {
+                // Block scalars span lines; the listing has one line per item.
+                Some(description) => node.with_children(vec![
+                    ProfileNode::new(SectionKind::Field, "Description")
+                        .with_text(description.split_whitespace().collect::<Vec<_>>().join(" ")),
+                ]),
+                None => node,
+            }
+        })
         .collect();
 
     ProfileNode::new(SectionKind::ResourceGroup { exists: true }, label)
-        .with_text(format!("({}) {}", summary.items.len(), items_text))
+        .with_text(format!(
+            "({}{}) {}",
+            summary.items.len(),
+            if summary.truncated { "+" } else { "" },
+            items_text
+        ))
         .with_children(children)
 }
 
+/// The description shown next to a resource item, if its front matter has one.
+fn item_description(item: &ProfileNode) -> Option<&str> {
+    item.children
+        .iter()
+        .find(|c| c.label == "Description")
+        .and_then(|c| c.text.as_deref())
+}
+
+/// `name` with its first letter upper-cased, as a group label.
+fn capitalized(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 /// Render profile nodes to flat CLI text output.
 pub fn nodes_to_text(nodes: &[ProfileNode]) -> String {
     let mut output = String::new();
@@ -317,12 +418,26 @@ fn render_node_text(out: &mut String, node: &ProfileNode) {
             } else {
                 let count_part = text.split(')').next().unwrap_or("");
                 let _ = writeln!(out, "{} {}):", node.label, count_part);
-                let items: Vec<&str> = node
-                    .children
-                    .iter()
-                    .filter_map(|c| c.text.as_deref())
-                    .collect();
-                let _ = writeln!(out, "  {}", items.join(", "));
+                if node.children.iter().any(|c| item_description(c).is_some()) {
+                    for child in &node.children {
+                        let name = child.text.as_deref().unwrap_or("");
+                        match item_description(child) {
+                            Some(description) => {
+                                let _ = writeln!(out, "  {name}: {}", description.dimmed());
+                            }
+                            None => {
+                                let _ = writeln!(out, "  {name}");
+                            }
+                        }
+                    }
+                } else {
+                    let items: Vec<&str> = node
+                        .children
+                        .iter()
+                        .filter_map(|c| c.text.as_deref())
+                        .collect();
+                    let _ = writeln!(out, "  {}", items.join(", "));
+                }
             }
         }
         SectionKind::ResourceItem => {}
@@ -335,11 +450,16 @@ fn render_node_text(out: &mut String, node: &ProfileNode) {
             );
         }
         SectionKind::Error => {
-            if node.label == "Errors" {
+            if !node.children.is_empty() {
                 let _ = writeln!(out);
                 let _ = writeln!(out, "{}:", node.label);
                 for child in &node.children {
-                    let _ = writeln!(out, "  \u{26a0} {}", child.text.as_deref().unwrap_or(""));
+                    let text = child.text.as_deref().unwrap_or("");
+                    if child.label.is_empty() {
+                        let _ = writeln!(out, "  \u{26a0} {text}");
+                    } else {
+                        let _ = writeln!(out, "  \u{26a0} {}: {text}", child.label.yellow().bold());
+                    }
                 }
             }
         }
@@ -401,7 +521,8 @@ pub fn nodes_to_lines(nodes: &[ProfileNode]) -> Vec<Line<'static>> {
         .iter()
         .filter(|n| !matches!(n.kind, SectionKind::Header))
         .filter(|n| {
-            !matches!(n.kind, SectionKind::Field) || (n.label == "Theme" || n.label == "Model")
+            !matches!(n.kind, SectionKind::Field)
+                || matches!(n.label.as_ref(), "Theme" | "Model" | "Large skills")
         })
         .filter(|n| {
             if matches!(n.kind, SectionKind::ResourceGroup { .. }) {
@@ -467,7 +588,7 @@ fn render_node_lines(lines: &mut Vec<Line<'static>>, node: &ProfileNode, tree: &
             let item_count = node.children.len();
             for (i, child) in node.children.iter().enumerate() {
                 let sub_tree = TreeBranch::for_index(i, item_count);
-                lines.push(Line::styled(
+                let mut spans = vec![Span::styled(
                     format!(
                         "  {} {} {}",
                         tree.continuation,
@@ -475,32 +596,41 @@ fn render_node_lines(lines: &mut Vec<Line<'static>>, node: &ProfileNode, tree: &
                         child.text.as_deref().unwrap_or("")
                     ),
                     Style::default().fg(Color::Gray),
-                ));
+                )];
+                if let Some(description) = item_description(child) {
+                    spans.push(Span::styled(
+                        format!(" - {description}"),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                lines.push(Line::from(spans));
             }
         }
-        SectionKind::RulesFile { exists } => {
-            if *exists {
-                lines.push(Line::styled(
-                    format!(
-                        "  {} Rules: {}",
-                        tree.branch,
-                        node.text.as_deref().unwrap_or("")
-                    ),
-                    Style::default().fg(Color::Gray),
-                ));
-            }
+        SectionKind::RulesFile { exists: true } => {
+            lines.push(Line::styled(
+                format!(
+                    "  {} Rules: {}",
+                    tree.branch,
+                    node.text.as_deref().unwrap_or("")
+                ),
+                Style::default().fg(Color::Gray),
+            ));
         }
+        SectionKind::RulesFile { exists: false } => {}
         SectionKind::Error => {
-            if node.label == "Errors" {
+            if !node.children.is_empty() {
+                let style = Style::default().fg(Color::Yellow);
                 for child in &node.children {
-                    lines.push(Line::styled(
-                        format!(
-                            "  {} ⚠ {}",
-                            tree.branch,
-                            child.text.as_deref().unwrap_or("")
-                        ),
-                        Style::default().fg(Color::Yellow),
-                    ));
+                    let text = child.text.as_deref().unwrap_or("");
+                    let mut spans = vec![Span::styled(format!("  {} ⚠ ", tree.branch), style)];
+                    if !child.label.is_empty() {
+                        spans.push(Span::styled(
+                            format!("{}: ", child.label),
+                            style.add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                    spans.push(Span::styled(text.to_string(), style));
+                    lines.push(Line::from(spans));
                 }
             } else {
                 lines.push(Line::styled(
@@ -543,6 +673,7 @@ fn render_mcp_server_line(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{ExtractionCategory, ExtractionIssue, ExtractionIssueKind, ResourceMeta};
     use std::path::PathBuf;
 
     #[test]
@@ -596,13 +727,18 @@ mod tests {
             path: PathBuf::from("/path/to/profile"),
             mcp_servers: vec![],
             skills: ResourceSummary::default(),
+            skill_descriptions: Default::default(),
+            skill_tokens: Default::default(),
             commands: ResourceSummary::default(),
             plugins: None,
             agents: None,
             rules_file: None,
+            rules_tokens: None,
             theme: Some("dark".to_string()),
             model: Some("gpt-4".to_string()),
             extraction_errors: vec![],
+            readme: None,
+            ..Default::default()
         };
 
         let nodes = profile_to_nodes(&info);
@@ -621,13 +757,30 @@ mod tests {
             path: PathBuf::from("/tmp"),
             mcp_servers: vec![],
             skills: ResourceSummary::default(),
+            skill_descriptions: Default::default(),
+            skill_tokens: Default::default(),
             commands: ResourceSummary::default(),
             plugins: None,
             agents: None,
             rules_file: None,
+            rules_tokens: None,
             theme: None,
             model: None,
-            extraction_errors: vec!["Error 1".to_string(), "Error 2".to_string()],
+            extraction_errors: vec![
+                ExtractionIssue::new(
+                    ExtractionCategory::Mcp,
+                    ExtractionIssueKind::Parse,
+                    "expected value",
+                )
+                .at("/tmp/opencode.jsonc"),
+                ExtractionIssue::new(
+                    ExtractionCategory::Rules,
+                    ExtractionIssueKind::Layout,
+                    "unsupported",
+                ),
+            ],
+            readme: None,
+            ..Default::default()
         };
 
         let nodes = profile_to_nodes(&info);
@@ -635,6 +788,102 @@ mod tests {
 
         assert!(errors_node.is_some());
         assert_eq!(errors_node.unwrap().children.len(), 2);
+        let text = nodes_to_text(&nodes);
+        assert!(text.contains("MCP config: /tmp/opencode.jsonc: expected value"));
+        assert!(text.contains("rules: unsupported"));
+    }
+
+    #[test]
+    fn resource_descriptions_are_listed_one_item_per_line() {
+        let info = ProfileInfo {
+            name: "described".to_string(),
+            commands: ResourceSummary {
+                items: vec!["commit".to_string(), "lint".to_string()],
+                directory_exists: true,
+                meta: [(
+                    "commit".to_string(),
+                    ResourceMeta {
+                        description: Some("Writes a\ncommit message".to_string()),
+                        ..Default::default()
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let text = nodes_to_text(&profile_to_nodes(&info));
+
+        assert!(text.contains("Commands (2):\n  commit: Writes a commit message\n  lint\n"));
+    }
+
+    #[test]
+    fn custom_resource_categories_get_their_own_groups() {
+        let info = ProfileInfo {
+            name: "custom".to_string(),
+            resources: [(
+                "prompts".to_string(),
+                ResourceSummary {
+                    items: vec!["triage".to_string()],
+                    directory_exists: true,
+                    ..Default::default()
+                },
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        let nodes = profile_to_nodes(&info);
+
+        let prompts = nodes.iter().find(|n| n.label == "Prompts").unwrap();
+        assert_eq!(prompts.text.as_deref(), Some("(1) triage"));
+        assert!(nodes_to_text(&nodes).contains("Prompts (1):"));
+    }
+
+    #[test]
+    fn test_profile_to_nodes_reports_token_estimates() {
+        let info = ProfileInfo {
+            name: "big".to_string(),
+            harness_id: "claude-code".to_string(),
+            rules_file: Some(PathBuf::from("/tmp/CLAUDE.md")),
+            rules_tokens: Some(1_234),
+            skill_tokens: [("small".to_string(), 10), ("review".to_string(), 3_100)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let output = nodes_to_text(&profile_to_nodes(&info));
+
+        assert!(output.contains("Rules: CLAUDE.md (~1.2k tokens)"));
+        assert!(output.contains("Large skills: review (~3.1k tokens)"));
+        assert!(!output.contains("small (~"));
+    }
+
+    #[test]
+    fn test_profile_to_nodes_flags_managed_overrides() {
+        let info = ProfileInfo {
+            harness_id: "claude-code".to_string(),
+            model: Some("sonnet".to_string()),
+            setting_layers: [("model".to_string(), SettingsLayer::Managed)]
+                .into_iter()
+                .collect(),
+            overridden_settings: vec![crate::config::OverriddenSetting {
+                key: "model".to_string(),
+                layer: SettingsLayer::Managed,
+                profile_value: "opus".into(),
+                effective_value: "sonnet".into(),
+            }],
+            ..Default::default()
+        };
+
+        let output = nodes_to_text(&profile_to_nodes(&info));
+
+        assert!(output.contains("Model: sonnet (managed)"));
+        assert!(
+            output.contains(r#"model: profile sets "opus", managed settings enforce "sonnet""#)
+        );
     }
 
     #[test]
@@ -671,13 +920,13 @@ mod tests {
             ProfileNode::new(SectionKind::McpGroup, "MCP Servers").with_children(vec![
                 ProfileNode {
                     kind: SectionKind::McpServer { enabled: true },
-                    label: "",
+                    label: "".into(),
                     text: Some("enabled-server (stdio): cmd".to_string()),
                     children: vec![],
                 },
                 ProfileNode {
                     kind: SectionKind::McpServer { enabled: false },
-                    label: "",
+                    label: "".into(),
                     text: Some("disabled-server (stdio): cmd2 (disabled)".to_string()),
                     children: vec![],
                 },
@@ -709,7 +958,7 @@ mod tests {
             ProfileNode::new(SectionKind::McpGroup, "MCP Servers").with_children(vec![
                 ProfileNode {
                     kind: SectionKind::McpServer { enabled: false },
-                    label: "",
+                    label: "".into(),
                     text: Some("disabled-server (stdio): cmd (disabled)".to_string()),
                     children: vec![],
                 },
@@ -0,0 +1,291 @@
+//! Shareable Markdown and HTML reports of a profile, for documenting team setups.
+//!
+//! Unlike the CLI text output, reports leave out machine-specific details such
+//! as the profile path, and include skill descriptions and a rules file excerpt.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::config::{ProfileInfo, ResourceSummary};
+
+use super::{capitalized, format_mcp_detail};
+
+/// Lines of the rules file included in a report.
+const RULES_EXCERPT_LINES: usize = 20;
+
+/// A titled list of resource entries, each with an optional description.
+struct Section {
+    title: Cow<'static, str>,
+    entries: Vec<(String, Option<String>)>,
+}
+
+/// Collects the report sections shared by both renderers.
+fn sections(info: &ProfileInfo) -> Vec<Section> {
+    let mcp = info
+        .mcp_servers
+        .iter()
+        .map(|server| {
+            let mut detail = format_mcp_detail(server);
+            if !server.enabled {
+                detail = format!("{detail} (disabled)").trim_start().to_string();
+            }
+            (server.name.clone(), Some(detail).filter(|d| !d.is_empty()))
+        })
+        .collect();
+    let resources = |summary: Option<&ResourceSummary>| {
+        summary
+            .map(|s| {
+                s.items
+                    .iter()
+                    .map(|item| (item.clone(), s.description(item).map(String::from)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let skills = info
+        .skills
+        .items
+        .iter()
+        .map(|name| {
+            let description = info
+                .skill_descriptions
+                .get(name)
+                .map(String::as_str)
+                .or_else(|| info.skills.description(name));
+            (name.clone(), description.map(String::from))
+        })
+        .collect();
+
+    vec![
+        Section {
+            title: "MCP Servers".into(),
+            entries: mcp,
+        },
+        Section {
+            title: "Skills".into(),
+            entries: skills,
+        },
+        Section {
+            title: "Commands".into(),
+            entries: resources(Some(&info.commands)),
+        },
+        Section {
+            title: "Agents".into(),
+            entries: resources(info.agents.as_ref()),
+        },
+        Section {
+            title: "Plugins".into(),
+            entries: resources(info.plugins.as_ref()),
+        },
+    ]
+    .into_iter()
+    .chain(info.resources.iter().map(|(name, summary)| Section {
+        title: capitalized(name).into(),
+        entries: resources(Some(summary)),
+    }))
+    .collect()
+}
+
+/// The rules file's name and its first lines, if the profile has one.
+fn rules_excerpt(info: &ProfileInfo) -> Option<(String, String)> {
+    let path = info.rules_file.as_ref()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let mut excerpt: Vec<&str> = content.lines().take(RULES_EXCERPT_LINES).collect();
+    if content.lines().count() > RULES_EXCERPT_LINES {
+        excerpt.push("...");
+    }
+    Some((name, excerpt.join("\n")))
+}
+
+fn status(info: &ProfileInfo) -> &'static str {
+    if info.is_active { "Active" } else { "Inactive" }
+}
+
+/// Renders `info` as a Markdown document.
+pub fn profile_to_markdown(info: &ProfileInfo) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Profile `{}`\n", info.name);
+    let _ = writeln!(out, "| Setting | Value |");
+    let _ = writeln!(out, "| --- | --- |");
+    let fields = [
+        ("Harness", Some(info.harness_id.as_str())),
+        ("Status", Some(status(info))),
+        ("Model", info.model.as_deref()),
+        ("Theme", info.theme.as_deref()),
+    ];
+    for (label, value) in fields {
+        let value = value.map_or("(not set)".to_string(), |v| format!("`{v}`"));
+        let _ = writeln!(out, "| {label} | {value} |");
+    }
+
+    for section in sections(info) {
+        let _ = writeln!(out, "\n## {}\n", section.title);
+        if section.entries.is_empty() {
+            let _ = writeln!(out, "_None_");
+        }
+        for (name, description) in section.entries {
+            match description {
+                Some(description) => {
+                    let _ = writeln!(out, "- **{name}**: {description}");
+                }
+                None => {
+                    let _ = writeln!(out, "- **{name}**");
+                }
+            }
+        }
+    }
+
+    if let Some((name, excerpt)) = rules_excerpt(info) {
+        let _ = writeln!(out, "\n## Rules (`{name}`)\n");
+        let _ = writeln!(out, "```markdown\n{excerpt}\n```");
+    }
+    out
+}
+
+/// Escapes text for use in HTML element content and attribute values.
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders `info` as a standalone HTML document.
+pub fn profile_to_html(info: &ProfileInfo) -> String {
+    let mut out = String::new();
+    let title = escape_html(&info.name);
+    let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>");
+    let _ = writeln!(out, "<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>Profile {title}</title>\n</head>\n<body>");
+    let _ = writeln!(out, "<h1>Profile <code>{title}</code></h1>");
+    let _ = writeln!(out, "<table>");
+    let fields = [
+        ("Harness", Some(info.harness_id.as_str())),
+        ("Status", Some(status(info))),
+        ("Model", info.model.as_deref()),
+        ("Theme", info.theme.as_deref()),
+    ];
+    for (label, value) in fields {
+        let value = value.map_or("(not set)".to_string(), |v| {
+            format!("<code>{}</code>", escape_html(v))
+        });
+        let _ = writeln!(out, "<tr><th>{label}</th><td>{value}</td></tr>");
+    }
+    let _ = writeln!(out, "</table>");
+
+    for section in sections(info) {
+        let _ = writeln!(out, "<h2>{}</h2>", escape_html(&section.title));
+        if section.entries.is_empty() {
+            let _ = writeln!(out, "<p><em>None</em></p>");
+            continue;
+        }
+        let _ = writeln!(out, "<ul>");
+        for (name, description) in section.entries {
+            let name = escape_html(&name);
+            match description {
+                Some(description) => {
+                    let _ = writeln!(
+                        out,
+                        "<li><strong>{name}</strong>: {}</li>",
+                        escape_html(&description)
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "<li><strong>{name}</strong></li>");
+                }
+            }
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+
+    if let Some((name, excerpt)) = rules_excerpt(info) {
+        let _ = writeln!(out, "<h2>Rules (<code>{}</code>)</h2>", escape_html(&name));
+        let _ = writeln!(out, "<pre>{}</pre>", escape_html(&excerpt));
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{McpServerInfo, ResourceMeta};
+
+    fn sample_info(rules_file: Option<std::path::PathBuf>) -> ProfileInfo {
+        ProfileInfo {
+            name: "team".to_string(),
+            harness_id: "claude-code".to_string(),
+            model: Some("sonnet".to_string()),
+            mcp_servers: vec![McpServerInfo {
+                name: "github".to_string(),
+                enabled: false,
+                server_type: Some("stdio".to_string()),
+                command: Some("npx".to_string()),
+                ..Default::default()
+            }],
+            skills: ResourceSummary {
+                items: vec!["review".to_string(), "plain".to_string()],
+                directory_exists: true,
+                ..Default::default()
+            },
+            skill_descriptions: [("review".to_string(), "Reviews <diffs>".to_string())]
+                .into_iter()
+                .collect(),
+            rules_file,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn markdown_lists_servers_skills_and_rules() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let rules = temp.path().join("CLAUDE.md");
+        std::fs::write(&rules, "Be brief.\n").unwrap();
+
+        let mut info = sample_info(Some(rules));
+        info.commands = ResourceSummary {
+            items: vec!["ship".to_string()],
+            directory_exists: true,
+            meta: [(
+                "ship".to_string(),
+                ResourceMeta {
+                    description: Some("Tags a release".to_string()),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        let report = profile_to_markdown(&info);
+
+        assert!(report.starts_with("# Profile `team`"));
+        assert!(report.contains("| Model | `sonnet` |"));
+        assert!(report.contains("| Theme | (not set) |"));
+        assert!(report.contains("- **github**: (stdio): npx (disabled)"));
+        assert!(report.contains("- **review**: Reviews <diffs>"));
+        assert!(report.contains("- **plain**\n"));
+        assert!(report.contains("- **ship**: Tags a release"));
+        assert!(report.contains("## Rules (`CLAUDE.md`)\n\n```markdown\nBe brief.\n```"));
+        assert!(!report.contains(temp.path().to_str().unwrap()));
+    }
+
+    #[test]
+    fn html_escapes_content() {
+        let report = profile_to_html(&sample_info(None));
+
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains("<li><strong>review</strong>: Reviews &lt;diffs&gt;</li>"));
+        assert!(report.contains("<h2>Commands</h2>\n<p><em>None</em></p>"));
+        assert!(!report.contains("<h2>Rules"));
+    }
+}
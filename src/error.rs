@@ -2,57 +2,153 @@
 
 #![allow(dead_code)]
 
+use std::path::PathBuf;
+
+use serde::Serialize;
 use thiserror::Error;
 
+use crate::config::{InvalidProfileName, Violation};
+
 /// Result type alias using bridle's Error.
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Broad classification of an [`Error`], used to pick exit behavior and hints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// A referenced profile, harness, or file does not exist.
+    NotFound,
+    /// The operation would clobber something that already exists.
+    Conflict,
+    /// User input failed validation.
+    InvalidInput,
+    /// A configuration file could not be parsed or serialized.
+    Parse,
+    /// The environment is missing something bridle needs (harness, config dir).
+    Environment,
+    /// The team policy file forbids the operation.
+    Policy,
+    /// Filesystem or external command failure.
+    Io,
+}
+
 /// Errors that can occur in bridle.
 #[derive(Error, Debug)]
 pub enum Error {
-    /// No configuration file found at expected location.
-    #[error("no config found: {0}")]
-    NoConfigFound(String),
+    /// No configuration found for a harness.
+    #[error("no config found for {harness}")]
+    NoConfigFound { harness: String },
+
+    /// Bridle's own configuration directory could not be determined.
+    #[error("could not determine config directory: {0}")]
+    ConfigDirUnavailable(String),
 
     /// Failed to read or write configuration.
     #[error("config error: {0}")]
     Config(String),
 
+    /// A harness configuration file exists but could not be parsed.
+    #[error("failed to parse {}: {message}", path.display())]
+    ConfigParse { path: PathBuf, message: String },
+
+    /// A key path is not set in a harness config file.
+    #[error("{key} is not set in {}", path.display())]
+    ConfigKeyNotFound { key: String, path: PathBuf },
+
+    /// A harness has no known primary config file to edit.
+    #[error("{harness} has no default config file")]
+    NoDefaultConfigFile { harness: String },
+
     /// Harness executable not installed or not in PATH.
-    #[error("harness not installed")]
-    HarnessNotInstalled,
+    #[error("harness not installed: {harness}")]
+    HarnessNotInstalled { harness: String },
 
     /// Profile with given name does not exist.
-    #[error("profile not found: {0}")]
-    ProfileNotFound(String),
+    #[error("profile not found: {harness}/{profile}")]
+    ProfileNotFound { harness: String, profile: String },
+
+    /// Profile directory disappeared from disk.
+    #[error("profile directory not found: {}", .0.display())]
+    ProfileDirMissing(PathBuf),
+
+    /// A shared fragment included by a profile does not exist.
+    #[error("shared fragment not found: {}", .0.display())]
+    FragmentNotFound(PathBuf),
+
+    /// A switch was stopped because the live config has unsaved changes.
+    #[error("live config has unsaved changes to {harness}/{profile}: {}", files.join(", "))]
+    DriftAborted {
+        harness: String,
+        profile: String,
+        files: Vec<String>,
+    },
 
     /// Profile with given name already exists.
-    #[error("profile already exists: {0}")]
-    ProfileExists(String),
+    #[error("profile already exists: {harness}/{profile}")]
+    ProfileExists { harness: String, profile: String },
 
     /// No profile is currently active.
-    #[error("no active profile")]
-    NoActiveProfile,
+    #[error("no active profile for {harness}")]
+    NoActiveProfile { harness: String },
 
     /// Profile name contains invalid characters.
-    #[error("invalid profile name: {0}")]
-    InvalidProfileName(String),
+    #[error("invalid profile name '{name}': {reason}")]
+    InvalidProfileName {
+        name: String,
+        #[source]
+        reason: InvalidProfileName,
+    },
+
+    /// A profile template references a variable with no value.
+    #[error("no value for template variable '{name}' in profile {profile}")]
+    MissingVariable { name: String, profile: String },
+
+    /// A profile references a secret that is not stored.
+    #[error("secret not found: {name}")]
+    MissingSecret { name: String },
+
+    /// The system keyring could not be used.
+    #[error("keyring error: {0}")]
+    Keyring(String),
+
+    /// A configured secret provider failed.
+    #[error("secret provider {provider} failed: {message}")]
+    SecretProvider { provider: String, message: String },
+
+    /// Context with given name does not exist.
+    #[error("context not found: {0}")]
+    ContextNotFound(String),
+
+    /// Context with given name already exists.
+    #[error("context already exists: {0}")]
+    ContextExists(String),
 
     /// Unknown harness name.
-    #[error("unknown harness: {0}\nValid options: claude-code, opencode, goose, amp-code")]
+    #[error("unknown harness: {0}")]
     UnknownHarness(String),
 
     /// Command failed.
     #[error("{0}")]
     Command(String),
 
+    /// The team policy file forbids what was asked for.
+    #[error(
+        "{} policy violation(s) in {policy}:{}",
+        violations.len(),
+        violations.iter().map(|v| format!("\n  - {v}")).collect::<String>()
+    )]
+    PolicyViolation {
+        policy: String,
+        violations: Vec<Violation>,
+    },
+
     /// Unknown configuration setting.
-    #[error("unknown setting: {0}\nValid options: editor, marker_files, default_harness")]
+    #[error("unknown setting: {0}")]
     UnknownSetting(String),
 
     /// Invalid configuration value.
-    #[error("invalid value: {0}")]
-    InvalidValue(String),
+    #[error("invalid value for {key}: {value}")]
+    InvalidValue { key: String, value: String },
 
     /// IO error.
     #[error(transparent)]
@@ -74,3 +170,172 @@ pub enum Error {
     #[error(transparent)]
     Yaml(#[from] serde_yaml::Error),
 }
+
+impl Error {
+    /// Shorthand for [`Error::ProfileNotFound`].
+    pub fn profile_not_found(harness: &str, profile: &str) -> Self {
+        Self::ProfileNotFound {
+            harness: harness.to_string(),
+            profile: profile.to_string(),
+        }
+    }
+
+    /// Shorthand for [`Error::ProfileExists`].
+    pub fn profile_exists(harness: &str, profile: &str) -> Self {
+        Self::ProfileExists {
+            harness: harness.to_string(),
+            profile: profile.to_string(),
+        }
+    }
+
+    /// Shorthand for [`Error::InvalidProfileName`].
+    pub fn invalid_profile_name(name: &str, reason: InvalidProfileName) -> Self {
+        Self::InvalidProfileName {
+            name: name.to_string(),
+            reason,
+        }
+    }
+
+    /// Returns the broad category this error belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::NoConfigFound { .. }
+            | Self::ProfileNotFound { .. }
+            | Self::ProfileDirMissing(_)
+            | Self::FragmentNotFound(_)
+            | Self::ConfigKeyNotFound { .. }
+            | Self::NoActiveProfile { .. }
+            | Self::MissingVariable { .. }
+            | Self::MissingSecret { .. }
+            | Self::ContextNotFound(_) => ErrorCategory::NotFound,
+            Self::ProfileExists { .. } | Self::ContextExists(_) | Self::DriftAborted { .. } => {
+                ErrorCategory::Conflict
+            }
+            Self::InvalidProfileName { .. }
+            | Self::UnknownHarness(_)
+            | Self::UnknownSetting(_)
+            | Self::NoDefaultConfigFile { .. }
+            | Self::InvalidValue { .. } => ErrorCategory::InvalidInput,
+            Self::ConfigParse { .. } | Self::Toml(_) | Self::Json(_) | Self::Yaml(_) => {
+                ErrorCategory::Parse
+            }
+            Self::ConfigDirUnavailable(_)
+            | Self::HarnessNotInstalled { .. }
+            | Self::Harness(_)
+            | Self::Keyring(_)
+            | Self::SecretProvider { .. } => ErrorCategory::Environment,
+            Self::PolicyViolation { .. } => ErrorCategory::Policy,
+            Self::Config(_) | Self::Command(_) | Self::Io(_) => ErrorCategory::Io,
+        }
+    }
+
+    /// Returns a remediation hint suitable for showing below the error message.
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            Self::NoConfigFound { harness } => Some(format!(
+                "Is {harness} installed? Run `bridle status` to check"
+            )),
+            Self::ConfigDirUnavailable(_) => {
+                Some("Set BRIDLE_CONFIG_DIR to choose a config location".to_string())
+            }
+            Self::ConfigParse { path, .. } => {
+                Some(format!("Fix the syntax in {} and retry", path.display()))
+            }
+            Self::NoDefaultConfigFile { .. } => Some(
+                "Pass --file with the config file's path relative to the harness directory"
+                    .to_string(),
+            ),
+            Self::HarnessNotInstalled { harness } => Some(format!(
+                "Install {harness}, then run `bridle init` to capture its config"
+            )),
+            Self::ProfileNotFound { harness, profile } => Some(format!(
+                "Run `bridle profile list {harness}` to see profiles, or \
+                 `bridle profile create {harness} {profile}` to create it"
+            )),
+            Self::ProfileExists { harness, profile } => Some(format!(
+                "Pick another name or run `bridle profile delete {harness} {profile}` first"
+            )),
+            Self::DriftAborted { .. } => {
+                Some("Re-run with `--on-drift save`, `discard`, or `stash`".to_string())
+            }
+            Self::NoActiveProfile { harness } => Some(format!(
+                "Run `bridle profile switch {harness} <name>` to activate one"
+            )),
+            Self::InvalidProfileName {
+                reason: InvalidProfileName::CaseCollision(existing),
+                ..
+            } => Some(format!(
+                "Rename the '{existing}' directory to lowercase, or pick another name"
+            )),
+            Self::InvalidProfileName { .. } => Some(
+                "Use 1-64 lowercase letters, digits, and single hyphens (e.g. `work-setup`)"
+                    .to_string(),
+            ),
+            Self::MissingVariable { name, .. } => Some(format!(
+                "Set {} or add `{name} = \"...\"` to the profile's {}",
+                crate::config::template::env_name(name),
+                crate::config::template::VARS_FILE
+            )),
+            Self::FragmentNotFound(path) => Some(format!(
+                "Create {} or remove it from the profile's include list in profile.toml",
+                path.display()
+            )),
+            Self::MissingSecret { name } => Some(match name.split_once('/') {
+                Some((provider, _)) => format!(
+                    "The {provider} provider printed nothing; check its command in config.toml"
+                ),
+                None => format!("Store it with `bridle secret set {name}`"),
+            }),
+            Self::SecretProvider { provider, .. } => Some(format!(
+                "Check the [secret_providers.{provider}] command in config.toml"
+            )),
+            Self::ContextNotFound(name) => Some(format!(
+                "Run `bridle context list` to see contexts, or `bridle context create {name}`"
+            )),
+            Self::ContextExists(name) => {
+                Some(format!("Switch to it with `bridle context use {name}`"))
+            }
+            Self::UnknownHarness(_) => {
+                Some("Valid options: claude-code, opencode, goose, amp-code".to_string())
+            }
+            Self::PolicyViolation { policy, .. } => Some(format!(
+                "Remove the components it names, or ask the maintainers of {policy} for an exception"
+            )),
+            Self::UnknownSetting(_) => Some(format!(
+                "Valid options: profile_marker, {}",
+                crate::config::Settings::KEYS.join(", ")
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_not_found_carries_context() {
+        let err = Error::profile_not_found("opencode", "work");
+        assert_eq!(err.to_string(), "profile not found: opencode/work");
+        assert_eq!(err.category(), ErrorCategory::NotFound);
+        let hint = err.hint().unwrap();
+        assert!(hint.contains("bridle profile create opencode work"));
+    }
+
+    #[test]
+    fn invalid_profile_name_exposes_reason() {
+        let err =
+            Error::invalid_profile_name("my_profile", InvalidProfileName::InvalidCharacter('_'));
+        assert!(err.to_string().contains("my_profile"));
+        assert!(std::error::Error::source(&err).is_some());
+        assert_eq!(err.category(), ErrorCategory::InvalidInput);
+    }
+
+    #[test]
+    fn io_errors_have_no_hint() {
+        let err = Error::from(std::io::Error::other("boom"));
+        assert!(err.hint().is_none());
+        assert_eq!(err.category(), ErrorCategory::Io);
+    }
+}
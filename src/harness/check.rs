@@ -0,0 +1,197 @@
+//! Post-switch checks: the harness loads the config it was just switched to.
+//!
+//! Each built-in harness has a command that reads its config and fails when
+//! it cannot, see [`schema::check_command`](super::schema::check_command);
+//! `check = [...]` in a `[harness.<id>]` table replaces it. The command runs
+//! in the config directory, so no project config is picked up, with stdin
+//! closed and a [`TIMEOUT`], so a harness that waits for input or hangs fails
+//! the check instead of stalling the switch.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::HarnessConfig;
+
+/// Longest a check may run before it is killed.
+pub const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Most output kept per stream; longer output keeps its end.
+const OUTPUT_LIMIT: usize = 4 * 1024;
+
+/// How a check ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum CheckOutcome {
+    Passed,
+    /// The command exited with a failure status.
+    Failed {
+        status: Option<i32>,
+        output: String,
+    },
+    /// The command ran longer than [`TIMEOUT`] and was killed.
+    TimedOut {
+        output: String,
+    },
+    /// The command could not be started, e.g. the harness is not installed.
+    Unavailable {
+        reason: String,
+    },
+}
+
+/// One check of a harness's config.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigCheck {
+    /// The command, as typed in a shell.
+    pub command: String,
+    #[serde(flatten)]
+    pub outcome: CheckOutcome,
+}
+
+impl ConfigCheck {
+    /// Whether the harness could not load its config. A check that could
+    /// not run at all says nothing about the config.
+    pub fn failed(&self) -> bool {
+        matches!(
+            self.outcome,
+            CheckOutcome::Failed { .. } | CheckOutcome::TimedOut { .. }
+        )
+    }
+}
+
+/// Runs `harness`'s check, or returns `None` when it has none.
+pub fn run(harness: &dyn HarnessConfig) -> Option<ConfigCheck> {
+    let dir = harness.config_dir().ok().filter(|d| d.is_dir());
+    run_command(&harness.check_command(), dir.as_deref(), TIMEOUT)
+}
+
+fn run_command(argv: &[String], dir: Option<&Path>, timeout: Duration) -> Option<ConfigCheck> {
+    let (program, args) = argv.split_first()?;
+    let check = |outcome| {
+        Some(ConfigCheck {
+            command: argv.join(" "),
+            outcome,
+        })
+    };
+    let mut command = Command::new(program);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let mut child = match command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            return check(CheckOutcome::Unavailable {
+                reason: format!("could not run {program}: {e}"),
+            });
+        }
+    };
+
+    // Drain both pipes while waiting, so a chatty harness cannot block on a full pipe.
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .map(|stream| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut stream) = stream {
+                let _ = stream.read_to_end(&mut buf);
+            }
+            buf
+        })
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status: Option<ExitStatus> = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(_) => break None,
+        }
+    };
+
+    let mut output = String::new();
+    for reader in readers {
+        let buf = reader.join().unwrap_or_default();
+        let start = buf.len().saturating_sub(OUTPUT_LIMIT);
+        output.push_str(&String::from_utf8_lossy(&buf[start..]));
+    }
+    let output = output.trim_end().to_string();
+    check(match status {
+        Some(status) if status.success() => CheckOutcome::Passed,
+        Some(status) => CheckOutcome::Failed {
+            status: status.code(),
+            output,
+        },
+        None => CheckOutcome::TimedOut { output },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_how_the_check_ended() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("settings.json"), "{}").unwrap();
+        assert_eq!(run_command(&[], None, TIMEOUT).map(|c| c.outcome), None);
+
+        let passed = run_command(
+            &argv(&["sh", "-c", "test -f settings.json"]),
+            Some(temp.path()),
+            TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(passed.outcome, CheckOutcome::Passed);
+        assert!(!passed.failed());
+
+        let failed = run_command(
+            &argv(&["sh", "-c", "echo 'bad key: model' >&2; exit 3"]),
+            None,
+            TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(failed.command, "sh -c echo 'bad key: model' >&2; exit 3");
+        assert_eq!(
+            failed.outcome,
+            CheckOutcome::Failed {
+                status: Some(3),
+                output: "bad key: model".to_string()
+            }
+        );
+        assert!(failed.failed());
+
+        let hung = run_command(&argv(&["sleep", "5"]), None, Duration::from_millis(100)).unwrap();
+        assert!(matches!(hung.outcome, CheckOutcome::TimedOut { .. }));
+
+        let missing = run_command(&argv(&["bridle-no-such-harness"]), None, TIMEOUT).unwrap();
+        assert!(matches!(missing.outcome, CheckOutcome::Unavailable { .. }));
+        assert!(!missing.failed());
+    }
+}
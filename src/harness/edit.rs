@@ -0,0 +1,1155 @@
+//! Key-path edits to harness config files that keep their formatting.
+//!
+//! JSON and JSONC files are edited in place: only the text of the changed
+//! value or member is rewritten, so comments, key order, and indentation
+//! survive. TOML files go through `toml_edit`, which does the same. YAML
+//! block mappings are edited line by line; other YAML layouts are
+//! re-serialized, which drops comments.
+//!
+//! Key paths are dot-separated (`mcp.github.enabled`); in JSON and YAML a
+//! numeric segment indexes an array.
+
+use serde_json::Value;
+
+use super::schema::ConfigFormat;
+use crate::config::jsonc::strip_jsonc_comments;
+use crate::error::{Error, Result};
+
+type EditResult<T> = std::result::Result<T, String>;
+
+/// Splits a dot-separated key path.
+///
+/// # Errors
+/// Returns [`Error::InvalidValue`] for an empty path or empty segment.
+pub fn parse_key_path(key: &str) -> Result<Vec<String>> {
+    let path: Vec<String> = key.split('.').map(str::to_string).collect();
+    if path.iter().any(String::is_empty) {
+        return Err(Error::InvalidValue {
+            key: "key path".to_string(),
+            value: key.to_string(),
+        });
+    }
+    Ok(path)
+}
+
+/// Reads a command-line value as JSON (`true`, `42`, `["a"]`, `"x"`), or as
+/// a plain string when it is not valid JSON.
+pub fn parse_value(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// The value at `path`, or `None` if it is not set.
+pub fn get(content: &str, format: ConfigFormat, path: &[String]) -> EditResult<Option<Value>> {
+    let root: Value = match format {
+        ConfigFormat::Json | ConfigFormat::Jsonc => {
+            if content.trim().is_empty() {
+                return Ok(None);
+            }
+            serde_json::from_str(&strip_jsonc_comments(content)).map_err(|e| e.to_string())?
+        }
+        ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string())?,
+        ConfigFormat::Toml => {
+            let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+            serde_json::to_value(table).map_err(|e| e.to_string())?
+        }
+    };
+    let mut node = &root;
+    for segment in path {
+        let next = match node {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        match next {
+            Some(next) => node = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(node.clone()))
+}
+
+/// `content` with `path` set to `value`, creating missing parents.
+pub fn set(
+    content: &str,
+    format: ConfigFormat,
+    path: &[String],
+    value: &Value,
+) -> EditResult<String> {
+    match format {
+        ConfigFormat::Json | ConfigFormat::Jsonc => json_set(content, path, value),
+        ConfigFormat::Toml => toml_set(content, path, value),
+        ConfigFormat::Yaml => yaml_set(content, path, value),
+    }
+}
+
+/// `content` without `path`, or `None` if it was not set.
+pub fn unset(content: &str, format: ConfigFormat, path: &[String]) -> EditResult<Option<String>> {
+    match format {
+        ConfigFormat::Json | ConfigFormat::Jsonc => json_unset(content, path),
+        ConfigFormat::Toml => toml_unset(content, path),
+        ConfigFormat::Yaml => yaml_unset(content, path),
+    }
+}
+
+/// `content` changed to hold `new`, editing only the values that differ, so
+/// whatever did not change keeps its comments and layout. A `null` in `new`
+/// removes the key.
+pub fn update(content: &str, format: ConfigFormat, new: &Value) -> EditResult<String> {
+    let old = get(content, format, &[])?.unwrap_or(Value::Null);
+    let mut content = content.to_string();
+    update_at(&mut content, format, &mut Vec::new(), &old, new)?;
+    Ok(content)
+}
+
+fn update_at(
+    content: &mut String,
+    format: ConfigFormat,
+    path: &mut Vec<String>,
+    old: &Value,
+    new: &Value,
+) -> EditResult<()> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for key in old.keys().filter(|k| !new.contains_key(*k)) {
+                path.push(key.clone());
+                if let Some(edited) = unset(content, format, path)? {
+                    *content = edited;
+                }
+                path.pop();
+            }
+            for (key, value) in new {
+                path.push(key.clone());
+                match old.get(key) {
+                    Some(previous) => update_at(content, format, path, previous, value)?,
+                    None if value.is_null() => {}
+                    None => *content = set(content, format, path, value)?,
+                }
+                path.pop();
+            }
+            Ok(())
+        }
+        _ if old == new => Ok(()),
+        _ if path.is_empty() => Err("the file is not a mapping".to_string()),
+        (_, Value::Null) => {
+            if let Some(edited) = unset(content, format, path)? {
+                *content = edited;
+            }
+            Ok(())
+        }
+        _ => {
+            *content = set(content, format, path, new)?;
+            Ok(())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JSON / JSONC: edits spliced into the original text.
+
+/// A parsed value and the byte range of its text.
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Object(Vec<Entry>),
+    Array(Vec<Entry>),
+    Scalar,
+}
+
+/// An object member or array item. `start` is where its key (or value) begins.
+#[derive(Debug)]
+struct Entry {
+    key: Option<String>,
+    start: usize,
+    value: Node,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(text: &'a str) -> EditResult<Node> {
+        let mut parser = Self { text, pos: 0 };
+        parser.value()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.pos += trimmed.find("*/").map_or(trimmed.len(), |i| i + 2);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        format!("{message} at line {line}")
+    }
+
+    fn value(&mut self) -> EditResult<Node> {
+        self.skip_trivia();
+        let start = self.pos;
+        let kind = match self.peek() {
+            Some(b'{') => NodeKind::Object(self.entries(b'}', true)?),
+            Some(b'[') => NodeKind::Array(self.entries(b']', false)?),
+            Some(b'"') => {
+                self.string()?;
+                NodeKind::Scalar
+            }
+            Some(_) => {
+                let rest = &self.text[self.pos..];
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || ",]}/".contains(c))
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(self.error("expected a value"));
+                }
+                self.pos += len;
+                NodeKind::Scalar
+            }
+            None => return Err(self.error("unexpected end of file")),
+        };
+        Ok(Node {
+            start,
+            end: self.pos,
+            kind,
+        })
+    }
+
+    fn string(&mut self) -> EditResult<String> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut i = start + 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'"' => {
+                    self.pos = i + 1;
+                    return serde_json::from_str(&self.text[start..self.pos])
+                        .map_err(|e| self.error(&e.to_string()));
+                }
+                _ => i += 1,
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn entries(&mut self, close: u8, keyed: bool) -> EditResult<Vec<Entry>> {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_trivia();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(entries);
+            }
+            let start = self.pos;
+            let key = if keyed {
+                if self.peek() != Some(b'"') {
+                    return Err(self.error("expected a key"));
+                }
+                let key = self.string()?;
+                self.skip_trivia();
+                if self.peek() != Some(b':') {
+                    return Err(self.error("expected `:`"));
+                }
+                self.pos += 1;
+                Some(key)
+            } else {
+                None
+            };
+            let value = self.value()?;
+            entries.push(Entry { key, start, value });
+            self.skip_trivia();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {}
+                _ => return Err(self.error("expected `,`")),
+            }
+        }
+    }
+}
+
+/// What follows a value on its line: an optional comma, then spaces and an
+/// optional `//` comment, up to `end` (the newline, or the next token).
+struct Tail {
+    comma: Option<usize>,
+    end: usize,
+}
+
+impl Tail {
+    fn after(text: &str, pos: usize) -> Self {
+        let skip_blanks = |pos: usize| {
+            pos + text[pos..]
+                .find(|c: char| c != ' ' && c != '\t')
+                .unwrap_or(text.len() - pos)
+        };
+        let mut end = skip_blanks(pos);
+        let comma = text[end..].starts_with(',').then_some(end);
+        if comma.is_some() {
+            end = skip_blanks(end + 1);
+        }
+        if text[end..].starts_with("//") {
+            end += text[end..].find('\n').unwrap_or(text.len() - end);
+        }
+        Self { comma, end }
+    }
+}
+
+/// Whitespace that starts the line containing `pos`.
+fn line_indent(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// `value` as pretty JSON, with continuation lines indented by `indent`.
+fn render(value: &Value, indent: &str) -> String {
+    serde_json::to_string_pretty(value)
+        .unwrap_or_default()
+        .replace('\n', &format!("\n{indent}"))
+}
+
+fn splice(text: &str, range: std::ops::Range<usize>, replacement: &str) -> String {
+    format!(
+        "{}{replacement}{}",
+        &text[..range.start],
+        &text[range.end..]
+    )
+}
+
+/// `value` wrapped in one object per remaining path segment.
+fn nest(rest: &[String], value: &Value) -> Value {
+    rest.iter().rev().fold(value.clone(), |inner, key| {
+        Value::Object([(key.clone(), inner)].into_iter().collect())
+    })
+}
+
+fn json_set(content: &str, path: &[String], value: &Value) -> EditResult<String> {
+    let content = if content.trim().is_empty() {
+        "{}\n"
+    } else {
+        content
+    };
+    let root = Parser::parse(content)?;
+    let mut node = &root;
+    for (i, segment) in path.iter().enumerate() {
+        let found = match &node.kind {
+            NodeKind::Object(entries) => {
+                match entries.iter().find(|e| e.key.as_deref() == Some(segment)) {
+                    Some(entry) => entry,
+                    None => {
+                        let member = nest(&path[i + 1..], value);
+                        return Ok(insert_entry(content, node, entries, Some(segment), &member));
+                    }
+                }
+            }
+            NodeKind::Array(entries) => {
+                let index: usize = segment
+                    .parse()
+                    .map_err(|_| format!("`{}` is an array", path[..i].join(".")))?;
+                match entries.get(index) {
+                    Some(entry) => entry,
+                    None if index == entries.len() => {
+                        let item = nest(&path[i + 1..], value);
+                        return Ok(insert_entry(content, node, entries, None, &item));
+                    }
+                    None => return Err(format!("index {index} is out of range")),
+                }
+            }
+            NodeKind::Scalar => {
+                return Err(format!("`{}` is not an object", path[..i].join(".")));
+            }
+        };
+        node = &found.value;
+    }
+    let indent = line_indent(content, node.start);
+    Ok(splice(
+        content,
+        node.start..node.end,
+        &render(value, indent),
+    ))
+}
+
+/// Adds a member (or item, when `key` is `None`) at the end of `container`.
+fn insert_entry(
+    text: &str,
+    container: &Node,
+    entries: &[Entry],
+    key: Option<&str>,
+    value: &Value,
+) -> String {
+    let entry = |indent: &str| match key {
+        Some(key) => format!("{}: {}", Value::from(key), render(value, indent)),
+        None => render(value, indent),
+    };
+    if let Some(last) = entries.last() {
+        let indent = line_indent(text, last.start);
+        if !text[container.start..last.start].contains('\n') {
+            let insertion = format!(", {}", entry(indent));
+            return splice(text, last.value.end..last.value.end, &insertion);
+        }
+        // A new line after the last member, past its comma and any trailing comment.
+        let tail = Tail::after(text, last.value.end);
+        match tail.comma {
+            Some(_) => {
+                let insertion = format!("\n{indent}{},", entry(indent));
+                splice(text, tail.end..tail.end, &insertion)
+            }
+            None => {
+                let insertion = format!("\n{indent}{}", entry(indent));
+                let text = splice(text, tail.end..tail.end, &insertion);
+                splice(&text, last.value.end..last.value.end, ",")
+            }
+        }
+    } else {
+        let indent = line_indent(text, container.start);
+        let inner = format!("{indent}  ");
+        let (open, close) = match container.kind {
+            NodeKind::Array(_) => ('[', ']'),
+            _ => ('{', '}'),
+        };
+        let replacement = format!("{open}\n{inner}{}\n{indent}{close}", entry(&inner));
+        splice(text, container.start..container.end, &replacement)
+    }
+}
+
+fn json_unset(content: &str, path: &[String]) -> EditResult<Option<String>> {
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    let root = Parser::parse(content)?;
+    let Some((last, parents)) = path.split_last() else {
+        return Ok(None);
+    };
+    let mut node = &root;
+    for segment in parents {
+        let next = match &node.kind {
+            NodeKind::Object(entries) => entries.iter().find(|e| e.key.as_deref() == Some(segment)),
+            NodeKind::Array(entries) => segment.parse::<usize>().ok().and_then(|i| entries.get(i)),
+            NodeKind::Scalar => None,
+        };
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        node = &next.value;
+    }
+    let (entries, index) = match &node.kind {
+        NodeKind::Object(entries) => (
+            entries,
+            entries.iter().position(|e| e.key.as_deref() == Some(last)),
+        ),
+        NodeKind::Array(entries) => (
+            entries,
+            last.parse::<usize>().ok().filter(|&i| i < entries.len()),
+        ),
+        NodeKind::Scalar => return Ok(None),
+    };
+    let Some(index) = index else {
+        return Ok(None);
+    };
+
+    if entries.len() == 1 {
+        let empty = match node.kind {
+            NodeKind::Array(_) => "[]",
+            _ => "{}",
+        };
+        return Ok(Some(splice(content, node.start..node.end, empty)));
+    }
+    let entry = &entries[index];
+    let tail = Tail::after(content, entry.value.end);
+    let line_start = content[..entry.start].rfind('\n').map_or(0, |i| i + 1);
+    let own_line =
+        content[line_start..entry.start].trim().is_empty() && content[tail.end..].starts_with('\n');
+    let range = if own_line {
+        // Whole lines, plus the previous member's comma if this was the last
+        // member and had none of its own.
+        let text = if index + 1 == entries.len() && tail.comma.is_none() {
+            let prev = Tail::after(content, entries[index - 1].value.end);
+            prev.comma
+                .map(|comma| splice(content, comma..comma + 1, ""))
+                .map(|text| (text, 1))
+        } else {
+            None
+        };
+        if let Some((text, shift)) = text {
+            return Ok(Some(splice(
+                &text,
+                line_start - shift..tail.end + 1 - shift,
+                "",
+            )));
+        }
+        line_start..tail.end + 1
+    } else if index + 1 < entries.len() {
+        entry.start..entries[index + 1].start
+    } else {
+        entries[index - 1].value.end..entry.value.end
+    };
+    Ok(Some(splice(content, range, "")))
+}
+
+// ---------------------------------------------------------------------------
+// TOML: edits through toml_edit, which keeps comments and layout.
+
+fn to_toml(value: &Value) -> EditResult<toml_edit::Value> {
+    Ok(match value {
+        Value::Null => return Err("TOML has no null value; use `unset` instead".to_string()),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(to_toml(item)?);
+            }
+            array.into()
+        }
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                table.insert(key, to_toml(item)?);
+            }
+            table.into()
+        }
+    })
+}
+
+fn toml_set(content: &str, path: &[String], value: &Value) -> EditResult<String> {
+    let mut doc: toml_edit::DocumentMut = content.parse().map_err(|e| format!("{e}"))?;
+    let Some((last, parents)) = path.split_last() else {
+        return Err("empty key path".to_string());
+    };
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for (i, segment) in parents.iter().enumerate() {
+        table = table
+            .entry(segment)
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .ok_or_else(|| format!("`{}` is not a table", path[..=i].join(".")))?;
+    }
+    let new = to_toml(value)?;
+    match table.get_mut(last) {
+        Some(toml_edit::Item::Value(existing)) => {
+            // Keep the comment and spacing around the old value.
+            let decor = existing.decor().clone();
+            *existing = new;
+            *existing.decor_mut() = decor;
+        }
+        _ => {
+            table.insert(last, toml_edit::Item::Value(new));
+        }
+    }
+    Ok(doc.to_string())
+}
+
+fn toml_unset(content: &str, path: &[String]) -> EditResult<Option<String>> {
+    let mut doc: toml_edit::DocumentMut = content.parse().map_err(|e| format!("{e}"))?;
+    let Some((last, parents)) = path.split_last() else {
+        return Ok(None);
+    };
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for segment in parents {
+        let Some(next) = table
+            .get_mut(segment)
+            .and_then(toml_edit::Item::as_table_like_mut)
+        else {
+            return Ok(None);
+        };
+        table = next;
+    }
+    Ok(table.remove(last).map(|_| doc.to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// YAML: block mappings are edited line by line, so comments, key order, and
+// indentation survive. Layouts the line editor does not follow, such as flow
+// mappings, lists on the key path, or several documents, are re-serialized,
+// which drops comments. Either way the result must parse to the same tree as
+// the edit applied to the parsed file.
+
+fn yaml_key(segment: &str) -> serde_yaml::Value {
+    serde_yaml::Value::String(segment.to_string())
+}
+
+fn yaml_set(content: &str, path: &[String], value: &Value) -> EditResult<String> {
+    let mut root: serde_yaml::Value = if content.trim().is_empty() {
+        serde_yaml::Value::Mapping(Default::default())
+    } else {
+        serde_yaml::from_str(content).map_err(|e| e.to_string())?
+    };
+    let new = serde_yaml::to_value(value).map_err(|e| e.to_string())?;
+    let mut node = &mut root;
+    for (i, segment) in path.iter().enumerate() {
+        node = match node {
+            serde_yaml::Value::Mapping(map) => map
+                .entry(yaml_key(segment))
+                .or_insert(serde_yaml::Value::Null),
+            serde_yaml::Value::Sequence(items) => {
+                let index: usize = segment
+                    .parse()
+                    .map_err(|_| format!("`{}` is a list", path[..i].join(".")))?;
+                if index == items.len() {
+                    items.push(serde_yaml::Value::Null);
+                }
+                items
+                    .get_mut(index)
+                    .ok_or_else(|| format!("index {index} is out of range"))?
+            }
+            serde_yaml::Value::Null => {
+                *node = serde_yaml::Value::Mapping(Default::default());
+                let serde_yaml::Value::Mapping(map) = node else {
+                    unreachable!()
+                };
+                map.entry(yaml_key(segment))
+                    .or_insert(serde_yaml::Value::Null)
+            }
+            _ => return Err(format!("`{}` is not a mapping", path[..i].join("."))),
+        };
+    }
+    *node = new;
+    yaml_keep_layout(yaml_block_set(content, path, value), &root)
+}
+
+fn yaml_unset(content: &str, path: &[String]) -> EditResult<Option<String>> {
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    let mut root: serde_yaml::Value = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+    let Some((last, parents)) = path.split_last() else {
+        return Ok(None);
+    };
+    let mut node = &mut root;
+    for segment in parents {
+        let next = match node {
+            serde_yaml::Value::Mapping(map) => map.get_mut(yaml_key(segment)),
+            serde_yaml::Value::Sequence(items) => {
+                segment.parse::<usize>().ok().and_then(|i| items.get_mut(i))
+            }
+            _ => None,
+        };
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        node = next;
+    }
+    let removed = match node {
+        serde_yaml::Value::Mapping(map) => map.remove(yaml_key(last)).is_some(),
+        serde_yaml::Value::Sequence(items) => match last.parse::<usize>() {
+            Ok(i) if i < items.len() => {
+                items.remove(i);
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    };
+    if !removed {
+        return Ok(None);
+    }
+    yaml_keep_layout(yaml_block_unset(content, path), &root).map(Some)
+}
+
+/// `edited` when it holds `expected`, otherwise `expected` re-serialized.
+fn yaml_keep_layout(edited: Option<String>, expected: &serde_yaml::Value) -> EditResult<String> {
+    match edited {
+        Some(text)
+            if serde_yaml::from_str::<serde_yaml::Value>(&text)
+                .ok()
+                .as_ref()
+                == Some(expected) =>
+        {
+            Ok(text)
+        }
+        _ => serde_yaml::to_string(expected).map_err(|e| e.to_string()),
+    }
+}
+
+/// A `key: value` entry of a block mapping.
+struct YamlEntry {
+    /// Line of the key.
+    line: usize,
+    /// One past the last line of its value; comments after it are not counted.
+    end: usize,
+    /// Byte offset just past the key's `:` in its line.
+    colon: usize,
+}
+
+fn yaml_indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn yaml_is_trivia(line: &str) -> bool {
+    let text = line.trim();
+    text.is_empty() || text.starts_with('#')
+}
+
+/// The key of a block mapping line, with the byte offset just past its `:`.
+fn yaml_line_key(line: &str) -> Option<(String, usize)> {
+    let indent = yaml_indent(line);
+    let text = line[indent..].trim_end_matches(['\n', '\r']);
+    let bytes = text.as_bytes();
+    let (key, colon) = match *bytes.first()? {
+        quote @ (b'"' | b'\'') => {
+            let mut i = 1;
+            loop {
+                let byte = *bytes.get(i)?;
+                let escaped = if quote == b'"' {
+                    byte == b'\\'
+                } else {
+                    byte == b'\'' && bytes.get(i + 1) == Some(&b'\'')
+                };
+                if escaped {
+                    i += 2;
+                } else if byte == quote {
+                    break;
+                } else {
+                    i += 1;
+                }
+            }
+            if bytes.get(i + 1) != Some(&b':') {
+                return None;
+            }
+            (serde_yaml::from_str(&text[..=i]).ok()?, i + 1)
+        }
+        b'-' | b'?' | b'[' | b'{' | b'&' | b'*' | b'!' | b'|' | b'>' | b'%' | b'@' | b'`' => {
+            return None;
+        }
+        _ => {
+            let (colon, _) = text.char_indices().find(|&(i, c)| {
+                c == ':' && text[i + 1..].chars().next().is_none_or(char::is_whitespace)
+            })?;
+            (text[..colon].trim_end().to_string(), colon)
+        }
+    };
+    if !text[colon + 1..]
+        .chars()
+        .next()
+        .is_none_or(char::is_whitespace)
+    {
+        return None;
+    }
+    Some((key, indent + colon + 1))
+}
+
+/// Splits what follows a key's `:` into its inline value and the comment
+/// after it, with the spaces before the comment.
+fn yaml_split_comment(rest: &str) -> (&str, &str) {
+    let rest = rest.trim_end_matches(['\n', '\r']);
+    let mut quote = None;
+    let mut after_space = true;
+    for (i, c) in rest.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if after_space && (c == '"' || c == '\'') => quote = Some(c),
+            None if after_space && c == '#' => {
+                let end = rest[..i].trim_end().len();
+                return (rest[..end].trim_start(), &rest[end..]);
+            }
+            None => {}
+        }
+        after_space = c.is_whitespace();
+    }
+    (rest.trim(), "")
+}
+
+/// Indentation of the first entry in `lines[range]`.
+fn yaml_block_indent(lines: &[&str], range: std::ops::Range<usize>) -> Option<usize> {
+    lines[range]
+        .iter()
+        .find(|line| !yaml_is_trivia(line))
+        .map(|line| yaml_indent(line))
+}
+
+/// One past the last line of the value of the key on `line`.
+fn yaml_value_end(lines: &[&str], line: usize, limit: usize, indent: usize) -> usize {
+    let mut end = line + 1;
+    for (i, text) in lines.iter().enumerate().take(limit).skip(line + 1) {
+        if yaml_is_trivia(text) {
+            continue;
+        }
+        let nested = yaml_indent(text) > indent;
+        // A list may sit at the same indentation as its key.
+        let item = yaml_indent(text) == indent && text.trim_start().starts_with("- ");
+        if !nested && !item {
+            break;
+        }
+        end = i + 1;
+    }
+    end
+}
+
+/// Finds `key` among the entries indented by `indent` in `lines[range]`;
+/// `None` when they are not a block mapping.
+fn yaml_find(
+    lines: &[&str],
+    range: std::ops::Range<usize>,
+    indent: usize,
+    key: &str,
+) -> Option<Option<YamlEntry>> {
+    let mut i = range.start;
+    while i < range.end {
+        if yaml_is_trivia(lines[i]) {
+            i += 1;
+            continue;
+        }
+        if yaml_indent(lines[i]) != indent {
+            return None;
+        }
+        let (name, colon) = yaml_line_key(lines[i])?;
+        let end = yaml_value_end(lines, i, range.end, indent);
+        if name == key {
+            return Some(Some(YamlEntry {
+                line: i,
+                end,
+                colon,
+            }));
+        }
+        i = end;
+    }
+    Some(None)
+}
+
+/// Whether the line editor can follow `lines` at all.
+fn yaml_single_document(lines: &[&str]) -> bool {
+    !lines
+        .iter()
+        .any(|line| line.starts_with("---") || line.starts_with("...") || line.starts_with('%'))
+}
+
+/// `value` as written after `key:` in an entry indented by `indent`,
+/// ending with a line break.
+fn yaml_render(value: &Value, indent: usize) -> Option<String> {
+    let text = serde_yaml::to_string(value).ok()?;
+    let text = text.trim_end_matches('\n');
+    let nested = match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    };
+    let pad = |width: usize, line: &str| {
+        if line.is_empty() {
+            "\n".to_string()
+        } else {
+            format!("\n{}{line}", " ".repeat(width))
+        }
+    };
+    if nested {
+        return Some(
+            text.lines()
+                .map(|line| pad(indent + 2, line))
+                .collect::<String>()
+                + "\n",
+        );
+    }
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default();
+    let rest: String = lines.map(|line| pad(indent, line)).collect();
+    Some(format!(" {first}{rest}\n"))
+}
+
+/// `lines` with `lines[range]` replaced by `text`.
+fn yaml_splice(lines: &[&str], range: std::ops::Range<usize>, text: &str) -> String {
+    let mut out = lines[..range.start].concat();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(text);
+    out.push_str(&lines[range.end..].concat());
+    out
+}
+
+/// Sets `path` in place; `None` when the file's layout is not followed.
+fn yaml_block_set(content: &str, path: &[String], value: &Value) -> Option<String> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if !yaml_single_document(&lines) {
+        return None;
+    }
+    let mut range = 0..lines.len();
+    let mut indent = yaml_block_indent(&lines, range.clone()).unwrap_or(0);
+    for (depth, segment) in path.iter().enumerate() {
+        let rest = &path[depth + 1..];
+        let Some(entry) = yaml_find(&lines, range.clone(), indent, segment)? else {
+            let at = range
+                .clone()
+                .rev()
+                .find(|&i| !yaml_is_trivia(lines[i]))
+                .map_or(range.end, |i| i + 1);
+            let key = serde_yaml::to_string(&yaml_key(segment)).ok()?;
+            let text = format!(
+                "{}{}:{}",
+                " ".repeat(indent),
+                key.trim_end(),
+                yaml_render(&nest(rest, value), indent)?
+            );
+            return Some(yaml_splice(&lines, at..at, &text));
+        };
+        let line = lines[entry.line];
+        let (inline, comment) = yaml_split_comment(&line[entry.colon..]);
+        let empty = matches!(inline, "" | "{}" | "~" | "null") && entry.end == entry.line + 1;
+        if rest.is_empty() || empty {
+            let rendered = yaml_render(&nest(rest, value), indent)?;
+            let prefix = &line[..entry.colon];
+            let text = if rendered.starts_with('\n') {
+                format!("{prefix}{comment}{rendered}")
+            } else if rendered.lines().count() == 1 {
+                format!("{prefix}{}{comment}\n", rendered.trim_end())
+            } else {
+                format!("{prefix}{rendered}")
+            };
+            return Some(yaml_splice(&lines, entry.line..entry.end, &text));
+        }
+        if !inline.is_empty() {
+            return None;
+        }
+        range = entry.line + 1..entry.end;
+        indent = yaml_block_indent(&lines, range.clone())?;
+        if indent <= yaml_indent(line) {
+            return None;
+        }
+    }
+    None
+}
+
+/// Removes `path` in place; `None` when the file's layout is not followed.
+fn yaml_block_unset(content: &str, path: &[String]) -> Option<String> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if !yaml_single_document(&lines) {
+        return None;
+    }
+    let mut range = 0..lines.len();
+    let mut indent = yaml_block_indent(&lines, range.clone())?;
+    let mut parent: Option<YamlEntry> = None;
+    let (last, parents) = path.split_last()?;
+    for segment in parents {
+        let entry = yaml_find(&lines, range.clone(), indent, segment)??;
+        let line = lines[entry.line];
+        if !yaml_split_comment(&line[entry.colon..]).0.is_empty() {
+            return None;
+        }
+        range = entry.line + 1..entry.end;
+        indent = yaml_block_indent(&lines, range.clone())?;
+        if indent <= yaml_indent(line) {
+            return None;
+        }
+        parent = Some(entry);
+    }
+    let entry = yaml_find(&lines, range.clone(), indent, last)??;
+    let others = range
+        .filter(|i| !(entry.line..entry.end).contains(i))
+        .any(|i| !yaml_is_trivia(lines[i]));
+    match parent {
+        // Leave an empty mapping rather than a key without a value.
+        Some(parent) if !others => {
+            let prefix = &lines[parent.line][..parent.colon];
+            Some(yaml_splice(
+                &lines,
+                parent.line..parent.end,
+                &format!("{prefix} {{}}\n"),
+            ))
+        }
+        _ => Some(yaml_splice(&lines, entry.line..entry.end, "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(key: &str) -> Vec<String> {
+        parse_key_path(key).unwrap()
+    }
+
+    const JSONC: &str = r#"{
+  // Default model
+  "model": "old",
+  "mcp": {
+    "github": { "enabled": true }, // keep me
+  },
+}
+"#;
+
+    #[test]
+    fn jsonc_edits_keep_comments() {
+        let out = set(JSONC, ConfigFormat::Jsonc, &path("model"), &json!("new")).unwrap();
+        assert!(out.contains("// Default model\n  \"model\": \"new\","));
+        assert!(out.contains("// keep me"));
+
+        let out = set(
+            &out,
+            ConfigFormat::Jsonc,
+            &path("mcp.docs.enabled"),
+            &json!(false),
+        )
+        .unwrap();
+        assert!(out.contains("// keep me"));
+        assert_eq!(
+            get(&out, ConfigFormat::Jsonc, &path("mcp.docs.enabled")).unwrap(),
+            Some(json!(false))
+        );
+
+        let out = unset(&out, ConfigFormat::Jsonc, &path("model"))
+            .unwrap()
+            .unwrap();
+        assert!(!out.contains("\"model\""));
+        assert_eq!(
+            get(&out, ConfigFormat::Jsonc, &path("mcp.github.enabled")).unwrap(),
+            Some(json!(true))
+        );
+        assert_eq!(
+            unset(&out, ConfigFormat::Jsonc, &path("nope")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn json_inserts_into_empty_objects_and_arrays() {
+        let out = set("{}", ConfigFormat::Json, &path("a.b"), &json!(1)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            json!({"a": {"b": 1}})
+        );
+
+        let out = set(
+            r#"{"list": [1]}"#,
+            ConfigFormat::Json,
+            &path("list.1"),
+            &json!(2),
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            json!({"list": [1, 2]})
+        );
+        let out = unset(&out, ConfigFormat::Json, &path("list.0"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            json!({"list": [2]})
+        );
+        assert!(set(r#"{"a": 1}"#, ConfigFormat::Json, &path("a.b"), &json!(1)).is_err());
+    }
+
+    #[test]
+    fn toml_edits_keep_comments() {
+        let content = "# top\nmodel = \"old\" # inline\n\n[tools]\nshell = true\n";
+        let out = set(content, ConfigFormat::Toml, &path("model"), &json!("new")).unwrap();
+        assert_eq!(
+            out,
+            "# top\nmodel = \"new\" # inline\n\n[tools]\nshell = true\n"
+        );
+
+        let out = set(&out, ConfigFormat::Toml, &path("tools.web"), &json!(false)).unwrap();
+        assert!(out.contains("[tools]\nshell = true\nweb = false\n"));
+        let out = unset(&out, ConfigFormat::Toml, &path("tools.shell"))
+            .unwrap()
+            .unwrap();
+        assert!(!out.contains("shell"));
+    }
+
+    #[test]
+    fn yaml_set_and_unset() {
+        let content = "GOOSE_MODEL: old\nextensions:\n  dev:\n    enabled: true\n";
+        let out = set(
+            content,
+            ConfigFormat::Yaml,
+            &path("extensions.dev.enabled"),
+            &json!(false),
+        )
+        .unwrap();
+        assert_eq!(
+            get(&out, ConfigFormat::Yaml, &path("extensions.dev.enabled")).unwrap(),
+            Some(json!(false))
+        );
+        let out = unset(&out, ConfigFormat::Yaml, &path("GOOSE_MODEL"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            get(&out, ConfigFormat::Yaml, &path("GOOSE_MODEL")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn yaml_edits_keep_comments() {
+        let content = "# Goose\nGOOSE_MODEL: old  # default\n\nextensions:\n  # built in\n  developer:\n    enabled: true\n";
+        let out = set(
+            content,
+            ConfigFormat::Yaml,
+            &path("GOOSE_MODEL"),
+            &json!("new"),
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "# Goose\nGOOSE_MODEL: new  # default\n\nextensions:\n  # built in\n  developer:\n    enabled: true\n"
+        );
+
+        let out = set(
+            &out,
+            ConfigFormat::Yaml,
+            &path("extensions.github"),
+            &json!({"type": "stdio", "args": ["-y", "server"]}),
+        )
+        .unwrap();
+        assert!(out.starts_with("# Goose\nGOOSE_MODEL: new  # default\n"));
+        assert!(out.contains("  # built in\n  developer:\n    enabled: true\n  github:\n"));
+        assert_eq!(
+            get(&out, ConfigFormat::Yaml, &path("extensions.github.args")).unwrap(),
+            Some(json!(["-y", "server"]))
+        );
+
+        let out = unset(&out, ConfigFormat::Yaml, &path("extensions.developer"))
+            .unwrap()
+            .unwrap();
+        assert!(out.contains("# Goose\n") && !out.contains("developer"));
+        let out = unset(&out, ConfigFormat::Yaml, &path("extensions.github"))
+            .unwrap()
+            .unwrap();
+        assert!(out.contains("# Goose\n") && out.contains("extensions: {}\n"));
+
+        // Flow mappings are re-serialized.
+        let out = set(
+            "a: {b: 1} # c\n",
+            ConfigFormat::Yaml,
+            &path("a.d"),
+            &json!(2),
+        )
+        .unwrap();
+        assert_eq!(
+            get(&out, ConfigFormat::Yaml, &path("a")).unwrap(),
+            Some(json!({"b": 1, "d": 2}))
+        );
+    }
+
+    #[test]
+    fn values_parse_as_json_or_string() {
+        assert_eq!(parse_value("claude-opus-4"), json!("claude-opus-4"));
+        assert_eq!(parse_value("true"), json!(true));
+        assert_eq!(parse_value("[1, 2]"), json!([1, 2]));
+        assert!(parse_key_path("a..b").is_err());
+    }
+}
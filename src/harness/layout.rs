@@ -0,0 +1,369 @@
+//! Where a harness keeps its resource directories (skills, commands, agents, plugins).
+
+use std::path::{Component, Path, PathBuf};
+
+pub use harness_locate::DirectoryStructure;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A kind of resource directory a harness can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Skills,
+    Commands,
+    Agents,
+    Plugins,
+}
+
+impl ResourceKind {
+    /// Every kind, in the order resources are copied.
+    pub const ALL: [Self; 4] = [Self::Commands, Self::Agents, Self::Skills, Self::Plugins];
+
+    /// Directory name used for this kind inside a profile, whatever the
+    /// harness calls it, so profiles stay portable across harnesses.
+    pub fn canonical_dir(self) -> &'static str {
+        match self {
+            Self::Skills => "skills",
+            Self::Commands => "commands",
+            Self::Agents => "agents",
+            Self::Plugins => "plugins",
+        }
+    }
+}
+
+/// How resources are laid out inside a resource directory.
+///
+/// Extends [`DirectoryStructure`] with layouts `harness_locate` does not
+/// describe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceStructure {
+    /// Files matching `file_pattern` directly in the directory, named by
+    /// their stem: `commands/commit.md` is `commit`.
+    Flat { file_pattern: String },
+    /// One subdirectory per resource holding `file_name`, named by the
+    /// subdirectory: `skills/review/SKILL.md` is `review`.
+    Nested {
+        subdir_pattern: String,
+        file_name: String,
+    },
+    /// Files matching `file_pattern` at any depth, named by their path
+    /// below the directory with `separator` between levels and the last
+    /// level's extension dropped: `commands/git/commit.md` is `git:commit`.
+    Recursive {
+        file_pattern: String,
+        separator: String,
+    },
+}
+
+impl ResourceStructure {
+    /// The name of the resource in the file at `relative`, a path below
+    /// the resource directory, by the naming rule of this structure.
+    pub fn resource_name(&self, relative: &Path) -> Option<String> {
+        let levels: Vec<_> = relative.iter().map(|c| c.to_string_lossy()).collect();
+        match self {
+            Self::Flat { .. } => Some(relative.file_stem()?.to_string_lossy().into_owned()),
+            Self::Nested { .. } => Some(levels.first()?.to_string()),
+            Self::Recursive { separator, .. } => {
+                let (_, dirs) = levels.split_last()?;
+                let stem = relative.file_stem()?.to_string_lossy();
+                Some(
+                    dirs.iter()
+                        .map(|d| d.as_ref())
+                        .chain([stem.as_ref()])
+                        .collect::<Vec<_>>()
+                        .join(separator),
+                )
+            }
+        }
+    }
+}
+
+impl From<DirectoryStructure> for ResourceStructure {
+    fn from(structure: DirectoryStructure) -> Self {
+        match structure {
+            DirectoryStructure::Flat { file_pattern } => Self::Flat { file_pattern },
+            DirectoryStructure::Nested {
+                subdir_pattern,
+                file_name,
+            } => Self::Nested {
+                subdir_pattern,
+                file_name,
+            },
+        }
+    }
+}
+
+/// One resource directory of a harness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceDir {
+    /// Absolute path of the directory in the live config.
+    pub path: PathBuf,
+    /// How resources are laid out inside the directory.
+    pub structure: ResourceStructure,
+    /// Whether restored skill names must be sanitized and written back into
+    /// each `SKILL.md` (OpenCode only accepts a restricted set of names).
+    pub sanitize_names: bool,
+}
+
+impl ResourceDir {
+    pub fn new(path: impl Into<PathBuf>, structure: impl Into<ResourceStructure>) -> Self {
+        Self {
+            path: path.into(),
+            structure: structure.into(),
+            sanitize_names: false,
+        }
+    }
+
+    /// Marks restored entries as needing sanitized names.
+    pub fn sanitize_names(mut self) -> Self {
+        self.sanitize_names = true;
+        self
+    }
+
+    /// The directory's own name, e.g. `skill` or `skills`.
+    pub fn dir_name(&self) -> &str {
+        self.path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(ResourceKind::Skills.canonical_dir())
+    }
+}
+
+/// A resource category declared in configuration rather than built into
+/// bridle, e.g. prompts or workflows, in `[harness.<id>.resources.<name>]`
+/// or a custom harness's `[harness.resources.<name>]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CustomResource {
+    /// Directory holding the resources, relative to the config directory.
+    #[serde(deserialize_with = "relative_dir")]
+    pub dir: PathBuf,
+    /// Files listed as resources, e.g. `"*.md"`. With `file_name`, the
+    /// subdirectories listed instead.
+    #[serde(default = "default_pattern")]
+    pub pattern: String,
+    /// File each resource subdirectory must hold, e.g. `"WORKFLOW.md"`, for
+    /// categories with one subdirectory per resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    /// Without `file_name`, also lists files in subdirectories, named by
+    /// their path with this between levels, e.g. `":"` for `git:commit`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub separator: Option<String>,
+}
+
+fn default_pattern() -> String {
+    "*".to_string()
+}
+
+/// Rejects directories that would reach outside the config directory.
+fn relative_dir<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    let dir = PathBuf::deserialize(deserializer)?;
+    let inside = dir
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !inside || dir.as_os_str().is_empty() {
+        return Err(serde::de::Error::custom(format!(
+            "resource dir '{}' must be relative to the config directory",
+            dir.display()
+        )));
+    }
+    Ok(dir)
+}
+
+impl CustomResource {
+    /// How resources are laid out inside [`CustomResource::dir`].
+    pub fn structure(&self) -> ResourceStructure {
+        match (&self.file_name, &self.separator) {
+            (Some(file_name), _) => ResourceStructure::Nested {
+                subdir_pattern: self.pattern.clone(),
+                file_name: file_name.clone(),
+            },
+            (None, Some(separator)) => ResourceStructure::Recursive {
+                file_pattern: self.pattern.clone(),
+                separator: separator.clone(),
+            },
+            (None, None) => ResourceStructure::Flat {
+                file_pattern: self.pattern.clone(),
+            },
+        }
+    }
+}
+
+/// The resource directories a harness declares, and any that could not be located.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLayout {
+    dirs: Vec<(ResourceKind, ResourceDir)>,
+    errors: Vec<(ResourceKind, String)>,
+    custom: Vec<(String, CustomResource)>,
+}
+
+impl ResourceLayout {
+    /// Declares `dir` as the harness's directory for `kind`.
+    pub fn with_dir(mut self, kind: ResourceKind, dir: ResourceDir) -> Self {
+        self.dirs.retain(|(k, _)| *k != kind);
+        self.dirs.push((kind, dir));
+        self
+    }
+
+    /// Records that the directory for `kind` could not be located.
+    pub fn with_error(mut self, kind: ResourceKind, message: impl Into<String>) -> Self {
+        self.errors.push((kind, message.into()));
+        self
+    }
+
+    /// Declares the custom category `name`, replacing one of the same name.
+    pub fn with_custom(mut self, name: impl Into<String>, resource: CustomResource) -> Self {
+        let name = name.into();
+        self.custom.retain(|(n, _)| *n != name);
+        self.custom.push((name, resource));
+        self
+    }
+
+    /// The directory for `kind`, if the harness has one.
+    pub fn get(&self, kind: ResourceKind) -> Option<&ResourceDir> {
+        self.dirs.iter().find(|(k, _)| *k == kind).map(|(_, d)| d)
+    }
+
+    /// Why the directory for `kind` could not be located, if it could not.
+    pub fn error(&self, kind: ResourceKind) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, e)| e.as_str())
+    }
+
+    /// All declared directories, in [`ResourceKind::ALL`] order.
+    pub fn dirs(&self) -> impl Iterator<Item = (ResourceKind, &ResourceDir)> {
+        ResourceKind::ALL
+            .into_iter()
+            .filter_map(|kind| self.get(kind).map(|dir| (kind, dir)))
+    }
+
+    /// Custom categories, in the order they were declared.
+    pub fn custom(&self) -> impl Iterator<Item = (&str, &CustomResource)> {
+        self.custom.iter().map(|(name, r)| (name.as_str(), r))
+    }
+
+    /// Paths of the declared directories that lie inside `config_dir`,
+    /// relative to it, custom categories last.
+    pub fn relative_paths(&self, config_dir: &Path) -> Vec<PathBuf> {
+        self.dirs()
+            .filter_map(|(_, dir)| dir.path.strip_prefix(config_dir).ok())
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .chain(self.custom().map(|(_, r)| r.dir.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat() -> DirectoryStructure {
+        DirectoryStructure::Flat {
+            file_pattern: "*.md".to_string(),
+        }
+    }
+
+    #[test]
+    fn layout_lists_dirs_in_copy_order() {
+        let layout = ResourceLayout::default()
+            .with_dir(ResourceKind::Skills, ResourceDir::new("/cfg/skill", flat()))
+            .with_dir(
+                ResourceKind::Commands,
+                ResourceDir::new("/cfg/command", flat()),
+            )
+            .with_error(ResourceKind::Agents, "not found");
+
+        let kinds: Vec<_> = layout.dirs().map(|(k, _)| k).collect();
+        assert_eq!(kinds, vec![ResourceKind::Commands, ResourceKind::Skills]);
+        assert_eq!(
+            layout.get(ResourceKind::Skills).unwrap().dir_name(),
+            "skill"
+        );
+        assert!(layout.get(ResourceKind::Agents).is_none());
+        assert_eq!(layout.error(ResourceKind::Agents), Some("not found"));
+    }
+
+    #[test]
+    fn relative_paths_skip_dirs_outside_config_dir() {
+        let layout = ResourceLayout::default()
+            .with_dir(
+                ResourceKind::Skills,
+                ResourceDir::new("/cfg/skills", flat()),
+            )
+            .with_dir(
+                ResourceKind::Plugins,
+                ResourceDir::new("/elsewhere", flat()),
+            );
+
+        assert_eq!(
+            layout.relative_paths(Path::new("/cfg")),
+            vec![PathBuf::from("skills")]
+        );
+    }
+
+    #[test]
+    fn custom_categories_add_their_dirs() {
+        let prompts: CustomResource =
+            toml::from_str("dir = \"prompts\"\npattern = \"*.md\"").unwrap();
+        let workflows: CustomResource =
+            toml::from_str("dir = \"flows\"\nfile_name = \"WORKFLOW.md\"").unwrap();
+        assert_eq!(prompts.structure(), flat().into());
+        assert_eq!(
+            workflows.structure(),
+            ResourceStructure::Nested {
+                subdir_pattern: "*".to_string(),
+                file_name: "WORKFLOW.md".to_string(),
+            }
+        );
+
+        let layout = ResourceLayout::default()
+            .with_dir(
+                ResourceKind::Skills,
+                ResourceDir::new("/cfg/skills", flat()),
+            )
+            .with_custom("prompts", prompts)
+            .with_custom("workflows", workflows);
+
+        let names: Vec<_> = layout.custom().map(|(name, _)| name).collect();
+        assert_eq!(names, ["prompts", "workflows"]);
+        assert_eq!(
+            layout.relative_paths(Path::new("/cfg")),
+            ["skills", "prompts", "flows"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn recursive_names_join_levels_with_the_separator() {
+        let recursive = ResourceStructure::Recursive {
+            file_pattern: "*.md".to_string(),
+            separator: ":".to_string(),
+        };
+        let name = |structure: &ResourceStructure, path: &str| {
+            structure.resource_name(Path::new(path)).unwrap()
+        };
+
+        assert_eq!(name(&recursive, "commit.md"), "commit");
+        assert_eq!(name(&recursive, "git/commit.md"), "git:commit");
+        assert_eq!(
+            name(&recursive, "git/remote/push.v2.md"),
+            "git:remote:push.v2"
+        );
+        assert_eq!(name(&flat().into(), "commit.md"), "commit");
+
+        let commands: CustomResource =
+            toml::from_str("dir = \"commands\"\nseparator = \"/\"").unwrap();
+        assert_eq!(name(&commands.structure(), "git/commit.md"), "git/commit");
+    }
+
+    #[test]
+    fn custom_dirs_must_stay_inside_the_config_dir() {
+        for dir in ["/etc/prompts", "../prompts", "prompts/../.."] {
+            let parsed = toml::from_str::<CustomResource>(&format!("dir = \"{dir}\""));
+            assert!(parsed.is_err(), "{dir} was accepted");
+        }
+    }
+}
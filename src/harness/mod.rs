@@ -5,7 +5,13 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+pub mod check;
 mod display;
+pub mod edit;
+pub mod layout;
+mod overrides;
+pub mod registry;
+pub mod schema;
 
 use std::path::PathBuf;
 
@@ -14,6 +20,9 @@ use harness_locate::{InstallationStatus, McpServer, Scope};
 use crate::error::Result;
 
 pub use display::DisplayInfo;
+pub use layout::{CustomResource, ResourceDir, ResourceKind, ResourceLayout, ResourceStructure};
+pub use overrides::{OverriddenHarness, with_overrides};
+pub use registry::{HarnessEntry, HarnessRegistry};
 
 /// Configuration interface for AI coding assistant harnesses.
 ///
@@ -39,6 +48,130 @@ pub trait HarnessConfig {
     ///
     /// Returns a list of (server_name, enabled) pairs.
     fn parse_mcp_servers(&self, content: &str, filename: &str) -> Result<Vec<(String, bool)>>;
+
+    /// Returns the directories holding the harness's skills, commands, agents,
+    /// plugins, and custom resource categories. Empty for harnesses without
+    /// resource directories.
+    fn resource_layout(&self) -> ResourceLayout {
+        ResourceLayout::default()
+    }
+
+    /// Returns the underlying built-in harness, if this is one.
+    fn as_builtin(&self) -> Option<&harness_locate::Harness> {
+        None
+    }
+
+    /// Returns every executable on `PATH` that could be this harness, so
+    /// duplicate installations can be pointed out. Empty without a binary.
+    fn binary_candidates(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Returns extra file-name patterns never copied for this harness.
+    fn exclude_patterns(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns the switch strategy configured for this harness, if any.
+    fn switch_strategy(&self) -> Option<crate::config::SwitchStrategy> {
+        None
+    }
+
+    /// Returns the config file sections whose live values a switch keeps, as
+    /// `<file>:<key.path>`; see [`schema::protected_sections`].
+    fn protected_sections(&self) -> Vec<String> {
+        schema::protected_sections(self.id())
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    /// Returns the command, program first, that makes the harness load its
+    /// config and fail if it cannot. Empty when there is none.
+    fn check_command(&self) -> Vec<String> {
+        schema::check_command(self.id())
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    /// Returns the paths, relative to [`HarnessConfig::config_dir`], that
+    /// [`SwitchStrategy::Known`](crate::config::SwitchStrategy::Known) syncs.
+    ///
+    /// Resource directories and MCP config kept outside the config directory
+    /// are synced separately, if at all.
+    fn known_paths(&self) -> Vec<PathBuf> {
+        let Ok(config_dir) = self.config_dir() else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = schema::config_files(self.id())
+            .iter()
+            .map(PathBuf::from)
+            .chain(self.resource_layout().relative_paths(&config_dir))
+            .chain(
+                self.mcp_config_path()
+                    .and_then(|p| p.strip_prefix(&config_dir).ok().map(PathBuf::from)),
+            )
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
+
+/// Expands a leading `~` in `path` to the user's home directory.
+pub fn expand_home(path: &std::path::Path) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(rest) => Ok(harness_locate::platform::home_dir()?.join(rest)),
+        Err(_) => Ok(path.to_path_buf()),
+    }
+}
+
+/// Every distinct executable called `name` on `PATH`, in lookup order.
+///
+/// Links to the same file (an npm shim and its target, say) count once.
+pub fn find_all_binaries(name: &str) -> Vec<PathBuf> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let file_names: Vec<String> = if cfg!(windows) {
+        [".exe", ".cmd", ".bat"]
+            .iter()
+            .map(|ext| format!("{name}{ext}"))
+            .collect()
+    } else {
+        vec![name.to_string()]
+    };
+    let mut found: Vec<PathBuf> = Vec::new();
+    let mut targets: Vec<PathBuf> = Vec::new();
+    for dir in std::env::split_paths(&path) {
+        for file_name in &file_names {
+            let candidate = dir.join(file_name);
+            if !is_executable(&candidate) {
+                continue;
+            }
+            let target = candidate
+                .canonicalize()
+                .unwrap_or_else(|_| candidate.clone());
+            if !targets.contains(&target) {
+                targets.push(target);
+                found.push(candidate);
+            }
+        }
+    }
+    found
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
 }
 
 fn mcp_server_enabled(server: &McpServer) -> bool {
@@ -51,13 +184,7 @@ fn mcp_server_enabled(server: &McpServer) -> bool {
 
 impl HarnessConfig for harness_locate::Harness {
     fn id(&self) -> &'static str {
-        match self.kind() {
-            harness_locate::HarnessKind::ClaudeCode => "claude-code",
-            harness_locate::HarnessKind::OpenCode => "opencode",
-            harness_locate::HarnessKind::Goose => "goose",
-            harness_locate::HarnessKind::AmpCode => "amp-code",
-            _ => "unknown",
-        }
+        registry::builtin_id(self.kind())
     }
 
     fn config_dir(&self) -> Result<PathBuf> {
@@ -82,13 +209,10 @@ impl HarnessConfig for harness_locate::Harness {
     }
 
     fn parse_mcp_servers(&self, content: &str, filename: &str) -> Result<Vec<(String, bool)>> {
-        let is_yaml = filename.ends_with(".yaml") || filename.ends_with(".yml");
-        let mut parsed: serde_json::Value = if is_yaml {
-            let yaml: serde_yaml::Value = serde_yaml::from_str(content)?;
-            serde_json::to_value(yaml)?
-        } else {
-            serde_json::from_str(content)?
-        };
+        let mut parsed = schema::parse_named_document(content, filename)?;
+        if parsed.is_null() {
+            return Ok(Vec::new());
+        }
 
         // For Goose, filter extensions to only include actual MCP server types
         // (exclude builtin/platform which are Goose-internal, not MCP)
@@ -96,11 +220,10 @@ impl HarnessConfig for harness_locate::Harness {
             && let Some(extensions) = parsed.get_mut("extensions")
             && let Some(ext_obj) = extensions.as_object_mut()
         {
-            let mcp_types = ["stdio", "sse", "http", "streamable_http"];
             ext_obj.retain(|_, v| {
                 v.get("type")
                     .and_then(|t| t.as_str())
-                    .is_some_and(|t| mcp_types.contains(&t))
+                    .is_some_and(|t| schema::GOOSE_MCP_TYPES.contains(&t))
             });
         }
 
@@ -113,4 +236,58 @@ impl HarnessConfig for harness_locate::Harness {
         result.sort_by(|a, b| a.0.cmp(&b.0));
         Ok(result)
     }
+
+    fn as_builtin(&self) -> Option<&harness_locate::Harness> {
+        Some(self)
+    }
+
+    fn binary_candidates(&self) -> Vec<PathBuf> {
+        self.kind()
+            .binary_names()
+            .iter()
+            .flat_map(|name| find_all_binaries(name))
+            .collect()
+    }
+
+    fn resource_layout(&self) -> ResourceLayout {
+        let scope = Scope::Global;
+        ResourceKind::ALL
+            .into_iter()
+            .fold(ResourceLayout::default(), |layout, kind| {
+                let found = match kind {
+                    ResourceKind::Skills => self.skills(&scope),
+                    ResourceKind::Commands => self.commands(&scope),
+                    ResourceKind::Agents => self.agents(&scope),
+                    ResourceKind::Plugins => self.plugins(&scope),
+                };
+                match found {
+                    Ok(Some(resource)) => {
+                        let structure = match (kind, self.kind(), resource.structure) {
+                            // Claude Code names commands in subdirectories
+                            // `/<dir>:<name>`.
+                            (
+                                ResourceKind::Commands,
+                                harness_locate::HarnessKind::ClaudeCode,
+                                harness_locate::DirectoryStructure::Flat { file_pattern },
+                            ) => ResourceStructure::Recursive {
+                                file_pattern,
+                                separator: ":".to_string(),
+                            },
+                            (_, _, structure) => structure.into(),
+                        };
+                        let dir = ResourceDir::new(resource.path, structure);
+                        let dir = if kind == ResourceKind::Skills
+                            && matches!(self.kind(), harness_locate::HarnessKind::OpenCode)
+                        {
+                            dir.sanitize_names()
+                        } else {
+                            dir
+                        };
+                        layout.with_dir(kind, dir)
+                    }
+                    Ok(None) => layout,
+                    Err(e) => layout.with_error(kind, e.to_string()),
+                }
+            })
+    }
 }
@@ -0,0 +1,243 @@
+//! Per-harness overrides from the `[harness.<id>]` tables in `config.toml`.
+
+use std::path::PathBuf;
+
+use harness_locate::{Harness, InstallationStatus};
+
+use super::{HarnessConfig, expand_home};
+use crate::config::{BridleConfig, HarnessOverride};
+use crate::error::Result;
+
+/// A harness whose directory locations are replaced by a [`HarnessOverride`].
+///
+/// Resource directories (skills, agents, commands) keep the inner harness's
+/// [`ResourceLayout`](super::ResourceLayout), plus any custom categories the
+/// overrides declare.
+pub struct OverriddenHarness {
+    inner: Box<dyn HarnessConfig>,
+    overrides: HarnessOverride,
+}
+
+impl OverriddenHarness {
+    pub fn new(inner: Box<dyn HarnessConfig>, overrides: HarnessOverride) -> Self {
+        Self { inner, overrides }
+    }
+
+    fn overridden_config_dir(&self) -> Option<Result<PathBuf>> {
+        self.overrides.config_dir.as_deref().map(expand_home)
+    }
+
+    fn pinned_binary(&self) -> Option<Result<PathBuf>> {
+        self.overrides.binary.as_deref().map(expand_home)
+    }
+}
+
+/// Wraps `harness` with the overrides configured for its id, if any.
+pub fn with_overrides(
+    harness: Box<dyn HarnessConfig>,
+    config: &BridleConfig,
+) -> Box<dyn HarnessConfig> {
+    match config.harness_override(harness.id()) {
+        Some(overrides) => Box::new(OverriddenHarness::new(harness, overrides.clone())),
+        None => harness,
+    }
+}
+
+impl HarnessConfig for OverriddenHarness {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn config_dir(&self) -> Result<PathBuf> {
+        self.overridden_config_dir()
+            .unwrap_or_else(|| self.inner.config_dir())
+    }
+
+    fn installation_status(&self) -> Result<InstallationStatus> {
+        let pinned_binary = self.pinned_binary().transpose()?;
+        let config_dir = self.overridden_config_dir().transpose()?;
+        if pinned_binary.is_none() && config_dir.is_none() {
+            return self.inner.installation_status();
+        }
+        let (found_binary, found_config) = match self.inner.installation_status()? {
+            InstallationStatus::FullyInstalled {
+                binary_path,
+                config_path,
+            } => (Some(binary_path), Some(config_path)),
+            InstallationStatus::BinaryOnly { binary_path } => (Some(binary_path), None),
+            InstallationStatus::ConfigOnly { config_path } => (None, Some(config_path)),
+            _ => (None, None),
+        };
+        let binary_path = match pinned_binary {
+            Some(path) => Some(path).filter(|p| p.is_file()),
+            None => found_binary,
+        };
+        let config_path = match config_dir {
+            Some(path) => Some(path).filter(|p| p.is_dir()),
+            None => found_config,
+        };
+        Ok(match (binary_path, config_path) {
+            (Some(binary_path), Some(config_path)) => InstallationStatus::FullyInstalled {
+                binary_path,
+                config_path,
+            },
+            (Some(binary_path), None) => InstallationStatus::BinaryOnly { binary_path },
+            (None, Some(config_path)) => InstallationStatus::ConfigOnly { config_path },
+            (None, None) => InstallationStatus::NotInstalled,
+        })
+    }
+
+    fn binary_candidates(&self) -> Vec<PathBuf> {
+        match self.pinned_binary() {
+            Some(pinned) => pinned.into_iter().collect(),
+            None => self.inner.binary_candidates(),
+        }
+    }
+
+    fn mcp_filename(&self) -> Option<String> {
+        match &self.overrides.mcp_config {
+            Some(path) => path.file_name().and_then(|n| n.to_str()).map(String::from),
+            None => self.inner.mcp_filename(),
+        }
+    }
+
+    fn mcp_config_path(&self) -> Option<PathBuf> {
+        match &self.overrides.mcp_config {
+            Some(path) => expand_home(path).ok(),
+            None => self.inner.mcp_config_path(),
+        }
+    }
+
+    fn parse_mcp_servers(&self, content: &str, filename: &str) -> Result<Vec<(String, bool)>> {
+        self.inner.parse_mcp_servers(content, filename)
+    }
+
+    fn resource_layout(&self) -> super::ResourceLayout {
+        self.overrides
+            .resources
+            .iter()
+            .fold(self.inner.resource_layout(), |layout, (name, resource)| {
+                layout.with_custom(name.as_str(), resource.clone())
+            })
+    }
+
+    fn as_builtin(&self) -> Option<&Harness> {
+        self.inner.as_builtin()
+    }
+
+    fn exclude_patterns(&self) -> Vec<String> {
+        let mut patterns = self.inner.exclude_patterns();
+        patterns.extend(self.overrides.exclude.iter().cloned());
+        patterns
+    }
+
+    fn switch_strategy(&self) -> Option<crate::config::SwitchStrategy> {
+        self.overrides
+            .switch_strategy
+            .or_else(|| self.inner.switch_strategy())
+    }
+
+    fn known_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.inner.known_paths();
+        paths.extend(self.overrides.resources.values().map(|r| r.dir.clone()));
+        paths.extend(self.overrides.include.iter().cloned());
+        paths
+    }
+
+    fn protected_sections(&self) -> Vec<String> {
+        let mut sections = self.inner.protected_sections();
+        sections.extend(self.overrides.protect.iter().cloned());
+        sections
+    }
+
+    fn check_command(&self) -> Vec<String> {
+        if !self.overrides.check.is_empty() {
+            return self.overrides.check.clone();
+        }
+        let mut command = self.inner.check_command();
+        if let (Some(program), Some(Ok(pinned))) = (command.first_mut(), self.pinned_binary()) {
+            *program = pinned.to_string_lossy().into_owned();
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockHarness;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn overrides_replace_locations_and_add_excludes() {
+        let temp = TempDir::new().unwrap();
+        let custom_dir = temp.path().join("elsewhere");
+        std::fs::create_dir_all(&custom_dir).unwrap();
+
+        let mut config = BridleConfig::default();
+        config.harness.insert(
+            "test".to_string(),
+            HarnessOverride {
+                config_dir: Some(custom_dir.clone()),
+                binary: None,
+                mcp_config: Some(custom_dir.join("servers.json")),
+                exclude: vec!["*.log".to_string()],
+                switch_strategy: Some(crate::config::SwitchStrategy::Merge),
+                include: vec![PathBuf::from("themes")],
+                check: vec!["true".to_string()],
+                protect: vec!["auth.json:token".to_string()],
+                resources: BTreeMap::from([(
+                    "prompts".to_string(),
+                    toml::from_str("dir = \"prompts\"").unwrap(),
+                )]),
+            },
+        );
+
+        let inner = MockHarness::new("test", temp.path().join("default"));
+        let harness = with_overrides(Box::new(inner), &config);
+
+        assert_eq!(harness.config_dir().unwrap(), custom_dir);
+        assert_eq!(harness.mcp_filename().as_deref(), Some("servers.json"));
+        assert_eq!(harness.exclude_patterns(), vec!["*.log".to_string()]);
+        assert_eq!(
+            harness.switch_strategy(),
+            Some(crate::config::SwitchStrategy::Merge)
+        );
+        assert_eq!(
+            harness.known_paths(),
+            vec![PathBuf::from("prompts"), PathBuf::from("themes")]
+        );
+        assert_eq!(harness.check_command(), vec!["true".to_string()]);
+        assert_eq!(harness.protected_sections(), vec!["auth.json:token"]);
+    }
+
+    #[test]
+    fn check_runs_the_pinned_binary() {
+        let temp = TempDir::new().unwrap();
+        let mut config = BridleConfig::default();
+        config.harness.insert(
+            "opencode".to_string(),
+            HarnessOverride {
+                binary: Some(PathBuf::from("/opt/opencode/bin/opencode")),
+                ..Default::default()
+            },
+        );
+        let inner = MockHarness::new("opencode", temp.path().to_path_buf());
+        let harness = with_overrides(Box::new(inner), &config);
+        assert_eq!(
+            harness.check_command(),
+            ["/opt/opencode/bin/opencode", "debug", "config"]
+        );
+    }
+
+    #[test]
+    fn harness_without_overrides_is_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let inner = MockHarness::new("plain", temp.path().to_path_buf());
+        let harness = with_overrides(Box::new(inner), &BridleConfig::default());
+        assert_eq!(harness.config_dir().unwrap(), temp.path());
+        assert!(harness.exclude_patterns().is_empty());
+        assert_eq!(harness.switch_strategy(), None);
+    }
+}
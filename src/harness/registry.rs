@@ -0,0 +1,532 @@
+//! Registry of every harness bridle can manage.
+//!
+//! Built-in harnesses come from `harness_locate`. Additional harnesses can be
+//! declared in `harnesses.toml` next to bridle's `config.toml`:
+//!
+//! ```toml
+//! [[harness]]
+//! id = "cursor"
+//! name = "Cursor"
+//! aliases = ["cur"]
+//! config_dir = "~/.cursor"
+//! binary = "cursor"
+//! mcp_file = "mcp.json"
+//!
+//! [harness.resources.rules]
+//! dir = "rules"
+//! pattern = "*.mdc"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use harness_locate::{Harness, HarnessKind, InstallationStatus};
+use serde::Deserialize;
+
+use super::{CustomResource, HarnessConfig, ResourceLayout, with_overrides};
+use crate::config::BridleConfig;
+use crate::error::{Error, Result};
+
+/// File in bridle's config directory that declares custom harnesses.
+pub const CUSTOM_HARNESSES_FILENAME: &str = "harnesses.toml";
+
+/// Built-in harnesses as `(kind, id, display name, extra aliases)`.
+const BUILTINS: &[(HarnessKind, &str, &str, &[&str])] = &[
+    (
+        HarnessKind::ClaudeCode,
+        "claude-code",
+        "Claude Code",
+        &["claude", "cc"],
+    ),
+    (HarnessKind::OpenCode, "opencode", "OpenCode", &["oc"]),
+    (HarnessKind::Goose, "goose", "Goose", &[]),
+    (
+        HarnessKind::AmpCode,
+        "amp-code",
+        "AMP Code",
+        &["amp", "ampcode"],
+    ),
+];
+
+/// Returns the bridle identifier for a built-in harness kind.
+pub fn builtin_id(kind: HarnessKind) -> &'static str {
+    BUILTINS
+        .iter()
+        .find(|(k, ..)| *k == kind)
+        .map(|(_, id, ..)| *id)
+        .unwrap_or("unknown")
+}
+
+/// Returns the display name for a built-in harness kind.
+pub fn builtin_name(kind: HarnessKind) -> &'static str {
+    BUILTINS
+        .iter()
+        .find(|(k, ..)| *k == kind)
+        .map(|(_, _, name, _)| *name)
+        .unwrap_or("Unknown")
+}
+
+/// Resolves a built-in harness id or alias (e.g. `cc`, `oc`) to its kind.
+pub fn parse_kind(name: &str) -> Option<HarnessKind> {
+    BUILTINS
+        .iter()
+        .find(|(_, id, _, aliases)| *id == name || aliases.contains(&name))
+        .map(|(kind, ..)| *kind)
+}
+
+/// A harness known to the registry, with its display name and aliases.
+pub struct HarnessEntry {
+    harness: Box<dyn HarnessConfig>,
+    name: String,
+    aliases: Vec<String>,
+}
+
+impl HarnessEntry {
+    /// Returns the harness identifier.
+    pub fn id(&self) -> &str {
+        self.harness.id()
+    }
+
+    /// Returns the human-readable harness name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the alternative names accepted on the command line.
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// Returns the built-in kind, or `None` for custom harnesses.
+    pub fn kind(&self) -> Option<HarnessKind> {
+        self.harness.as_builtin().map(|h| h.kind())
+    }
+
+    /// Returns the harness implementation.
+    pub fn harness(&self) -> &dyn HarnessConfig {
+        self.harness.as_ref()
+    }
+
+    /// Returns `true` if `name` is this harness's id or one of its aliases.
+    pub fn matches(&self, name: &str) -> bool {
+        self.id() == name || self.aliases.iter().any(|a| a == name)
+    }
+}
+
+/// Owns every harness bridle knows about, built-in and user-defined.
+#[derive(Default)]
+pub struct HarnessRegistry {
+    entries: Vec<HarnessEntry>,
+}
+
+impl HarnessRegistry {
+    /// Creates a registry containing only the built-in harnesses.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for (kind, _, name, aliases) in BUILTINS {
+            registry.entries.push(HarnessEntry {
+                harness: Box::new(Harness::new(*kind)),
+                name: (*name).to_string(),
+                aliases: aliases.iter().map(|a| (*a).to_string()).collect(),
+            });
+        }
+        registry
+    }
+
+    /// Creates a registry with the built-in harnesses plus any declared in
+    /// [`CUSTOM_HARNESSES_FILENAME`] in bridle's config directory, with the
+    /// `[harness.<id>]` overrides from `config.toml` applied.
+    ///
+    /// # Errors
+    /// Returns an error if the custom harness file or `config.toml` is invalid.
+    pub fn load() -> Result<Self> {
+        let path = BridleConfig::config_dir()?.join(CUSTOM_HARNESSES_FILENAME);
+        let mut registry = Self::builtin();
+        registry.load_custom_file(&path)?;
+        registry.apply_overrides(&BridleConfig::load()?);
+        Ok(registry)
+    }
+
+    /// Wraps every harness that has a `[harness.<id>]` table in `config`.
+    pub fn apply_overrides(&mut self, config: &BridleConfig) {
+        self.entries = std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(|mut entry| {
+                entry.harness = with_overrides(entry.harness, config);
+                entry
+            })
+            .collect();
+    }
+
+    /// Registers every harness declared in a custom harness file.
+    ///
+    /// A missing file is not an error.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be parsed or declares a harness
+    /// whose id or alias is already taken.
+    pub fn load_custom_file(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let file: CustomHarnessFile = toml::from_str(&content).map_err(|e| Error::ConfigParse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        for harness in file.harnesses {
+            self.register_custom(harness)?;
+        }
+        Ok(())
+    }
+
+    /// Registers a user-defined harness.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] if its id or an alias is already registered.
+    pub fn register_custom(&mut self, harness: CustomHarness) -> Result<()> {
+        let name = harness.name.clone().unwrap_or_else(|| harness.id.clone());
+        let aliases = harness.aliases.clone();
+        self.register(Box::new(harness), name, aliases)
+    }
+
+    /// Registers a harness implementation under a display name and aliases.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] if its id or an alias is already registered.
+    pub fn register(
+        &mut self,
+        harness: Box<dyn HarnessConfig>,
+        name: String,
+        aliases: Vec<String>,
+    ) -> Result<()> {
+        let id = harness.id();
+        if let Some(taken) = std::iter::once(id)
+            .chain(aliases.iter().map(String::as_str))
+            .find(|n| self.get(n).is_some())
+        {
+            return Err(Error::Config(format!(
+                "harness name '{taken}' is already registered"
+            )));
+        }
+        self.entries.push(HarnessEntry {
+            harness,
+            name,
+            aliases,
+        });
+        Ok(())
+    }
+
+    /// Iterates over all registered harnesses, built-ins first.
+    pub fn iter(&self) -> impl Iterator<Item = &HarnessEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the ids of all registered harnesses.
+    pub fn ids(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.id()).collect()
+    }
+
+    /// Looks up a harness by id or alias.
+    pub fn get(&self, name: &str) -> Option<&HarnessEntry> {
+        self.entries.iter().find(|e| e.matches(name))
+    }
+
+    /// Looks up a harness by id or alias.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownHarness`] if no harness matches.
+    pub fn resolve(&self, name: &str) -> Result<&HarnessEntry> {
+        self.get(name)
+            .ok_or_else(|| Error::UnknownHarness(name.to_string()))
+    }
+
+    /// Removes and returns the harness matching `name`.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownHarness`] if no harness matches.
+    pub fn take(mut self, name: &str) -> Result<Box<dyn HarnessConfig>> {
+        let idx = self
+            .entries
+            .iter()
+            .position(|e| e.matches(name))
+            .ok_or_else(|| Error::UnknownHarness(name.to_string()))?;
+        Ok(self.entries.swap_remove(idx).harness)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CustomHarnessFile {
+    #[serde(default, rename = "harness")]
+    harnesses: Vec<CustomHarness>,
+}
+
+fn default_mcp_key() -> String {
+    "mcpServers".to_string()
+}
+
+/// A harness declared in [`CUSTOM_HARNESSES_FILENAME`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomHarness {
+    /// Identifier used on the command line and in profile paths.
+    pub id: String,
+    /// Display name. Defaults to the id.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Alternative names accepted on the command line.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Live configuration directory. A leading `~` expands to the home directory.
+    pub config_dir: PathBuf,
+    /// Executable looked up in PATH to decide whether the harness is installed.
+    #[serde(default)]
+    pub binary: Option<String>,
+    /// MCP config file, relative to `config_dir`.
+    #[serde(default)]
+    pub mcp_file: Option<String>,
+    /// Top-level key holding MCP server definitions in `mcp_file`.
+    #[serde(default = "default_mcp_key")]
+    pub mcp_key: String,
+    /// Resource categories bridle lists and copies for this harness, keyed
+    /// by name.
+    #[serde(default)]
+    pub resources: BTreeMap<String, CustomResource>,
+}
+
+impl CustomHarness {
+    fn expanded_config_dir(&self) -> Result<PathBuf> {
+        super::expand_home(&self.config_dir)
+    }
+}
+
+impl HarnessConfig for CustomHarness {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn config_dir(&self) -> Result<PathBuf> {
+        self.expanded_config_dir()
+    }
+
+    fn installation_status(&self) -> Result<InstallationStatus> {
+        let config_path = self.expanded_config_dir()?;
+        let binary_path = match &self.binary {
+            Some(binary) => harness_locate::find_binary(binary)?,
+            None => None,
+        };
+        Ok(match (binary_path, config_path.is_dir()) {
+            (Some(binary_path), true) => InstallationStatus::FullyInstalled {
+                binary_path,
+                config_path,
+            },
+            (Some(binary_path), false) => InstallationStatus::BinaryOnly { binary_path },
+            // Without a binary to look for, an existing config dir is enough.
+            (None, true) if self.binary.is_none() => InstallationStatus::FullyInstalled {
+                binary_path: PathBuf::new(),
+                config_path,
+            },
+            (None, true) => InstallationStatus::ConfigOnly { config_path },
+            (None, false) => InstallationStatus::NotInstalled,
+        })
+    }
+
+    fn binary_candidates(&self) -> Vec<PathBuf> {
+        self.binary
+            .as_deref()
+            .map(super::find_all_binaries)
+            .unwrap_or_default()
+    }
+
+    fn mcp_filename(&self) -> Option<String> {
+        self.mcp_file.clone()
+    }
+
+    fn mcp_config_path(&self) -> Option<PathBuf> {
+        let file = self.mcp_file.as_ref()?;
+        self.expanded_config_dir().ok().map(|dir| dir.join(file))
+    }
+
+    fn resource_layout(&self) -> ResourceLayout {
+        self.resources
+            .iter()
+            .fold(ResourceLayout::default(), |layout, (name, resource)| {
+                layout.with_custom(name.as_str(), resource.clone())
+            })
+    }
+
+    fn parse_mcp_servers(&self, content: &str, filename: &str) -> Result<Vec<(String, bool)>> {
+        let parsed = super::schema::parse_named_document(content, filename)?;
+
+        let Some(servers) = parsed.get(&self.mcp_key).and_then(|v| v.as_object()) else {
+            return Ok(Vec::new());
+        };
+        let mut result: Vec<(String, bool)> = servers
+            .iter()
+            .map(|(name, server)| {
+                let disabled = server.get("disabled").and_then(|v| v.as_bool()) == Some(true);
+                let enabled = server.get("enabled").and_then(|v| v.as_bool()) != Some(false);
+                (name.clone(), enabled && !disabled)
+            })
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn custom(id: &str, config_dir: &Path) -> CustomHarness {
+        CustomHarness {
+            id: id.to_string(),
+            name: None,
+            aliases: Vec::new(),
+            config_dir: config_dir.to_path_buf(),
+            binary: None,
+            mcp_file: Some("mcp.json".to_string()),
+            mcp_key: default_mcp_key(),
+            resources: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn builtin_resolves_aliases() {
+        let registry = HarnessRegistry::builtin();
+        assert_eq!(registry.resolve("cc").unwrap().id(), "claude-code");
+        assert_eq!(registry.resolve("oc").unwrap().id(), "opencode");
+        assert_eq!(registry.resolve("amp").unwrap().id(), "amp-code");
+        assert_eq!(
+            registry.resolve("goose").unwrap().kind(),
+            Some(HarnessKind::Goose)
+        );
+        assert!(matches!(
+            registry.resolve("vim"),
+            Err(Error::UnknownHarness(_))
+        ));
+    }
+
+    #[test]
+    fn builtin_covers_every_kind() {
+        let registry = HarnessRegistry::builtin();
+        for kind in HarnessKind::ALL {
+            assert_eq!(parse_kind(builtin_id(*kind)), Some(*kind));
+            assert!(registry.iter().any(|e| e.kind() == Some(*kind)));
+        }
+    }
+
+    #[test]
+    fn loads_custom_harnesses_from_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CUSTOM_HARNESSES_FILENAME);
+        std::fs::write(
+            &path,
+            r#"
+[[harness]]
+id = "cursor"
+name = "Cursor"
+aliases = ["cur"]
+config_dir = "/tmp/cursor"
+mcp_file = "mcp.json"
+
+[harness.resources.rules]
+dir = "rules"
+pattern = "*.mdc"
+"#,
+        )
+        .unwrap();
+
+        let mut registry = HarnessRegistry::builtin();
+        registry.load_custom_file(&path).unwrap();
+
+        let entry = registry.resolve("cur").unwrap();
+        assert_eq!(entry.id(), "cursor");
+        assert_eq!(entry.name(), "Cursor");
+        assert_eq!(entry.kind(), None);
+        assert_eq!(
+            entry.harness().mcp_config_path(),
+            Some(PathBuf::from("/tmp/cursor/mcp.json"))
+        );
+        let layout = entry.harness().resource_layout();
+        let (name, rules) = layout.custom().next().unwrap();
+        assert_eq!(name, "rules");
+        assert_eq!(rules.pattern, "*.mdc");
+    }
+
+    #[test]
+    fn rejects_duplicate_names() {
+        let temp = TempDir::new().unwrap();
+        let mut registry = HarnessRegistry::builtin();
+        let mut harness = custom("mine", temp.path());
+        harness.aliases = vec!["cc".to_string()];
+        assert!(matches!(
+            registry.register_custom(harness),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_custom_file_reports_path() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CUSTOM_HARNESSES_FILENAME);
+        std::fs::write(&path, "[[harness]]\nid = 3\n").unwrap();
+
+        let mut registry = HarnessRegistry::builtin();
+        let err = registry.load_custom_file(&path).unwrap_err();
+        assert!(matches!(err, Error::ConfigParse { path: p, .. } if p == path));
+    }
+
+    #[test]
+    fn custom_harness_parses_mcp_servers() {
+        let temp = TempDir::new().unwrap();
+        let harness = custom("mine", temp.path());
+        let content = r#"{
+            // comment
+            "mcpServers": {
+                "b": { "command": "b", "disabled": true },
+                "a": { "command": "a" }
+            }
+        }"#;
+        let servers = harness.parse_mcp_servers(content, "mcp.json").unwrap();
+        assert_eq!(
+            servers,
+            vec![("a".to_string(), true), ("b".to_string(), false)]
+        );
+
+        let yaml =
+            "base: &base\n  command: x\nmcpServers:\n  c:\n    <<: *base\n    disabled: true\n";
+        let servers = harness.parse_mcp_servers(yaml, "mcp.yaml").unwrap();
+        assert_eq!(servers, vec![("c".to_string(), false)]);
+        assert!(
+            harness
+                .parse_mcp_servers("", "mcp.json")
+                .unwrap()
+                .is_empty()
+        );
+
+        let broken = "{\n  /* servers */ \"mcpServers\": {\n    \"a\": { \"command\": \"a\" }\n    \"b\": {}\n  }\n}";
+        let err = harness.parse_mcp_servers(broken, "mcp.json").unwrap_err();
+        assert!(
+            err.to_string().contains("mcp.json") && err.to_string().contains("line 4 column 5"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn custom_harness_without_binary_is_installed_when_config_exists() {
+        let temp = TempDir::new().unwrap();
+        let harness = custom("mine", temp.path());
+        assert!(matches!(
+            harness.installation_status().unwrap(),
+            InstallationStatus::FullyInstalled { .. }
+        ));
+
+        let missing = custom("gone", &temp.path().join("missing"));
+        assert!(matches!(
+            missing.installation_status().unwrap(),
+            InstallationStatus::NotInstalled
+        ));
+    }
+}
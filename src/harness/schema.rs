@@ -0,0 +1,823 @@
+//! Typed models of each harness's primary config file.
+//!
+//! Every model keeps unrecognised keys in a flattened `extra` map so that
+//! loading and saving a file round-trips settings bridle does not know about.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::config::jsonc::strip_jsonc_comments;
+use crate::config::write_atomic;
+use crate::error::{Error, Result};
+
+type JsonMap = serde_json::Map<String, serde_json::Value>;
+
+/// On-disk encoding of a harness config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// Plain JSON.
+    Json,
+    /// JSON with comments and trailing commas (written back as plain JSON).
+    Jsonc,
+    /// YAML.
+    Yaml,
+    /// TOML.
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Format implied by a file extension (`.json`, `.jsonc`, `.yaml`/`.yml`, `.toml`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "jsonc" => Some(Self::Jsonc),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Files in a built-in harness's config directory holding its settings and
+/// global instructions. Empty for unknown ids.
+pub fn config_files(harness_id: &str) -> &'static [&'static str] {
+    match harness_id {
+        "opencode" => &[OpenCodeConfig::FILENAME, "opencode.json", "AGENTS.md"],
+        "claude-code" => &[ClaudeSettings::FILENAME, "CLAUDE.md"],
+        "goose" => &[GooseConfig::FILENAME, ".goosehints"],
+        "amp-code" => &[AmpSettings::FILENAME, "AGENTS.md"],
+        _ => &[],
+    }
+}
+
+/// The global instructions file in a built-in harness's config directory.
+pub fn rules_file(harness_id: &str) -> Option<&'static str> {
+    match harness_id {
+        "opencode" | "amp-code" => Some("AGENTS.md"),
+        "claude-code" => Some("CLAUDE.md"),
+        "goose" => Some(".goosehints"),
+        _ => None,
+    }
+}
+
+/// Sections of a built-in harness's config files that belong to this machine
+/// rather than to a profile, such as sign-ins and MCP OAuth tokens, as
+/// `<file>:<key.path>`. Empty for unknown ids.
+pub fn protected_sections(harness_id: &str) -> &'static [&'static str] {
+    match harness_id {
+        "claude-code" => &[
+            ".claude.json:oauthAccount",
+            ".credentials.json:claudeAiOauth",
+            ".credentials.json:mcpOAuth",
+        ],
+        _ => &[],
+    }
+}
+
+/// The command, program first, with which a built-in harness loads and
+/// prints its own config. Empty for harnesses without one.
+pub fn check_command(harness_id: &str) -> &'static [&'static str] {
+    match harness_id {
+        "claude-code" => &["claude", "config", "list"],
+        "opencode" => &["opencode", "debug", "config"],
+        "goose" => &["goose", "info", "-v"],
+        _ => &[],
+    }
+}
+
+/// Reads and deserializes a config file.
+///
+/// Returns `Ok(None)` if the file does not exist or is empty.
+///
+/// # Errors
+/// Returns [`Error::ConfigParse`] if the file does not match the model.
+pub fn read_config<T: DeserializeOwned>(path: &Path, format: ConfigFormat) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_config(&content, format)
+        .map(Some)
+        .map_err(|message| Error::ConfigParse {
+            path: path.to_path_buf(),
+            message,
+        })
+}
+
+fn parse_config<T: DeserializeOwned>(
+    content: &str,
+    format: ConfigFormat,
+) -> std::result::Result<T, String> {
+    match format {
+        ConfigFormat::Json | ConfigFormat::Jsonc => {
+            serde_json::from_str(&strip_jsonc_comments(content)).map_err(|e| e.to_string())
+        }
+        ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+    }
+}
+
+/// Parses a config file's text without a model, as read by harnesses: JSON
+/// may have comments and trailing commas, and YAML merge keys (`<<: *base`)
+/// are applied and its keys read as strings. An empty file is `null`.
+///
+/// # Errors
+/// Returns the parser's message, which names the line and column in
+/// `content` where parsing stopped.
+pub fn parse_document(
+    content: &str,
+    format: ConfigFormat,
+) -> std::result::Result<serde_json::Value, String> {
+    if content.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    match format {
+        ConfigFormat::Yaml => {
+            let mut yaml: serde_yaml::Value =
+                serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+            yaml.apply_merge().map_err(|e| e.to_string())?;
+            Ok(yaml_to_json(yaml))
+        }
+        _ => parse_config(content, format),
+    }
+}
+
+/// [`parse_document`] for a file named `filename`, whose extension gives the
+/// format; files that are not YAML or TOML are read as JSONC.
+///
+/// # Errors
+/// Returns [`Error::ConfigParse`] with `filename` and where parsing stopped.
+pub fn parse_named_document(content: &str, filename: &str) -> Result<serde_json::Value> {
+    let path = Path::new(filename);
+    let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Jsonc);
+    parse_document(content, format).map_err(|message| Error::ConfigParse {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// `value` as JSON: keys become strings and tags are dropped.
+fn yaml_to_json(value: serde_yaml::Value) -> serde_json::Value {
+    use serde_yaml::Value as Yaml;
+    match value {
+        Yaml::Null => serde_json::Value::Null,
+        Yaml::Bool(b) => b.into(),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into()
+            } else if let Some(u) = n.as_u64() {
+                u.into()
+            } else {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or(serde_json::Value::Null, serde_json::Value::Number)
+            }
+        }
+        Yaml::String(s) => s.into(),
+        Yaml::Sequence(items) => items.into_iter().map(yaml_to_json).collect(),
+        Yaml::Mapping(map) => map
+            .into_iter()
+            .map(|(key, value)| {
+                let key = match yaml_to_json(key) {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                (key, yaml_to_json(value))
+            })
+            .collect(),
+        Yaml::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// A map of MCP servers, read one entry at a time so that a server with a
+/// mistyped field, such as a number among its `env` values, is set aside in
+/// `invalid` instead of failing the whole file. Dereferences to the servers
+/// that parsed; invalid entries are written back unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpServers<T> {
+    pub servers: BTreeMap<String, T>,
+    /// Entries that did not parse, by name, with why and as written.
+    pub invalid: BTreeMap<String, InvalidServer>,
+}
+
+/// An MCP server entry whose fields do not parse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidServer {
+    pub error: String,
+    pub raw: serde_json::Value,
+}
+
+impl<T> McpServers<T> {
+    /// Whether there are no entries, valid or not.
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.invalid.is_empty()
+    }
+}
+
+impl<T> Default for McpServers<T> {
+    fn default() -> Self {
+        Self {
+            servers: BTreeMap::new(),
+            invalid: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> std::ops::Deref for McpServers<T> {
+    type Target = BTreeMap<String, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.servers
+    }
+}
+
+impl<T> std::ops::DerefMut for McpServers<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.servers
+    }
+}
+
+impl<T> IntoIterator for McpServers<T> {
+    type Item = (String, T);
+    type IntoIter = std::collections::btree_map::IntoIter<String, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.servers.into_iter()
+    }
+}
+
+impl<T> FromIterator<(String, T)> for McpServers<T> {
+    fn from_iter<I: IntoIterator<Item = (String, T)>>(iter: I) -> Self {
+        Self {
+            servers: iter.into_iter().collect(),
+            invalid: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for McpServers<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let raw = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        let mut servers = Self::default();
+        for (name, value) in raw {
+            match T::deserialize(&value) {
+                Ok(server) => {
+                    servers.servers.insert(name, server);
+                }
+                Err(e) => {
+                    let error = e.to_string();
+                    servers
+                        .invalid
+                        .insert(name, InvalidServer { error, raw: value });
+                }
+            }
+        }
+        Ok(servers)
+    }
+}
+
+impl<T: Serialize> Serialize for McpServers<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.servers.len() + self.invalid.len()))?;
+        for (name, server) in &self.servers {
+            map.serialize_entry(name, server)?;
+        }
+        for (name, invalid) in &self.invalid {
+            map.serialize_entry(name, &invalid.raw)?;
+        }
+        map.end()
+    }
+}
+
+/// Writes a config model to `path`, creating parent directories. An existing
+/// file only has the values that changed edited, so its comments and layout
+/// survive; one that cannot be edited in place is serialized anew.
+pub fn write_config<T: Serialize>(path: &Path, value: &T, format: ConfigFormat) -> Result<()> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(content) if !content.trim().is_empty() => Some(content),
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let updated = existing.and_then(|content| {
+        let value = serde_json::to_value(value).ok()?;
+        super::edit::update(&content, format, &value).ok()
+    });
+    let content = match (updated, format) {
+        (Some(content), _) => content,
+        (None, ConfigFormat::Json | ConfigFormat::Jsonc) => serde_json::to_string_pretty(value)?,
+        (None, ConfigFormat::Yaml) => serde_yaml::to_string(value)?,
+        (None, ConfigFormat::Toml) => {
+            toml::to_string_pretty(value).map_err(|e| Error::Config(e.to_string()))?
+        }
+    };
+    write_atomic(path, content)
+}
+
+/// Common accessors for harness settings that bridle reads and edits.
+pub trait HarnessSettings {
+    /// Returns the configured default model.
+    fn model(&self) -> Option<&str>;
+
+    /// Sets (or clears) the default model.
+    fn set_model(&mut self, model: Option<String>);
+
+    /// Returns the configured UI theme.
+    fn theme(&self) -> Option<&str>;
+
+    /// Sets (or clears) the UI theme.
+    fn set_theme(&mut self, theme: Option<String>);
+}
+
+/// A command given either as a single string or as an argv array.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CommandLine {
+    /// `"command": "npx"`
+    Program(String),
+    /// `"command": ["npx", "-y", "server"]`
+    Argv(Vec<String>),
+}
+
+impl CommandLine {
+    /// Returns the program to execute.
+    pub fn program(&self) -> Option<&str> {
+        match self {
+            Self::Program(p) => Some(p),
+            Self::Argv(argv) => argv.first().map(String::as_str),
+        }
+    }
+
+    /// Returns arguments embedded in an argv-style command.
+    pub fn embedded_args(&self) -> &[String] {
+        match self {
+            Self::Program(_) => &[],
+            Self::Argv(argv) => argv.get(1..).unwrap_or_default(),
+        }
+    }
+}
+
+/// MCP server entry shared by Claude Code's `.mcp.json` and AMP's settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsonMcpServer {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub server_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// OpenCode's `opencode.jsonc`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenCodeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agent: BTreeMap<String, OpenCodeAgent>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub command: BTreeMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugin: Vec<String>,
+    #[serde(default, skip_serializing_if = "McpServers::is_empty")]
+    pub mcp: McpServers<OpenCodeMcpServer>,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// Agent definition inside `opencode.jsonc`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenCodeAgent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// MCP server entry inside `opencode.jsonc`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenCodeMcpServer {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub server_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<CommandLine>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl OpenCodeConfig {
+    pub const FILENAME: &'static str = "opencode.jsonc";
+    pub const FORMAT: ConfigFormat = ConfigFormat::Jsonc;
+
+    /// Loads `opencode.jsonc` from a config or profile directory.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        read_config(&dir.join(Self::FILENAME), Self::FORMAT)
+    }
+}
+
+impl HarnessSettings for OpenCodeConfig {
+    /// Top-level `model`, falling back to the `general` agent's model.
+    fn model(&self) -> Option<&str> {
+        self.model
+            .as_deref()
+            .or_else(|| self.agent.get("general")?.model.as_deref())
+    }
+
+    fn set_model(&mut self, model: Option<String>) {
+        self.model = model;
+    }
+
+    fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    fn set_theme(&mut self, theme: Option<String>) {
+        self.theme = theme;
+    }
+}
+
+/// Claude Code's `settings.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClaudeSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl ClaudeSettings {
+    pub const FILENAME: &'static str = "settings.json";
+    pub const FORMAT: ConfigFormat = ConfigFormat::Json;
+
+    /// Loads `settings.json` from a config or profile directory.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        read_config(&dir.join(Self::FILENAME), Self::FORMAT)
+    }
+}
+
+impl HarnessSettings for ClaudeSettings {
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    fn set_model(&mut self, model: Option<String>) {
+        self.model = model;
+    }
+
+    fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    fn set_theme(&mut self, theme: Option<String>) {
+        self.theme = theme;
+    }
+}
+
+/// Claude Code's `.mcp.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClaudeMcpConfig {
+    #[serde(rename = "mcpServers", default)]
+    pub mcp_servers: McpServers<JsonMcpServer>,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl ClaudeMcpConfig {
+    pub const FILENAME: &'static str = ".mcp.json";
+    pub const FORMAT: ConfigFormat = ConfigFormat::Json;
+
+    /// Loads `.mcp.json` from a config or profile directory.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        read_config(&dir.join(Self::FILENAME), Self::FORMAT)
+    }
+}
+
+/// Extension types Goose treats as MCP servers (as opposed to `builtin`/`platform`).
+pub const GOOSE_MCP_TYPES: &[&str] = &["stdio", "sse", "http", "streamable_http"];
+
+/// Goose's `config.yaml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseConfig {
+    #[serde(
+        rename = "GOOSE_MODEL",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub model: Option<String>,
+    #[serde(
+        rename = "GOOSE_CLI_THEME",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub theme: Option<String>,
+    #[serde(default, skip_serializing_if = "McpServers::is_empty")]
+    pub extensions: McpServers<GooseExtension>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Extension entry inside Goose's `config.yaml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseExtension {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub ext_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl GooseExtension {
+    /// Whether this extension is an MCP server rather than a Goose builtin.
+    pub fn is_mcp(&self) -> bool {
+        self.ext_type
+            .as_deref()
+            .is_some_and(|t| GOOSE_MCP_TYPES.contains(&t))
+    }
+}
+
+impl GooseConfig {
+    pub const FILENAME: &'static str = "config.yaml";
+    pub const FORMAT: ConfigFormat = ConfigFormat::Yaml;
+
+    /// Loads `config.yaml` from a config or profile directory.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        read_config(&dir.join(Self::FILENAME), Self::FORMAT)
+    }
+}
+
+impl HarnessSettings for GooseConfig {
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    fn set_model(&mut self, model: Option<String>) {
+        self.model = model;
+    }
+
+    fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    fn set_theme(&mut self, theme: Option<String>) {
+        self.theme = theme;
+    }
+}
+
+/// AMP Code's `settings.json`, which uses dotted top-level keys.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AmpSettings {
+    #[serde(rename = "amp.theme", default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(
+        rename = "amp.model.default",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub model: Option<String>,
+    #[serde(
+        rename = "amp.mcpServers",
+        default,
+        skip_serializing_if = "McpServers::is_empty"
+    )]
+    pub mcp_servers: McpServers<JsonMcpServer>,
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl AmpSettings {
+    pub const FILENAME: &'static str = "settings.json";
+    pub const FORMAT: ConfigFormat = ConfigFormat::Json;
+
+    /// Loads `settings.json` from a config or profile directory.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        read_config(&dir.join(Self::FILENAME), Self::FORMAT)
+    }
+}
+
+impl HarnessSettings for AmpSettings {
+    /// `amp.model.default`, falling back to a nested `amp.model` string.
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref().or_else(|| {
+            self.extra
+                .get("amp")
+                .and_then(|amp| amp.get("model"))
+                .and_then(|m| m.as_str())
+        })
+    }
+
+    fn set_model(&mut self, model: Option<String>) {
+        self.model = model;
+    }
+
+    fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    fn set_theme(&mut self, theme: Option<String>) {
+        self.theme = theme;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn opencode_round_trips_unknown_keys() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("opencode.jsonc"),
+            r#"{
+  // comment
+  "theme": "dark",
+  "keybinds": {"leader": "ctrl+x"},
+  "mcp": {"fs": {"type": "local", "command": ["npx", "fs"], "enabled": false}},
+}"#,
+        )
+        .unwrap();
+
+        let mut config = OpenCodeConfig::load(temp.path()).unwrap().unwrap();
+        assert_eq!(config.theme(), Some("dark"));
+        let fs = &config.mcp["fs"];
+        assert_eq!(fs.command.as_ref().unwrap().program(), Some("npx"));
+        assert_eq!(fs.enabled, Some(false));
+
+        config.set_model(Some("anthropic/claude".to_string()));
+        let path = temp.path().join("opencode.jsonc");
+        write_config(&path, &config, OpenCodeConfig::FORMAT).unwrap();
+
+        let reloaded = OpenCodeConfig::load(temp.path()).unwrap().unwrap();
+        assert_eq!(reloaded.model(), Some("anthropic/claude"));
+        assert!(reloaded.extra.contains_key("keybinds"));
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("// comment\n  \"theme\": \"dark\","));
+    }
+
+    #[test]
+    fn write_config_keeps_goose_comments() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(GooseConfig::FILENAME);
+        std::fs::write(
+            &path,
+            "# Provider settings\nGOOSE_PROVIDER: openai\nGOOSE_MODEL: gpt-4o # fast\nextensions:\n  fs:\n    type: stdio\n    cmd: npx\n",
+        )
+        .unwrap();
+
+        let mut config = GooseConfig::load(temp.path()).unwrap().unwrap();
+        config.set_model(Some("o3".to_string()));
+        write_config(&path, &config, GooseConfig::FORMAT).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            written.starts_with(
+                "# Provider settings\nGOOSE_PROVIDER: openai\nGOOSE_MODEL: o3 # fast\n"
+            )
+        );
+        assert_eq!(
+            GooseConfig::load(temp.path()).unwrap().unwrap().model(),
+            Some("o3")
+        );
+    }
+
+    #[test]
+    fn parse_document_reads_jsonc_and_yaml() {
+        let json = parse_document("{\"a\": [1, 2,], // note\n}", ConfigFormat::Json).unwrap();
+        assert_eq!(json, serde_json::json!({"a": [1, 2]}));
+
+        let yaml = parse_document(
+            "defaults: &d\n  type: stdio\nextensions:\n  fs:\n    <<: *d\n    cmd: npx\n1: one\n",
+            ConfigFormat::Yaml,
+        )
+        .unwrap();
+        assert_eq!(yaml["extensions"]["fs"]["type"], "stdio");
+        assert_eq!(yaml["1"], "one");
+        assert_eq!(
+            parse_document("  \n", ConfigFormat::Yaml).unwrap(),
+            serde_json::Value::Null
+        );
+
+        let err = parse_document(
+            "extensions:\n  fs:\n    cmd: npx\n   bad: [\n",
+            ConfigFormat::Yaml,
+        )
+        .unwrap_err();
+        assert!(err.contains("line 4"), "{err}");
+    }
+
+    #[test]
+    fn opencode_model_falls_back_to_general_agent() {
+        let config: OpenCodeConfig =
+            serde_json::from_str(r#"{"agent": {"general": {"model": "gpt"}}}"#).unwrap();
+        assert_eq!(config.model(), Some("gpt"));
+    }
+
+    #[test]
+    fn goose_filters_builtin_extensions() {
+        let config: GooseConfig = serde_yaml::from_str(
+            "GOOSE_MODEL: gpt-4o\nextensions:\n  dev:\n    type: builtin\n  fs:\n    type: stdio\n    cmd: npx\n",
+        )
+        .unwrap();
+        assert_eq!(config.model(), Some("gpt-4o"));
+        assert!(!config.extensions["dev"].is_mcp());
+        assert!(config.extensions["fs"].is_mcp());
+    }
+
+    #[test]
+    fn mistyped_field_is_a_parse_error() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("settings.json"), r#"{"model": 42}"#).unwrap();
+        let err = ClaudeSettings::load(temp.path()).unwrap_err();
+        assert!(matches!(err, Error::ConfigParse { .. }));
+    }
+
+    #[test]
+    fn mistyped_mcp_server_does_not_fail_the_file() {
+        let config: ClaudeMcpConfig = serde_json::from_str(
+            r#"{"mcpServers": {
+                "bad": {"command": "srv", "env": {"PORT": 8080}},
+                "good": {"command": "npx", "args": ["-y", "fs"]}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(config.mcp_servers["good"].command.as_deref(), Some("npx"));
+        assert!(!config.mcp_servers.contains_key("bad"));
+        assert!(config.mcp_servers.invalid["bad"].error.contains("8080"));
+
+        let goose: GooseConfig = serde_yaml::from_str(
+            "extensions:\n  bad:\n    type: stdio\n    args: [[1]]\n  fs:\n    type: stdio\n    cmd: npx\n",
+        )
+        .unwrap();
+        assert_eq!(goose.extensions["fs"].cmd.as_deref(), Some("npx"));
+        assert!(goose.extensions.invalid.contains_key("bad"));
+    }
+
+    #[test]
+    fn write_config_sets_theme_and_keeps_invalid_servers() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(AmpSettings::FILENAME);
+        std::fs::write(
+            &path,
+            r#"{"amp.mcpServers": {"bad": {"command": 1}, "fs": {"command": "npx"}}}"#,
+        )
+        .unwrap();
+
+        let mut settings = AmpSettings::load(temp.path()).unwrap().unwrap();
+        settings.set_theme(Some("light".to_string()));
+        write_config(&path, &settings, AmpSettings::FORMAT).unwrap();
+
+        let reloaded = AmpSettings::load(temp.path()).unwrap().unwrap();
+        assert_eq!(reloaded.theme(), Some("light"));
+        assert_eq!(reloaded.mcp_servers["fs"].command.as_deref(), Some("npx"));
+        assert_eq!(reloaded.mcp_servers.invalid["bad"].raw["command"], 1);
+    }
+
+    #[test]
+    fn amp_reads_dotted_and_nested_model() {
+        let dotted: AmpSettings =
+            serde_json::from_str(r#"{"amp.model.default": "claude"}"#).unwrap();
+        assert_eq!(dotted.model(), Some("claude"));
+        let nested: AmpSettings = serde_json::from_str(r#"{"amp": {"model": "gpt"}}"#).unwrap();
+        assert_eq!(nested.model(), Some("gpt"));
+    }
+
+    #[test]
+    fn missing_file_loads_as_none() {
+        let temp = TempDir::new().unwrap();
+        assert!(GooseConfig::load(temp.path()).unwrap().is_none());
+    }
+}
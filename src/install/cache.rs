@@ -0,0 +1,231 @@
+//! Download cache for content that cannot change under the same name.
+//!
+//! OCI registry blobs are stored by digest, so an entry never needs
+//! revalidating. The cache lives in [`StatePaths::downloads_dir`], one file
+//! per entry named by the SHA-256 of its key, with hit and miss counts kept
+//! beside them for `bridle cache info`. Nothing evicts entries on its own;
+//! `bridle cache clean` does.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::{StatePaths, write_atomic};
+
+const STATS_FILE: &str = "stats.json";
+
+/// Cumulative lookups, kept in `stats.json`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// What [`DownloadCache::info`] reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheInfo {
+    pub path: PathBuf,
+    pub entries: usize,
+    pub size: u64,
+    #[serde(flatten)]
+    pub stats: CacheStats,
+}
+
+/// Entries removed by [`DownloadCache::clean`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Cleaned {
+    pub removed: usize,
+    pub freed: u64,
+}
+
+pub struct DownloadCache {
+    dir: PathBuf,
+}
+
+impl DownloadCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache in the state directory, or `None` if it cannot be found.
+    pub fn open() -> Option<Self> {
+        StatePaths::resolve()
+            .ok()
+            .map(|paths| Self::new(paths.downloads_dir()))
+    }
+
+    /// The content cached under `key`, counting the lookup as a hit or miss.
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(key);
+        let content = fs::read(&path).ok();
+        if content.is_some() {
+            // Reading is a use, so age-based cleaning keeps what is still needed.
+            let _ = fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|f| f.set_modified(SystemTime::now()));
+        }
+        self.record(content.is_some());
+        content
+    }
+
+    /// Stores `content` under `key`. A cache that cannot be written is skipped.
+    pub fn put(&self, key: &str, content: &[u8]) {
+        let _ = write_atomic(&self.entry_path(key), content);
+    }
+
+    pub fn info(&self) -> CacheInfo {
+        let entries = self.entries();
+        CacheInfo {
+            path: self.dir.clone(),
+            entries: entries.len(),
+            size: entries.iter().map(|e| e.size).sum(),
+            stats: self.stats(),
+        }
+    }
+
+    /// Removes entries last used before `older_than` ago, then the least
+    /// recently used until the rest fit in `max_size` bytes.
+    pub fn clean(&self, max_size: Option<u64>, older_than: Option<Duration>) -> Cleaned {
+        let mut entries = self.entries();
+        entries.sort_by_key(|e| e.modified);
+        let cutoff = older_than.and_then(|age| SystemTime::now().checked_sub(age));
+        let mut remaining: u64 = entries.iter().map(|e| e.size).sum();
+        let mut cleaned = Cleaned::default();
+        for entry in entries {
+            let expired = cutoff.is_some_and(|cutoff| entry.modified < cutoff);
+            let over = max_size.is_some_and(|max| remaining > max);
+            if (expired || over) && fs::remove_file(&entry.path).is_ok() {
+                remaining -= entry.size;
+                cleaned.removed += 1;
+                cleaned.freed += entry.size;
+            }
+        }
+        cleaned
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let hash: String = Sha256::digest(key.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        self.dir.join(hash)
+    }
+
+    fn entries(&self) -> Vec<Entry> {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.flatten()
+            .filter(|e| e.file_name() != STATS_FILE)
+            .filter_map(|e| {
+                let metadata = e.metadata().ok().filter(|m| m.is_file())?;
+                Some(Entry {
+                    path: e.path(),
+                    size: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                })
+            })
+            .collect()
+    }
+
+    fn stats(&self) -> CacheStats {
+        fs::read(self.dir.join(STATS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn record(&self, hit: bool) {
+        let mut stats = self.stats();
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        if let Ok(content) = serde_json::to_vec(&stats) {
+            let _ = write_atomic(&self.dir.join(STATS_FILE), content);
+        }
+    }
+}
+
+struct Entry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Parses an age such as `30d`, `12h`, `2w`, or `90m`; a bare number is days.
+pub fn parse_age(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let seconds = match unit.trim() {
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    number
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn counts_hits_and_cleans_oldest_first() {
+        let temp = TempDir::new().unwrap();
+        let cache = DownloadCache::new(temp.path());
+        assert_eq!(cache.get("sha256:aaa"), None);
+        cache.put("sha256:aaa", &[0; 100]);
+        cache.put("sha256:bbb", &[0; 50]);
+        assert_eq!(cache.get("sha256:aaa").map(|c| c.len()), Some(100));
+
+        let info = cache.info();
+        assert_eq!((info.entries, info.size), (2, 150));
+        assert_eq!(info.stats, CacheStats { hits: 1, misses: 1 });
+
+        let age = |key: &str| {
+            fs::File::options()
+                .write(true)
+                .open(cache.entry_path(key))
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(3600))
+                .unwrap();
+        };
+        age("sha256:bbb");
+        assert_eq!(
+            cache.clean(Some(120), None),
+            Cleaned {
+                removed: 1,
+                freed: 50
+            }
+        );
+        assert!(cache.get("sha256:aaa").is_some());
+        assert_eq!(cache.clean(None, Some(Duration::from_secs(60))).removed, 0);
+        age("sha256:aaa");
+        assert_eq!(cache.clean(None, Some(Duration::from_secs(60))).removed, 1);
+        assert_eq!(cache.info().entries, 0);
+    }
+
+    #[test]
+    fn parses_ages() {
+        assert_eq!(parse_age("30d"), Some(Duration::from_secs(30 * 86400)));
+        assert_eq!(parse_age("12h"), Some(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_age("2w"), Some(Duration::from_secs(14 * 86400)));
+        assert_eq!(parse_age("7"), Some(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_age("soon"), None);
+    }
+}
@@ -0,0 +1,86 @@
+//! Harnesses a skill declares it supports.
+//!
+//! A skill lists them under `harnesses` in its `SKILL.md` frontmatter, by
+//! bridle id or alias:
+//!
+//! ```yaml
+//! harnesses: [claude-code, opencode]
+//! ```
+//!
+//! A skill without the key is taken to work everywhere. Installing into a
+//! harness it does not list adds a note, or skips the skill with
+//! `--strict-compat`.
+
+use serde::Deserialize;
+
+use super::convert::split_frontmatter;
+use super::types::SkillInfo;
+use crate::harness::registry::parse_kind;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Declared {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct Frontmatter {
+    harnesses: Option<Declared>,
+}
+
+/// The harnesses listed under `harnesses` in `content`'s frontmatter.
+pub fn declared_harnesses(content: &str) -> Vec<String> {
+    let Some((yaml, _)) = split_frontmatter(content) else {
+        return Vec::new();
+    };
+    match serde_yaml::from_str::<Frontmatter>(yaml).map(|f| f.harnesses) {
+        Ok(Some(Declared::One(name))) => vec![name],
+        Ok(Some(Declared::Many(names))) => names,
+        _ => Vec::new(),
+    }
+}
+
+impl SkillInfo {
+    /// Whether the skill declares support for `harness_id`, or declares none.
+    pub fn supports(&self, harness_id: &str) -> bool {
+        self.harnesses.is_empty()
+            || self.harnesses.iter().any(|declared| {
+                declared == harness_id
+                    || parse_kind(declared).is_some_and(|kind| parse_kind(harness_id) == Some(kind))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(content: &str) -> SkillInfo {
+        SkillInfo {
+            name: "review".to_string(),
+            description: None,
+            path: "skills/review/SKILL.md".to_string(),
+            content: content.to_string(),
+            setup_script: None,
+            harnesses: declared_harnesses(content),
+        }
+    }
+
+    #[test]
+    fn reads_declared_harnesses_by_id_or_alias() {
+        let listed = skill("---\nname: review\nharnesses: [cc, opencode]\n---\n");
+        assert_eq!(listed.harnesses, ["cc", "opencode"]);
+        assert!(listed.supports("claude-code"));
+        assert!(listed.supports("opencode"));
+        assert!(!listed.supports("goose"));
+
+        let single = skill("---\nname: review\nharnesses: goose\n---\n");
+        assert!(single.supports("goose"));
+        assert!(!single.supports("opencode"));
+
+        let undeclared = skill("---\nname: review\n---\n");
+        assert!(undeclared.harnesses.is_empty());
+        assert!(undeclared.supports("amp-code"));
+    }
+}
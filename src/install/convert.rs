@@ -0,0 +1,236 @@
+//! Converting skills between harness formats.
+//!
+//! Every harness with skills reads a `SKILL.md` with YAML frontmatter, but
+//! they disagree on the details: OpenCode needs a lowercase-hyphenated
+//! `name` that matches the skill directory and a `description`, Claude Code
+//! understands keys such as `allowed-tools` and expands `$ARGUMENTS` in the
+//! body, and Goose has no skills at all. [`convert_skill`] rewrites a skill
+//! for a target harness and reports what does not carry over.
+
+use harness_locate::{HarnessKind, SkillCapabilities};
+
+use super::installer::{sanitize_name_for_opencode, transform_skill_for_opencode};
+use super::types::SkillInfo;
+
+/// Frontmatter keys only one harness acts on.
+const HARNESS_KEYS: &[(&str, HarnessKind)] = &[
+    ("allowed-tools", HarnessKind::ClaudeCode),
+    ("model", HarnessKind::ClaudeCode),
+    ("disable-model-invocation", HarnessKind::ClaudeCode),
+    ("user-invocable", HarnessKind::ClaudeCode),
+    ("argument-hint", HarnessKind::ClaudeCode),
+    ("context", HarnessKind::ClaudeCode),
+    ("agent", HarnessKind::ClaudeCode),
+    ("hooks", HarnessKind::ClaudeCode),
+    ("compatibility", HarnessKind::OpenCode),
+];
+
+/// Placeholders in a skill body that only one harness expands.
+const HARNESS_PLACEHOLDERS: &[(&str, HarnessKind)] = &[
+    ("$ARGUMENTS", HarnessKind::ClaudeCode),
+    ("${CLAUDE_PLUGIN_ROOT}", HarnessKind::ClaudeCode),
+    ("${CLAUDE_SKILL_DIR}", HarnessKind::ClaudeCode),
+];
+
+/// A skill rewritten for one harness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillConversion {
+    /// Directory name to install the skill under.
+    pub name: String,
+    /// `SKILL.md` content for the target harness.
+    pub content: String,
+    /// What was changed, or will not work, on the target harness.
+    pub notes: Vec<String>,
+}
+
+/// Rewrites `skill` for harness `kind`.
+///
+/// Returns `None` if the harness has no skills.
+pub fn convert_skill(skill: &SkillInfo, kind: HarnessKind) -> Option<SkillConversion> {
+    let caps = SkillCapabilities::for_kind(kind)?;
+    let mut notes = Vec::new();
+
+    let (name, content) = if caps.name_must_match_directory {
+        let sanitized = sanitize_name_for_opencode(&skill.name);
+        if sanitized != skill.name {
+            notes.push(format!("renamed to `{sanitized}` for {kind}"));
+        }
+        if caps.description_required && skill.description.is_none() {
+            notes.push(format!(
+                "added a placeholder description, which {kind} requires"
+            ));
+        }
+        let content = transform_skill_for_opencode(&skill.content, &sanitized);
+        (sanitized, content)
+    } else {
+        (skill.name.clone(), skill.content.clone())
+    };
+
+    for key in frontmatter_keys(&skill.content) {
+        let owner = HARNESS_KEYS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, o)| *o);
+        if let Some(owner) = owner.filter(|o| *o != kind) {
+            notes.push(format!(
+                "`{key}` is only used by {owner}; {kind} ignores it"
+            ));
+        }
+    }
+    for (placeholder, owner) in HARNESS_PLACEHOLDERS {
+        if *owner != kind && body(&skill.content).contains(placeholder) {
+            notes.push(format!(
+                "`{placeholder}` is only expanded by {owner}; {kind} leaves it as text"
+            ));
+        }
+    }
+
+    Some(SkillConversion {
+        name,
+        content,
+        notes,
+    })
+}
+
+/// `skill` under `name`, with the frontmatter `name` changed to match.
+pub fn rename_skill(skill: &SkillInfo, name: &str) -> SkillInfo {
+    let content = match split_frontmatter(&skill.content) {
+        Some((yaml, body)) => {
+            let mut lines: Vec<String> = yaml
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| match line.strip_prefix("name:") {
+                    Some(_) => format!("name: {name}"),
+                    None => line.to_string(),
+                })
+                .collect();
+            if !lines.iter().any(|line| line.starts_with("name:")) {
+                lines.insert(0, format!("name: {name}"));
+            }
+            format!("---\n{}\n---{body}", lines.join("\n"))
+        }
+        None => format!("---\nname: {name}\n---\n{}", skill.content),
+    };
+    SkillInfo {
+        name: name.to_string(),
+        content,
+        ..skill.clone()
+    }
+}
+
+/// Splits `content` into its YAML frontmatter and body, if it has frontmatter.
+pub(crate) fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let rest = content.trim_start().strip_prefix("---")?;
+    let end = rest.find("\n---")?;
+    Some((&rest[..end], &rest[end + 4..]))
+}
+
+fn body(content: &str) -> &str {
+    split_frontmatter(content).map_or(content, |(_, body)| body)
+}
+
+/// Top-level keys of `content`'s frontmatter, in order.
+fn frontmatter_keys(content: &str) -> Vec<String> {
+    let Some((yaml, _)) = split_frontmatter(content) else {
+        return Vec::new();
+    };
+    let Ok(serde_yaml::Value::Mapping(map)) = serde_yaml::from_str(yaml) else {
+        return Vec::new();
+    };
+    map.keys()
+        .filter_map(|k| k.as_str().map(String::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claude_skill() -> SkillInfo {
+        SkillInfo {
+            name: "Code Review".to_string(),
+            description: None,
+            path: "skills/code-review/SKILL.md".to_string(),
+            content: "---\nname: Code Review\nallowed-tools: Read, Grep\n---\nReview $ARGUMENTS.\n"
+                .to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn converts_claude_skill_for_opencode() {
+        let converted = convert_skill(&claude_skill(), HarnessKind::OpenCode).unwrap();
+
+        assert_eq!(converted.name, "code-review");
+        assert!(converted.content.contains("name: code-review"));
+        assert!(
+            converted
+                .content
+                .contains("description: Skill installed by Bridle")
+        );
+        assert_eq!(
+            converted.notes,
+            vec![
+                "renamed to `code-review` for OpenCode".to_string(),
+                "added a placeholder description, which OpenCode requires".to_string(),
+                "`allowed-tools` is only used by Claude Code; OpenCode ignores it".to_string(),
+                "`$ARGUMENTS` is only expanded by Claude Code; OpenCode leaves it as text"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_claude_skill_unchanged_for_claude_code() {
+        let skill = claude_skill();
+        let converted = convert_skill(&skill, HarnessKind::ClaudeCode).unwrap();
+
+        assert_eq!(converted.name, skill.name);
+        assert_eq!(converted.content, skill.content);
+        assert!(converted.notes.is_empty());
+    }
+
+    #[test]
+    fn reports_opencode_keys_for_claude_code() {
+        let skill = SkillInfo {
+            name: "lint".to_string(),
+            description: Some("Lints".to_string()),
+            path: "skill/lint/SKILL.md".to_string(),
+            content: "---\nname: lint\ndescription: Lints\ncompatibility: opencode\n---\nRun it.\n"
+                .to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let converted = convert_skill(&skill, HarnessKind::ClaudeCode).unwrap();
+
+        assert_eq!(
+            converted.notes,
+            vec!["`compatibility` is only used by OpenCode; Claude Code ignores it".to_string()]
+        );
+    }
+
+    #[test]
+    fn renames_skill_in_frontmatter() {
+        let renamed = rename_skill(&claude_skill(), "pr-review");
+        assert_eq!(renamed.name, "pr-review");
+        assert_eq!(
+            renamed.content,
+            "---\nname: pr-review\nallowed-tools: Read, Grep\n---\nReview $ARGUMENTS.\n"
+        );
+
+        let bare = SkillInfo {
+            content: "Just a body\n".to_string(),
+            ..claude_skill()
+        };
+        assert_eq!(
+            rename_skill(&bare, "notes").content,
+            "---\nname: notes\n---\nJust a body\n"
+        );
+    }
+
+    #[test]
+    fn goose_has_no_skills() {
+        assert!(convert_skill(&claude_skill(), HarnessKind::Goose).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-//! Skill discovery from GitHub repositories.
+//! Skill discovery from GitHub repositories and pulled bundles.
 //!
 //! Wraps the `skills-locate` crate to discover installable skills.
 
@@ -6,9 +6,12 @@ use std::collections::HashMap;
 
 use harness_locate::McpServer;
 use skills_locate::parse_mcp_json;
-use skills_locate::{GitHubRef, extract_file, fetch_bytes, list_files, parse_skill_descriptor};
+use skills_locate::{GitHubRef, extract_file, list_files, parse_skill_descriptor};
 use thiserror::Error;
 
+use super::compat::declared_harnesses;
+use super::hooks::SETUP_SCRIPT;
+use super::http::{HttpError, fetch_bytes};
 use super::types::{AgentInfo, CommandInfo, DiscoveryResult, SkillInfo, SourceInfo};
 
 #[derive(Debug, Error)]
@@ -19,6 +22,9 @@ pub enum DiscoveryError {
     #[error("Failed to fetch repository: {0}")]
     FetchError(#[source] skills_locate::Error),
 
+    #[error("Failed to download repository: {0}")]
+    Download(#[from] HttpError),
+
     #[error("No skills found in repository")]
     NoSkillsFound,
 }
@@ -34,15 +40,45 @@ pub fn discover_skills(url: &str) -> Result<DiscoveryResult, DiscoveryError> {
     };
 
     let archive_url = github_ref.archive_url();
-    let zip_bytes = fetch_bytes(&archive_url).map_err(DiscoveryError::FetchError)?;
+    let zip_bytes = fetch_bytes(&archive_url)?;
+    let paths = list_files(&zip_bytes, "").map_err(DiscoveryError::FetchError)?;
+
+    discover_in(
+        &paths,
+        |path| extract_file(&zip_bytes, path).ok(),
+        |path| normalize_archive_path(path, &github_ref),
+        source,
+    )
+}
 
-    let skill_paths = list_files(&zip_bytes, "SKILL.md").map_err(DiscoveryError::FetchError)?;
+/// Discovers components in files already in memory, keyed by relative path.
+pub fn discover_files(
+    files: &[(String, Vec<u8>)],
+    source: SourceInfo,
+) -> Result<DiscoveryResult, DiscoveryError> {
+    let paths: Vec<String> = files.iter().map(|(path, _)| path.clone()).collect();
+    let read = |path: &str| {
+        files
+            .iter()
+            .find(|(p, _)| p == path)
+            .and_then(|(_, content)| String::from_utf8(content.clone()).ok())
+    };
+    discover_in(&paths, read, str::to_string, source)
+}
+
+/// Finds skills, MCP servers, agents, and commands among `paths`.
+fn discover_in(
+    paths: &[String],
+    read: impl Fn(&str) -> Option<String>,
+    normalize: impl Fn(&str) -> String,
+    source: SourceInfo,
+) -> Result<DiscoveryResult, DiscoveryError> {
+    let with_suffix = |suffix: &'static str| paths.iter().filter(move |p| p.ends_with(suffix));
 
     let mut skills = Vec::new();
-    for path in skill_paths {
-        let content = match extract_file(&zip_bytes, &path) {
-            Ok(c) => c,
-            Err(_) => continue,
+    for path in with_suffix("SKILL.md") {
+        let Some(content) = read(path) else {
+            continue;
         };
 
         let descriptor = match parse_skill_descriptor(&content) {
@@ -50,21 +86,21 @@ pub fn discover_skills(url: &str) -> Result<DiscoveryResult, DiscoveryError> {
             Err(_) => continue,
         };
 
+        let script = format!("{}{SETUP_SCRIPT}", &path[..path.len() - "SKILL.md".len()]);
         skills.push(SkillInfo {
             name: descriptor.name,
             description: descriptor.description,
-            path: normalize_archive_path(&path, &github_ref),
+            path: normalize(path),
+            harnesses: declared_harnesses(&content),
             content,
+            setup_script: paths.contains(&script).then(|| read(&script)).flatten(),
         });
     }
 
-    let mcp_paths = list_files(&zip_bytes, ".mcp.json").map_err(DiscoveryError::FetchError)?;
-
     let mut mcp_servers: HashMap<String, McpServer> = HashMap::new();
-    for path in mcp_paths {
-        let content = match extract_file(&zip_bytes, &path) {
-            Ok(c) => c,
-            Err(_) => continue,
+    for path in with_suffix(".mcp.json") {
+        let Some(content) = read(path) else {
+            continue;
         };
 
         if let Ok(servers) = parse_mcp_json(&content) {
@@ -73,81 +109,72 @@ pub fn discover_skills(url: &str) -> Result<DiscoveryResult, DiscoveryError> {
     }
 
     // Discover agents from AGENT.md files (legacy format)
-    let agent_paths = list_files(&zip_bytes, "AGENT.md").map_err(DiscoveryError::FetchError)?;
-
     let mut agents = Vec::new();
-    for path in agent_paths {
-        let content = match extract_file(&zip_bytes, &path) {
-            Ok(c) => c,
-            Err(_) => continue,
+    for path in with_suffix("AGENT.md") {
+        let Some(content) = read(path) else {
+            continue;
         };
 
-        if let Some(agent) = parse_agent_frontmatter(&content, &path) {
+        if let Some(agent) = parse_agent_frontmatter(&content, path) {
             agents.push(AgentInfo {
                 name: agent.0,
                 description: agent.1,
-                path: normalize_archive_path(&path, &github_ref),
+                path: normalize(path),
                 content,
             });
         }
     }
 
     // Discover agents from */agents/*.md directories (claude-code format)
-    let all_md_paths = list_files(&zip_bytes, ".md").map_err(DiscoveryError::FetchError)?;
-    for path in &all_md_paths {
+    for path in with_suffix(".md") {
         if !is_in_agents_dir(path) {
             continue;
         }
-        let content = match extract_file(&zip_bytes, path) {
-            Ok(c) => c,
-            Err(_) => continue,
+        let Some(content) = read(path) else {
+            continue;
         };
 
         if let Some(agent) = parse_agent_frontmatter(&content, path) {
             agents.push(AgentInfo {
                 name: agent.0,
                 description: agent.1,
-                path: normalize_archive_path(path, &github_ref),
+                path: normalize(path),
                 content,
             });
         }
     }
 
     // Discover commands from COMMAND.md files (legacy format)
-    let command_paths = list_files(&zip_bytes, "COMMAND.md").map_err(DiscoveryError::FetchError)?;
-
     let mut commands = Vec::new();
-    for path in command_paths {
-        let content = match extract_file(&zip_bytes, &path) {
-            Ok(c) => c,
-            Err(_) => continue,
+    for path in with_suffix("COMMAND.md") {
+        let Some(content) = read(path) else {
+            continue;
         };
 
-        if let Some(cmd) = parse_command_frontmatter(&content, &path) {
+        if let Some(cmd) = parse_command_frontmatter(&content, path) {
             commands.push(CommandInfo {
                 name: cmd.0,
                 description: cmd.1,
-                path: normalize_archive_path(&path, &github_ref),
+                path: normalize(path),
                 content,
             });
         }
     }
 
     // Discover commands from */commands/*.md directories (claude-code format)
-    for path in &all_md_paths {
+    for path in with_suffix(".md") {
         if !is_in_commands_dir(path) {
             continue;
         }
-        let content = match extract_file(&zip_bytes, path) {
-            Ok(c) => c,
-            Err(_) => continue,
+        let Some(content) = read(path) else {
+            continue;
         };
 
         if let Some(cmd) = parse_command_frontmatter(&content, path) {
             commands.push(CommandInfo {
                 name: cmd.0,
                 description: cmd.1,
-                path: normalize_archive_path(path, &github_ref),
+                path: normalize(path),
                 content,
             });
         }
@@ -174,7 +201,8 @@ fn parse_command_frontmatter(content: &str, path: &str) -> Option<(String, Optio
     parse_yaml_frontmatter(content, filename_stem(path))
 }
 
-fn parse_yaml_frontmatter(
+/// Reads `name` and `description` from a Markdown file's YAML front matter.
+pub(crate) fn parse_yaml_frontmatter(
     content: &str,
     fallback_name: Option<&str>,
 ) -> Option<(String, Option<String>)> {
@@ -210,11 +238,15 @@ fn normalize_archive_path(archive_path: &str, github_ref: &GitHubRef) -> String
 }
 
 fn is_in_agents_dir(path: &str) -> bool {
-    path.contains("/agents/") && path.ends_with(".md") && !path.ends_with("AGENT.md")
+    (path.starts_with("agents/") || path.contains("/agents/"))
+        && path.ends_with(".md")
+        && !path.ends_with("AGENT.md")
 }
 
 fn is_in_commands_dir(path: &str) -> bool {
-    path.contains("/commands/") && path.ends_with(".md") && !path.ends_with("COMMAND.md")
+    (path.starts_with("commands/") || path.contains("/commands/"))
+        && path.ends_with(".md")
+        && !path.ends_with("COMMAND.md")
 }
 
 #[cfg(test)]
@@ -254,6 +286,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn discover_files_finds_top_level_components() {
+        let files = vec![
+            (
+                "skills/review/SKILL.md".to_string(),
+                b"---\nname: review\ndescription: Reviews\n---\nBody\n".to_vec(),
+            ),
+            (
+                "skills/review/install.sh".to_string(),
+                b"echo setup\n".to_vec(),
+            ),
+            (
+                "commands/ship.md".to_string(),
+                b"---\ndescription: Ships\n---\nShip it\n".to_vec(),
+            ),
+            ("agents/helper.md".to_string(), b"Helps".to_vec()),
+        ];
+        let source = SourceInfo {
+            owner: "ghcr.io".to_string(),
+            repo: "org/skills".to_string(),
+            git_ref: Some("v1".to_string()),
+        };
+        let discovery = discover_files(&files, source).unwrap();
+
+        assert_eq!(discovery.skills[0].name, "review");
+        assert_eq!(discovery.skills[0].path, "skills/review/SKILL.md");
+        assert_eq!(
+            discovery.skills[0].setup_script.as_deref(),
+            Some("echo setup\n")
+        );
+        assert_eq!(discovery.commands[0].name, "ship");
+        assert_eq!(discovery.agents[0].name, "helper");
+    }
+
     #[test]
     fn parse_mcp_wrapper_format() {
         let content = r#"{
@@ -0,0 +1,179 @@
+//! Environment variables a skill declares it needs.
+//!
+//! A skill lists them under `env` in its `SKILL.md` frontmatter, by name or
+//! with a description and whether the value is a secret:
+//!
+//! ```yaml
+//! env:
+//!   - JIRA_URL
+//!   - name: JIRA_TOKEN
+//!     description: API token from id.atlassian.com
+//!     secret: true
+//! ```
+//!
+//! Values are written where the harness reads environment variables for the
+//! tools it runs; for Claude Code that is the `env` table of `settings.json`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use harness_locate::HarnessKind;
+use serde::Deserialize;
+
+use super::convert::split_frontmatter;
+use crate::config::write_atomic;
+use crate::harness::edit;
+use crate::harness::schema::{ClaudeSettings, ConfigFormat};
+
+/// An environment variable declared in a skill's frontmatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: String,
+    pub description: Option<String>,
+    /// Whether the value belongs in the keyring rather than a config file.
+    pub secret: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Declared {
+    Name(String),
+    Full {
+        name: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        secret: bool,
+    },
+}
+
+#[derive(Deserialize)]
+struct Frontmatter {
+    #[serde(default)]
+    env: Vec<Declared>,
+}
+
+/// The variables declared under `env` in `content`'s frontmatter.
+pub fn required_env(content: &str) -> Vec<EnvVar> {
+    let Some((yaml, _)) = split_frontmatter(content) else {
+        return Vec::new();
+    };
+    let Ok(frontmatter) = serde_yaml::from_str::<Frontmatter>(yaml) else {
+        return Vec::new();
+    };
+    frontmatter
+        .env
+        .into_iter()
+        .map(|declared| match declared {
+            Declared::Name(name) => EnvVar {
+                name,
+                description: None,
+                secret: false,
+            },
+            Declared::Full {
+                name,
+                description,
+                secret,
+            } => EnvVar {
+                name,
+                description,
+                secret,
+            },
+        })
+        .collect()
+}
+
+/// Config file, relative to the harness directory, and the key of the table
+/// in it holding environment variables for `kind`'s tools.
+pub fn env_location(kind: HarnessKind) -> Option<(&'static str, &'static str)> {
+    match kind {
+        HarnessKind::ClaudeCode => Some((ClaudeSettings::FILENAME, "env")),
+        _ => None,
+    }
+}
+
+/// Sets `values` in the environment table of `kind`'s config file under
+/// `dir`, returning the file written, or `None` if the harness has no such
+/// table.
+pub fn write_env(
+    dir: &Path,
+    kind: HarnessKind,
+    values: &BTreeMap<String, String>,
+) -> Result<Option<PathBuf>, String> {
+    let Some((file, table)) = env_location(kind) else {
+        return Ok(None);
+    };
+    let path = dir.join(file);
+    let format = ConfigFormat::from_path(&path)
+        .ok_or_else(|| format!("unsupported config file {}", path.display()))?;
+    let mut content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("{}: {e}", path.display())),
+    };
+    for (name, value) in values {
+        let key = [table.to_string(), name.clone()];
+        content = edit::set(
+            &content,
+            format,
+            &key,
+            &serde_json::Value::String(value.clone()),
+        )
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+    write_atomic(&path, &content).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reads_declared_env_vars() {
+        let content = "---\nname: jira\nenv:\n  - JIRA_URL\n  - name: JIRA_TOKEN\n    description: API token\n    secret: true\n---\nBody\n";
+        assert_eq!(
+            required_env(content),
+            vec![
+                EnvVar {
+                    name: "JIRA_URL".to_string(),
+                    description: None,
+                    secret: false,
+                },
+                EnvVar {
+                    name: "JIRA_TOKEN".to_string(),
+                    description: Some("API token".to_string()),
+                    secret: true,
+                },
+            ]
+        );
+        assert!(required_env("---\nname: plain\n---\nBody\n").is_empty());
+        assert!(required_env("no frontmatter").is_empty());
+    }
+
+    #[test]
+    fn writes_claude_code_env_table() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("settings.json"),
+            r#"{"model": "opus", "env": {"KEEP": "1"}}"#,
+        )
+        .unwrap();
+        let values = BTreeMap::from([("JIRA_URL".to_string(), "https://jira".to_string())]);
+
+        let path = write_env(temp.path(), HarnessKind::ClaudeCode, &values)
+            .unwrap()
+            .unwrap();
+        let settings: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(settings["env"]["JIRA_URL"], "https://jira");
+        assert_eq!(settings["env"]["KEEP"], "1");
+        assert_eq!(settings["model"], "opus");
+
+        assert_eq!(
+            write_env(temp.path(), HarnessKind::OpenCode, &values).unwrap(),
+            None
+        );
+    }
+}
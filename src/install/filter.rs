@@ -0,0 +1,129 @@
+//! `--filter` and `--exclude` patterns for discovered components.
+//!
+//! Patterns are globs: `*` matches within one path segment, `**` across
+//! segments, and `?` one character. A pattern matches a component if it
+//! matches its name, or its path in the repository from any directory down,
+//! so `experimental/*` matches `skills/experimental/draft/SKILL.md`. Paths
+//! are taken without `SKILL.md` and without a `.md` extension.
+
+use std::path::Path;
+
+use super::types::DiscoveryResult;
+
+/// Components to keep: those matching any `include` pattern (or all, if
+/// there are none) and no `exclude` pattern.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl ComponentFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether a component named `name` at `path` passes the filter.
+    pub fn allows(&self, name: &str, path: &str) -> bool {
+        let candidates = candidates(name, path);
+        let matches = |pattern: &String| candidates.iter().any(|c| glob_match(pattern, c));
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Drops the components of `discovery` the filter does not allow.
+    pub fn apply(&self, discovery: &mut DiscoveryResult) {
+        if self.is_empty() {
+            return;
+        }
+        discovery.skills.retain(|s| self.allows(&s.name, &s.path));
+        discovery
+            .mcp_servers
+            .retain(|name, _| self.allows(name, ""));
+        discovery.agents.retain(|a| self.allows(&a.name, &a.path));
+        discovery.commands.retain(|c| self.allows(&c.name, &c.path));
+    }
+}
+
+/// `name`, and `path` from each of its directories down.
+fn candidates(name: &str, path: &str) -> Vec<String> {
+    let path = Path::new(path);
+    let path = if path.file_name().is_some_and(|f| f == "SKILL.md") {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+    let path = if path.extension().is_some_and(|e| e == "md") {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    };
+    let parts: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let mut candidates = vec![name.to_string()];
+    candidates.extend((0..parts.len()).map(|i| parts[i..].join("/")));
+    candidates
+}
+
+/// Whether `pattern` has glob wildcards, rather than naming one thing.
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Whether `text` matches the glob `pattern`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches_from(&pattern, &text)
+}
+
+fn matches_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            let rest = rest.strip_prefix(&['/']).unwrap_or(rest);
+            (0..=text.len()).any(|i| matches_from(rest, &text[i..]))
+        }
+        ['*', rest @ ..] => {
+            let segment = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=segment).any(|i| matches_from(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(text.first(), Some(&c) if c != '/') && matches_from(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && matches_from(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_within_and_across_segments() {
+        assert!(glob_match("pdf*", "pdf-extract"));
+        assert!(!glob_match("pdf*", "extract-pdf"));
+        assert!(glob_match("experimental/*", "experimental/draft"));
+        assert!(!glob_match("experimental/*", "experimental/a/b"));
+        assert!(glob_match("skills/**/draft", "skills/experimental/x/draft"));
+        assert!(glob_match("skills/**/draft", "skills/draft"));
+        assert!(glob_match("v?", "v2"));
+        assert!(!glob_match("v?", "v/"));
+    }
+
+    #[test]
+    fn filters_by_name_or_path() {
+        let filter = ComponentFilter {
+            include: vec!["pdf*".to_string(), "tools/*".to_string()],
+            exclude: vec!["experimental/*".to_string()],
+        };
+        assert!(filter.allows("pdf-extract", "skills/pdf-extract/SKILL.md"));
+        assert!(filter.allows("fmt", "skills/tools/fmt/SKILL.md"));
+        assert!(!filter.allows("pdf-draft", "skills/experimental/pdf-draft/SKILL.md"));
+        assert!(!filter.allows("lint", "skills/lint/SKILL.md"));
+        assert!(filter.allows("reviewer", "agents/tools/reviewer.md"));
+        assert!(ComponentFilter::default().allows("anything", ""));
+    }
+}
@@ -0,0 +1,196 @@
+//! Setup hooks: commands a skill runs once after it is installed, for
+//! example to fetch a model or a binary it needs.
+//!
+//! A skill either ships an `install.sh` next to its `SKILL.md`, or lists
+//! commands under `setup` in the frontmatter:
+//!
+//! ```yaml
+//! setup:
+//!   - curl -fsSLo model.bin https://example.com/model.bin
+//!   - chmod +x bin/tool
+//! ```
+//!
+//! Hooks never run on their own: the installer runs one only for skills in
+//! [`InstallOptions::run_setup`](super::InstallOptions::run_setup), which the
+//! CLI fills after the user confirms, or with `--allow-hooks`. The hook runs
+//! through `sh` in the installed skill's directory, with stdin closed, an
+//! environment reduced to [`PASSED_ENV`], and a [`TIMEOUT`].
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::convert::split_frontmatter;
+use super::types::SkillInfo;
+
+/// File holding a skill's setup script, next to `SKILL.md`.
+pub const SETUP_SCRIPT: &str = "install.sh";
+
+/// Environment variables passed through to a hook; everything else is cleared.
+const PASSED_ENV: [&str; 6] = ["PATH", "HOME", "USER", "LANG", "TERM", "TMPDIR"];
+
+/// Longest a hook may run before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Most output kept per stream; longer output keeps its end.
+const OUTPUT_LIMIT: usize = 16 * 1024;
+
+#[derive(Deserialize)]
+struct Frontmatter {
+    #[serde(default)]
+    setup: Vec<String>,
+}
+
+/// What running a hook produced.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookRun {
+    /// Exit code, or `None` if the hook was killed or could not start.
+    pub status: Option<i32>,
+    pub success: bool,
+    /// Combined stdout and stderr, trimmed to the last [`OUTPUT_LIMIT`] bytes of each.
+    pub output: String,
+}
+
+/// The shell script that sets `skill` up, if it has one: its `install.sh`,
+/// or else the `setup` commands of its frontmatter.
+pub fn setup_script(skill: &SkillInfo) -> Option<String> {
+    if let Some(script) = &skill.setup_script {
+        return Some(script.clone());
+    }
+    let (yaml, _) = split_frontmatter(&skill.content)?;
+    let steps = serde_yaml::from_str::<Frontmatter>(yaml).ok()?.setup;
+    if steps.is_empty() {
+        return None;
+    }
+    Some(format!("set -e\n{}\n", steps.join("\n")))
+}
+
+/// Runs `script` in `dir`, the installed skill's directory.
+pub fn run(script: &str, dir: &Path, skill: &str) -> HookRun {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(script)
+        .current_dir(dir)
+        .env_clear()
+        .envs(
+            PASSED_ENV
+                .iter()
+                .filter_map(|k| Some((k, std::env::var_os(k)?))),
+        )
+        .env("BRIDLE_SKILL_NAME", skill)
+        .env("BRIDLE_SKILL_DIR", dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return HookRun {
+                status: None,
+                success: false,
+                output: format!("failed to run sh: {e}"),
+            };
+        }
+    };
+
+    // Drain both pipes while waiting, so a chatty hook cannot block on a full pipe.
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .map(|stream| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut stream) = stream {
+                let _ = stream.read_to_end(&mut buf);
+            }
+            buf
+        })
+    });
+
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(_) => break None,
+        }
+    };
+
+    let mut output = String::new();
+    for reader in readers {
+        let buf = reader.join().unwrap_or_default();
+        let start = buf.len().saturating_sub(OUTPUT_LIMIT);
+        output.push_str(&String::from_utf8_lossy(&buf[start..]));
+    }
+    if status.is_none() {
+        output.push_str(&format!("\nkilled after {}s", TIMEOUT.as_secs()));
+    }
+    HookRun {
+        status: status.and_then(|s| s.code()),
+        success: status.is_some_and(|s| s.success()),
+        output: output.trim_end().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill(content: &str, setup_script: Option<&str>) -> SkillInfo {
+        SkillInfo {
+            name: "model".to_string(),
+            description: None,
+            path: "skills/model/SKILL.md".to_string(),
+            content: content.to_string(),
+            setup_script: setup_script.map(str::to_string),
+            harnesses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn prefers_install_sh_over_declared_steps() {
+        let declared = "---\nname: model\nsetup:\n  - echo one\n  - echo two\n---\nBody\n";
+        assert_eq!(
+            setup_script(&skill(declared, None)).as_deref(),
+            Some("set -e\necho one\necho two\n")
+        );
+        assert_eq!(
+            setup_script(&skill(declared, Some("echo script"))).as_deref(),
+            Some("echo script")
+        );
+        assert_eq!(setup_script(&skill("---\nname: model\n---\n", None)), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_in_skill_dir_with_reduced_env() {
+        let temp = TempDir::new().unwrap();
+        unsafe { std::env::set_var("BRIDLE_HOOK_TEST_LEAK", "leaked") };
+        let run = run(
+            "echo \"$BRIDLE_SKILL_NAME:${BRIDLE_HOOK_TEST_LEAK:-clean}\"; touch fetched; echo oops >&2; exit 3",
+            temp.path(),
+            "model",
+        );
+        assert_eq!(run.status, Some(3));
+        assert!(!run.success);
+        assert_eq!(run.output, "model:clean\noops");
+        assert!(temp.path().join("fetched").exists());
+    }
+}
@@ -0,0 +1,288 @@
+//! HTTP agents for everything bridle downloads or uploads.
+//!
+//! Proxies come from the environment: `ALL_PROXY`, `HTTPS_PROXY`, or
+//! `HTTP_PROXY`, skipping hosts listed in `NO_PROXY`. `settings.ca_bundle`
+//! swaps the built-in root certificates for those in a PEM file, for
+//! networks behind a TLS-intercepting proxy, and `settings.insecure` (or
+//! `--insecure`) stops verifying certificates at all. Every request is
+//! limited to `settings.http_timeout_secs`, and [`RetryPolicy`] retries
+//! transient failures `settings.http_retries` times with doubling backoff.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Once;
+use std::thread;
+use std::time::Duration;
+
+use thiserror::Error;
+use ureq::Agent;
+use ureq::config::ConfigBuilder;
+use ureq::tls::{Certificate, PemItem, RootCerts, TlsConfig, parse_pem};
+use ureq::typestate::AgentScope;
+
+use crate::config::{BridleConfig, Settings};
+use crate::harness::expand_home;
+
+/// Largest response [`fetch_bytes`] reads.
+const MAX_BODY: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("cannot use CA bundle {path}: {reason}")]
+    CaBundle { path: String, reason: String },
+
+    #[error("request to {url} failed: {}", describe(.source))]
+    Request {
+        url: String,
+        #[source]
+        source: ureq::Error,
+    },
+
+    #[error("HTTP {status} for {url}")]
+    Status { status: u16, url: String },
+}
+
+impl HttpError {
+    fn request(url: &str) -> impl FnOnce(ureq::Error) -> Self + '_ {
+        move |source| Self::Request {
+            url: url.to_string(),
+            source,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request { source, .. } => is_transient(source),
+            Self::Status { status, .. } => *status >= 500,
+            Self::CaBundle { .. } => false,
+        }
+    }
+}
+
+/// Whether `error` is worth retrying: a timeout, a failed connection, or an
+/// I/O error on the way.
+pub fn is_transient(error: &ureq::Error) -> bool {
+    matches!(
+        error,
+        ureq::Error::Io(_) | ureq::Error::Timeout(_) | ureq::Error::ConnectionFailed
+    )
+}
+
+/// What went wrong with a request, telling timeouts, unresolvable hosts, and
+/// refused connections apart.
+pub fn describe(error: &ureq::Error) -> String {
+    match error {
+        ureq::Error::Timeout(_) => {
+            "timed out; raise `http_timeout_secs` if the network is slow".to_string()
+        }
+        ureq::Error::Io(e) if e.kind() == ErrorKind::TimedOut => {
+            describe(&ureq::Error::Timeout(ureq::Timeout::Global))
+        }
+        ureq::Error::HostNotFound => "could not resolve host".to_string(),
+        ureq::Error::Io(e) if e.to_string().contains("lookup address") => {
+            format!("could not resolve host ({e})")
+        }
+        ureq::Error::ConnectionFailed => "could not connect".to_string(),
+        ureq::Error::Io(e) if e.kind() == ErrorKind::ConnectionRefused => {
+            "connection refused".to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+/// How many times, and how long apart, failed requests are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    /// Wait before the first retry; each later one waits twice as long.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// The policy in `settings.http_retries` and `settings.http_backoff_ms`.
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            retries: settings.http_retries,
+            backoff: Duration::from_millis(settings.http_backoff_ms),
+        }
+    }
+
+    /// Calls `attempt` until it succeeds, fails with an error `retryable`
+    /// rejects, or has been retried [`retries`](Self::retries) times.
+    pub fn run<T, E>(
+        &self,
+        mut attempt: impl FnMut() -> Result<T, E>,
+        retryable: impl Fn(&E) -> bool,
+    ) -> Result<T, E> {
+        let mut delay = self.backoff;
+        for _ in 0..self.retries {
+            match attempt() {
+                Err(e) if retryable(&e) => {
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+        attempt()
+    }
+}
+
+fn settings() -> Settings {
+    BridleConfig::load()
+        .map(|config| config.settings().clone())
+        .unwrap_or_default()
+}
+
+/// Retry policy from the current settings.
+pub fn retry_policy() -> RetryPolicy {
+    RetryPolicy::from_settings(&settings())
+}
+
+/// Agent configuration honouring the proxy, certificate, and timeout settings.
+///
+/// # Errors
+/// Returns [`HttpError::CaBundle`] if `settings.ca_bundle` cannot be read or
+/// holds no certificates.
+pub fn agent_config() -> Result<ConfigBuilder<AgentScope>, HttpError> {
+    let settings = settings();
+    let timeout = Some(settings.http_timeout_secs)
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs);
+    Ok(Agent::config_builder()
+        .tls_config(tls_config(&settings)?)
+        .timeout_global(timeout))
+}
+
+fn tls_config(settings: &Settings) -> Result<TlsConfig, HttpError> {
+    let mut tls = TlsConfig::builder();
+    if let Some(path) = &settings.ca_bundle {
+        tls = tls.root_certs(RootCerts::new_with_certs(&load_certificates(path)?));
+    }
+    if settings.insecure {
+        static WARNED: Once = Once::new();
+        WARNED.call_once(|| eprintln!("Warning: TLS certificate verification is disabled"));
+        tls = tls.disable_verification(true);
+    }
+    Ok(tls.build())
+}
+
+fn load_certificates(path: &str) -> Result<Vec<Certificate<'static>>, HttpError> {
+    let invalid = |reason: String| HttpError::CaBundle {
+        path: path.to_string(),
+        reason,
+    };
+    let resolved = expand_home(Path::new(path)).map_err(|e| invalid(e.to_string()))?;
+    let pem = fs::read(resolved).map_err(|e| invalid(e.to_string()))?;
+    let mut certificates = Vec::new();
+    for item in parse_pem(&pem) {
+        if let PemItem::Certificate(certificate) = item.map_err(|e| invalid(e.to_string()))? {
+            certificates.push(certificate);
+        }
+    }
+    if certificates.is_empty() {
+        return Err(invalid("no certificates found".to_string()));
+    }
+    Ok(certificates)
+}
+
+/// Downloads `url`, retrying timeouts, connection failures, and server errors.
+pub fn fetch_bytes(url: &str) -> Result<Vec<u8>, HttpError> {
+    let agent: Agent = agent_config()?.http_status_as_error(false).build().into();
+    retry_policy().run(|| try_fetch(&agent, url), HttpError::is_retryable)
+}
+
+fn try_fetch(agent: &Agent, url: &str) -> Result<Vec<u8>, HttpError> {
+    let mut response = agent.get(url).call().map_err(HttpError::request(url))?;
+    let status = response.status().as_u16();
+    if status >= 400 {
+        return Err(HttpError::Status {
+            status,
+            url: url.to_string(),
+        });
+    }
+    response
+        .body_mut()
+        .with_config()
+        .limit(MAX_BODY)
+        .read_to_vec()
+        .map_err(HttpError::request(url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    #[test]
+    fn retries_only_transient_failures_with_doubling_backoff() {
+        let policy = RetryPolicy {
+            retries: 2,
+            backoff: Duration::from_millis(1),
+        };
+        let calls = Cell::new(0);
+        let result: Result<(), HttpError> = policy.run(
+            || {
+                calls.set(calls.get() + 1);
+                Err(HttpError::Status {
+                    status: 503,
+                    url: "https://example.com".to_string(),
+                })
+            },
+            HttpError::is_retryable,
+        );
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let _ = policy.run(
+            || {
+                calls.set(calls.get() + 1);
+                Err::<(), _>(HttpError::Status {
+                    status: 404,
+                    url: "https://example.com".to_string(),
+                })
+            },
+            HttpError::is_retryable,
+        );
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn describes_timeouts_and_dns_failures_differently() {
+        let timeout = HttpError::request("https://example.com/a.zip")(ureq::Error::Timeout(
+            ureq::Timeout::Global,
+        ));
+        assert_eq!(
+            timeout.to_string(),
+            "request to https://example.com/a.zip failed: timed out; raise `http_timeout_secs` if the network is slow"
+        );
+        assert_eq!(
+            describe(&ureq::Error::HostNotFound),
+            "could not resolve host"
+        );
+    }
+
+    #[test]
+    fn ca_bundle_must_hold_a_certificate() {
+        let temp = TempDir::new().unwrap();
+        let bundle = temp.path().join("ca.pem");
+        let path = bundle.to_string_lossy().into_owned();
+        fs::write(
+            &bundle,
+            "-----BEGIN CERTIFICATE-----\nMAA=\n-----END CERTIFICATE-----\n",
+        )
+        .unwrap();
+        assert_eq!(load_certificates(&path).unwrap().len(), 1);
+
+        fs::write(&bundle, "not a certificate\n").unwrap();
+        assert!(matches!(
+            load_certificates(&path),
+            Err(HttpError::CaBundle { .. })
+        ));
+        let missing = temp.path().join("missing.pem");
+        assert!(load_certificates(&missing.to_string_lossy()).is_err());
+    }
+}
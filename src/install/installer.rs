@@ -1,5 +1,6 @@
 //! Skill installation executor.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -7,13 +8,21 @@ use thiserror::Error;
 
 use harness_locate::{Harness, HarnessKind, Scope};
 
+use super::convert::convert_skill;
+use super::env;
+use super::hooks;
+use super::lock::{Lockfile, lock_path, slash_path};
 use super::manifest::{InstallManifest, ManifestEntry, manifest_path};
+use super::progress::InstallEvent;
 use super::types::{
     AgentInfo, CommandInfo, ComponentType, InstallFailure, InstallOptions, InstallReport,
     InstallSkip, InstallSuccess, InstallTarget, SkillInfo, SkipReason, SourceInfo,
 };
-use crate::config::BridleConfig;
+use crate::config::{
+    BridleConfig, Policy, SECRET_SCHEME, SecretResolver, SecretStore, Violation, write_atomic,
+};
 use crate::harness::HarnessConfig;
+use crate::harness::registry::parse_kind;
 
 #[derive(Debug, Error)]
 pub enum InstallError {
@@ -31,6 +40,32 @@ pub enum InstallError {
 
     #[error("Invalid component name: {0}")]
     InvalidComponentName(String),
+
+    #[error("{0} does not support skills")]
+    SkillsUnsupported(String),
+
+    #[error("Failed to configure environment: {0}")]
+    EnvConfig(String),
+
+    #[error("Failed to record the file for undo: {0}")]
+    Record(String),
+
+    #[error("{0}")]
+    Policy(String),
+}
+
+/// [`write_atomic`], failing with an [`InstallError`].
+fn write_file(path: &Path, content: impl AsRef<[u8]>) -> Result<(), InstallError> {
+    write_atomic(path, content).map_err(|e| match e {
+        crate::error::Error::Io(e) => InstallError::WriteFile(e),
+        other => InstallError::WriteFile(std::io::Error::other(other.to_string())),
+    })
+}
+
+/// Checks a `/`-separated directory below the skills directory, such as
+/// `team/review`, for parts that are not plain names.
+pub fn validate_subdir(subdir: &str) -> Result<(), InstallError> {
+    subdir.split('/').try_for_each(validate_component_name)
 }
 
 fn validate_component_name(name: &str) -> Result<(), InstallError> {
@@ -46,16 +81,6 @@ fn validate_component_name(name: &str) -> Result<(), InstallError> {
     Ok(())
 }
 
-fn parse_harness_kind(id: &str) -> Option<HarnessKind> {
-    match id {
-        "claude-code" | "claude" | "cc" => Some(HarnessKind::ClaudeCode),
-        "opencode" | "oc" => Some(HarnessKind::OpenCode),
-        "goose" => Some(HarnessKind::Goose),
-        "amp-code" | "amp" | "ampcode" => Some(HarnessKind::AmpCode),
-        _ => None,
-    }
-}
-
 pub fn sanitize_name_for_opencode(name: &str) -> String {
     name.to_lowercase()
         .chars()
@@ -196,11 +221,24 @@ const CANONICAL_AGENTS_DIR: &str = "agents";
 /// Canonical directory name for commands in profile storage.
 const CANONICAL_COMMANDS_DIR: &str = "commands";
 
+/// Every violation of `policy` in installing `name` from `source`.
+fn component_violations(
+    policy: &Policy,
+    name: Option<&str>,
+    source: Option<&SourceInfo>,
+) -> Vec<Violation> {
+    name.and_then(|name| policy.check_skill(name))
+        .into_iter()
+        .chain(source.and_then(|source| policy.check_source(source)))
+        .collect()
+}
+
 pub fn install_skill(
     skill: &SkillInfo,
     target: &InstallTarget,
     options: &InstallOptions,
 ) -> InstallResult {
+    options.check_policy(|policy| component_violations(policy, Some(&skill.name), None))?;
     let profiles_dir = BridleConfig::profiles_dir().map_err(|_| InstallError::ProfileNotFound {
         harness: target.harness.clone(),
         profile: target.profile.as_str().to_string(),
@@ -209,6 +247,21 @@ pub fn install_skill(
     install_skill_to_dir(skill, target, options, &profiles_dir)
 }
 
+/// Installs `skill`, recording `source` in the profile's install manifest.
+pub fn install_skill_with_source(
+    skill: &SkillInfo,
+    target: &InstallTarget,
+    options: &InstallOptions,
+    source: Option<&SourceInfo>,
+) -> InstallResult {
+    options.check_policy(|policy| component_violations(policy, Some(&skill.name), source))?;
+    let profiles_dir = BridleConfig::profiles_dir().map_err(|_| InstallError::ProfileNotFound {
+        harness: target.harness.clone(),
+        profile: target.profile.as_str().to_string(),
+    })?;
+    install_skill_to_dir_with_source(skill, target, options, &profiles_dir, source)
+}
+
 fn install_skill_to_dir(
     skill: &SkillInfo,
     target: &InstallTarget,
@@ -238,17 +291,39 @@ fn install_skill_to_dir_with_source(
         });
     }
 
-    // For OpenCode, sanitize skill name and content before writing to profile
-    // This ensures consistency between profile and harness (both use sanitized names)
-    let kind = parse_harness_kind(&target.harness);
-    let (skill_name, skill_content) = if matches!(kind, Some(HarnessKind::OpenCode)) {
-        let sanitized = sanitize_name_for_opencode(&skill.name);
-        let transformed = transform_skill_for_opencode(&skill.content, &sanitized);
-        (sanitized, transformed)
-    } else {
-        (skill.name.clone(), skill.content.clone())
+    if options.strict_compat && !skill.supports(&target.harness) {
+        return Ok(InstallOutcome::Skipped(InstallSkip {
+            skill: skill.name.clone(),
+            target: target.clone(),
+            reason: SkipReason::Incompatible,
+        }));
+    }
+
+    // Convert for the target harness before writing to the profile, so the
+    // profile and the live config hold the same name and content.
+    let (skill_name, skill_content, mut notes) = match parse_kind(&target.harness) {
+        Some(kind) => {
+            let converted = convert_skill(skill, kind)
+                .ok_or_else(|| InstallError::SkillsUnsupported(target.harness.clone()))?;
+            (converted.name, converted.content, converted.notes)
+        }
+        None => (skill.name.clone(), skill.content.clone(), Vec::new()),
     };
+    if !skill.supports(&target.harness) {
+        notes.push(format!(
+            "only declares support for {}",
+            skill.harnesses.join(", ")
+        ));
+    }
 
+    // Skills installed into a subdirectory go by their path below the skills directory.
+    let skill_name = match &options.subdir {
+        Some(subdir) => {
+            validate_subdir(subdir)?;
+            format!("{subdir}/{skill_name}")
+        }
+        None => skill_name,
+    };
     let skill_dir = profile_dir.join("skills").join(&skill_name);
     let skill_path = skill_dir.join("SKILL.md");
 
@@ -260,11 +335,25 @@ fn install_skill_to_dir_with_source(
         }));
     }
 
+    options.before_write(&skill_path)?;
     fs::create_dir_all(&skill_dir).map_err(InstallError::CreateDir)?;
-    fs::write(&skill_path, &skill_content).map_err(InstallError::WriteFile)?;
+    write_file(&skill_path, &skill_content)?;
+    update_lock(
+        &profile_dir,
+        &skill_path,
+        skill_content.as_bytes(),
+        source,
+        options,
+    )?;
 
     if let Some(source_info) = source {
-        update_manifest(&profile_dir, ComponentType::Skill, &skill_name, source_info);
+        update_manifest(
+            &profile_dir,
+            ComponentType::Skill,
+            &skill_name,
+            source_info,
+            options,
+        )?;
     }
 
     let skill_for_harness = SkillInfo {
@@ -272,20 +361,129 @@ fn install_skill_to_dir_with_source(
         description: skill.description.clone(),
         path: skill.path.clone(),
         content: skill_content,
+        setup_script: None,
+        harnesses: skill.harnesses.clone(),
     };
-    let harness_path = write_to_harness_if_active(target, &skill_for_harness)?;
+    let harness_path = write_to_harness_if_active(target, &skill_for_harness, options)?;
+    let (env, env_notes) = configure_env(&skill.content, target, options, &profile_dir)?;
+    notes.extend(env_notes);
+
+    // Run where the harness will look for what the hook fetches: the live
+    // copy when the profile is active, which the next save brings back.
+    let setup = hooks::setup_script(skill).and_then(|script| {
+        if !options.run_setup.contains(&skill.name) {
+            notes.push("setup hook not run; approve it or pass --allow-hooks".to_string());
+            return None;
+        }
+        let dir = harness_path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(&skill_dir);
+        Some(hooks::run(&script, dir, &skill_name))
+    });
 
+    report_written(
+        options,
+        target,
+        "skill",
+        &skill_name,
+        &skill_path,
+        harness_path.as_deref(),
+    );
     Ok(InstallOutcome::Installed(InstallSuccess {
         skill: skill_name,
         target: target.clone(),
         profile_path: skill_path,
         harness_path,
+        notes,
+        env,
+        setup,
     }))
 }
 
+/// Writes the values in `options.env` for the variables `content` declares
+/// to the profile and, when it is active, the live config.
+///
+/// Returns the names written, and notes for variables left unset.
+fn configure_env(
+    content: &str,
+    target: &InstallTarget,
+    options: &InstallOptions,
+    profile_dir: &Path,
+) -> Result<(Vec<String>, Vec<String>), InstallError> {
+    let required = env::required_env(content);
+    if required.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let (set, unset): (Vec<_>, Vec<_>) = required
+        .into_iter()
+        .map(|var| var.name)
+        .partition(|name| options.env.contains_key(name));
+
+    let Some(kind) = parse_kind(&target.harness).filter(|k| env::env_location(*k).is_some()) else {
+        let names: Vec<_> = set.iter().chain(&unset).cloned().collect();
+        return Ok((
+            Vec::new(),
+            vec![format!(
+                "{} has no config for environment variables; export {} in your shell",
+                target.harness,
+                names.join(", ")
+            )],
+        ));
+    };
+    let mut notes = Vec::new();
+    if !unset.is_empty() {
+        notes.push(format!("not set: {}", unset.join(", ")));
+    }
+    if set.is_empty() {
+        return Ok((set, notes));
+    }
+
+    let values: BTreeMap<String, String> = set
+        .iter()
+        .map(|name| (name.clone(), options.env[name].clone()))
+        .collect();
+    if let Some((file, _)) = env::env_location(kind) {
+        options.before_write(&profile_dir.join(file))?;
+    }
+    env::write_env(profile_dir, kind, &values).map_err(InstallError::EnvConfig)?;
+
+    let config = BridleConfig::load().ok();
+    let is_active = config
+        .as_ref()
+        .and_then(|c| c.active_profile_for(&target.harness))
+        .is_some_and(|active| active == target.profile.as_str());
+    if let (true, Some(config)) = (is_active, config) {
+        let harness = Harness::locate(kind)
+            .map_err(|_| InstallError::HarnessNotFound(target.harness.clone()))?;
+        let config_dir = harness
+            .config_dir()
+            .map_err(|_| InstallError::HarnessNotFound(target.harness.clone()))?;
+        // The live config holds the secret itself, as it would after a switch.
+        let resolver = SecretResolver::new(&config);
+        let mut resolved = BTreeMap::new();
+        for (name, value) in values {
+            let value = match value.strip_prefix(SECRET_SCHEME) {
+                Some(secret) => resolver
+                    .get(secret)
+                    .map_err(|e| InstallError::EnvConfig(e.to_string()))?
+                    .unwrap_or(value),
+                None => value,
+            };
+            resolved.insert(name, value);
+        }
+        if let Some((file, _)) = env::env_location(kind) {
+            options.before_write(&config_dir.join(file))?;
+        }
+        env::write_env(&config_dir, kind, &resolved).map_err(InstallError::EnvConfig)?;
+    }
+    Ok((set, notes))
+}
+
 fn write_to_harness_if_active(
     target: &InstallTarget,
     skill: &SkillInfo,
+    options: &InstallOptions,
 ) -> Result<Option<PathBuf>, InstallError> {
     let config = BridleConfig::load().ok();
     let is_active = config
@@ -298,7 +496,7 @@ fn write_to_harness_if_active(
         return Ok(None);
     }
 
-    let kind = parse_harness_kind(&target.harness)
+    let kind = parse_kind(&target.harness)
         .ok_or_else(|| InstallError::HarnessNotFound(target.harness.clone()))?;
     let harness =
         Harness::locate(kind).map_err(|_| InstallError::HarnessNotFound(target.harness.clone()))?;
@@ -314,18 +512,12 @@ fn write_to_harness_if_active(
                 .map(|d| d.join("skills"))
                 .unwrap_or_default()
         });
-    let (skill_dir_name, content) = if matches!(kind, HarnessKind::OpenCode) {
-        let sanitized = sanitize_name_for_opencode(&skill.name);
-        let transformed = transform_skill_for_opencode(&skill.content, &sanitized);
-        (sanitized, transformed)
-    } else {
-        (skill.name.clone(), skill.content.clone())
-    };
-    let harness_skill_dir = skills_dir.join(&skill_dir_name);
+    let harness_skill_dir = skills_dir.join(&skill.name);
     let harness_skill_path = harness_skill_dir.join("SKILL.md");
 
+    options.before_write(&harness_skill_path)?;
     fs::create_dir_all(&harness_skill_dir).map_err(InstallError::CreateDir)?;
-    fs::write(&harness_skill_path, &content).map_err(InstallError::WriteFile)?;
+    write_file(&harness_skill_path, &skill.content)?;
 
     Ok(Some(harness_skill_path))
 }
@@ -333,6 +525,7 @@ fn write_to_harness_if_active(
 fn write_agent_to_harness_if_active(
     target: &InstallTarget,
     agent: &AgentInfo,
+    options: &InstallOptions,
 ) -> Result<Option<PathBuf>, InstallError> {
     let config = BridleConfig::load().ok();
     let is_active = config
@@ -345,7 +538,7 @@ fn write_agent_to_harness_if_active(
         return Ok(None);
     }
 
-    let kind = parse_harness_kind(&target.harness)
+    let kind = parse_kind(&target.harness)
         .ok_or_else(|| InstallError::HarnessNotFound(target.harness.clone()))?;
     let harness =
         Harness::locate(kind).map_err(|_| InstallError::HarnessNotFound(target.harness.clone()))?;
@@ -355,6 +548,7 @@ fn write_agent_to_harness_if_active(
         return Ok(None);
     };
     let harness_agent_path = agents_resource.path.join(format!("{}.md", &agent.name));
+    options.before_write(&harness_agent_path)?;
 
     if let Some(parent) = harness_agent_path.parent() {
         fs::create_dir_all(parent).map_err(InstallError::CreateDir)?;
@@ -365,7 +559,7 @@ fn write_agent_to_harness_if_active(
     } else {
         agent.content.clone()
     };
-    fs::write(&harness_agent_path, &content).map_err(InstallError::WriteFile)?;
+    write_file(&harness_agent_path, &content)?;
 
     Ok(Some(harness_agent_path))
 }
@@ -373,6 +567,7 @@ fn write_agent_to_harness_if_active(
 fn write_command_to_harness_if_active(
     target: &InstallTarget,
     command: &CommandInfo,
+    options: &InstallOptions,
 ) -> Result<Option<PathBuf>, InstallError> {
     let config = BridleConfig::load().ok();
     let is_active = config
@@ -385,7 +580,7 @@ fn write_command_to_harness_if_active(
         return Ok(None);
     }
 
-    let kind = parse_harness_kind(&target.harness)
+    let kind = parse_kind(&target.harness)
         .ok_or_else(|| InstallError::HarnessNotFound(target.harness.clone()))?;
     let harness =
         Harness::locate(kind).map_err(|_| InstallError::HarnessNotFound(target.harness.clone()))?;
@@ -394,11 +589,12 @@ fn write_command_to_harness_if_active(
         return Ok(None);
     };
     let harness_command_path = commands_resource.path.join(format!("{}.md", &command.name));
+    options.before_write(&harness_command_path)?;
 
     if let Some(parent) = harness_command_path.parent() {
         fs::create_dir_all(parent).map_err(InstallError::CreateDir)?;
     }
-    fs::write(&harness_command_path, &command.content).map_err(InstallError::WriteFile)?;
+    write_file(&harness_command_path, &command.content)?;
 
     Ok(Some(harness_command_path))
 }
@@ -408,8 +604,10 @@ fn update_manifest(
     component_type: ComponentType,
     name: &str,
     source: &SourceInfo,
-) {
+    options: &InstallOptions,
+) -> Result<(), InstallError> {
     let manifest_file = manifest_path(profile_dir);
+    options.before_write(&manifest_file)?;
     let mut manifest = InstallManifest::load(&manifest_file).unwrap_or_default();
 
     manifest.add_entry(ManifestEntry {
@@ -420,6 +618,46 @@ fn update_manifest(
     });
 
     let _ = manifest.save(&manifest_file);
+    Ok(())
+}
+
+/// Tells the observer in `options` about the files written for a component.
+pub(super) fn report_written(
+    options: &InstallOptions,
+    target: &InstallTarget,
+    component: &'static str,
+    name: &str,
+    profile_path: &Path,
+    harness_path: Option<&Path>,
+) {
+    for path in std::iter::once(profile_path).chain(harness_path) {
+        options.emit(InstallEvent::FileWritten {
+            harness: target.harness.clone(),
+            profile: target.profile.as_str().to_string(),
+            component,
+            name: name.to_string(),
+            path: path.to_path_buf(),
+        });
+    }
+}
+
+/// Records the file written at `path` in the profile's `bridle.lock`.
+fn update_lock(
+    profile_dir: &Path,
+    path: &Path,
+    content: &[u8],
+    source: Option<&SourceInfo>,
+    options: &InstallOptions,
+) -> Result<(), InstallError> {
+    let Ok(relative) = path.strip_prefix(profile_dir) else {
+        return Ok(());
+    };
+    let lock_file = lock_path(profile_dir);
+    options.before_write(&lock_file)?;
+    let mut lock = Lockfile::load(&lock_file).unwrap_or_default();
+    lock.record(&slash_path(relative), content, source);
+    let _ = lock.save(&lock_file);
+    Ok(())
 }
 
 pub enum InstallOutcome {
@@ -450,12 +688,13 @@ pub fn install_agent_to_dir(
     install_agent_to_dir_with_source(agent, target, options, profiles_dir, None)
 }
 
-fn install_agent_with_source(
+pub fn install_agent_with_source(
     agent: &AgentInfo,
     target: &InstallTarget,
     options: &InstallOptions,
     source: Option<&SourceInfo>,
 ) -> InstallResult {
+    options.check_policy(|policy| component_violations(policy, None, source))?;
     let profiles_dir = BridleConfig::profiles_dir().map_err(|_| InstallError::ProfileNotFound {
         harness: target.harness.clone(),
         profile: target.profile.as_str().to_string(),
@@ -494,20 +733,45 @@ fn install_agent_to_dir_with_source(
         }));
     }
 
+    options.before_write(&agent_path)?;
     fs::create_dir_all(&agents_dir).map_err(InstallError::CreateDir)?;
-    fs::write(&agent_path, &agent.content).map_err(InstallError::WriteFile)?;
+    write_file(&agent_path, &agent.content)?;
+    update_lock(
+        &profile_dir,
+        &agent_path,
+        agent.content.as_bytes(),
+        source,
+        options,
+    )?;
 
     if let Some(source_info) = source {
-        update_manifest(&profile_dir, ComponentType::Agent, &agent.name, source_info);
+        update_manifest(
+            &profile_dir,
+            ComponentType::Agent,
+            &agent.name,
+            source_info,
+            options,
+        )?;
     }
 
-    let harness_path = write_agent_to_harness_if_active(target, agent)?;
+    let harness_path = write_agent_to_harness_if_active(target, agent, options)?;
+    report_written(
+        options,
+        target,
+        "agent",
+        &agent.name,
+        &agent_path,
+        harness_path.as_deref(),
+    );
 
     Ok(InstallOutcome::Installed(InstallSuccess {
         skill: agent.name.clone(),
         target: target.clone(),
         profile_path: agent_path,
         harness_path,
+        notes: Vec::new(),
+        env: Vec::new(),
+        setup: None,
     }))
 }
 
@@ -532,12 +796,13 @@ pub fn install_command_to_dir(
     install_command_to_dir_with_source(command, target, options, profiles_dir, None)
 }
 
-fn install_command_with_source(
+pub fn install_command_with_source(
     command: &CommandInfo,
     target: &InstallTarget,
     options: &InstallOptions,
     source: Option<&SourceInfo>,
 ) -> InstallResult {
+    options.check_policy(|policy| component_violations(policy, None, source))?;
     let profiles_dir = BridleConfig::profiles_dir().map_err(|_| InstallError::ProfileNotFound {
         harness: target.harness.clone(),
         profile: target.profile.as_str().to_string(),
@@ -576,8 +841,16 @@ fn install_command_to_dir_with_source(
         }));
     }
 
+    options.before_write(&command_path)?;
     fs::create_dir_all(&commands_dir).map_err(InstallError::CreateDir)?;
-    fs::write(&command_path, &command.content).map_err(InstallError::WriteFile)?;
+    write_file(&command_path, &command.content)?;
+    update_lock(
+        &profile_dir,
+        &command_path,
+        command.content.as_bytes(),
+        source,
+        options,
+    )?;
 
     if let Some(source_info) = source {
         update_manifest(
@@ -585,16 +858,28 @@ fn install_command_to_dir_with_source(
             ComponentType::Command,
             &command.name,
             source_info,
-        );
+            options,
+        )?;
     }
 
-    let harness_path = write_command_to_harness_if_active(target, command)?;
+    let harness_path = write_command_to_harness_if_active(target, command, options)?;
+    report_written(
+        options,
+        target,
+        "command",
+        &command.name,
+        &command_path,
+        harness_path.as_deref(),
+    );
 
     Ok(InstallOutcome::Installed(InstallSuccess {
         skill: command.name.clone(),
         target: target.clone(),
         profile_path: command_path,
         harness_path,
+        notes: Vec::new(),
+        env: Vec::new(),
+        setup: None,
     }))
 }
 
@@ -602,13 +887,14 @@ pub fn install_skills(
     skills: &[SkillInfo],
     target: &InstallTarget,
     options: &InstallOptions,
+    source: Option<&SourceInfo>,
 ) -> InstallReport {
     let mut installed = Vec::new();
     let mut skipped = Vec::new();
     let mut errors = Vec::new();
 
     for skill in skills {
-        match install_skill(skill, target, options) {
+        match install_skill_with_source(skill, target, options, source) {
             Ok(InstallOutcome::Installed(success)) => installed.push(success),
             Ok(InstallOutcome::Skipped(skip)) => skipped.push(skip),
             Err(e) => errors.push(InstallFailure {
@@ -655,6 +941,8 @@ mod tests {
             description: Some("A test skill".to_string()),
             path: "skills/my-skill/SKILL.md".to_string(),
             content: "# My Skill\n\nContent here".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
         };
 
         let result =
@@ -675,6 +963,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn install_into_subdir_nests_skill_directory() {
+        let (temp, target, profiles_dir) = setup_test_env();
+        let skill = SkillInfo {
+            name: "review".to_string(),
+            description: Some("Reviews code".to_string()),
+            path: "skills/review/SKILL.md".to_string(),
+            content: "---\nname: review\ndescription: Reviews code\n---\nBody".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let options = InstallOptions {
+            subdir: Some("team/backend".to_string()),
+            ..Default::default()
+        };
+
+        let Ok(InstallOutcome::Installed(success)) =
+            install_skill_to_dir(&skill, &target, &options, &profiles_dir)
+        else {
+            panic!("skill was not installed");
+        };
+        assert_eq!(success.skill, "team/backend/review");
+        assert_eq!(
+            success.profile_path,
+            temp.path()
+                .join("profiles/opencode/test/skills/team/backend/review/SKILL.md")
+        );
+
+        let escaping = InstallOptions {
+            subdir: Some("../outside".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            install_skill_to_dir(&skill, &target, &escaping, &profiles_dir),
+            Err(InstallError::InvalidComponentName(_))
+        ));
+    }
+
+    #[test]
+    fn install_notes_or_skips_undeclared_harness() {
+        let (_temp, target, profiles_dir) = setup_test_env();
+        let skill = SkillInfo {
+            name: "review".to_string(),
+            description: Some("Reviews code".to_string()),
+            path: "skills/review/SKILL.md".to_string(),
+            content:
+                "---\nname: review\ndescription: Reviews code\nharnesses: [claude-code]\n---\n"
+                    .to_string(),
+            setup_script: None,
+            harnesses: vec!["claude-code".to_string()],
+        };
+
+        let strict = InstallOptions {
+            strict_compat: true,
+            ..Default::default()
+        };
+        let Ok(InstallOutcome::Skipped(skip)) =
+            install_skill_to_dir(&skill, &target, &strict, &profiles_dir)
+        else {
+            panic!("skill was not skipped");
+        };
+        assert!(matches!(skip.reason, SkipReason::Incompatible));
+
+        let Ok(InstallOutcome::Installed(success)) =
+            install_skill_to_dir(&skill, &target, &InstallOptions::default(), &profiles_dir)
+        else {
+            panic!("skill was not installed");
+        };
+        assert!(
+            success
+                .notes
+                .contains(&"only declares support for claude-code".to_string())
+        );
+    }
+
     #[test]
     fn install_skips_existing_without_force() {
         let (temp, target, profiles_dir) = setup_test_env();
@@ -688,6 +1051,8 @@ mod tests {
             description: None,
             path: "skills/existing/SKILL.md".to_string(),
             content: "new content".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
         };
 
         let result =
@@ -708,12 +1073,17 @@ mod tests {
             description: None,
             path: "skills/existing/SKILL.md".to_string(),
             content: "new content".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
         };
 
         let result = install_skill_to_dir(
             &skill,
             &target,
-            &InstallOptions { force: true },
+            &InstallOptions {
+                force: true,
+                ..Default::default()
+            },
             &profiles_dir,
         );
         assert!(matches!(result, Ok(InstallOutcome::Installed(_))));
@@ -729,6 +1099,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn install_over_a_deduped_skill_leaves_other_profiles_alone() {
+        let (temp, target, profiles_dir) = setup_test_env();
+
+        let skill_dir = temp.path().join("profiles/opencode/test/skills/existing");
+        let other_dir = temp.path().join("profiles/opencode/other/skills/existing");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::create_dir_all(&other_dir).unwrap();
+        fs::write(other_dir.join("SKILL.md"), "shared content").unwrap();
+        fs::hard_link(other_dir.join("SKILL.md"), skill_dir.join("SKILL.md")).unwrap();
+
+        let skill = SkillInfo {
+            name: "existing".to_string(),
+            description: None,
+            path: "skills/existing/SKILL.md".to_string(),
+            content: "new content".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let options = InstallOptions {
+            force: true,
+            ..Default::default()
+        };
+        let result = install_skill_to_dir(&skill, &target, &options, &profiles_dir);
+        assert!(matches!(result, Ok(InstallOutcome::Installed(_))));
+
+        let installed = fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+        assert!(installed.contains("new content"));
+        assert_eq!(
+            fs::read_to_string(other_dir.join("SKILL.md")).unwrap(),
+            "shared content"
+        );
+    }
+
     #[test]
     fn install_rejects_invalid_skill_names() {
         let (_temp, target, profiles_dir) = setup_test_env();
@@ -740,6 +1144,8 @@ mod tests {
                 description: None,
                 path: String::new(),
                 content: "content".to_string(),
+                setup_script: None,
+                harnesses: Vec::new(),
             };
             let result =
                 install_skill_to_dir(&skill, &target, &InstallOptions::default(), &profiles_dir);
@@ -750,6 +1156,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn install_refuses_components_the_policy_bans() {
+        let options = InstallOptions::default();
+        options
+            .policy
+            .set(Some(Policy {
+                banned_skills: vec!["yolo".to_string()],
+                banned_sources: vec!["untrusted-org/*".to_string()],
+                location: "policy.toml".to_string(),
+                ..Default::default()
+            }))
+            .unwrap();
+        let target = InstallTarget {
+            harness: "opencode".to_string(),
+            profile: ProfileName::new("test").unwrap(),
+        };
+        let skill = |name: &str| SkillInfo {
+            name: name.to_string(),
+            description: None,
+            path: String::new(),
+            content: "content".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let untrusted = SourceInfo {
+            owner: "untrusted-org".to_string(),
+            repo: "tools".to_string(),
+            git_ref: None,
+        };
+
+        for refused in [
+            install_skill(&skill("yolo"), &target, &options),
+            install_skill_with_source(&skill("review"), &target, &options, Some(&untrusted)),
+        ] {
+            assert!(matches!(refused, Err(InstallError::Policy(_))));
+        }
+    }
+
     #[test]
     fn install_returns_error_for_missing_profile() {
         let temp = TempDir::new().unwrap();
@@ -766,6 +1210,8 @@ mod tests {
             description: None,
             path: "skills/skill/SKILL.md".to_string(),
             content: "content".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
         };
 
         let result =
@@ -833,6 +1279,8 @@ mod tests {
             description: Some("A skill with spaces".to_string()),
             path: "skills/Hook Development/SKILL.md".to_string(),
             content: "---\nname: Hook Development\ndescription: Test\n---\n# Content".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
         };
 
         let result =
@@ -857,4 +1305,41 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn install_writes_declared_env_to_claude_code_settings() {
+        let temp = TempDir::new().unwrap();
+        let profiles_dir = temp.path().join("profiles");
+        let profile_dir = profiles_dir.join("claude-code").join("test");
+        fs::create_dir_all(&profile_dir).unwrap();
+        let target = InstallTarget {
+            harness: "claude-code".to_string(),
+            profile: ProfileName::new("test").unwrap(),
+        };
+        let skill = SkillInfo {
+            name: "jira".to_string(),
+            description: Some("Jira".to_string()),
+            path: "skills/jira/SKILL.md".to_string(),
+            content: "---\nname: jira\ndescription: Jira\nenv:\n  - JIRA_URL\n  - JIRA_TOKEN\n---\nBody\n".to_string(),
+            setup_script: None,
+            harnesses: Vec::new(),
+        };
+        let options = InstallOptions {
+            force: false,
+            env: BTreeMap::from([("JIRA_URL".to_string(), "https://jira".to_string())]),
+            ..Default::default()
+        };
+
+        let Ok(InstallOutcome::Installed(success)) =
+            install_skill_to_dir(&skill, &target, &options, &profiles_dir)
+        else {
+            panic!("skill was not installed");
+        };
+        assert_eq!(success.env, vec!["JIRA_URL"]);
+        assert!(success.notes.iter().any(|n| n.contains("JIRA_TOKEN")));
+        let settings: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(profile_dir.join("settings.json")).unwrap())
+                .unwrap();
+        assert_eq!(settings["env"]["JIRA_URL"], "https://jira");
+    }
 }
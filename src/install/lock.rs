@@ -0,0 +1,224 @@
+//! `bridle.lock`: what `bridle install` wrote into a profile.
+//!
+//! Each profile directory gets a lockfile listing every skill, agent, and
+//! command file bridle installed there, by path in the profile, with a
+//! SHA-256 of its content and the source it came from. `bridle verify`
+//! hashes the files again to find ones edited, deleted, or added since.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use super::types::SourceInfo;
+
+/// File name of the lockfile in a profile directory.
+pub const LOCK_FILENAME: &str = "bridle.lock";
+
+const HEADER: &str = "# Written by `bridle install`; check with `bridle verify`.\n";
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("Failed to read lockfile: {0}")]
+    Read(#[source] std::io::Error),
+
+    #[error("Failed to write lockfile: {0}")]
+    Write(#[source] std::io::Error),
+
+    #[error("Failed to parse lockfile: {0}")]
+    Parse(#[source] toml::de::Error),
+
+    #[error("Failed to serialize lockfile: {0}")]
+    Serialize(#[source] toml::ser::Error),
+}
+
+/// One installed file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedArtifact {
+    /// Path below the profile directory, with `/` separators.
+    pub path: String,
+    pub sha256: String,
+    /// `owner/repo` it was installed from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(rename = "artifact", default)]
+    pub artifacts: Vec<LockedArtifact>,
+}
+
+impl Lockfile {
+    pub fn load(path: &Path) -> Result<Self, LockError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(LockError::Read)?;
+        toml::from_str(&content).map_err(LockError::Parse)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), LockError> {
+        let content = toml::to_string(self).map_err(LockError::Serialize)?;
+        fs::write(path, format!("{HEADER}\n{content}")).map_err(LockError::Write)
+    }
+
+    /// Records `content` as installed at `path`, replacing an earlier entry.
+    pub fn record(&mut self, path: &str, content: &[u8], source: Option<&SourceInfo>) {
+        self.artifacts.retain(|a| a.path != path);
+        self.artifacts.push(LockedArtifact {
+            path: path.to_string(),
+            sha256: sha256(content),
+            source: source.map(|s| format!("{}/{}", s.owner, s.repo)),
+            git_ref: source.and_then(|s| s.git_ref.clone()),
+        });
+        self.artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    /// Drops the entries for `path` and the files below it.
+    pub fn remove(&mut self, path: &str) {
+        let dir = format!("{path}/");
+        self.artifacts
+            .retain(|a| a.path != path && !a.path.starts_with(&dir));
+    }
+}
+
+pub fn lock_path(profile_dir: &Path) -> PathBuf {
+    profile_dir.join(LOCK_FILENAME)
+}
+
+/// How an installed file differs from the lockfile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Drift {
+    /// Its content no longer matches the recorded hash.
+    Modified,
+    /// It is in the lockfile but not in the profile.
+    Missing,
+    /// It is in the profile but not in the lockfile.
+    Extraneous,
+}
+
+/// Compares the skill, agent, and command files in `profile_dir` with
+/// `lock`, returning the paths that differ, sorted.
+pub fn verify(profile_dir: &Path, lock: &Lockfile) -> Vec<(String, Drift)> {
+    let mut drift = Vec::new();
+    for artifact in &lock.artifacts {
+        match fs::read(profile_dir.join(&artifact.path)) {
+            Ok(content) if sha256(&content) != artifact.sha256 => {
+                drift.push((artifact.path.clone(), Drift::Modified));
+            }
+            Ok(_) => {}
+            Err(_) => drift.push((artifact.path.clone(), Drift::Missing)),
+        }
+    }
+    for path in installed_files(profile_dir) {
+        if !lock.artifacts.iter().any(|a| a.path == path) {
+            drift.push((path, Drift::Extraneous));
+        }
+    }
+    drift.sort();
+    drift
+}
+
+/// Paths of the files `bridle install` writes: `SKILL.md` at any depth
+/// below `skills/`, and `.md` files in `agents/` and `commands/`.
+fn installed_files(profile_dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut dirs = vec![PathBuf::from("skills")];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(profile_dir.join(&dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let relative = dir.join(entry.file_name());
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                dirs.push(relative);
+            } else if entry.file_name() == "SKILL.md" {
+                files.push(slash_path(&relative));
+            }
+        }
+    }
+    for dir in ["agents", "commands"] {
+        let Ok(entries) = fs::read_dir(profile_dir.join(dir)) else {
+            continue;
+        };
+        files.extend(
+            entries
+                .flatten()
+                .map(|entry| Path::new(dir).join(entry.file_name()))
+                .filter(|path| path.extension().is_some_and(|e| e == "md"))
+                .map(|path| slash_path(&path)),
+        );
+    }
+    files
+}
+
+/// `path` below the profile directory as stored in the lockfile.
+pub fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub(crate) fn sha256(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reports_modified_missing_and_extraneous_files() {
+        let temp = TempDir::new().unwrap();
+        let profile = temp.path();
+        let source = SourceInfo {
+            owner: "org".to_string(),
+            repo: "skills".to_string(),
+            git_ref: Some("v1".to_string()),
+        };
+        let mut lock = Lockfile::default();
+        for (path, content) in [
+            ("skills/review/SKILL.md", "review"),
+            ("skills/team/lint/SKILL.md", "lint"),
+            ("agents/helper.md", "helper"),
+            ("commands/ship.md", "ship"),
+        ] {
+            fs::create_dir_all(profile.join(path).parent().unwrap()).unwrap();
+            fs::write(profile.join(path), content).unwrap();
+            lock.record(path, content.as_bytes(), Some(&source));
+        }
+        lock.save(&lock_path(profile)).unwrap();
+        let lock = Lockfile::load(&lock_path(profile)).unwrap();
+        assert_eq!(lock.artifacts[0].source.as_deref(), Some("org/skills"));
+        assert!(verify(profile, &lock).is_empty());
+
+        fs::write(profile.join("skills/team/lint/SKILL.md"), "edited").unwrap();
+        fs::remove_file(profile.join("commands/ship.md")).unwrap();
+        fs::create_dir_all(profile.join("skills/extra")).unwrap();
+        fs::write(profile.join("skills/extra/SKILL.md"), "extra").unwrap();
+
+        assert_eq!(
+            verify(profile, &lock),
+            [
+                ("commands/ship.md".to_string(), Drift::Missing),
+                ("skills/extra/SKILL.md".to_string(), Drift::Extraneous),
+                ("skills/team/lint/SKILL.md".to_string(), Drift::Modified),
+            ]
+        );
+
+        let mut lock = lock;
+        lock.remove("skills/team");
+        assert_eq!(lock.artifacts.len(), 3);
+    }
+}
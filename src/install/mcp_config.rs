@@ -2,22 +2,20 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use harness_locate::HarnessKind;
 
-use crate::config::jsonc::strip_jsonc_comments;
+use crate::harness::edit;
+use crate::harness::schema::{ConfigFormat, GOOSE_MCP_TYPES, parse_document};
 
 #[derive(Debug, thiserror::Error)]
 pub enum McpConfigError {
     #[error("Failed to read config file: {0}")]
     Read(#[from] std::io::Error),
 
-    #[error("Failed to parse JSON: {0}")]
-    JsonParse(#[from] serde_json::Error),
-
-    #[error("Failed to parse YAML: {0}")]
-    YamlParse(#[from] serde_yaml::Error),
+    #[error("Failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
 
     #[error("Failed to write config: {0}")]
     Write(String),
@@ -46,17 +44,14 @@ pub fn read_mcp_config(
         return Ok(HashMap::new());
     }
 
-    let parsed: serde_json::Value = match kind {
-        HarnessKind::Goose => {
-            let yaml: serde_yaml::Value = serde_yaml::from_str(&content)?;
-            serde_json::to_value(yaml)?
-        }
-        HarnessKind::OpenCode => {
-            let stripped = strip_jsonc_comments(&content);
-            serde_json::from_str(&stripped)?
-        }
-        _ => serde_json::from_str(&content)?,
+    let format = match kind {
+        HarnessKind::Goose => ConfigFormat::Yaml,
+        _ => ConfigFormat::Jsonc,
     };
+    let parsed = parse_document(&content, format).map_err(|message| McpConfigError::Parse {
+        path: config_path.to_path_buf(),
+        message,
+    })?;
 
     let key = get_mcp_key(kind);
     let mcp_section = parsed.get(key).and_then(|v| v.as_object());
@@ -67,7 +62,7 @@ pub fn read_mcp_config(
             for (name, value) in obj {
                 if kind == HarnessKind::Goose {
                     if let Some(ext_type) = value.get("type").and_then(|t| t.as_str()) {
-                        if !["stdio", "sse", "http", "streamable_http"].contains(&ext_type) {
+                        if !GOOSE_MCP_TYPES.contains(&ext_type) {
                             continue;
                         }
                     } else {
@@ -82,195 +77,45 @@ pub fn read_mcp_config(
     }
 }
 
+/// Adds `servers` to the MCP config at `config_path`, replacing servers of
+/// the same name. Only the changed entries are rewritten, so comments and
+/// the rest of the file stay as they are.
 pub fn write_mcp_config(
     kind: HarnessKind,
     config_path: &Path,
     servers: &HashMap<String, serde_json::Value>,
 ) -> Result<(), McpConfigError> {
-    if kind == HarnessKind::Goose {
-        return write_goose_yaml_preserving_comments(config_path, servers);
-    }
-
+    let format = match kind {
+        HarnessKind::OpenCode => ConfigFormat::Jsonc,
+        HarnessKind::Goose => ConfigFormat::Yaml,
+        _ => ConfigFormat::Json,
+    };
     let key = get_mcp_key(kind);
 
-    let mut existing: serde_json::Value = if config_path.exists() {
-        let content = fs::read_to_string(config_path)?;
-        if content.trim().is_empty() {
-            serde_json::json!({})
-        } else {
-            match kind {
-                HarnessKind::OpenCode => {
-                    let stripped = strip_jsonc_comments(&content);
-                    serde_json::from_str(&stripped)?
-                }
-                _ => serde_json::from_str(&content)?,
-            }
-        }
+    let mut content = if config_path.exists() {
+        fs::read_to_string(config_path)?
     } else {
-        serde_json::json!({})
+        String::new()
     };
-
-    let mcp_section = existing
-        .as_object_mut()
-        .ok_or_else(|| McpConfigError::Write("Config root is not an object".to_string()))?
-        .entry(key)
-        .or_insert_with(|| serde_json::json!({}));
-
-    let mcp_obj = mcp_section
-        .as_object_mut()
-        .ok_or_else(|| McpConfigError::Write(format!("{} section is not an object", key)))?;
-
-    for (name, value) in servers {
-        mcp_obj.insert(name.clone(), value.clone());
-    }
-
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent)?;
+    let mut names: Vec<&String> = servers.keys().collect();
+    names.sort();
+    for name in names {
+        content = edit::set(
+            &content,
+            format,
+            &[key.to_string(), name.clone()],
+            &servers[name],
+        )
+        .map_err(McpConfigError::Write)?;
     }
 
-    let output = serde_json::to_string_pretty(&existing)?;
-    fs::write(config_path, output)?;
-    Ok(())
-}
-
-fn write_goose_yaml_preserving_comments(
-    config_path: &Path,
-    servers: &HashMap<String, serde_json::Value>,
-) -> Result<(), McpConfigError> {
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent)?;
     }
-
-    let content = if config_path.exists() {
-        fs::read_to_string(config_path)?
-    } else {
-        String::new()
-    };
-
-    let mut output = if content.trim().is_empty() {
-        String::new()
-    } else {
-        content.clone()
-    };
-
-    for (name, value) in servers {
-        if mcp_entry_exists_in_yaml(&output, name) {
-            continue;
-        }
-        let yaml_entry = format_goose_mcp_entry(name, value);
-        output = insert_into_extensions_section(&output, &yaml_entry);
-    }
-
-    fs::write(config_path, output)?;
+    fs::write(config_path, content)?;
     Ok(())
 }
 
-fn mcp_entry_exists_in_yaml(content: &str, name: &str) -> bool {
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with(&format!("{}:", name))
-            || trimmed.starts_with(&format!("\"{}\":", name))
-        {
-            return true;
-        }
-    }
-    false
-}
-
-fn format_goose_mcp_entry(name: &str, value: &serde_json::Value) -> String {
-    let mut lines = vec![format!("  {}:", name)];
-
-    if let Some(obj) = value.as_object() {
-        for (k, v) in obj {
-            match v {
-                serde_json::Value::String(s) => {
-                    lines.push(format!("    {}: {}", k, s));
-                }
-                serde_json::Value::Array(arr) => {
-                    let items: Vec<String> = arr
-                        .iter()
-                        .filter_map(|item| item.as_str().map(|s| format!("\"{}\"", s)))
-                        .collect();
-                    lines.push(format!("    {}: [{}]", k, items.join(", ")));
-                }
-                serde_json::Value::Bool(b) => {
-                    lines.push(format!("    {}: {}", k, b));
-                }
-                serde_json::Value::Number(n) => {
-                    lines.push(format!("    {}: {}", k, n));
-                }
-                serde_json::Value::Object(map) => {
-                    lines.push(format!("    {}:", k));
-                    for (inner_k, inner_v) in map {
-                        if let Some(s) = inner_v.as_str() {
-                            lines.push(format!("      {}: {}", inner_k, s));
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
-    lines.join("\n")
-}
-
-fn insert_into_extensions_section(content: &str, entry: &str) -> String {
-    if content.trim().is_empty() {
-        return format!("extensions:\n{}\n", entry);
-    }
-
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result = Vec::new();
-    let mut found_extensions = false;
-    let mut inserted = false;
-    let mut i = 0;
-
-    while i < lines.len() {
-        let line = lines[i];
-        result.push(line.to_string());
-
-        if line.trim() == "extensions:" || line.trim().starts_with("extensions:") {
-            found_extensions = true;
-            i += 1;
-
-            while i < lines.len() {
-                let next_line = lines[i];
-                let is_indented = next_line.starts_with("  ") || next_line.starts_with("\t");
-                let is_empty = next_line.trim().is_empty();
-                let is_comment = next_line.trim().starts_with('#');
-
-                if is_indented || is_empty || is_comment {
-                    result.push(next_line.to_string());
-                    i += 1;
-                } else {
-                    break;
-                }
-            }
-
-            result.push(entry.to_string());
-            inserted = true;
-            continue;
-        }
-        i += 1;
-    }
-
-    if !found_extensions {
-        if !result.is_empty() && !result.last().map(|s| s.is_empty()).unwrap_or(true) {
-            result.push(String::new());
-        }
-        result.push("extensions:".to_string());
-        result.push(entry.to_string());
-        inserted = true;
-    }
-
-    let mut output = result.join("\n");
-    if !output.ends_with('\n') && inserted {
-        output.push('\n');
-    }
-    output
-}
-
 pub fn mcp_exists(
     kind: HarnessKind,
     config_path: &Path,
@@ -533,6 +378,41 @@ GOOSE_PROVIDER: anthropic
         assert!(content.contains("new-mcp"), "New MCP added");
     }
 
+    #[test]
+    fn write_replaces_servers_in_place() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.yaml");
+        fs::write(
+            &path,
+            "extensions:\n  # from the team\n  docs:\n    type: stdio\n    cmd: old-command\n",
+        )
+        .unwrap();
+        let mut servers = HashMap::new();
+        servers.insert(
+            "docs".to_string(),
+            serde_json::json!({"type": "sse", "uri": "https://docs.example.com"}),
+        );
+        write_mcp_config(HarnessKind::Goose, &path, &servers).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("# from the team"));
+        assert!(!content.contains("old-command"));
+        assert_eq!(
+            read_mcp_config(HarnessKind::Goose, &path).unwrap()["docs"]["uri"],
+            "https://docs.example.com"
+        );
+
+        let path = tmp.path().join("opencode.jsonc");
+        fs::write(&path, "{\n  // keep me\n  \"theme\": \"dark\"\n}\n").unwrap();
+        write_mcp_config(HarnessKind::OpenCode, &path, &servers).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("// keep me\n  \"theme\": \"dark\","));
+        assert!(
+            read_mcp_config(HarnessKind::OpenCode, &path)
+                .unwrap()
+                .contains_key("docs")
+        );
+    }
+
     #[test]
     fn goose_yaml_preserves_existing_mcps() {
         let tmp = TempDir::new().unwrap();
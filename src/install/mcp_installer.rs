@@ -6,9 +6,10 @@ use std::path::{Path, PathBuf};
 use harness_locate::{Harness, HarnessKind, McpServer, StdioMcpServer};
 
 use crate::harness::HarnessConfig;
+use crate::harness::registry::parse_kind;
 use serde_json::Value;
 
-use super::installer::InstallError;
+use super::installer::{InstallError, report_written};
 use super::mcp_config::{mcp_exists, write_mcp_config};
 use super::types::{InstallOptions, InstallTarget, SkipReason};
 use crate::config::BridleConfig;
@@ -35,17 +36,7 @@ pub enum McpInstallOutcome {
 
 pub type McpInstallResult = Result<McpInstallOutcome, InstallError>;
 
-fn parse_harness_kind(id: &str) -> Option<HarnessKind> {
-    match id {
-        "claude-code" | "claude" | "cc" => Some(HarnessKind::ClaudeCode),
-        "opencode" | "oc" => Some(HarnessKind::OpenCode),
-        "goose" => Some(HarnessKind::Goose),
-        "amp-code" | "amp" | "ampcode" => Some(HarnessKind::AmpCode),
-        _ => None,
-    }
-}
-
-fn get_profile_config_path(profile_dir: &Path, harness_kind: HarnessKind) -> PathBuf {
+pub fn get_profile_config_path(profile_dir: &Path, harness_kind: HarnessKind) -> PathBuf {
     match harness_kind {
         HarnessKind::ClaudeCode => profile_dir.join(".mcp.json"),
         HarnessKind::OpenCode => profile_dir.join("opencode.jsonc"),
@@ -73,6 +64,7 @@ pub fn install_mcp(
     target: &InstallTarget,
     options: &InstallOptions,
 ) -> McpInstallResult {
+    options.check_policy(|policy| policy.check_mcp_server(name).into_iter().collect())?;
     let profiles_dir = BridleConfig::profiles_dir().map_err(|_| InstallError::ProfileNotFound {
         harness: target.harness.clone(),
         profile: target.profile.as_str().to_string(),
@@ -88,7 +80,7 @@ pub fn install_mcp_to_dir(
     options: &InstallOptions,
     profiles_dir: &Path,
 ) -> McpInstallResult {
-    let kind = parse_harness_kind(&target.harness)
+    let kind = parse_kind(&target.harness)
         .ok_or_else(|| InstallError::HarnessNotFound(target.harness.clone()))?;
 
     let profile_dir = profiles_dir
@@ -135,10 +127,19 @@ pub fn install_mcp_to_dir(
     let mut servers_to_write: HashMap<String, Value> = HashMap::new();
     servers_to_write.insert(name.to_string(), native_value);
 
+    options.before_write(&profile_config_path)?;
     write_mcp_config(kind, &profile_config_path, &servers_to_write)
         .map_err(|e| InstallError::WriteFile(std::io::Error::other(e)))?;
 
-    let harness_path = write_mcp_to_harness_if_active(name, server, target, kind)?;
+    let harness_path = write_mcp_to_harness_if_active(name, server, target, kind, options)?;
+    report_written(
+        options,
+        target,
+        "mcp",
+        name,
+        &profile_config_path,
+        harness_path.as_deref(),
+    );
 
     Ok(McpInstallOutcome::Installed(McpInstallSuccess {
         name: name.to_string(),
@@ -153,6 +154,7 @@ fn write_mcp_to_harness_if_active(
     server: &McpServer,
     target: &InstallTarget,
     kind: HarnessKind,
+    options: &InstallOptions,
 ) -> Result<Option<PathBuf>, InstallError> {
     let config = BridleConfig::load().ok();
     let is_active = config
@@ -179,6 +181,7 @@ fn write_mcp_to_harness_if_active(
     let mut servers_to_write: HashMap<String, Value> = HashMap::new();
     servers_to_write.insert(name.to_string(), native_value);
 
+    options.before_write(&config_path)?;
     write_mcp_config(kind, &config_path, &servers_to_write)
         .map_err(|e| InstallError::WriteFile(std::io::Error::other(e)))?;
 
@@ -201,7 +204,7 @@ pub fn check_env_var_warnings(servers: &HashMap<String, McpServer>) -> Vec<Strin
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::ProfileName;
+    use crate::config::{Policy, ProfileName};
     use std::fs;
     use tempfile::TempDir;
 
@@ -233,6 +236,26 @@ mod tests {
         })
     }
 
+    #[test]
+    fn install_mcp_refuses_servers_the_policy_does_not_allow() {
+        let options = InstallOptions::default();
+        options
+            .policy
+            .set(Some(Policy {
+                allowed_mcp_servers: Some(vec!["github".to_string()]),
+                location: "policy.toml".to_string(),
+                ..Default::default()
+            }))
+            .unwrap();
+        let target = InstallTarget {
+            harness: "opencode".to_string(),
+            profile: ProfileName::new("test").unwrap(),
+        };
+
+        let result = install_mcp("filesystem", &create_stdio_server(), &target, &options);
+        assert!(matches!(result, Err(InstallError::Policy(_))));
+    }
+
     #[test]
     fn install_mcp_to_claude_profile() {
         let (_temp, target, profiles_dir) = setup_test_env("claude-code");
@@ -377,7 +400,10 @@ mod tests {
             "filesystem",
             &server,
             &target,
-            &InstallOptions { force: true },
+            &InstallOptions {
+                force: true,
+                ..Default::default()
+            },
             &profiles_dir,
         );
         assert!(matches!(result, Ok(McpInstallOutcome::Installed(_))));
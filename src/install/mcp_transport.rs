@@ -0,0 +1,258 @@
+//! Rewriting MCP servers between transports.
+//!
+//! Remote servers (SSE or streamable HTTP) become stdio servers by running
+//! them through [supergateway](https://github.com/supercorp-ai/supergateway),
+//! which harnesses without remote support can start like any local server.
+//! A stdio server has no URL of its own, so the reverse only works for such
+//! a supergateway wrapper, which is unwrapped again. SSE and HTTP servers
+//! switch into each other keeping their URL and headers.
+
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use harness_locate::types::EnvValue;
+use harness_locate::{HttpMcpServer, McpServer, SseMcpServer, StdioMcpServer};
+use serde::Serialize;
+
+/// Package that bridges remote MCP servers to stdio.
+const GATEWAY: &str = "supergateway";
+
+/// An MCP transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    Stdio,
+    Sse,
+    Http,
+}
+
+impl Transport {
+    pub fn of(server: &McpServer) -> Self {
+        match server {
+            McpServer::Stdio(_) => Self::Stdio,
+            McpServer::Sse(_) => Self::Sse,
+            McpServer::Http(_) => Self::Http,
+        }
+    }
+
+    fn gateway_flag(self) -> &'static str {
+        match self {
+            Self::Sse => "--sse",
+            _ => "--streamableHttp",
+        }
+    }
+}
+
+impl std::fmt::Display for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Stdio => "stdio",
+            Self::Sse => "sse",
+            Self::Http => "http",
+        })
+    }
+}
+
+/// A remote server's URL, headers, and common settings.
+struct Remote {
+    transport: Transport,
+    url: String,
+    headers: HashMap<String, EnvValue>,
+    enabled: bool,
+    timeout_ms: Option<u64>,
+}
+
+impl Remote {
+    fn into_server(self, to: Transport) -> McpServer {
+        match to {
+            Transport::Sse => McpServer::Sse(SseMcpServer {
+                url: self.url,
+                headers: self.headers,
+                enabled: self.enabled,
+                timeout_ms: self.timeout_ms,
+            }),
+            Transport::Http => McpServer::Http(HttpMcpServer {
+                url: self.url,
+                headers: self.headers,
+                oauth: None,
+                enabled: self.enabled,
+                timeout_ms: self.timeout_ms,
+            }),
+            Transport::Stdio => {
+                let mut args = vec![
+                    "-y".to_string(),
+                    GATEWAY.to_string(),
+                    self.transport.gateway_flag().to_string(),
+                    self.url,
+                ];
+                let mut headers: Vec<_> = self.headers.into_iter().collect();
+                headers.sort_by(|a, b| a.0.cmp(&b.0));
+                for (name, value) in headers {
+                    let value = match value {
+                        EnvValue::Plain(value) => value,
+                        EnvValue::EnvRef { env } => format!("${{{env}}}"),
+                    };
+                    args.extend(["--header".to_string(), format!("{name}: {value}")]);
+                }
+                McpServer::Stdio(StdioMcpServer {
+                    command: "npx".to_string(),
+                    args,
+                    env: Default::default(),
+                    cwd: None,
+                    enabled: self.enabled,
+                    timeout_ms: self.timeout_ms,
+                })
+            }
+        }
+    }
+}
+
+/// The remote server a supergateway stdio wrapper connects to.
+fn unwrap_gateway(server: &StdioMcpServer) -> Option<Remote> {
+    let start = if server.command == GATEWAY {
+        0
+    } else {
+        server
+            .args
+            .iter()
+            .position(|a| a == GATEWAY || a.starts_with(&format!("{GATEWAY}@")))?
+    };
+    let mut remote = None;
+    let mut headers = HashMap::new();
+    let mut args = server.args[start..].iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sse" => remote = Some((Transport::Sse, args.next()?.clone())),
+            "--streamableHttp" => remote = Some((Transport::Http, args.next()?.clone())),
+            "--header" => {
+                let (name, value) = args.next()?.split_once(':')?;
+                let value = value.trim();
+                let value = match value.strip_prefix("${").and_then(|v| v.strip_suffix('}')) {
+                    Some(env) => EnvValue::EnvRef {
+                        env: env.to_string(),
+                    },
+                    None => EnvValue::Plain(value.to_string()),
+                };
+                headers.insert(name.trim().to_string(), value);
+            }
+            _ => {}
+        }
+    }
+    let (transport, url) = remote?;
+    Some(Remote {
+        transport,
+        url,
+        headers,
+        enabled: server.enabled,
+        timeout_ms: server.timeout_ms,
+    })
+}
+
+/// `server` rewritten to use `to`.
+///
+/// # Errors
+/// Returns why the conversion is not possible: the server already uses
+/// `to`, is a stdio server that does not wrap a remote one, or carries
+/// settings `to` cannot express.
+pub fn convert(server: &McpServer, to: Transport) -> Result<McpServer, String> {
+    let from = Transport::of(server);
+    if from == to {
+        return Err(format!("the server already uses {to}"));
+    }
+    let remote = match server {
+        McpServer::Stdio(stdio) => unwrap_gateway(stdio).ok_or_else(|| {
+            format!(
+                "a stdio server has no URL to connect to over {to}; serve it with \
+                 `npx -y {GATEWAY} --stdio \"{}\"` and add that URL instead",
+                std::iter::once(stdio.command.as_str())
+                    .chain(stdio.args.iter().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )
+        })?,
+        McpServer::Sse(sse) => Remote {
+            transport: Transport::Sse,
+            url: sse.url.clone(),
+            headers: sse.headers.clone(),
+            enabled: sse.enabled,
+            timeout_ms: sse.timeout_ms,
+        },
+        McpServer::Http(http) => {
+            if http.oauth.is_some() {
+                return Err(format!(
+                    "the server's OAuth settings cannot be carried over to {to}"
+                ));
+            }
+            Remote {
+                transport: Transport::Http,
+                url: http.url.clone(),
+                headers: http.headers.clone(),
+                enabled: http.enabled,
+                timeout_ms: http.timeout_ms,
+            }
+        }
+    };
+    Ok(remote.into_server(to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sse() -> McpServer {
+        McpServer::Sse(SseMcpServer {
+            url: "https://mcp.example.com/sse".to_string(),
+            headers: [(
+                "Authorization".to_string(),
+                EnvValue::Plain("Bearer ${TOKEN}".to_string()),
+            )]
+            .into(),
+            enabled: true,
+            timeout_ms: None,
+        })
+    }
+
+    #[test]
+    fn wraps_remote_servers_in_a_gateway_and_back() {
+        let stdio = convert(&sse(), Transport::Stdio).unwrap();
+        let McpServer::Stdio(ref wrapper) = stdio else {
+            panic!("expected stdio, got {stdio:?}");
+        };
+        assert_eq!(wrapper.command, "npx");
+        assert_eq!(
+            wrapper.args,
+            [
+                "-y",
+                "supergateway",
+                "--sse",
+                "https://mcp.example.com/sse",
+                "--header",
+                "Authorization: Bearer ${TOKEN}"
+            ]
+        );
+        assert_eq!(convert(&stdio, Transport::Sse).unwrap(), sse());
+
+        let http = convert(&stdio, Transport::Http).unwrap();
+        assert!(matches!(http, McpServer::Http(ref h) if h.url.ends_with("/sse")));
+        assert_eq!(convert(&http, Transport::Sse).unwrap(), sse());
+    }
+
+    #[test]
+    fn refuses_what_cannot_be_converted() {
+        let local = McpServer::Stdio(StdioMcpServer {
+            command: "mcp-fs".to_string(),
+            args: vec!["/srv".to_string()],
+            env: Default::default(),
+            cwd: None,
+            enabled: true,
+            timeout_ms: None,
+        });
+        let err = convert(&local, Transport::Http).unwrap_err();
+        assert!(err.contains("--stdio \"mcp-fs /srv\""), "{err}");
+        assert!(
+            convert(&sse(), Transport::Sse)
+                .unwrap_err()
+                .contains("already")
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Mirrors: a copy of every source kept at install time.
+//!
+//! With `settings.mirror` set, the files of the skills, agents, and commands
+//! being installed are copied there before they are written to any profile,
+//! so they stay available if the upstream repository disappears or its ref
+//! is force-pushed. A mirror is a directory, or an OCI registry namespace
+//! given as `oci://<registry>/<namespace>`.
+//!
+//! Each copy is keyed by source, ref, and a digest of its files:
+//! `<dir>/<owner>/<repo>/<ref>-<digest>/` for directories and
+//! `<namespace>/<owner>/<repo>:<ref>-<digest>` for registries. Installing
+//! the same bits twice reuses the copy; a force-pushed ref gets a new one.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::hooks::SETUP_SCRIPT;
+use super::oci::{self, Artifact, ArtifactKind, OCI_SCHEME, OciReference};
+use super::types::{AgentInfo, CommandInfo, SkillInfo, SourceInfo};
+
+/// Hex digits of the files' digest kept in a copy's name.
+const DIGEST_LEN: usize = 12;
+
+/// Ref used in a copy's name when the source was fetched without one.
+const DEFAULT_REF: &str = "default";
+
+/// The files of `skills`, `agents`, and `commands` at their paths in the
+/// source, including each skill's setup script.
+pub fn collect(
+    skills: &[SkillInfo],
+    agents: &[AgentInfo],
+    commands: &[CommandInfo],
+) -> Vec<(String, Vec<u8>)> {
+    let mut files = Vec::new();
+    for skill in skills {
+        files.push((skill.path.clone(), skill.content.clone().into_bytes()));
+        if let Some(script) = &skill.setup_script {
+            let dir = Path::new(&skill.path).parent().unwrap_or(Path::new(""));
+            let path = dir.join(SETUP_SCRIPT).to_string_lossy().into_owned();
+            files.push((path, script.clone().into_bytes()));
+        }
+    }
+    files.extend(
+        agents
+            .iter()
+            .map(|a| (a.path.clone(), a.content.clone().into_bytes())),
+    );
+    files.extend(
+        commands
+            .iter()
+            .map(|c| (c.path.clone(), c.content.clone().into_bytes())),
+    );
+    files.sort();
+    files.dedup_by(|a, b| a.0 == b.0);
+    files
+}
+
+/// Copies `files` from `source` to `mirror`, returning where they are.
+pub fn store(
+    mirror: &str,
+    source: &SourceInfo,
+    files: Vec<(String, Vec<u8>)>,
+) -> Result<String, String> {
+    let version = format!(
+        "{}-{}",
+        segment(source.git_ref.as_deref().unwrap_or(DEFAULT_REF)),
+        &digest(&files)[..DIGEST_LEN]
+    );
+    let artifact = Artifact {
+        kind: ArtifactKind::Skills,
+        files,
+    };
+
+    if let Some(namespace) = mirror.strip_prefix(OCI_SCHEME) {
+        let location = format!(
+            "{}/{}/{}:{version}",
+            namespace.trim_end_matches('/'),
+            segment(&source.owner).to_lowercase(),
+            repo_path(&source.repo).to_lowercase()
+        );
+        let reference = OciReference::parse(&location).map_err(|e| e.to_string())?;
+        oci::push(&reference, &artifact).map_err(|e| format!("{location}: {e}"))?;
+        return Ok(format!("{OCI_SCHEME}{reference}"));
+    }
+
+    let dir = crate::harness::expand_home(Path::new(mirror))
+        .map_err(|e| e.to_string())?
+        .join(segment(&source.owner))
+        .join(repo_path(&source.repo))
+        .join(version);
+    if !dir.exists() {
+        write_dir(&artifact, &dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    Ok(dir.display().to_string())
+}
+
+/// Writes `artifact` below `dir` through a sibling staging directory, so an
+/// interrupted copy never looks complete.
+fn write_dir(artifact: &Artifact, dir: &Path) -> Result<(), String> {
+    let staging = PathBuf::from(format!("{}.partial", dir.display()));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    artifact.write_to(&staging).map_err(|e| e.to_string())?;
+    std::fs::rename(&staging, dir).map_err(|e| e.to_string())
+}
+
+/// Digest of `files`, which are sorted by path.
+fn digest(files: &[(String, Vec<u8>)]) -> String {
+    let mut hasher = Sha256::new();
+    for (path, content) in files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_be_bytes());
+        hasher.update(content);
+    }
+    hex(&hasher.finalize())
+}
+
+/// `value` with anything but letters, digits, `.`, `_`, and `-` replaced,
+/// usable as a path component and in a tag.
+fn segment(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    value.trim_start_matches(['.', '-']).to_string()
+}
+
+/// `repo` with each `/`-separated part made a [`segment`].
+fn repo_path(repo: &str) -> String {
+    repo.split('/')
+        .map(segment)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn copies_files_once_per_ref_and_content() {
+        let temp = TempDir::new().unwrap();
+        let mirror = temp.path().to_string_lossy().into_owned();
+        let source = SourceInfo {
+            owner: "org".to_string(),
+            repo: "skills".to_string(),
+            git_ref: Some("release/v1".to_string()),
+        };
+        let skill = SkillInfo {
+            name: "model".to_string(),
+            description: None,
+            path: "skills/model/SKILL.md".to_string(),
+            content: "---\nname: model\n---\n".to_string(),
+            setup_script: Some("echo setup".to_string()),
+            harnesses: Vec::new(),
+        };
+
+        let files = collect(std::slice::from_ref(&skill), &[], &[]);
+        let location = store(&mirror, &source, files.clone()).unwrap();
+        let copy = PathBuf::from(&location);
+        assert!(copy.starts_with(temp.path().join("org/skills")));
+        let name = copy.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("release-v1-"), "{name}");
+        assert_eq!(
+            std::fs::read_to_string(copy.join("skills/model/install.sh")).unwrap(),
+            "echo setup"
+        );
+        assert!(copy.join("skills/model/SKILL.md").exists());
+        assert_eq!(store(&mirror, &source, files).unwrap(), location);
+
+        let changed = SkillInfo {
+            content: "---\nname: model\n---\nForce-pushed\n".to_string(),
+            ..skill
+        };
+        let moved = store(&mirror, &source, collect(&[changed], &[], &[])).unwrap();
+        assert_ne!(moved, location);
+        assert!(copy.exists());
+    }
+}
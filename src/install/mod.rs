@@ -3,13 +3,26 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+pub mod cache;
+pub mod compat;
+pub mod convert;
 pub mod discovery;
+pub mod env;
+pub mod filter;
+pub mod hooks;
+pub mod http;
 pub mod installer;
+pub mod lock;
 pub mod manifest;
 pub mod mcp_config;
 pub mod mcp_installer;
+pub mod mcp_transport;
+pub mod mirror;
+pub mod oci;
+pub mod progress;
 pub mod types;
+pub mod undo;
 pub mod uninstaller;
 
-pub use discovery::{DiscoveryError, discover_skills};
+pub use discovery::{DiscoveryError, discover_files, discover_skills};
 pub use types::*;
@@ -0,0 +1,825 @@
+//! Distributing skill bundles and profiles through OCI registries.
+//!
+//! Files are stored the way ORAS stores them: an image manifest with
+//! `artifactType` [`ARTIFACT_TYPE`], the empty config blob, and one layer per
+//! file whose `org.opencontainers.image.title` annotation holds its relative
+//! path. A manifest annotation records whether the files are a skills
+//! bundle or a profile export.
+//!
+//! Registries that ask for authentication get it the way their
+//! `WWW-Authenticate` challenge requests: a Bearer token from the named
+//! realm, or Basic auth. Credentials come from `BRIDLE_OCI_USERNAME` and
+//! `BRIDLE_OCI_PASSWORD`, or else from the `auths` in Docker's
+//! `config.json`, so a `docker login` is enough.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+use thiserror::Error;
+use ureq::http::{Method, Request, Response};
+use ureq::{Agent, Body};
+
+use super::cache::DownloadCache;
+use super::http::{HttpError, agent_config, describe, is_transient, retry_policy};
+
+/// Prefix marking an `install` source as an OCI reference.
+pub const OCI_SCHEME: &str = "oci://";
+
+/// `artifactType` of manifests bridle pushes.
+const ARTIFACT_TYPE: &str = "application/vnd.bridle.bundle.v1";
+const MANIFEST_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+const EMPTY_TYPE: &str = "application/vnd.oci.empty.v1+json";
+const LAYER_TYPE: &str = "application/octet-stream";
+const TITLE_ANNOTATION: &str = "org.opencontainers.image.title";
+const KIND_ANNOTATION: &str = "dev.bridle.kind";
+
+/// The empty JSON object OCI uses as the config of artifacts without one.
+const EMPTY_CONFIG: &[u8] = b"{}";
+
+/// Largest manifest or blob bridle reads from a registry.
+const MAX_BODY: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum OciError {
+    #[error("invalid OCI reference '{reference}': {reason}")]
+    InvalidReference {
+        reference: String,
+        reason: &'static str,
+    },
+
+    #[error("registry request failed: {}", describe(.0))]
+    Http(#[from] ureq::Error),
+
+    #[error(transparent)]
+    Network(#[from] HttpError),
+
+    #[error("registry returned {status} for {url}")]
+    Status { status: u16, url: String },
+
+    #[error("registry authentication failed: {0}")]
+    Auth(String),
+
+    #[error("{reference} is not a bridle bundle: {reason}")]
+    InvalidManifest { reference: String, reason: String },
+
+    #[error("digest mismatch for {0}")]
+    DigestMismatch(String),
+
+    #[error("unsafe path in bundle: {0}")]
+    UnsafePath(String),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A `registry/repository[:tag|@digest]` reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OciReference {
+    /// Registry host, with port if any (e.g. `ghcr.io`).
+    pub registry: String,
+    /// Repository path within the registry (e.g. `org/skills`).
+    pub repository: String,
+    /// Tag or `sha256:` digest; `latest` when neither was given.
+    pub reference: String,
+}
+
+impl OciReference {
+    /// Parses a reference, with or without the `oci://` prefix.
+    pub fn parse(input: &str) -> Result<Self, OciError> {
+        let invalid = |reason| OciError::InvalidReference {
+            reference: input.to_string(),
+            reason,
+        };
+        let rest = input.strip_prefix(OCI_SCHEME).unwrap_or(input);
+        let (registry, path) = rest
+            .split_once('/')
+            .ok_or_else(|| invalid("expected registry/repository"))?;
+        if !(registry.contains('.') || registry.contains(':') || registry == "localhost") {
+            return Err(invalid("the first component must be a registry host"));
+        }
+
+        let (repository, reference) = match path.split_once('@') {
+            Some((repo, digest)) => {
+                if !is_digest(digest) {
+                    return Err(invalid("digests must be sha256:<64 hex digits>"));
+                }
+                (repo, digest)
+            }
+            None => match path.rsplit_once(':') {
+                Some((repo, tag)) if !tag.contains('/') => (repo, tag),
+                _ => (path, "latest"),
+            },
+        };
+        let valid_repo = !repository.is_empty()
+            && repository.split('/').all(|part| !part.is_empty())
+            && repository.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-' | '/')
+            });
+        if !valid_repo {
+            return Err(invalid(
+                "repository must be lowercase letters, digits, '.', '_', '-', and '/'",
+            ));
+        }
+        if reference.is_empty() {
+            return Err(invalid("empty tag"));
+        }
+
+        Ok(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+
+    fn base_url(&self) -> String {
+        let host = self.registry.split(':').next().unwrap_or_default();
+        let scheme = if host == "localhost" || host == "127.0.0.1" {
+            "http"
+        } else {
+            "https"
+        };
+        format!("{scheme}://{}", self.registry)
+    }
+
+    fn endpoint(&self, kind: &str, reference: &str) -> String {
+        format!(
+            "{}/v2/{}/{kind}/{reference}",
+            self.base_url(),
+            self.repository
+        )
+    }
+}
+
+impl fmt::Display for OciReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if is_digest(&self.reference) { '@' } else { ':' };
+        write!(
+            f,
+            "{}/{}{separator}{}",
+            self.registry, self.repository, self.reference
+        )
+    }
+}
+
+/// What an artifact's files are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Skills, agents, commands, and MCP servers, laid out like a repository.
+    Skills,
+    /// Profiles as written by `bridle export --dotfiles`.
+    Profile,
+}
+
+impl ArtifactKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Skills => "skills",
+            Self::Profile => "profile",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "skills" => Some(Self::Skills),
+            "profile" => Some(Self::Profile),
+            _ => None,
+        }
+    }
+}
+
+/// Files pushed to or pulled from a registry, keyed by relative path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+impl Artifact {
+    /// Reads every file under `dir`, skipping `.git`.
+    pub fn from_dir(kind: ArtifactKind, dir: &Path) -> Result<Self, OciError> {
+        fn walk(
+            dir: &Path,
+            prefix: &str,
+            files: &mut Vec<(String, Vec<u8>)>,
+        ) -> Result<(), OciError> {
+            let mut entries: Vec<_> = std::fs::read_dir(dir)?.flatten().collect();
+            entries.sort_by_key(|e| e.file_name());
+            for entry in entries {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name == ".git" {
+                    continue;
+                }
+                let rel = format!("{prefix}{name}");
+                if entry.path().is_dir() {
+                    walk(&entry.path(), &format!("{rel}/"), files)?;
+                } else {
+                    files.push((rel, std::fs::read(entry.path())?));
+                }
+            }
+            Ok(())
+        }
+        let mut files = Vec::new();
+        walk(dir, "", &mut files)?;
+        Ok(Self { kind, files })
+    }
+
+    /// Writes the files below `dir`.
+    pub fn write_to(&self, dir: &Path) -> Result<(), OciError> {
+        for (rel, content) in &self.files {
+            let path = dir.join(safe_path(rel)?);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok(())
+    }
+}
+
+/// A new, empty directory under the system temp dir for staging `purpose`'s
+/// files, with a random name. Removed when dropped.
+pub fn scratch_dir(purpose: &str) -> std::io::Result<TempDir> {
+    tempfile::Builder::new()
+        .prefix(&format!("bridle-{purpose}-"))
+        .tempdir()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+}
+
+impl Descriptor {
+    fn new(media_type: &str, content: &[u8]) -> Self {
+        Self {
+            media_type: media_type.to_string(),
+            digest: digest(content),
+            size: content.len() as u64,
+            annotations: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    artifact_type: Option<String>,
+    config: Descriptor,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    fn for_artifact(artifact: &Artifact) -> Self {
+        let layers = artifact
+            .files
+            .iter()
+            .map(|(rel, content)| {
+                let mut layer = Descriptor::new(LAYER_TYPE, content);
+                layer
+                    .annotations
+                    .insert(TITLE_ANNOTATION.to_string(), rel.clone());
+                layer
+            })
+            .collect();
+        Self {
+            schema_version: 2,
+            media_type: Some(MANIFEST_TYPE.to_string()),
+            artifact_type: Some(ARTIFACT_TYPE.to_string()),
+            config: Descriptor::new(EMPTY_TYPE, EMPTY_CONFIG),
+            layers,
+            annotations: BTreeMap::from([(
+                KIND_ANNOTATION.to_string(),
+                artifact.kind.as_str().to_string(),
+            )]),
+        }
+    }
+
+    fn kind(&self) -> Result<ArtifactKind, String> {
+        if self.artifact_type.as_deref() != Some(ARTIFACT_TYPE) {
+            return Err(format!(
+                "artifactType is {}",
+                self.artifact_type.as_deref().unwrap_or("unset")
+            ));
+        }
+        let kind = self
+            .annotations
+            .get(KIND_ANNOTATION)
+            .map(String::as_str)
+            .unwrap_or_default();
+        ArtifactKind::parse(kind).ok_or_else(|| format!("unknown bundle kind '{kind}'"))
+    }
+}
+
+/// Pushes `artifact` to `reference`, returning the manifest digest.
+pub fn push(reference: &OciReference, artifact: &Artifact) -> Result<String, OciError> {
+    let mut client = Client::new(reference)?;
+    client.upload_blob(EMPTY_CONFIG)?;
+    for (_, content) in &artifact.files {
+        client.upload_blob(content)?;
+    }
+
+    let manifest = serde_json::to_vec(&Manifest::for_artifact(artifact))?;
+    let url = reference.endpoint("manifests", &reference.reference);
+    let response = client.send(
+        Method::PUT,
+        &url,
+        &[("Content-Type", MANIFEST_TYPE)],
+        Some(&manifest),
+    )?;
+    expect_success(&response, &url)?;
+    Ok(digest(&manifest))
+}
+
+/// Pulls the bundle at `reference`, verifying every blob's digest. Blobs
+/// come from the download cache when it has them.
+pub fn pull(reference: &OciReference) -> Result<Artifact, OciError> {
+    let mut client = Client::new(reference)?;
+    let url = reference.endpoint("manifests", &reference.reference);
+    let body = client.fetch(&url, &[("Accept", MANIFEST_TYPE)])?;
+    if is_digest(&reference.reference) && digest(&body) != reference.reference {
+        return Err(OciError::DigestMismatch(reference.to_string()));
+    }
+    let invalid = |reason: String| OciError::InvalidManifest {
+        reference: reference.to_string(),
+        reason,
+    };
+    let manifest: Manifest = serde_json::from_slice(&body).map_err(|e| invalid(e.to_string()))?;
+    let kind = manifest.kind().map_err(invalid)?;
+
+    let cache = DownloadCache::open();
+    let mut files = Vec::new();
+    for layer in &manifest.layers {
+        let rel = layer
+            .annotations
+            .get(TITLE_ANNOTATION)
+            .ok_or_else(|| invalid(format!("layer {} has no title", layer.digest)))?;
+        safe_path(rel)?;
+        let cached = cache.as_ref().and_then(|c| c.get(&layer.digest));
+        let fetched = cached.is_none();
+        let content = match cached {
+            Some(content) => content,
+            None => client.fetch(&reference.endpoint("blobs", &layer.digest), &[])?,
+        };
+        if digest(&content) != layer.digest {
+            return Err(OciError::DigestMismatch(rel.clone()));
+        }
+        if let Some(cache) = cache.as_ref().filter(|_| fetched) {
+            cache.put(&layer.digest, &content);
+        }
+        files.push((rel.clone(), content));
+    }
+    Ok(Artifact { kind, files })
+}
+
+/// A registry connection that authenticates on demand.
+struct Client {
+    agent: Agent,
+    base_url: String,
+    registry: String,
+    repository: String,
+    authorization: Option<String>,
+}
+
+impl Client {
+    fn new(reference: &OciReference) -> Result<Self, OciError> {
+        let agent = agent_config()?.http_status_as_error(false).build().into();
+        Ok(Self {
+            agent,
+            base_url: reference.base_url(),
+            registry: reference.registry.clone(),
+            repository: reference.repository.clone(),
+            authorization: None,
+        })
+    }
+
+    /// Sends a request, answering one authentication challenge.
+    fn send(
+        &mut self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&[u8]>,
+    ) -> Result<Response<Body>, OciError> {
+        let response = self.send_once(method.clone(), url, headers, body)?;
+        if response.status() != 401 || self.authorization.is_some() {
+            return Ok(response);
+        }
+        let challenge = response
+            .headers()
+            .get("www-authenticate")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| OciError::Auth(format!("{url} returned 401 without a challenge")))?
+            .to_string();
+        self.authorization = Some(self.authorize(&challenge)?);
+        self.send_once(method, url, headers, body)
+    }
+
+    fn send_once(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&[u8]>,
+    ) -> Result<Response<Body>, OciError> {
+        let mut request = Request::builder().method(method).uri(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+        let response = match body {
+            Some(body) => self
+                .agent
+                .run(request.body(body).map_err(ureq::Error::from)?),
+            None => self.agent.run(request.body(()).map_err(ureq::Error::from)?),
+        };
+        Ok(response?)
+    }
+
+    /// GETs `url` and reads the whole body, retrying transient failures.
+    fn fetch(&mut self, url: &str, headers: &[(&str, &str)]) -> Result<Vec<u8>, OciError> {
+        let retryable = |e: &OciError| match e {
+            OciError::Http(e) => is_transient(e),
+            OciError::Status { status, .. } => *status >= 500,
+            _ => false,
+        };
+        retry_policy().run(
+            || {
+                let mut response = self.send(Method::GET, url, headers, None)?;
+                expect_success(&response, url)?;
+                Ok(response
+                    .body_mut()
+                    .with_config()
+                    .limit(MAX_BODY)
+                    .read_to_vec()?)
+            },
+            retryable,
+        )
+    }
+
+    /// Uploads `content` unless the registry already has it.
+    fn upload_blob(&mut self, content: &[u8]) -> Result<(), OciError> {
+        let digest = digest(content);
+        let blob_url = format!("{}/v2/{}/blobs/{digest}", self.base_url, self.repository);
+        if self
+            .send(Method::HEAD, &blob_url, &[], None)?
+            .status()
+            .is_success()
+        {
+            return Ok(());
+        }
+
+        let start_url = format!("{}/v2/{}/blobs/uploads/", self.base_url, self.repository);
+        let response = self.send(Method::POST, &start_url, &[], Some(&[]))?;
+        expect_success(&response, &start_url)?;
+        let location = response
+            .headers()
+            .get("location")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| OciError::Status {
+                status: response.status().as_u16(),
+                url: start_url.clone(),
+            })?;
+        let upload_url = upload_url(&self.base_url, location, &digest);
+        let response = self.send(
+            Method::PUT,
+            &upload_url,
+            &[("Content-Type", LAYER_TYPE)],
+            Some(content),
+        )?;
+        expect_success(&response, &upload_url)
+    }
+
+    /// The `Authorization` header answering `challenge`.
+    fn authorize(&self, challenge: &str) -> Result<String, OciError> {
+        let (scheme, params) = parse_challenge(challenge)
+            .ok_or_else(|| OciError::Auth(format!("unsupported challenge: {challenge}")))?;
+        let credentials = credentials(&self.registry);
+        let basic = credentials.as_ref().map(|(user, password)| {
+            format!("Basic {}", BASE64.encode(format!("{user}:{password}")))
+        });
+
+        if scheme.eq_ignore_ascii_case("basic") {
+            return basic.ok_or_else(|| {
+                OciError::Auth(format!(
+                    "{} needs credentials; set BRIDLE_OCI_USERNAME and BRIDLE_OCI_PASSWORD or run `docker login`",
+                    self.registry
+                ))
+            });
+        }
+
+        let realm = params
+            .get("realm")
+            .ok_or_else(|| OciError::Auth("Bearer challenge has no realm".to_string()))?;
+        let query: Vec<String> = ["service", "scope"]
+            .iter()
+            .filter_map(|key| params.get(*key).map(|v| format!("{key}={}", encode(v))))
+            .collect();
+        let url = if query.is_empty() {
+            realm.clone()
+        } else {
+            format!("{realm}?{}", query.join("&"))
+        };
+        let mut request = self.agent.get(&url);
+        if let Some(basic) = &basic {
+            request = request.header("Authorization", basic);
+        }
+        let mut response = request.call()?;
+        if !response.status().is_success() {
+            return Err(OciError::Auth(format!(
+                "token request to {realm} returned {}",
+                response.status().as_u16()
+            )));
+        }
+        let body: serde_json::Value = serde_json::from_slice(&response.body_mut().read_to_vec()?)
+            .map_err(|e| OciError::Auth(e.to_string()))?;
+        body.get("token")
+            .or_else(|| body.get("access_token"))
+            .and_then(|t| t.as_str())
+            .map(|token| format!("Bearer {token}"))
+            .ok_or_else(|| OciError::Auth(format!("{realm} returned no token")))
+    }
+}
+
+fn expect_success(response: &Response<Body>, url: &str) -> Result<(), OciError> {
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(OciError::Status {
+            status: response.status().as_u16(),
+            url: url.to_string(),
+        })
+    }
+}
+
+/// The URL that completes an upload started at `location`.
+fn upload_url(base_url: &str, location: &str, digest: &str) -> String {
+    let location = if location.starts_with('/') {
+        format!("{base_url}{location}")
+    } else {
+        location.to_string()
+    };
+    let separator = if location.contains('?') { '&' } else { '?' };
+    format!("{location}{separator}digest={}", encode(digest))
+}
+
+/// Splits a `WWW-Authenticate` value into its scheme and parameters.
+fn parse_challenge(challenge: &str) -> Option<(String, BTreeMap<String, String>)> {
+    let (scheme, rest) = challenge
+        .trim()
+        .split_once(' ')
+        .unwrap_or((challenge.trim(), ""));
+    if scheme.is_empty() {
+        return None;
+    }
+    let mut params = BTreeMap::new();
+    let mut rest = rest.trim();
+    while let Some((key, tail)) = rest.split_once('=') {
+        let key = key
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_ascii_lowercase();
+        let (value, tail) = match tail.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => tail.split_once(',').unwrap_or((tail, "")),
+        };
+        params.insert(key, value.to_string());
+        rest = tail.trim().trim_start_matches(',').trim();
+    }
+    Some((scheme.to_string(), params))
+}
+
+/// Username and password for `registry`, from the environment or Docker's config.
+fn credentials(registry: &str) -> Option<(String, String)> {
+    if let (Ok(user), Ok(password)) = (
+        std::env::var("BRIDLE_OCI_USERNAME"),
+        std::env::var("BRIDLE_OCI_PASSWORD"),
+    ) {
+        return Some((user, password));
+    }
+    let path = match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) => Path::new(&dir).join("config.json"),
+        None => harness_locate::platform::home_dir()
+            .ok()?
+            .join(".docker/config.json"),
+    };
+    docker_credentials(&std::fs::read_to_string(path).ok()?, registry)
+}
+
+fn docker_credentials(config: &str, registry: &str) -> Option<(String, String)> {
+    let config: serde_json::Value = serde_json::from_str(config).ok()?;
+    let auths = config.get("auths")?.as_object()?;
+    let auth = auths
+        .iter()
+        .find(|(key, _)| {
+            let host = key
+                .trim_start_matches("https://")
+                .trim_start_matches("http://");
+            host.split('/').next() == Some(registry)
+        })?
+        .1
+        .get("auth")?
+        .as_str()?;
+    let decoded = String::from_utf8(BASE64.decode(auth).ok()?).ok()?;
+    let (user, password) = decoded.split_once(':')?;
+    Some((user.to_string(), password.to_string()))
+}
+
+/// `rel` as a relative path, refusing absolute paths and `..`.
+fn safe_path(rel: &str) -> Result<&Path, OciError> {
+    let path = Path::new(rel);
+    let safe = !rel.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if safe {
+        Ok(path)
+    } else {
+        Err(OciError::UnsafePath(rel.to_string()))
+    }
+}
+
+fn digest(content: &[u8]) -> String {
+    let hash = Sha256::digest(content);
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    format!("sha256:{hex}")
+}
+
+fn is_digest(value: &str) -> bool {
+    value
+        .strip_prefix("sha256:")
+        .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Percent-encodes a query parameter value.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parses_references() {
+        let tagged = OciReference::parse("oci://ghcr.io/org/skills:v1").unwrap();
+        assert_eq!(tagged.registry, "ghcr.io");
+        assert_eq!(tagged.repository, "org/skills");
+        assert_eq!(tagged.reference, "v1");
+        assert_eq!(tagged.to_string(), "ghcr.io/org/skills:v1");
+
+        let latest = OciReference::parse("localhost:5000/team/profile").unwrap();
+        assert_eq!(latest.registry, "localhost:5000");
+        assert_eq!(latest.reference, "latest");
+        assert_eq!(latest.base_url(), "http://localhost:5000");
+
+        let digest = format!("sha256:{}", "a".repeat(64));
+        let pinned = OciReference::parse(&format!("ghcr.io/org/skills@{digest}")).unwrap();
+        assert_eq!(pinned.reference, digest);
+        assert_eq!(pinned.to_string(), format!("ghcr.io/org/skills@{digest}"));
+
+        assert!(OciReference::parse("org/skills:v1").is_err());
+        assert!(OciReference::parse("ghcr.io/Org/Skills").is_err());
+        assert!(OciReference::parse("ghcr.io/org/skills@sha256:abc").is_err());
+    }
+
+    #[test]
+    fn manifest_round_trips_files_and_kind() {
+        let artifact = Artifact {
+            kind: ArtifactKind::Profile,
+            files: vec![("claude-code/work/CLAUDE.md".to_string(), b"hi".to_vec())],
+        };
+        let manifest = Manifest::for_artifact(&artifact);
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(r#""artifactType":"application/vnd.bridle.bundle.v1""#));
+        assert!(json.contains(r#""org.opencontainers.image.title":"claude-code/work/CLAUDE.md""#));
+        assert_eq!(
+            manifest.config.digest,
+            "sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+
+        let parsed: Manifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.kind(), Ok(ArtifactKind::Profile));
+        assert_eq!(parsed.layers[0].digest, digest(b"hi"));
+    }
+
+    #[test]
+    fn rejects_foreign_manifests() {
+        let mut manifest = Manifest::for_artifact(&Artifact {
+            kind: ArtifactKind::Skills,
+            files: Vec::new(),
+        });
+        manifest.artifact_type = Some("application/vnd.oci.image.config.v1+json".to_string());
+        assert!(manifest.kind().is_err());
+    }
+
+    #[test]
+    fn refuses_paths_outside_the_bundle() {
+        assert!(safe_path("skills/review/SKILL.md").is_ok());
+        assert!(safe_path("../escape").is_err());
+        assert!(safe_path("/etc/passwd").is_err());
+        assert!(safe_path("").is_err());
+
+        let temp = TempDir::new().unwrap();
+        let artifact = Artifact {
+            kind: ArtifactKind::Skills,
+            files: vec![("a/../../b".to_string(), Vec::new())],
+        };
+        assert!(artifact.write_to(temp.path()).is_err());
+    }
+
+    #[test]
+    fn reads_and_writes_bundle_directories() {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(src.join("skills/review")).unwrap();
+        std::fs::create_dir_all(src.join(".git")).unwrap();
+        std::fs::write(src.join("skills/review/SKILL.md"), "review").unwrap();
+        std::fs::write(src.join(".git/HEAD"), "ref").unwrap();
+
+        let artifact = Artifact::from_dir(ArtifactKind::Skills, &src).unwrap();
+        assert_eq!(
+            artifact.files,
+            vec![("skills/review/SKILL.md".to_string(), b"review".to_vec())]
+        );
+
+        let dst = temp.path().join("dst");
+        artifact.write_to(&dst).unwrap();
+        assert!(dst.join("skills/review/SKILL.md").exists());
+    }
+
+    #[test]
+    fn parses_auth_challenges() {
+        let (scheme, params) = parse_challenge(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/skills:pull""#,
+        )
+        .unwrap();
+        assert_eq!(scheme, "Bearer");
+        assert_eq!(params["realm"], "https://ghcr.io/token");
+        assert_eq!(params["service"], "ghcr.io");
+        assert_eq!(params["scope"], "repository:org/skills:pull");
+
+        let (scheme, params) = parse_challenge(r#"Basic realm="Registry""#).unwrap();
+        assert_eq!(scheme, "Basic");
+        assert_eq!(params["realm"], "Registry");
+    }
+
+    #[test]
+    fn completes_upload_locations() {
+        let digest = digest(b"x");
+        assert_eq!(
+            upload_url("https://r.io", "/v2/a/blobs/uploads/1", &digest),
+            format!(
+                "https://r.io/v2/a/blobs/uploads/1?digest=sha256%3A{}",
+                &digest[7..]
+            )
+        );
+        assert!(upload_url("https://r.io", "https://s3/u?id=1", &digest).contains("?id=1&digest="));
+    }
+
+    #[test]
+    fn reads_docker_credentials() {
+        let auth = BASE64.encode("me:secret");
+        let config = format!(r#"{{"auths": {{"https://ghcr.io": {{"auth": "{auth}"}}}}}}"#);
+        assert_eq!(
+            docker_credentials(&config, "ghcr.io"),
+            Some(("me".to_string(), "secret".to_string()))
+        );
+        assert_eq!(docker_credentials(&config, "docker.io"), None);
+    }
+}
@@ -0,0 +1,85 @@
+//! Progress events from an install, for wrapping tools and GUIs.
+//!
+//! An [`InstallObserver`] set on [`InstallOptions`](super::InstallOptions)
+//! hears what happens as it happens, the way a
+//! [`ProfileObserver`](crate::config::ProfileObserver) hears about profile
+//! switches. [`JsonLines`] writes each event as one line of JSON, which is
+//! what `bridle install --progress json-lines` prints on stdout.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// Something that happened during `bridle install`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum InstallEvent {
+    /// Looking for components in a source began.
+    DiscoveryStarted { source: String },
+    /// A skill's `SKILL.md` was read from the source.
+    SkillFetched { name: String, path: String },
+    /// A file was written for a component, in the profile or the live config.
+    FileWritten {
+        harness: String,
+        profile: String,
+        /// `skill`, `agent`, `command`, or `mcp`.
+        component: &'static str,
+        name: String,
+        path: PathBuf,
+    },
+    /// Installing into one target finished.
+    TargetDone {
+        harness: String,
+        profile: String,
+        installed: usize,
+        failed: usize,
+    },
+}
+
+/// Receives [`InstallEvent`]s emitted while installing.
+pub trait InstallObserver: Send + Sync {
+    fn on_event(&self, event: &InstallEvent);
+}
+
+impl<F> InstallObserver for F
+where
+    F: Fn(&InstallEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &InstallEvent) {
+        self(event)
+    }
+}
+
+/// Prints each event to stdout as a line of JSON.
+pub struct JsonLines;
+
+impl InstallObserver for JsonLines {
+    fn on_event(&self, event: &InstallEvent) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{line}");
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_as_tagged_json() {
+        let event = InstallEvent::TargetDone {
+            harness: "opencode".to_string(),
+            profile: "work".to_string(),
+            installed: 2,
+            failed: 0,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"target_done","harness":"opencode","profile":"work","installed":2,"failed":0}"#
+        );
+    }
+}
@@ -1,15 +1,21 @@
 //! Types for installation operations.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
 use harness_locate::McpServer;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::config::ProfileName;
+use super::hooks::HookRun;
+use super::installer::InstallError;
+use super::progress::{InstallEvent, InstallObserver};
+use super::undo::InstallRecorder;
+use crate::config::{BridleConfig, Policy, ProfileName, Violation};
 
 /// Information about a discovered skill
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillInfo {
     /// Skill name (from SKILL.md frontmatter)
     pub name: String,
@@ -19,10 +25,15 @@ pub struct SkillInfo {
     pub path: String,
     /// Actual SKILL.md file content
     pub content: String,
+    /// Content of an `install.sh` next to SKILL.md
+    pub setup_script: Option<String>,
+    /// Harnesses listed under `harnesses` in the frontmatter; empty if any
+    #[serde(default)]
+    pub harnesses: Vec<String>,
 }
 
 /// Information about a discovered agent
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentInfo {
     pub name: String,
     pub description: Option<String>,
@@ -31,7 +42,7 @@ pub struct AgentInfo {
 }
 
 /// Information about a discovered command
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandInfo {
     pub name: String,
     pub description: Option<String>,
@@ -48,10 +59,86 @@ pub struct InstallTarget {
 }
 
 /// Options controlling installation behavior
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct InstallOptions {
     /// Overwrite existing files
     pub force: bool,
+    /// Values for environment variables the installed skills declare
+    pub env: BTreeMap<String, String>,
+    /// Skills whose setup hook the user allowed to run
+    pub run_setup: BTreeSet<String>,
+    /// Directory below the skills directory to install skills into, e.g. `team`
+    pub subdir: Option<String>,
+    /// Skip skills that do not list the target harness, instead of noting it
+    pub strict_compat: bool,
+    /// Notified of every file written
+    pub observer: Option<Arc<dyn InstallObserver>>,
+    /// Keeps each file as it was before the install wrote it
+    pub recorder: Option<Arc<InstallRecorder>>,
+    /// Team policy; read from the settings on the first component installed
+    /// when left unset
+    pub policy: OnceLock<Option<Policy>>,
+}
+
+impl InstallOptions {
+    pub fn emit(&self, event: InstallEvent) {
+        if let Some(observer) = &self.observer {
+            observer.on_event(&event);
+        }
+    }
+
+    /// Refuses a component if the team policy bans it, naming every
+    /// violation `check` finds.
+    pub fn check_policy(
+        &self,
+        check: impl FnOnce(&Policy) -> Vec<Violation>,
+    ) -> Result<(), InstallError> {
+        let policy = match self.policy.get() {
+            Some(policy) => policy,
+            None => {
+                let loaded = BridleConfig::load()
+                    .and_then(|config| Policy::from_settings(config.settings()))
+                    .map_err(|e| InstallError::Policy(e.to_string()))?;
+                self.policy.get_or_init(|| loaded)
+            }
+        };
+        let Some(policy) = policy else {
+            return Ok(());
+        };
+        let violations = check(policy);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(InstallError::Policy(
+                policy.violated(violations).to_string(),
+            ))
+        }
+    }
+
+    /// Hands `path` to the recorder, if there is one, before it is written.
+    pub fn before_write(&self, path: &Path) -> Result<(), InstallError> {
+        match &self.recorder {
+            Some(recorder) => recorder
+                .before_write(path)
+                .map_err(|e| InstallError::Record(e.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for InstallOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstallOptions")
+            .field("force", &self.force)
+            .field("env", &self.env)
+            .field("run_setup", &self.run_setup)
+            .field("subdir", &self.subdir)
+            .field("strict_compat", &self.strict_compat)
+            .field("observer", &self.observer.is_some())
+            .field("recorder", &self.recorder.is_some())
+            .field("policy", &self.policy.get())
+            .finish()
+    }
 }
 
 /// Result of discovery operation
@@ -95,6 +182,15 @@ pub struct InstallSuccess {
     pub profile_path: PathBuf,
     /// Path in harness config (None if profile not active)
     pub harness_path: Option<PathBuf>,
+    /// Compatibility notes from converting the component for the harness
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// Environment variables written to the harness config for the component
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    /// Result of the skill's setup hook, if it ran
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup: Option<HookRun>,
 }
 
 #[derive(Debug, Serialize)]
@@ -108,6 +204,8 @@ pub struct InstallSkip {
 pub enum SkipReason {
     /// File already exists and --force not specified
     AlreadyExists,
+    /// The skill does not list the target harness and --strict-compat was given
+    Incompatible,
 }
 
 #[derive(Debug, Serialize)]
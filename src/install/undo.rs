@@ -0,0 +1,320 @@
+//! Undo records: what one `bridle install` changed, file by file, so
+//! `bridle undo --last-install` can put it back.
+//!
+//! Before the installer writes a file, in a profile or in the live config, it
+//! hands the path to the [`InstallRecorder`] on
+//! [`InstallOptions`](super::InstallOptions). The first time a path comes up
+//! the recorder copies the file as it is, or notes that there was none and
+//! which of its directories are new. Records live in
+//! [`StatePaths::installs_dir`](crate::config::StatePaths::installs_dir) as
+//! `<id>/record.json` next to the copies; the newest [`KEEP`] are kept.
+//!
+//! Undoing puts the copies back, deletes the files the install created, and
+//! removes the directories it created once they are empty. It refuses when a
+//! file was changed after the install, unless forced. What setup hooks fetch
+//! is not recorded.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use super::lock::sha256;
+use crate::config::write_atomic;
+use crate::error::{Error, Result};
+
+/// How many install records are kept; older ones are dropped.
+pub const KEEP: usize = 10;
+
+const RECORD_FILE: &str = "record.json";
+
+const ID_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// One file an install wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedFile {
+    pub path: PathBuf,
+    /// Copy of the file from before the install, relative to the record;
+    /// `None` when the install created it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+    /// Directories the install created for it, innermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created_dirs: Vec<PathBuf>,
+    /// SHA-256 of the file as the install left it; `None` until the install
+    /// finished, or if it left no file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub written: Option<String>,
+}
+
+/// What one install changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallRecord {
+    /// When the install started, e.g. `20260310_142501`.
+    pub id: String,
+    /// `owner/repo` the components came from.
+    pub source: String,
+    pub installed_at: String,
+    /// `<harness>/<profile>` of each target.
+    pub targets: Vec<String>,
+    pub files: Vec<ChangedFile>,
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+/// What [`InstallRecord::undo`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UndoReport {
+    /// Files put back as they were before the install.
+    pub restored: Vec<PathBuf>,
+    /// Files the install created, now deleted.
+    pub removed: Vec<PathBuf>,
+}
+
+/// Collects an [`InstallRecord`] while an install runs.
+#[derive(Debug)]
+pub struct InstallRecorder {
+    record: Mutex<InstallRecord>,
+}
+
+impl InstallRecorder {
+    /// Starts a record in `root` of an install of `source` into `targets`.
+    pub fn begin(root: &Path, source: &str, targets: Vec<String>) -> Result<Self> {
+        let now = Local::now();
+        let stamp = now.format(ID_FORMAT).to_string();
+        let mut id = stamp.clone();
+        let mut n = 1;
+        while root.join(&id).exists() {
+            n += 1;
+            id = format!("{stamp}-{n}");
+        }
+        let record = InstallRecord {
+            dir: root.join(&id),
+            id,
+            source: source.to_string(),
+            installed_at: now.to_rfc3339(),
+            targets,
+            files: Vec::new(),
+        };
+        std::fs::create_dir_all(&record.dir)?;
+        record.save()?;
+        Ok(Self {
+            record: Mutex::new(record),
+        })
+    }
+
+    /// Keeps what is at `path` before the install writes it. Later calls for
+    /// the same path do nothing, so the record holds the file as it was
+    /// before the install, not before its last write.
+    pub fn before_write(&self, path: &Path) -> Result<()> {
+        let mut record = self
+            .record
+            .lock()
+            .map_err(|_| Error::Config("install record is poisoned".to_string()))?;
+        if record.files.iter().any(|f| f.path == path) {
+            return Ok(());
+        }
+        let backup = if path.is_file() {
+            let backup = PathBuf::from("files").join(record.files.len().to_string());
+            write_atomic(&record.dir.join(&backup), std::fs::read(path)?)?;
+            Some(backup)
+        } else {
+            None
+        };
+        let created_dirs = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        record.files.push(ChangedFile {
+            path: path.to_path_buf(),
+            backup,
+            created_dirs,
+            written: None,
+        });
+        record.save()
+    }
+
+    /// Notes how the install left each file and drops records beyond
+    /// [`KEEP`]. Returns the record, or `None` when the install changed
+    /// nothing and no record is kept.
+    pub fn finish(self) -> Result<Option<InstallRecord>> {
+        let mut record = self
+            .record
+            .into_inner()
+            .map_err(|_| Error::Config("install record is poisoned".to_string()))?;
+        let root = record.dir.parent().map(Path::to_path_buf);
+        if record.files.is_empty() {
+            std::fs::remove_dir_all(&record.dir)?;
+            return Ok(None);
+        }
+        for file in &mut record.files {
+            file.written = std::fs::read(&file.path).ok().map(|c| sha256(&c));
+        }
+        record.save()?;
+        if let Some(root) = root {
+            let records = InstallRecord::all(&root)?;
+            for old in records.iter().skip(KEEP) {
+                std::fs::remove_dir_all(&old.dir)?;
+            }
+        }
+        Ok(Some(record))
+    }
+}
+
+impl InstallRecord {
+    /// The records in `root`, newest first.
+    pub fn all(root: &Path) -> Result<Vec<Self>> {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            return Ok(Vec::new());
+        };
+        let mut records = Vec::new();
+        for entry in entries {
+            let dir = entry?.path();
+            let file = dir.join(RECORD_FILE);
+            if !file.is_file() {
+                continue;
+            }
+            let content = std::fs::read_to_string(&file)?;
+            let mut record: Self =
+                serde_json::from_str(&content).map_err(|e| Error::ConfigParse {
+                    path: file.clone(),
+                    message: e.to_string(),
+                })?;
+            record.dir = dir;
+            records.push(record);
+        }
+        records.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(records)
+    }
+
+    /// The newest record in `root`, if there is one.
+    pub fn latest(root: &Path) -> Result<Option<Self>> {
+        Ok(Self::all(root)?.into_iter().next())
+    }
+
+    /// Files changed since the install left them.
+    pub fn changed_since(&self) -> Vec<&Path> {
+        self.files
+            .iter()
+            .filter(|f| std::fs::read(&f.path).ok().map(|c| sha256(&c)) != f.written)
+            .map(|f| f.path.as_path())
+            .collect()
+    }
+
+    /// Puts every file back as it was before the install and drops the
+    /// record.
+    ///
+    /// # Errors
+    /// Returns [`Error::Config`] when a file was changed after the install,
+    /// unless `force` is set; nothing is undone then.
+    pub fn undo(self, force: bool) -> Result<UndoReport> {
+        let changed = self.changed_since();
+        if !force && !changed.is_empty() {
+            let list: Vec<String> = changed.iter().map(|p| p.display().to_string()).collect();
+            return Err(Error::Config(format!(
+                "changed since install {}: {}; pass --force to undo anyway",
+                self.id,
+                list.join(", ")
+            )));
+        }
+        let mut report = UndoReport::default();
+        for file in self.files.iter().rev() {
+            match &file.backup {
+                Some(backup) => {
+                    write_atomic(&file.path, std::fs::read(self.dir.join(backup))?)?;
+                    report.restored.push(file.path.clone());
+                }
+                None => {
+                    if file.path.is_file() {
+                        std::fs::remove_file(&file.path)?;
+                        report.removed.push(file.path.clone());
+                    }
+                    for dir in &file.created_dirs {
+                        // Still holding something else, such as a skill's bundled files.
+                        if std::fs::remove_dir(dir).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        std::fs::remove_dir_all(&self.dir)?;
+        Ok(report)
+    }
+
+    fn save(&self) -> Result<()> {
+        write_atomic(
+            &self.dir.join(RECORD_FILE),
+            serde_json::to_string_pretty(self)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn undo_restores_overwritten_files_and_removes_new_ones() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().join("installs");
+        let profile = temp.path().join("profile");
+        fs::create_dir_all(profile.join("skills/lint")).unwrap();
+        fs::write(profile.join("skills/lint/SKILL.md"), "mine").unwrap();
+        fs::write(profile.join("bridle.lock"), "old lock").unwrap();
+
+        let recorder = InstallRecorder::begin(&root, "acme/skills", vec!["a/b".into()]).unwrap();
+        for (path, content) in [
+            ("skills/lint/SKILL.md", "theirs"),
+            ("skills/team/review/SKILL.md", "review"),
+            ("bridle.lock", "new lock"),
+            ("bridle.lock", "newer lock"),
+        ] {
+            let path = profile.join(path);
+            recorder.before_write(&path).unwrap();
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+        }
+        let record = recorder.finish().unwrap().unwrap();
+        assert_eq!(record.files.len(), 3);
+        assert_eq!(InstallRecord::latest(&root).unwrap(), Some(record.clone()));
+
+        fs::write(profile.join("bridle.lock"), "edited").unwrap();
+        assert_eq!(record.changed_since(), [profile.join("bridle.lock")]);
+        assert!(record.clone().undo(false).is_err());
+        assert_eq!(
+            fs::read_to_string(profile.join("skills/lint/SKILL.md")).unwrap(),
+            "theirs"
+        );
+
+        let report = record.undo(true).unwrap();
+        assert_eq!(
+            report.removed,
+            [profile.join("skills/team/review/SKILL.md")]
+        );
+        assert_eq!(
+            fs::read_to_string(profile.join("skills/lint/SKILL.md")).unwrap(),
+            "mine"
+        );
+        assert_eq!(
+            fs::read_to_string(profile.join("bridle.lock")).unwrap(),
+            "old lock"
+        );
+        assert!(!profile.join("skills/team").exists());
+        assert!(profile.join("skills").exists());
+        assert_eq!(InstallRecord::latest(&root).unwrap(), None);
+    }
+
+    #[test]
+    fn keeps_only_installs_that_changed_something() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().join("installs");
+        let recorder = InstallRecorder::begin(&root, "acme/skills", Vec::new()).unwrap();
+        assert_eq!(recorder.finish().unwrap(), None);
+        assert!(InstallRecord::all(&root).unwrap().is_empty());
+    }
+}
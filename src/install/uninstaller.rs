@@ -7,12 +7,14 @@ use thiserror::Error;
 
 use harness_locate::{Harness, HarnessKind, Scope};
 
+use super::lock::{Lockfile, lock_path};
 use super::manifest::{InstallManifest, manifest_path};
 use super::types::{
     ComponentType, InstallTarget, UninstallFailure, UninstallReport, UninstallSuccess,
 };
 use crate::config::BridleConfig;
 use crate::harness::HarnessConfig;
+use crate::harness::registry::parse_kind;
 
 #[derive(Debug, Error)]
 pub enum UninstallError {
@@ -29,16 +31,6 @@ pub enum UninstallError {
     HarnessNotFound(String),
 }
 
-fn parse_harness_kind(id: &str) -> Option<HarnessKind> {
-    match id {
-        "claude-code" | "claude" | "cc" => Some(HarnessKind::ClaudeCode),
-        "opencode" | "oc" => Some(HarnessKind::OpenCode),
-        "goose" => Some(HarnessKind::Goose),
-        "amp-code" | "amp" | "ampcode" => Some(HarnessKind::AmpCode),
-        _ => None,
-    }
-}
-
 pub fn uninstall_component(
     component_name: &str,
     component_type: ComponentType,
@@ -88,6 +80,12 @@ fn uninstall_component_from_dir(
         let _ = manifest.save(&manifest_file);
     }
 
+    let lock_file = lock_path(&profile_dir);
+    if let Ok(mut lock) = Lockfile::load(&lock_file) {
+        lock.remove(&format!("{}/{component_name}", component_type.dir_name()));
+        let _ = lock.save(&lock_file);
+    }
+
     let harness_path = remove_from_harness_if_active(target, component_name, component_type)?;
 
     Ok(UninstallSuccess {
@@ -115,7 +113,7 @@ fn remove_from_harness_if_active(
         return Ok(None);
     }
 
-    let kind = parse_harness_kind(&target.harness)
+    let kind = parse_kind(&target.harness)
         .ok_or_else(|| UninstallError::HarnessNotFound(target.harness.clone()))?;
     let harness = Harness::locate(kind)
         .map_err(|_| UninstallError::HarnessNotFound(target.harness.clone()))?;
@@ -0,0 +1,13 @@
+//! Profile management for AI coding harnesses, as used by the `bridle` CLI.
+//!
+//! [`config::ProfileManager`] creates, switches, and snapshots profiles for
+//! any [`harness::HarnessConfig`]. With the `test-utils` feature,
+//! [`test_utils`] provides a mock harness and temporary profile trees for
+//! testing against bridle without real harness installs.
+
+pub mod config;
+pub mod error;
+pub mod harness;
+pub mod install;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
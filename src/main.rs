@@ -1,14 +1,19 @@
 mod cli;
-mod config;
 mod display;
-mod error;
-mod harness;
-mod install;
 mod tui;
 
-use clap::Parser;
-use cli::output::OutputFormat;
-use cli::{Commands, ConfigCommands, ProfileCommands};
+#[cfg(test)]
+use bridle::test_utils;
+use bridle::{config, error, harness, install};
+
+use clap::{CommandFactory, Parser};
+use cli::output::{OutputFormat, ResolvedFormat};
+use cli::{
+    CacheCommands, Commands, ConfigCommands, ConfigFileCommands, ContextCommands, DedupeCommands,
+    HarnessCommands, McpCommands, MigrateCommands, ProfileCommands, ProjectCommands, RulesCommands,
+    SecretCommands, SyncCommands,
+};
+use color_eyre::Section;
 
 #[derive(Parser)]
 #[command(name = "bridle")]
@@ -17,25 +22,183 @@ struct Cli {
     #[arg(long, short = 'o', default_value = "auto", global = true)]
     output: OutputFormat,
 
+    /// Use this context for this run.
+    #[arg(long, global = true, value_name = "NAME")]
+    context: Option<String>,
+
+    /// Copy strategy for this run (copy, hardlink).
+    #[arg(long, global = true, value_name = "STRATEGY")]
+    copy_strategy: Option<String>,
+
+    /// Skip the snapshot taken before switching profiles.
+    #[arg(long, global = true)]
+    no_backup: bool,
+
+    /// When to colorize output (auto, always, never).
+    #[arg(long, global = true, value_name = "WHEN")]
+    color: Option<String>,
+
+    /// Skip TLS certificate verification for downloads. A last resort.
+    #[arg(long, global = true)]
+    insecure: bool,
+
+    /// Team policy file or URL to check installs, switches, and audits against.
+    #[arg(long, global = true, value_name = "PATH|URL")]
+    policy: Option<String>,
+
+    /// Run the command on another machine over SSH: `user@server`, or a
+    /// name from `[remotes]`. That machine needs bridle installed.
+    #[arg(long, global = true, value_name = "TARGET")]
+    host: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+impl Cli {
+    /// Config overrides from global flags; these take precedence over `BRIDLE_*` variables.
+    fn overrides(&self) -> Vec<config::layers::Override> {
+        let flags = [
+            ("context", "--context", self.context.clone()),
+            (
+                "copy_strategy",
+                "--copy-strategy",
+                self.copy_strategy.clone(),
+            ),
+            (
+                "auto_backup",
+                "--no-backup",
+                self.no_backup.then(|| "false".into()),
+            ),
+            ("color", "--color", self.color.clone()),
+            ("policy", "--policy", self.policy.clone()),
+            (
+                "insecure",
+                "--insecure",
+                self.insecure.then(|| "true".into()),
+            ),
+        ];
+        flags
+            .into_iter()
+            .filter_map(|(key, flag, value)| {
+                Some(config::layers::Override {
+                    key,
+                    value: value?,
+                    source: flag.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
     let cli = Cli::parse();
+    if let Some(target) = &cli.host {
+        let code = cli::remote::run(target)?;
+        std::process::exit(code);
+    }
     let format = cli.output.resolve();
+    config::layers::set_cli_overrides(cli.overrides());
+    // Prompts render on every keystroke and completions on every Tab; skip
+    // everything that loads the full config.
+    match &cli.command {
+        Some(Commands::Prompt { harness }) => return Ok(cli::prompt::run(harness.as_deref())?),
+        Some(Commands::Complete { words }) => {
+            cli::completions::run(Cli::command(), words);
+            return Ok(());
+        }
+        _ => {}
+    }
+    apply_color_setting();
+    if !matches!(cli.command, Some(Commands::Recover { .. })) {
+        cli::recover::warn_if_interrupted();
+    }
+
+    let check_subscriptions = format == ResolvedFormat::Text
+        && !matches!(
+            cli.command,
+            None | Some(Commands::Tui | Commands::Update { .. } | Commands::Daemon { .. })
+        );
+    run(cli.command, format).map_err(|report| {
+        if let (ResolvedFormat::Json, Some(error::Error::PolicyViolation { policy, violations })) =
+            (format, report.downcast_ref::<error::Error>())
+        {
+            cli::output::output(
+                &serde_json::json!({ "policy": policy, "violations": violations }),
+                format,
+                |_| {},
+            );
+        }
+        match report.downcast_ref::<error::Error>().and_then(|e| e.hint()) {
+            Some(hint) => report.suggestion(hint),
+            None => report,
+        }
+    })?;
+    if check_subscriptions {
+        cli::update::check_if_due();
+    }
+    Ok(())
+}
+
+/// Forces colored output on or off when `settings.color` says so.
+fn apply_color_setting() {
+    let color = config::BridleConfig::load()
+        .map(|c| c.settings().color)
+        .unwrap_or_default();
+    match color {
+        config::ColorChoice::Auto => {}
+        config::ColorChoice::Always => colored::control::set_override(true),
+        config::ColorChoice::Never => colored::control::set_override(false),
+    }
+}
 
-    match cli.command {
+fn run(command: Option<Commands>, format: ResolvedFormat) -> color_eyre::Result<()> {
+    match command {
         None | Some(Commands::Tui) => cli::tui::run_tui()?,
-        Some(Commands::Status) => cli::status::display_status(format),
+        Some(Commands::Status {
+            check: false,
+            explain,
+        }) => cli::status::display_status(explain, format),
+        Some(Commands::Status { check: true, .. }) => {
+            let code = cli::status::check(format)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
         Some(Commands::Init) => cli::init::run_init()?,
         Some(Commands::Profile(profile_cmd)) => match profile_cmd {
-            ProfileCommands::List { harness } => cli::profile::list_profiles(&harness, format)?,
-            ProfileCommands::Show { harness, name } => {
-                cli::profile::show_profile(&harness, &name, format)?
-            }
+            ProfileCommands::List {
+                harness,
+                sort,
+                tags,
+                pattern,
+            } => cli::profile::list_profiles(
+                harness.as_deref(),
+                sort,
+                &tags,
+                pattern.as_deref(),
+                format,
+            )?,
+            ProfileCommands::Show {
+                harness,
+                name,
+                format: report,
+                explain,
+            } => cli::profile::show_profile(&harness, &name, report, explain, format)?,
+            ProfileCommands::Package {
+                harness,
+                name,
+                dir,
+                owner,
+                force,
+            } => cli::package::package(&harness, &name, &dir, &owner, force, format)?,
+            ProfileCommands::Sbom {
+                harness,
+                name,
+                file,
+            } => cli::sbom::sbom(&harness, &name, file.as_deref())?,
             ProfileCommands::Create {
                 harness,
                 name,
@@ -47,11 +210,26 @@ fn main() -> color_eyre::Result<()> {
                     cli::profile::create_profile(&harness, &name)?
                 }
             }
-            ProfileCommands::Delete { harness, name } => {
-                cli::profile::delete_profile(&harness, &name)?
+            ProfileCommands::Delete { harness, name, yes } => {
+                cli::profile::delete_profile(&harness, &name, yes)?
             }
-            ProfileCommands::Switch { harness, name } => {
-                cli::profile::switch_profile(&harness, &name)?
+            ProfileCommands::Prune {
+                harness,
+                days,
+                delete,
+            } => cli::profile::prune_profiles(&harness, days, delete)?,
+            ProfileCommands::Switch {
+                harness,
+                name,
+                on_drift,
+                strategy,
+                check,
+            } => {
+                let code =
+                    cli::profile::switch_profile(&harness, &name, on_drift, strategy, check)?;
+                if code != 0 {
+                    std::process::exit(code);
+                }
             }
             ProfileCommands::Edit { harness, name } => cli::profile::edit_profile(&harness, &name)?,
             ProfileCommands::Diff {
@@ -59,13 +237,296 @@ fn main() -> color_eyre::Result<()> {
                 name,
                 other,
             } => cli::profile::diff_profiles(&harness, &name, other.as_deref())?,
+            ProfileCommands::Log {
+                harness,
+                name,
+                patch,
+                from,
+                to,
+            } => cli::history::log(
+                &harness,
+                &name,
+                patch,
+                from.as_deref(),
+                to.as_deref(),
+                format,
+            )?,
+            ProfileCommands::RestoreFile {
+                harness,
+                name,
+                from_snapshot,
+                path,
+            } => cli::history::restore_file(&harness, &name, &from_snapshot, &path, format)?,
         },
         Some(Commands::Config(config_cmd)) => match config_cmd {
             ConfigCommands::Set { key, value } => cli::config_cmd::set_config(&key, &value)?,
             ConfigCommands::Get { key } => cli::config_cmd::get_config(&key)?,
+            ConfigCommands::Schema => cli::config_cmd::print_schema()?,
+        },
+        Some(Commands::ConfigFile(cmd)) => match cmd {
+            ConfigFileCommands::Get {
+                harness,
+                key,
+                profile,
+                file,
+            } => cli::config_file::get(
+                &cli::config_file::Target {
+                    harness: &harness,
+                    profile: profile.as_deref(),
+                    file: file.as_deref(),
+                },
+                &key,
+            )?,
+            ConfigFileCommands::Set {
+                harness,
+                key,
+                value,
+                profile,
+                file,
+            } => cli::config_file::set(
+                &cli::config_file::Target {
+                    harness: &harness,
+                    profile: profile.as_deref(),
+                    file: file.as_deref(),
+                },
+                &key,
+                &value,
+            )?,
+            ConfigFileCommands::Unset {
+                harness,
+                key,
+                profile,
+                file,
+            } => cli::config_file::unset(
+                &cli::config_file::Target {
+                    harness: &harness,
+                    profile: profile.as_deref(),
+                    file: file.as_deref(),
+                },
+                &key,
+            )?,
+        },
+        Some(Commands::Context(context_cmd)) => match context_cmd {
+            ContextCommands::List => cli::context::list_contexts(format)?,
+            ContextCommands::Current => cli::context::current_context()?,
+            ContextCommands::Use { name } => cli::context::use_context(&name)?,
+            ContextCommands::Create { name, profiles_dir } => {
+                cli::context::create_context(&name, profiles_dir.as_deref())?
+            }
+            ContextCommands::Delete { name } => cli::context::delete_context(&name)?,
+        },
+        Some(Commands::Secret(secret_cmd)) => match secret_cmd {
+            SecretCommands::Set { name } => cli::secret::set(&name)?,
+            SecretCommands::Get { name } => cli::secret::get(&name)?,
+            SecretCommands::List => cli::secret::list(format)?,
         },
-        Some(Commands::Install { source, force }) => cli::install::run(&source, force)?,
-        Some(Commands::Uninstall { harness, profile }) => cli::uninstall::run(&harness, &profile)?,
+        Some(Commands::Cache(cache_cmd)) => match cache_cmd {
+            CacheCommands::Info => cli::cache::info(format)?,
+            CacheCommands::Clean {
+                max_size,
+                older_than,
+            } => cli::cache::clean(max_size.as_deref(), older_than.as_deref(), format)?,
+        },
+        Some(Commands::Provision {
+            manifest,
+            allow_hooks,
+        }) => cli::provision::run(&manifest, allow_hooks, format)?,
+        Some(Commands::Harness(harness_cmd)) => match harness_cmd {
+            HarnessCommands::Install { harness, via, yes } => {
+                cli::harness::install(&harness, via, yes)?
+            }
+            HarnessCommands::Remove {
+                harness,
+                purge_profiles,
+            } => cli::harness::remove(&harness, purge_profiles, format)?,
+        },
+        Some(Commands::Project(project_cmd)) => match project_cmd {
+            ProjectCommands::Snapshot { name, path, force } => {
+                cli::project::snapshot(&name, &path, force, format)?
+            }
+            ProjectCommands::Apply { name, path } => cli::project::apply(&name, &path, format)?,
+            ProjectCommands::List => cli::project::list(format)?,
+        },
+        Some(Commands::Mcp(McpCommands::List { duplicates })) => {
+            cli::mcp::list(duplicates, format)?
+        }
+        Some(Commands::Mcp(McpCommands::Convert {
+            harness,
+            name,
+            to,
+            profile,
+        })) => cli::mcp::convert(&harness, &name, to, profile.as_deref(), format)?,
+        Some(Commands::Mcp(McpCommands::Check { harness, profile })) => {
+            let code = cli::mcp::check(&harness, profile.as_deref(), format)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Rules(RulesCommands::Explain { harness, profile })) => {
+            cli::rules::explain(&harness, profile.as_deref(), format)?
+        }
+        Some(Commands::Install {
+            source,
+            force,
+            set,
+            allow_hooks,
+            targets,
+            filter,
+            exclude,
+            rename,
+            into,
+            strict_compat,
+            progress,
+            resume,
+        }) => match source {
+            Some(source) if !resume => {
+                let filter = install::filter::ComponentFilter {
+                    include: filter,
+                    exclude,
+                };
+                cli::install::run(
+                    &source,
+                    force,
+                    &set,
+                    allow_hooks,
+                    &targets,
+                    &filter,
+                    rename.as_deref(),
+                    into.as_deref(),
+                    strict_compat,
+                    progress,
+                )?
+            }
+            _ => cli::install::resume(progress)?,
+        },
+        Some(Commands::Update {
+            dry_run,
+            allow_hooks,
+        }) => cli::update::run(dry_run, allow_hooks, format)?,
+        Some(Commands::Push {
+            reference,
+            from,
+            harness,
+            profile,
+        }) => cli::push::run(
+            &reference,
+            from.as_deref(),
+            harness.as_deref().zip(profile.as_deref()),
+            format,
+        )?,
+        Some(Commands::Uninstall {
+            harness,
+            profile,
+            components,
+        }) => cli::uninstall::run(&harness, &profile, &components)?,
+        Some(Commands::Exec {
+            harness,
+            profile,
+            command,
+        }) => {
+            let code = cli::exec::run(&harness, &profile, &command)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Dedupe(cmd)) => match cmd {
+            DedupeCommands::Report { harness } => cli::dedupe::report(harness.as_deref(), format)?,
+            DedupeCommands::Apply { harness, mode } => {
+                cli::dedupe::apply(harness.as_deref(), mode)?
+            }
+        },
+        Some(Commands::Audit {
+            harness,
+            profile,
+            fail_on,
+        }) => {
+            let code = cli::audit::run(&harness, profile.as_deref(), fail_on, format)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Sync {
+            command: Some(SyncCommands::Pull { harness, resolve }),
+            ..
+        }) => {
+            let code = cli::sync::pull(harness.as_deref(), resolve, format)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Sync {
+            push,
+            daemon: true,
+            interval,
+            jitter,
+            ..
+        }) => cli::sync::daemon(push, interval, jitter.unwrap_or(interval / 10))?,
+        Some(Commands::Sync {
+            push,
+            jitter,
+            cron: true,
+            ..
+        }) => cli::sync::print_cron(push, jitter.unwrap_or_default())?,
+        Some(Commands::Sync { push, jitter, .. }) => {
+            let code = cli::sync::run(push, jitter.unwrap_or_default(), format)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Logs {
+            harness,
+            mcp,
+            lines,
+            tail,
+        }) => cli::logs::show(&harness, mcp.as_deref(), lines, tail, format)?,
+        Some(Commands::Verify { harness, profile }) => {
+            let code = cli::verify::run(&harness, profile.as_deref(), format)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Show { harness, live }) => {
+            if live {
+                cli::profile::show_live(&harness, format)?
+            } else {
+                cli::profile::show_active(&harness, format)?
+            }
+        }
+        Some(Commands::Insights { harness, days }) => {
+            cli::insights::run(harness.as_deref(), days, format)?
+        }
+        Some(Commands::Export { dotfiles, harness }) => {
+            cli::dotfiles::export(&dotfiles, harness.as_deref())?
+        }
+        Some(Commands::Migrate(MigrateCommands::Export { archive })) => {
+            cli::migrate::export(&archive, format)?
+        }
+        Some(Commands::Migrate(MigrateCommands::Import { archive, force })) => {
+            cli::migrate::import(&archive, force, format)?
+        }
+        Some(Commands::Import {
+            dotfiles,
+            harness,
+            force,
+        }) => cli::dotfiles::import(&dotfiles, harness.as_deref(), force)?,
+        Some(Commands::ImportConfig { harness, name, yes }) => {
+            cli::import_config::run(&harness, &name, yes, format)?
+        }
+        Some(Commands::Activate { dir, hook }) => cli::activate::run(dir, hook)?,
+        Some(Commands::Hook { shell }) => cli::activate::print_hook(shell),
+        Some(Commands::Prompt { harness }) => cli::prompt::run(harness.as_deref())?,
+        Some(Commands::Completions { shell }) => cli::completions::script(shell),
+        Some(Commands::Complete { words }) => cli::completions::run(Cli::command(), &words),
+        Some(Commands::Daemon { socket }) => cli::daemon::run(socket)?,
+        Some(Commands::Recover { complete, rollback }) => {
+            let action = match (complete, rollback) {
+                (true, _) => Some(config::RecoveryAction::Complete),
+                (_, true) => Some(config::RecoveryAction::RollBack),
+                _ => None,
+            };
+            cli::recover::run(action)?
+        }
+        Some(Commands::Undo { force, .. }) => cli::undo::last_install(force, format)?,
     }
 
     Ok(())
@@ -0,0 +1,230 @@
+//! Test fixtures for exercising bridle without real harness installs.
+//!
+//! Compiled for the crate's own tests and, with the `test-utils` feature, for
+//! integrators that want to drive [`ProfileManager`] against a temporary tree.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use harness_locate::InstallationStatus;
+use tempfile::TempDir;
+
+use crate::config::ProfileManager;
+use crate::error::Result;
+use crate::harness::{HarnessConfig, ResourceLayout};
+
+/// In-memory harness backed by an arbitrary config directory.
+///
+/// Reports itself as fully installed and parses no MCP servers, so profile
+/// operations only touch the directories it is pointed at.
+#[derive(Debug, Clone)]
+pub struct MockHarness {
+    id: String,
+    config_dir: PathBuf,
+    mcp_path: Option<PathBuf>,
+    known_paths: Vec<PathBuf>,
+    layout: ResourceLayout,
+}
+
+impl MockHarness {
+    /// Creates a mock harness with the given id and live config directory.
+    pub fn new(id: &str, config_dir: PathBuf) -> Self {
+        Self {
+            id: id.to_string(),
+            config_dir,
+            mcp_path: None,
+            known_paths: Vec::new(),
+            layout: ResourceLayout::default(),
+        }
+    }
+
+    /// Sets the path of the MCP config file managed alongside the config directory.
+    pub fn with_mcp(mut self, mcp_path: PathBuf) -> Self {
+        self.mcp_path = Some(mcp_path);
+        self
+    }
+
+    /// Sets the paths reported by [`HarnessConfig::known_paths`].
+    pub fn with_known_paths(mut self, paths: &[&str]) -> Self {
+        self.known_paths = paths.iter().map(PathBuf::from).collect();
+        self
+    }
+
+    /// Sets the layout reported by [`HarnessConfig::resource_layout`].
+    pub fn with_resource_layout(mut self, layout: ResourceLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+}
+
+impl HarnessConfig for MockHarness {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn config_dir(&self) -> Result<PathBuf> {
+        Ok(self.config_dir.clone())
+    }
+
+    fn installation_status(&self) -> Result<InstallationStatus> {
+        Ok(InstallationStatus::FullyInstalled {
+            binary_path: PathBuf::from("/bin/mock"),
+            config_path: self.config_dir.clone(),
+        })
+    }
+
+    fn mcp_filename(&self) -> Option<String> {
+        None
+    }
+
+    fn mcp_config_path(&self) -> Option<PathBuf> {
+        self.mcp_path.clone()
+    }
+
+    fn parse_mcp_servers(&self, _content: &str, _filename: &str) -> Result<Vec<(String, bool)>> {
+        Ok(vec![])
+    }
+
+    fn known_paths(&self) -> Vec<PathBuf> {
+        self.known_paths.clone()
+    }
+
+    fn resource_layout(&self) -> ResourceLayout {
+        self.layout.clone()
+    }
+}
+
+static CONFIG_ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+/// Points `BRIDLE_CONFIG_DIR` at a temporary directory until dropped.
+///
+/// Holds a process-wide lock so tests that touch bridle's own config
+/// never observe each other's state.
+pub struct ConfigEnvGuard {
+    _lock: MutexGuard<'static, ()>,
+    prev: Vec<(&'static str, Option<OsString>)>,
+}
+
+impl ConfigEnvGuard {
+    /// Sets `BRIDLE_CONFIG_DIR` to `dir`, creating it if needed.
+    ///
+    /// `BRIDLE_STATE_DIR` and the `BRIDLE_*` config overrides are cleared so
+    /// state lands in `<dir>/state` and only the config file applies.
+    pub fn new(dir: &Path) -> Self {
+        let lock = CONFIG_ENV_LOCK
+            .get_or_init(|| Mutex::new(()))
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let cleared = crate::config::layers::ENV_VARS
+            .iter()
+            .map(|&(var, _, _)| var);
+        let vars = ["BRIDLE_CONFIG_DIR", "BRIDLE_STATE_DIR"]
+            .into_iter()
+            .chain(cleared);
+        let prev = vars.map(|var| (var, std::env::var_os(var))).collect();
+
+        std::fs::create_dir_all(dir).expect("failed to create isolated config dir");
+        unsafe {
+            std::env::set_var("BRIDLE_CONFIG_DIR", dir);
+            std::env::remove_var("BRIDLE_STATE_DIR");
+            for &(var, _, _) in crate::config::layers::ENV_VARS {
+                std::env::remove_var(var);
+            }
+        }
+
+        Self { _lock: lock, prev }
+    }
+}
+
+impl Drop for ConfigEnvGuard {
+    fn drop(&mut self) {
+        for (var, prev) in &self.prev {
+            match prev {
+                Some(value) => unsafe { std::env::set_var(var, value) },
+                None => unsafe { std::env::remove_var(var) },
+            }
+        }
+    }
+}
+
+/// Temporary profile store with a live harness config directory.
+///
+/// ```text
+/// <tmp>/
+/// ├── bridle_config/   (BRIDLE_CONFIG_DIR, see `isolate_config`)
+/// ├── profiles/
+/// └── live_config/
+/// ```
+pub struct TempProfiles {
+    temp: TempDir,
+}
+
+impl TempProfiles {
+    /// Creates the temporary tree with empty `profiles/` and `live_config/` directories.
+    pub fn new() -> Self {
+        let temp = TempDir::new().expect("failed to create temp dir");
+        std::fs::create_dir_all(temp.path().join("profiles")).expect("failed to create profiles");
+        std::fs::create_dir_all(temp.path().join("live_config"))
+            .expect("failed to create live config");
+        Self { temp }
+    }
+
+    /// Root of the temporary tree.
+    pub fn root(&self) -> &Path {
+        self.temp.path()
+    }
+
+    /// Directory holding all profiles.
+    pub fn profiles_dir(&self) -> PathBuf {
+        self.root().join("profiles")
+    }
+
+    /// Directory standing in for the harness's live config.
+    pub fn live_config(&self) -> PathBuf {
+        self.root().join("live_config")
+    }
+
+    /// Returns a [`ProfileManager`] rooted at [`Self::profiles_dir`].
+    pub fn manager(&self) -> ProfileManager {
+        ProfileManager::new(self.profiles_dir())
+    }
+
+    /// Returns a [`MockHarness`] whose config dir is [`Self::live_config`].
+    pub fn harness(&self, id: &str) -> MockHarness {
+        MockHarness::new(id, self.live_config())
+    }
+
+    /// Redirects bridle's own config into this tree for the guard's lifetime.
+    pub fn isolate_config(&self) -> ConfigEnvGuard {
+        ConfigEnvGuard::new(&self.root().join("bridle_config"))
+    }
+}
+
+impl Default for TempProfiles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProfileName;
+
+    #[test]
+    fn temp_profiles_round_trip() {
+        let fixture = TempProfiles::new();
+        let _env = fixture.isolate_config();
+        std::fs::write(fixture.live_config().join("settings.json"), "{}").unwrap();
+
+        let harness = fixture.harness("mock");
+        let manager = fixture.manager();
+        let name = ProfileName::new("work").unwrap();
+        let path = manager.create_from_current(&harness, &name).unwrap();
+
+        assert_eq!(path, fixture.profiles_dir().join("mock").join("work"));
+        assert!(path.join("settings.json").exists());
+    }
+}
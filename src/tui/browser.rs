@@ -0,0 +1,428 @@
+//! Tree browser for picking components out of large repositories.
+//!
+//! Components are grouped by the directory they sit in (`skills/pdf`,
+//! `agents`, ...), with groups collapsed until opened. `/` filters by name,
+//! path, and description; Space on a group toggles every component in it;
+//! the right pane previews the component under the cursor.
+
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use super::theme::Theme;
+use super::{init_terminal, restore_terminal};
+
+/// A component offered by the browser.
+pub struct BrowseItem {
+    /// Category the component belongs to, e.g. `Skills`.
+    pub kind: String,
+    pub name: String,
+    /// Path in the repository; empty for components without a file of their own.
+    pub path: String,
+    pub description: Option<String>,
+    /// Content shown in the preview pane.
+    pub preview: String,
+}
+
+struct Group {
+    label: String,
+    items: Vec<usize>,
+    expanded: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Group(usize),
+    Item(usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Continue,
+    Done,
+    Cancel,
+}
+
+struct Browser {
+    items: Vec<BrowseItem>,
+    groups: Vec<Group>,
+    selected: Vec<bool>,
+    query: String,
+    searching: bool,
+    cursor: usize,
+}
+
+impl Browser {
+    fn new(items: Vec<BrowseItem>) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        for (idx, item) in items.iter().enumerate() {
+            let label = group_label(item);
+            match groups.iter_mut().find(|g| g.label == label) {
+                Some(group) => group.items.push(idx),
+                None => groups.push(Group {
+                    label,
+                    items: vec![idx],
+                    expanded: false,
+                }),
+            }
+        }
+        groups.sort_by(|a, b| a.label.cmp(&b.label));
+        for group in &mut groups {
+            group
+                .items
+                .sort_by(|&a, &b| items[a].name.cmp(&items[b].name));
+        }
+        // A lone group has nothing to hide behind.
+        if let [group] = groups.as_mut_slice() {
+            group.expanded = true;
+        }
+        Self {
+            selected: vec![false; items.len()],
+            items,
+            groups,
+            query: String::new(),
+            searching: false,
+            cursor: 0,
+        }
+    }
+
+    fn matches(&self, idx: usize) -> bool {
+        if self.query.is_empty() {
+            return true;
+        }
+        let query = self.query.to_lowercase();
+        let item = &self.items[idx];
+        item.name.to_lowercase().contains(&query)
+            || item.path.to_lowercase().contains(&query)
+            || item
+                .description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&query))
+    }
+
+    fn matching(&self, group: usize) -> Vec<usize> {
+        self.groups[group]
+            .items
+            .iter()
+            .copied()
+            .filter(|&idx| self.matches(idx))
+            .collect()
+    }
+
+    /// Rows on screen: every group with a match, and the matching items of
+    /// open groups. A search opens every group.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (g, group) in self.groups.iter().enumerate() {
+            let matching = self.matching(g);
+            if matching.is_empty() {
+                continue;
+            }
+            rows.push(Row::Group(g));
+            if group.expanded || !self.query.is_empty() {
+                rows.extend(matching.into_iter().map(Row::Item));
+            }
+        }
+        rows
+    }
+
+    fn current(&self) -> Option<Row> {
+        self.rows().get(self.cursor).copied()
+    }
+
+    fn group_of(&self, idx: usize) -> usize {
+        self.groups
+            .iter()
+            .position(|g| g.items.contains(&idx))
+            .unwrap_or_default()
+    }
+
+    /// Selects `items`, or clears them if all are already selected.
+    fn toggle_all(&mut self, items: &[usize]) {
+        let value = !items.iter().all(|&idx| self.selected[idx]);
+        for &idx in items {
+            self.selected[idx] = value;
+        }
+    }
+
+    fn chosen(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|&i| self.selected[i])
+            .collect()
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> Step {
+        if self.searching {
+            match code {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.query.clear();
+                    self.searching = false;
+                }
+                _ => return Step::Continue,
+            }
+            self.cursor = 0;
+            return Step::Continue;
+        }
+
+        let rows = self.rows().len();
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor = (self.cursor + 1).min(rows.saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(Row::Group(g)) = self.current() {
+                    self.groups[g].expanded = true;
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                let group = match self.current() {
+                    Some(Row::Group(g)) => Some(g),
+                    Some(Row::Item(idx)) => Some(self.group_of(idx)),
+                    None => None,
+                };
+                if let Some(g) = group {
+                    self.groups[g].expanded = false;
+                    self.cursor = self
+                        .rows()
+                        .iter()
+                        .position(|row| *row == Row::Group(g))
+                        .unwrap_or_default();
+                }
+            }
+            KeyCode::Char(' ') => match self.current() {
+                Some(Row::Item(idx)) => self.selected[idx] = !self.selected[idx],
+                Some(Row::Group(g)) => {
+                    let items = self.matching(g);
+                    self.toggle_all(&items);
+                }
+                None => {}
+            },
+            KeyCode::Char('a') => {
+                let items: Vec<usize> =
+                    (0..self.items.len()).filter(|&i| self.matches(i)).collect();
+                self.toggle_all(&items);
+            }
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Enter => return Step::Done,
+            KeyCode::Esc | KeyCode::Char('q') => return Step::Cancel,
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    fn render(&self, frame: &mut Frame, prompt: &str) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(frame.area());
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(chunks[0]);
+
+        let rows = self.rows();
+        let lines: Vec<ListItem> = rows
+            .iter()
+            .map(|row| match *row {
+                Row::Group(g) => {
+                    let group = &self.groups[g];
+                    let open = group.expanded || !self.query.is_empty();
+                    let chosen = group.items.iter().filter(|&&i| self.selected[i]).count();
+                    ListItem::new(Line::from(vec![
+                        Span::raw(if open { "▾ " } else { "▸ " }),
+                        Span::styled(
+                            group.label.clone(),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            format!(" ({chosen}/{})", group.items.len()),
+                            Theme::text_muted(),
+                        ),
+                    ]))
+                }
+                Row::Item(idx) => {
+                    let mark = if self.selected[idx] { "[x]" } else { "[ ]" };
+                    ListItem::new(format!("    {mark} {}", self.items[idx].name))
+                }
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.cursor));
+        let list = List::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(prompt))
+            .highlight_style(Theme::tab_selected().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, panes[0], &mut state);
+
+        let preview = match rows.get(self.cursor) {
+            Some(Row::Item(idx)) => {
+                let item = &self.items[*idx];
+                let mut lines = vec![Line::styled(
+                    item.name.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )];
+                if !item.path.is_empty() {
+                    lines.push(Line::styled(item.path.clone(), Theme::text_muted()));
+                }
+                if let Some(description) = &item.description {
+                    lines.push(Line::raw(description.clone()));
+                }
+                lines.push(Line::raw(""));
+                lines.extend(item.preview.lines().map(|l| Line::raw(l.to_string())));
+                lines
+            }
+            Some(Row::Group(g)) => self.groups[*g]
+                .items
+                .iter()
+                .map(|&idx| {
+                    let item = &self.items[idx];
+                    Line::from(vec![
+                        Span::raw(item.name.clone()),
+                        Span::styled(
+                            item.description
+                                .as_deref()
+                                .map(|d| format!("  {d}"))
+                                .unwrap_or_default(),
+                            Theme::text_muted(),
+                        ),
+                    ])
+                })
+                .collect(),
+            None => vec![Line::styled("No matches", Theme::text_warning())],
+        };
+        frame.render_widget(
+            Paragraph::new(preview)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title("Preview")),
+            panes[1],
+        );
+
+        let status = if self.searching {
+            Line::from(vec![
+                Span::styled("/", Style::default().fg(Color::Cyan)),
+                Span::raw(format!("{}▏", self.query)),
+            ])
+        } else {
+            let filter = if self.query.is_empty() {
+                String::new()
+            } else {
+                format!("filter: {}  ", self.query)
+            };
+            Line::styled(
+                format!(
+                    "{filter}{} selected  ↑↓ move  →← open/close  space toggle  a all  / search  enter install  esc cancel",
+                    self.chosen().len()
+                ),
+                Theme::text_muted(),
+            )
+        };
+        frame.render_widget(Paragraph::new(status), chunks[1]);
+    }
+}
+
+/// Category and directory of `item`; a skill's own directory is left out,
+/// so `skills/pdf/extract/SKILL.md` falls under `skills/pdf`.
+fn group_label(item: &BrowseItem) -> String {
+    let path = std::path::Path::new(&item.path);
+    let mut dir = path.parent();
+    if path.file_name().is_some_and(|name| name == "SKILL.md") {
+        dir = dir.and_then(|d| d.parent());
+    }
+    match dir.map(|d| d.to_string_lossy()).filter(|d| !d.is_empty()) {
+        Some(dir) => format!("{}: {dir}", item.kind),
+        None => item.kind.clone(),
+    }
+}
+
+/// Lets the user pick among `items`, returning the indices chosen, or `None`
+/// if they cancelled.
+pub fn browse(prompt: &str, items: Vec<BrowseItem>) -> io::Result<Option<Vec<usize>>> {
+    let mut browser = Browser::new(items);
+    let mut terminal = init_terminal()?;
+    let step = (|| -> io::Result<Step> {
+        loop {
+            terminal.draw(|frame| browser.render(frame, prompt))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Ok(Step::Cancel);
+            }
+            match browser.handle_key(key.code) {
+                Step::Continue => {}
+                step => return Ok(step),
+            }
+        }
+    })();
+    restore_terminal(&mut terminal)?;
+    Ok(match step? {
+        Step::Done => Some(browser.chosen()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: &str, name: &str, path: &str) -> BrowseItem {
+        BrowseItem {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            description: None,
+            preview: String::new(),
+        }
+    }
+
+    #[test]
+    fn groups_by_directory_and_toggles_whole_groups() {
+        let mut browser = Browser::new(vec![
+            item("Skills", "pdf-split", "skills/pdf/split/SKILL.md"),
+            item("Skills", "pdf-merge", "skills/pdf/merge/SKILL.md"),
+            item("Skills", "lint", "skills/dev/lint/SKILL.md"),
+            item("MCP Servers", "github", ""),
+        ]);
+        let labels: Vec<&str> = browser.groups.iter().map(|g| g.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            ["MCP Servers", "Skills: skills/dev", "Skills: skills/pdf"]
+        );
+        assert_eq!(browser.rows().len(), 3);
+
+        browser.handle_key(KeyCode::Down);
+        browser.handle_key(KeyCode::Down);
+        browser.handle_key(KeyCode::Char(' '));
+        assert_eq!(browser.chosen(), vec![0, 1]);
+
+        browser.handle_key(KeyCode::Right);
+        assert_eq!(
+            browser.rows()[3..],
+            [Row::Item(1), Row::Item(0)],
+            "items are sorted by name"
+        );
+
+        for c in "/lint".chars() {
+            browser.handle_key(KeyCode::Char(c));
+        }
+        browser.handle_key(KeyCode::Enter);
+        assert_eq!(browser.rows(), [Row::Group(1), Row::Item(2)]);
+        browser.handle_key(KeyCode::Char('a'));
+        assert_eq!(browser.handle_key(KeyCode::Enter), Step::Done);
+        assert_eq!(browser.chosen(), vec![0, 1, 2]);
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides an interactive TUI for browsing harnesses, profiles, and their configurations.
 
+pub mod browser;
 mod theme;
 mod views;
 mod widgets;
@@ -20,7 +21,7 @@ use crossterm::{
 };
 use harness_locate::{Harness, HarnessKind, InstallationStatus};
 
-use crate::harness::HarnessConfig;
+use crate::harness::{HarnessConfig, HarnessRegistry, registry, with_overrides};
 use ratatui::{
     Frame, Terminal,
     layout::{Constraint, Direction, Layout, Rect},
@@ -30,6 +31,7 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, TableState},
 };
 
+use crate::cli::profile::manager_builder;
 use crate::config::{BridleConfig, ProfileInfo, ProfileManager, ProfileName};
 use crate::error::Error;
 use views::ViewMode;
@@ -38,23 +40,11 @@ use widgets::{DetailPane, HarnessTabs, ProfileTable, StatusBar};
 type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 fn harness_id(kind: &HarnessKind) -> &'static str {
-    match kind {
-        HarnessKind::ClaudeCode => "claude-code",
-        HarnessKind::OpenCode => "opencode",
-        HarnessKind::Goose => "goose",
-        HarnessKind::AmpCode => "amp-code",
-        _ => "unknown",
-    }
+    registry::builtin_id(*kind)
 }
 
 fn harness_name(kind: &HarnessKind) -> &'static str {
-    match kind {
-        HarnessKind::ClaudeCode => "Claude Code",
-        HarnessKind::OpenCode => "OpenCode",
-        HarnessKind::Goose => "Goose",
-        HarnessKind::AmpCode => "AMP Code",
-        _ => "Unknown",
-    }
+    registry::builtin_name(*kind)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -100,13 +90,16 @@ struct App {
 impl App {
     fn new() -> Result<Self, Error> {
         let bridle_config = BridleConfig::load()?;
-        let profiles_dir = BridleConfig::profiles_dir()?;
-        let manager = ProfileManager::new(profiles_dir);
-        let harnesses = HarnessKind::ALL.to_vec();
+        // Prompting would garble the terminal the TUI is drawing on.
+        let manager = manager_builder()?.var_prompt(|_| None).build()?;
+        let harnesses: Vec<HarnessKind> = HarnessRegistry::builtin()
+            .iter()
+            .filter_map(|entry| entry.kind())
+            .collect();
 
         for kind in &harnesses {
-            let harness = Harness::new(*kind);
-            let _ = manager.create_from_current_if_missing(&harness);
+            let harness = with_overrides(Box::new(Harness::new(*kind)), &bridle_config);
+            let _ = manager.create_from_current_if_missing(harness.as_ref());
         }
         let mut harness_state = ListState::default();
         let default_idx = bridle_config
@@ -143,6 +136,11 @@ impl App {
         Ok(app)
     }
 
+    /// Builds the harness for `kind` with any `[harness.<id>]` overrides applied.
+    fn harness_for(&self, kind: HarnessKind) -> Box<dyn HarnessConfig> {
+        with_overrides(Box::new(Harness::new(kind)), &self.bridle_config)
+    }
+
     fn selected_harness(&self) -> Option<HarnessKind> {
         self.harness_state
             .selected()
@@ -180,14 +178,16 @@ impl App {
 
     fn sync_active_profiles(&mut self) {
         for &kind in &self.harnesses {
-            let harness = Harness::new(kind);
+            let harness = self.harness_for(kind);
             let harness_id = harness.id();
             if let Some(active_name) = self.bridle_config.active_profile_for(harness_id)
                 && let Ok(profile_name) = ProfileName::new(active_name)
             {
-                let _ = self
-                    .manager
-                    .save_to_profile(&harness, Some(&harness), &profile_name);
+                let _ = self.manager.save_to_profile(
+                    harness.as_ref(),
+                    harness.as_builtin(),
+                    &profile_name,
+                );
             }
         }
     }
@@ -200,11 +200,11 @@ impl App {
         self.detail_scroll = 0;
 
         if let Some(kind) = self.selected_harness() {
-            let harness = Harness::new(kind);
+            let harness = self.harness_for(kind);
 
-            if let Ok(names) = self.manager.list_profiles(&harness) {
+            if let Ok(names) = self.manager.list_profiles(harness.as_ref()) {
                 for name in names {
-                    if let Ok(info) = self.manager.show_profile(&harness, &name) {
+                    if let Ok(info) = self.manager.show_profile(harness.as_ref(), &name) {
                         self.profiles.push(info);
                     }
                 }
@@ -370,13 +370,13 @@ impl App {
             return;
         };
         let profile = &self.profiles[idx];
-        let harness = Harness::new(kind);
+        let harness = self.harness_for(kind);
         let Ok(profile_name) = ProfileName::new(&profile.name) else {
             self.status_message = Some("Invalid profile name".to_string());
             return;
         };
 
-        match self.manager.delete_profile(&harness, &profile_name) {
+        match self.manager.delete_profile(harness.as_ref(), &profile_name) {
             Ok(()) => {
                 self.status_message = Some(format!("Deleted '{}'", profile.name));
                 self.refresh_profiles();
@@ -396,7 +396,7 @@ impl App {
             return;
         };
         let profile = &self.profiles[idx];
-        let harness = Harness::new(kind);
+        let harness = self.harness_for(kind);
         let Ok(profile_name) = ProfileName::new(&profile.name) else {
             self.status_message = Some("Invalid profile name".to_string());
             return;
@@ -414,7 +414,7 @@ impl App {
                 }
             }
         } else {
-            self.manager.profile_path(&harness, &profile_name)
+            self.manager.profile_path(harness.as_ref(), &profile_name)
         };
         let (program, args) = self.bridle_config.editor_command();
 
@@ -487,19 +487,28 @@ impl App {
             return;
         }
 
-        let harness = Harness::new(kind);
+        let harness = self.harness_for(kind);
         let Ok(profile_name) = ProfileName::new(&profile.name) else {
             self.status_message = Some("Invalid profile name".to_string());
             return;
         };
 
-        match self
-            .manager
-            .switch_profile_with_resources(&harness, Some(&harness), &profile_name)
-        {
+        match self.manager.switch_profile_with_resources(
+            harness.as_ref(),
+            harness.as_builtin(),
+            &profile_name,
+        ) {
             Ok(_) => {
-                self.bridle_config = BridleConfig::load().unwrap_or_default();
-                self.status_message = Some(format!("Switched to '{}'", profile.name));
+                self.status_message = Some(match BridleConfig::load() {
+                    Ok(config) => {
+                        self.bridle_config = config;
+                        format!("Switched to '{}'", profile.name)
+                    }
+                    Err(e) => format!(
+                        "Switched to '{}', but reloading config failed: {}",
+                        profile.name, e
+                    ),
+                });
                 let selected_idx = self.profile_state.selected();
                 self.refresh_profiles();
                 if let Some(idx) = selected_idx {
@@ -577,15 +586,11 @@ impl App {
                 #[cfg(feature = "tui-cards")]
                 ViewMode::Cards => self.next_profile(),
             },
-            KeyCode::Left | KeyCode::Char('h') => {
-                if self.view_mode == ViewMode::Dashboard {
-                    self.prev_harness();
-                }
+            KeyCode::Left | KeyCode::Char('h') if self.view_mode == ViewMode::Dashboard => {
+                self.prev_harness();
             }
-            KeyCode::Right | KeyCode::Char('l') => {
-                if self.view_mode == ViewMode::Dashboard {
-                    self.next_harness();
-                }
+            KeyCode::Right | KeyCode::Char('l') if self.view_mode == ViewMode::Dashboard => {
+                self.next_harness();
             }
             KeyCode::Enter => match self.view_mode {
                 ViewMode::Dashboard => {
@@ -605,10 +610,8 @@ impl App {
                     self.switch_to_selected();
                 }
             },
-            KeyCode::Char(' ') => {
-                if self.active_pane == Pane::Profiles {
-                    self.toggle_expansion();
-                }
+            KeyCode::Char(' ') if self.active_pane == Pane::Profiles => {
+                self.toggle_expansion();
             }
             KeyCode::Char('r') => {
                 self.sync_active_profiles();
@@ -627,15 +630,19 @@ impl App {
                     && let Some(profile) = self.profiles.get(idx)
                 {
                     self.input_buffer = profile.name.clone();
-                    self.input_mode = InputMode::ConfirmingDelete;
+                    if self.bridle_config.settings().confirm {
+                        self.input_mode = InputMode::ConfirmingDelete;
+                    } else {
+                        self.delete_selected();
+                        self.input_buffer.clear();
+                    }
                 }
             }
-            KeyCode::Char('e') => {
+            KeyCode::Char('e')
                 if matches!(self.view_mode, ViewMode::Dashboard)
-                    || self.active_pane == Pane::Profiles
-                {
-                    self.edit_selected();
-                }
+                    || self.active_pane == Pane::Profiles =>
+            {
+                self.edit_selected();
             }
             KeyCode::Char('f') => {
                 if let Some(harness_kind) = self.selected_harness() {
@@ -703,7 +710,7 @@ impl App {
             return;
         };
 
-        let harness = Harness::new(kind);
+        let harness = self.harness_for(kind);
         let profile_name = match ProfileName::new(&name) {
             Ok(pn) => pn,
             Err(_) => {
@@ -713,8 +720,8 @@ impl App {
         };
 
         match self.manager.create_from_current_with_resources(
-            &harness,
-            Some(&harness),
+            harness.as_ref(),
+            harness.as_builtin(),
             &profile_name,
         ) {
             Ok(_) => {
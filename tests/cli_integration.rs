@@ -3,13 +3,14 @@ use predicates::prelude::*;
 use tempfile::TempDir;
 
 fn bridle() -> Command {
-    Command::cargo_bin("bridle").unwrap()
+    assert_cmd::cargo::cargo_bin_cmd!("bridle")
 }
 
 fn with_isolated_config() -> (Command, TempDir) {
     let temp = TempDir::new().unwrap();
     let mut cmd = bridle();
-    cmd.env("BRIDLE_CONFIG_DIR", temp.path());
+    cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR");
     (cmd, temp)
 }
 
@@ -57,6 +58,111 @@ fn profile_create_and_list() {
         .stdout(predicate::str::contains("test-profile"));
 }
 
+#[test]
+fn profile_list_filters_by_tag_and_sorts_by_size() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "small"])
+        .assert()
+        .success();
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    run(&["profile", "create", "claude-code", "big"]);
+    let profiles = temp.path().join("profiles");
+    std::fs::write(profiles.join("claude-code/big/notes.md"), "x".repeat(4096)).unwrap();
+    std::fs::write(
+        profiles.join("opencode/small/profile.toml"),
+        "tags = [\"team\"]\n",
+    )
+    .unwrap();
+
+    let out = run(&["profile", "list", "--sort", "size", "-o", "json"]);
+    let entries: serde_json::Value = serde_json::from_slice(&out.get_output().stdout).unwrap();
+    assert_eq!(entries[0]["name"], "big");
+    assert_eq!(entries[0]["harness_id"], "claude-code");
+    assert_eq!(entries[1]["tags"], serde_json::json!(["team"]));
+
+    run(&["profile", "list", "--tag", "team"])
+        .stdout(predicate::str::contains("NAME"))
+        .stdout(predicate::str::contains("small"))
+        .stdout(predicate::str::contains("big").not());
+}
+
+#[test]
+fn profile_globs_select_profiles_to_list_and_delete() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "work-a"])
+        .assert()
+        .success();
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .args(args)
+            .assert()
+    };
+    for name in ["work-b", "tmp-x", "tmp-y"] {
+        run(&["profile", "create", "opencode", name]).success();
+    }
+
+    run(&["profile", "list", "opencode", "--match", "work-*"])
+        .success()
+        .stdout(predicate::str::contains("work-b"))
+        .stdout(predicate::str::contains("tmp-x").not());
+    run(&["profile", "show", "opencode", "work-*"])
+        .failure()
+        .stderr(predicate::str::contains("matches 2 profiles"));
+
+    run(&["profile", "delete", "opencode", "tmp-*"])
+        .failure()
+        .stderr(predicate::str::contains("pass --yes"));
+    let profiles = temp.path().join("profiles/opencode");
+    assert!(profiles.join("tmp-x").exists());
+
+    run(&["profile", "delete", "opencode", "tmp-*", "--yes"])
+        .success()
+        .stdout(predicate::str::contains("Deleted profile: tmp-y"));
+    assert!(!profiles.join("tmp-x").exists());
+    assert!(profiles.join("work-a").exists());
+}
+
+#[test]
+fn profile_delete_glob_skips_the_active_profile() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let profiles = temp.path().join("profiles/opencode");
+    for name in ["tmp-a", "tmp-b"] {
+        std::fs::create_dir_all(profiles.join(name)).unwrap();
+    }
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(args)
+            .assert()
+    };
+
+    run(&["profile", "switch", "opencode", "tmp-a"]).success();
+    run(&["profile", "delete", "opencode", "tmp-*"])
+        .success()
+        .stdout(predicate::str::contains("Skipped active profile: tmp-a"))
+        .stdout(predicate::str::contains("Deleted profile: tmp-b"));
+    assert!(profiles.join("tmp-a").exists());
+    assert!(!profiles.join("tmp-b").exists());
+}
+
 #[test]
 fn profile_show_not_found() {
     let (mut cmd, _temp) = with_isolated_config();
@@ -65,6 +171,20 @@ fn profile_show_not_found() {
         .failure();
 }
 
+#[test]
+fn profile_show_not_found_suggests_create() {
+    let (mut cmd, _temp) = with_isolated_config();
+    cmd.args(["profile", "show", "opencode", "nonexistent"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "profile not found: opencode/nonexistent",
+        ))
+        .stderr(predicate::str::contains(
+            "bridle profile create opencode nonexistent",
+        ));
+}
+
 #[test]
 fn profile_create_and_show() {
     let (mut cmd, temp) = with_isolated_config();
@@ -215,3 +335,2563 @@ fn profile_switch_preserves_unknown_files() {
         "Profile content should still be applied"
     );
 }
+
+#[test]
+fn custom_harness_from_harnesses_toml() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("cursor");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("harnesses.toml"),
+        format!(
+            "[[harness]]\nid = \"cursor\"\naliases = [\"cur\"]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["profile", "create", "cur", "work"])
+        .assert()
+        .success();
+
+    let mut cmd2 = bridle();
+    cmd2.env("BRIDLE_CONFIG_DIR", temp.path());
+    cmd2.args(["profile", "list", "cursor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("work"));
+}
+
+#[test]
+fn harness_install_creates_default_profile_for_installed_harness() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("cursor");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(live.join("settings.json"), "{}").unwrap();
+    std::fs::write(
+        temp.path().join("harnesses.toml"),
+        format!(
+            "[[harness]]\nid = \"cursor\"\nconfig_dir = {:?}\n\n[[harness]]\nid = \"zed\"\nconfig_dir = {:?}\nbinary = \"bridle-test-missing-zed\"\n",
+            live.display().to_string(),
+            temp.path().join("zed").display().to_string()
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["harness", "install", "cursor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cursor is already installed"))
+        .stdout(predicate::str::contains(
+            "Created profile 'default' from the current config",
+        ));
+    assert!(
+        temp.path()
+            .join("profiles/cursor/default/settings.json")
+            .exists()
+    );
+
+    let mut unknown = bridle();
+    unknown
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["harness", "install", "zed", "--yes"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "bridle does not know how to install zed",
+        ));
+}
+
+#[test]
+fn harness_remove_clears_state_and_keeps_live_config() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("cursor");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(live.join("settings.json"), "{}").unwrap();
+    std::fs::write(
+        temp.path().join("harnesses.toml"),
+        format!(
+            "[[harness]]\nid = \"cursor\"\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    cmd.args(["profile", "create", "cursor", "work", "--from-current"])
+        .assert()
+        .success();
+    let run = |args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(args)
+            .assert()
+    };
+
+    run(&["harness", "remove", "cursor"])
+        .success()
+        .stdout(predicate::str::contains("was using profile 'work'"))
+        .stdout(predicate::str::contains("Kept 1 profile(s)"))
+        .stdout(predicate::str::contains("Live config remains at"));
+    run(&["prompt", "--harness", "cursor"])
+        .success()
+        .stdout(predicate::str::contains("work").not());
+
+    run(&["harness", "remove", "cursor", "--purge-profiles"])
+        .success()
+        .stdout(predicate::str::contains("Deleted 1 profile(s): work"));
+    assert!(!temp.path().join("profiles/cursor").exists());
+    assert!(live.join("settings.json").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn status_warns_about_duplicate_installations_until_pinned() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("cursor");
+    std::fs::create_dir_all(&live).unwrap();
+    let mut bins = Vec::new();
+    for dir in ["npm-bin", "native-bin"] {
+        let dir = temp.path().join(dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("bridle-test-cursor");
+        std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        bins.push(binary);
+    }
+    std::fs::write(
+        temp.path().join("harnesses.toml"),
+        format!(
+            "[[harness]]\nid = \"cursor\"\nconfig_dir = {:?}\nbinary = \"bridle-test-cursor\"\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let path =
+        std::env::join_paths([bins[0].parent().unwrap(), bins[1].parent().unwrap()]).unwrap();
+
+    cmd.env("PATH", &path)
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Warning: 2 installations on PATH; pin one with `[harness.cursor] binary`",
+        ))
+        .stdout(predicate::str::contains(bins[1].display().to_string()));
+
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "[harness.cursor]\nbinary = {:?}\n",
+            bins[1].display().to_string()
+        ),
+    )
+    .unwrap();
+    let mut pinned = bridle();
+    pinned
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .env("PATH", &path)
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Warning").not())
+        .stdout(predicate::str::contains("cursor - installed"));
+}
+
+#[cfg(unix)]
+#[test]
+fn host_flag_runs_command_on_remote_over_ssh() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (mut cmd, temp) = with_isolated_config();
+    let ssh = temp.path().join("fake-ssh");
+    std::fs::write(&ssh, "#!/bin/sh\necho \"$@\"\nexit 3\n").unwrap();
+    std::fs::set_permissions(&ssh, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "[remotes.dev]\nhost = \"me@devbox\"\nssh = \"{} -p 2222\"\n",
+            ssh.display()
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["profile", "list", "opencode", "--host", "dev"])
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains(
+            "-p 2222 me@devbox -- bridle profile list opencode",
+        ));
+}
+
+#[test]
+fn show_live_inspects_unmanaged_config_without_profiles() {
+    let temp = TempDir::new().unwrap();
+    let xdg_config = temp.path().join("xdg");
+    let opencode_config = xdg_config.join("opencode");
+    std::fs::create_dir_all(opencode_config.join("skill/review")).unwrap();
+    std::fs::write(
+        opencode_config.join("opencode.jsonc"),
+        r#"{"model": "anthropic/sonnet", "mcp": {"github": {"type": "local", "command": ["npx", "server-github"]}}}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        opencode_config.join("skill/review/SKILL.md"),
+        "---\nname: review\ndescription: Review code\n---\nReview.\n",
+    )
+    .unwrap();
+    let show = |args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path().join("bridle"))
+            .env_remove("BRIDLE_STATE_DIR")
+            .env("XDG_CONFIG_HOME", &xdg_config)
+            .args(["show", "opencode", "--live"])
+            .args(args)
+            .assert()
+            .success()
+    };
+
+    show(&[]).stdout(
+        predicate::str::contains("Live config")
+            .and(predicate::str::contains("(no active profile)"))
+            .and(predicate::str::contains("anthropic/sonnet"))
+            .and(predicate::str::contains("github"))
+            .and(predicate::str::contains("review")),
+    );
+    show(&["-o", "json"]).stdout(predicate::str::contains(r#""model":"anthropic/sonnet""#));
+    assert!(!temp.path().join("bridle/profiles").exists());
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path().join("bridle"))
+        .env_remove("BRIDLE_STATE_DIR")
+        .env("XDG_CONFIG_HOME", &xdg_config)
+        .args(["show", "opencode"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --live"));
+}
+
+#[test]
+fn project_snapshot_applies_repo_config_to_another_repo() {
+    let (mut cmd, temp) = with_isolated_config();
+    let repo = temp.path().join("repo");
+    std::fs::create_dir_all(repo.join(".claude/commands")).unwrap();
+    std::fs::create_dir_all(repo.join("src")).unwrap();
+    std::fs::write(repo.join(".claude/commands/ship.md"), "Ship it.").unwrap();
+    std::fs::write(repo.join(".mcp.json"), r#"{"mcpServers": {}}"#).unwrap();
+    std::fs::write(repo.join("src/main.rs"), "fn main() {}").unwrap();
+    cmd.args(["project", "snapshot", "agents", "--path"])
+        .arg(&repo)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".claude, .mcp.json"));
+
+    let other = temp.path().join("other");
+    std::fs::create_dir_all(other.join(".claude")).unwrap();
+    std::fs::write(other.join(".claude/stale.md"), "old").unwrap();
+    let project = |args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .arg("project")
+            .args(args)
+            .assert()
+    };
+    project(&["snapshot", "agents", "--path", repo.to_str().unwrap()])
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    project(&["apply", "agents", "--path", other.to_str().unwrap()])
+        .success()
+        .stdout(
+            predicate::str::contains("Replaced: .claude")
+                .and(predicate::str::contains("Added: .mcp.json")),
+        );
+    assert_eq!(
+        std::fs::read_to_string(other.join(".claude/commands/ship.md")).unwrap(),
+        "Ship it."
+    );
+    assert!(!other.join(".claude/stale.md").exists());
+    assert!(other.join(".mcp.json").exists());
+    assert!(!other.join("src").exists());
+    project(&["list"])
+        .success()
+        .stdout(predicate::str::contains("agents  .claude, .mcp.json"));
+}
+
+#[test]
+fn provision_applies_manifest_and_is_idempotent() {
+    let temp = TempDir::new().unwrap();
+    let xdg_config = temp.path().join("xdg");
+    let opencode_config = xdg_config.join("opencode");
+    std::fs::create_dir_all(&opencode_config).unwrap();
+    std::fs::write(opencode_config.join("opencode.jsonc"), "{}").unwrap();
+    let manifest = temp.path().join("bridle.toml");
+    std::fs::write(
+        &manifest,
+        r#"
+[[profiles]]
+harness = "oc"
+name = "base"
+
+[[profiles]]
+harness = "opencode"
+name = "dev"
+active = true
+
+[profiles.mcp.github]
+transport = "stdio"
+command = "npx"
+args = ["-y", "server-github"]
+"#,
+    )
+    .unwrap();
+    let provision = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path().join("bridle"))
+            .env_remove("BRIDLE_STATE_DIR")
+            .env("XDG_CONFIG_HOME", &xdg_config)
+            .args(["provision", "--manifest"])
+            .arg(&manifest)
+            .assert()
+    };
+
+    provision()
+        .success()
+        .stdout(predicate::str::contains("Created opencode/base (empty)"))
+        .stdout(predicate::str::contains("Created opencode/dev (empty)"))
+        .stdout(predicate::str::contains(
+            "opencode/dev: Installed MCP server: github",
+        ))
+        .stdout(predicate::str::contains("Switched opencode to dev"));
+    let live = std::fs::read_to_string(opencode_config.join("opencode.jsonc")).unwrap();
+    assert!(live.contains("server-github"));
+
+    provision()
+        .success()
+        .stdout(predicate::str::diff("Everything is already provisioned\n"));
+}
+
+#[test]
+fn import_config_excludes_caches_from_first_profile() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("cursor");
+    std::fs::create_dir_all(live.join("cache")).unwrap();
+    std::fs::write(live.join("cache/blob"), "regenerated").unwrap();
+    std::fs::write(live.join("settings.json"), "{}").unwrap();
+    std::fs::write(
+        temp.path().join("harnesses.toml"),
+        format!(
+            "[[harness]]\nid = \"cursor\"\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["import-config", "cursor", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("suggest excluding `cache`"))
+        .stdout(predicate::str::contains(
+            "Imported cursor as profile 'default'",
+        ));
+
+    let profile = temp.path().join("profiles/cursor/default");
+    assert!(profile.join("settings.json").exists());
+    assert!(!profile.join("cache").exists());
+    let config = std::fs::read_to_string(temp.path().join("config.toml")).unwrap();
+    assert!(config.contains("exclude = [\"cache\"]"));
+
+    let mut again = bridle();
+    again.env("BRIDLE_CONFIG_DIR", temp.path());
+    again
+        .args(["import-config", "cursor"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already has 1 profile(s)"));
+}
+
+#[test]
+fn config_set_and_get_settings() {
+    let (mut cmd, temp) = with_isolated_config();
+
+    cmd.args(["config", "set", "settings.copy_strategy", "hardlink"])
+        .assert()
+        .success();
+
+    let mut cmd2 = bridle();
+    cmd2.env("BRIDLE_CONFIG_DIR", temp.path());
+    cmd2.args(["config", "get", "copy_strategy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hardlink"));
+
+    let config = std::fs::read_to_string(temp.path().join("config.toml")).unwrap();
+    assert!(config.contains("[settings]"));
+}
+
+#[test]
+fn harness_override_moves_config_dir() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("goose-elsewhere");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "[harness.goose]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["status", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("goose-elsewhere"));
+}
+
+#[test]
+fn active_profile_is_recorded_in_state_dir() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("goose-live");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "[harness.goose]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["profile", "create", "goose", "work", "--from-current"])
+        .assert()
+        .success();
+
+    let state = std::fs::read_to_string(temp.path().join("state/state.toml")).unwrap();
+    assert!(state.contains("goose = \"work\""));
+    let config = std::fs::read_to_string(temp.path().join("config.toml")).unwrap();
+    assert!(!config.contains("[active]"));
+}
+
+#[test]
+fn contexts_have_separate_profiles() {
+    let (mut cmd, temp) = with_isolated_config();
+    std::fs::create_dir_all(temp.path().join("profiles/opencode/personal")).unwrap();
+
+    cmd.args(["context", "create", "work"]).assert().success();
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["context", "use", "work"])
+        .assert()
+        .success();
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["context", "current"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("work"));
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["profile", "list", "opencode"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("personal").not());
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["context", "delete", "default"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn context_use_saves_and_applies_each_contexts_profiles() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let home = temp.path().join("profiles/opencode/home");
+    let client = temp.path().join("contexts/work/profiles/opencode/client");
+    let other = temp.path().join("contexts/work/profiles/opencode/other");
+    for (dir, model) in [(&home, "home"), (&client, "client"), (&other, "other")] {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("opencode.json"),
+            format!(r#"{{"model": "{model}"}}"#),
+        )
+        .unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(args)
+            .assert()
+            .success();
+    };
+    let model = |dir: &std::path::Path| -> String {
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("opencode.json")).unwrap())
+                .unwrap();
+        json["model"].as_str().unwrap().to_string()
+    };
+
+    run(&["context", "create", "work"]);
+    run(&["profile", "switch", "opencode", "home"]);
+    run(&["context", "use", "work"]);
+    run(&["profile", "switch", "opencode", "client"]);
+    std::fs::write(live.join("opencode.json"), r#"{"model": "client-edited"}"#).unwrap();
+
+    run(&["context", "use", "default"]);
+    assert_eq!(model(&live), "home");
+    assert_eq!(model(&home), "home");
+    assert_eq!(model(&client), "client-edited");
+
+    run(&["context", "use", "work"]);
+    assert_eq!(model(&live), "client-edited");
+    run(&["profile", "switch", "opencode", "other"]);
+    assert_eq!(model(&live), "other");
+    assert_eq!(model(&home), "home");
+    assert_eq!(model(&client), "client-edited");
+
+    std::fs::remove_dir_all(&other).unwrap();
+    run(&["context", "use", "default"]);
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["context", "use", "work"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no longer exists"));
+    assert_eq!(model(&live), "home");
+}
+
+#[test]
+fn config_schema_is_json() {
+    let (mut cmd, _temp) = with_isolated_config();
+    let output = cmd.args(["config", "schema"]).assert().success();
+    let schema: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(schema["title"], "BridleConfig");
+}
+
+#[test]
+fn env_and_flag_overrides_take_precedence() {
+    let (mut cmd, temp) = with_isolated_config();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        "[settings]\ncopy_strategy = \"copy\"\n",
+    )
+    .unwrap();
+
+    cmd.env("BRIDLE_COPY_STRATEGY", "hardlink")
+        .args(["config", "get", "copy_strategy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hardlink"));
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .env("BRIDLE_COPY_STRATEGY", "hardlink")
+        .args(["--copy-strategy", "copy", "config", "get", "copy_strategy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("copy"));
+
+    let config = std::fs::read_to_string(temp.path().join("config.toml")).unwrap();
+    assert!(config.contains("copy_strategy = \"copy\""));
+}
+
+#[cfg(unix)]
+#[test]
+fn daemon_answers_over_socket() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let temp = TempDir::new().unwrap();
+    let socket = temp.path().join("bridle.sock");
+    let mut daemon = std::process::Command::new(assert_cmd::cargo::cargo_bin!("bridle"))
+        .args(["daemon", "--socket"])
+        .arg(&socket)
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut stream = None;
+    for _ in 0..100 {
+        if let Ok(s) = UnixStream::connect(&socket) {
+            stream = Some(s);
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    let stream = stream.expect("daemon did not start");
+
+    (&stream)
+        .write_all(b"{\"id\":1,\"method\":\"ping\"}\n{\"id\":2,\"method\":\"profiles.list\",\"params\":{\"harness\":\"opencode\"}}\n")
+        .unwrap();
+    let mut lines = BufReader::new(&stream).lines();
+    let ping: serde_json::Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    let list: serde_json::Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    daemon.kill().unwrap();
+    let _ = daemon.wait();
+
+    assert_eq!(ping["id"], 1);
+    assert!(ping["result"]["version"].is_string());
+    assert_eq!(list["id"], 2);
+    assert!(list.get("result").is_some() || list.get("error").is_some());
+}
+
+#[test]
+fn prompt_prints_active_profile() {
+    let (mut cmd, temp) = with_isolated_config();
+    let state = temp.path().join("state");
+    std::fs::create_dir_all(&state).unwrap();
+    std::fs::write(state.join("state.toml"), "[active]\nopencode = \"work\"\n").unwrap();
+
+    cmd.args(["prompt", "--harness", "opencode"])
+        .assert()
+        .success()
+        .stdout("work\n");
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["prompt", "--harness", "goose"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn activate_applies_project_file() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let profile = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(profile.join("opencode.json"), "{}").unwrap();
+
+    let project = temp.path().join("project/src");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(
+        temp.path().join("project/.bridle.toml"),
+        "[profiles]\nopencode = \"work\"\n",
+    )
+    .unwrap();
+
+    cmd.args(["activate", "--hook"])
+        .arg(&project)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("opencode -> work"));
+    assert!(live.join("opencode.json").exists());
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["prompt", "--harness", "opencode"])
+        .assert()
+        .stdout("work\n");
+
+    bridle()
+        .args(["hook", "zsh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("add-zsh-hook chpwd"));
+}
+
+#[test]
+fn activate_switches_harnesses_together_and_reports_each() {
+    let temp = TempDir::new().unwrap();
+    let live_opencode = temp.path().join("live-opencode");
+    let live_goose = temp.path().join("live-goose");
+    std::fs::create_dir_all(&live_opencode).unwrap();
+    std::fs::create_dir_all(&live_goose).unwrap();
+    std::fs::write(live_goose.join("config.yaml"), "model: old\n").unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n[harness.goose]\nconfig_dir = {:?}\n",
+            live_opencode.display().to_string(),
+            live_goose.display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::write(work.join("opencode.json"), "{}").unwrap();
+    let broken = temp.path().join("profiles/goose/broken");
+    std::fs::create_dir_all(&broken).unwrap();
+    std::fs::write(broken.join("config.yaml"), "model: {{ model }}\n").unwrap();
+    let project = temp.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(
+        project.join(".bridle.toml"),
+        "[profiles]\nopencode = \"work\"\ngoose = \"broken\"\n",
+    )
+    .unwrap();
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    bridle()
+        .args(["activate", "--hook"])
+        .arg(&project)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("bridle: opencode -> work\n"))
+        .stderr(predicate::str::contains(
+            "bridle: goose -> broken failed (previous config kept)",
+        ))
+        .stderr(predicate::str::contains(
+            "1 of 2 harnesses could not switch",
+        ));
+    assert!(live_opencode.join("opencode.json").exists());
+    assert_eq!(
+        std::fs::read_to_string(live_goose.join("config.yaml")).unwrap(),
+        "model: old\n"
+    );
+    bridle()
+        .args(["prompt"])
+        .assert()
+        .success()
+        .stdout("opencode:work\n");
+
+    std::fs::write(
+        project.join(".bridle.toml"),
+        "[profiles]\ngoose = \"gone\"\n",
+    )
+    .unwrap();
+    bridle()
+        .args(["activate", "--hook"])
+        .arg(&project)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn dotfiles_export_import_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let profile = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(
+        profile.join("opencode.json"),
+        "{\n  \"apiKey\": \"sk-live\",\n  \"model\": \"opus\"\n}\n",
+    )
+    .unwrap();
+    let dotfiles = temp.path().join("dotfiles");
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["export", "--harness", "opencode", "--dotfiles"])
+        .arg(&dotfiles)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "BRIDLE_SECRET_OPENCODE_WORK_APIKEY",
+        ));
+    let template =
+        std::fs::read_to_string(dotfiles.join("opencode/work/opencode.json.tmpl")).unwrap();
+    assert!(!template.contains("sk-live"));
+
+    std::fs::remove_dir_all(&profile).unwrap();
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .env_remove("BRIDLE_SECRET_OPENCODE_WORK_APIKEY")
+        .args(["import", "--dotfiles"])
+        .arg(&dotfiles)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "BRIDLE_SECRET_OPENCODE_WORK_APIKEY",
+        ));
+    assert!(!profile.exists());
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .env("BRIDLE_SECRET_OPENCODE_WORK_APIKEY", "sk-live")
+        .args(["import", "--dotfiles"])
+        .arg(&dotfiles)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported opencode/work"));
+    assert_eq!(
+        std::fs::read_to_string(profile.join("opencode.json")).unwrap(),
+        "{\n  \"apiKey\": \"sk-live\",\n  \"model\": \"opus\"\n}\n"
+    );
+}
+
+#[test]
+fn profile_usage_drives_sort_and_prune() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(args)
+            .assert()
+            .success()
+    };
+    for name in ["a", "b", "c"] {
+        run(&["profile", "create", "opencode", name]);
+    }
+    run(&["profile", "switch", "opencode", "c"]);
+    run(&["profile", "switch", "opencode", "b"]);
+
+    let out = run(&[
+        "profile", "list", "opencode", "--sort", "recent", "-o", "json",
+    ]);
+    let entries: serde_json::Value = serde_json::from_slice(&out.get_output().stdout).unwrap();
+    let names: Vec<&str> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["b", "c", "a"]);
+    assert_eq!(entries[0]["switches"], 1);
+
+    run(&["profile", "prune", "opencode", "--days", "30"])
+        .stdout(predicate::str::contains("All opencode profiles were used"));
+    run(&["profile", "prune", "opencode", "--days", "0", "--delete"])
+        .stdout(predicate::str::contains("Deleted profile: a"))
+        .stdout(predicate::str::contains("Deleted profile: c"));
+    assert!(!temp.path().join("profiles/opencode/a").exists());
+    assert!(temp.path().join("profiles/opencode/b").exists());
+}
+
+#[test]
+fn insights_requires_opt_in() {
+    let (mut cmd, _temp) = with_isolated_config();
+    cmd.env_remove("BRIDLE_INSIGHTS")
+        .arg("insights")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("bridle config set insights true"));
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_runs_under_profile_and_restores() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let ci = temp.path().join("profiles/opencode/ci");
+    std::fs::create_dir_all(&ci).unwrap();
+    std::fs::write(ci.join("opencode.json"), "ci").unwrap();
+    std::fs::write(live.join("opencode.json"), "mine").unwrap();
+
+    let bridle_in = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+    bridle_in()
+        .args(["exec", "opencode", "--profile", "ci", "--", "cat"])
+        .arg(live.join("opencode.json"))
+        .assert()
+        .success()
+        .stdout("ci");
+    assert_eq!(
+        std::fs::read_to_string(live.join("opencode.json")).unwrap(),
+        "mine"
+    );
+
+    bridle_in()
+        .args([
+            "exec",
+            "opencode",
+            "--profile",
+            "ci",
+            "--",
+            "sh",
+            "-c",
+            "exit 3",
+        ])
+        .assert()
+        .code(3);
+    bridle_in()
+        .args(["prompt", "--harness", "opencode"])
+        .assert()
+        .stdout("");
+}
+
+#[test]
+fn secret_list_starts_empty_and_set_validates_names() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["secret", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No secrets stored"));
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["secret", "set", "bad/name"])
+        .write_stdin("value")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value for secret name"));
+}
+
+#[cfg(unix)]
+#[test]
+fn switch_resolves_secrets_from_provider_command() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n\n\
+             [secret_providers.vault]\ncommand = \"echo from-vault-{{key}}\"\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&work).unwrap();
+    let template = r#"{"apiKey": "bridle-secret://vault/anthropic"}"#;
+    std::fs::write(work.join("opencode.json"), template).unwrap();
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(live.join("opencode.json")).unwrap(),
+        r#"{"apiKey": "from-vault-anthropic"}"#
+    );
+    assert_eq!(
+        std::fs::read_to_string(work.join("opencode.json")).unwrap(),
+        template
+    );
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["secret", "get", "vault/other"])
+        .assert()
+        .success()
+        .stdout("from-vault-other\n");
+}
+
+#[test]
+fn shared_fragments_merge_on_switch_and_stay_out_of_profiles() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let shared = temp.path().join("shared/mcp-base");
+    std::fs::create_dir_all(&shared).unwrap();
+    std::fs::write(
+        shared.join("opencode.json"),
+        r#"{"mcp": {"github": {"url": "v1"}}}"#,
+    )
+    .unwrap();
+    let profiles = temp.path().join("profiles/opencode");
+    for name in ["work", "other"] {
+        std::fs::create_dir_all(profiles.join(name)).unwrap();
+    }
+    std::fs::write(
+        profiles.join("work/profile.toml"),
+        "include = [\"mcp-base\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        profiles.join("work/opencode.json"),
+        r#"{"model": "m", "mcp": {"local": {"cmd": "x"}}}"#,
+    )
+    .unwrap();
+
+    let switch = |name: &str| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(["profile", "switch", "opencode", name])
+            .assert()
+            .success();
+    };
+    let live_json = || -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(live.join("opencode.json")).unwrap()).unwrap()
+    };
+
+    switch("work");
+    assert_eq!(live_json()["mcp"]["github"]["url"], "v1");
+    assert_eq!(live_json()["mcp"]["local"]["cmd"], "x");
+    assert!(!live.join("profile.toml").exists());
+
+    switch("other");
+    let saved: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(profiles.join("work/opencode.json")).unwrap(),
+    )
+    .unwrap();
+    assert!(saved["mcp"].get("github").is_none());
+    assert!(profiles.join("work/profile.toml").exists());
+
+    std::fs::write(
+        shared.join("opencode.json"),
+        r#"{"mcp": {"github": {"url": "v2"}}}"#,
+    )
+    .unwrap();
+    switch("work");
+    assert_eq!(live_json()["mcp"]["github"]["url"], "v2");
+}
+
+#[test]
+fn config_file_set_get_unset_keeps_comments() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        live.join("opencode.jsonc"),
+        "{\n  // chosen model\n  \"model\": \"old\",\n}\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .arg("config-file")
+            .args(args)
+            .assert()
+    };
+
+    run(&["set", "opencode", "model", "claude-opus-4"]).success();
+    run(&["set", "opencode", "mcp.github.enabled", "false"]).success();
+    run(&["get", "opencode", "model"])
+        .success()
+        .stdout("claude-opus-4\n");
+    run(&["get", "opencode", "mcp.github.enabled"])
+        .success()
+        .stdout("false\n");
+    let content = std::fs::read_to_string(live.join("opencode.jsonc")).unwrap();
+    assert!(content.contains("// chosen model"));
+
+    run(&["unset", "opencode", "mcp"]).success();
+    run(&["get", "opencode", "mcp"])
+        .failure()
+        .stderr(predicate::str::contains("not set"));
+    run(&["set", "opencode", "model", "x", "--profile", "missing"])
+        .failure()
+        .stderr(predicate::str::contains("profile not found"));
+}
+
+#[test]
+fn switch_on_drift_abort_and_discard() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let profiles = temp.path().join("profiles/opencode");
+    for name in ["work", "other"] {
+        std::fs::create_dir_all(profiles.join(name)).unwrap();
+        std::fs::write(profiles.join(name).join("opencode.json"), "{}").unwrap();
+    }
+    let switch = |name: &str, on_drift: &str| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args([
+                "profile",
+                "switch",
+                "opencode",
+                name,
+                "--on-drift",
+                on_drift,
+            ])
+            .assert()
+    };
+
+    switch("work", "save").success();
+    std::fs::write(live.join("opencode.json"), r#"{"model": "edited"}"#).unwrap();
+
+    switch("other", "abort")
+        .failure()
+        .stderr(predicate::str::contains("opencode.json"));
+    assert_eq!(
+        std::fs::read_to_string(live.join("opencode.json")).unwrap(),
+        r#"{"model": "edited"}"#
+    );
+
+    switch("other", "discard").success();
+    assert_eq!(
+        std::fs::read_to_string(profiles.join("work/opencode.json")).unwrap(),
+        "{}"
+    );
+}
+
+#[test]
+fn profile_show_renders_readme_first() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "team"])
+        .assert()
+        .success();
+    std::fs::write(
+        temp.path().join("profiles/opencode/team/README.md"),
+        "# Team profile\n\n- Uses the `review` skill\n",
+    )
+    .unwrap();
+    let show = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .args(["profile", "show", "opencode", "team"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let text = String::from_utf8(show(&[]).stdout).unwrap();
+    assert!(text.starts_with("Team profile\n\n  \u{2022} Uses the review skill\n"));
+    assert!(text.contains("Profile: team"));
+
+    let json: serde_json::Value = serde_json::from_slice(&show(&["-o", "json"]).stdout).unwrap();
+    assert_eq!(
+        json["readme"],
+        "# Team profile\n\n- Uses the `review` skill\n"
+    );
+}
+
+#[test]
+fn status_check_fails_on_drift_and_missing_profile() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::write(work.join("opencode.json"), "{}").unwrap();
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(args)
+            .assert()
+    };
+
+    run(&["profile", "switch", "opencode", "work"]).success();
+    run(&["status", "--check"]).success().stdout("");
+
+    std::fs::write(live.join("opencode.json"), r#"{"model": "edited"}"#).unwrap();
+    run(&["status", "--check"])
+        .code(1)
+        .stdout("opencode/work: 1 file(s) changed since switch\n");
+
+    std::fs::remove_dir_all(&work).unwrap();
+    run(&["status", "--check", "-o", "json"])
+        .code(1)
+        .stdout(predicate::str::contains(r#""kind":"missing_profile""#));
+}
+
+#[test]
+fn merge_switch_strategy_from_harness_config_keeps_runtime_files() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(live.join("cache.db"), "runtime").unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nswitch_strategy = \"merge\"\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let profiles = temp.path().join("profiles/opencode");
+    for name in ["work", "other"] {
+        std::fs::create_dir_all(profiles.join(name)).unwrap();
+        std::fs::write(profiles.join(name).join("opencode.json"), name).unwrap();
+    }
+    let switch = |name: &str, extra: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(["profile", "switch", "opencode", name])
+            .args(extra)
+            .assert()
+            .success();
+    };
+
+    switch("work", &[]);
+    switch("other", &[]);
+    assert_eq!(
+        std::fs::read_to_string(live.join("opencode.json")).unwrap(),
+        "other"
+    );
+    assert!(live.join("cache.db").exists());
+    assert!(!profiles.join("work/cache.db").exists());
+
+    switch("work", &["--strategy", "replace"]);
+    assert!(!live.join("cache.db").exists());
+}
+
+#[test]
+fn update_requires_subscriptions() {
+    let (mut cmd, _temp) = with_isolated_config();
+    cmd.arg("update")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("[[subscriptions]]"));
+}
+
+#[test]
+fn audit_fails_on_high_severity_findings() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "risky"])
+        .assert()
+        .success();
+    std::fs::write(
+        temp.path().join("profiles/opencode/risky/opencode.jsonc"),
+        r#"{"mcp": {"files": {"type": "local", "command": ["npx", "-y", "@modelcontextprotocol/server-filesystem@1.0.0", "/"]}}}"#,
+    )
+    .unwrap();
+
+    let audit = |args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .args(["audit", "opencode", "risky"])
+            .args(args)
+            .assert()
+    };
+    audit(&[])
+        .code(1)
+        .stdout(predicate::str::contains("HIGH").and(predicate::str::contains("files")));
+    std::fs::write(
+        temp.path().join("profiles/opencode/risky/opencode.jsonc"),
+        r#"{"mcp": {"files": {"type": "local", "command": ["npx", "-y", "@modelcontextprotocol/server-filesystem@1.0.0", "./docs"]}}}"#,
+    )
+    .unwrap();
+    audit(&[])
+        .success()
+        .stdout(predicate::str::contains("MEDIUM"));
+    audit(&["--fail-on", "medium"]).code(1);
+}
+
+#[test]
+fn policy_blocks_switching_and_exec_and_is_reported_by_audit() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "risky"])
+        .assert()
+        .success();
+    std::fs::write(
+        temp.path().join("profiles/opencode/risky/opencode.jsonc"),
+        r#"{"mcp": {"shell": {"type": "local", "command": ["mcp-shell@1.0.0"]}}}"#,
+    )
+    .unwrap();
+    let policy = temp.path().join("policy.toml");
+    std::fs::write(
+        &policy,
+        "allowed_mcp_servers = [\"github\"]\nrequired_rules = [\"Never commit secrets.\"]\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env("XDG_CONFIG_HOME", temp.path().join("xdg"))
+            .arg("--policy")
+            .arg(&policy)
+            .args(args)
+            .assert()
+    };
+
+    run(&["profile", "switch", "opencode", "risky"])
+        .failure()
+        .stderr(
+            predicate::str::contains("2 policy violation(s)")
+                .and(predicate::str::contains("MCP server 'shell'"))
+                .and(predicate::str::contains("Never commit secrets.")),
+        );
+    run(&["exec", "opencode", "--profile", "risky", "--", "true"])
+        .failure()
+        .stderr(predicate::str::contains("2 policy violation(s)"));
+    run(&["-o", "json", "profile", "switch", "opencode", "risky"])
+        .failure()
+        .stdout(predicate::str::contains(
+            r#""rule":"allowed_mcp_servers","subject":"shell""#,
+        ));
+    run(&["audit", "opencode", "risky"])
+        .code(1)
+        .stdout(predicate::str::contains("policy").and(predicate::str::contains("shell")));
+}
+
+#[test]
+fn rules_snippets_are_assembled_on_switch_and_explained() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live/opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(work.join("rules.d")).unwrap();
+    std::fs::create_dir_all(temp.path().join("profiles/opencode/other")).unwrap();
+    std::fs::write(work.join("rules.d/10-style.md"), "# Style\n\nBe brief.\n").unwrap();
+    std::fs::write(work.join("rules.d/20-testing.md"), "# Testing\n").unwrap();
+    let run = |args: &[&str]| {
+        bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(args)
+            .assert()
+    };
+
+    run(&["profile", "switch", "opencode", "work"]).success();
+    assert_eq!(
+        std::fs::read_to_string(live.join("AGENTS.md")).unwrap(),
+        "# Style\n\nBe brief.\n\n# Testing\n"
+    );
+    assert!(!live.join("rules.d").exists());
+
+    run(&["rules", "explain", "opencode"])
+        .success()
+        .stdout(predicate::str::contains(
+            "AGENTS.md for opencode/work, from 2 snippet(s)",
+        ));
+    run(&["-o", "json", "rules", "explain", "opencode", "work"])
+        .success()
+        .stdout(predicate::str::contains(
+            r##"{"line":5,"heading":"# Testing","snippet":"20-testing.md"}"##,
+        ));
+
+    run(&["profile", "switch", "opencode", "other"]).success();
+    assert!(!work.join("AGENTS.md").exists());
+    assert!(work.join("rules.d/20-testing.md").exists());
+}
+
+#[test]
+fn mcp_render_check_reports_unresolved_references_before_switching() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live/opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::write(
+        work.join("opencode.jsonc"),
+        r#"{"mcp": {"github": {"type": "local", "command": ["gh-mcp"], "environment": {"GITHUB_TOKEN": "${GITHUB_TOKEN}"}}}}"#,
+    )
+    .unwrap();
+    let run = |token: Option<&str>, args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .env_remove("BRIDLE_VAR_GITHUB_TOKEN");
+        if let Some(token) = token {
+            cmd.env("BRIDLE_VAR_GITHUB_TOKEN", token);
+        }
+        cmd.args(args).assert()
+    };
+
+    run(None, &["mcp", "check", "opencode", "work"])
+        .code(1)
+        .stdout(
+            predicate::str::contains("1 reference(s) do not resolve")
+                .and(predicate::str::contains("${GITHUB_TOKEN}")),
+        );
+    run(
+        Some("ghp-1"),
+        &["-o", "json", "mcp", "check", "opencode", "work"],
+    )
+    .success()
+    .stdout(predicate::str::contains(r#""source":"environment""#));
+
+    run(Some("ghp-1"), &["profile", "switch", "opencode", "work"]).success();
+    let rendered = std::fs::read_to_string(live.join("opencode.jsonc")).unwrap();
+    assert!(
+        rendered.contains(r#""GITHUB_TOKEN": "ghp-1""#),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn mcp_list_spans_profiles_and_finds_inconsistent_servers() {
+    let (mut cmd, temp) = with_isolated_config();
+    let profiles = temp.path().join("profiles/opencode");
+    for (name, version) in [("work", "1.0.0"), ("home", "2.0.0")] {
+        std::fs::create_dir_all(profiles.join(name)).unwrap();
+        std::fs::write(
+            profiles.join(name).join("opencode.jsonc"),
+            format!(
+                r#"{{"mcp": {{"github": {{"type": "local", "command": ["gh-mcp@{version}"]}}, "fs": {{"type": "local", "command": ["mcp-fs"]}}}}}}"#
+            ),
+        )
+        .unwrap();
+    }
+
+    cmd.args(["mcp", "list"]).assert().success().stdout(
+        predicate::str::contains("opencode/home  github  local  gh-mcp@2.0.0")
+            .and(predicate::str::contains("opencode/work  fs  local  mcp-fs")),
+    );
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["-o", "json", "mcp", "list", "--duplicates"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(r#""name":"github""#)
+                .and(predicate::str::contains(r#""name":"fs""#).not()),
+        );
+}
+
+#[test]
+fn mcp_convert_wraps_remote_servers_in_a_stdio_gateway() {
+    let (mut cmd, temp) = with_isolated_config();
+    let profile = temp.path().join("profiles/claude-code/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(
+        profile.join(".mcp.json"),
+        r#"{"mcpServers": {
+            "docs": {"type": "sse", "url": "https://mcp.example.com/sse"},
+            "fs": {"command": "mcp-fs", "args": ["/srv"]}
+        }}"#,
+    )
+    .unwrap();
+
+    cmd.args([
+        "mcp",
+        "convert",
+        "claude-code",
+        "docs",
+        "--to",
+        "stdio",
+        "--profile",
+        "work",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+        "Converted docs in claude-code/work from sse to stdio",
+    ));
+    let config = std::fs::read_to_string(profile.join(".mcp.json")).unwrap();
+    assert!(config.contains("supergateway"), "{config}");
+    assert!(config.contains("https://mcp.example.com/sse"), "{config}");
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["mcp", "convert", "claude-code", "fs", "--to", "http"])
+        .args(["--profile", "work"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--stdio \"mcp-fs /srv\""));
+}
+
+#[test]
+fn logs_shows_harness_and_mcp_server_logs() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("live-claude");
+    let cache = temp.path().join("cache");
+    std::fs::create_dir_all(live.join("debug")).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.claude-code]\nconfig_dir = {:?}\n",
+            live.display().to_string()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        live.join("debug/session.txt"),
+        "session started\nfs: spawn mcp-fs ENOENT\nsession ended\n",
+    )
+    .unwrap();
+    let server_logs = cache.join("claude-cli-nodejs/-repo/mcp-logs-github");
+    std::fs::create_dir_all(&server_logs).unwrap();
+    std::fs::write(server_logs.join("1.txt"), "connecting\nconnected\n").unwrap();
+
+    cmd.env("XDG_CACHE_HOME", &cache)
+        .args(["logs", "claude-code", "--mcp", "github", "-n", "1"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("connected").and(predicate::str::contains("connecting").not()),
+        );
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env("XDG_CACHE_HOME", &cache)
+        .args(["logs", "claude-code", "--mcp", "fs"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("spawn mcp-fs ENOENT")
+                .and(predicate::str::contains("session started").not()),
+        );
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env("XDG_CACHE_HOME", &cache)
+        .args(["logs", "claude-code", "--mcp", "slack"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no logs of MCP server 'slack'"));
+}
+
+#[test]
+fn status_and_show_score_profile_health() {
+    let (mut cmd, temp) = with_isolated_config();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let profile = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(
+        profile.join("opencode.jsonc"),
+        r#"{"mcp": {"fs": {"type": "local", "command": ["bridle-test-missing-mcp", "/bridle-test-missing-dir"]}}}"#,
+    )
+    .unwrap();
+
+    cmd.args(["profile", "show", "opencode", "work", "--explain"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Health: 75/100 (degraded)")
+                .and(predicate::str::contains(
+                    "mcp: fs: command bridle-test-missing-mcp not found",
+                ))
+                .and(predicate::str::contains(
+                    "missing file: /bridle-test-missing-dir is referenced by MCP server fs",
+                )),
+        );
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["status", "--explain"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("opencode: work (health 75/100, degraded")
+                .and(predicate::str::contains("    - mcp: fs: command")),
+        );
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["-o", "json", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""health":{"score":75"#));
+}
+
+#[test]
+fn sync_saves_live_changes_and_pushes_to_remote() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    let remote = temp.path().join("remote");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[settings]\nsync_remote = {:?}\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            remote.display().to_string(),
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let profile = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(profile.join("AGENTS.md"), "Be brief.\n").unwrap();
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    bridle()
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    bridle()
+        .args(["sync"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing changed"));
+
+    std::fs::write(live.join("AGENTS.md"), "Be thorough.\n").unwrap();
+    bridle().args(["sync", "--push"]).assert().success().stdout(
+        predicate::str::contains("Saved opencode/work")
+            .and(predicate::str::contains("Pushed opencode/work")),
+    );
+    assert_eq!(
+        std::fs::read_to_string(profile.join("AGENTS.md")).unwrap(),
+        "Be thorough.\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(remote.join("opencode/work/AGENTS.md")).unwrap(),
+        "Be thorough.\n"
+    );
+
+    bridle().args(["status"]).assert().success().stdout(
+        predicate::str::contains("Last sync: ").and(predicate::str::contains("1 saved, 1 pushed")),
+    );
+    bridle()
+        .args(["sync", "--cron", "--push"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^\d+ \* \* \* \* .*bridle\S* sync --push").unwrap());
+}
+
+#[test]
+fn sync_pull_merges_remote_changes_and_reports_conflicts() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    let remote = temp.path().join("remote");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[settings]\nsync_remote = {:?}\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            remote.display().to_string(),
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let profile = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    for file in ["AGENTS.md", "notes.md", "shared.md"] {
+        std::fs::write(profile.join(file), "base\n").unwrap();
+    }
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+    bridle()
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    bridle().args(["sync", "--push"]).assert().success();
+
+    let pushed = remote.join("opencode/work");
+    std::fs::write(pushed.join("notes.md"), "remote\n").unwrap();
+    std::fs::write(pushed.join("shared.md"), "remote\n").unwrap();
+    std::fs::write(pushed.join("extra.md"), "remote\n").unwrap();
+    std::fs::write(profile.join("AGENTS.md"), "local\n").unwrap();
+    std::fs::write(profile.join("shared.md"), "local\n").unwrap();
+
+    bridle().args(["sync", "pull"]).assert().code(1).stdout(
+        predicate::str::contains("Pulled opencode/work: 2 updated, 0 removed, 1 conflict(s)")
+            .and(predicate::str::contains(
+                "shared.md: unresolved, kept local",
+            ))
+            .and(predicate::str::contains(
+                "bridle profile switch opencode work",
+            )),
+    );
+    let read = |file: &str| std::fs::read_to_string(profile.join(file)).unwrap();
+    assert_eq!(read("AGENTS.md"), "local\n");
+    assert_eq!(read("notes.md"), "remote\n");
+    assert_eq!(read("extra.md"), "remote\n");
+    assert_eq!(read("shared.md"), "local\n");
+
+    bridle()
+        .args(["sync", "pull", "opencode", "--resolve", "markers"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "shared.md: wrote conflict markers",
+        ));
+    assert_eq!(
+        read("shared.md"),
+        "<<<<<<< local\nlocal\n=======\nremote\n>>>>>>> remote\n"
+    );
+    bridle()
+        .args(["sync", "pull"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("opencode/work is up to date"));
+}
+
+#[test]
+fn machine_variants_apply_on_their_machine_and_keep_its_edits() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let profiles = temp.path().join("profiles/opencode");
+    let work = profiles.join("work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::create_dir_all(profiles.join("home")).unwrap();
+    std::fs::write(work.join("AGENTS.md"), "shared\n").unwrap();
+    std::fs::write(work.join("AGENTS.md.machine-laptop"), "laptop\n").unwrap();
+    std::fs::write(work.join("AGENTS.md.machine-desktop"), "desktop\n").unwrap();
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env("BRIDLE_MACHINE", "Laptop")
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    bridle()
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(live.join("AGENTS.md")).unwrap(),
+        "laptop\n"
+    );
+    assert!(!live.join("AGENTS.md.machine-desktop").exists());
+    bridle()
+        .args(["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Machine: laptop"));
+
+    std::fs::write(live.join("AGENTS.md"), "laptop, edited\n").unwrap();
+    bridle()
+        .args(["profile", "switch", "opencode", "home"])
+        .assert()
+        .success();
+    let read = |file: &str| std::fs::read_to_string(work.join(file)).unwrap();
+    assert_eq!(read("AGENTS.md"), "shared\n");
+    assert_eq!(read("AGENTS.md.machine-laptop"), "laptop, edited\n");
+    assert_eq!(read("AGENTS.md.machine-desktop"), "desktop\n");
+}
+
+#[test]
+fn migrate_moves_config_profiles_and_state_to_a_new_machine() {
+    let old = TempDir::new().unwrap();
+    let live = old.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        old.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let profile = old.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(profile.join("AGENTS.md"), "Be brief.\n").unwrap();
+    std::fs::create_dir_all(old.path().join("backups/opencode")).unwrap();
+    std::fs::write(old.path().join("backups/opencode/old.md"), "").unwrap();
+    let archive = old.path().join("bridle.zip");
+    let on = |dir: &std::path::Path| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", dir)
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    on(old.path())
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    on(old.path())
+        .args(["migrate", "export"])
+        .arg(&archive)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported"));
+
+    let new = TempDir::new().unwrap();
+    on(new.path())
+        .args(["migrate", "import"])
+        .arg(&archive)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "bridle profile switch opencode work",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(new.path().join("profiles/opencode/work/AGENTS.md")).unwrap(),
+        "Be brief.\n"
+    );
+    assert!(new.path().join("config.toml").exists());
+    assert!(!new.path().join("backups").exists());
+    assert!(!new.path().join("bridle.zip").exists());
+    let state = std::fs::read_to_string(new.path().join("state/state.toml")).unwrap();
+    assert!(!state.contains("opencode = \"work\""), "{state}");
+    assert!(state.contains("[usage.opencode.work]"));
+
+    on(new.path())
+        .args(["migrate", "import"])
+        .arg(&archive)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+}
+
+#[test]
+fn profile_log_lists_snapshots_and_diffs_them() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let profiles = temp.path().join("profiles/opencode");
+    let work = profiles.join("work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::create_dir_all(profiles.join("home")).unwrap();
+    std::fs::write(work.join("opencode.jsonc"), "{\"permission\": \"ask\"}\n").unwrap();
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    for name in ["work", "home"] {
+        bridle()
+            .args(["profile", "switch", "opencode", name])
+            .assert()
+            .success();
+    }
+    let backups = temp.path().join("backups/opencode");
+    let taken = std::fs::read_dir(&backups)
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .find(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap();
+    let older = backups.join("20200101_000000");
+    std::fs::create_dir_all(&older).unwrap();
+    std::fs::write(older.join(".bridle-snapshot"), "work\n").unwrap();
+    std::fs::write(older.join("opencode.jsonc"), "{\"permission\": \"deny\"}\n").unwrap();
+    std::fs::write(work.join("opencode.jsonc"), "{\"permission\": \"allow\"}\n").unwrap();
+
+    bridle()
+        .args(["profile", "log", "opencode", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("snapshot {taken}")))
+        .stdout(predicate::str::contains(
+            "snapshot 20200101_000000  (2020-01-01 00:00:00)",
+        ))
+        .stdout(predicate::str::contains("+++").not());
+    bridle()
+        .args(["profile", "log", "opencode", "work", "-p"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "-{\"permission\": \"deny\"}\n+{\"permission\": \"ask\"}",
+        ))
+        .stdout(predicate::str::contains(
+            "-{\"permission\": \"ask\"}\n+{\"permission\": \"allow\"}",
+        ))
+        .stdout(predicate::str::contains(".bridle-snapshot").not());
+    bridle()
+        .args([
+            "profile",
+            "log",
+            "opencode",
+            "work",
+            "--from",
+            "20200101_000000",
+        ])
+        .args(["--to", &taken])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+{\"permission\": \"ask\"}"))
+        .stdout(predicate::str::contains("allow").not());
+    bridle()
+        .args([
+            "profile",
+            "log",
+            "opencode",
+            "home",
+            "--from",
+            "20200101_000000",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no snapshot 20200101_000000"));
+}
+
+#[test]
+fn profile_restore_file_takes_one_file_from_a_snapshot() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(&work).unwrap();
+    std::fs::write(work.join("opencode.jsonc"), "{\"permission\": \"allow\"}\n").unwrap();
+    std::fs::write(work.join("AGENTS.md"), "new rules\n").unwrap();
+    let snapshot = temp.path().join("backups/opencode/20200101_000000");
+    std::fs::create_dir_all(&snapshot).unwrap();
+    std::fs::write(snapshot.join(".bridle-snapshot"), "work\n").unwrap();
+    std::fs::write(
+        snapshot.join("opencode.jsonc"),
+        "{\"permission\": \"ask\"}\n",
+    )
+    .unwrap();
+    std::fs::write(snapshot.join("AGENTS.md"), "old rules\n").unwrap();
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    bridle()
+        .args(["profile", "switch", "opencode", "work"])
+        .assert()
+        .success();
+    bridle()
+        .args(["profile", "restore-file", "opencode", "work"])
+        .args(["--from-snapshot", "20200101_000000", "opencode.jsonc"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Restored opencode.jsonc from snapshot 20200101_000000",
+        ));
+    for dir in [&work, &live] {
+        assert_eq!(
+            std::fs::read_to_string(dir.join("opencode.jsonc")).unwrap(),
+            "{\"permission\": \"ask\"}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("AGENTS.md")).unwrap(),
+            "new rules\n"
+        );
+    }
+    bridle()
+        .args(["profile", "restore-file", "opencode", "work"])
+        .args(["--from-snapshot", "20200102_000000", "AGENTS.md"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no snapshot 20200102_000000"));
+}
+
+#[test]
+fn completion_offers_profiles_components_mcp_servers_and_snapshots() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live-opencode");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\n",
+            live.display().to_string(),
+            live.join("opencode.jsonc").display().to_string()
+        ),
+    )
+    .unwrap();
+    let work = temp.path().join("profiles/opencode/work");
+    std::fs::create_dir_all(work.join("skills/review")).unwrap();
+    std::fs::write(
+        work.join("opencode.jsonc"),
+        r#"{"mcp": {"github": {"type": "local", "command": ["npx", "server-github"]}}}"#,
+    )
+    .unwrap();
+    let snapshot = temp.path().join("backups/opencode/20200101_000000");
+    std::fs::create_dir_all(&snapshot).unwrap();
+    std::fs::write(snapshot.join(".bridle-snapshot"), "work\n").unwrap();
+    let complete = |words: &[&str]| {
+        let output = bridle()
+            .env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .arg("__complete")
+            .arg("--")
+            .args(words)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(complete(&["prof"]), "profile\n");
+    assert!(complete(&["profile", "switch", ""]).contains("opencode\n"));
+    assert_eq!(complete(&["profile", "switch", "opencode", ""]), "work\n");
+    assert_eq!(
+        complete(&["uninstall", "opencode", "work", "r"]),
+        "review\n"
+    );
+    assert_eq!(
+        complete(&["mcp", "convert", "opencode", "--profile", "work", ""]),
+        "github\n"
+    );
+    assert_eq!(
+        complete(&[
+            "profile",
+            "restore-file",
+            "opencode",
+            "work",
+            "--from-snapshot",
+            ""
+        ]),
+        "20200101_000000\n"
+    );
+    assert_eq!(complete(&["profile", "switch", "nope", ""]), "");
+
+    bridle()
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bridle __complete --"));
+}
+
+#[test]
+fn profile_switch_check_reports_a_config_the_harness_rejects() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\ncheck = [\"sh\", \"-c\", \"grep -q model opencode.json || {{ echo 'opencode.json: no model' >&2; exit 2; }}\"]\n",
+            live.display().to_string(),
+            live.join("opencode.json").display().to_string()
+        ),
+    )
+    .unwrap();
+    for (name, content) in [("good", "{\"model\": \"a\"}"), ("bad", "{}")] {
+        let dir = temp.path().join("profiles/opencode").join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("opencode.json"), content).unwrap();
+    }
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR");
+        cmd
+    };
+
+    bridle()
+        .args(["profile", "switch", "opencode", "good", "--check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Check passed: sh -c"));
+    bridle()
+        .args(["profile", "switch", "opencode", "bad", "--check"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("exited with status 2"))
+        .stderr(predicate::str::contains("opencode.json: no model"));
+    assert_eq!(
+        std::fs::read_to_string(live.join("opencode.json")).unwrap(),
+        "{}"
+    );
+
+    bridle()
+        .args(["profile", "switch", "opencode", "good"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Check passed").not());
+    bridle()
+        .args(["profile", "switch", "opencode", "bad"])
+        .env("BRIDLE_CHECK_AFTER_SWITCH", "true")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn profile_switch_keeps_protected_sections_from_the_live_config() {
+    let temp = TempDir::new().unwrap();
+    let live = temp.path().join("live");
+    std::fs::create_dir_all(&live).unwrap();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        format!(
+            "version = 2\n[harness.opencode]\nconfig_dir = {:?}\nmcp_config = {:?}\nprotect = [\"opencode.json:mcp.*.headers\"]\n",
+            live.display().to_string(),
+            live.join("opencode.json").display().to_string()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        live.join("opencode.json"),
+        r#"{"mcp": {"github": {"url": "https://old", "headers": {"Authorization": "Bearer fresh"}}}}"#,
+    )
+    .unwrap();
+    let home = temp.path().join("profiles/opencode/home");
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(
+        home.join("opencode.json"),
+        r#"{"mcp": {"github": {"url": "https://new", "headers": {"Authorization": "Bearer stale"}}}}"#,
+    )
+    .unwrap();
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["profile", "switch", "opencode", "home"])
+        .assert()
+        .success();
+
+    let applied = std::fs::read_to_string(live.join("opencode.json")).unwrap();
+    assert!(applied.contains("https://new"), "{applied}");
+    assert!(applied.contains("Bearer fresh"), "{applied}");
+    assert!(!applied.contains("Bearer stale"), "{applied}");
+}
+
+#[test]
+fn profile_show_reports_where_a_config_fails_to_parse() {
+    let (mut cmd, temp) = with_isolated_config();
+    let profile = temp.path().join("profiles/opencode/broken");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(
+        profile.join("opencode.jsonc"),
+        "{\n  /* servers\n     used at work */ \"mcp\": {\n    \"a\": {\"type\": \"local\"}\n    \"b\": {}\n  },\n}\n",
+    )
+    .unwrap();
+
+    cmd.args(["profile", "show", "opencode", "broken", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("opencode.jsonc"))
+        .stdout(predicate::str::contains("line 5 column 5"));
+}
+
+#[test]
+fn profile_show_caches_extraction_until_the_profile_changes() {
+    let (_, temp) = with_isolated_config();
+    let profile = temp.path().join("profiles/opencode/cached");
+    std::fs::create_dir_all(profile.join("skill/review")).unwrap();
+    std::fs::write(profile.join("skill/review/SKILL.md"), "# Review\n").unwrap();
+    let show = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .env_remove("BRIDLE_STATE_DIR")
+            .args(["profile", "show", "opencode", "cached", "-o", "json"]);
+        cmd
+    };
+
+    show()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"review\""));
+    assert!(
+        temp.path()
+            .join("state/cache/extraction/opencode/cached.json")
+            .exists()
+    );
+
+    std::fs::create_dir_all(profile.join("skill/lint")).unwrap();
+    std::fs::write(profile.join("skill/lint/SKILL.md"), "# Lint\n").unwrap();
+    show()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"lint\""));
+}
+
+#[test]
+fn profile_show_lists_custom_resource_categories() {
+    let (_, temp) = with_isolated_config();
+    std::fs::write(
+        temp.path().join("config.toml"),
+        "[harness.opencode.resources.prompts]\ndir = \"prompts\"\npattern = \"*.md\"\n",
+    )
+    .unwrap();
+    let profile = temp.path().join("profiles/opencode/custom");
+    std::fs::create_dir_all(profile.join("prompts")).unwrap();
+    std::fs::write(profile.join("prompts/triage.md"), "Triage issues\n").unwrap();
+
+    let mut cmd = bridle();
+    cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+        .env_remove("BRIDLE_STATE_DIR")
+        .args(["profile", "show", "opencode", "custom"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Prompts (1):\n  triage"));
+}
+
+#[test]
+fn profile_show_names_nested_claude_commands_by_namespace() {
+    let (mut cmd, temp) = with_isolated_config();
+    let profile = temp.path().join("profiles/claude-code/nested");
+    std::fs::create_dir_all(profile.join("commands/git")).unwrap();
+    std::fs::write(profile.join("commands/review.md"), "Review\n").unwrap();
+    std::fs::write(profile.join("commands/git/commit.md"), "Commit\n").unwrap();
+
+    cmd.args(["profile", "show", "claude-code", "nested", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"git:commit\""))
+        .stdout(predicate::str::contains("\"review\""));
+}
+
+#[test]
+fn profile_show_describes_resources_from_front_matter() {
+    let (mut cmd, temp) = with_isolated_config();
+    let profile = temp.path().join("profiles/claude-code/described");
+    std::fs::create_dir_all(profile.join("commands")).unwrap();
+    std::fs::write(
+        profile.join("commands/ship.md"),
+        "---\ndescription: Tags and publishes a release\nmodel: haiku\n---\nShip it.\n",
+    )
+    .unwrap();
+    std::fs::write(profile.join("commands/lint.md"), "Lint it.\n").unwrap();
+
+    cmd.args(["profile", "show", "claude-code", "described"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Commands (2):\n  lint\n  ship: Tags and publishes a release",
+        ));
+}
+
+#[test]
+fn profile_package_parses_dir_alongside_output_format() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "package", "claude-code", "missing", "--dir"])
+        .arg(temp.path().join("out"))
+        .args(["-o", "json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("profile not found"));
+}
+
+/// Leaves a failed install of skill `review` from `org/skills` into
+/// `opencode/work` for `bridle install --resume`, returning the state file.
+fn pending_review_install(config: &std::path::Path) -> std::path::PathBuf {
+    let pending = config.join("state/pending-installs.json");
+    std::fs::create_dir_all(pending.parent().unwrap()).unwrap();
+    std::fs::write(
+        &pending,
+        r#"[{
+            "source": "org/skills",
+            "target": "opencode/work",
+            "components": {
+                "skills": [{
+                    "name": "review",
+                    "description": "Reviews code",
+                    "path": "skills/review/SKILL.md",
+                    "content": "---\nname: review\ndescription: Reviews code\n---\nReview it.\n",
+                    "setup_script": null
+                }],
+                "mcp_servers": {},
+                "agents": [],
+                "commands": [],
+                "source": {"owner": "org", "repo": "skills", "git_ref": "main"}
+            }
+        }]"#,
+    )
+    .unwrap();
+    pending
+}
+
+#[test]
+fn install_resume_retries_pending_components() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["install", "--resume"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No incomplete install to resume"));
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["profile", "create", "opencode", "work"])
+        .assert()
+        .success();
+    let pending = pending_review_install(temp.path());
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["install", "--resume"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Installed skill: review"));
+    assert!(!pending.exists());
+}
+
+#[test]
+fn undo_last_install_puts_back_what_it_overwrote() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "work"])
+        .assert()
+        .success();
+    let profile = temp.path().join("profiles/opencode/work");
+    let skill = profile.join("skills/review/SKILL.md");
+    std::fs::create_dir_all(skill.parent().unwrap()).unwrap();
+    std::fs::write(&skill, "my own review skill\n").unwrap();
+    pending_review_install(temp.path());
+    let bridle = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path());
+        cmd
+    };
+
+    bridle()
+        .args(["install", "--resume"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Undo with `bridle undo --last-install`",
+        ));
+    assert!(
+        std::fs::read_to_string(&skill)
+            .unwrap()
+            .contains("Review it.")
+    );
+    assert!(profile.join("bridle.lock").exists());
+
+    bridle()
+        .args(["undo", "--last-install"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("of org/skills into opencode/work"))
+        .stdout(predicate::str::contains("restored"));
+    assert_eq!(
+        std::fs::read_to_string(&skill).unwrap(),
+        "my own review skill\n"
+    );
+    assert!(!profile.join("bridle.lock").exists());
+    bridle()
+        .args(["undo", "--last-install"])
+        .assert()
+        .success()
+        .stdout("No install to undo\n");
+}
+
+#[test]
+fn install_progress_streams_json_lines() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "work"])
+        .assert()
+        .success();
+    pending_review_install(temp.path());
+
+    let output = bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["install", "--resume", "--progress", "json-lines"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events[0]["event"], "file_written");
+    assert_eq!(events[0]["component"], "skill");
+    assert_eq!(events[0]["name"], "review");
+    let done = events.last().unwrap();
+    assert_eq!(done["event"], "target_done");
+    assert_eq!(done["installed"], 1);
+    assert_eq!(done["failed"], 0);
+}
+
+#[test]
+fn install_rejects_unreadable_ca_bundle_before_downloading() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "work"])
+        .assert()
+        .success();
+
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .env("BRIDLE_CA_BUNDLE", temp.path().join("missing.pem"))
+        .args(["install", "org/skills", "--targets", "opencode/work"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot use CA bundle"));
+}
+
+#[test]
+fn verify_locked_reports_files_changed_since_install() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["profile", "create", "opencode", "work"])
+        .assert()
+        .success();
+    pending_review_install(temp.path());
+    bridle()
+        .env("BRIDLE_CONFIG_DIR", temp.path())
+        .args(["install", "--resume"])
+        .assert()
+        .success();
+
+    let verify = || {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .args(["verify", "opencode", "work"])
+            .assert()
+    };
+    verify()
+        .success()
+        .stdout(predicate::str::contains("1 file(s) match bridle.lock"));
+
+    let skills = temp.path().join("profiles/opencode/work/skills");
+    std::fs::write(skills.join("review/SKILL.md"), "edited").unwrap();
+    std::fs::create_dir_all(skills.join("extra")).unwrap();
+    std::fs::write(skills.join("extra/SKILL.md"), "extra").unwrap();
+    verify().code(1).stdout(
+        predicate::str::contains("modified    skills/review/SKILL.md").and(
+            predicate::str::contains("extraneous  skills/extra/SKILL.md"),
+        ),
+    );
+}
+
+#[test]
+fn non_interactive_install_requires_targets() {
+    let (mut cmd, _temp) = with_isolated_config();
+    cmd.args([
+        "install",
+        "org/skills",
+        "--filter",
+        "pdf*",
+        "--exclude",
+        "experimental/*",
+    ])
+    .write_stdin("")
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("needs --targets"));
+}
+
+#[test]
+fn cache_info_and_clean_manage_downloads() {
+    let (mut cmd, temp) = with_isolated_config();
+    cmd.args(["cache", "info"]).assert().success().stdout(
+        predicate::str::contains("Entries:   0").and(predicate::str::contains("no lookups")),
+    );
+
+    let downloads = temp.path().join("state/cache/downloads");
+    std::fs::create_dir_all(&downloads).unwrap();
+    std::fs::write(downloads.join("entry"), vec![0; 2048]).unwrap();
+    let cache = |args: &[&str]| {
+        let mut cmd = bridle();
+        cmd.env("BRIDLE_CONFIG_DIR", temp.path())
+            .arg("cache")
+            .args(args)
+            .assert()
+    };
+    cache(&["info"]).stdout(predicate::str::contains("Size:      2KB"));
+    cache(&["clean", "--older-than", "30d", "--max-size", "500MB"])
+        .success()
+        .stdout(predicate::str::contains("Removed 0 entries"));
+    cache(&["clean", "--older-than", "soon"])
+        .failure()
+        .stderr(predicate::str::contains("--older-than"));
+    cache(&["clean"])
+        .success()
+        .stdout(predicate::str::contains("Removed 1 entry (2KB)"));
+}